@@ -18,7 +18,6 @@ pub struct McpRoot {
     pub codex: McpConfig,
 }
 
-use crate::config::{copy_file, get_app_config_dir, get_app_config_path, write_json_file};
 use crate::provider::ProviderManager;
 
 /// 应用类型
@@ -50,7 +49,8 @@ impl From<&str> for AppType {
 /// 多应用配置结构（向后兼容）
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct MultiAppConfig {
-    #[serde(default = "default_version")]
+    /// 配置结构版本（见 `store::CONFIG_VERSION`，加载时按版本逐级迁移）
+    #[serde(rename = "config_version", default = "default_version")]
     pub version: u32,
     /// 应用管理器（claude/codex）
     #[serde(flatten)]
@@ -58,10 +58,13 @@ pub struct MultiAppConfig {
     /// MCP 配置（按客户端分治）
     #[serde(default)]
     pub mcp: McpRoot,
+    /// 项目级切换记住的项目目录（写入各项目的 `.claude/settings.local.json`）
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub projects: Vec<crate::project::ProjectBinding>,
 }
 
 fn default_version() -> u32 {
-    2
+    crate::store::CONFIG_VERSION
 }
 
 impl Default for MultiAppConfig {
@@ -71,80 +74,20 @@ impl Default for MultiAppConfig {
         apps.insert("codex".to_string(), ProviderManager::default());
 
         Self {
-            version: 2,
+            version: crate::store::CONFIG_VERSION,
             apps,
             mcp: McpRoot::default(),
+            projects: Vec::new(),
         }
     }
 }
 
 impl MultiAppConfig {
-    /// 从文件加载配置（处理v1到v2的迁移）
-    pub fn load() -> Result<Self, String> {
-        let config_path = get_app_config_path();
-
-        if !config_path.exists() {
-            log::info!("配置文件不存在，创建新的多应用配置");
-            return Ok(Self::default());
-        }
-
-        // 尝试读取文件
-        let content = std::fs::read_to_string(&config_path)
-            .map_err(|e| format!("读取配置文件失败: {}", e))?;
-
-        // 检查是否是旧版本格式（v1）
-        if let Ok(v1_config) = serde_json::from_str::<ProviderManager>(&content) {
-            log::info!("检测到v1配置，自动迁移到v2");
-
-            // 迁移到新格式
-            let mut apps = HashMap::new();
-            apps.insert("claude".to_string(), v1_config);
-            apps.insert("codex".to_string(), ProviderManager::default());
-
-            let config = Self {
-                version: 2,
-                apps,
-                mcp: McpRoot::default(),
-            };
-
-            // 迁移前备份旧版(v1)配置文件
-            let backup_dir = get_app_config_dir();
-            let ts = std::time::SystemTime::now()
-                .duration_since(std::time::UNIX_EPOCH)
-                .unwrap_or_default()
-                .as_secs();
-            let backup_path = backup_dir.join(format!("config.v1.backup.{}.json", ts));
-
-            match copy_file(&config_path, &backup_path) {
-                Ok(()) => log::info!(
-                    "已备份旧版配置文件: {} -> {}",
-                    config_path.display(),
-                    backup_path.display()
-                ),
-                Err(e) => log::warn!("备份旧版配置文件失败: {}", e),
-            }
-
-            // 保存迁移后的配置
-            config.save()?;
-            return Ok(config);
-        }
-
-        // 尝试读取v2格式
-        serde_json::from_str::<Self>(&content).map_err(|e| format!("解析配置文件失败: {}", e))
-    }
-
-    /// 保存配置到文件
+    /// 保存配置到数据库（单个事务内只写入变化的行）
     pub fn save(&self) -> Result<(), String> {
-        let config_path = get_app_config_path();
-        // 先备份旧版（若存在）到 ~/.cc-switch/config.json.bak，再写入新内容
-        if config_path.exists() {
-            let backup_path = get_app_config_dir().join("config.json.bak");
-            if let Err(e) = copy_file(&config_path, &backup_path) {
-                log::warn!("备份 config.json 到 .bak 失败: {}", e);
-            }
-        }
-
-        write_json_file(&config_path, self)?;
+        crate::database::save_config(self)?;
+        // 每次落盘即视为配置变更：刷新代理使用的供应商快照
+        crate::proxy::refresh_provider_snapshot(self);
         Ok(())
     }
 
@@ -0,0 +1,213 @@
+//! 应用锁：可选的访问密码。启用后，查看明文密钥、携带密钥分享、导出配置与切换运行模式
+//! 需先验证密码，校验在命令层执行，绕过界面直接调用命令同样会被拒绝。
+//! 验证通过后在一段时间内保持解锁；密码以 PBKDF2 加盐哈希单独保存，不随设置返回前端。
+
+use std::num::NonZeroU32;
+use std::path::PathBuf;
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+
+use base64::engine::general_purpose::STANDARD;
+use base64::Engine;
+use ring::pbkdf2;
+use ring::rand::{SecureRandom, SystemRandom};
+use serde::{Deserialize, Serialize};
+
+use crate::audit::AuditAction;
+use crate::config::{delete_file, get_app_config_dir, read_json_file, write_json_file};
+use crate::error::CcSwitchError;
+use crate::i18n::t;
+
+const LOCK_FILE: &str = "app_lock.json";
+
+const PBKDF2_ITERATIONS: u32 = 210_000;
+
+const SALT_LEN: usize = 16;
+
+const MIN_PASSCODE_LEN: usize = 4;
+
+/// 验证后保持解锁的时长
+const UNLOCK_TTL: Duration = Duration::from_secs(5 * 60);
+
+/// 密码错误后的等待时间，减缓暴力尝试
+const FAILURE_DELAY: Duration = Duration::from_secs(1);
+
+/// 解锁有效期截止时间
+static UNLOCKED_UNTIL: Mutex<Option<Instant>> = Mutex::new(None);
+
+#[derive(Debug, Serialize, Deserialize)]
+struct LockFile {
+    salt: String,
+    hash: String,
+    iterations: u32,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct AppLockStatus {
+    pub enabled: bool,
+    pub unlocked: bool,
+}
+
+fn lock_path() -> PathBuf {
+    get_app_config_dir().join(LOCK_FILE)
+}
+
+fn load() -> Option<LockFile> {
+    let path = lock_path();
+    if !path.exists() {
+        return None;
+    }
+    match read_json_file(&path) {
+        Ok(lock) => Some(lock),
+        Err(e) => {
+            // 文件损坏时仍视为已启用，避免因此绕过应用锁
+            tracing::error!("读取应用锁配置失败: {}", e);
+            Some(LockFile {
+                salt: String::new(),
+                hash: String::new(),
+                iterations: PBKDF2_ITERATIONS,
+            })
+        }
+    }
+}
+
+fn verify(lock: &LockFile, passcode: &str) -> bool {
+    let (Ok(salt), Ok(hash)) = (STANDARD.decode(&lock.salt), STANDARD.decode(&lock.hash)) else {
+        return false;
+    };
+    let Some(iterations) = NonZeroU32::new(lock.iterations) else {
+        return false;
+    };
+    !hash.is_empty()
+        && pbkdf2::verify(
+            pbkdf2::PBKDF2_HMAC_SHA256,
+            iterations,
+            &salt,
+            passcode.as_bytes(),
+            &hash,
+        )
+        .is_ok()
+}
+
+fn hash_passcode(passcode: &str) -> Result<LockFile, String> {
+    let mut salt = [0u8; SALT_LEN];
+    SystemRandom::new()
+        .fill(&mut salt)
+        .map_err(|_| "生成随机盐失败".to_string())?;
+    let iterations = NonZeroU32::new(PBKDF2_ITERATIONS).expect("iterations > 0");
+    let mut hash = [0u8; ring::digest::SHA256_OUTPUT_LEN];
+    pbkdf2::derive(
+        pbkdf2::PBKDF2_HMAC_SHA256,
+        iterations,
+        &salt,
+        passcode.as_bytes(),
+        &mut hash,
+    );
+    Ok(LockFile {
+        salt: STANDARD.encode(salt),
+        hash: STANDARD.encode(hash),
+        iterations: PBKDF2_ITERATIONS,
+    })
+}
+
+fn set_unlocked(unlocked: bool) {
+    if let Ok(mut until) = UNLOCKED_UNTIL.lock() {
+        *until = unlocked.then(|| Instant::now() + UNLOCK_TTL);
+    }
+}
+
+fn is_unlocked() -> bool {
+    UNLOCKED_UNTIL
+        .lock()
+        .map(|until| until.is_some_and(|t| Instant::now() < t))
+        .unwrap_or(false)
+}
+
+pub fn is_enabled() -> bool {
+    lock_path().exists()
+}
+
+fn status() -> AppLockStatus {
+    let enabled = is_enabled();
+    AppLockStatus {
+        enabled,
+        unlocked: !enabled || is_unlocked(),
+    }
+}
+
+/// 敏感操作前调用：已启用应用锁且不在解锁有效期内时返回错误
+pub fn ensure_unlocked() -> Result<(), String> {
+    if !is_enabled() || is_unlocked() {
+        return Ok(());
+    }
+    Err(t("errors.appLocked"))
+}
+
+async fn check_passcode(lock: &LockFile, passcode: &str) -> Result<(), String> {
+    if verify(lock, passcode) {
+        return Ok(());
+    }
+    tracing::warn!("应用锁密码验证失败");
+    tokio::time::sleep(FAILURE_DELAY).await;
+    Err(t("errors.passcodeIncorrect"))
+}
+
+/// 获取应用锁状态
+#[tauri::command]
+pub async fn get_app_lock_status() -> Result<AppLockStatus, CcSwitchError> {
+    Ok(status())
+}
+
+/// 验证密码并解锁
+#[tauri::command]
+pub async fn unlock_app(passcode: String) -> Result<AppLockStatus, CcSwitchError> {
+    if let Some(lock) = load() {
+        check_passcode(&lock, &passcode).await?;
+        set_unlocked(true);
+        tracing::info!("应用已解锁");
+    }
+    Ok(status())
+}
+
+/// 立即重新锁定
+#[tauri::command]
+pub async fn lock_app() -> Result<AppLockStatus, CcSwitchError> {
+    set_unlocked(false);
+    Ok(status())
+}
+
+/// 设置、修改或移除访问密码（已启用时需提供当前密码；passcode 为空表示移除）
+#[tauri::command]
+pub async fn set_app_lock_passcode(
+    current: Option<String>,
+    passcode: Option<String>,
+) -> Result<AppLockStatus, CcSwitchError> {
+    if let Some(lock) = load() {
+        check_passcode(&lock, current.as_deref().unwrap_or_default()).await?;
+    }
+
+    match passcode.filter(|p| !p.is_empty()) {
+        Some(passcode) => {
+            if passcode.chars().count() < MIN_PASSCODE_LEN {
+                return Err(t("errors.passcodeTooShort").into());
+            }
+            write_json_file(&lock_path(), &hash_passcode(&passcode)?)?;
+            set_unlocked(true);
+            tracing::info!("已设置应用锁密码");
+            crate::audit::record(AuditAction::AppLockChanged, None, None, Some("set".into()));
+        }
+        None => {
+            delete_file(&lock_path())?;
+            set_unlocked(false);
+            tracing::info!("已移除应用锁密码");
+            crate::audit::record(
+                AuditAction::AppLockChanged,
+                None,
+                None,
+                Some("removed".into()),
+            );
+        }
+    }
+    Ok(status())
+}
@@ -6,6 +6,17 @@ use tauri_plugin_store::StoreExt;
 /// Store 中的键名
 const STORE_KEY_APP_CONFIG_DIR: &str = "app_config_dir_override";
 
+/// Store 文件名（默认位于 Tauri 应用数据目录）
+const STORE_FILE: &str = "app_paths.json";
+
+/// Store 文件路径：便携模式下使用可执行文件旁的数据目录（绝对路径），否则交由插件解析
+fn store_path() -> PathBuf {
+    match crate::portable::portable_data_dir() {
+        Some(dir) => dir.join(STORE_FILE),
+        None => PathBuf::from(STORE_FILE),
+    }
+}
+
 /// 全局缓存的 AppHandle (在应用启动时设置)
 static APP_HANDLE: OnceLock<RwLock<Option<tauri::AppHandle>>> = OnceLock::new();
 
@@ -18,7 +29,7 @@ pub fn set_app_handle(handle: tauri::AppHandle) {
 }
 
 /// 获取全局 AppHandle
-fn get_app_handle() -> Option<tauri::AppHandle> {
+pub(crate) fn get_app_handle() -> Option<tauri::AppHandle> {
     let store = APP_HANDLE.get()?;
     let guard = store.read().ok()?;
     guard.as_ref().cloned()
@@ -32,10 +43,10 @@ pub fn get_app_config_dir_override() -> Option<PathBuf> {
 
 /// 从 Tauri Store 读取 app_config_dir 覆盖配置（公开函数）
 pub fn get_app_config_dir_from_store(app: &tauri::AppHandle) -> Option<PathBuf> {
-    let store = app.store_builder("app_paths.json").build();
+    let store = app.store_builder(store_path()).build();
 
     if let Err(e) = &store {
-        log::warn!("无法创建 Store: {}", e);
+        tracing::warn!("无法创建 Store: {}", e);
         return None;
     }
 
@@ -52,7 +63,7 @@ pub fn get_app_config_dir_from_store(app: &tauri::AppHandle) -> Option<PathBuf>
 
             // 验证路径是否存在
             if !path.exists() {
-                log::warn!(
+                tracing::warn!(
                     "Store 中配置的 app_config_dir 不存在: {:?}\n\
                      将使用默认路径。",
                     path
@@ -60,11 +71,14 @@ pub fn get_app_config_dir_from_store(app: &tauri::AppHandle) -> Option<PathBuf>
                 return None;
             }
 
-            log::info!("使用 Store 中的 app_config_dir: {:?}", path);
+            tracing::info!("使用 Store 中的 app_config_dir: {:?}", path);
             Some(path)
         }
         Some(_) => {
-            log::warn!("Store 中的 {} 类型不正确，应为字符串", STORE_KEY_APP_CONFIG_DIR);
+            tracing::warn!(
+                "Store 中的 {} 类型不正确，应为字符串",
+                STORE_KEY_APP_CONFIG_DIR
+            );
             None
         }
         None => None,
@@ -77,7 +91,7 @@ pub fn set_app_config_dir_to_store(
     path: Option<&str>,
 ) -> Result<(), String> {
     let store = app
-        .store_builder("app_paths.json")
+        .store_builder(store_path())
         .build()
         .map_err(|e| format!("创建 Store 失败: {}", e))?;
 
@@ -86,21 +100,23 @@ pub fn set_app_config_dir_to_store(
             let trimmed = p.trim();
             if !trimmed.is_empty() {
                 store.set(STORE_KEY_APP_CONFIG_DIR, Value::String(trimmed.to_string()));
-                log::info!("已将 app_config_dir 写入 Store: {}", trimmed);
+                tracing::info!("已将 app_config_dir 写入 Store: {}", trimmed);
             } else {
                 // 空字符串 = 删除配置
                 store.delete(STORE_KEY_APP_CONFIG_DIR);
-                log::info!("已从 Store 中删除 app_config_dir 配置");
+                tracing::info!("已从 Store 中删除 app_config_dir 配置");
             }
         }
         None => {
             // None = 删除配置
             store.delete(STORE_KEY_APP_CONFIG_DIR);
-            log::info!("已从 Store 中删除 app_config_dir 配置");
+            tracing::info!("已从 Store 中删除 app_config_dir 配置");
         }
     }
 
-    store.save().map_err(|e| format!("保存 Store 失败: {}", e))?;
+    store
+        .save()
+        .map_err(|e| format!("保存 Store 失败: {}", e))?;
 
     Ok(())
 }
@@ -124,14 +140,67 @@ fn resolve_path(raw: &str) -> PathBuf {
     PathBuf::from(raw)
 }
 
-/// 从旧的 settings.json 迁移 app_config_dir 到 Store
-pub fn migrate_app_config_dir_from_settings(app: &tauri::AppHandle) -> Result<(), String> {
-    // app_config_dir 已从 settings.json 移除，此函数保留但不再执行迁移
-    // 如果用户在旧版本设置过 app_config_dir，需要在 Store 中手动配置
-    log::info!("app_config_dir 迁移功能已移除，请在设置中重新配置");
+/// 从旧的 settings.json 迁移 app_config_dir 到 Store，返回迁移的目录（未发生迁移时为 None）
+///
+/// 旧版本将 app_config_dir 保存在 settings.json 中；仅当 Store 尚未配置且目录存在时迁移，
+/// 迁移前先归档旧 settings.json，随后从中移除该字段
+pub fn migrate_app_config_dir_from_settings(
+    app: &tauri::AppHandle,
+) -> Result<Option<String>, String> {
+    let settings_path = crate::settings::get_settings_file_path();
+    if !settings_path.exists() {
+        return Ok(None);
+    }
+    let mut raw: Value = match crate::config::read_json_file(&settings_path) {
+        Ok(raw) => raw,
+        Err(e) => {
+            tracing::warn!(
+                "读取旧版 settings.json 失败，跳过 app_config_dir 迁移: {}",
+                e
+            );
+            return Ok(None);
+        }
+    };
+    let Some(obj) = raw.as_object_mut() else {
+        return Ok(None);
+    };
+
+    let legacy_key = ["appConfigDir", "app_config_dir"]
+        .into_iter()
+        .find(|key| obj.contains_key(*key));
+    let Some(legacy_key) = legacy_key else {
+        return Ok(None);
+    };
+    let legacy_dir = obj
+        .get(legacy_key)
+        .and_then(|v| v.as_str())
+        .map(|s| s.trim().to_string())
+        .filter(|s| !s.is_empty());
+
+    let mut migrated = None;
+    if let Some(dir) = legacy_dir {
+        if get_app_config_dir_from_store(app).is_some() {
+            tracing::info!(
+                "Store 中已配置 app_config_dir，忽略 settings.json 中的旧值: {}",
+                dir
+            );
+        } else if !resolve_path(&dir).exists() {
+            tracing::warn!("旧版 app_config_dir 不存在，跳过迁移: {}", dir);
+        } else {
+            set_app_config_dir_to_store(app, Some(&dir))?;
+            tracing::info!(
+                "已将 settings.json 中的 app_config_dir 迁移到 Store: {}",
+                dir
+            );
+            migrated = Some(dir);
+        }
+    }
 
-    // 确保 Store 初始化正常
-    let _ = get_app_config_dir_from_store(app);
+    // 归档旧 settings.json 后移除遗留字段，避免下次启动重复处理
+    let ts = chrono::Utc::now().timestamp() as u64;
+    crate::config::archive_file(ts, "settings", &settings_path)?;
+    obj.remove(legacy_key);
+    crate::config::write_json_file(&settings_path, &raw)?;
 
-    Ok(())
-}
\ No newline at end of file
+    Ok(migrated)
+}
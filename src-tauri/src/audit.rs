@@ -0,0 +1,202 @@
+//! 审计日志：记录修改配置的操作（谁、何时、做了什么），写入应用数据库的 `audit_log` 表，
+//! 便于在多人共用的机器上追溯供应商、密钥与运行模式的变更。
+//! 记录中不包含密钥本身；超过保留天数（`auditRetentionDays`，0 表示永久保留）的记录在启动时清理。
+//! 旧版以 JSON Lines 写入的 `audit.log` 在启动时导入数据库。
+
+use std::fs;
+
+use rusqlite::{params, Connection};
+use serde::{Deserialize, Serialize};
+
+use crate::app_config::AppType;
+use crate::config::get_app_config_dir;
+use crate::database::db_err;
+use crate::error::CcSwitchError;
+
+/// 旧版审计日志文件（导入数据库后改名为 audit.log.imported）
+const LEGACY_AUDIT_FILE: &str = "audit.log";
+
+/// 单次查询返回的最大条数
+const MAX_QUERY_LIMIT: usize = 1000;
+
+const DAY_MS: i64 = 24 * 60 * 60 * 1000;
+
+/// 审计动作
+#[derive(Debug, Clone, Copy, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum AuditAction {
+    ProviderAdded,
+    ProviderUpdated,
+    /// 供应商配置中的密钥发生变化
+    KeyChanged,
+    ProviderDeleted,
+    ProviderSwitched,
+    ProviderEnabled,
+    ProviderDisabled,
+    ProviderLocked,
+    ProviderUnlocked,
+    /// 批量操作（detail 为操作类型与涉及的供应商 ID）
+    BulkUpdate,
+    ModeChanged,
+    ConfigImported,
+    /// 从团队托管配置源同步（detail 为新增/更新/移除数量）
+    ManagedSync,
+    AppLockChanged,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct AuditEntry {
+    /// 毫秒时间戳
+    pub timestamp: i64,
+    /// 执行操作的系统用户
+    pub user: String,
+    pub action: AuditAction,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub app_type: Option<String>,
+    /// 操作对象（通常为供应商名称）
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub target: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub detail: Option<String>,
+}
+
+fn current_user() -> String {
+    std::env::var("USER")
+        .or_else(|_| std::env::var("USERNAME"))
+        .unwrap_or_else(|_| "unknown".to_string())
+}
+
+/// 追加一条审计记录；写入失败只记录日志，不影响操作本身
+pub fn record(
+    action: AuditAction,
+    app_type: Option<&AppType>,
+    target: Option<&str>,
+    detail: Option<String>,
+) {
+    let entry = AuditEntry {
+        timestamp: chrono::Utc::now().timestamp_millis(),
+        user: current_user(),
+        action,
+        app_type: app_type.map(|a| a.as_str().to_string()),
+        target: target.map(str::to_string),
+        detail,
+    };
+    if let Err(e) = append(&entry) {
+        tracing::warn!("写入审计日志失败: {}", e);
+    }
+}
+
+fn insert(conn: &Connection, entry: &AuditEntry) -> Result<(), String> {
+    let action = serde_json::to_value(entry.action)
+        .ok()
+        .and_then(|v| v.as_str().map(str::to_string))
+        .ok_or("序列化审计动作失败")?;
+    conn.execute(
+        "INSERT INTO audit_log (timestamp, user, action, app_type, target, detail)
+         VALUES (?1, ?2, ?3, ?4, ?5, ?6)",
+        params![
+            entry.timestamp,
+            entry.user,
+            action,
+            entry.app_type,
+            entry.target,
+            entry.detail
+        ],
+    )
+    .map_err(db_err)?;
+    Ok(())
+}
+
+fn append(entry: &AuditEntry) -> Result<(), String> {
+    insert(&crate::database::open()?, entry)
+}
+
+/// 将旧版 audit.log（JSON Lines）导入数据库，完成后改名保留；无法解析的行被跳过
+pub fn import_legacy_log() -> Result<usize, String> {
+    let path = get_app_config_dir().join(LEGACY_AUDIT_FILE);
+    if !path.exists() {
+        return Ok(0);
+    }
+    let content = fs::read_to_string(&path)
+        .map_err(|e| format!("读取审计日志失败: {}: {}", path.display(), e))?;
+    let entries: Vec<AuditEntry> = content
+        .lines()
+        .filter(|line| !line.trim().is_empty())
+        .filter_map(|line| serde_json::from_str(line).ok())
+        .collect();
+
+    let mut conn = crate::database::open()?;
+    let tx = conn.transaction().map_err(db_err)?;
+    for entry in &entries {
+        insert(&tx, entry)?;
+    }
+    tx.commit().map_err(db_err)?;
+
+    let imported = path.with_extension("log.imported");
+    fs::rename(&path, &imported)
+        .map_err(|e| format!("重命名已导入的审计日志失败: {}: {}", path.display(), e))?;
+    tracing::info!("已导入 {} 条旧版审计记录", entries.len());
+    Ok(entries.len())
+}
+
+/// 清理超过保留天数的记录（retention_days 为 0 时不清理）
+pub fn prune(retention_days: u32) -> Result<usize, String> {
+    if retention_days == 0 {
+        return Ok(0);
+    }
+    let cutoff = chrono::Utc::now().timestamp_millis() - i64::from(retention_days) * DAY_MS;
+    let removed = crate::database::open()?
+        .execute("DELETE FROM audit_log WHERE timestamp < ?1", [cutoff])
+        .map_err(db_err)?;
+    if removed > 0 {
+        tracing::info!("已清理 {} 条过期审计记录", removed);
+    }
+    Ok(removed)
+}
+
+/// 获取审计记录（最新在前）
+#[tauri::command]
+pub async fn get_audit_log(
+    limit: Option<usize>,
+    offset: Option<usize>,
+) -> Result<Vec<AuditEntry>, CcSwitchError> {
+    let limit = limit.unwrap_or(200).min(MAX_QUERY_LIMIT);
+    let conn = crate::database::open()?;
+    let mut stmt = conn
+        .prepare(
+            "SELECT timestamp, user, action, app_type, target, detail FROM audit_log
+             ORDER BY id DESC LIMIT ?1 OFFSET ?2",
+        )
+        .map_err(db_err)?;
+    let rows = stmt
+        .query_map(params![limit as i64, offset.unwrap_or(0) as i64], |row| {
+            Ok((
+                row.get::<_, i64>(0)?,
+                row.get::<_, String>(1)?,
+                row.get::<_, String>(2)?,
+                row.get::<_, Option<String>>(3)?,
+                row.get::<_, Option<String>>(4)?,
+                row.get::<_, Option<String>>(5)?,
+            ))
+        })
+        .map_err(db_err)?;
+
+    let mut entries = Vec::new();
+    for row in rows {
+        let (timestamp, user, action, app_type, target, detail) = row.map_err(db_err)?;
+        // 无法识别的动作（如更新版本写入）直接跳过
+        let Ok(action) = serde_json::from_value(serde_json::Value::String(action)) else {
+            continue;
+        };
+        entries.push(AuditEntry {
+            timestamp,
+            user,
+            action,
+            app_type,
+            target,
+            detail,
+        });
+    }
+    Ok(entries)
+}
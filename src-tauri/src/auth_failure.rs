@@ -0,0 +1,90 @@
+//! 代理模式下的鉴权失败黑名单：上游返回 401/403 时几乎可以肯定是 Key 配置错误，
+//! 重试无济于事，因此将该供应商标记为鉴权失败并在较长的冷却期内直接跳过，
+//! 同时通知前端提示用户修正 Key。编辑供应商后立即解除；记录仅保存在进程内。
+
+use serde::Serialize;
+use std::collections::HashMap;
+use std::sync::{Mutex, OnceLock};
+use std::time::{Duration, Instant};
+use tauri::Emitter;
+
+use crate::app_config::AppType;
+use crate::provider::Provider;
+
+/// 鉴权失败后的冷却时间
+const COOLDOWN: Duration = Duration::from_secs(30 * 60);
+
+/// 鉴权失败事件名
+pub const AUTH_FAILED_EVENT: &str = "proxy-auth-failed";
+
+/// 应用类型:供应商 ID -> 标记时间
+static FAILED: OnceLock<Mutex<HashMap<String, Instant>>> = OnceLock::new();
+
+/// 鉴权失败事件负载
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct AuthFailedPayload {
+    pub app_type: String,
+    pub provider_id: String,
+    pub provider_name: String,
+    pub status: u16,
+    /// 冷却时长（分钟）
+    pub cooldown_minutes: u64,
+}
+
+fn key(app_type: &AppType, provider_id: &str) -> String {
+    format!("{}:{}", app_type.as_str(), provider_id)
+}
+
+/// 是否为鉴权失败的状态码
+pub fn is_auth_failure(status: u16) -> bool {
+    matches!(status, 401 | 403)
+}
+
+/// 供应商是否处于鉴权失败冷却期（过期记录顺带清除）
+pub fn is_blocked(app_type: &AppType, provider: &Provider) -> bool {
+    let Ok(mut failed) = FAILED.get_or_init(|| Mutex::new(HashMap::new())).lock() else {
+        return false;
+    };
+    let key = key(app_type, &provider.id);
+    match failed.get(&key) {
+        Some(at) if at.elapsed() < COOLDOWN => true,
+        Some(_) => {
+            failed.remove(&key);
+            false
+        }
+        None => false,
+    }
+}
+
+/// 标记供应商鉴权失败并通知前端
+pub fn mark(app_type: &AppType, provider: &Provider, status: u16) {
+    if let Ok(mut failed) = FAILED.get_or_init(|| Mutex::new(HashMap::new())).lock() {
+        failed.insert(key(app_type, &provider.id), Instant::now());
+    }
+    tracing::warn!(
+        provider = %provider.name,
+        "上游返回 {}，已将供应商标记为鉴权失败，{} 分钟内不再尝试",
+        status,
+        COOLDOWN.as_secs() / 60
+    );
+    let payload = AuthFailedPayload {
+        app_type: app_type.as_str().to_string(),
+        provider_id: provider.id.clone(),
+        provider_name: provider.name.clone(),
+        status,
+        cooldown_minutes: COOLDOWN.as_secs() / 60,
+    };
+    if let Some(app) = crate::app_store::get_app_handle() {
+        if let Err(e) = app.emit(AUTH_FAILED_EVENT, &payload) {
+            tracing::error!("发射鉴权失败事件失败: {}", e);
+        }
+    }
+}
+
+/// 解除供应商的鉴权失败标记（编辑供应商后调用）
+pub fn clear(app_type: &AppType, provider_id: &str) {
+    if let Ok(mut failed) = FAILED.get_or_init(|| Mutex::new(HashMap::new())).lock() {
+        failed.remove(&key(app_type, provider_id));
+    }
+}
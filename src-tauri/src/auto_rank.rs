@@ -0,0 +1,210 @@
+//! 按实测表现自动排序供应商：对每个已启用的供应商连续发送几次探测请求，
+//! 按错误率、平均延迟重写 sort_index，使代理故障转移的顺序贴合实际网络状况。
+//! 可在设置中开启定时排序（每轮重新读取设置，两个应用依次处理）。
+
+use futures::future::join_all;
+use serde::Serialize;
+use std::time::{Duration, Instant};
+use tauri::{Manager, State};
+
+use crate::app_config::AppType;
+use crate::audit::AuditAction;
+use crate::error::CcSwitchError;
+use crate::i18n::t_args;
+use crate::store::AppState;
+
+/// 每个供应商的探测次数
+const PROBE_COUNT: usize = 5;
+
+/// 单次探测超时（秒）
+const PROBE_TIMEOUT_SECS: u64 = 8;
+
+/// 最短定时间隔（分钟）
+const MIN_INTERVAL_MINUTES: u32 = 10;
+
+/// 未开启定时排序时重新检查设置的间隔
+const IDLE_CHECK_INTERVAL: Duration = Duration::from_secs(60);
+
+/// 单个供应商的探测结果
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ProviderRank {
+    pub id: String,
+    pub name: String,
+    /// 成功请求的平均延迟（毫秒），全部失败时为空
+    pub latency: Option<u64>,
+    /// 失败请求占比（0–1），无法提取地址时为 1
+    pub error_rate: f64,
+    pub sort_index: usize,
+}
+
+/// 连续探测 base_url：网络错误、5xx 与 429 计为失败
+async fn probe(client: &reqwest::Client, base_url: &str) -> (Option<u64>, f64) {
+    let mut latencies = Vec::new();
+    for _ in 0..PROBE_COUNT {
+        let start = Instant::now();
+        match client.get(base_url).send().await {
+            Ok(resp) if !resp.status().is_server_error() && resp.status().as_u16() != 429 => {
+                latencies.push(start.elapsed().as_millis() as u64);
+            }
+            Ok(_) | Err(_) => {}
+        }
+    }
+    let errors = PROBE_COUNT - latencies.len();
+    let latency =
+        (!latencies.is_empty()).then(|| latencies.iter().sum::<u64>() / latencies.len() as u64);
+    (latency, errors as f64 / PROBE_COUNT as f64)
+}
+
+/// 探测并重写 sort_index：已启用的供应商按错误率、延迟排在前面，其余保持原有相对顺序
+pub async fn rank(
+    handle: &tauri::AppHandle,
+    app_state: &AppState,
+    app_type: &AppType,
+) -> Result<Vec<ProviderRank>, String> {
+    let targets: Vec<(String, String, Option<String>)> = {
+        let config = app_state
+            .config
+            .lock()
+            .map_err(|e| t_args("errors.lockFailed", &[("error", &e)]))?;
+        let manager = config
+            .get_manager(app_type)
+            .ok_or_else(|| t_args("errors.appTypeNotFound", &[("app", &app_type.as_str())]))?;
+        manager
+            .sorted_providers()
+            .into_iter()
+            .filter(|p| p.is_enabled() && !p.in_maintenance())
+            .map(|p| {
+                let base_url = crate::commands::extract_credentials(p, app_type)
+                    .ok()
+                    .map(|(_, url)| url);
+                (p.id.clone(), p.name.clone(), base_url)
+            })
+            .collect()
+    };
+    if targets.is_empty() {
+        return Ok(Vec::new());
+    }
+
+    let client = crate::speedtest::build_client(PROBE_TIMEOUT_SECS)?;
+    let probes = targets.iter().map(|(_, _, base_url)| {
+        let client = client.clone();
+        async move {
+            match base_url {
+                Some(url) => probe(&client, url).await,
+                None => (None, 1.0),
+            }
+        }
+    });
+    let results = join_all(probes).await;
+
+    let mut ranks: Vec<ProviderRank> = targets
+        .into_iter()
+        .zip(results)
+        .map(|((id, name, _), (latency, error_rate))| ProviderRank {
+            id,
+            name,
+            latency,
+            error_rate,
+            sort_index: 0,
+        })
+        .collect();
+    // 稳定排序：表现相同的供应商保持原顺序
+    ranks.sort_by(|a, b| {
+        a.error_rate
+            .total_cmp(&b.error_rate)
+            .then(match (a.latency, b.latency) {
+                (Some(x), Some(y)) => x.cmp(&y),
+                (Some(_), None) => std::cmp::Ordering::Less,
+                (None, Some(_)) => std::cmp::Ordering::Greater,
+                (None, None) => std::cmp::Ordering::Equal,
+            })
+    });
+
+    {
+        let mut config = app_state
+            .config
+            .lock()
+            .map_err(|e| t_args("errors.lockFailed", &[("error", &e)]))?;
+        let manager = config
+            .get_manager_mut(app_type)
+            .ok_or_else(|| t_args("errors.appTypeNotFound", &[("app", &app_type.as_str())]))?;
+        let rest: Vec<String> = manager
+            .sorted_providers()
+            .into_iter()
+            .filter(|p| !ranks.iter().any(|r| r.id == p.id))
+            .map(|p| p.id.clone())
+            .collect();
+        for (index, rank) in ranks.iter_mut().enumerate() {
+            rank.sort_index = index;
+        }
+        let order = ranks.iter().map(|r| r.id.clone()).chain(rest);
+        for (index, id) in order.enumerate() {
+            if let Some(provider) = manager.providers.get_mut(&id) {
+                provider.sort_index = Some(index);
+            }
+        }
+    }
+    app_state.save_now()?;
+
+    let ids: Vec<String> = ranks.iter().map(|r| r.id.clone()).collect();
+    tracing::info!(
+        "已按探测结果重排 {} 供应商: {}",
+        app_type.as_str(),
+        ids.join(", ")
+    );
+    crate::audit::record(
+        AuditAction::BulkUpdate,
+        Some(app_type),
+        None,
+        Some(format!("auto_rank: {}", ids.join(", "))),
+    );
+    if let Err(e) = crate::tray::refresh_tray_menu(handle) {
+        tracing::warn!("刷新托盘菜单失败: {}", e);
+    }
+    crate::bulk::emit_providers_updated(handle, app_type, "auto_rank", ids);
+    Ok(ranks)
+}
+
+/// 后台定时排序：开启后按设置的间隔对两个应用依次排序（每轮重新读取设置）
+pub fn start_auto_rank(app: tauri::AppHandle) {
+    tauri::async_runtime::spawn(async move {
+        loop {
+            let settings = crate::settings::get_settings().auto_rank;
+            if !settings.enabled {
+                tokio::time::sleep(IDLE_CHECK_INTERVAL).await;
+                continue;
+            }
+            let minutes = settings.interval_minutes.max(MIN_INTERVAL_MINUTES);
+            tokio::time::sleep(Duration::from_secs(u64::from(minutes) * 60)).await;
+            if !crate::settings::get_settings().auto_rank.enabled {
+                continue;
+            }
+            let Some(app_state) = app.try_state::<AppState>() else {
+                continue;
+            };
+            for app_type in [AppType::Claude, AppType::Codex] {
+                if let Err(e) = rank(&app, &app_state, &app_type).await {
+                    tracing::warn!("自动排序 {} 供应商失败: {}", app_type.as_str(), e);
+                }
+            }
+        }
+    });
+}
+
+/// 立即探测并按结果重排供应商
+#[tauri::command]
+#[allow(non_snake_case)]
+pub async fn auto_rank_providers(
+    handle: tauri::AppHandle,
+    state: State<'_, AppState>,
+    app_type: Option<AppType>,
+    app: Option<String>,
+    appType: Option<String>,
+) -> Result<Vec<ProviderRank>, CcSwitchError> {
+    let app_type = app_type
+        .or_else(|| app.as_deref().map(|s| s.into()))
+        .or_else(|| appType.as_deref().map(|s| s.into()))
+        .unwrap_or(AppType::Claude);
+    Ok(rank(&handle, &state, &app_type).await?)
+}
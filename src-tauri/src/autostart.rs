@@ -0,0 +1,60 @@
+//! 登录时自动启动：通过 tauri-plugin-autostart 注册/注销系统自启项。
+//! 自启项携带 `--autostart` 参数，用于区分手动打开与开机自启，后者可按设置隐藏到托盘。
+
+use tauri::Manager;
+use tauri_plugin_autostart::{MacosLauncher, ManagerExt};
+
+/// 自启项的启动参数
+pub const AUTOSTART_ARG: &str = "--autostart";
+
+/// 初始化自启插件，并按设置同步系统自启项（应用更新后可执行文件路径可能变化，每次启动都重新注册）
+pub fn init(app: &tauri::AppHandle) {
+    if let Err(e) = app.plugin(tauri_plugin_autostart::init(
+        MacosLauncher::LaunchAgent,
+        Some(vec![AUTOSTART_ARG]),
+    )) {
+        tracing::warn!("初始化自启插件失败，已跳过：{}", e);
+        return;
+    }
+    if let Err(e) = sync(app, crate::settings::get_settings().launch_at_login) {
+        tracing::warn!("同步开机自启状态失败: {}", e);
+    }
+}
+
+/// 注册或注销系统自启项
+pub fn sync(app: &tauri::AppHandle, enabled: bool) -> Result<(), String> {
+    let autolaunch = app.autolaunch();
+    let result = if enabled {
+        autolaunch.enable()
+    } else if autolaunch.is_enabled().unwrap_or(false) {
+        autolaunch.disable()
+    } else {
+        Ok(())
+    };
+    result.map_err(|e| e.to_string())
+}
+
+/// 本次是否由开机自启拉起
+fn launched_at_login() -> bool {
+    std::env::args().skip(1).any(|arg| arg == AUTOSTART_ARG)
+}
+
+/// 开机自启且开启了“启动时最小化”时，隐藏主窗口只保留托盘图标
+pub fn hide_on_startup(app: &tauri::AppHandle) {
+    let settings = crate::settings::get_settings();
+    if !launched_at_login() || !settings.start_minimized {
+        return;
+    }
+    if let Some(window) = app.get_webview_window("main") {
+        let _ = window.hide();
+        #[cfg(target_os = "windows")]
+        {
+            let _ = window.set_skip_taskbar(true);
+        }
+        #[cfg(target_os = "macos")]
+        {
+            crate::tray::apply_tray_policy(app, false);
+        }
+        tracing::info!("开机自启：主窗口已隐藏到托盘");
+    }
+}
@@ -0,0 +1,277 @@
+//! 供应商批量操作：代理开关、删除、调整分类与排序。
+//! 每次调用只保存一次配置、只发送一次 `providers-updated` 事件，避免逐个操作时的多次写盘。
+
+#![allow(non_snake_case)]
+
+use serde::Serialize;
+use tauri::{Emitter, State};
+
+use crate::app_config::AppType;
+use crate::audit::AuditAction;
+use crate::error::CcSwitchError;
+use crate::i18n::{t, t_args};
+use crate::provider::ProviderManager;
+use crate::store::AppState;
+
+/// 批量操作完成后发送给前端的事件
+pub const PROVIDERS_UPDATED_EVENT: &str = "providers-updated";
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+struct ProvidersUpdatedPayload {
+    app_type: String,
+    action: &'static str,
+    ids: Vec<String>,
+}
+
+/// 被跳过的供应商及原因
+#[derive(Debug, Clone, Serialize)]
+pub struct BulkSkipped {
+    pub id: String,
+    pub reason: String,
+}
+
+/// 批量操作结果
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct BulkResult {
+    pub updated: Vec<String>,
+    pub skipped: Vec<BulkSkipped>,
+}
+
+fn resolve_app_type(
+    app_type: Option<AppType>,
+    app: Option<String>,
+    appType: Option<String>,
+) -> AppType {
+    app_type
+        .or_else(|| app.as_deref().map(|s| s.into()))
+        .or_else(|| appType.as_deref().map(|s| s.into()))
+        .unwrap_or(AppType::Claude)
+}
+
+/// 在一次加锁内对每个 ID 执行操作（返回 Err 表示跳过该项），有变更时统一保存并发送事件
+fn apply<F>(
+    handle: &tauri::AppHandle,
+    state: &AppState,
+    app_type: &AppType,
+    action: &'static str,
+    ids: &[String],
+    mut op: F,
+) -> Result<BulkResult, CcSwitchError>
+where
+    F: FnMut(&mut ProviderManager, &str) -> Result<(), String>,
+{
+    let mut result = BulkResult::default();
+    {
+        let mut config = state
+            .config
+            .lock()
+            .map_err(|e| t_args("errors.lockFailed", &[("error", &e)]))?;
+        let manager = config
+            .get_manager_mut(app_type)
+            .ok_or_else(|| t_args("errors.appTypeNotFound", &[("app", &app_type.as_str())]))?;
+
+        for id in ids {
+            if result.updated.contains(id) {
+                continue;
+            }
+            let outcome = if manager.providers.contains_key(id) {
+                op(manager, id)
+            } else {
+                Err(t_args("errors.providerNotFound", &[("id", &id)]))
+            };
+            match outcome {
+                Ok(()) => result.updated.push(id.clone()),
+                Err(reason) => result.skipped.push(BulkSkipped {
+                    id: id.clone(),
+                    reason,
+                }),
+            }
+        }
+    }
+
+    if result.updated.is_empty() {
+        return Ok(result);
+    }
+    state.save_now()?;
+    tracing::info!(
+        "批量操作 {} 完成: {} 个供应商已更新，{} 个跳过",
+        action,
+        result.updated.len(),
+        result.skipped.len()
+    );
+
+    crate::audit::record(
+        AuditAction::BulkUpdate,
+        Some(app_type),
+        None,
+        Some(format!("{}: {}", action, result.updated.join(", "))),
+    );
+
+    if let Err(e) = crate::tray::refresh_tray_menu(handle) {
+        tracing::warn!("刷新托盘菜单失败: {}", e);
+    }
+    emit_providers_updated(handle, app_type, action, result.updated.clone());
+    Ok(result)
+}
+
+/// 通知前端供应商列表已在后端批量变更
+pub(crate) fn emit_providers_updated(
+    handle: &tauri::AppHandle,
+    app_type: &AppType,
+    action: &'static str,
+    ids: Vec<String>,
+) {
+    let payload = ProvidersUpdatedPayload {
+        app_type: app_type.as_str().to_string(),
+        action,
+        ids,
+    };
+    if let Err(e) = handle.emit(PROVIDERS_UPDATED_EVENT, &payload) {
+        tracing::warn!("发送供应商批量更新事件失败: {}", e);
+    }
+}
+
+/// 批量启用/关闭代理
+#[tauri::command]
+pub async fn bulk_set_proxy_enabled(
+    handle: tauri::AppHandle,
+    state: State<'_, AppState>,
+    ids: Vec<String>,
+    enabled: bool,
+    app_type: Option<AppType>,
+    app: Option<String>,
+    appType: Option<String>,
+) -> Result<BulkResult, CcSwitchError> {
+    let app_type = resolve_app_type(app_type, app, appType);
+    apply(&handle, &state, &app_type, "proxy", &ids, |manager, id| {
+        if let Some(provider) = manager.providers.get_mut(id) {
+            provider.proxy_enabled = Some(enabled);
+        }
+        Ok(())
+    })
+}
+
+/// 批量删除供应商（当前供应商会被跳过）
+#[tauri::command]
+pub async fn bulk_delete_providers(
+    handle: tauri::AppHandle,
+    state: State<'_, AppState>,
+    ids: Vec<String>,
+    app_type: Option<AppType>,
+    app: Option<String>,
+    appType: Option<String>,
+) -> Result<BulkResult, CcSwitchError> {
+    let app_type = resolve_app_type(app_type, app, appType);
+    apply(&handle, &state, &app_type, "delete", &ids, |manager, id| {
+        if manager.current == id {
+            return Err(t("errors.cannotDeleteCurrent"));
+        }
+        if let Some(provider) = manager.providers.get(id) {
+            provider.ensure_editable()?;
+        }
+        if let Some(provider) = manager.providers.remove(id) {
+            // 历史副本文件删除失败不影响配置中的删除
+            if let Err(e) = crate::commands::delete_provider_copies(&app_type, id, &provider.name) {
+                tracing::warn!("删除供应商 {} 的副本文件失败: {}", provider.name, e);
+            }
+        }
+        Ok(())
+    })
+}
+
+/// 批量设置分类（category 为空时清除分类）
+#[tauri::command]
+pub async fn bulk_set_category(
+    handle: tauri::AppHandle,
+    state: State<'_, AppState>,
+    ids: Vec<String>,
+    category: Option<String>,
+    app_type: Option<AppType>,
+    app: Option<String>,
+    appType: Option<String>,
+) -> Result<BulkResult, CcSwitchError> {
+    let app_type = resolve_app_type(app_type, app, appType);
+    let category = category
+        .map(|c| c.trim().to_string())
+        .filter(|c| !c.is_empty());
+    apply(
+        &handle,
+        &state,
+        &app_type,
+        "category",
+        &ids,
+        |manager, id| {
+            if let Some(provider) = manager.providers.get_mut(id) {
+                provider.ensure_editable()?;
+                provider.category = category.clone();
+            }
+            Ok(())
+        },
+    )
+}
+
+/// 按给定顺序重排：ordered_ids 依次填入它们在当前顺序中占据的位置（只传过滤后的部分列表时，
+/// 其余供应商保持原位），随后把全部供应商的 sortIndex 重写为连续的 0..n，消除空缺与重复。
+/// 一次加锁、一次保存，代理快照随保存刷新；返回重排后的完整顺序
+#[tauri::command]
+pub async fn reorder_providers(
+    handle: tauri::AppHandle,
+    state: State<'_, AppState>,
+    ordered_ids: Option<Vec<String>>,
+    orderedIds: Option<Vec<String>>,
+    app_type: Option<AppType>,
+    app: Option<String>,
+    appType: Option<String>,
+) -> Result<Vec<String>, CcSwitchError> {
+    let app_type = resolve_app_type(app_type, app, appType);
+    let ordered_ids = ordered_ids
+        .or(orderedIds)
+        .ok_or_else(|| t_args("errors.missingParam", &[("name", &"orderedIds")]))?;
+
+    let order = {
+        let mut config = state
+            .config
+            .lock()
+            .map_err(|e| t_args("errors.lockFailed", &[("error", &e)]))?;
+        let manager = config
+            .get_manager_mut(&app_type)
+            .ok_or_else(|| t_args("errors.appTypeNotFound", &[("app", &app_type.as_str())]))?;
+
+        let mut moved: Vec<&String> = Vec::with_capacity(ordered_ids.len());
+        for id in &ordered_ids {
+            if !manager.providers.contains_key(id) {
+                return Err(t_args("errors.providerNotFound", &[("id", &id)]).into());
+            }
+            if !moved.contains(&id) {
+                moved.push(id);
+            }
+        }
+
+        let mut next = moved.iter();
+        let order: Vec<String> = manager
+            .sorted_providers()
+            .into_iter()
+            .map(|p| {
+                if moved.contains(&&p.id) {
+                    next.next().map_or_else(|| p.id.clone(), |id| (*id).clone())
+                } else {
+                    p.id.clone()
+                }
+            })
+            .collect();
+        for (index, id) in order.iter().enumerate() {
+            if let Some(provider) = manager.providers.get_mut(id) {
+                provider.sort_index = Some(index);
+            }
+        }
+        order
+    };
+    state.save_now()?;
+
+    if let Err(e) = crate::tray::refresh_tray_menu(&handle) {
+        tracing::warn!("刷新托盘菜单失败: {}", e);
+    }
+    emit_providers_updated(&handle, &app_type, "reorder", order.clone());
+    Ok(order)
+}
@@ -0,0 +1,224 @@
+//! 上游连接的证书信任：在内置的 webpki 根证书之外，加载应用配置目录 `ca-certs/` 中的
+//! PEM 证书（`.pem` / `.crt`），用于企业中间人代理或使用私有 CA 的自建中转。
+//! 供应商可配置 mTLS 客户端证书（见 `client_cert`）；还可以开启"忽略证书错误"，此时不再校验上游证书——连接可能被中间人截获，仅应在排查问题时临时使用。
+
+use std::path::PathBuf;
+use std::sync::Arc;
+
+use rustls::client::danger::{HandshakeSignatureValid, ServerCertVerified, ServerCertVerifier};
+use rustls::crypto::{verify_tls12_signature, verify_tls13_signature, CryptoProvider};
+use rustls::pki_types::pem::PemObject;
+use rustls::pki_types::{CertificateDer, ServerName, UnixTime};
+use rustls::{ClientConfig, DigitallySignedStruct, RootCertStore, SignatureScheme};
+use serde::Serialize;
+use tauri_plugin_opener::OpenerExt;
+
+use crate::config::get_app_config_dir;
+use crate::error::CcSwitchError;
+use crate::i18n::t_args;
+use crate::provider::ClientCertificate;
+
+/// 额外根证书所在目录名
+const CA_DIR: &str = "ca-certs";
+
+/// 一个证书文件的加载结果
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CaCertificateFile {
+    pub file: String,
+    /// 成功加入信任列表的证书数
+    pub certificates: usize,
+    pub error: Option<String>,
+}
+
+/// 额外根证书目录与其中的证书文件
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CaCertificates {
+    pub dir: String,
+    pub files: Vec<CaCertificateFile>,
+}
+
+pub fn ca_dir() -> PathBuf {
+    get_app_config_dir().join(CA_DIR)
+}
+
+/// 读取目录中的全部证书文件（按文件名排序），返回证书与每个文件的加载结果
+fn load_extra() -> (Vec<CertificateDer<'static>>, Vec<CaCertificateFile>) {
+    let Ok(entries) = std::fs::read_dir(ca_dir()) else {
+        return (Vec::new(), Vec::new());
+    };
+    let mut paths: Vec<PathBuf> = entries
+        .filter_map(|entry| entry.ok().map(|entry| entry.path()))
+        .filter(|path| {
+            path.extension()
+                .and_then(|ext| ext.to_str())
+                .is_some_and(|ext| {
+                    ext.eq_ignore_ascii_case("pem") || ext.eq_ignore_ascii_case("crt")
+                })
+        })
+        .collect();
+    paths.sort();
+
+    let mut certs = Vec::new();
+    let mut files = Vec::new();
+    for path in paths {
+        let file = path
+            .file_name()
+            .map(|name| name.to_string_lossy().to_string())
+            .unwrap_or_default();
+        let loaded = CertificateDer::pem_file_iter(&path)
+            .and_then(|iter| iter.collect::<Result<Vec<_>, _>>())
+            .map_err(|e| e.to_string());
+        match loaded {
+            Ok(loaded) if loaded.is_empty() => files.push(CaCertificateFile {
+                file,
+                certificates: 0,
+                error: Some("文件中没有证书".to_string()),
+            }),
+            Ok(loaded) => {
+                files.push(CaCertificateFile {
+                    file,
+                    certificates: loaded.len(),
+                    error: None,
+                });
+                certs.extend(loaded);
+            }
+            Err(e) => {
+                tracing::warn!("读取根证书 {} 失败: {}", path.display(), e);
+                files.push(CaCertificateFile {
+                    file,
+                    certificates: 0,
+                    error: Some(e),
+                });
+            }
+        }
+    }
+    (certs, files)
+}
+
+/// 内置根证书加上额外根证书
+fn root_store() -> RootCertStore {
+    let mut roots = RootCertStore::empty();
+    roots.extend(webpki_roots::TLS_SERVER_ROOTS.iter().cloned());
+    let (extra, _) = load_extra();
+    let (added, ignored) = roots.add_parsable_certificates(extra);
+    if added > 0 || ignored > 0 {
+        tracing::debug!("已加载额外根证书 {} 个，忽略 {} 个", added, ignored);
+    }
+    roots
+}
+
+/// 上游连接的 TLS 配置；`accept_invalid_certs` 为 true 时不校验服务器证书，
+/// 传入 `client_cert` 时在握手中出示客户端证书
+pub fn client_config(
+    accept_invalid_certs: bool,
+    client_cert: Option<&ClientCertificate>,
+) -> Result<ClientConfig, String> {
+    let provider = Arc::new(rustls::crypto::ring::default_provider());
+    let builder = ClientConfig::builder_with_provider(provider.clone())
+        .with_safe_default_protocol_versions()
+        .map_err(|e| format!("创建 TLS 配置失败: {}", e))?;
+    let builder = if accept_invalid_certs {
+        builder
+            .dangerous()
+            .with_custom_certificate_verifier(Arc::new(AcceptAnyServerCert(provider)))
+    } else {
+        builder.with_root_certificates(root_store())
+    };
+    match client_cert {
+        Some(cert) => {
+            let (chain, key) = crate::client_cert::load(cert)?;
+            builder
+                .with_client_auth_cert(chain, key)
+                .map_err(|e| t_args("errors.invalidClientCert", &[("error", &e)]))
+        }
+        None => Ok(builder.with_no_client_auth()),
+    }
+}
+
+/// 不校验证书链与域名的校验器（仍校验握手签名）
+#[derive(Debug)]
+struct AcceptAnyServerCert(Arc<CryptoProvider>);
+
+impl ServerCertVerifier for AcceptAnyServerCert {
+    fn verify_server_cert(
+        &self,
+        _end_entity: &CertificateDer<'_>,
+        _intermediates: &[CertificateDer<'_>],
+        _server_name: &ServerName<'_>,
+        _ocsp_response: &[u8],
+        _now: UnixTime,
+    ) -> Result<ServerCertVerified, rustls::Error> {
+        Ok(ServerCertVerified::assertion())
+    }
+
+    fn verify_tls12_signature(
+        &self,
+        message: &[u8],
+        cert: &CertificateDer<'_>,
+        dss: &DigitallySignedStruct,
+    ) -> Result<HandshakeSignatureValid, rustls::Error> {
+        verify_tls12_signature(
+            message,
+            cert,
+            dss,
+            &self.0.signature_verification_algorithms,
+        )
+    }
+
+    fn verify_tls13_signature(
+        &self,
+        message: &[u8],
+        cert: &CertificateDer<'_>,
+        dss: &DigitallySignedStruct,
+    ) -> Result<HandshakeSignatureValid, rustls::Error> {
+        verify_tls13_signature(
+            message,
+            cert,
+            dss,
+            &self.0.signature_verification_algorithms,
+        )
+    }
+
+    fn supported_verify_schemes(&self) -> Vec<SignatureScheme> {
+        self.0.signature_verification_algorithms.supported_schemes()
+    }
+}
+
+fn list() -> CaCertificates {
+    CaCertificates {
+        dir: ca_dir().to_string_lossy().to_string(),
+        files: load_extra().1,
+    }
+}
+
+/// 查看额外根证书目录与其中的证书
+#[tauri::command]
+pub async fn get_ca_certificates() -> Result<CaCertificates, CcSwitchError> {
+    Ok(list())
+}
+
+/// 重新加载额外根证书：丢弃现有上游连接，之后的连接使用新的信任列表（同时重新读取客户端证书）
+#[tauri::command]
+pub async fn reload_ca_certificates() -> Result<CaCertificates, CcSwitchError> {
+    crate::upstream::reset();
+    let certificates = list();
+    tracing::info!(
+        "已重新加载额外根证书（{} 个文件）",
+        certificates.files.len()
+    );
+    Ok(certificates)
+}
+
+/// 打开额外根证书目录（不存在时创建）
+#[tauri::command]
+pub async fn open_ca_certificates_folder(handle: tauri::AppHandle) -> Result<bool, CcSwitchError> {
+    let dir = ca_dir();
+    std::fs::create_dir_all(&dir).map_err(|e| format!("创建目录失败: {}", e))?;
+    handle
+        .opener()
+        .open_path(dir.to_string_lossy().to_string(), None::<String>)
+        .map_err(|e| format!("打开文件夹失败: {}", e))?;
+    Ok(true)
+}
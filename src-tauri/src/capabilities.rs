@@ -0,0 +1,66 @@
+//! 供应商接口能力：并非每个中转都实现了 count_tokens、模型列表、批量接口等可选接口。
+//! 能力记录在 `meta.capabilities` 中（接口名 -> 是否支持），可在供应商表单中手动设置；
+//! 未设置时，代理在某个供应商对可选接口返回 404/405 后自动记为不支持，
+//! 之后请求该接口时直接跳过这个供应商，不再浪费重试。
+
+use crate::app_config::AppType;
+use crate::provider::Provider;
+use crate::store::AppState;
+
+/// 请求路径对应的可选接口；核心接口（messages、chat/completions、responses）返回 None，
+/// 这些接口 404 通常是地址配置错误，不应据此标记为不支持
+pub fn endpoint(path: &str) -> Option<&'static str> {
+    let path = path.split('?').next().unwrap_or(path);
+    let path = path.strip_prefix("/v1").unwrap_or(path);
+    if path == "/messages/count_tokens" {
+        Some("count_tokens")
+    } else if path == "/models" || path.starts_with("/models/") {
+        Some("models")
+    } else if path.starts_with("/messages/batches") || path.starts_with("/batches") {
+        Some("batches")
+    } else {
+        None
+    }
+}
+
+/// 供应商是否已知不支持该接口
+pub fn is_unsupported(provider: &Provider, endpoint: &str) -> bool {
+    provider
+        .meta
+        .as_ref()
+        .and_then(|meta| meta.capabilities.get(endpoint))
+        .is_some_and(|supported| !supported)
+}
+
+/// 上游对可选接口返回 404/405 时记为不支持；已手动设置的能力不覆盖
+pub fn learn_unsupported(
+    app_state: &AppState,
+    app_type: &AppType,
+    provider_id: &str,
+    endpoint: &'static str,
+) {
+    let learned = {
+        let Ok(mut config) = app_state.config.lock() else {
+            return;
+        };
+        let Some(provider) = config
+            .get_manager_mut(app_type)
+            .and_then(|manager| manager.providers.get_mut(provider_id))
+        else {
+            return;
+        };
+        let capabilities = &mut provider
+            .meta
+            .get_or_insert_with(Default::default)
+            .capabilities;
+        if capabilities.contains_key(endpoint) {
+            return;
+        }
+        capabilities.insert(endpoint.to_string(), false);
+        provider.name.clone()
+    };
+    tracing::info!("供应商 {} 不支持 {} 接口，之后将跳过", learned, endpoint);
+    if let Err(e) = app_state.save() {
+        tracing::warn!("保存供应商接口能力失败: {}", e);
+    }
+}
@@ -0,0 +1,283 @@
+//! 代理请求录制：开启后在内存中保存最近的请求/响应（请求体与响应体按上限截断，凭证请求头与
+//! 请求体中的 Key 脱敏），并可通过 `replay_request` 用指定供应商重新发送某条请求，
+//! 便于排查"某个中转对这条提示返回异常"一类的问题。录制内容仅保存在进程内。
+
+use axum::{
+    body::{Body, Bytes},
+    http::{HeaderMap, Request, Response},
+};
+use http_body_util::BodyExt;
+use regex::Regex;
+use serde::Serialize;
+use std::collections::VecDeque;
+use std::sync::{Mutex, OnceLock};
+use std::time::Instant;
+use tauri::State;
+
+use crate::app_config::AppType;
+use crate::error::CcSwitchError;
+use crate::i18n::{t, t_args};
+use crate::store::AppState;
+
+/// 需要脱敏的请求头
+const SECRET_HEADERS: &[&str] = &[
+    "authorization",
+    "x-api-key",
+    "proxy-authorization",
+    "cookie",
+];
+
+/// 最近的录制（新的在前）
+static CAPTURES: OnceLock<Mutex<VecDeque<CapturedRequest>>> = OnceLock::new();
+
+fn captures() -> &'static Mutex<VecDeque<CapturedRequest>> {
+    CAPTURES.get_or_init(|| Mutex::new(VecDeque::new()))
+}
+
+/// 一条录制的请求/响应
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CapturedRequest {
+    pub id: String,
+    /// 请求到达时间（毫秒时间戳）
+    pub timestamp: i64,
+    pub app_type: AppType,
+    pub method: String,
+    pub path: String,
+    pub request_headers: Vec<(String, String)>,
+    pub request_body: String,
+    pub request_truncated: bool,
+    pub status: u16,
+    pub response_body: String,
+    pub response_truncated: bool,
+    /// 最终返回响应的供应商（全部失败时为空）
+    pub provider_id: Option<String>,
+    pub provider_name: Option<String>,
+    pub duration_ms: u64,
+}
+
+/// 重放结果
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ReplayResult {
+    pub provider_name: String,
+    pub status: u16,
+    pub body: String,
+    pub truncated: bool,
+    pub duration_ms: u64,
+}
+
+/// 代理在成功响应的 extensions 中记录实际返回响应的供应商（不会发送给客户端）
+#[derive(Debug, Clone)]
+pub struct ServedBy {
+    pub id: String,
+    pub name: String,
+}
+
+/// 录制中的请求：转发前保存请求信息，拿到响应后完成录制
+pub struct Pending {
+    started: Instant,
+    timestamp: i64,
+    app_type: AppType,
+    method: String,
+    path: String,
+    headers: Vec<(String, String)>,
+    body: Bytes,
+}
+
+/// 开启录制时读取请求体并保存请求信息，返回重新组装的请求
+pub async fn begin(req: Request<Body>) -> (Request<Body>, Option<Pending>) {
+    if !crate::settings::get_settings().proxy_capture.enabled {
+        return (req, None);
+    }
+    let (parts, body) = req.into_parts();
+    let body = match body.collect().await {
+        Ok(collected) => collected.to_bytes(),
+        Err(e) => {
+            tracing::warn!("录制时读取请求体失败: {}", e);
+            return (Request::from_parts(parts, Body::empty()), None);
+        }
+    };
+    let user_agent = parts
+        .headers
+        .get("user-agent")
+        .and_then(|v| v.to_str().ok());
+    let pending = Pending {
+        started: Instant::now(),
+        timestamp: chrono::Utc::now().timestamp_millis(),
+        app_type: crate::proxy::detect_app_type_from_user_agent(user_agent),
+        method: parts.method.to_string(),
+        path: parts
+            .uri
+            .path_and_query()
+            .map(|pq| pq.as_str().to_string())
+            .unwrap_or_else(|| "/".to_string()),
+        headers: scrub_headers(&parts.headers),
+        body: body.clone(),
+    };
+    (Request::from_parts(parts, Body::from(body)), Some(pending))
+}
+
+/// 读取响应体完成录制，返回重新组装的响应
+pub async fn finish(pending: Pending, response: Response<Body>) -> Response<Body> {
+    let (parts, body) = response.into_parts();
+    let body = match body.collect().await {
+        Ok(collected) => collected.to_bytes(),
+        Err(e) => {
+            tracing::warn!("录制时读取响应体失败: {}", e);
+            return Response::from_parts(parts, Body::empty());
+        }
+    };
+
+    let settings = crate::settings::get_settings().proxy_capture;
+    let limit = settings.max_body_kb as usize * 1024;
+    let (request_body, request_truncated) = body_text(&pending.body, limit);
+    let (response_body, response_truncated) = match encoded(&parts.headers) {
+        Some(encoding) => (
+            t_args(
+                "proxy.captureEncodedBody",
+                &[("encoding", &encoding), ("size", &body.len())],
+            ),
+            false,
+        ),
+        None => body_text(&body, limit),
+    };
+    let served_by = parts.extensions.get::<ServedBy>();
+    let captured = CapturedRequest {
+        id: uuid::Uuid::new_v4().simple().to_string()[..12].to_string(),
+        timestamp: pending.timestamp,
+        app_type: pending.app_type,
+        method: pending.method,
+        path: pending.path,
+        request_headers: pending.headers,
+        request_body,
+        request_truncated,
+        status: parts.status.as_u16(),
+        response_body,
+        response_truncated,
+        provider_id: served_by.map(|s| s.id.clone()),
+        provider_name: served_by.map(|s| s.name.clone()),
+        duration_ms: pending.started.elapsed().as_millis() as u64,
+    };
+    if let Ok(mut list) = captures().lock() {
+        list.push_front(captured);
+        list.truncate(settings.max_entries as usize);
+    }
+    Response::from_parts(parts, Body::from(body))
+}
+
+/// 请求头脱敏：凭证类请求头只保留末 4 位
+fn scrub_headers(headers: &HeaderMap) -> Vec<(String, String)> {
+    headers
+        .iter()
+        .map(|(name, value)| {
+            let value = value.to_str().unwrap_or_default();
+            let value = if SECRET_HEADERS.contains(&name.as_str()) {
+                crate::redact::mask(value)
+            } else {
+                value.to_string()
+            };
+            (name.to_string(), value)
+        })
+        .collect()
+}
+
+/// 响应体的压缩编码（压缩内容无法以文本展示）
+fn encoded(headers: &HeaderMap) -> Option<String> {
+    headers
+        .get(axum::http::header::CONTENT_ENCODING)
+        .and_then(|v| v.to_str().ok())
+        .filter(|encoding| !encoding.eq_ignore_ascii_case("identity"))
+        .map(str::to_string)
+}
+
+/// 按上限截断为文本，并对其中形如 `sk-...` 的 Key 脱敏
+fn body_text(body: &[u8], limit: usize) -> (String, bool) {
+    static KEY_PATTERN: OnceLock<Regex> = OnceLock::new();
+    let pattern = KEY_PATTERN.get_or_init(|| Regex::new(r"sk-[A-Za-z0-9_\-]{16,}").unwrap());
+    let truncated = body.len() > limit;
+    let text = String::from_utf8_lossy(&body[..body.len().min(limit)]);
+    let text = pattern.replace_all(&text, |caps: &regex::Captures| {
+        crate::redact::mask(&caps[0])
+    });
+    (text.into_owned(), truncated)
+}
+
+/// 获取录制的请求（新的在前）
+#[tauri::command]
+pub async fn get_captured_requests() -> Result<Vec<CapturedRequest>, CcSwitchError> {
+    let list = captures()
+        .lock()
+        .map_err(|e| t_args("errors.lockFailed", &[("error", &e)]))?;
+    Ok(list.iter().cloned().collect())
+}
+
+/// 清空录制
+#[tauri::command]
+pub async fn clear_captured_requests() -> Result<bool, CcSwitchError> {
+    captures()
+        .lock()
+        .map_err(|e| t_args("errors.lockFailed", &[("error", &e)]))?
+        .clear();
+    Ok(true)
+}
+
+/// 用指定供应商重新发送一条录制的请求（不重试、不切换供应商）
+#[tauri::command]
+pub async fn replay_request(
+    state: State<'_, AppState>,
+    id: String,
+    provider_id: String,
+) -> Result<ReplayResult, CcSwitchError> {
+    let captured = captures()
+        .lock()
+        .map_err(|e| t_args("errors.lockFailed", &[("error", &e)]))?
+        .iter()
+        .find(|c| c.id == id)
+        .cloned()
+        .ok_or_else(|| t_args("errors.captureNotFound", &[("id", &id)]))?;
+    if captured.request_truncated {
+        return Err(t("errors.captureTruncated").into());
+    }
+
+    let provider = {
+        let config = state
+            .config
+            .lock()
+            .map_err(|e| t_args("errors.lockFailed", &[("error", &e)]))?;
+        let manager = config.get_manager(&captured.app_type).ok_or_else(|| {
+            t_args(
+                "errors.appTypeNotFound",
+                &[("app", &captured.app_type.as_str())],
+            )
+        })?;
+        manager
+            .providers
+            .get(&provider_id)
+            .cloned()
+            .ok_or_else(|| t_args("errors.providerNotFound", &[("id", &provider_id)]))?
+    };
+
+    let started = Instant::now();
+    let (status, body) = crate::proxy::send_once(
+        &captured.app_type,
+        &provider,
+        &captured.method,
+        &captured.path,
+        &captured.request_headers,
+        Bytes::from(captured.request_body),
+    )
+    .await
+    .map_err(|e| t_args("errors.replayFailed", &[("error", &e)]))?;
+    tracing::info!(provider = %provider.name, "重放录制的请求 {}: {}", id, status);
+
+    let limit = crate::settings::get_settings().proxy_capture.max_body_kb as usize * 1024;
+    let (body, truncated) = body_text(&body, limit);
+    Ok(ReplayResult {
+        provider_name: provider.name,
+        status,
+        body,
+        truncated,
+        duration_ms: started.elapsed().as_millis() as u64,
+    })
+}
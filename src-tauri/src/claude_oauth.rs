@@ -0,0 +1,269 @@
+//! Claude Pro/Max 订阅账号切换：管理 Claude Code 的 OAuth 凭证。
+//! - 凭证：`<配置目录>/.credentials.json`；macOS 默认目录下存放在钥匙串 `Claude Code-credentials`
+//! - 账号信息：`~/.claude.json`（自定义配置目录时为 `<目录>/.claude.json`）中的 `oauthAccount`
+//!
+//! 切换时先把当前账号刷新后的令牌回填到其供应商，再备份并换入目标账号的令牌。
+
+use serde_json::Value;
+use std::path::{Path, PathBuf};
+use tauri::State;
+
+use crate::app_config::AppType;
+use crate::audit::AuditAction;
+use crate::config::{atomic_write, get_live_backup_dir, read_json_file, write_json_file};
+use crate::error::CcSwitchError;
+use crate::i18n::{t, t_args};
+use crate::provider::{CredentialKind, OAuthCredential, Provider, ProviderMeta};
+use crate::store::AppState;
+
+const CREDENTIALS_FILE: &str = ".credentials.json";
+const OAUTH_ACCOUNT_KEY: &str = "oauthAccount";
+
+#[cfg(target_os = "macos")]
+const KEYCHAIN_SERVICE: &str = "Claude Code-credentials";
+
+fn default_claude_dir() -> Option<PathBuf> {
+    dirs::home_dir().map(|home| home.join(".claude"))
+}
+
+/// 是否为默认的 `~/.claude` 目录（决定 .claude.json 位置与是否使用钥匙串）
+fn is_default_dir(dir: &Path) -> bool {
+    default_claude_dir().is_some_and(|d| d == dir)
+}
+
+pub fn credentials_path(dir: &Path) -> PathBuf {
+    dir.join(CREDENTIALS_FILE)
+}
+
+fn user_config_path(dir: &Path) -> PathBuf {
+    match dirs::home_dir() {
+        Some(home) if is_default_dir(dir) => home.join(".claude.json"),
+        _ => dir.join(".claude.json"),
+    }
+}
+
+/// 仅允许当前用户读写凭证文件
+fn restrict_permissions(path: &Path) {
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::PermissionsExt;
+        if let Err(e) = std::fs::set_permissions(path, std::fs::Permissions::from_mode(0o600)) {
+            tracing::warn!("设置 {} 权限失败: {}", path.display(), e);
+        }
+    }
+    #[cfg(not(unix))]
+    let _ = path;
+}
+
+#[cfg(target_os = "macos")]
+fn read_keychain() -> Result<Option<Value>, String> {
+    let output = std::process::Command::new("security")
+        .args(["find-generic-password", "-s", KEYCHAIN_SERVICE, "-w"])
+        .output()
+        .map_err(|e| format!("读取钥匙串失败: {}", e))?;
+    if !output.status.success() {
+        return Ok(None);
+    }
+    let text = String::from_utf8_lossy(&output.stdout);
+    serde_json::from_str(text.trim())
+        .map(Some)
+        .map_err(|e| format!("解析钥匙串中的凭证失败: {}", e))
+}
+
+#[cfg(target_os = "macos")]
+fn write_keychain(credentials: &Value) -> Result<(), String> {
+    let account = std::env::var("USER").unwrap_or_default();
+    let status = std::process::Command::new("security")
+        .args([
+            "add-generic-password",
+            "-U",
+            "-a",
+            &account,
+            "-s",
+            KEYCHAIN_SERVICE,
+            "-w",
+            &credentials.to_string(),
+        ])
+        .status()
+        .map_err(|e| format!("写入钥匙串失败: {}", e))?;
+    if !status.success() {
+        return Err("写入钥匙串失败".to_string());
+    }
+    Ok(())
+}
+
+fn read_credentials(dir: &Path) -> Result<Option<Value>, String> {
+    #[cfg(target_os = "macos")]
+    if is_default_dir(dir) {
+        if let Some(value) = read_keychain()? {
+            return Ok(Some(value));
+        }
+    }
+    let path = credentials_path(dir);
+    if !path.exists() {
+        return Ok(None);
+    }
+    read_json_file(&path).map(Some)
+}
+
+fn write_credentials(dir: &Path, credentials: &Value) -> Result<(), String> {
+    #[cfg(target_os = "macos")]
+    if is_default_dir(dir) {
+        return write_keychain(credentials);
+    }
+    let path = credentials_path(dir);
+    write_json_file(&path, credentials)?;
+    restrict_permissions(&path);
+    Ok(())
+}
+
+fn read_account(dir: &Path) -> Option<Value> {
+    let path = user_config_path(dir);
+    if !path.exists() {
+        return None;
+    }
+    read_json_file::<Value>(&path)
+        .ok()?
+        .get(OAUTH_ACCOUNT_KEY)
+        .cloned()
+}
+
+/// 仅替换 .claude.json 中的 oauthAccount，保留其余内容
+fn write_account(dir: &Path, account: &Value) -> Result<(), String> {
+    let path = user_config_path(dir);
+    let mut root = if path.exists() {
+        read_json_file::<Value>(&path)?
+    } else {
+        Value::Object(Default::default())
+    };
+    let Some(obj) = root.as_object_mut() else {
+        return Err(format!("{} 不是 JSON 对象", path.display()));
+    };
+    obj.insert(OAUTH_ACCOUNT_KEY.to_string(), account.clone());
+    write_json_file(&path, &root)
+}
+
+/// 读取 live 中当前登录的订阅账号凭证
+pub fn read_live(dir: &Path) -> Result<Option<OAuthCredential>, String> {
+    Ok(read_credentials(dir)?.map(|credentials| OAuthCredential {
+        credentials,
+        account: read_account(dir),
+        updated_at: Some(chrono::Utc::now().timestamp_millis()),
+    }))
+}
+
+/// 把 live 中（可能已被 Claude Code 刷新的）令牌回填到当前供应商；
+/// live 账号与供应商记录的账号不一致时（用户在外部重新登录了其他账号）跳过
+pub fn backfill(provider: &mut Provider, dir: &Path) {
+    let live = match read_live(dir) {
+        Ok(Some(live)) => live,
+        Ok(None) => return,
+        Err(e) => {
+            tracing::warn!("读取订阅账号凭证失败，跳过回填: {}", e);
+            return;
+        }
+    };
+    let Some(meta) = provider.meta.as_mut() else {
+        return;
+    };
+    let expected = meta.oauth.as_ref().and_then(|o| o.email());
+    if expected.is_some() && expected != live.email() {
+        tracing::warn!(
+            "当前登录账号与供应商 {} 记录的账号不一致，跳过令牌回填",
+            provider.name
+        );
+        return;
+    }
+    meta.oauth = Some(live);
+}
+
+/// 换入目标账号的令牌：写入前备份当前凭证到 live-backup 目录
+pub fn apply(provider: &Provider, dir: &Path) -> Result<(), String> {
+    let oauth = provider
+        .meta
+        .as_ref()
+        .and_then(|m| m.oauth.as_ref())
+        .ok_or_else(|| t_args("errors.oauthCredentialMissing", &[("name", &provider.name)]))?;
+
+    if let Some(previous) = read_credentials(dir)? {
+        let backup = get_live_backup_dir().join("claude-credentials.json");
+        let bytes = serde_json::to_vec_pretty(&previous)
+            .map_err(|e| format!("序列化凭证备份失败: {}", e))?;
+        match atomic_write(&backup, &bytes) {
+            Ok(()) => restrict_permissions(&backup),
+            Err(e) => tracing::warn!("备份订阅账号凭证失败: {}", e),
+        }
+    }
+
+    write_credentials(dir, &oauth.credentials)?;
+    if let Some(account) = &oauth.account {
+        if let Err(e) = write_account(dir, account) {
+            tracing::warn!("更新 .claude.json 中的账号信息失败: {}", e);
+        }
+    }
+    tracing::info!("已换入订阅账号凭证: {}", provider.name);
+    Ok(())
+}
+
+/// 将 Claude Code 当前登录的订阅账号导入为供应商；同一账号已存在时只更新令牌
+#[tauri::command]
+pub async fn import_claude_oauth_account(
+    state: State<'_, AppState>,
+) -> Result<Provider, CcSwitchError> {
+    let (provider, action) = {
+        let mut config = state
+            .config
+            .lock()
+            .map_err(|e| t_args("errors.lockFailed", &[("error", &e)]))?;
+        let dir = crate::live_config::current_live_dir(&config, &AppType::Claude);
+        let live = read_live(&dir)?.ok_or_else(|| t("errors.oauthNotLoggedIn"))?;
+
+        let manager = config
+            .get_manager_mut(&AppType::Claude)
+            .ok_or_else(|| t_args("errors.appTypeNotFound", &[("app", &"claude")]))?;
+        let existing = manager.providers.values_mut().find(|p| {
+            p.credential_kind() == CredentialKind::ClaudeOauth
+                && p.meta
+                    .as_ref()
+                    .and_then(|m| m.oauth.as_ref())
+                    .and_then(|o| o.email())
+                    .is_some_and(|email| Some(email) == live.email())
+        });
+
+        match existing {
+            Some(provider) => {
+                if let Some(meta) = provider.meta.as_mut() {
+                    meta.oauth = Some(live);
+                }
+                (provider.clone(), AuditAction::KeyChanged)
+            }
+            None => {
+                let name = live
+                    .email()
+                    .map(|email| format!("Claude ({})", email))
+                    .unwrap_or_else(|| "Claude 订阅账号".to_string());
+                let mut provider = Provider::with_id(
+                    uuid::Uuid::new_v4().to_string(),
+                    name,
+                    serde_json::json!({ "env": {} }),
+                    Some("https://claude.ai".to_string()),
+                );
+                provider.category = Some("official".to_string());
+                provider.created_at = Some(chrono::Utc::now().timestamp_millis());
+                provider.meta = Some(ProviderMeta {
+                    credential_kind: Some(CredentialKind::ClaudeOauth),
+                    oauth: Some(live),
+                    ..Default::default()
+                });
+                manager
+                    .providers
+                    .insert(provider.id.clone(), provider.clone());
+                (provider, AuditAction::ProviderAdded)
+            }
+        }
+    };
+    state.save_now()?;
+    tracing::info!("已导入订阅账号: {}", provider.name);
+    crate::audit::record(action, Some(&AppType::Claude), Some(&provider.name), None);
+    Ok(crate::redact::redact_provider(&AppType::Claude, &provider))
+}
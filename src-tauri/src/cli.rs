@@ -0,0 +1,270 @@
+//! 命令行辅助子命令：
+//! - `cc-switch completions <bash|zsh|fish|powershell>`：输出 shell 补全脚本
+//! - `cc-switch prompt [claude|codex] [--shell zsh|bash] [--no-color] [--file <path>]`：
+//!   输出当前供应商与剩余额度的简短彩色片段，供 starship / oh-my-zsh 等提示符嵌入
+//!
+//! `prompt` 只读取 `~/.cc-switch/current.json`（见 `current_status`），不访问网络也不连接正在运行的应用，
+//! 适合在每次渲染提示符时调用；状态文件不存在时输出为空。
+//! 在设置中自定义了配置目录时，命令行无法读取该设置，需要用 `--file` 指定状态文件。
+//!
+//! 示例：
+//! - bash：`source <(cc-switch completions bash)`；zsh：`cc-switch completions zsh > "${fpath[1]}/_cc-switch"`
+//! - oh-my-zsh：`RPROMPT='$(cc-switch prompt --shell zsh)'`（需 `setopt prompt_subst`）
+//! - starship：`[custom.ccswitch]` 中设置 `command = "cc-switch prompt"`、`when = true`
+
+use std::path::PathBuf;
+
+use crate::current_status::{CurrentProvider, CurrentStatus};
+
+/// 输出补全脚本的子命令名
+pub const COMPLETIONS_SUBCOMMAND: &str = "completions";
+
+/// 输出提示符片段的子命令名
+pub const PROMPT_SUBCOMMAND: &str = "prompt";
+
+const BASH_COMPLETION: &str = r#"_cc_switch() {
+    local cur prev
+    cur="${COMP_WORDS[COMP_CWORD]}"
+    prev="${COMP_WORDS[COMP_CWORD-1]}"
+    if [ "$COMP_CWORD" -eq 1 ]; then
+        COMPREPLY=($(compgen -W "mcp reapply completions prompt" -- "$cur"))
+        return
+    fi
+    case "${COMP_WORDS[1]}" in
+        reapply)
+            COMPREPLY=($(compgen -W "claude codex" -- "$cur"))
+            ;;
+        completions)
+            [ "$COMP_CWORD" -eq 2 ] && COMPREPLY=($(compgen -W "bash zsh fish powershell" -- "$cur"))
+            ;;
+        prompt)
+            case "$prev" in
+                --shell) COMPREPLY=($(compgen -W "zsh bash" -- "$cur")) ;;
+                --file) COMPREPLY=($(compgen -f -- "$cur")) ;;
+                *) COMPREPLY=($(compgen -W "claude codex --shell --no-color --file" -- "$cur")) ;;
+            esac
+            ;;
+    esac
+}
+complete -F _cc_switch cc-switch
+"#;
+
+const ZSH_COMPLETION: &str = r#"#compdef cc-switch
+
+_cc_switch() {
+  local -a subcommands
+  subcommands=(
+    'mcp:以 MCP stdio 服务运行'
+    'reapply:请求正在运行的应用重新写入当前配置'
+    'completions:输出 shell 补全脚本'
+    'prompt:输出当前供应商与剩余额度的提示符片段'
+  )
+  if (( CURRENT == 2 )); then
+    _describe 'command' subcommands
+    return
+  fi
+  case $words[2] in
+    reapply)
+      _values -w 'app' claude codex
+      ;;
+    completions)
+      (( CURRENT == 3 )) && _values 'shell' bash zsh fish powershell
+      ;;
+    prompt)
+      shift words
+      (( CURRENT-- ))
+      _arguments \
+        '--shell[按 shell 包裹颜色转义]:shell:(zsh bash)' \
+        '--no-color[不输出颜色]' \
+        '--file[状态文件路径]:file:_files' \
+        '1::app:(claude codex)'
+      ;;
+  esac
+}
+
+if [ "$funcstack[1]" = "_cc_switch" ]; then
+  _cc_switch "$@"
+else
+  compdef _cc_switch cc-switch
+fi
+"#;
+
+const FISH_COMPLETION: &str = r#"complete -c cc-switch -f
+complete -c cc-switch -n __fish_use_subcommand -a mcp -d '以 MCP stdio 服务运行'
+complete -c cc-switch -n __fish_use_subcommand -a reapply -d '请求正在运行的应用重新写入当前配置'
+complete -c cc-switch -n __fish_use_subcommand -a completions -d '输出 shell 补全脚本'
+complete -c cc-switch -n __fish_use_subcommand -a prompt -d '输出当前供应商与剩余额度的提示符片段'
+complete -c cc-switch -n '__fish_seen_subcommand_from reapply prompt' -a 'claude codex'
+complete -c cc-switch -n '__fish_seen_subcommand_from completions' -a 'bash zsh fish powershell'
+complete -c cc-switch -n '__fish_seen_subcommand_from prompt' -l shell -x -a 'zsh bash' -d '按 shell 包裹颜色转义'
+complete -c cc-switch -n '__fish_seen_subcommand_from prompt' -l no-color -d '不输出颜色'
+complete -c cc-switch -n '__fish_seen_subcommand_from prompt' -l file -r -F -d '状态文件路径'
+"#;
+
+const POWERSHELL_COMPLETION: &str = r#"Register-ArgumentCompleter -Native -CommandName 'cc-switch', 'cc-switch.exe' -ScriptBlock {
+    param($wordToComplete, $commandAst, $cursorPosition)
+    $words = @($commandAst.CommandElements |
+        Where-Object { $_.Extent.EndOffset -lt $cursorPosition } |
+        ForEach-Object { $_.ToString() })
+    $candidates = if ($words.Count -le 1) {
+        'mcp', 'reapply', 'completions', 'prompt'
+    } else {
+        switch ($words[1]) {
+            'reapply' { 'claude', 'codex' }
+            'completions' { if ($words.Count -eq 2) { 'bash', 'zsh', 'fish', 'powershell' } }
+            'prompt' {
+                if ($words[-1] -eq '--shell') { 'zsh', 'bash' }
+                elseif ($words[-1] -ne '--file') { 'claude', 'codex', '--shell', '--no-color', '--file' }
+            }
+        }
+    }
+    $candidates | Where-Object { $_ -like "$wordToComplete*" } | ForEach-Object {
+        [System.Management.Automation.CompletionResult]::new($_, $_, 'ParameterValue', $_)
+    }
+}
+"#;
+
+/// ANSI 颜色
+const CYAN: &str = "36";
+const GREEN: &str = "32";
+const RED: &str = "31";
+
+/// 颜色转义的包裹方式：提示符需要知道哪些字符不占宽度，否则光标位置会错乱
+#[derive(Clone, Copy)]
+enum PromptShell {
+    Plain,
+    Zsh,
+    Bash,
+}
+
+struct PromptOptions {
+    app: Option<String>,
+    shell: PromptShell,
+    color: bool,
+    file: Option<PathBuf>,
+}
+
+/// 处理补全与提示符子命令，返回进程退出码；命令行不是这些子命令时返回 None
+pub fn run() -> Option<i32> {
+    let mut args = std::env::args().skip(1);
+    match args.next().as_deref() {
+        Some(COMPLETIONS_SUBCOMMAND) => Some(completions(args.next().as_deref())),
+        Some(PROMPT_SUBCOMMAND) => Some(match parse_prompt_options(args) {
+            Ok(options) => {
+                print!("{}", prompt(&options));
+                0
+            }
+            Err(e) => {
+                eprintln!("{}", e);
+                2
+            }
+        }),
+        _ => None,
+    }
+}
+
+fn completions(shell: Option<&str>) -> i32 {
+    let script = match shell {
+        Some("bash") => BASH_COMPLETION,
+        Some("zsh") => ZSH_COMPLETION,
+        Some("fish") => FISH_COMPLETION,
+        Some("powershell") | Some("pwsh") => POWERSHELL_COMPLETION,
+        _ => {
+            eprintln!("用法: cc-switch completions <bash|zsh|fish|powershell>");
+            return 2;
+        }
+    };
+    print!("{}", script);
+    0
+}
+
+fn parse_prompt_options(mut args: impl Iterator<Item = String>) -> Result<PromptOptions, String> {
+    let mut options = PromptOptions {
+        app: None,
+        shell: PromptShell::Plain,
+        // 遵循 https://no-color.org 约定
+        color: std::env::var_os("NO_COLOR").is_none_or(|value| value.is_empty()),
+        file: None,
+    };
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "--no-color" => options.color = false,
+            "--shell" => {
+                options.shell = match args.next().as_deref() {
+                    Some("zsh") => PromptShell::Zsh,
+                    Some("bash") => PromptShell::Bash,
+                    other => return Err(format!("不支持的 shell: {}", other.unwrap_or(""))),
+                }
+            }
+            "--file" => {
+                let path = args.next().ok_or("--file 需要指定路径")?;
+                options.file = Some(PathBuf::from(path));
+            }
+            "claude" | "codex" => options.app = Some(arg),
+            _ => {
+                return Err(format!(
+                    "未知参数: {}\n用法: cc-switch prompt [claude|codex] [--shell zsh|bash] [--no-color] [--file <path>]",
+                    arg
+                ))
+            }
+        }
+    }
+    Ok(options)
+}
+
+/// 读取状态文件；未指定应用时取最近一次切换的应用
+fn current_provider(options: &PromptOptions) -> Option<CurrentProvider> {
+    let status: CurrentStatus = match &options.file {
+        Some(path) => crate::config::read_json_file(path).ok()?,
+        None => crate::current_status::read()?,
+    };
+    match &options.app {
+        Some(app) => status.apps.get(app).cloned(),
+        None => status.last,
+    }
+}
+
+/// 去掉多余的小数位：12.50 -> 12.5，3.00 -> 3
+fn format_amount(value: f64) -> String {
+    let text = format!("{:.2}", value);
+    text.trim_end_matches('0').trim_end_matches('.').to_string()
+}
+
+fn paint(text: &str, color: &str, options: &PromptOptions) -> String {
+    if !options.color {
+        return text.to_string();
+    }
+    let (start, end) = (format!("\x1b[{}m", color), "\x1b[0m");
+    match options.shell {
+        PromptShell::Plain => format!("{}{}{}", start, text, end),
+        PromptShell::Zsh => format!("%{{{}%}}{}%{{{}%}}", start, text, end),
+        // 命令替换的输出中 \[ \] 不生效，需使用 readline 的 \001 \002
+        PromptShell::Bash => format!("\x01{}\x02{}\x01{}\x02", start, text, end),
+    }
+}
+
+/// zsh 提示符中 % 为转义字符
+fn escape(text: &str, options: &PromptOptions) -> String {
+    match options.shell {
+        PromptShell::Zsh => text.replace('%', "%%"),
+        _ => text.to_string(),
+    }
+}
+
+fn prompt(options: &PromptOptions) -> String {
+    let Some(current) = current_provider(options) else {
+        return String::new();
+    };
+    let mut segment = paint(&escape(&current.provider_name, options), CYAN, options);
+    if let Some(balance) = current.balance {
+        let amount = format!(
+            "{}{}",
+            format_amount(balance.remaining),
+            balance.unit.as_deref().unwrap_or("")
+        );
+        let color = if balance.remaining > 0.0 { GREEN } else { RED };
+        segment.push_str(" · ");
+        segment.push_str(&paint(&escape(&amount, options), color, options));
+    }
+    segment
+}
@@ -0,0 +1,76 @@
+//! mTLS 客户端证书：部分企业网关要求客户端出示证书。证书与私钥可以是 PEM 文件路径，
+//! 也可以是 `keychain:<服务名>` 形式的钥匙串引用（仅 macOS，读取该服务名下的通用密码，内容为 PEM 文本），
+//! 用于代理转发与该供应商的用量脚本请求。
+
+use rustls::pki_types::pem::PemObject;
+use rustls::pki_types::{CertificateDer, PrivateKeyDer};
+
+use crate::i18n::t_args;
+use crate::provider::ClientCertificate;
+
+/// 钥匙串引用前缀
+const KEYCHAIN_PREFIX: &str = "keychain:";
+
+fn invalid(error: impl std::fmt::Display) -> String {
+    t_args("errors.invalidClientCert", &[("error", &error)])
+}
+
+#[cfg(target_os = "macos")]
+fn read_keychain(service: &str) -> Result<Vec<u8>, String> {
+    let output = std::process::Command::new("security")
+        .args(["find-generic-password", "-s", service, "-w"])
+        .output()
+        .map_err(|e| format!("读取钥匙串失败: {}", e))?;
+    if !output.status.success() {
+        return Err(format!("钥匙串中没有服务名为 {} 的条目", service));
+    }
+    let text = String::from_utf8_lossy(&output.stdout).trim().to_string();
+    // 含换行等不可打印字符的密码会以十六进制输出
+    if !text.contains("-----BEGIN") && text.len() % 2 == 0 {
+        let decoded: Option<Vec<u8>> = (0..text.len())
+            .step_by(2)
+            .map(|i| u8::from_str_radix(&text[i..i + 2], 16).ok())
+            .collect();
+        if let Some(decoded) = decoded {
+            return Ok(decoded);
+        }
+    }
+    Ok(text.into_bytes())
+}
+
+#[cfg(not(target_os = "macos"))]
+fn read_keychain(_service: &str) -> Result<Vec<u8>, String> {
+    Err("钥匙串引用仅支持 macOS".to_string())
+}
+
+/// 读取证书或私钥的 PEM 内容
+fn read_source(source: &str) -> Result<Vec<u8>, String> {
+    let source = source.trim();
+    match source.strip_prefix(KEYCHAIN_PREFIX) {
+        Some(service) => read_keychain(service.trim()),
+        None => std::fs::read(source).map_err(|e| format!("读取 {} 失败: {}", source, e)),
+    }
+}
+
+/// 加载证书链与私钥
+pub fn load(
+    cert: &ClientCertificate,
+) -> Result<(Vec<CertificateDer<'static>>, PrivateKeyDer<'static>), String> {
+    let chain = CertificateDer::pem_slice_iter(&read_source(&cert.cert).map_err(invalid)?)
+        .collect::<Result<Vec<_>, _>>()
+        .map_err(invalid)?;
+    if chain.is_empty() {
+        return Err(invalid("证书文件中没有证书"));
+    }
+    let key = PrivateKeyDer::from_pem_slice(&read_source(&cert.key).map_err(invalid)?)
+        .map_err(invalid)?;
+    Ok((chain, key))
+}
+
+/// 私钥与证书合并后的 PEM（用量脚本的 HTTP 客户端使用）
+pub fn identity_pem(cert: &ClientCertificate) -> Result<Vec<u8>, String> {
+    let mut pem = read_source(&cert.key).map_err(invalid)?;
+    pem.push(b'\n');
+    pem.extend(read_source(&cert.cert).map_err(invalid)?);
+    Ok(pem)
+}
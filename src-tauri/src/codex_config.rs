@@ -4,6 +4,7 @@ use std::path::PathBuf;
 use crate::config::{
     atomic_write, delete_file, sanitize_provider_name, write_json_file, write_text_file,
 };
+use crate::provider::{CredentialKind, Provider};
 use serde_json::Value;
 use std::fs;
 use std::path::Path;
@@ -54,10 +55,52 @@ pub fn delete_codex_provider_config(provider_id: &str, provider_name: &str) -> R
 
 //（移除未使用的备份/保存/恢复/导入函数，避免 dead_code 告警）
 
-/// 原子写 Codex 的 `auth.json` 与 `config.toml`，在第二步失败时回滚第一步
-pub fn write_codex_live_atomic(auth: &Value, config_text_opt: Option<&str>) -> Result<(), String> {
-    let auth_path = get_codex_auth_path();
-    let config_path = get_codex_config_path();
+/// Codex live 文件快照（用于多文件事务回滚）
+pub struct CodexLiveSnapshot {
+    dir: PathBuf,
+    auth: Option<Vec<u8>>,
+    config: Option<Vec<u8>>,
+}
+
+impl CodexLiveSnapshot {
+    /// 记录指定 Codex 目录下 `auth.json` 与 `config.toml` 的当前内容（不存在记为 None）
+    pub fn capture_in(dir: &Path) -> Result<Self, String> {
+        let read = |path: &Path| -> Result<Option<Vec<u8>>, String> {
+            if path.exists() {
+                fs::read(path)
+                    .map(Some)
+                    .map_err(|e| format!("读取 {} 失败: {}", path.display(), e))
+            } else {
+                Ok(None)
+            }
+        };
+        Ok(Self {
+            dir: dir.to_path_buf(),
+            auth: read(&dir.join("auth.json"))?,
+            config: read(&dir.join("config.toml"))?,
+        })
+    }
+
+    /// 恢复到快照时的状态（原本不存在的文件会被删除）
+    pub fn restore(&self) -> Result<(), String> {
+        let restore = |path: &Path, bytes: &Option<Vec<u8>>| match bytes {
+            Some(bytes) => atomic_write(path, bytes),
+            None => delete_file(path),
+        };
+        let auth_result = restore(&self.dir.join("auth.json"), &self.auth);
+        let config_result = restore(&self.dir.join("config.toml"), &self.config);
+        auth_result.and(config_result)
+    }
+}
+
+/// 原子写指定 Codex 目录下的 `auth.json` 与 `config.toml`，在第二步失败时回滚第一步
+pub fn write_codex_live_atomic_in(
+    dir: &Path,
+    auth: &Value,
+    config_text_opt: Option<&str>,
+) -> Result<(), String> {
+    let auth_path = dir.join("auth.json");
+    let config_path = dir.join("config.toml");
 
     if let Some(parent) = auth_path.parent() {
         std::fs::create_dir_all(parent)
@@ -156,3 +199,45 @@ pub fn read_and_validate_config_from_path(path: &Path) -> Result<String, String>
     validate_config_toml(&s)?;
     Ok(s)
 }
+
+/// auth.json 是否来自 ChatGPT 登录（`codex login`）：包含带 refresh_token 的 `tokens`
+pub fn is_chatgpt_auth(auth: &Value) -> bool {
+    auth.get("tokens")
+        .and_then(|t| t.get("refresh_token"))
+        .and_then(|v| v.as_str())
+        .is_some_and(|s| !s.is_empty())
+}
+
+/// ChatGPT 登录对应的账号 ID
+pub fn chatgpt_account_id(auth: &Value) -> Option<&str> {
+    auth.get("tokens")?.get("account_id")?.as_str()
+}
+
+/// 按 settings_config.auth 识别 Codex 供应商的凭证类型并记录到 meta
+pub fn sync_credential_kind(provider: &mut Provider) {
+    let oauth = provider
+        .settings_config
+        .get("auth")
+        .is_some_and(is_chatgpt_auth);
+    if oauth {
+        provider
+            .meta
+            .get_or_insert_with(Default::default)
+            .credential_kind = Some(CredentialKind::CodexOauth);
+    } else if provider.credential_kind() == CredentialKind::CodexOauth {
+        if let Some(meta) = provider.meta.as_mut() {
+            meta.credential_kind = None;
+        }
+    }
+}
+
+/// live 中的 ChatGPT 登录是否可回填到该供应商：
+/// 供应商记录了账号 ID 而 live 已是其他账号（用户在外部重新登录）时不可回填
+pub fn can_backfill_auth(stored_settings: &Value, live_settings: &Value) -> bool {
+    let stored = stored_settings.get("auth").and_then(chatgpt_account_id);
+    let live = live_settings.get("auth").and_then(chatgpt_account_id);
+    match stored {
+        Some(stored) => live == Some(stored),
+        None => true,
+    }
+}
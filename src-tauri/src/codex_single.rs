@@ -0,0 +1,163 @@
+//! Codex 单文件模式：在 config.toml 中为每个供应商维护独立的 `[model_providers.<id>]` 条目，
+//! 切换时只改写顶层 `model_provider`，文件中的其余内容（包括用户手动添加的）保持不变。
+
+use serde_json::Value;
+use std::path::Path;
+use toml_edit::{DocumentMut, Item, Table};
+
+use crate::app_config::AppType;
+use crate::provider::{Provider, ProviderManager};
+use crate::settings::OperationMode;
+use crate::store::AppState;
+
+/// 是否启用单文件模式
+pub fn is_enabled() -> bool {
+    crate::settings::get_settings().codex_single_config
+}
+
+fn config_text(settings: &Value) -> &str {
+    settings
+        .get("config")
+        .and_then(|v| v.as_str())
+        .unwrap_or("")
+}
+
+/// 供应商配置中 model_provider 指向的 model_providers 条目；官方供应商没有该条目
+fn provider_entry(settings: &Value) -> Option<Item> {
+    let doc = config_text(settings).parse::<DocumentMut>().ok()?;
+    let name = doc.get("model_provider")?.as_str()?;
+    let entry = doc.get("model_providers")?.get(name)?.clone();
+    entry.is_table_like().then_some(entry)
+}
+
+/// 取出（必要时创建）顶层 model_providers 表，内联写法会转换为标准表
+fn providers_table(doc: &mut DocumentMut) -> &mut Table {
+    let table = doc
+        .remove("model_providers")
+        .and_then(|item| item.into_table().ok())
+        .unwrap_or_else(|| {
+            let mut table = Table::new();
+            table.set_implicit(true);
+            table
+        });
+    doc.insert("model_providers", Item::Table(table));
+    doc["model_providers"]
+        .as_table_mut()
+        .expect("model_providers 已写入为表")
+}
+
+/// 生成单文件模式下写入 dir 的配置：以现有 config.toml 为底稿（不存在时使用目标供应商的配置），
+/// 写入全部供应商的条目并将 model_provider 指向目标供应商；settings 为目标供应商合并片段后的配置
+pub fn render(manager: &ProviderManager, target: &Provider, settings: Value, dir: &Path) -> Value {
+    let live_text = crate::codex_config::read_config_text_from_path(&dir.join("config.toml"))
+        .unwrap_or_default();
+    let base = if live_text.trim().is_empty() {
+        config_text(&settings).to_string()
+    } else {
+        live_text
+    };
+    let Ok(mut doc) = base.parse::<DocumentMut>() else {
+        tracing::warn!("现有 config.toml 无法解析，单文件模式改为整体写入");
+        return settings;
+    };
+
+    let mut ids: Vec<&String> = manager.providers.keys().collect();
+    ids.sort();
+    let table = providers_table(&mut doc);
+    for id in ids {
+        let source = if *id == target.id {
+            &settings
+        } else {
+            &manager.providers[id].settings_config
+        };
+        if let Some(entry) = provider_entry(source) {
+            table.insert(id, entry);
+        }
+    }
+    if table.is_empty() {
+        doc.remove("model_providers");
+    }
+
+    match provider_entry(&settings) {
+        Some(_) => {
+            doc.insert("model_provider", toml_edit::value(target.id.as_str()));
+        }
+        None => {
+            doc.remove("model_provider");
+        }
+    }
+
+    let mut result = settings;
+    if let Some(obj) = result.as_object_mut() {
+        obj.insert("config".to_string(), Value::String(doc.to_string()));
+    }
+    result
+}
+
+/// 单文件模式下从 live 回填：auth 取 live，config 保留供应商原有配置，
+/// 仅以 live 中 `[model_providers.<id>]` 的内容更新其自身条目
+pub fn extract(provider: &Provider, live: Value) -> Value {
+    let mut result = provider.settings_config.clone();
+    if let (Some(auth), Some(obj)) = (live.get("auth"), result.as_object_mut()) {
+        obj.insert("auth".to_string(), auth.clone());
+    }
+
+    let entry = config_text(&live)
+        .parse::<DocumentMut>()
+        .ok()
+        .and_then(|doc| doc.get("model_providers")?.get(&provider.id).cloned());
+    let Some(entry) = entry else {
+        return result;
+    };
+    let Ok(mut own) = config_text(&provider.settings_config).parse::<DocumentMut>() else {
+        return result;
+    };
+    let Some(name) = own
+        .get("model_provider")
+        .and_then(|v| v.as_str())
+        .map(str::to_string)
+    else {
+        return result;
+    };
+    if let Some(table) = own
+        .get_mut("model_providers")
+        .and_then(Item::as_table_like_mut)
+    {
+        table.insert(&name, entry);
+        if let Some(obj) = result.as_object_mut() {
+            obj.insert("config".to_string(), Value::String(own.to_string()));
+        }
+    }
+    result
+}
+
+/// 非当前供应商增删改后重新写入 config.toml，使条目与供应商列表保持一致（仅写入模式）
+pub fn refresh(app_state: &AppState, app_type: &AppType) {
+    if !matches!(app_type, AppType::Codex) || !is_enabled() {
+        return;
+    }
+    if crate::settings::get_settings().operation_mode != OperationMode::Write {
+        return;
+    }
+    if let Err(e) = crate::self_check::reapply_live(app_state, app_type) {
+        tracing::warn!("刷新 Codex 单文件配置失败: {}", e);
+    }
+}
+
+/// 删除供应商时从 config.toml 中移除其条目
+pub fn remove_entry(id: &str) -> Result<(), String> {
+    let path = crate::codex_config::get_codex_config_path();
+    let text = crate::codex_config::read_config_text_from_path(&path)?;
+    let Ok(mut doc) = text.parse::<DocumentMut>() else {
+        return Ok(());
+    };
+    let removed = doc
+        .get_mut("model_providers")
+        .and_then(Item::as_table_like_mut)
+        .and_then(|table| table.remove(id))
+        .is_some();
+    if removed {
+        crate::config::write_text_file(&path, &doc.to_string())?;
+    }
+    Ok(())
+}
@@ -1,48 +1,141 @@
 #![allow(non_snake_case)]
 
 use std::collections::HashMap;
-use tauri::State;
+use tauri::{Manager, State};
 use tauri_plugin_dialog::DialogExt;
 use tauri_plugin_opener::OpenerExt;
 
 use crate::app_config::AppType;
+use crate::audit::AuditAction;
 use crate::claude_mcp;
 use crate::claude_plugin;
 use crate::codex_config;
 use crate::config::{self, get_claude_settings_path, ConfigStatus};
-use crate::provider::{Provider, ProviderMeta};
+use crate::error::CcSwitchError;
+use crate::i18n::{t, t_args};
+use crate::provider::{CredentialKind, Provider, ProviderMeta};
 use crate::speedtest;
 use crate::store::AppState;
 
-fn validate_provider_settings(app_type: &AppType, provider: &Provider) -> Result<(), String> {
-    match app_type {
-        AppType::Claude => {
-            if !provider.settings_config.is_object() {
-                return Err("Claude 配置必须是 JSON 对象".to_string());
-            }
+pub(crate) fn validate_provider_settings(
+    app_type: &AppType,
+    provider: &Provider,
+) -> Result<(), CcSwitchError> {
+    if let Some(dir) = provider.live_config_dir() {
+        if !dir.is_absolute() {
+            return Err(t_args(
+                "errors.liveConfigDirNotAbsolute",
+                &[("path", &dir.display())],
+            )
+            .into());
         }
-        AppType::Codex => {
-            let settings = provider
-                .settings_config
-                .as_object()
-                .ok_or_else(|| "Codex 配置必须是 JSON 对象".to_string())?;
-            let auth = settings
-                .get("auth")
-                .ok_or_else(|| "Codex 配置缺少 auth 字段".to_string())?;
-            if !auth.is_object() {
-                return Err("Codex auth 配置必须是 JSON 对象".to_string());
-            }
-            if let Some(config_value) = settings.get("config") {
-                if !(config_value.is_string() || config_value.is_null()) {
-                    return Err("Codex config 字段必须是字符串".to_string());
-                }
-                if let Some(cfg_text) = config_value.as_str() {
-                    codex_config::validate_config_toml(cfg_text)?;
+    }
+    if let Some(meta) = provider.meta.as_ref() {
+        crate::dns::validate_hosts(&meta.host_mapping)?;
+    }
+    crate::upstream::validate(provider)?;
+    crate::validation::ensure_valid(app_type, &provider.settings_config)
+}
+
+/// 还原前端原样回传的掩码凭证（列表数据已脱敏），按已保存配置与当前 live 配置查找原值
+fn restore_masked_secrets(
+    state: &State<'_, AppState>,
+    app_type: &AppType,
+    provider: &mut Provider,
+) -> Result<(), String> {
+    let (stored, is_current) = {
+        let config = state
+            .config
+            .lock()
+            .map_err(|e| t_args("errors.lockFailed", &[("error", &e)]))?;
+        let manager = config
+            .get_manager(app_type)
+            .ok_or_else(|| t_args("errors.appTypeNotFound", &[("app", &app_type.as_str())]))?;
+        (
+            manager.providers.get(&provider.id).cloned(),
+            manager.current == provider.id,
+        )
+    };
+
+    let mut sources = Vec::new();
+    if let Some(stored) = stored {
+        if is_current {
+            // 编辑当前供应商时表单以 live 配置为初始值
+            for dir in [
+                crate::live_config::live_dir(app_type, Some(&stored)),
+                crate::live_config::live_dir(app_type, None),
+            ] {
+                if let Ok(live) = crate::live_config::read_live_settings(app_type, &dir) {
+                    sources.push(live);
                 }
             }
         }
+        sources.push(stored.settings_config);
     }
-    Ok(())
+    let sources: Vec<&serde_json::Value> = sources.iter().collect();
+    crate::redact::restore_secrets(app_type, &mut provider.settings_config, &sources)
+}
+
+/// 环境变量模式下按当前供应商重新生成 env 脚本（其他模式不做处理）
+fn refresh_env_files(state: &State<'_, AppState>) -> Result<(), String> {
+    if !crate::env_mode::is_env_mode() {
+        return Ok(());
+    }
+    let config = state
+        .config
+        .lock()
+        .map_err(|e| t_args("errors.lockFailed", &[("error", &e)]))?;
+    crate::env_mode::write_env_files(&config)
+}
+
+/// 当前供应商的 live 配置目录（短锁）
+fn current_live_dir(
+    state: &State<'_, AppState>,
+    app_type: &AppType,
+) -> Result<std::path::PathBuf, String> {
+    let config = state
+        .config
+        .lock()
+        .map_err(|e| t_args("errors.lockFailed", &[("error", &e)]))?;
+    Ok(crate::live_config::current_live_dir(&config, app_type))
+}
+
+/// 获取供应商配置的 JSON Schema（供前端表单生成与编辑器校验）
+#[tauri::command]
+pub async fn get_provider_schema(
+    app_type: Option<AppType>,
+    app: Option<String>,
+    appType: Option<String>,
+) -> Result<serde_json::Value, CcSwitchError> {
+    let app_type = app_type
+        .or_else(|| app.as_deref().map(|s| s.into()))
+        .or_else(|| appType.as_deref().map(|s| s.into()))
+        .unwrap_or(AppType::Claude);
+
+    Ok(crate::schema::provider_schema(&app_type))
+}
+
+/// 校验供应商配置，返回字段级错误列表（供前端表单提示）
+#[tauri::command]
+pub async fn validate_provider_config(
+    app_type: Option<AppType>,
+    app: Option<String>,
+    appType: Option<String>,
+    settings_config: Option<serde_json::Value>,
+    settingsConfig: Option<serde_json::Value>,
+) -> Result<Vec<crate::validation::FieldError>, CcSwitchError> {
+    let app_type = app_type
+        .or_else(|| app.as_deref().map(|s| s.into()))
+        .or_else(|| appType.as_deref().map(|s| s.into()))
+        .unwrap_or(AppType::Claude);
+    let settings_config = settings_config
+        .or(settingsConfig)
+        .ok_or_else(|| t_args("errors.missingParam", &[("name", &"settingsConfig")]))?;
+
+    Ok(crate::validation::validate_settings_config(
+        &app_type,
+        &settings_config,
+    ))
 }
 
 /// 获取所有供应商
@@ -52,7 +145,7 @@ pub async fn get_providers(
     app_type: Option<AppType>,
     app: Option<String>,
     appType: Option<String>,
-) -> Result<HashMap<String, Provider>, String> {
+) -> Result<HashMap<String, Provider>, CcSwitchError> {
     let app_type = app_type
         .or_else(|| app.as_deref().map(|s| s.into()))
         .or_else(|| appType.as_deref().map(|s| s.into()))
@@ -61,13 +154,18 @@ pub async fn get_providers(
     let config = state
         .config
         .lock()
-        .map_err(|e| format!("获取锁失败: {}", e))?;
+        .map_err(|e| t_args("errors.lockFailed", &[("error", &e)]))?;
 
     let manager = config
         .get_manager(&app_type)
-        .ok_or_else(|| format!("应用类型不存在: {:?}", app_type))?;
-
-    Ok(manager.get_all_providers().clone())
+        .ok_or_else(|| t_args("errors.appTypeNotFound", &[("app", &app_type.as_str())]))?;
+
+    // 列表数据脱敏，明文需通过 reveal_provider_secret 获取
+    Ok(manager
+        .get_all_providers()
+        .iter()
+        .map(|(id, p)| (id.clone(), crate::redact::redact_provider(&app_type, p)))
+        .collect())
 }
 
 /// 获取当前供应商ID
@@ -77,7 +175,7 @@ pub async fn get_current_provider(
     app_type: Option<AppType>,
     app: Option<String>,
     appType: Option<String>,
-) -> Result<String, String> {
+) -> Result<String, CcSwitchError> {
     let app_type = app_type
         .or_else(|| app.as_deref().map(|s| s.into()))
         .or_else(|| appType.as_deref().map(|s| s.into()))
@@ -86,11 +184,11 @@ pub async fn get_current_provider(
     let config = state
         .config
         .lock()
-        .map_err(|e| format!("获取锁失败: {}", e))?;
+        .map_err(|e| t_args("errors.lockFailed", &[("error", &e)]))?;
 
     let manager = config
         .get_manager(&app_type)
-        .ok_or_else(|| format!("应用类型不存在: {:?}", app_type))?;
+        .ok_or_else(|| t_args("errors.appTypeNotFound", &[("app", &app_type.as_str())]))?;
 
     Ok(manager.current.clone())
 }
@@ -103,45 +201,45 @@ pub async fn add_provider(
     app: Option<String>,
     appType: Option<String>,
     provider: Provider,
-) -> Result<bool, String> {
+) -> Result<bool, CcSwitchError> {
     let app_type = app_type
         .or_else(|| app.as_deref().map(|s| s.into()))
         .or_else(|| appType.as_deref().map(|s| s.into()))
         .unwrap_or(AppType::Claude);
 
+    let mut provider = provider;
+    // 锁定状态只能通过 set_provider_locked 修改
+    provider.locked = None;
+    restore_masked_secrets(&state, &app_type, &mut provider)?;
     validate_provider_settings(&app_type, &provider)?;
+    if matches!(app_type, AppType::Codex) {
+        crate::codex_config::sync_credential_kind(&mut provider);
+    }
 
-    // 读取当前是否是激活供应商（短锁）
-    let is_current = {
+    // 读取当前是否是激活供应商，并生成需要写入的 live 内容（短锁）
+    let (is_current, live) = {
         let config = state
             .config
             .lock()
-            .map_err(|e| format!("获取锁失败: {}", e))?;
+            .map_err(|e| t_args("errors.lockFailed", &[("error", &e)]))?;
         let manager = config
             .get_manager(&app_type)
-            .ok_or_else(|| format!("应用类型不存在: {:?}", app_type))?;
-        manager.current == provider.id
+            .ok_or_else(|| t_args("errors.appTypeNotFound", &[("app", &app_type.as_str())]))?;
+        if let Some(existing) = manager.providers.get(&provider.id) {
+            existing.ensure_editable()?;
+        }
+        let is_current = manager.current == provider.id;
+        let live = (is_current && !crate::env_mode::is_env_mode()).then(|| {
+            let dir = crate::live_config::live_dir(&app_type, Some(&provider));
+            let settings = crate::common_config::live_settings(&app_type, manager, &provider, &dir);
+            (dir, settings)
+        });
+        (is_current, live)
     };
 
     // 若目标为当前供应商，则先写 live，成功后再落盘配置
-    if is_current {
-        match app_type {
-            AppType::Claude => {
-                let settings_path = crate::config::get_claude_settings_path();
-                crate::config::write_json_file(&settings_path, &provider.settings_config)?;
-            }
-            AppType::Codex => {
-                let auth = provider
-                    .settings_config
-                    .get("auth")
-                    .ok_or_else(|| "目标供应商缺少 auth 配置".to_string())?;
-                let cfg_text = provider
-                    .settings_config
-                    .get("config")
-                    .and_then(|v| v.as_str());
-                crate::codex_config::write_codex_live_atomic(auth, cfg_text)?;
-            }
-        }
+    if let Some((dir, settings)) = &live {
+        crate::live_config::write_live_settings(&app_type, dir, settings)?;
     }
 
     // 更新内存并保存配置
@@ -149,15 +247,26 @@ pub async fn add_provider(
         let mut config = state
             .config
             .lock()
-            .map_err(|e| format!("获取锁失败: {}", e))?;
+            .map_err(|e| t_args("errors.lockFailed", &[("error", &e)]))?;
         let manager = config
             .get_manager_mut(&app_type)
-            .ok_or_else(|| format!("应用类型不存在: {:?}", app_type))?;
+            .ok_or_else(|| t_args("errors.appTypeNotFound", &[("app", &app_type.as_str())]))?;
         manager
             .providers
             .insert(provider.id.clone(), provider.clone());
     }
-    state.save()?;
+    state.save_now()?;
+    if is_current {
+        refresh_env_files(&state)?;
+    } else {
+        crate::codex_single::refresh(&state, &app_type);
+    }
+    crate::audit::record(
+        AuditAction::ProviderAdded,
+        Some(&app_type),
+        Some(&provider.name),
+        None,
+    );
 
     Ok(true)
 }
@@ -170,64 +279,68 @@ pub async fn update_provider(
     app: Option<String>,
     appType: Option<String>,
     provider: Provider,
-) -> Result<bool, String> {
+) -> Result<bool, CcSwitchError> {
     let app_type = app_type
         .or_else(|| app.as_deref().map(|s| s.into()))
         .or_else(|| appType.as_deref().map(|s| s.into()))
         .unwrap_or(AppType::Claude);
 
+    let mut provider = provider;
+    restore_masked_secrets(&state, &app_type, &mut provider)?;
     validate_provider_settings(&app_type, &provider)?;
 
-    // 读取校验 & 是否当前（短锁）
-    let (exists, is_current) = {
+    // 读取校验 & 是否当前，并生成需要写入的 live 内容（短锁）
+    let (exists, is_current, live) = {
         let config = state
             .config
             .lock()
-            .map_err(|e| format!("获取锁失败: {}", e))?;
+            .map_err(|e| t_args("errors.lockFailed", &[("error", &e)]))?;
         let manager = config
             .get_manager(&app_type)
-            .ok_or_else(|| format!("应用类型不存在: {:?}", app_type))?;
+            .ok_or_else(|| t_args("errors.appTypeNotFound", &[("app", &app_type.as_str())]))?;
+        if let Some(existing) = manager.providers.get(&provider.id) {
+            existing.ensure_editable()?;
+        }
+        let is_current = manager.current == provider.id;
+        let live = (is_current && !crate::env_mode::is_env_mode()).then(|| {
+            let dir = crate::live_config::live_dir(&app_type, Some(&provider));
+            let settings = crate::common_config::live_settings(&app_type, manager, &provider, &dir);
+            (dir, settings)
+        });
         (
             manager.providers.contains_key(&provider.id),
-            manager.current == provider.id,
+            is_current,
+            live,
         )
     };
     if !exists {
-        return Err(format!("供应商不存在: {}", provider.id));
+        return Err(t_args("errors.providerNotFound", &[("id", &provider.id)]).into());
     }
 
     // 若更新的是当前供应商，先写 live 成功再保存
-    if is_current {
-        match app_type {
-            AppType::Claude => {
-                let settings_path = crate::config::get_claude_settings_path();
-                crate::config::write_json_file(&settings_path, &provider.settings_config)?;
-            }
-            AppType::Codex => {
-                let auth = provider
-                    .settings_config
-                    .get("auth")
-                    .ok_or_else(|| "目标供应商缺少 auth 配置".to_string())?;
-                let cfg_text = provider
-                    .settings_config
-                    .get("config")
-                    .and_then(|v| v.as_str());
-                crate::codex_config::write_codex_live_atomic(auth, cfg_text)?;
-            }
-        }
+    if let Some((dir, settings)) = &live {
+        crate::live_config::write_live_settings(&app_type, dir, settings)?;
     }
 
     // 更新内存并保存（保留/合并已有的 meta.custom_endpoints，避免丢失在编辑流程中新增的自定义端点）
-    {
+    let key_changed = {
         let mut config = state
             .config
             .lock()
-            .map_err(|e| format!("获取锁失败: {}", e))?;
+            .map_err(|e| t_args("errors.lockFailed", &[("error", &e)]))?;
         let manager = config
             .get_manager_mut(&app_type)
-            .ok_or_else(|| format!("应用类型不存在: {:?}", app_type))?;
+            .ok_or_else(|| t_args("errors.appTypeNotFound", &[("app", &app_type.as_str())]))?;
 
         // 若已存在旧供应商，合并其 meta（尤其是 custom_endpoints）到新对象
+        let key_changed = manager.providers.get(&provider.id).is_some_and(|existing| {
+            crate::redact::secrets_differ(
+                &app_type,
+                &existing.settings_config,
+                &provider.settings_config,
+            )
+        });
+
         let merged_provider = if let Some(existing) = manager.providers.get(&provider.id) {
             // 克隆入参作为基准
             let mut updated = provider.clone();
@@ -246,7 +359,10 @@ pub async fn update_provider(
                     }
                     updated.meta = Some(crate::provider::ProviderMeta {
                         custom_endpoints: merged_map,
-                        usage_script: new_meta.usage_script.clone(),
+                        credential_kind: new_meta.credential_kind.or(old_meta.credential_kind),
+                        // OAuth 令牌只由后端在切换时回填，避免前端持有的旧令牌覆盖刷新后的令牌
+                        oauth: old_meta.oauth.clone(),
+                        ..new_meta
                     });
                 }
                 // 旧 meta 不存在：使用入参（可能为 None）
@@ -260,12 +376,37 @@ pub async fn update_provider(
             // 不存在旧供应商（理论上不应发生，因为前面已校验 exists）
             provider.clone()
         };
+        let mut merged_provider = merged_provider;
+        merged_provider.locked = None;
+        if matches!(app_type, AppType::Codex) {
+            crate::codex_config::sync_credential_kind(&mut merged_provider);
+        }
 
         manager
             .providers
             .insert(merged_provider.id.clone(), merged_provider);
+        key_changed
+    };
+    state.save_now()?;
+    // 用户可能已修正 Key，解除鉴权失败标记
+    crate::auth_failure::clear(&app_type, &provider.id);
+    if is_current {
+        refresh_env_files(&state)?;
+        if let Ok(config) = state.config.lock() {
+            if matches!(app_type, AppType::Claude) {
+                crate::statusline::refresh(&config);
+            }
+            crate::current_status::refresh(&config, None);
+        }
+    } else {
+        crate::codex_single::refresh(&state, &app_type);
     }
-    state.save()?;
+    let action = if key_changed {
+        AuditAction::KeyChanged
+    } else {
+        AuditAction::ProviderUpdated
+    };
+    crate::audit::record(action, Some(&app_type), Some(&provider.name), None);
 
     Ok(true)
 }
@@ -278,7 +419,7 @@ pub async fn delete_provider(
     app: Option<String>,
     appType: Option<String>,
     id: String,
-) -> Result<bool, String> {
+) -> Result<bool, CcSwitchError> {
     let app_type = app_type
         .or_else(|| app.as_deref().map(|s| s.into()))
         .or_else(|| appType.as_deref().map(|s| s.into()))
@@ -287,49 +428,259 @@ pub async fn delete_provider(
     let mut config = state
         .config
         .lock()
-        .map_err(|e| format!("获取锁失败: {}", e))?;
+        .map_err(|e| t_args("errors.lockFailed", &[("error", &e)]))?;
 
     let manager = config
         .get_manager_mut(&app_type)
-        .ok_or_else(|| format!("应用类型不存在: {:?}", app_type))?;
+        .ok_or_else(|| t_args("errors.appTypeNotFound", &[("app", &app_type.as_str())]))?;
 
     // 检查是否为当前供应商
     if manager.current == id {
-        return Err("不能删除当前正在使用的供应商".to_string());
+        return Err(t("errors.cannotDeleteCurrent").into());
     }
 
     // 获取供应商信息
     let provider = manager
         .providers
         .get(&id)
-        .ok_or_else(|| format!("供应商不存在: {}", id))?
+        .ok_or_else(|| t_args("errors.providerNotFound", &[("id", &id)]))?
         .clone();
+    provider.ensure_editable()?;
 
     // 删除配置文件
+    delete_provider_copies(&app_type, &id, &provider.name)?;
+
+    // 从管理器删除
+    manager.providers.remove(&id);
+
+    // 保存配置
+    drop(config); // 释放锁
+    state.save_now()?;
+    crate::audit::record(
+        AuditAction::ProviderDeleted,
+        Some(&app_type),
+        Some(&provider.name),
+        None,
+    );
+
+    Ok(true)
+}
+
+/// 删除供应商的历史副本文件（SSOT 之前按供应商保存的 live 配置副本）
+pub(crate) fn delete_provider_copies(
+    app_type: &AppType,
+    id: &str,
+    name: &str,
+) -> Result<(), String> {
     match app_type {
         AppType::Codex => {
-            codex_config::delete_codex_provider_config(&id, &provider.name)?;
+            if crate::codex_single::is_enabled() {
+                crate::codex_single::remove_entry(id)?;
+            }
+            codex_config::delete_codex_provider_config(id, name)
         }
         AppType::Claude => {
             use crate::config::{delete_file, get_provider_config_path};
             // 兼容历史两种命名：settings-{name}.json 与 settings-{id}.json
-            let by_name = get_provider_config_path(&id, Some(&provider.name));
-            let by_id = get_provider_config_path(&id, None);
+            let by_name = get_provider_config_path(id, Some(name));
+            let by_id = get_provider_config_path(id, None);
             delete_file(&by_name)?;
-            delete_file(&by_id)?;
+            delete_file(&by_id)
         }
     }
+}
 
-    // 从管理器删除
-    manager.providers.remove(&id);
+/// 处理 live 配置漂移：reapply（用当前供应商覆盖 live）、import（将 live 导入当前供应商）或 ignore
+#[tauri::command]
+pub async fn resolve_config_drift(
+    state: State<'_, AppState>,
+    app_type: Option<AppType>,
+    app: Option<String>,
+    appType: Option<String>,
+    action: String,
+) -> Result<bool, CcSwitchError> {
+    let app_type = app_type
+        .or_else(|| app.as_deref().map(|s| s.into()))
+        .or_else(|| appType.as_deref().map(|s| s.into()))
+        .unwrap_or(AppType::Claude);
 
-    // 保存配置
-    drop(config); // 释放锁
-    state.save()?;
+    match action.as_str() {
+        "reapply" => {
+            let config = state
+                .config
+                .lock()
+                .map_err(|e| t_args("errors.lockFailed", &[("error", &e)]))?;
+            let manager = config
+                .get_manager(&app_type)
+                .ok_or_else(|| t_args("errors.appTypeNotFound", &[("app", &app_type.as_str())]))?;
+            let provider = manager
+                .providers
+                .get(&manager.current)
+                .ok_or_else(|| t("errors.noCurrentProvider"))?;
+            let dir = crate::live_config::live_dir(&app_type, Some(provider));
+            let settings = crate::common_config::live_settings(&app_type, manager, provider, &dir);
+            crate::live_config::write_live_settings(&app_type, &dir, &settings)?;
+        }
+        "import" => {
+            let dir = current_live_dir(&state, &app_type)?;
+            let live = crate::live_config::read_live_settings(&app_type, &dir)?;
+            {
+                let mut config = state
+                    .config
+                    .lock()
+                    .map_err(|e| t_args("errors.lockFailed", &[("error", &e)]))?;
+                let manager = config.get_manager_mut(&app_type).ok_or_else(|| {
+                    t_args("errors.appTypeNotFound", &[("app", &app_type.as_str())])
+                })?;
+                let current = manager.current.clone();
+                let provider = manager
+                    .providers
+                    .get_mut(&current)
+                    .ok_or_else(|| t("errors.noCurrentProvider"))?;
+                provider.ensure_editable()?;
+                provider.settings_config =
+                    crate::common_config::backfill(&app_type, provider, live);
+            }
+            state.save_now()?;
+        }
+        "ignore" => {
+            let dir = current_live_dir(&state, &app_type)?;
+            crate::config_watcher::ignore_current_drift(&app_type, &dir)?;
+        }
+        other => return Err(t_args("errors.unknownDriftAction", &[("action", &other)]).into()),
+    }
+
+    Ok(true)
+}
+
+/// 按当前运行模式重新写入 live 配置（写入模式为当前供应商，代理模式为代理模板），
+/// 用于 Claude Code 升级或其他工具覆盖配置文件之后
+#[tauri::command]
+pub async fn reapply_current(
+    state: State<'_, AppState>,
+    app_type: Option<AppType>,
+    app: Option<String>,
+    appType: Option<String>,
+) -> Result<bool, CcSwitchError> {
+    let app_type = app_type
+        .or_else(|| app.as_deref().map(|s| s.into()))
+        .or_else(|| appType.as_deref().map(|s| s.into()))
+        .unwrap_or(AppType::Claude);
 
+    crate::self_check::reapply_live(&state, &app_type)?;
+    tracing::info!("已重新应用 {} 当前配置", app_type.as_str());
     Ok(true)
 }
 
+/// 将 live 配置中的手动修改同步回当前供应商
+/// - apply 为 false（默认）时仅返回差异（旧：供应商配置，新：live 配置）供前端确认
+/// - apply 为 true 时写入当前供应商并保存
+#[tauri::command]
+pub async fn sync_from_live(
+    state: State<'_, AppState>,
+    app_type: Option<AppType>,
+    app: Option<String>,
+    appType: Option<String>,
+    apply: Option<bool>,
+) -> Result<crate::live_config::ConfigDiff, CcSwitchError> {
+    let app_type = app_type
+        .or_else(|| app.as_deref().map(|s| s.into()))
+        .or_else(|| appType.as_deref().map(|s| s.into()))
+        .unwrap_or(AppType::Claude);
+
+    let dir = current_live_dir(&state, &app_type)?;
+    let live = crate::live_config::read_live_settings(&app_type, &dir)?;
+
+    let diff = {
+        let mut config = state
+            .config
+            .lock()
+            .map_err(|e| t_args("errors.lockFailed", &[("error", &e)]))?;
+        let manager = config
+            .get_manager_mut(&app_type)
+            .ok_or_else(|| t_args("errors.appTypeNotFound", &[("app", &app_type.as_str())]))?;
+        let current = manager.current.clone();
+        let provider = manager
+            .providers
+            .get_mut(&current)
+            .ok_or_else(|| t("errors.noCurrentProvider"))?;
+        // 通用配置片段合并进来的内容不属于供应商自身
+        let live = crate::common_config::backfill(&app_type, provider, live);
+
+        let diff = crate::live_config::diff_settings(
+            &app_type,
+            &dir,
+            Some(&provider.settings_config),
+            &live,
+        );
+        if !apply.unwrap_or(false) || !diff.changed {
+            return Ok(diff);
+        }
+        provider.ensure_editable()?;
+        provider.settings_config = live;
+        diff
+    };
+
+    state.save_now()?;
+    tracing::info!("已将 {} live 配置同步回当前供应商", app_type.as_str());
+    Ok(diff)
+}
+
+/// 预览切换供应商将写入 live 配置的内容（不做任何写入）
+#[tauri::command]
+pub async fn preview_switch(
+    state: State<'_, AppState>,
+    app_type: Option<AppType>,
+    app: Option<String>,
+    appType: Option<String>,
+    provider_id: Option<String>,
+    providerId: Option<String>,
+) -> Result<crate::live_config::SwitchPreview, CcSwitchError> {
+    let app_type = app_type
+        .or_else(|| app.as_deref().map(|s| s.into()))
+        .or_else(|| appType.as_deref().map(|s| s.into()))
+        .unwrap_or(AppType::Claude);
+    let provider_id = provider_id
+        .or(providerId)
+        .ok_or_else(|| t_args("errors.missingParam", &[("name", &"providerId")]))?;
+
+    let (provider_name, current, target, dir) = {
+        let config = state
+            .config
+            .lock()
+            .map_err(|e| t_args("errors.lockFailed", &[("error", &e)]))?;
+        let manager = config
+            .get_manager(&app_type)
+            .ok_or_else(|| t_args("errors.appTypeNotFound", &[("app", &app_type.as_str())]))?;
+        let provider = manager
+            .providers
+            .get(&provider_id)
+            .ok_or_else(|| t_args("errors.providerNotFound", &[("id", &provider_id)]))?;
+        let dir = crate::live_config::live_dir(&app_type, Some(provider));
+        (
+            provider.name.clone(),
+            manager.current.clone(),
+            crate::common_config::live_settings(&app_type, manager, provider, &dir),
+            dir,
+        )
+    };
+
+    // 预览目标供应商 live 目录的变化；live 文件不存在时视为全部新增
+    let live = crate::live_config::read_live_settings(&app_type, &dir).ok();
+    let diff = crate::live_config::diff_settings(&app_type, &dir, live.as_ref(), &target);
+
+    Ok(crate::live_config::SwitchPreview {
+        provider_id,
+        provider_name,
+        current_provider_id: if current.is_empty() {
+            None
+        } else {
+            Some(current)
+        },
+        diff,
+    })
+}
+
 /// 切换供应商
 #[tauri::command]
 pub async fn switch_provider(
@@ -338,32 +689,165 @@ pub async fn switch_provider(
     app: Option<String>,
     appType: Option<String>,
     id: String,
-) -> Result<bool, String> {
+) -> Result<bool, CcSwitchError> {
     let app_type = app_type
         .or_else(|| app.as_deref().map(|s| s.into()))
         .or_else(|| appType.as_deref().map(|s| s.into()))
         .unwrap_or(AppType::Claude);
 
+    // 前置钩子在加锁前执行，可取消本次切换
+    let hook_context = {
+        let config = state
+            .config
+            .lock()
+            .map_err(|e| t_args("errors.lockFailed", &[("error", &e)]))?;
+        let manager = config.get_manager(&app_type);
+        let name = manager
+            .and_then(|manager| manager.providers.get(&id))
+            .map_or(id.as_str(), |provider| provider.name.as_str());
+        let previous = manager.map_or("", |manager| manager.current.as_str());
+        crate::hooks::HookContext::switch(app_type.as_str(), &id, name, previous)
+    };
+    crate::hooks::before(crate::hooks::HookEvent::BeforeSwitch, &hook_context).await?;
+
     let mut config = state
         .config
         .lock()
-        .map_err(|e| format!("获取锁失败: {}", e))?;
+        .map_err(|e| t_args("errors.lockFailed", &[("error", &e)]))?;
+
+    // 环境变量模式：不触碰 live 配置文件，仅更新当前供应商并重新生成 env 脚本
+    if crate::env_mode::is_env_mode() {
+        switch_provider_env(&mut config, &app_type, &id)?;
+        record_switch(&config, &app_type, &id);
+        crate::vscode_targets::apply_current(&config, &app_type);
+        crate::hooks::after(crate::hooks::HookEvent::AfterSwitch, hook_context);
+        crate::running_sessions::warn_stale(
+            Some(app_type),
+            crate::running_sessions::ChangeReason::Switch,
+        );
+        return Ok(true);
+    }
+
+    // Codex 需同时写 auth.json 与 config.toml：先备份目标 live 文件与内存配置，任一步失败整体回滚
+    let codex_snapshot = match app_type {
+        AppType::Codex => {
+            let target = config
+                .get_manager(&app_type)
+                .and_then(|manager| manager.providers.get(&id));
+            let dir = crate::live_config::live_dir(&app_type, target);
+            Some(crate::codex_config::CodexLiveSnapshot::capture_in(&dir)?)
+        }
+        AppType::Claude => None,
+    };
+    let config_backup = config.clone();
+
+    if let Err(e) = switch_provider_locked(&mut config, &app_type, &id) {
+        *config = config_backup;
+        if let Some(snapshot) = codex_snapshot {
+            if let Err(restore_err) = snapshot.restore() {
+                tracing::error!("回滚 Codex live 配置失败: {}", restore_err);
+                return Err(format!("{}；回滚 Codex 配置失败: {}", e, restore_err).into());
+            }
+        }
+        return Err(e.into());
+    }
+    record_switch(&config, &app_type, &id);
+    crate::vscode_targets::apply_current(&config, &app_type);
+    crate::hooks::after(crate::hooks::HookEvent::AfterSwitch, hook_context);
+    crate::running_sessions::warn_stale(
+        Some(app_type),
+        crate::running_sessions::ChangeReason::Switch,
+    );
+
+    Ok(true)
+}
+
+fn record_switch(config: &crate::app_config::MultiAppConfig, app_type: &AppType, id: &str) {
+    let name = config
+        .get_manager(app_type)
+        .and_then(|manager| manager.providers.get(id))
+        .map_or(id, |provider| provider.name.as_str());
+    crate::audit::record(
+        AuditAction::ProviderSwitched,
+        Some(app_type),
+        Some(name),
+        None,
+    );
+    if matches!(app_type, AppType::Claude) {
+        crate::statusline::refresh(config);
+    }
+    crate::current_status::refresh(config, Some(app_type));
+    crate::webhook::notify(
+        crate::webhook::WebhookEvent::ProviderSwitched,
+        app_type.as_str(),
+        serde_json::json!({ "appType": app_type.as_str(), "providerId": id, "provider": name }),
+    );
+}
+
+/// 环境变量模式下的切换：校验目标供应商、更新 current、落盘并重新生成 env 脚本
+fn switch_provider_env(
+    config: &mut crate::app_config::MultiAppConfig,
+    app_type: &AppType,
+    id: &str,
+) -> Result<(), CcSwitchError> {
+    let manager = config
+        .get_manager_mut(app_type)
+        .ok_or_else(|| t_args("errors.appTypeNotFound", &[("app", &app_type.as_str())]))?;
+    let provider = manager
+        .providers
+        .get(id)
+        .ok_or_else(|| t_args("errors.providerNotFound", &[("id", &id)]))?;
+    crate::validation::ensure_valid(app_type, &provider.settings_config)?;
+    let previous = std::mem::replace(&mut manager.current, id.to_string());
+
+    if let Err(e) = config
+        .save()
+        .and_then(|_| crate::env_mode::write_env_files(config))
+    {
+        if let Some(manager) = config.get_manager_mut(app_type) {
+            manager.current = previous;
+        }
+        return Err(e.into());
+    }
+    tracing::info!("环境变量模式：已切换 {} 供应商为 {}", app_type.as_str(), id);
+    Ok(())
+}
 
+/// 在已持有配置锁的情况下执行切换：回填当前供应商、写入 live、更新 current 并落盘
+fn switch_provider_locked(
+    config: &mut crate::app_config::MultiAppConfig,
+    app_type: &AppType,
+    id: &str,
+) -> Result<(), String> {
     // 为避免长期可变借用，尽快获取必要数据并缩小借用范围
     let provider = {
         let manager = config
-            .get_manager_mut(&app_type)
-            .ok_or_else(|| format!("应用类型不存在: {:?}", app_type))?;
+            .get_manager_mut(app_type)
+            .ok_or_else(|| t_args("errors.appTypeNotFound", &[("app", &app_type.as_str())]))?;
 
         // 检查供应商是否存在
         let provider = manager
             .providers
-            .get(&id)
-            .ok_or_else(|| format!("供应商不存在: {}", id))?
+            .get(id)
+            .ok_or_else(|| t_args("errors.providerNotFound", &[("id", &id)]))?
             .clone();
+        // 已停用的供应商不可切换（重新应用当前供应商除外）
+        if !provider.is_enabled() && manager.current != id {
+            return Err(t_args(
+                "errors.providerDisabled",
+                &[("name", &provider.name)],
+            ));
+        }
         provider
     };
 
+    // 写入 live 前校验目标配置，避免切换到残缺配置
+    crate::validation::ensure_valid(app_type, &provider.settings_config)?;
+
+    // 回填读取当前供应商的 live 目录，写入目标供应商的 live 目录（供应商可自定义目录）
+    let current_dir = crate::live_config::current_live_dir(config, app_type);
+    let target_dir = crate::live_config::live_dir(app_type, Some(&provider));
+
     // SSOT 切换：先回填 live 配置到当前供应商，然后从内存写入目标主配置
     match app_type {
         AppType::Codex => {
@@ -371,13 +855,13 @@ pub async fn switch_provider(
 
             // 回填：读取 live（auth.json + config.toml）写回当前供应商 settings_config
             if !{
-                let cur = config
-                    .get_manager_mut(&app_type)
-                    .ok_or_else(|| format!("应用类型不存在: {:?}", app_type))?;
+                let cur = config.get_manager_mut(app_type).ok_or_else(|| {
+                    t_args("errors.appTypeNotFound", &[("app", &app_type.as_str())])
+                })?;
                 cur.current.is_empty()
             } {
-                let auth_path = codex_config::get_codex_auth_path();
-                let config_path = codex_config::get_codex_config_path();
+                let auth_path = current_dir.join("auth.json");
+                let config_path = current_dir.join("config.toml");
                 if auth_path.exists() {
                     let auth: Value = crate::config::read_json_file(&auth_path)?;
                     let config_str = if config_path.exists() {
@@ -394,72 +878,117 @@ pub async fn switch_provider(
                     });
 
                     let cur_id2 = {
-                        let m = config
-                            .get_manager(&app_type)
-                            .ok_or_else(|| format!("应用类型不存在: {:?}", app_type))?;
+                        let m = config.get_manager(app_type).ok_or_else(|| {
+                            t_args("errors.appTypeNotFound", &[("app", &app_type.as_str())])
+                        })?;
                         m.current.clone()
                     };
-                    let m = config
-                        .get_manager_mut(&app_type)
-                        .ok_or_else(|| format!("应用类型不存在: {:?}", app_type))?;
+                    let m = config.get_manager_mut(app_type).ok_or_else(|| {
+                        t_args("errors.appTypeNotFound", &[("app", &app_type.as_str())])
+                    })?;
                     if let Some(cur) = m.providers.get_mut(&cur_id2) {
-                        cur.settings_config = live;
+                        // 去掉通用配置片段合并进来的内容，避免沉淀到供应商中
+                        let live = crate::common_config::backfill(app_type, cur, live);
+                        // 锁定的供应商不回填 live 中的手动修改（ChatGPT 登录的令牌刷新除外）
+                        let skip_locked =
+                            cur.is_locked() && cur.credential_kind() != CredentialKind::CodexOauth;
+                        if skip_locked {
+                            tracing::debug!("供应商 {} 已锁定，跳过回填", cur.name);
+                        } else if crate::codex_config::can_backfill_auth(
+                            &cur.settings_config,
+                            &live,
+                        ) {
+                            cur.settings_config = live;
+                            crate::codex_config::sync_credential_kind(cur);
+                        } else {
+                            tracing::warn!(
+                                "当前 ChatGPT 登录账号与供应商 {} 记录的账号不一致，跳过回填",
+                                cur.name
+                            );
+                        }
                     }
                 }
             }
 
-            // 切换：从目标供应商 settings_config 写入主配置（Codex 双文件原子+回滚）
-            let auth = provider
-                .settings_config
+            // 切换：从目标供应商 settings_config（合并通用配置片段）写入主配置（Codex 双文件原子+回滚）
+            let manager = config
+                .get_manager(app_type)
+                .ok_or_else(|| t_args("errors.appTypeNotFound", &[("app", &app_type.as_str())]))?;
+            let settings =
+                crate::common_config::live_settings(app_type, manager, &provider, &target_dir);
+            let auth = settings
                 .get("auth")
                 .ok_or_else(|| "目标供应商缺少 auth 配置".to_string())?;
-            let cfg_text = provider
-                .settings_config
-                .get("config")
-                .and_then(|v| v.as_str());
-            crate::codex_config::write_codex_live_atomic(auth, cfg_text)?;
+            let cfg_text = settings.get("config").and_then(|v| v.as_str());
+            crate::codex_config::write_codex_live_atomic_in(&target_dir, auth, cfg_text)?;
         }
         AppType::Claude => {
-            use crate::config::{read_json_file, write_json_file};
+            use crate::config::{claude_settings_path_in, read_json_file};
 
-            let settings_path = get_claude_settings_path();
+            let settings_path = claude_settings_path_in(&current_dir);
 
             // 回填：读取 live settings.json 写回当前供应商 settings_config
             if settings_path.exists() {
                 let cur_id = {
-                    let m = config
-                        .get_manager(&app_type)
-                        .ok_or_else(|| format!("应用类型不存在: {:?}", app_type))?;
+                    let m = config.get_manager(app_type).ok_or_else(|| {
+                        t_args("errors.appTypeNotFound", &[("app", &app_type.as_str())])
+                    })?;
                     m.current.clone()
                 };
                 if !cur_id.is_empty() {
                     if let Ok(live) = read_json_file::<serde_json::Value>(&settings_path) {
-                        let m = config
-                            .get_manager_mut(&app_type)
-                            .ok_or_else(|| format!("应用类型不存在: {:?}", app_type))?;
+                        let m = config.get_manager_mut(app_type).ok_or_else(|| {
+                            t_args("errors.appTypeNotFound", &[("app", &app_type.as_str())])
+                        })?;
+                        // 锁定的供应商不回填 live 中的手动修改
                         if let Some(cur) = m.providers.get_mut(&cur_id) {
-                            cur.settings_config = live;
+                            if !cur.is_locked() {
+                                let mut live = crate::common_config::strip(app_type, live);
+                                crate::endpoint_probe::restore(app_type, cur, &mut live);
+                                cur.settings_config = live;
+                            }
                         }
                     }
                 }
             }
 
-            // 切换：从目标供应商 settings_config 写入主配置
-            if let Some(parent) = settings_path.parent() {
-                std::fs::create_dir_all(parent).map_err(|e| format!("创建目录失败: {}", e))?;
+            // 订阅账号：先回填当前账号被刷新的令牌，再换入目标账号的令牌
+            {
+                let m = config.get_manager_mut(app_type).ok_or_else(|| {
+                    t_args("errors.appTypeNotFound", &[("app", &app_type.as_str())])
+                })?;
+                let cur_id = m.current.clone();
+                if let Some(cur) = m.providers.get_mut(&cur_id) {
+                    if cur.credential_kind() == CredentialKind::ClaudeOauth {
+                        crate::claude_oauth::backfill(cur, &current_dir);
+                    }
+                }
+                // 重新切换到当前账号时需使用刚回填的令牌
+                if let Some(target) = m.providers.get(id) {
+                    if target.credential_kind() == CredentialKind::ClaudeOauth {
+                        crate::claude_oauth::apply(target, &target_dir)?;
+                    }
+                }
             }
 
-            // 不做归档，直接写入
-            write_json_file(&settings_path, &provider.settings_config)?;
+            // 切换：从目标供应商 settings_config（合并通用配置片段）原子写入主配置（失败自动回滚）
+            let target_path = claude_settings_path_in(&target_dir);
+            let mut settings =
+                crate::common_config::merged(app_type, &provider.settings_config, &target_dir);
+            // 开启自动选择端点时写入当前最快的端点
+            crate::endpoint_probe::apply(app_type, &provider, &mut settings);
+            crate::config::write_claude_settings_atomic_at(&target_path, &settings)?;
 
             // 写入后回读 live，并回填到目标供应商的 SSOT，保证一致
-            if settings_path.exists() {
-                if let Ok(live_after) = read_json_file::<serde_json::Value>(&settings_path) {
-                    let m = config
-                        .get_manager_mut(&app_type)
-                        .ok_or_else(|| format!("应用类型不存在: {:?}", app_type))?;
-                    if let Some(target) = m.providers.get_mut(&id) {
-                        target.settings_config = live_after;
+            if target_path.exists() {
+                if let Ok(live_after) = read_json_file::<serde_json::Value>(&target_path) {
+                    let m = config.get_manager_mut(app_type).ok_or_else(|| {
+                        t_args("errors.appTypeNotFound", &[("app", &app_type.as_str())])
+                    })?;
+                    if let Some(target) = m.providers.get_mut(id) {
+                        let mut live = crate::common_config::strip(app_type, live_after);
+                        crate::endpoint_probe::restore(app_type, target, &mut live);
+                        target.settings_config = live;
                     }
                 }
             }
@@ -469,30 +998,38 @@ pub async fn switch_provider(
     // 更新当前供应商（短借用范围）
     {
         let manager = config
-            .get_manager_mut(&app_type)
-            .ok_or_else(|| format!("应用类型不存在: {:?}", app_type))?;
-        manager.current = id;
+            .get_manager_mut(app_type)
+            .ok_or_else(|| t_args("errors.appTypeNotFound", &[("app", &app_type.as_str())]))?;
+        manager.current = id.to_string();
     }
 
     // 对 Codex：切换完成后，同步 MCP 到 config.toml，并将最新的 config.toml 回填到当前供应商 settings_config.config
     if let AppType::Codex = app_type {
-        // 1) 依据 SSOT 将启用的 MCP 投影到 ~/.codex/config.toml
-        crate::mcp::sync_enabled_to_codex(&config)?;
+        // 1) 依据 SSOT 将启用的 MCP 投影到目标供应商 live 目录的 config.toml
+        crate::mcp::sync_enabled_to_codex(config)?;
 
         // 2) 读取投影后的 live config.toml 文本
-        let cfg_text_after = crate::codex_config::read_and_validate_codex_config_text()?;
+        let cfg_text_after = crate::codex_config::read_and_validate_config_from_path(
+            &target_dir.join("config.toml"),
+        )?;
+        let cfg_text_after = crate::common_config::strip_codex_config(cfg_text_after);
 
         // 3) 回填到当前（目标）供应商的 settings_config.config，确保编辑面板读取到最新 MCP
         let cur_id = {
             let m = config
-                .get_manager(&app_type)
-                .ok_or_else(|| format!("应用类型不存在: {:?}", app_type))?;
+                .get_manager(app_type)
+                .ok_or_else(|| t_args("errors.appTypeNotFound", &[("app", &app_type.as_str())]))?;
             m.current.clone()
         };
         let m = config
-            .get_manager_mut(&app_type)
-            .ok_or_else(|| format!("应用类型不存在: {:?}", app_type))?;
-        if let Some(p) = m.providers.get_mut(&cur_id) {
+            .get_manager_mut(app_type)
+            .ok_or_else(|| t_args("errors.appTypeNotFound", &[("app", &app_type.as_str())]))?;
+        // 单文件模式下 config.toml 包含全部供应商的条目，不回填
+        let provider_mut = m
+            .providers
+            .get_mut(&cur_id)
+            .filter(|_| !crate::codex_single::is_enabled());
+        if let Some(p) = provider_mut {
             if let Some(obj) = p.settings_config.as_object_mut() {
                 obj.insert(
                     "config".to_string(),
@@ -502,13 +1039,9 @@ pub async fn switch_provider(
         }
     }
 
-    log::info!("成功切换到供应商: {}", provider.name);
-
-    // 保存配置
-    drop(config); // 释放锁
-    state.save()?;
+    tracing::info!("成功切换到供应商: {}", provider.name);
 
-    Ok(true)
+    config.save()
 }
 
 /// 导入当前配置为默认供应商
@@ -518,7 +1051,7 @@ pub async fn import_default_config(
     app_type: Option<AppType>,
     app: Option<String>,
     appType: Option<String>,
-) -> Result<bool, String> {
+) -> Result<bool, CcSwitchError> {
     let app_type = app_type
         .or_else(|| app.as_deref().map(|s| s.into()))
         .or_else(|| appType.as_deref().map(|s| s.into()))
@@ -529,7 +1062,7 @@ pub async fn import_default_config(
         let config = state
             .config
             .lock()
-            .map_err(|e| format!("获取锁失败: {}", e))?;
+            .map_err(|e| t_args("errors.lockFailed", &[("error", &e)]))?;
 
         if let Some(manager) = config.get_manager(&app_type) {
             if !manager.get_all_providers().is_empty() {
@@ -544,42 +1077,45 @@ pub async fn import_default_config(
         AppType::Codex => {
             let auth_path = codex_config::get_codex_auth_path();
             if !auth_path.exists() {
-                return Err("Codex 配置文件不存在".to_string());
+                return Err(t("errors.codexConfigMissing").into());
             }
             let auth: serde_json::Value =
                 crate::config::read_json_file::<serde_json::Value>(&auth_path)?;
             let config_str = match crate::codex_config::read_and_validate_codex_config_text() {
                 Ok(s) => s,
-                Err(e) => return Err(e),
+                Err(e) => return Err(e.into()),
             };
             serde_json::json!({ "auth": auth, "config": config_str })
         }
         AppType::Claude => {
             let settings_path = get_claude_settings_path();
             if !settings_path.exists() {
-                return Err("Claude Code 配置文件不存在".to_string());
+                return Err(t("errors.claudeConfigMissing").into());
             }
             crate::config::read_json_file::<serde_json::Value>(&settings_path)?
         }
     };
 
     // 创建默认供应商（仅首次初始化）
-    let provider = Provider::with_id(
+    let mut provider = Provider::with_id(
         "default".to_string(),
         "default".to_string(),
         settings_config,
         None,
     );
+    if matches!(app_type, AppType::Codex) {
+        crate::codex_config::sync_credential_kind(&mut provider);
+    }
 
     // 添加到管理器
     let mut config = state
         .config
         .lock()
-        .map_err(|e| format!("获取锁失败: {}", e))?;
+        .map_err(|e| t_args("errors.lockFailed", &[("error", &e)]))?;
 
     let manager = config
         .get_manager_mut(&app_type)
-        .ok_or_else(|| format!("应用类型不存在: {:?}", app_type))?;
+        .ok_or_else(|| t_args("errors.appTypeNotFound", &[("app", &app_type.as_str())]))?;
 
     manager.providers.insert(provider.id.clone(), provider);
     // 设置当前供应商为默认项
@@ -587,14 +1123,14 @@ pub async fn import_default_config(
 
     // 保存配置
     drop(config); // 释放锁
-    state.save()?;
+    state.save_now()?;
 
     Ok(true)
 }
 
 /// 获取 Claude Code 配置状态
 #[tauri::command]
-pub async fn get_claude_config_status() -> Result<ConfigStatus, String> {
+pub async fn get_claude_config_status() -> Result<ConfigStatus, CcSwitchError> {
     Ok(crate::config::get_claude_config_status())
 }
 
@@ -605,7 +1141,7 @@ pub async fn get_config_status(
     app_type: Option<AppType>,
     app: Option<String>,
     appType: Option<String>,
-) -> Result<ConfigStatus, String> {
+) -> Result<ConfigStatus, CcSwitchError> {
     let app = app_type
         .or_else(|| app.as_deref().map(|s| s.into()))
         .or_else(|| appType.as_deref().map(|s| s.into()))
@@ -628,7 +1164,7 @@ pub async fn get_config_status(
 
 /// 获取 Claude Code 配置文件路径
 #[tauri::command]
-pub async fn get_claude_code_config_path() -> Result<String, String> {
+pub async fn get_claude_code_config_path() -> Result<String, CcSwitchError> {
     Ok(get_claude_settings_path().to_string_lossy().to_string())
 }
 
@@ -638,7 +1174,7 @@ pub async fn get_config_dir(
     app_type: Option<AppType>,
     app: Option<String>,
     appType: Option<String>,
-) -> Result<String, String> {
+) -> Result<String, CcSwitchError> {
     let app = app_type
         .or_else(|| app.as_deref().map(|s| s.into()))
         .or_else(|| appType.as_deref().map(|s| s.into()))
@@ -660,7 +1196,7 @@ pub async fn open_config_folder(
     app_type: Option<AppType>,
     app: Option<String>,
     appType: Option<String>,
-) -> Result<bool, String> {
+) -> Result<bool, CcSwitchError> {
     let app_type = app_type
         .or_else(|| app.as_deref().map(|s| s.into()))
         .or_else(|| appType.as_deref().map(|s| s.into()))
@@ -690,7 +1226,7 @@ pub async fn open_config_folder(
 pub async fn pick_directory(
     app: tauri::AppHandle,
     default_path: Option<String>,
-) -> Result<Option<String>, String> {
+) -> Result<Option<String>, CcSwitchError> {
     let initial = default_path
         .map(|p| p.trim().to_string())
         .filter(|p| !p.is_empty());
@@ -719,7 +1255,7 @@ pub async fn pick_directory(
 
 /// 打开外部链接
 #[tauri::command]
-pub async fn open_external(app: tauri::AppHandle, url: String) -> Result<bool, String> {
+pub async fn open_external(app: tauri::AppHandle, url: String) -> Result<bool, CcSwitchError> {
     // 规范化 URL，缺少协议时默认加 https://
     let url = if url.starts_with("http://") || url.starts_with("https://") {
         url
@@ -735,18 +1271,123 @@ pub async fn open_external(app: tauri::AppHandle, url: String) -> Result<bool, S
     Ok(true)
 }
 
-/// 获取应用配置文件路径
+/// 拼接供应商控制台地址：website_url 加上按 meta.dashboard_query 模板渲染的查询参数
+fn provider_dashboard_url(provider: &Provider, app_type: &AppType) -> Result<String, String> {
+    let website = provider
+        .website_url
+        .as_deref()
+        .map(str::trim)
+        .filter(|url| !url.is_empty())
+        .ok_or_else(|| t_args("errors.providerWebsiteMissing", &[("name", &provider.name)]))?;
+    // 与 open_external 一致，缺少协议时默认加 https://
+    let website = if website.starts_with("http://") || website.starts_with("https://") {
+        website.to_string()
+    } else {
+        format!("https://{}", website)
+    };
+    let mut url = url::Url::parse(&website)
+        .map_err(|e| t_args("errors.providerWebsiteInvalid", &[("error", &e)]))?;
+
+    let template = provider
+        .meta
+        .as_ref()
+        .and_then(|m| m.dashboard_query.as_deref())
+        .map(|q| q.trim().trim_start_matches(['?', '&']))
+        .filter(|q| !q.is_empty());
+    let Some(template) = template else {
+        return Ok(url.into());
+    };
+
+    // 仅在模板引用时读取凭证；替换值做 URL 编码，避免 Key 中的特殊字符破坏查询串
+    let query = if template.contains("{{apiKey}}") || template.contains("{{baseUrl}}") {
+        let (api_key, base_url) = extract_credentials(provider, app_type)?;
+        let encode =
+            |s: &str| url::form_urlencoded::byte_serialize(s.as_bytes()).collect::<String>();
+        template
+            .replace("{{apiKey}}", &encode(&api_key))
+            .replace("{{baseUrl}}", &encode(&base_url))
+    } else {
+        template.to_string()
+    };
+    let merged = match url.query() {
+        Some(existing) if !existing.is_empty() => format!("{}&{}", existing, query),
+        _ => query,
+    };
+    url.set_query(Some(&merged));
+    Ok(url.into())
+}
+
+/// 打开供应商的网站控制台（按配置附带登录 Token 等查询参数）
+#[tauri::command]
+pub async fn open_provider_dashboard(
+    handle: tauri::AppHandle,
+    state: State<'_, AppState>,
+    id: String,
+    app_type: Option<AppType>,
+    app: Option<String>,
+    #[allow(non_snake_case)] appType: Option<String>,
+) -> Result<bool, CcSwitchError> {
+    let app_type = app_type
+        .or_else(|| app.as_deref().map(|s| s.into()))
+        .or_else(|| appType.as_deref().map(|s| s.into()))
+        .unwrap_or(AppType::Claude);
+
+    let url = {
+        let config = state
+            .config
+            .lock()
+            .map_err(|e| t_args("errors.lockFailed", &[("error", &e)]))?;
+        let provider = config
+            .get_manager(&app_type)
+            .and_then(|m| m.providers.get(&id))
+            .ok_or_else(|| t_args("errors.providerNotFound", &[("id", &id)]))?;
+        provider_dashboard_url(provider, &app_type)?
+    };
+
+    handle
+        .opener()
+        .open_url(&url, None::<String>)
+        .map_err(|e| format!("打开链接失败: {}", e))?;
+
+    Ok(true)
+}
+
+/// 获取尚未确认的旧版布局迁移报告（供首次启动向导展示）
+#[tauri::command]
+pub async fn get_migration_report(
+) -> Result<Option<crate::migration::MigrationReport>, CcSwitchError> {
+    Ok(crate::migration::load_report().filter(|report| !report.acknowledged))
+}
+
+/// 确认迁移报告，之后不再提示
+#[tauri::command]
+pub async fn acknowledge_migration_report() -> Result<bool, CcSwitchError> {
+    let Some(mut report) = crate::migration::load_report() else {
+        return Ok(false);
+    };
+    report.acknowledged = true;
+    crate::migration::save_report(&report)?;
+    Ok(true)
+}
+
+/// 获取最近的日志行（供应用内日志查看器使用）
+#[tauri::command]
+pub async fn get_recent_logs(lines: Option<usize>) -> Result<Vec<String>, CcSwitchError> {
+    Ok(crate::logging::read_recent_logs(lines)?)
+}
+
+/// 获取应用配置存储路径（cc-switch.db）
 #[tauri::command]
-pub async fn get_app_config_path() -> Result<String, String> {
-    use crate::config::get_app_config_path;
+pub async fn get_app_config_path() -> Result<String, CcSwitchError> {
+    use crate::config::get_app_db_path;
 
-    let config_path = get_app_config_path();
+    let config_path = get_app_db_path();
     Ok(config_path.to_string_lossy().to_string())
 }
 
 /// 打开应用配置文件夹
 #[tauri::command]
-pub async fn open_app_config_folder(handle: tauri::AppHandle) -> Result<bool, String> {
+pub async fn open_app_config_folder(handle: tauri::AppHandle) -> Result<bool, CcSwitchError> {
     use crate::config::get_app_config_dir;
 
     let config_dir = get_app_config_dir();
@@ -771,32 +1412,35 @@ pub async fn open_app_config_folder(handle: tauri::AppHandle) -> Result<bool, St
 
 /// 获取 Claude MCP 状态（settings.local.json 与 mcp.json）
 #[tauri::command]
-pub async fn get_claude_mcp_status() -> Result<crate::claude_mcp::McpStatus, String> {
-    claude_mcp::get_mcp_status()
+pub async fn get_claude_mcp_status() -> Result<crate::claude_mcp::McpStatus, CcSwitchError> {
+    claude_mcp::get_mcp_status().map_err(Into::into)
 }
 
 /// 读取 mcp.json 文本内容（不存在则返回 Ok(None)）
 #[tauri::command]
-pub async fn read_claude_mcp_config() -> Result<Option<String>, String> {
-    claude_mcp::read_mcp_json()
+pub async fn read_claude_mcp_config() -> Result<Option<String>, CcSwitchError> {
+    claude_mcp::read_mcp_json().map_err(Into::into)
 }
 
 /// 新增或更新一个 MCP 服务器条目
 #[tauri::command]
-pub async fn upsert_claude_mcp_server(id: String, spec: serde_json::Value) -> Result<bool, String> {
-    claude_mcp::upsert_mcp_server(&id, spec)
+pub async fn upsert_claude_mcp_server(
+    id: String,
+    spec: serde_json::Value,
+) -> Result<bool, CcSwitchError> {
+    claude_mcp::upsert_mcp_server(&id, spec).map_err(Into::into)
 }
 
 /// 删除一个 MCP 服务器条目
 #[tauri::command]
-pub async fn delete_claude_mcp_server(id: String) -> Result<bool, String> {
-    claude_mcp::delete_mcp_server(&id)
+pub async fn delete_claude_mcp_server(id: String) -> Result<bool, CcSwitchError> {
+    claude_mcp::delete_mcp_server(&id).map_err(Into::into)
 }
 
 /// 校验命令是否在 PATH 中可用（不执行）
 #[tauri::command]
-pub async fn validate_mcp_command(cmd: String) -> Result<bool, String> {
-    claude_mcp::validate_command_in_path(&cmd)
+pub async fn validate_mcp_command(cmd: String) -> Result<bool, CcSwitchError> {
+    claude_mcp::validate_command_in_path(&cmd).map_err(Into::into)
 }
 
 // =====================
@@ -806,19 +1450,20 @@ pub async fn validate_mcp_command(cmd: String) -> Result<bool, String> {
 /// 查询供应商用量
 #[tauri::command]
 pub async fn query_provider_usage(
+    handle: tauri::AppHandle,
     state: State<'_, AppState>,
     provider_id: Option<String>,
     providerId: Option<String>,
     app_type: Option<AppType>,
     app: Option<String>,
     appType: Option<String>,
-) -> Result<crate::provider::UsageResult, String> {
+) -> Result<crate::provider::UsageResult, CcSwitchError> {
     use crate::provider::{UsageData, UsageResult};
 
     // 解析参数
     let provider_id = provider_id
         .or(providerId)
-        .ok_or("缺少 providerId 参数")?;
+        .ok_or_else(|| t_args("errors.missingParam", &[("name", &"providerId")]))?;
 
     let app_type = app_type
         .or_else(|| app.as_deref().map(|s| s.into()))
@@ -826,41 +1471,72 @@ pub async fn query_provider_usage(
         .unwrap_or(AppType::Claude);
 
     // 1. 获取供应商配置并克隆所需数据
-    let (api_key, base_url, usage_script_code, timeout) = {
+    let (
+        api_key,
+        base_url,
+        usage_script_code,
+        timeout,
+        allowed_hosts,
+        cookie_scope,
+        alert,
+        client_cert,
+    ) = {
         let config = state
             .config
             .lock()
-            .map_err(|e| format!("获取锁失败: {}", e))?;
+            .map_err(|e| t_args("errors.lockFailed", &[("error", &e)]))?;
 
         let manager = config
             .get_manager(&app_type)
-            .ok_or("应用类型不存在")?;
+            .ok_or_else(|| t_args("errors.appTypeNotFound", &[("app", &app_type.as_str())]))?;
 
         let provider = manager
             .providers
             .get(&provider_id)
-            .ok_or("供应商不存在")?;
+            .ok_or_else(|| t_args("errors.providerNotFound", &[("id", &provider_id)]))?;
+
+        // 已停用的供应商不参与用量查询
+        if !provider.is_enabled() {
+            return Err(t_args("errors.providerDisabledUsage", &[("name", &provider.name)]).into());
+        }
 
         // 2. 检查脚本配置
         let usage_script = provider
             .meta
             .as_ref()
             .and_then(|m| m.usage_script.as_ref())
-            .ok_or("未配置用量查询脚本")?;
+            .ok_or_else(|| t("errors.usageScriptMissing"))?;
 
         if !usage_script.enabled {
-            return Err("用量查询未启用".to_string());
+            return Err(t("errors.usageQueryDisabled").into());
         }
 
         // 3. 提取凭证和脚本配置
         let (api_key, base_url) = extract_credentials(provider, &app_type)?;
         let timeout = usage_script.timeout.unwrap_or(10);
         let code = usage_script.code.clone();
+        let allowed_hosts = usage_script.allowed_hosts.clone();
+        let cookie_scope = usage_script.cookie_scope(&app_type, &provider_id);
+        let alert = provider
+            .meta
+            .as_ref()
+            .and_then(|m| m.balance_alert.clone())
+            .map(|a| (provider.name.clone(), a));
+        let client_cert = provider_client_cert(provider);
 
         // 显式释放锁
         drop(config);
 
-        (api_key, base_url, code, timeout)
+        (
+            api_key,
+            base_url,
+            code,
+            timeout,
+            allowed_hosts,
+            cookie_scope,
+            alert,
+            client_cert,
+        )
     };
 
     // 5. 执行脚本
@@ -869,6 +1545,9 @@ pub async fn query_provider_usage(
         &api_key,
         &base_url,
         timeout,
+        &allowed_hosts,
+        cookie_scope.as_deref(),
+        client_cert.as_ref(),
     )
     .await;
 
@@ -878,33 +1557,124 @@ pub async fn query_provider_usage(
             // 尝试解析为数组
             let usage_list: Vec<UsageData> = if data.is_array() {
                 // 直接解析为数组
-                serde_json::from_value(data)
-                    .map_err(|e| format!("数据格式错误: {}", e))?
+                serde_json::from_value(data).map_err(|e| {
+                    CcSwitchError::Script(t_args("errors.usageDataInvalid", &[("error", &e)]))
+                })?
             } else {
                 // 单对象包装为数组（向后兼容）
-                let single: UsageData = serde_json::from_value(data)
-                    .map_err(|e| format!("数据格式错误: {}", e))?;
+                let single: UsageData = serde_json::from_value(data).map_err(|e| {
+                    CcSwitchError::Script(t_args("errors.usageDataInvalid", &[("error", &e)]))
+                })?;
                 vec![single]
             };
 
+            // 7. 评估低余额告警
+            if let Some((provider_name, alert)) = alert.as_ref() {
+                crate::usage_alert::evaluate(
+                    &handle,
+                    &app_type,
+                    &provider_id,
+                    provider_name,
+                    alert,
+                    &usage_list,
+                );
+            }
+            if let Ok(config) = state.config.lock() {
+                if matches!(app_type, AppType::Claude) {
+                    crate::statusline::record_usage(&config, &provider_id, &usage_list);
+                }
+                crate::current_status::record_usage(&config, &app_type, &provider_id, &usage_list);
+            }
+
             Ok(UsageResult {
                 success: true,
                 data: Some(usage_list),
                 error: None,
             })
         }
-        Err(e) => {
-            Ok(UsageResult {
-                success: false,
-                data: None,
-                error: Some(e),
-            })
-        }
+        Err(e) => Ok(UsageResult {
+            success: false,
+            data: None,
+            error: Some(e),
+        }),
     }
 }
 
+/// 调试用量脚本：使用供应商凭证执行（未保存的）脚本，返回逐阶段追踪信息
+#[tauri::command]
+pub async fn test_usage_script(
+    state: State<'_, AppState>,
+    provider_id: Option<String>,
+    providerId: Option<String>,
+    app_type: Option<AppType>,
+    app: Option<String>,
+    appType: Option<String>,
+    script: crate::provider::UsageScript,
+) -> Result<crate::usage_script::UsageScriptTrace, CcSwitchError> {
+    // 脚本与允许访问的主机由调用方提供，会带着已保存的密钥发出请求，锁定时拒绝
+    crate::app_lock::ensure_unlocked()?;
+    let provider_id = provider_id
+        .or(providerId)
+        .ok_or_else(|| t_args("errors.missingParam", &[("name", &"providerId")]))?;
+
+    let app_type = app_type
+        .or_else(|| app.as_deref().map(|s| s.into()))
+        .or_else(|| appType.as_deref().map(|s| s.into()))
+        .unwrap_or(AppType::Claude);
+
+    let (api_key, base_url, client_cert) = {
+        let config = state
+            .config
+            .lock()
+            .map_err(|e| t_args("errors.lockFailed", &[("error", &e)]))?;
+
+        let provider = config
+            .get_manager(&app_type)
+            .ok_or_else(|| t_args("errors.appTypeNotFound", &[("app", &app_type.as_str())]))?
+            .providers
+            .get(&provider_id)
+            .ok_or_else(|| t_args("errors.providerNotFound", &[("id", &provider_id)]))?;
+
+        let (api_key, base_url) = extract_credentials(provider, &app_type)?;
+        (api_key, base_url, provider_client_cert(provider))
+    };
+
+    Ok(crate::usage_script::trace_usage_script(
+        &script.code,
+        &api_key,
+        &base_url,
+        script.timeout.unwrap_or(10),
+        &script.allowed_hosts,
+        script.cookie_scope(&app_type, &provider_id).as_deref(),
+        client_cert.as_ref(),
+    )
+    .await)
+}
+
+/// 清除供应商用量脚本保存的 Cookie 会话
+#[tauri::command]
+pub async fn clear_usage_script_cookies(
+    provider_id: Option<String>,
+    providerId: Option<String>,
+    app_type: Option<AppType>,
+    app: Option<String>,
+    appType: Option<String>,
+) -> Result<bool, CcSwitchError> {
+    let provider_id = provider_id
+        .or(providerId)
+        .ok_or_else(|| t_args("errors.missingParam", &[("name", &"providerId")]))?;
+
+    let app_type = app_type
+        .or_else(|| app.as_deref().map(|s| s.into()))
+        .or_else(|| appType.as_deref().map(|s| s.into()))
+        .unwrap_or(AppType::Claude);
+
+    crate::usage_client::clear_jar(&format!("{}:{}", app_type.as_str(), provider_id))
+        .map_err(CcSwitchError::Script)
+}
+
 /// 从供应商配置中提取 API Key 和 Base URL
-fn extract_credentials(
+pub(crate) fn extract_credentials(
     provider: &crate::provider::Provider,
     app_type: &AppType,
 ) -> Result<(String, String), String> {
@@ -914,18 +1684,18 @@ fn extract_credentials(
                 .settings_config
                 .get("env")
                 .and_then(|v| v.as_object())
-                .ok_or("配置格式错误: 缺少 env")?;
+                .ok_or_else(|| t("errors.missingEnv"))?;
 
             let api_key = env
                 .get("ANTHROPIC_AUTH_TOKEN")
                 .and_then(|v| v.as_str())
-                .ok_or("缺少 API Key")?
+                .ok_or_else(|| t("errors.missingApiKey"))?
                 .to_string();
 
             let base_url = env
                 .get("ANTHROPIC_BASE_URL")
                 .and_then(|v| v.as_str())
-                .ok_or("缺少 ANTHROPIC_BASE_URL 配置")?
+                .ok_or_else(|| t("errors.missingAnthropicBaseUrl"))?
                 .to_string();
 
             Ok((api_key, base_url))
@@ -935,12 +1705,12 @@ fn extract_credentials(
                 .settings_config
                 .get("auth")
                 .and_then(|v| v.as_object())
-                .ok_or("配置格式错误: 缺少 auth")?;
+                .ok_or_else(|| t("errors.missingAuth"))?;
 
             let api_key = auth
                 .get("OPENAI_API_KEY")
                 .and_then(|v| v.as_str())
-                .ok_or("缺少 API Key")?
+                .ok_or_else(|| t("errors.missingApiKey"))?
                 .to_string();
 
             // 从 config TOML 中提取 base_url
@@ -955,9 +1725,9 @@ fn extract_credentials(
                 re.captures(config_toml)
                     .and_then(|caps| caps.get(1))
                     .map(|m| m.as_str().to_string())
-                    .ok_or("config.toml 中 base_url 格式错误")?
+                    .ok_or_else(|| t("errors.codexBaseUrlInvalid"))?
             } else {
-                return Err("config.toml 中缺少 base_url 配置".to_string());
+                return Err(t("errors.codexBaseUrlMissing"));
             };
 
             Ok((api_key, base_url))
@@ -965,6 +1735,18 @@ fn extract_credentials(
     }
 }
 
+/// 供应商配置的 mTLS 客户端证书（未填写完整时视为未配置）
+fn provider_client_cert(
+    provider: &crate::provider::Provider,
+) -> Option<crate::provider::ClientCertificate> {
+    provider
+        .meta
+        .as_ref()?
+        .client_cert
+        .clone()
+        .filter(crate::provider::ClientCertificate::is_configured)
+}
+
 // =====================
 // 新：集中以 config.json 为 SSOT 的 MCP 配置命令
 // =====================
@@ -975,25 +1757,25 @@ pub struct McpConfigResponse {
     pub servers: std::collections::HashMap<String, serde_json::Value>,
 }
 
-/// 获取 MCP 配置（来自 ~/.cc-switch/config.json）
+/// 获取 MCP 配置（来自 ~/.cc-switch/cc-switch.db）
 #[tauri::command]
 pub async fn get_mcp_config(
     state: State<'_, AppState>,
     app: Option<String>,
-) -> Result<McpConfigResponse, String> {
-    let config_path = crate::config::get_app_config_path()
+) -> Result<McpConfigResponse, CcSwitchError> {
+    let config_path = crate::config::get_app_db_path()
         .to_string_lossy()
         .to_string();
     let mut cfg = state
         .config
         .lock()
-        .map_err(|e| format!("获取锁失败: {}", e))?;
+        .map_err(|e| t_args("errors.lockFailed", &[("error", &e)]))?;
     let app_ty = crate::app_config::AppType::from(app.as_deref().unwrap_or("claude"));
     let (servers, normalized) = crate::mcp::get_servers_snapshot_for(&mut cfg, &app_ty);
     let need_save = normalized > 0;
     drop(cfg);
     if need_save {
-        state.save()?;
+        state.save_now()?;
     }
     Ok(McpConfigResponse {
         config_path,
@@ -1009,11 +1791,11 @@ pub async fn upsert_mcp_server_in_config(
     id: String,
     spec: serde_json::Value,
     sync_other_side: Option<bool>,
-) -> Result<bool, String> {
+) -> Result<bool, CcSwitchError> {
     let mut cfg = state
         .config
         .lock()
-        .map_err(|e| format!("获取锁失败: {}", e))?;
+        .map_err(|e| t_args("errors.lockFailed", &[("error", &e)]))?;
     let app_ty = crate::app_config::AppType::from(app.as_deref().unwrap_or("claude"));
     let mut sync_targets: Vec<crate::app_config::AppType> = Vec::new();
 
@@ -1049,12 +1831,12 @@ pub async fn upsert_mcp_server_in_config(
         }
     }
     drop(cfg);
-    state.save()?;
+    state.save_now()?;
 
     let cfg2 = state
         .config
         .lock()
-        .map_err(|e| format!("获取锁失败: {}", e))?;
+        .map_err(|e| t_args("errors.lockFailed", &[("error", &e)]))?;
     for app_ty_to_sync in sync_targets {
         match app_ty_to_sync {
             crate::app_config::AppType::Claude => crate::mcp::sync_enabled_to_claude(&cfg2)?,
@@ -1070,20 +1852,20 @@ pub async fn delete_mcp_server_in_config(
     state: State<'_, AppState>,
     app: Option<String>,
     id: String,
-) -> Result<bool, String> {
+) -> Result<bool, CcSwitchError> {
     let mut cfg = state
         .config
         .lock()
-        .map_err(|e| format!("获取锁失败: {}", e))?;
+        .map_err(|e| t_args("errors.lockFailed", &[("error", &e)]))?;
     let app_ty = crate::app_config::AppType::from(app.as_deref().unwrap_or("claude"));
     let existed = crate::mcp::delete_in_config_for(&mut cfg, &app_ty, &id)?;
     drop(cfg);
-    state.save()?;
+    state.save_now()?;
     // 若删除的是 Claude/Codex 客户端的条目，则同步一次，确保启用项从对应 live 配置中移除
     let cfg2 = state
         .config
         .lock()
-        .map_err(|e| format!("获取锁失败: {}", e))?;
+        .map_err(|e| t_args("errors.lockFailed", &[("error", &e)]))?;
     match app_ty {
         crate::app_config::AppType::Claude => crate::mcp::sync_enabled_to_claude(&cfg2)?,
         crate::app_config::AppType::Codex => crate::mcp::sync_enabled_to_codex(&cfg2)?,
@@ -1098,78 +1880,78 @@ pub async fn set_mcp_enabled(
     app: Option<String>,
     id: String,
     enabled: bool,
-) -> Result<bool, String> {
+) -> Result<bool, CcSwitchError> {
     let mut cfg = state
         .config
         .lock()
-        .map_err(|e| format!("获取锁失败: {}", e))?;
+        .map_err(|e| t_args("errors.lockFailed", &[("error", &e)]))?;
     let app_ty = crate::app_config::AppType::from(app.as_deref().unwrap_or("claude"));
     let changed = crate::mcp::set_enabled_and_sync_for(&mut cfg, &app_ty, &id, enabled)?;
     drop(cfg);
-    state.save()?;
+    state.save_now()?;
     Ok(changed)
 }
 
 /// 手动同步：将启用的 MCP 投影到 ~/.claude.json（不更改 config.json）
 #[tauri::command]
-pub async fn sync_enabled_mcp_to_claude(state: State<'_, AppState>) -> Result<bool, String> {
+pub async fn sync_enabled_mcp_to_claude(state: State<'_, AppState>) -> Result<bool, CcSwitchError> {
     let mut cfg = state
         .config
         .lock()
-        .map_err(|e| format!("获取锁失败: {}", e))?;
+        .map_err(|e| t_args("errors.lockFailed", &[("error", &e)]))?;
     let normalized = crate::mcp::normalize_servers_for(&mut cfg, &AppType::Claude);
     crate::mcp::sync_enabled_to_claude(&cfg)?;
     let need_save = normalized > 0;
     drop(cfg);
     if need_save {
-        state.save()?;
+        state.save_now()?;
     }
     Ok(true)
 }
 
 /// 手动同步：将启用的 MCP 投影到 ~/.codex/config.toml（不更改 config.json）
 #[tauri::command]
-pub async fn sync_enabled_mcp_to_codex(state: State<'_, AppState>) -> Result<bool, String> {
+pub async fn sync_enabled_mcp_to_codex(state: State<'_, AppState>) -> Result<bool, CcSwitchError> {
     let mut cfg = state
         .config
         .lock()
-        .map_err(|e| format!("获取锁失败: {}", e))?;
+        .map_err(|e| t_args("errors.lockFailed", &[("error", &e)]))?;
     let normalized = crate::mcp::normalize_servers_for(&mut cfg, &AppType::Codex);
     crate::mcp::sync_enabled_to_codex(&cfg)?;
     let need_save = normalized > 0;
     drop(cfg);
     if need_save {
-        state.save()?;
+        state.save_now()?;
     }
     Ok(true)
 }
 
 /// 从 ~/.claude.json 导入 MCP 定义到 config.json，返回变更数量
 #[tauri::command]
-pub async fn import_mcp_from_claude(state: State<'_, AppState>) -> Result<usize, String> {
+pub async fn import_mcp_from_claude(state: State<'_, AppState>) -> Result<usize, CcSwitchError> {
     let mut cfg = state
         .config
         .lock()
-        .map_err(|e| format!("获取锁失败: {}", e))?;
+        .map_err(|e| t_args("errors.lockFailed", &[("error", &e)]))?;
     let changed = crate::mcp::import_from_claude(&mut cfg)?;
     drop(cfg);
     if changed > 0 {
-        state.save()?;
+        state.save_now()?;
     }
     Ok(changed)
 }
 
 /// 从 ~/.codex/config.toml 导入 MCP 定义到 config.json（Codex 作用域），返回变更数量
 #[tauri::command]
-pub async fn import_mcp_from_codex(state: State<'_, AppState>) -> Result<usize, String> {
+pub async fn import_mcp_from_codex(state: State<'_, AppState>) -> Result<usize, CcSwitchError> {
     let mut cfg = state
         .config
         .lock()
-        .map_err(|e| format!("获取锁失败: {}", e))?;
+        .map_err(|e| t_args("errors.lockFailed", &[("error", &e)]))?;
     let changed = crate::mcp::import_from_codex(&mut cfg)?;
     drop(cfg);
     if changed > 0 {
-        state.save()?;
+        state.save_now()?;
     }
     Ok(changed)
 }
@@ -1182,7 +1964,7 @@ pub async fn read_live_provider_settings(
     app_type: Option<AppType>,
     app: Option<String>,
     appType: Option<String>,
-) -> Result<serde_json::Value, String> {
+) -> Result<serde_json::Value, CcSwitchError> {
     let app_type = app_type
         .or_else(|| app.as_deref().map(|s| s.into()))
         .or_else(|| appType.as_deref().map(|s| s.into()))
@@ -1192,46 +1974,126 @@ pub async fn read_live_provider_settings(
         AppType::Codex => {
             let auth_path = crate::codex_config::get_codex_auth_path();
             if !auth_path.exists() {
-                return Err("Codex 配置文件不存在：缺少 auth.json".to_string());
+                return Err(t("errors.codexAuthMissing").into());
             }
             let auth: serde_json::Value = crate::config::read_json_file(&auth_path)?;
             let cfg_text = crate::codex_config::read_and_validate_codex_config_text()?;
-            Ok(serde_json::json!({ "auth": auth, "config": cfg_text }))
+            let live = serde_json::json!({ "auth": auth, "config": cfg_text });
+            Ok(crate::redact::redact_settings(&app_type, &live))
         }
         AppType::Claude => {
             let path = crate::config::get_claude_settings_path();
             if !path.exists() {
-                return Err("Claude Code 配置文件不存在".to_string());
+                return Err(t("errors.claudeConfigMissing").into());
             }
             let v: serde_json::Value = crate::config::read_json_file(&path)?;
-            Ok(v)
+            Ok(crate::redact::redact_settings(&app_type, &v))
         }
     }
 }
 
 /// 获取设置
 #[tauri::command]
-pub async fn get_settings() -> Result<crate::settings::AppSettings, String> {
+pub async fn get_settings() -> Result<crate::settings::AppSettings, CcSwitchError> {
     Ok(crate::settings::get_settings())
 }
 
 /// 保存设置
 #[tauri::command]
-pub async fn save_settings(settings: crate::settings::AppSettings) -> Result<bool, String> {
+pub async fn save_settings(
+    app: tauri::AppHandle,
+    state: State<'_, AppState>,
+    settings: crate::settings::AppSettings,
+) -> Result<bool, CcSwitchError> {
+    // 运行模式与钩子（会执行任意命令）变更属于受保护操作
+    let previous = crate::settings::get_settings();
+    if settings.operation_mode != previous.operation_mode || settings.hooks != previous.hooks {
+        crate::app_lock::ensure_unlocked()?;
+    }
+    #[cfg(desktop)]
+    let hotkeys = settings.hotkeys.clone();
+    let audit_retention_days = settings.audit_retention_days;
+    let single_config_changed = settings.codex_single_config != previous.codex_single_config;
+    let statusline_changed = settings.claude_statusline != previous.claude_statusline;
+    let proxy_tls_changed = settings.proxy_tls != previous.proxy_tls;
+    let lan_share_changed = settings.lan_share != previous.lan_share;
+    if settings.lan_share.enabled && settings.lan_share.token.trim().is_empty() {
+        return Err(t("errors.lanShareTokenRequired").into());
+    }
+    let upstream_changed = settings.proxy_dns != previous.proxy_dns
+        || settings.proxy_connection != previous.proxy_connection;
+    let doh_url = settings.proxy_dns.doh_url.as_deref();
+    if let Some(doh_url) = doh_url.filter(|url| !url.trim().is_empty()) {
+        crate::dns::validate_doh_url(doh_url)?;
+    }
+    crate::dns::validate_hosts(&settings.proxy_dns.hosts)?;
+    // 开机自启变更时先同步系统自启项，失败则不保存设置
+    #[cfg(desktop)]
+    if settings.launch_at_login != previous.launch_at_login {
+        crate::autostart::sync(&app, settings.launch_at_login)
+            .map_err(|e| t_args("errors.autostartFailed", &[("error", &e)]))?;
+    }
+    // 通用配置片段由片段管理命令维护，不随设置表单覆盖
+    let mut settings = settings;
+    settings.common_fragments = previous.common_fragments;
     crate::settings::update_settings(settings)?;
+
+    // Codex 单文件模式切换后按新方式重写 config.toml
+    if single_config_changed
+        && crate::settings::get_settings().operation_mode == crate::settings::OperationMode::Write
+    {
+        if let Err(e) = crate::self_check::reapply_live(&state, &AppType::Codex) {
+            tracing::warn!("重写 Codex 配置失败: {}", e);
+        }
+    }
+    if statusline_changed {
+        crate::statusline::sync(&state);
+    }
+    // 解析或连接设置变更：丢弃按旧设置建立的上游连接
+    if upstream_changed {
+        crate::proxy::reset_upstream_pool();
+    }
+    // 代理监听变更：协议变化时按新地址重写 live 配置，并重启代理
+    if (proxy_tls_changed || lan_share_changed)
+        && crate::settings::get_settings().operation_mode == crate::settings::OperationMode::Proxy
+    {
+        if proxy_tls_changed {
+            crate::proxy::switch_to_proxy_mode(&state)?;
+        }
+        crate::proxy::restart_proxy_server(&state).await?;
+    }
+    if let Err(e) = crate::audit::prune(audit_retention_days) {
+        tracing::warn!("清理审计日志失败: {}", e);
+    }
+
+    // 快捷键配置可能变更，重新注册
+    #[cfg(desktop)]
+    if let Err(e) = crate::hotkeys::register_hotkeys(&app, &hotkeys) {
+        tracing::warn!("重新注册全局快捷键失败: {}", e);
+    }
+
+    // 语言可能变更，按新语言重建托盘菜单
+    if let Err(e) = crate::tray::refresh_tray_menu(&app) {
+        tracing::warn!("刷新托盘菜单失败: {}", e);
+    }
+
     Ok(true)
 }
 
 /// 重启应用程序（当 app_config_dir 变更后使用）
 #[tauri::command]
-pub async fn restart_app(app: tauri::AppHandle) -> Result<bool, String> {
+pub async fn restart_app(app: tauri::AppHandle) -> Result<bool, CcSwitchError> {
+    // restart 不会触发 Exit 事件，先写入尚在去抖中的配置修改
+    if let Err(e) = app.state::<AppState>().flush() {
+        tracing::error!("重启前保存配置失败: {}", e);
+    }
     // 使用 tauri-plugin-process 重启应用
     app.restart();
 }
 
 /// 检查更新
 #[tauri::command]
-pub async fn check_for_updates(handle: tauri::AppHandle) -> Result<bool, String> {
+pub async fn check_for_updates(handle: tauri::AppHandle) -> Result<bool, CcSwitchError> {
     // 打开 GitHub releases 页面
     handle
         .opener()
@@ -1246,47 +2108,48 @@ pub async fn check_for_updates(handle: tauri::AppHandle) -> Result<bool, String>
 
 /// 判断是否为便携版（绿色版）运行
 #[tauri::command]
-pub async fn is_portable_mode() -> Result<bool, String> {
-    let exe_path = std::env::current_exe().map_err(|e| format!("获取可执行路径失败: {}", e))?;
-    if let Some(dir) = exe_path.parent() {
-        Ok(dir.join("portable.ini").is_file())
-    } else {
-        Ok(false)
-    }
+pub async fn is_portable_mode() -> Result<bool, CcSwitchError> {
+    Ok(crate::portable::is_portable())
+}
+
+/// 获取便携模式的数据目录（非便携模式返回 None）
+#[tauri::command]
+pub async fn get_portable_data_dir() -> Result<Option<String>, CcSwitchError> {
+    Ok(crate::portable::portable_data_dir().map(|p| p.to_string_lossy().to_string()))
 }
 
 /// Claude 插件：获取 ~/.claude/config.json 状态
 #[tauri::command]
-pub async fn get_claude_plugin_status() -> Result<ConfigStatus, String> {
+pub async fn get_claude_plugin_status() -> Result<ConfigStatus, CcSwitchError> {
     match claude_plugin::claude_config_status() {
         Ok((exists, path)) => Ok(ConfigStatus {
             exists,
             path: path.to_string_lossy().to_string(),
         }),
-        Err(err) => Err(err),
+        Err(err) => Err(err.into()),
     }
 }
 
 /// Claude 插件：读取配置内容（若不存在返回 Ok(None)）
 #[tauri::command]
-pub async fn read_claude_plugin_config() -> Result<Option<String>, String> {
-    claude_plugin::read_claude_config()
+pub async fn read_claude_plugin_config() -> Result<Option<String>, CcSwitchError> {
+    claude_plugin::read_claude_config().map_err(Into::into)
 }
 
 /// Claude 插件：写入/清除固定配置
 #[tauri::command]
-pub async fn apply_claude_plugin_config(official: bool) -> Result<bool, String> {
+pub async fn apply_claude_plugin_config(official: bool) -> Result<bool, CcSwitchError> {
     if official {
-        claude_plugin::clear_claude_config()
+        claude_plugin::clear_claude_config().map_err(Into::into)
     } else {
-        claude_plugin::write_claude_config()
+        claude_plugin::write_claude_config().map_err(Into::into)
     }
 }
 
 /// Claude 插件：检测是否已写入目标配置
 #[tauri::command]
-pub async fn is_claude_plugin_applied() -> Result<bool, String> {
-    claude_plugin::is_claude_config_applied()
+pub async fn is_claude_plugin_applied() -> Result<bool, CcSwitchError> {
+    claude_plugin::is_claude_config_applied().map_err(Into::into)
 }
 
 /// 测试第三方/自定义供应商端点的网络延迟
@@ -1294,12 +2157,14 @@ pub async fn is_claude_plugin_applied() -> Result<bool, String> {
 pub async fn test_api_endpoints(
     urls: Vec<String>,
     timeout_secs: Option<u64>,
-) -> Result<Vec<speedtest::EndpointLatency>, String> {
+) -> Result<Vec<speedtest::EndpointLatency>, CcSwitchError> {
     let filtered: Vec<String> = urls
         .into_iter()
         .filter(|url| !url.trim().is_empty())
         .collect();
-    speedtest::test_endpoints(filtered, timeout_secs).await
+    speedtest::test_endpoints(filtered, timeout_secs)
+        .await
+        .map_err(Into::into)
 }
 
 /// 获取自定义端点列表
@@ -1311,7 +2176,7 @@ pub async fn get_custom_endpoints(
     appType: Option<String>,
     provider_id: Option<String>,
     providerId: Option<String>,
-) -> Result<Vec<crate::settings::CustomEndpoint>, String> {
+) -> Result<Vec<crate::settings::CustomEndpoint>, CcSwitchError> {
     let app_type = app_type
         .or_else(|| app.as_deref().map(|s| s.into()))
         .or_else(|| appType.as_deref().map(|s| s.into()))
@@ -1322,11 +2187,11 @@ pub async fn get_custom_endpoints(
     let mut cfg_guard = state
         .config
         .lock()
-        .map_err(|e| format!("获取锁失败: {}", e))?;
+        .map_err(|e| t_args("errors.lockFailed", &[("error", &e)]))?;
 
     let manager = cfg_guard
         .get_manager_mut(&app_type)
-        .ok_or_else(|| format!("应用类型不存在: {:?}", app_type))?;
+        .ok_or_else(|| t_args("errors.appTypeNotFound", &[("app", &app_type.as_str())]))?;
 
     let Some(provider) = manager.providers.get_mut(&provider_id) else {
         return Ok(vec![]);
@@ -1353,7 +2218,7 @@ pub async fn add_custom_endpoint(
     provider_id: Option<String>,
     providerId: Option<String>,
     url: String,
-) -> Result<(), String> {
+) -> Result<(), CcSwitchError> {
     let app_type = app_type
         .or_else(|| app.as_deref().map(|s| s.into()))
         .or_else(|| appType.as_deref().map(|s| s.into()))
@@ -1363,19 +2228,19 @@ pub async fn add_custom_endpoint(
         .ok_or_else(|| "缺少 providerId".to_string())?;
     let normalized = url.trim().trim_end_matches('/').to_string();
     if normalized.is_empty() {
-        return Err("URL 不能为空".to_string());
+        return Err(t("errors.urlEmpty").into());
     }
 
     let mut cfg_guard = state
         .config
         .lock()
-        .map_err(|e| format!("获取锁失败: {}", e))?;
+        .map_err(|e| t_args("errors.lockFailed", &[("error", &e)]))?;
     let manager = cfg_guard
         .get_manager_mut(&app_type)
-        .ok_or_else(|| format!("应用类型不存在: {:?}", app_type))?;
+        .ok_or_else(|| t_args("errors.appTypeNotFound", &[("app", &app_type.as_str())]))?;
 
     let Some(provider) = manager.providers.get_mut(&provider_id) else {
-        return Err("供应商不存在或未选择".to_string());
+        return Err(t("errors.providerNotFoundOrUnselected").into());
     };
     let meta = provider.meta.get_or_insert_with(ProviderMeta::default);
 
@@ -1391,7 +2256,7 @@ pub async fn add_custom_endpoint(
     };
     meta.custom_endpoints.insert(normalized, endpoint);
     drop(cfg_guard);
-    state.save()?;
+    state.save_now()?;
     Ok(())
 }
 
@@ -1405,7 +2270,7 @@ pub async fn remove_custom_endpoint(
     provider_id: Option<String>,
     providerId: Option<String>,
     url: String,
-) -> Result<(), String> {
+) -> Result<(), CcSwitchError> {
     let app_type = app_type
         .or_else(|| app.as_deref().map(|s| s.into()))
         .or_else(|| appType.as_deref().map(|s| s.into()))
@@ -1418,10 +2283,10 @@ pub async fn remove_custom_endpoint(
     let mut cfg_guard = state
         .config
         .lock()
-        .map_err(|e| format!("获取锁失败: {}", e))?;
+        .map_err(|e| t_args("errors.lockFailed", &[("error", &e)]))?;
     let manager = cfg_guard
         .get_manager_mut(&app_type)
-        .ok_or_else(|| format!("应用类型不存在: {:?}", app_type))?;
+        .ok_or_else(|| t_args("errors.appTypeNotFound", &[("app", &app_type.as_str())]))?;
 
     if let Some(provider) = manager.providers.get_mut(&provider_id) {
         if let Some(meta) = provider.meta.as_mut() {
@@ -1429,7 +2294,7 @@ pub async fn remove_custom_endpoint(
         }
     }
     drop(cfg_guard);
-    state.save()?;
+    state.save_now()?;
     Ok(())
 }
 
@@ -1443,7 +2308,7 @@ pub async fn update_endpoint_last_used(
     provider_id: Option<String>,
     providerId: Option<String>,
     url: String,
-) -> Result<(), String> {
+) -> Result<(), CcSwitchError> {
     let app_type = app_type
         .or_else(|| app.as_deref().map(|s| s.into()))
         .or_else(|| appType.as_deref().map(|s| s.into()))
@@ -1456,10 +2321,10 @@ pub async fn update_endpoint_last_used(
     let mut cfg_guard = state
         .config
         .lock()
-        .map_err(|e| format!("获取锁失败: {}", e))?;
+        .map_err(|e| t_args("errors.lockFailed", &[("error", &e)]))?;
     let manager = cfg_guard
         .get_manager_mut(&app_type)
-        .ok_or_else(|| format!("应用类型不存在: {:?}", app_type))?;
+        .ok_or_else(|| t_args("errors.appTypeNotFound", &[("app", &app_type.as_str())]))?;
 
     if let Some(provider) = manager.providers.get_mut(&provider_id) {
         if let Some(meta) = provider.meta.as_mut() {
@@ -1473,13 +2338,15 @@ pub async fn update_endpoint_last_used(
         }
     }
     drop(cfg_guard);
-    state.save()?;
+    state.save_now()?;
     Ok(())
 }
 
 /// 获取 app_config_dir 覆盖配置 (从 Store)
 #[tauri::command]
-pub async fn get_app_config_dir_override(app: tauri::AppHandle) -> Result<Option<String>, String> {
+pub async fn get_app_config_dir_override(
+    app: tauri::AppHandle,
+) -> Result<Option<String>, CcSwitchError> {
     Ok(crate::app_store::get_app_config_dir_from_store(&app)
         .map(|p| p.to_string_lossy().to_string()))
 }
@@ -1489,31 +2356,26 @@ pub async fn get_app_config_dir_override(app: tauri::AppHandle) -> Result<Option
 pub async fn set_app_config_dir_override(
     app: tauri::AppHandle,
     path: Option<String>,
-) -> Result<bool, String> {
+) -> Result<bool, CcSwitchError> {
     crate::app_store::set_app_config_dir_to_store(&app, path.as_deref())?;
     Ok(true)
 }
 
-// =====================
-// Provider Sort Order Management
-// =====================
-
-#[derive(serde::Deserialize)]
-pub struct ProviderSortUpdate {
-    pub id: String,
-    #[serde(rename = "sortIndex")]
-    pub sort_index: usize,
-}
-
-/// Update sort order for multiple providers
+/// 切换供应商的代理启用状态
 #[tauri::command]
-pub async fn update_providers_sort_order(
+pub async fn toggle_proxy_provider(
     state: State<'_, AppState>,
+    provider_id: Option<String>,
+    #[allow(non_snake_case)] providerId: Option<String>,
+    enabled: bool,
     app_type: Option<AppType>,
     app: Option<String>,
-    appType: Option<String>,
-    updates: Vec<ProviderSortUpdate>,
-) -> Result<bool, String> {
+    #[allow(non_snake_case)] appType: Option<String>,
+) -> Result<bool, CcSwitchError> {
+    let provider_id = provider_id
+        .or(providerId)
+        .ok_or_else(|| "缺少 provider_id 参数".to_string())?;
+
     let app_type = app_type
         .or_else(|| app.as_deref().map(|s| s.into()))
         .or_else(|| appType.as_deref().map(|s| s.into()))
@@ -1522,64 +2384,163 @@ pub async fn update_providers_sort_order(
     let mut config = state
         .config
         .lock()
-        .map_err(|e| format!("获取锁失败: {}", e))?;
+        .map_err(|e| t_args("errors.lockFailed", &[("error", &e)]))?;
 
     let manager = config
         .get_manager_mut(&app_type)
-        .ok_or_else(|| format!("应用类型不存在: {:?}", app_type))?;
+        .ok_or_else(|| t_args("errors.appTypeNotFound", &[("app", &app_type.as_str())]))?;
 
-    // Update sort_index for each provider
-    for update in updates {
-        if let Some(provider) = manager.providers.get_mut(&update.id) {
-            provider.sort_index = Some(update.sort_index);
-        }
+    if let Some(provider) = manager.providers.get_mut(&provider_id) {
+        provider.proxy_enabled = Some(enabled);
+    } else {
+        return Err(t_args("errors.providerNotFound", &[("id", &provider_id)]).into());
     }
 
     drop(config);
-    state.save()?;
-
+    state.save_now()?;
     Ok(true)
 }
 
-/// 切换供应商的代理启用状态
+/// 启用/停用供应商（停用后保留在列表中，不可切换、不参与代理与用量刷新）
 #[tauri::command]
-pub async fn toggle_proxy_provider(
+pub async fn set_provider_enabled(
+    handle: tauri::AppHandle,
     state: State<'_, AppState>,
-    provider_id: Option<String>,
-    #[allow(non_snake_case)]
-    providerId: Option<String>,
+    provider_id: String,
     enabled: bool,
     app_type: Option<AppType>,
     app: Option<String>,
-    #[allow(non_snake_case)]
-    appType: Option<String>,
-) -> Result<bool, String> {
-    let provider_id = provider_id
-        .or(providerId)
-        .ok_or_else(|| "缺少 provider_id 参数".to_string())?;
-
+    #[allow(non_snake_case)] appType: Option<String>,
+) -> Result<bool, CcSwitchError> {
     let app_type = app_type
         .or_else(|| app.as_deref().map(|s| s.into()))
         .or_else(|| appType.as_deref().map(|s| s.into()))
         .unwrap_or(AppType::Claude);
 
-    let mut config = state
-        .config
-        .lock()
-        .map_err(|e| format!("获取锁失败: {}", e))?;
+    let name = {
+        let mut config = state
+            .config
+            .lock()
+            .map_err(|e| t_args("errors.lockFailed", &[("error", &e)]))?;
+        let manager = config
+            .get_manager_mut(&app_type)
+            .ok_or_else(|| t_args("errors.appTypeNotFound", &[("app", &app_type.as_str())]))?;
+        let provider = manager
+            .providers
+            .get_mut(&provider_id)
+            .ok_or_else(|| t_args("errors.providerNotFound", &[("id", &provider_id)]))?;
+        // 默认即启用，启用时移除字段以保持配置文件简洁
+        provider.enabled = if enabled { None } else { Some(false) };
+        provider.name.clone()
+    };
+    state.save_now()?;
+    let action = if enabled {
+        AuditAction::ProviderEnabled
+    } else {
+        AuditAction::ProviderDisabled
+    };
+    crate::audit::record(action, Some(&app_type), Some(&name), None);
 
-    let manager = config
-        .get_manager_mut(&app_type)
-        .ok_or_else(|| format!("应用类型不存在: {:?}", app_type))?;
+    if let Err(e) = crate::tray::refresh_tray_menu(&handle) {
+        tracing::warn!("刷新托盘菜单失败: {}", e);
+    }
+    Ok(true)
+}
 
-    if let Some(provider) = manager.providers.get_mut(&provider_id) {
-        provider.proxy_enabled = Some(enabled);
+/// 锁定/解锁供应商（锁定后拒绝编辑与删除；解锁属于受保护操作）
+#[tauri::command]
+pub async fn set_provider_locked(
+    handle: tauri::AppHandle,
+    state: State<'_, AppState>,
+    provider_id: String,
+    locked: bool,
+    app_type: Option<AppType>,
+    app: Option<String>,
+    #[allow(non_snake_case)] appType: Option<String>,
+) -> Result<bool, CcSwitchError> {
+    let app_type = app_type
+        .or_else(|| app.as_deref().map(|s| s.into()))
+        .or_else(|| appType.as_deref().map(|s| s.into()))
+        .unwrap_or(AppType::Claude);
+    if !locked {
+        crate::app_lock::ensure_unlocked()?;
+    }
+
+    let name = {
+        let mut config = state
+            .config
+            .lock()
+            .map_err(|e| t_args("errors.lockFailed", &[("error", &e)]))?;
+        let manager = config
+            .get_manager_mut(&app_type)
+            .ok_or_else(|| t_args("errors.appTypeNotFound", &[("app", &app_type.as_str())]))?;
+        let provider = manager
+            .providers
+            .get_mut(&provider_id)
+            .ok_or_else(|| t_args("errors.providerNotFound", &[("id", &provider_id)]))?;
+        // 托管供应商只读；托管源已移除后允许解锁，以便清理遗留条目
+        if !locked
+            && provider.is_managed()
+            && crate::settings::get_settings().managed_source.is_some()
+        {
+            return Err(t_args("errors.providerManaged", &[("name", &provider.name)]).into());
+        }
+        provider.locked = locked.then_some(true);
+        provider.name.clone()
+    };
+    state.save_now()?;
+    let action = if locked {
+        AuditAction::ProviderLocked
     } else {
-        return Err(format!("供应商不存在: {}", provider_id));
+        AuditAction::ProviderUnlocked
+    };
+    crate::audit::record(action, Some(&app_type), Some(&name), None);
+
+    if let Err(e) = crate::tray::refresh_tray_menu(&handle) {
+        tracing::warn!("刷新托盘菜单失败: {}", e);
     }
+    Ok(true)
+}
 
-    drop(config);
-    state.save()?;
+/// 置顶/取消置顶供应商：置顶后在代理故障转移中始终优先尝试，
+/// 便于某个中转故障期间临时优先使用另一个，而无需调整整个列表的顺序
+#[tauri::command]
+pub async fn set_provider_pinned(
+    state: State<'_, AppState>,
+    provider_id: String,
+    pinned: bool,
+    app_type: Option<AppType>,
+    app: Option<String>,
+    #[allow(non_snake_case)] appType: Option<String>,
+) -> Result<bool, CcSwitchError> {
+    let app_type = app_type
+        .or_else(|| app.as_deref().map(|s| s.into()))
+        .or_else(|| appType.as_deref().map(|s| s.into()))
+        .unwrap_or(AppType::Claude);
+
+    let name = {
+        let mut config = state
+            .config
+            .lock()
+            .map_err(|e| t_args("errors.lockFailed", &[("error", &e)]))?;
+        let manager = config
+            .get_manager_mut(&app_type)
+            .ok_or_else(|| t_args("errors.appTypeNotFound", &[("app", &app_type.as_str())]))?;
+        let provider = manager
+            .providers
+            .get_mut(&provider_id)
+            .ok_or_else(|| t_args("errors.providerNotFound", &[("id", &provider_id)]))?;
+        provider.pinned = pinned.then_some(true);
+        provider.name.clone()
+    };
+    // 保存时刷新代理快照，新顺序对后续请求立即生效
+    state.save_now()?;
+    tracing::info!(
+        "{} 供应商 {}: {}",
+        if pinned { "置顶" } else { "取消置顶" },
+        app_type.as_str(),
+        name
+    );
     Ok(true)
 }
 
@@ -1588,70 +2549,85 @@ pub async fn toggle_proxy_provider(
 pub async fn handle_operation_mode_change(
     state: State<'_, AppState>,
     operation_mode: Option<String>,
-    #[allow(non_snake_case)]
-    operationMode: Option<String>,
-    #[allow(non_snake_case)]
-    claudeCommonConfig: Option<String>,
-    #[allow(non_snake_case)]
-    codexCommonConfig: Option<String>,
-) -> Result<bool, String> {
+    #[allow(non_snake_case)] operationMode: Option<String>,
+) -> Result<bool, CcSwitchError> {
     use crate::settings::OperationMode;
+    crate::app_lock::ensure_unlocked()?;
 
-    let operation_mode = operation_mode.or(operationMode).ok_or("缺少 operation_mode 参数")?;
+    let operation_mode = operation_mode
+        .or(operationMode)
+        .ok_or_else(|| t_args("errors.missingParam", &[("name", &"operation_mode")]))?;
 
-    let mode = if operation_mode == "proxy" {
-        OperationMode::Proxy
-    } else {
-        OperationMode::Write
+    let mode = match operation_mode.as_str() {
+        "proxy" => OperationMode::Proxy,
+        "env" => OperationMode::Env,
+        _ => OperationMode::Write,
     };
+    let hook_context = crate::hooks::HookContext::mode_change(
+        mode.as_str(),
+        crate::settings::get_settings().operation_mode.as_str(),
+    );
+    crate::hooks::before(crate::hooks::HookEvent::BeforeModeChange, &hook_context).await?;
 
     match mode {
         OperationMode::Proxy => {
-            crate::proxy::switch_to_proxy_mode(
-                state.inner(),
-                claudeCommonConfig.as_deref(),
-                codexCommonConfig.as_deref(),
-            )?;
+            crate::proxy::switch_to_proxy_mode(state.inner()).map_err(CcSwitchError::Proxy)?;
 
             let state_clone = state.inner().clone();
             tauri::async_runtime::spawn(async move {
                 if let Err(e) = crate::proxy::start_proxy_server(&state_clone).await {
-                    log::error!("启动代理服务器失败: {}", e);
+                    tracing::error!("启动代理服务器失败: {}", e);
                 }
             });
         }
         OperationMode::Write => {
-            crate::proxy::stop_proxy_server().await?;
-            crate::proxy::switch_to_write_mode(state.inner())?;
+            crate::proxy::stop_proxy_server()
+                .await
+                .map_err(CcSwitchError::Proxy)?;
+            crate::proxy::switch_to_write_mode(state.inner()).map_err(CcSwitchError::Proxy)?;
+        }
+        OperationMode::Env => {
+            // 从代理模式切换过来时先恢复 live 配置，避免其继续指向已停止的代理
+            if crate::proxy::is_proxy_running().await {
+                crate::proxy::stop_proxy_server()
+                    .await
+                    .map_err(CcSwitchError::Proxy)?;
+                crate::proxy::switch_to_write_mode(state.inner()).map_err(CcSwitchError::Proxy)?;
+            }
+            let config = state
+                .config
+                .lock()
+                .map_err(|e| t_args("errors.lockFailed", &[("error", &e)]))?;
+            crate::env_mode::write_env_files(&config)?;
         }
     }
+    crate::audit::record(
+        AuditAction::ModeChanged,
+        None,
+        None,
+        Some(mode.as_str().to_string()),
+    );
+    crate::current_status::sync(state.inner());
+    crate::hooks::after(crate::hooks::HookEvent::AfterModeChange, hook_context);
+    crate::running_sessions::warn_stale(None, crate::running_sessions::ChangeReason::ModeChange);
 
     Ok(true)
 }
 
-/// 同步代理模式的通用配置（在应用启动时调用）
+/// 获取环境变量模式的 shell 片段（sh 或 ps1，默认按平台选择）
 #[tauri::command]
-pub async fn sync_proxy_common_config(
+pub async fn get_env_snippet(
     state: State<'_, AppState>,
-    #[allow(non_snake_case)]
-    claudeCommonConfig: Option<String>,
-    #[allow(non_snake_case)]
-    codexCommonConfig: Option<String>,
-) -> Result<bool, String> {
-
-    // 只在代理模式下同步
-    let settings = crate::settings::get_settings();
-    if settings.operation_mode != crate::settings::OperationMode::Proxy {
-        log::warn!("当前不是代理模式，跳过通用配置同步");
-        return Ok(false);
-    }
-
-    // 调用 switch_to_proxy_mode 来重新生成配置文件
-    crate::proxy::switch_to_proxy_mode(
-        state.inner(),
-        claudeCommonConfig.as_deref(),
-        codexCommonConfig.as_deref(),
-    )?;
-
-    Ok(true)
-}
\ No newline at end of file
+    shell: Option<String>,
+) -> Result<crate::env_mode::EnvSnippet, CcSwitchError> {
+    let shell = match shell.as_deref() {
+        Some(name) => crate::env_mode::EnvShell::from_name(name),
+        None if cfg!(windows) => crate::env_mode::EnvShell::Ps1,
+        None => crate::env_mode::EnvShell::Sh,
+    };
+    let config = state
+        .config
+        .lock()
+        .map_err(|e| t_args("errors.lockFailed", &[("error", &e)]))?;
+    Ok(crate::env_mode::snippet(&config, shell))
+}
@@ -0,0 +1,448 @@
+//! 通用配置片段：按应用保存若干命名片段（Claude 为 JSON 对象，Codex 为 TOML），
+//! 每次写入 live 配置（切换供应商、更新当前供应商、代理模板、重新应用）时合并进去。
+//! 合并时供应商自身（或代理模板）的配置优先，片段只补充其未设置的键（可按路径调整，见 config_merge）；
+//! 从 live 配置回填到供应商时去掉与片段一致的键，避免片段内容沉淀到各个供应商中。
+
+use serde::Serialize;
+use serde_json::{Map, Value};
+use std::path::Path;
+use tauri::State;
+use toml_edit::{DocumentMut, TableLike};
+
+use crate::app_config::AppType;
+use crate::config_merge::{self, Strategies};
+use crate::error::CcSwitchError;
+use crate::i18n::{t, t_args};
+use crate::live_config::{diff_settings, live_dir, read_live_settings, ConfigDiff};
+use crate::provider::{Provider, ProviderManager};
+use crate::settings::{AppSettings, CommonFragment};
+use crate::store::AppState;
+
+fn fragments_of<'a>(settings: &'a AppSettings, app_type: &AppType) -> &'a Vec<CommonFragment> {
+    match app_type {
+        AppType::Claude => &settings.common_fragments.claude,
+        AppType::Codex => &settings.common_fragments.codex,
+    }
+}
+
+fn fragments_mut<'a>(
+    settings: &'a mut AppSettings,
+    app_type: &AppType,
+) -> &'a mut Vec<CommonFragment> {
+    match app_type {
+        AppType::Claude => &mut settings.common_fragments.claude,
+        AppType::Codex => &mut settings.common_fragments.codex,
+    }
+}
+
+/// 去掉 target 中与片段取值相同的键；因此变空的对象一并移除
+fn strip_json(target: &mut Map<String, Value>, fragment: &Map<String, Value>) {
+    for (key, value) in fragment {
+        let remove = match (target.get_mut(key), value) {
+            (Some(Value::Object(child)), Value::Object(source)) if !child.is_empty() => {
+                strip_json(child, source);
+                child.is_empty()
+            }
+            (Some(existing), _) => existing == value,
+            (None, _) => false,
+        };
+        if remove {
+            target.remove(key);
+        }
+    }
+}
+
+fn strip_toml(target: &mut dyn TableLike, fragment: &dyn TableLike) {
+    for (key, item) in fragment.iter() {
+        let remove = match (target.get_mut(key), item.as_table_like()) {
+            (Some(existing), Some(source)) if existing.is_table_like() => {
+                let child = existing.as_table_like_mut().expect("checked table-like");
+                if child.is_empty() {
+                    false
+                } else {
+                    strip_toml(child, source);
+                    child.is_empty()
+                }
+            }
+            (Some(existing), _) => config_merge::toml_item_eq(existing, item),
+            (None, _) => false,
+        };
+        if remove {
+            target.remove(key);
+        }
+    }
+}
+
+/// 校验片段内容：Claude 须为 JSON 对象，Codex 须为合法 TOML
+fn validate_content(app_type: &AppType, content: &str) -> Result<(), String> {
+    match app_type {
+        AppType::Claude => {
+            let value: Value = serde_json::from_str(content)
+                .map_err(|e| t_args("errors.fragmentInvalid", &[("error", &e)]))?;
+            if !value.is_object() {
+                return Err(t("errors.fragmentNotObject"));
+            }
+        }
+        AppType::Codex => {
+            toml::from_str::<toml::Table>(content)
+                .map_err(|e| t_args("errors.fragmentInvalid", &[("error", &e)]))?;
+        }
+    }
+    Ok(())
+}
+
+fn claude_tables(fragments: &[CommonFragment]) -> Vec<Map<String, Value>> {
+    fragments
+        .iter()
+        .filter(|f| f.enabled)
+        .filter_map(|f| match serde_json::from_str(&f.content) {
+            Ok(Value::Object(map)) => Some(map),
+            _ => None,
+        })
+        .collect()
+}
+
+fn codex_docs(fragments: &[CommonFragment]) -> Vec<DocumentMut> {
+    fragments
+        .iter()
+        .filter(|f| f.enabled)
+        .filter_map(|f| f.content.parse().ok())
+        .collect()
+}
+
+fn strategies_of<'a>(settings: &'a AppSettings, app_type: &AppType) -> &'a Strategies {
+    match app_type {
+        AppType::Claude => &settings.merge_strategies.claude,
+        AppType::Codex => &settings.merge_strategies.codex,
+    }
+}
+
+/// 片段为底、供应商配置在上按策略合并，再带入 live 中 preserve / append 路径的内容
+fn merge_maps(
+    own: &Map<String, Value>,
+    tables: &[Map<String, Value>],
+    strategies: &Strategies,
+    live: Option<&Map<String, Value>>,
+) -> Map<String, Value> {
+    let mut merged = Map::new();
+    for table in tables {
+        config_merge::merge_object(&mut merged, table, strategies, "");
+    }
+    config_merge::merge_object(&mut merged, own, strategies, "");
+    if let Some(live) = live {
+        config_merge::keep_live(&mut merged, live, strategies, "");
+    }
+    merged
+}
+
+/// 将给定片段合并到 settings_config（结构与供应商 settings_config 一致），live 为当前 live 配置
+pub fn merge_with(
+    app_type: &AppType,
+    settings: &Value,
+    fragments: &[CommonFragment],
+    strategies: &Strategies,
+    live: Option<&Value>,
+) -> Value {
+    match app_type {
+        AppType::Claude => {
+            let Some(own) = settings.as_object() else {
+                return settings.clone();
+            };
+            let live = live.and_then(|v| v.as_object());
+            Value::Object(merge_maps(own, &claude_tables(fragments), strategies, live))
+        }
+        AppType::Codex => {
+            let text = settings
+                .get("config")
+                .and_then(|v| v.as_str())
+                .unwrap_or("");
+            // 原配置无法解析时不合并，交由校验报错
+            let Ok(mut doc) = text.parse::<DocumentMut>() else {
+                return settings.clone();
+            };
+            // 以供应商配置为底稿补入片段（靠后的片段优先，因此倒序补入），保留其注释与格式
+            for fragment in codex_docs(fragments).iter().rev() {
+                config_merge::fill_toml(doc.as_table_mut(), fragment.as_table(), strategies, "");
+            }
+            let live = live
+                .and_then(|v| v.get("config"))
+                .and_then(|v| v.as_str())
+                .and_then(|text| text.parse::<DocumentMut>().ok());
+            if let Some(live) = live {
+                config_merge::keep_live_toml(doc.as_table_mut(), live.as_table(), strategies, "");
+            }
+            let merged = doc.to_string();
+            if merged == text {
+                return settings.clone();
+            }
+            let mut result = settings.clone();
+            if let Some(obj) = result.as_object_mut() {
+                obj.insert("config".to_string(), Value::String(merged));
+            }
+            result
+        }
+    }
+}
+
+/// 将已启用的通用配置片段与 dir 下现有 live 配置按策略合并到 settings_config（Claude 另带上状态栏设置）
+pub fn merged(app_type: &AppType, settings: &Value, dir: &Path) -> Value {
+    let app_settings = crate::settings::get_settings();
+    let strategies = strategies_of(&app_settings, app_type);
+    let live = config_merge::needs_live(strategies)
+        .then(|| read_live_settings(app_type, dir).ok())
+        .flatten();
+    let mut result = merge_with(
+        app_type,
+        settings,
+        fragments_of(&app_settings, app_type),
+        strategies,
+        live.as_ref(),
+    );
+    if matches!(app_type, AppType::Claude) {
+        crate::statusline::apply(&mut result);
+    }
+    result
+}
+
+/// 供应商写入 dir 的最终 live 内容：合并片段与策略；Codex 单文件模式下改为在现有 config.toml 中维护全部供应商条目
+pub fn live_settings(
+    app_type: &AppType,
+    manager: &ProviderManager,
+    provider: &Provider,
+    dir: &Path,
+) -> Value {
+    let settings = merged(app_type, &provider.settings_config, dir);
+    let mut settings = match app_type {
+        AppType::Codex if crate::codex_single::is_enabled() => {
+            crate::codex_single::render(manager, provider, settings, dir)
+        }
+        _ => settings,
+    };
+    crate::endpoint_probe::apply(app_type, provider, &mut settings);
+    settings
+}
+
+/// 从 live 回填到供应商的内容：去掉片段与 preserve 路径下的内容；Codex 单文件模式下只取该供应商自身的条目
+pub fn backfill(app_type: &AppType, provider: &Provider, live: Value) -> Value {
+    let mut live = match app_type {
+        AppType::Codex if crate::codex_single::is_enabled() => {
+            crate::codex_single::extract(provider, live)
+        }
+        _ => strip(app_type, live),
+    };
+    crate::endpoint_probe::restore(app_type, provider, &mut live);
+    live
+}
+
+/// 从 live 配置中去掉与已启用片段一致的内容及 preserve 路径下的内容（回填到供应商前调用）
+pub fn strip(app_type: &AppType, live: Value) -> Value {
+    let settings = crate::settings::get_settings();
+    let fragments = fragments_of(&settings, app_type);
+    let strategies = strategies_of(&settings, app_type);
+    match app_type {
+        AppType::Claude => {
+            let tables = claude_tables(fragments);
+            let Value::Object(mut map) = live else {
+                return live;
+            };
+            for table in &tables {
+                strip_json(&mut map, table);
+            }
+            config_merge::strip_preserved(&mut map, strategies);
+            crate::statusline::strip(&mut map);
+            Value::Object(map)
+        }
+        AppType::Codex => {
+            let mut live = live;
+            let text = live.get("config").and_then(|v| v.as_str());
+            let stripped = text.and_then(|text| strip_toml_text(fragments, strategies, text));
+            if let (Some(text), Some(obj)) = (stripped, live.as_object_mut()) {
+                obj.insert("config".to_string(), Value::String(text));
+            }
+            live
+        }
+    }
+}
+
+/// 从 config.toml 文本中去掉与已启用片段一致的内容；未去掉任何内容时返回 None（保留原文本格式）
+fn strip_toml_text(
+    fragments: &[CommonFragment],
+    strategies: &Strategies,
+    text: &str,
+) -> Option<String> {
+    let mut doc = text.parse::<DocumentMut>().ok()?;
+    for fragment in &codex_docs(fragments) {
+        strip_toml(doc.as_table_mut(), fragment.as_table());
+    }
+    config_merge::strip_preserved_toml(doc.as_table_mut(), strategies);
+    let stripped = doc.to_string();
+    (stripped != text).then_some(stripped)
+}
+
+/// 从 Codex live config.toml 文本中去掉与已启用片段一致的内容
+pub fn strip_codex_config(text: String) -> String {
+    let settings = crate::settings::get_settings();
+    let fragments = fragments_of(&settings, &AppType::Codex);
+    strip_toml_text(fragments, strategies_of(&settings, &AppType::Codex), &text).unwrap_or(text)
+}
+
+/// 片段变更后重新写入 live 配置，使其立即生效
+fn apply_changes(app_state: &AppState, app_type: &AppType) {
+    if let Err(e) = crate::self_check::reapply_live(app_state, app_type) {
+        tracing::warn!("应用通用配置片段失败: {}", e);
+    }
+}
+
+fn resolve_app_type(
+    app_type: Option<AppType>,
+    app: Option<String>,
+    app_type_name: Option<String>,
+) -> AppType {
+    app_type
+        .or_else(|| app.as_deref().map(|s| s.into()))
+        .or_else(|| app_type_name.as_deref().map(|s| s.into()))
+        .unwrap_or(AppType::Claude)
+}
+
+/// 获取通用配置片段
+#[tauri::command]
+#[allow(non_snake_case)]
+pub async fn get_common_fragments(
+    app_type: Option<AppType>,
+    app: Option<String>,
+    appType: Option<String>,
+) -> Result<Vec<CommonFragment>, CcSwitchError> {
+    let app_type = resolve_app_type(app_type, app, appType);
+    Ok(fragments_of(&crate::settings::get_settings(), &app_type).clone())
+}
+
+/// 新增或更新片段（original_name 为修改前的名称，新增时为空），返回更新后的列表
+#[tauri::command]
+#[allow(non_snake_case)]
+pub async fn save_common_fragment(
+    state: State<'_, AppState>,
+    app_type: Option<AppType>,
+    app: Option<String>,
+    appType: Option<String>,
+    fragment: CommonFragment,
+    original_name: Option<String>,
+    originalName: Option<String>,
+) -> Result<Vec<CommonFragment>, CcSwitchError> {
+    let app_type = resolve_app_type(app_type, app, appType);
+    let original_name = original_name.or(originalName);
+    let mut fragment = fragment;
+    fragment.name = fragment.name.trim().to_string();
+    if fragment.name.is_empty() {
+        return Err(t("errors.fragmentNameEmpty").into());
+    }
+    validate_content(&app_type, &fragment.content)?;
+
+    let mut settings = crate::settings::get_settings();
+    let fragments = fragments_mut(&mut settings, &app_type);
+    let duplicate = fragments
+        .iter()
+        .any(|f| f.name == fragment.name && Some(&f.name) != original_name.as_ref());
+    if duplicate {
+        return Err(t_args("errors.fragmentNameExists", &[("name", &fragment.name)]).into());
+    }
+    match original_name
+        .as_ref()
+        .and_then(|name| fragments.iter().position(|f| &f.name == name))
+    {
+        Some(index) => fragments[index] = fragment,
+        None => fragments.push(fragment),
+    }
+    let result = fragments.clone();
+    crate::settings::update_settings(settings)?;
+    apply_changes(&state, &app_type);
+    Ok(result)
+}
+
+/// 删除片段，返回更新后的列表
+#[tauri::command]
+#[allow(non_snake_case)]
+pub async fn delete_common_fragment(
+    state: State<'_, AppState>,
+    app_type: Option<AppType>,
+    app: Option<String>,
+    appType: Option<String>,
+    name: String,
+) -> Result<Vec<CommonFragment>, CcSwitchError> {
+    let app_type = resolve_app_type(app_type, app, appType);
+    let mut settings = crate::settings::get_settings();
+    let fragments = fragments_mut(&mut settings, &app_type);
+    let before = fragments.len();
+    fragments.retain(|f| f.name != name);
+    if fragments.len() == before {
+        return Err(t_args("errors.fragmentNotFound", &[("name", &name)]).into());
+    }
+    let result = fragments.clone();
+    crate::settings::update_settings(settings)?;
+    apply_changes(&state, &app_type);
+    Ok(result)
+}
+
+/// 合并预览：供应商配置 -> 合并片段后写入 live 的配置（密钥已打码）
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CommonMergePreview {
+    pub provider_id: String,
+    pub provider_name: String,
+    pub merged: Value,
+    #[serde(flatten)]
+    pub diff: ConfigDiff,
+}
+
+/// 预览片段合并结果：provider_id 缺省为当前供应商，fragments 缺省为已保存的片段（可用于预览未保存的编辑）
+#[tauri::command]
+#[allow(non_snake_case)]
+pub async fn preview_common_merge(
+    state: State<'_, AppState>,
+    app_type: Option<AppType>,
+    app: Option<String>,
+    appType: Option<String>,
+    provider_id: Option<String>,
+    providerId: Option<String>,
+    fragments: Option<Vec<CommonFragment>>,
+) -> Result<CommonMergePreview, CcSwitchError> {
+    let app_type = resolve_app_type(app_type, app, appType);
+    let fragments = match fragments {
+        Some(fragments) => {
+            for fragment in fragments.iter().filter(|f| f.enabled) {
+                validate_content(&app_type, &fragment.content)?;
+            }
+            fragments
+        }
+        None => fragments_of(&crate::settings::get_settings(), &app_type).clone(),
+    };
+
+    let config = state
+        .config
+        .lock()
+        .map_err(|e| t_args("errors.lockFailed", &[("error", &e)]))?;
+    let manager = config
+        .get_manager(&app_type)
+        .ok_or_else(|| t_args("errors.appTypeNotFound", &[("app", &app_type.as_str())]))?;
+    let id = provider_id
+        .or(providerId)
+        .unwrap_or_else(|| manager.current.clone());
+    let provider = manager
+        .providers
+        .get(&id)
+        .ok_or_else(|| t_args("errors.providerNotFound", &[("id", &id)]))?;
+
+    let own = crate::redact::redact_settings(&app_type, &provider.settings_config);
+    let dir = live_dir(&app_type, Some(provider));
+    let settings = crate::settings::get_settings();
+    let strategies = strategies_of(&settings, &app_type);
+    let live = read_live_settings(&app_type, &dir)
+        .ok()
+        .map(|live| crate::redact::redact_settings(&app_type, &live));
+    let merged = merge_with(&app_type, &own, &fragments, strategies, live.as_ref());
+    Ok(CommonMergePreview {
+        provider_id: provider.id.clone(),
+        provider_name: provider.name.clone(),
+        diff: diff_settings(&app_type, &dir, Some(&own), &merged),
+        merged,
+    })
+}
@@ -1,4 +1,5 @@
 use serde::{Deserialize, Serialize};
+use serde_json::Value;
 // unused import removed
 use std::fs;
 use std::io::Write;
@@ -17,7 +18,11 @@ pub fn get_claude_config_dir() -> PathBuf {
 
 /// 获取 Claude Code 主配置文件路径
 pub fn get_claude_settings_path() -> PathBuf {
-    let dir = get_claude_config_dir();
+    claude_settings_path_in(&get_claude_config_dir())
+}
+
+/// 获取指定 Claude 配置目录下的主配置文件路径
+pub fn claude_settings_path_in(dir: &Path) -> PathBuf {
     let settings = dir.join("settings.json");
     if settings.exists() {
         return settings;
@@ -31,8 +36,12 @@ pub fn get_claude_settings_path() -> PathBuf {
     settings
 }
 
-/// 获取应用配置目录路径 (~/.cc-switch)
+/// 获取应用配置目录路径 (~/.cc-switch；便携模式下位于可执行文件旁)
 pub fn get_app_config_dir() -> PathBuf {
+    if let Some(portable) = crate::portable::portable_data_dir() {
+        return portable;
+    }
+
     if let Some(custom) = crate::app_store::get_app_config_dir_override() {
         return custom;
     }
@@ -47,6 +56,11 @@ pub fn get_app_config_path() -> PathBuf {
     get_app_config_dir().join("config.json")
 }
 
+/// 获取应用数据库路径（供应商、MCP 等配置与审计日志）
+pub fn get_app_db_path() -> PathBuf {
+    get_app_config_dir().join("cc-switch.db")
+}
+
 /// 归档根目录 ~/.cc-switch/archive
 pub fn get_archive_root() -> PathBuf {
     get_app_config_dir().join("archive")
@@ -171,13 +185,20 @@ pub fn atomic_write(path: &Path, data: &[u8]) -> Result<(), String> {
         .as_nanos();
     tmp.push(format!("{}.tmp.{}", file_name, ts));
 
-    {
+    let write_tmp = || -> Result<(), String> {
         let mut f = fs::File::create(&tmp)
             .map_err(|e| format!("创建临时文件失败: {}: {}", tmp.display(), e))?;
         f.write_all(data)
             .map_err(|e| format!("写入临时文件失败: {}: {}", tmp.display(), e))?;
         f.flush()
             .map_err(|e| format!("刷新临时文件失败: {}: {}", tmp.display(), e))?;
+        // 确保数据落盘后再 rename，避免断电后出现空文件
+        f.sync_all()
+            .map_err(|e| format!("同步临时文件失败: {}: {}", tmp.display(), e))
+    };
+    if let Err(e) = write_tmp() {
+        let _ = fs::remove_file(&tmp);
+        return Err(e);
     }
 
     #[cfg(unix)]
@@ -196,6 +217,7 @@ pub fn atomic_write(path: &Path, data: &[u8]) -> Result<(), String> {
             let _ = fs::remove_file(path);
         }
         fs::rename(&tmp, path).map_err(|e| {
+            let _ = fs::remove_file(&tmp);
             format!(
                 "原子替换失败: {} -> {}: {}",
                 tmp.display(),
@@ -208,6 +230,7 @@ pub fn atomic_write(path: &Path, data: &[u8]) -> Result<(), String> {
     #[cfg(not(windows))]
     {
         fs::rename(&tmp, path).map_err(|e| {
+            let _ = fs::remove_file(&tmp);
             format!(
                 "原子替换失败: {} -> {}: {}",
                 tmp.display(),
@@ -215,10 +238,70 @@ pub fn atomic_write(path: &Path, data: &[u8]) -> Result<(), String> {
                 e
             )
         })?;
+        // 同步目录项，保证 rename 本身持久化
+        if let Ok(dir) = fs::File::open(parent) {
+            let _ = dir.sync_all();
+        }
     }
     Ok(())
 }
 
+/// live 配置写入前的备份目录
+pub fn get_live_backup_dir() -> PathBuf {
+    get_app_config_dir().join("live-backup")
+}
+
+/// 原子写入 Claude `settings.json`：写入前备份原文件，写入或回读校验失败时自动回滚
+pub fn write_claude_settings_atomic(settings: &Value) -> Result<(), String> {
+    write_claude_settings_atomic_at(&get_claude_settings_path(), settings)
+}
+
+/// 原子写入指定路径的 Claude 配置文件（供应商自定义 live 目录时使用）
+pub fn write_claude_settings_atomic_at(path: &Path, settings: &Value) -> Result<(), String> {
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent)
+            .map_err(|e| format!("创建目录失败: {}: {}", parent.display(), e))?;
+    }
+
+    let json =
+        serde_json::to_string_pretty(settings).map_err(|e| format!("序列化 JSON 失败: {}", e))?;
+
+    // 备份原内容：内存中保留一份用于回滚，磁盘上保留一份便于手动恢复
+    let original = if path.exists() {
+        let bytes = fs::read(path)
+            .map_err(|e| format!("读取旧 settings.json 失败: {}: {}", path.display(), e))?;
+        let backup_path = get_live_backup_dir().join("claude-settings.json");
+        if let Err(e) = atomic_write(&backup_path, &bytes) {
+            tracing::warn!("备份 settings.json 失败: {}", e);
+        }
+        Some(bytes)
+    } else {
+        None
+    };
+
+    let rollback = |reason: String| -> String {
+        let restored = match &original {
+            Some(bytes) => atomic_write(path, bytes),
+            None => delete_file(path),
+        };
+        match restored {
+            Ok(()) => format!("{}（已回滚到写入前的 settings.json）", reason),
+            Err(e) => format!("{}；回滚失败: {}", reason, e),
+        }
+    };
+
+    if let Err(e) = atomic_write(path, json.as_bytes()) {
+        return Err(rollback(e));
+    }
+
+    // 回读校验，防止部分写入
+    match read_json_file::<Value>(path) {
+        Ok(ref written) if written == settings => Ok(()),
+        Ok(_) => Err(rollback("写入后校验 settings.json 内容不一致".to_string())),
+        Err(e) => Err(rollback(e)),
+    }
+}
+
 /// 复制文件
 pub fn copy_file(from: &Path, to: &Path) -> Result<(), String> {
     fs::copy(from, to).map_err(|e| format!("复制文件失败: {}", e))?;
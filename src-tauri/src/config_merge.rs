@@ -0,0 +1,297 @@
+//! 按键路径配置的合并策略：写入 live 配置与合并通用配置片段时共用。
+//! 路径为点分隔的键（如 `permissions.allow`），未配置的路径继承最近的上级路径，缺省为 override。
+//! 合并顺序为 live 配置 → 通用配置片段 → 供应商配置：override 由后来者覆盖，
+//! preserve 保留先出现的值（因此 live 中已有的值不会被切换覆盖），append 将数组追加去重。
+//! Claude 的 JSON 配置与 Codex 的 TOML 配置各有一套实现，TOML 版本保留原有注释与格式。
+
+use serde_json::{Map, Value};
+use std::collections::BTreeMap;
+use toml_edit::{Item, TableLike};
+
+use crate::settings::MergeStrategy;
+
+pub type Strategies = BTreeMap<String, MergeStrategy>;
+
+fn join(prefix: &str, key: &str) -> String {
+    if prefix.is_empty() {
+        key.to_string()
+    } else {
+        format!("{}.{}", prefix, key)
+    }
+}
+
+/// 路径对应的策略：取最长的已配置前缀，缺省为 override
+pub fn strategy_for(strategies: &Strategies, path: &str) -> MergeStrategy {
+    let mut current = path;
+    loop {
+        if let Some(strategy) = strategies.get(current) {
+            return *strategy;
+        }
+        match current.rfind('.') {
+            Some(index) => current = &current[..index],
+            None => return MergeStrategy::Override,
+        }
+    }
+}
+
+/// 是否存在该路径之下（不含自身）的策略配置
+fn has_nested(strategies: &Strategies, path: &str) -> bool {
+    let prefix = format!("{}.", path);
+    strategies.keys().any(|key| key.starts_with(&prefix))
+}
+
+/// 是否配置了会读取 live 内容的策略（preserve / append）
+pub fn needs_live(strategies: &Strategies) -> bool {
+    strategies
+        .values()
+        .any(|strategy| *strategy != MergeStrategy::Override)
+}
+
+/// 按策略将 overlay 合并进 base（path 为 base 所在的路径）
+pub fn merge_value(base: &mut Value, overlay: &Value, strategies: &Strategies, path: &str) {
+    let strategy = if path.is_empty() {
+        MergeStrategy::Override
+    } else {
+        strategy_for(strategies, path)
+    };
+    match (strategy, base, overlay) {
+        (MergeStrategy::Preserve, _, _) => {}
+        (MergeStrategy::Append, Value::Array(items), Value::Array(extra)) => {
+            for item in extra {
+                if !items.contains(item) {
+                    items.push(item.clone());
+                }
+            }
+        }
+        (_, Value::Object(target), Value::Object(source)) => {
+            merge_object(target, source, strategies, path)
+        }
+        (_, base, overlay) => *base = overlay.clone(),
+    }
+}
+
+/// 按策略将 overlay 的各个键合并进 base
+pub fn merge_object(
+    base: &mut Map<String, Value>,
+    overlay: &Map<String, Value>,
+    strategies: &Strategies,
+    prefix: &str,
+) {
+    for (key, value) in overlay {
+        match base.get_mut(key) {
+            Some(existing) => merge_value(existing, value, strategies, &join(prefix, key)),
+            None => {
+                base.insert(key.clone(), value.clone());
+            }
+        }
+    }
+}
+
+/// 将 live 中 preserve / append 路径下的内容带入即将写入的配置；其余键以新配置为准
+pub fn keep_live(
+    target: &mut Map<String, Value>,
+    live: &Map<String, Value>,
+    strategies: &Strategies,
+    prefix: &str,
+) {
+    for (key, live_value) in live {
+        let path = join(prefix, key);
+        match strategy_for(strategies, &path) {
+            MergeStrategy::Preserve => {
+                target.insert(key.clone(), live_value.clone());
+            }
+            MergeStrategy::Append => {
+                let mut merged = live_value.clone();
+                if let Some(value) = target.get(key) {
+                    merge_value(&mut merged, value, strategies, &path);
+                }
+                target.insert(key.clone(), merged);
+            }
+            MergeStrategy::Override => {
+                let Value::Object(live_child) = live_value else {
+                    continue;
+                };
+                if !has_nested(strategies, &path) {
+                    continue;
+                }
+                let mut child = match target.get(key) {
+                    Some(Value::Object(child)) => child.clone(),
+                    Some(_) => continue,
+                    None => Map::new(),
+                };
+                keep_live(&mut child, live_child, strategies, &path);
+                if !child.is_empty() {
+                    target.insert(key.clone(), Value::Object(child));
+                }
+            }
+        }
+    }
+}
+
+/// 去掉 preserve 路径下的内容（从 live 回填到供应商前调用，避免用户自定义内容沉淀到供应商中）
+pub fn strip_preserved(target: &mut Map<String, Value>, strategies: &Strategies) {
+    for (path, strategy) in strategies {
+        if *strategy == MergeStrategy::Preserve {
+            remove_path(target, path);
+        }
+    }
+}
+
+fn remove_path(target: &mut Map<String, Value>, path: &str) {
+    match path.split_once('.') {
+        Some((key, rest)) => {
+            if let Some(Value::Object(child)) = target.get_mut(key) {
+                remove_path(child, rest);
+            }
+        }
+        None => {
+            target.remove(path);
+        }
+    }
+}
+
+// TOML（Codex config.toml）：基于 toml_edit 结构化合并，保留原有注释与格式
+
+/// 将 toml_edit 的值转换为普通 TOML 值，用于忽略格式比较
+fn plain_value(value: &toml_edit::Value) -> Option<toml::Value> {
+    let text = format!("v = {}", value.to_string().trim());
+    toml::from_str::<toml::Table>(&text).ok()?.remove("v")
+}
+
+/// 两个条目取值是否相同（忽略格式与注释）
+pub fn toml_item_eq(a: &Item, b: &Item) -> bool {
+    match (a, b) {
+        (Item::Value(a), Item::Value(b)) => {
+            let a = plain_value(a);
+            a.is_some() && a == plain_value(b)
+        }
+        _ => match (a.as_table_like(), b.as_table_like()) {
+            (Some(a), Some(b)) => {
+                a.len() == b.len()
+                    && a.iter().all(|(key, item)| {
+                        b.get(key).is_some_and(|other| toml_item_eq(item, other))
+                    })
+            }
+            _ => false,
+        },
+    }
+}
+
+fn union_arrays(target: &mut toml_edit::Array, extra: &toml_edit::Array) {
+    let before = target.len();
+    for value in extra.iter() {
+        let plain = plain_value(value);
+        if !target.iter().any(|existing| plain_value(existing) == plain) {
+            target.push(value.clone());
+        }
+    }
+    if target.len() != before {
+        target.fmt();
+    }
+}
+
+/// 以 base 为准补入 underlay 的内容（underlay 为更早的来源）：override 保留 base，
+/// preserve 改用 underlay 的值，append 合并数组；base 中已有条目的格式与注释不变
+pub fn fill_toml(
+    base: &mut dyn TableLike,
+    underlay: &dyn TableLike,
+    strategies: &Strategies,
+    prefix: &str,
+) {
+    for (key, item) in underlay.iter() {
+        let path = join(prefix, key);
+        let Some(existing) = base.get_mut(key) else {
+            base.insert(key, item.clone());
+            continue;
+        };
+        let strategy = strategy_for(strategies, &path);
+        if strategy == MergeStrategy::Preserve {
+            *existing = item.clone();
+            continue;
+        }
+        if let (Some(target), Some(source)) = (existing.as_table_like_mut(), item.as_table_like()) {
+            fill_toml(target, source, strategies, &path);
+        } else if strategy == MergeStrategy::Append {
+            if let (Some(target), Some(source)) = (existing.as_array_mut(), item.as_array()) {
+                union_arrays(target, source);
+            }
+        }
+    }
+}
+
+/// 将 live 中 preserve / append 路径下的内容带入即将写入的 TOML；其余键以新配置为准
+pub fn keep_live_toml(
+    target: &mut dyn TableLike,
+    live: &dyn TableLike,
+    strategies: &Strategies,
+    prefix: &str,
+) {
+    for (key, live_item) in live.iter() {
+        let path = join(prefix, key);
+        match strategy_for(strategies, &path) {
+            MergeStrategy::Preserve => {
+                target.insert(key, live_item.clone());
+            }
+            MergeStrategy::Append => match target.get_mut(key) {
+                Some(existing) => {
+                    let mut wrapper = toml_edit::Table::new();
+                    wrapper.insert(key, live_item.clone());
+                    let mut base = toml_edit::Table::new();
+                    base.insert(key, std::mem::take(existing));
+                    fill_toml(&mut base, &wrapper, strategies, prefix);
+                    if let Some(merged) = base.remove(key) {
+                        *existing = merged;
+                    }
+                }
+                None => {
+                    target.insert(key, live_item.clone());
+                }
+            },
+            MergeStrategy::Override => {
+                let Some(live_child) = live_item.as_table_like() else {
+                    continue;
+                };
+                if !has_nested(strategies, &path) {
+                    continue;
+                }
+                if !target.contains_key(key) {
+                    let mut table = toml_edit::Table::new();
+                    table.set_implicit(true);
+                    target.insert(key, Item::Table(table));
+                }
+                if let Some(child) = target.get_mut(key).and_then(Item::as_table_like_mut) {
+                    keep_live_toml(child, live_child, strategies, &path);
+                }
+                if target
+                    .get(key)
+                    .and_then(Item::as_table_like)
+                    .is_some_and(|t| t.is_empty())
+                {
+                    target.remove(key);
+                }
+            }
+        }
+    }
+}
+
+/// 去掉 TOML 中 preserve 路径下的内容
+pub fn strip_preserved_toml(target: &mut dyn TableLike, strategies: &Strategies) {
+    for (path, strategy) in strategies {
+        if *strategy == MergeStrategy::Preserve {
+            remove_toml_path(target, path);
+        }
+    }
+}
+
+fn remove_toml_path(target: &mut dyn TableLike, path: &str) {
+    match path.split_once('.') {
+        Some((key, rest)) => {
+            if let Some(child) = target.get_mut(key).and_then(Item::as_table_like_mut) {
+                remove_toml_path(child, rest);
+            }
+        }
+        None => {
+            target.remove(path);
+        }
+    }
+}
@@ -0,0 +1,95 @@
+//! 多实例同步：定期核对数据库中的配置修订号，其他进程（另一个 GUI 实例或 CLI）写入新配置后
+//! 自动重新加载到内存，刷新托盘并通知前端。写入侧的冲突检测见 `database::save_config`。
+//! 本地有尚未写盘的修改时不自动重新加载，而是发送冲突事件，由用户选择保留本地修改或使用外部配置。
+
+#![allow(non_snake_case)]
+
+use serde::Serialize;
+use tauri::{Emitter, Manager, State};
+
+use crate::error::CcSwitchError;
+use crate::i18n::t_args;
+use crate::store::{AppState, ReloadOutcome};
+
+/// 配置被外部修改并已重新加载的事件名
+pub const CONFIG_RELOADED_EVENT: &str = "config-reloaded";
+
+/// 配置被外部修改且与本地未保存的修改冲突的事件名
+pub const CONFIG_CONFLICT_EVENT: &str = "config-conflict";
+
+/// 修订号检查间隔
+const CHECK_INTERVAL_SECS: u64 = 2;
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+struct ConfigReloadedPayload {
+    revision: u64,
+}
+
+/// 启动后台检查任务
+pub fn start_config_sync(app: tauri::AppHandle) {
+    tauri::async_runtime::spawn(async move {
+        // 同一修订号的冲突只提示一次
+        let mut notified_conflict = None;
+        loop {
+            tokio::time::sleep(std::time::Duration::from_secs(CHECK_INTERVAL_SECS)).await;
+
+            let Some(state) = app.try_state::<AppState>() else {
+                continue;
+            };
+            let revision = match state.reload_if_changed() {
+                Ok(ReloadOutcome::Reloaded(revision)) => revision,
+                Ok(ReloadOutcome::Unchanged) => continue,
+                Ok(ReloadOutcome::Conflict(revision)) => {
+                    if notified_conflict != Some(revision) {
+                        notified_conflict = Some(revision);
+                        tracing::warn!(
+                            "配置已被其他进程修改（修订 {}），本地有尚未保存的修改，等待用户选择",
+                            revision
+                        );
+                        if let Err(e) =
+                            app.emit(CONFIG_CONFLICT_EVENT, ConfigReloadedPayload { revision })
+                        {
+                            tracing::warn!("发送配置冲突事件失败: {}", e);
+                        }
+                    }
+                    continue;
+                }
+                Err(e) => {
+                    tracing::warn!("检查外部配置修改失败: {}", e);
+                    continue;
+                }
+            };
+
+            tracing::info!("配置已被其他进程修改，已重新加载（修订 {}）", revision);
+            if let Err(e) = crate::tray::refresh_tray_menu(&app) {
+                tracing::warn!("更新托盘菜单失败: {}", e);
+            }
+            if let Err(e) = app.emit(CONFIG_RELOADED_EVENT, ConfigReloadedPayload { revision }) {
+                tracing::warn!("发送配置重新加载事件失败: {}", e);
+            }
+        }
+    });
+}
+
+/// 解决配置冲突：`keep_local` 为真时保留本地修改并覆盖其他进程写入的配置，否则使用外部配置
+#[tauri::command]
+pub async fn resolve_config_conflict(
+    app: tauri::AppHandle,
+    state: State<'_, AppState>,
+    keep_local: Option<bool>,
+    keepLocal: Option<bool>,
+) -> Result<bool, CcSwitchError> {
+    let keep_local = keep_local
+        .or(keepLocal)
+        .ok_or_else(|| t_args("errors.missingParam", &[("name", &"keep_local")]))?;
+    state.resolve_conflict(keep_local)?;
+    tracing::info!(
+        "配置冲突已解决：{}",
+        if keep_local { "保留本地修改" } else { "使用外部配置" }
+    );
+    if let Err(e) = crate::tray::refresh_tray_menu(&app) {
+        tracing::warn!("更新托盘菜单失败: {}", e);
+    }
+    Ok(true)
+}
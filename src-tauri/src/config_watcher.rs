@@ -0,0 +1,230 @@
+use notify::{Event, RecommendedWatcher, RecursiveMode, Watcher};
+use serde::Serialize;
+use std::collections::{HashMap, HashSet};
+use std::path::PathBuf;
+use std::sync::mpsc;
+use std::sync::{Mutex, OnceLock};
+use std::time::Duration;
+use tauri::{Emitter, Manager};
+
+use crate::app_config::AppType;
+use crate::live_config::{
+    current_live_dir, live_dir, live_matches, live_paths, read_live_settings,
+};
+use crate::settings::OperationMode;
+use crate::store::AppState;
+
+/// 配置漂移事件名
+pub const CONFIG_DRIFT_EVENT: &str = "config-drift";
+
+/// 文件事件去抖时间：编辑器保存时常连续触发多次事件
+const DEBOUNCE: Duration = Duration::from_millis(500);
+
+/// 已通知或已忽略的 live 内容指纹（按应用类型），避免同一内容重复提醒
+static DRIFT_FINGERPRINTS: OnceLock<Mutex<HashMap<String, DriftFingerprint>>> = OnceLock::new();
+
+#[derive(Debug, Clone, Default)]
+struct DriftFingerprint {
+    notified: Option<String>,
+    ignored: Option<String>,
+}
+
+/// 配置漂移事件负载
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ConfigDriftPayload {
+    pub app_type: String,
+    pub provider_id: String,
+    pub provider_name: String,
+    pub paths: Vec<String>,
+}
+
+fn fingerprints() -> &'static Mutex<HashMap<String, DriftFingerprint>> {
+    DRIFT_FINGERPRINTS.get_or_init(|| Mutex::new(HashMap::new()))
+}
+
+fn fingerprint_of(live: &serde_json::Value) -> String {
+    live.to_string()
+}
+
+/// 各应用当前供应商的 live 配置目录
+fn current_live_dirs(app: &tauri::AppHandle) -> Vec<(AppType, PathBuf)> {
+    let state = app.try_state::<AppState>();
+    let config = state.as_ref().and_then(|s| s.config.lock().ok());
+    [AppType::Claude, AppType::Codex]
+        .into_iter()
+        .map(|app_type| {
+            let dir = match &config {
+                Some(config) => current_live_dir(config, &app_type),
+                None => live_dir(&app_type, None),
+            };
+            (app_type, dir)
+        })
+        .collect()
+}
+
+/// 根据变更文件路径判断所属应用（仅匹配当前供应商的 live 配置文件）
+fn app_type_for_path(app: &tauri::AppHandle, path: &std::path::Path) -> Option<AppType> {
+    current_live_dirs(app)
+        .into_iter()
+        .find(|(app_type, dir)| live_paths(app_type, dir).iter().any(|p| p == path))
+        .map(|(app_type, _)| app_type)
+}
+
+/// 需要监听的目录：全局 live 目录及所有供应商自定义的 live 目录
+/// （启动后新增的自定义目录在下次启动时生效）
+fn watched_dirs(app: &tauri::AppHandle) -> HashSet<PathBuf> {
+    let mut dirs: HashSet<PathBuf> = HashSet::new();
+    for app_type in [AppType::Claude, AppType::Codex] {
+        dirs.insert(live_dir(&app_type, None));
+    }
+    if let Some(state) = app.try_state::<AppState>() {
+        if let Ok(config) = state.config.lock() {
+            for app_type in [AppType::Claude, AppType::Codex] {
+                if let Some(manager) = config.get_manager(&app_type) {
+                    dirs.extend(
+                        manager
+                            .providers
+                            .values()
+                            .filter_map(|p| p.live_config_dir()),
+                    );
+                }
+            }
+        }
+    }
+    dirs
+}
+
+/// 启动 live 配置文件监听（后台线程，失败仅记录日志）
+pub fn start_config_watcher(app: tauri::AppHandle) {
+    std::thread::spawn(move || {
+        if let Err(e) = run_watcher(app) {
+            tracing::warn!("启动配置文件监听失败: {}", e);
+        }
+    });
+}
+
+fn run_watcher(app: tauri::AppHandle) -> Result<(), String> {
+    let (tx, rx) = mpsc::channel::<notify::Result<Event>>();
+    let mut watcher = RecommendedWatcher::new(tx, notify::Config::default())
+        .map_err(|e| format!("创建文件监听器失败: {}", e))?;
+
+    // 监听所在目录而非文件本身：原子写入会替换文件，直接监听文件会丢失后续事件
+    let dirs = watched_dirs(&app);
+    for dir in &dirs {
+        if !dir.exists() {
+            tracing::info!("配置目录不存在，跳过监听: {}", dir.display());
+            continue;
+        }
+        if let Err(e) = watcher.watch(dir, RecursiveMode::NonRecursive) {
+            tracing::warn!("监听配置目录失败: {}: {}", dir.display(), e);
+        }
+    }
+
+    loop {
+        // 阻塞等待第一个事件，之后在去抖窗口内聚合
+        let first = rx
+            .recv()
+            .map_err(|e| format!("文件监听通道已关闭: {}", e))?;
+        let mut pending: Vec<AppType> = Vec::new();
+        let mut collect = |res: notify::Result<Event>| {
+            if let Ok(event) = res {
+                for path in &event.paths {
+                    if let Some(app_type) = app_type_for_path(&app, path) {
+                        if !pending.iter().any(|a| a.as_str() == app_type.as_str()) {
+                            pending.push(app_type);
+                        }
+                    }
+                }
+            }
+        };
+        collect(first);
+        while let Ok(res) = rx.recv_timeout(DEBOUNCE) {
+            collect(res);
+        }
+
+        for app_type in pending {
+            if let Err(e) = check_drift(&app, &app_type) {
+                tracing::debug!("检查 {} 配置漂移失败: {}", app_type.as_str(), e);
+            }
+        }
+    }
+}
+
+/// 比较 live 配置与当前供应商配置，不一致时发送漂移事件
+pub fn check_drift(app: &tauri::AppHandle, app_type: &AppType) -> Result<(), String> {
+    // 代理模式下 live 配置由代理接管，环境变量模式不写入 live 配置，均不做比较
+    if crate::settings::get_settings().operation_mode != OperationMode::Write {
+        return Ok(());
+    }
+
+    let state = app.try_state::<AppState>().ok_or("应用状态尚未就绪")?;
+    let (provider_id, provider_name, expected, dir) = {
+        let config = state
+            .config
+            .lock()
+            .map_err(|e| format!("获取锁失败: {}", e))?;
+        let manager = config.get_manager(app_type).ok_or("应用类型不存在")?;
+        let Some(provider) = manager.providers.get(&manager.current) else {
+            return Ok(());
+        };
+        let dir = live_dir(app_type, Some(provider));
+        (
+            provider.id.clone(),
+            provider.name.clone(),
+            crate::common_config::live_settings(app_type, manager, provider, &dir),
+            dir,
+        )
+    };
+
+    let live = read_live_settings(app_type, &dir)?;
+    let mut prints = fingerprints()
+        .lock()
+        .map_err(|e| format!("获取锁失败: {}", e))?;
+    let entry = prints.entry(app_type.as_str().to_string()).or_default();
+
+    if live_matches(app_type, &expected, &live) {
+        // 已恢复一致：清除记录，下次漂移时重新提醒
+        *entry = DriftFingerprint::default();
+        return Ok(());
+    }
+
+    let fingerprint = fingerprint_of(&live);
+    if entry.ignored.as_deref() == Some(fingerprint.as_str())
+        || entry.notified.as_deref() == Some(fingerprint.as_str())
+    {
+        return Ok(());
+    }
+    entry.notified = Some(fingerprint);
+    drop(prints);
+
+    tracing::info!(
+        "检测到 {} live 配置被外部修改（当前供应商: {}）",
+        app_type.as_str(),
+        provider_name
+    );
+    let payload = ConfigDriftPayload {
+        app_type: app_type.as_str().to_string(),
+        provider_id,
+        provider_name,
+        paths: live_paths(app_type, &dir)
+            .iter()
+            .map(|p| p.display().to_string())
+            .collect(),
+    };
+    app.emit(CONFIG_DRIFT_EVENT, &payload)
+        .map_err(|e| format!("发射配置漂移事件失败: {}", e))
+}
+
+/// 忽略当前 live 内容的漂移（内容再次变化前不再提醒），dir 为当前供应商的 live 目录
+pub fn ignore_current_drift(app_type: &AppType, dir: &std::path::Path) -> Result<(), String> {
+    let live = read_live_settings(app_type, dir)?;
+    let mut prints = fingerprints()
+        .lock()
+        .map_err(|e| format!("获取锁失败: {}", e))?;
+    prints
+        .entry(app_type.as_str().to_string())
+        .or_default()
+        .ignored = Some(fingerprint_of(&live));
+    Ok(())
+}
@@ -0,0 +1,457 @@
+//! 本地控制接口：在应用运行期间通过 Unix Socket（Windows 下为命名管道）
+//! 提供按行分隔的 JSON-RPC 2.0 服务，供外部工具或脚本切换供应商、重新应用当前配置、查询当前供应商与代理统计。
+//! 所有写操作都经由应用内的同一套切换逻辑完成，避免与 GUI 竞争写配置文件。
+//!
+//! 示例：`echo '{"jsonrpc":"2.0","id":1,"method":"getCurrentProvider","params":{"app":"claude"}}' | nc -U ~/.cc-switch/run/control.sock`
+
+use serde::Deserialize;
+use serde_json::{json, Value};
+use tauri::Manager;
+use tokio::io::{AsyncBufReadExt, AsyncRead, AsyncWrite, AsyncWriteExt, BufReader};
+
+use crate::app_config::AppType;
+use crate::store::AppState;
+
+/// 重新应用当前配置的子命令名：`cc-switch reapply [claude|codex]`
+pub const REAPPLY_SUBCOMMAND: &str = "reapply";
+
+/// 单行请求的最大长度（字节）
+const MAX_LINE_LEN: usize = 64 * 1024;
+
+/// JSON-RPC 错误码
+const PARSE_ERROR: i64 = -32700;
+const INVALID_REQUEST: i64 = -32600;
+const METHOD_NOT_FOUND: i64 = -32601;
+const INVALID_PARAMS: i64 = -32602;
+const INTERNAL_ERROR: i64 = -32603;
+
+#[derive(Debug, Deserialize)]
+struct RpcRequest {
+    #[serde(default)]
+    jsonrpc: Option<String>,
+    #[serde(default)]
+    id: Value,
+    method: String,
+    #[serde(default)]
+    params: Value,
+}
+
+struct RpcError {
+    code: i64,
+    message: String,
+}
+
+impl RpcError {
+    fn new(code: i64, message: impl Into<String>) -> Self {
+        Self {
+            code,
+            message: message.into(),
+        }
+    }
+}
+
+fn success_response(id: Value, result: Value) -> Value {
+    json!({ "jsonrpc": "2.0", "id": id, "result": result })
+}
+
+fn error_response(id: Value, err: RpcError) -> Value {
+    json!({
+        "jsonrpc": "2.0",
+        "id": id,
+        "error": { "code": err.code, "message": err.message }
+    })
+}
+
+/// 控制接口地址（Unix Socket 路径）：位于仅当前用户可访问的 `run` 目录中
+#[cfg(unix)]
+pub fn control_socket_path() -> std::path::PathBuf {
+    crate::config::get_app_config_dir()
+        .join("run")
+        .join("control.sock")
+}
+
+/// 控制接口地址（命名管道名称）
+#[cfg(windows)]
+pub fn control_pipe_name() -> String {
+    r"\\.\pipe\cc-switch-control".to_string()
+}
+
+/// 启动控制接口服务（后台运行，失败仅记录日志）
+pub fn start_control_server(app: tauri::AppHandle) {
+    tauri::async_runtime::spawn(async move {
+        if let Err(e) = serve(app).await {
+            tracing::warn!("本地控制接口启动失败: {}", e);
+        }
+    });
+}
+
+#[cfg(unix)]
+async fn serve(app: tauri::AppHandle) -> Result<(), String> {
+    use std::os::unix::fs::PermissionsExt;
+    use tokio::net::UnixListener;
+
+    let path = control_socket_path();
+    // socket 在 0700 目录中创建，绑定到修改权限之间其他用户也无法连接；无法收紧权限时不提供服务
+    if let Some(parent) = path.parent() {
+        std::fs::create_dir_all(parent).map_err(|e| format!("创建目录失败: {}", e))?;
+        std::fs::set_permissions(parent, std::fs::Permissions::from_mode(0o700))
+            .map_err(|e| format!("设置控制 socket 目录权限失败: {}", e))?;
+    }
+    // 清理上次异常退出遗留的 socket 文件（单实例插件保证此时没有其他实例在监听）
+    if path.exists() {
+        std::fs::remove_file(&path).map_err(|e| format!("删除旧的 socket 文件失败: {}", e))?;
+    }
+
+    let listener = UnixListener::bind(&path).map_err(|e| format!("绑定控制 socket 失败: {}", e))?;
+    // 仅允许当前用户访问
+    std::fs::set_permissions(&path, std::fs::Permissions::from_mode(0o600))
+        .map_err(|e| format!("设置控制 socket 权限失败: {}", e))?;
+    tracing::info!("本地控制接口已启动: {}", path.display());
+
+    loop {
+        match listener.accept().await {
+            Ok((stream, _)) => {
+                let app = app.clone();
+                tauri::async_runtime::spawn(async move {
+                    handle_connection(stream, app).await;
+                });
+            }
+            Err(e) => {
+                tracing::warn!("接受控制连接失败: {}", e);
+            }
+        }
+    }
+}
+
+#[cfg(windows)]
+async fn serve(app: tauri::AppHandle) -> Result<(), String> {
+    use tokio::net::windows::named_pipe::ServerOptions;
+
+    let name = control_pipe_name();
+    let mut server = ServerOptions::new()
+        .first_pipe_instance(true)
+        .create(&name)
+        .map_err(|e| format!("创建控制命名管道失败: {}", e))?;
+    tracing::info!("本地控制接口已启动: {}", name);
+
+    loop {
+        if let Err(e) = server.connect().await {
+            tracing::warn!("接受控制连接失败: {}", e);
+            continue;
+        }
+        let connected = server;
+        server = ServerOptions::new()
+            .create(&name)
+            .map_err(|e| format!("创建控制命名管道失败: {}", e))?;
+
+        let app = app.clone();
+        tauri::async_runtime::spawn(async move {
+            handle_connection(connected, app).await;
+        });
+    }
+}
+
+#[cfg(not(any(unix, windows)))]
+async fn serve(_app: tauri::AppHandle) -> Result<(), String> {
+    Err("当前平台不支持本地控制接口".to_string())
+}
+
+/// 处理单个连接：逐行读取请求并逐行写回响应
+async fn handle_connection<S>(stream: S, app: tauri::AppHandle)
+where
+    S: AsyncRead + AsyncWrite + Unpin,
+{
+    let (reader, mut writer) = tokio::io::split(stream);
+    let mut reader = BufReader::new(reader);
+    let mut line = String::new();
+
+    loop {
+        line.clear();
+        match reader.read_line(&mut line).await {
+            Ok(0) => break,
+            Ok(_) => {}
+            Err(e) => {
+                tracing::debug!("读取控制请求失败: {}", e);
+                break;
+            }
+        }
+
+        let response = if line.len() > MAX_LINE_LEN {
+            Some(error_response(
+                Value::Null,
+                RpcError::new(INVALID_REQUEST, "请求过长"),
+            ))
+        } else if line.trim().is_empty() {
+            None
+        } else {
+            handle_line(line.trim(), &app).await
+        };
+
+        if let Some(response) = response {
+            let mut text = response.to_string();
+            text.push('\n');
+            if writer.write_all(text.as_bytes()).await.is_err() {
+                break;
+            }
+            let _ = writer.flush().await;
+        }
+    }
+}
+
+/// 解析并执行单条请求；通知（无 id）不返回响应
+async fn handle_line(line: &str, app: &tauri::AppHandle) -> Option<Value> {
+    let request: RpcRequest = match serde_json::from_str(line) {
+        Ok(req) => req,
+        Err(e) => {
+            return Some(error_response(
+                Value::Null,
+                RpcError::new(PARSE_ERROR, format!("解析请求失败: {}", e)),
+            ))
+        }
+    };
+
+    if request.jsonrpc.as_deref().is_some_and(|v| v != "2.0") {
+        return Some(error_response(
+            request.id,
+            RpcError::new(INVALID_REQUEST, "仅支持 JSON-RPC 2.0"),
+        ));
+    }
+
+    let is_notification = request.id.is_null();
+    let result = dispatch(&request.method, &request.params, app).await;
+    if is_notification {
+        return None;
+    }
+
+    Some(match result {
+        Ok(value) => success_response(request.id, value),
+        Err(err) => error_response(request.id, err),
+    })
+}
+
+/// 从参数中解析应用类型（缺省为 Claude）
+fn parse_app_type(params: &Value) -> AppType {
+    params
+        .get("app")
+        .or_else(|| params.get("appType"))
+        .and_then(|v| v.as_str())
+        .map(AppType::from)
+        .unwrap_or(AppType::Claude)
+}
+
+fn parse_provider_id(params: &Value) -> Result<String, RpcError> {
+    params
+        .get("id")
+        .or_else(|| params.get("providerId"))
+        .and_then(|v| v.as_str())
+        .map(|s| s.to_string())
+        .ok_or_else(|| RpcError::new(INVALID_PARAMS, "缺少 providerId 参数"))
+}
+
+async fn dispatch(method: &str, params: &Value, app: &tauri::AppHandle) -> Result<Value, RpcError> {
+    match method {
+        "ping" => Ok(json!({ "version": app.package_info().version.to_string() })),
+        "getCurrentProvider" => current_provider(app, &parse_app_type(params)),
+        "listProviders" => list_providers(app, &parse_app_type(params)),
+        "switchProvider" => {
+            let app_type = parse_app_type(params);
+            let provider_id = parse_provider_id(params)?;
+            crate::switch_provider_internal(app, app_type.clone(), provider_id)
+                .await
+                .map_err(|e| RpcError::new(INTERNAL_ERROR, e))?;
+            current_provider(app, &app_type)
+        }
+        "reapplyCurrent" => {
+            let app_type = parse_app_type(params);
+            let state = app
+                .try_state::<AppState>()
+                .ok_or_else(|| RpcError::new(INTERNAL_ERROR, "应用状态尚未就绪"))?;
+            crate::self_check::reapply_live(&state, &app_type)
+                .map_err(|e| RpcError::new(INTERNAL_ERROR, e))?;
+            current_provider(app, &app_type)
+        }
+        "getUsage" => {
+            let app_type = parse_app_type(params);
+            let provider_id = match parse_provider_id(params) {
+                Ok(id) => id,
+                Err(_) => current_provider_id(app, &app_type)?,
+            };
+            let state = app
+                .try_state::<AppState>()
+                .ok_or_else(|| RpcError::new(INTERNAL_ERROR, "应用状态尚未就绪"))?;
+            let usage = crate::commands::query_provider_usage(
+                app.clone(),
+                state,
+                Some(provider_id),
+                None,
+                Some(app_type),
+                None,
+                None,
+            )
+            .await
+            .map_err(|e| RpcError::new(INTERNAL_ERROR, e.to_string()))?;
+            serde_json::to_value(usage).map_err(|e| RpcError::new(INTERNAL_ERROR, e.to_string()))
+        }
+        "getProxyStats" => {
+            let stats = crate::proxy::get_proxy_stats().await;
+            serde_json::to_value(stats).map_err(|e| RpcError::new(INTERNAL_ERROR, e.to_string()))
+        }
+        _ => Err(RpcError::new(
+            METHOD_NOT_FOUND,
+            format!("未知方法: {}", method),
+        )),
+    }
+}
+
+fn current_provider(app: &tauri::AppHandle, app_type: &AppType) -> Result<Value, RpcError> {
+    let state = app
+        .try_state::<AppState>()
+        .ok_or_else(|| RpcError::new(INTERNAL_ERROR, "应用状态尚未就绪"))?;
+    let config = state
+        .config
+        .lock()
+        .map_err(|e| RpcError::new(INTERNAL_ERROR, format!("获取锁失败: {}", e)))?;
+    let manager = config
+        .get_manager(app_type)
+        .ok_or_else(|| RpcError::new(INVALID_PARAMS, "应用类型不存在"))?;
+
+    Ok(match manager.providers.get(&manager.current) {
+        Some(provider) => json!({
+            "appType": app_type.as_str(),
+            "id": provider.id,
+            "name": provider.name,
+        }),
+        None => Value::Null,
+    })
+}
+
+/// 当前供应商 ID（未设置时报错）
+fn current_provider_id(app: &tauri::AppHandle, app_type: &AppType) -> Result<String, RpcError> {
+    current_provider(app, app_type)?
+        .get("id")
+        .and_then(|v| v.as_str())
+        .map(|s| s.to_string())
+        .ok_or_else(|| RpcError::new(INVALID_PARAMS, "当前没有选中的供应商"))
+}
+
+fn list_providers(app: &tauri::AppHandle, app_type: &AppType) -> Result<Value, RpcError> {
+    let state = app
+        .try_state::<AppState>()
+        .ok_or_else(|| RpcError::new(INTERNAL_ERROR, "应用状态尚未就绪"))?;
+    let config = state
+        .config
+        .lock()
+        .map_err(|e| RpcError::new(INTERNAL_ERROR, format!("获取锁失败: {}", e)))?;
+    let manager = config
+        .get_manager(app_type)
+        .ok_or_else(|| RpcError::new(INVALID_PARAMS, "应用类型不存在"))?;
+
+    Ok(Value::Array(
+        manager
+            .sorted_providers()
+            .into_iter()
+            .map(|p| {
+                json!({
+                    "id": p.id,
+                    "name": p.name,
+                    "current": p.id == manager.current,
+                    "proxyEnabled": p.proxy_enabled.unwrap_or(false),
+                    "enabled": p.is_enabled(),
+                    "locked": p.is_locked(),
+                })
+            })
+            .collect(),
+    ))
+}
+
+#[cfg(unix)]
+async fn connect() -> Result<tokio::net::UnixStream, String> {
+    tokio::net::UnixStream::connect(control_socket_path())
+        .await
+        .map_err(|e| format!("无法连接 cc-switch（请确认应用正在运行）: {}", e))
+}
+
+#[cfg(windows)]
+async fn connect() -> Result<tokio::net::windows::named_pipe::NamedPipeClient, String> {
+    tokio::net::windows::named_pipe::ClientOptions::new()
+        .open(control_pipe_name())
+        .map_err(|e| format!("无法连接 cc-switch（请确认应用正在运行）: {}", e))
+}
+
+/// 作为客户端调用正在运行的应用的控制接口（单次请求），返回 result 或错误信息
+#[cfg(any(unix, windows))]
+pub async fn call(method: &str, params: Value) -> Result<Value, String> {
+    let stream = connect().await?;
+    let (reader, mut writer) = tokio::io::split(stream);
+    let mut request =
+        json!({ "jsonrpc": "2.0", "id": 1, "method": method, "params": params }).to_string();
+    request.push('\n');
+    writer
+        .write_all(request.as_bytes())
+        .await
+        .map_err(|e| format!("发送控制请求失败: {}", e))?;
+    writer
+        .flush()
+        .await
+        .map_err(|e| format!("发送控制请求失败: {}", e))?;
+
+    let mut line = String::new();
+    BufReader::new(reader)
+        .read_line(&mut line)
+        .await
+        .map_err(|e| format!("读取控制响应失败: {}", e))?;
+    let response: Value =
+        serde_json::from_str(line.trim()).map_err(|e| format!("解析控制响应失败: {}", e))?;
+    if let Some(error) = response.get("error") {
+        return Err(error
+            .get("message")
+            .and_then(|m| m.as_str())
+            .unwrap_or("未知错误")
+            .to_string());
+    }
+    Ok(response.get("result").cloned().unwrap_or(Value::Null))
+}
+
+#[cfg(not(any(unix, windows)))]
+pub async fn call(_method: &str, _params: Value) -> Result<Value, String> {
+    Err("当前平台不支持本地控制接口".to_string())
+}
+
+/// 处理 `cc-switch reapply` 子命令：请求正在运行的应用重新写入当前配置（未指定应用时处理全部），
+/// 返回进程退出码；命令行不是该子命令时返回 None
+pub fn run_reapply_cli() -> Option<i32> {
+    let mut args = std::env::args().skip(1);
+    if args.next().as_deref() != Some(REAPPLY_SUBCOMMAND) {
+        return None;
+    }
+    let mut apps: Vec<String> = args.collect();
+    if apps.is_empty() {
+        apps = vec!["claude".to_string(), "codex".to_string()];
+    }
+
+    let runtime = match tokio::runtime::Builder::new_current_thread()
+        .enable_all()
+        .build()
+    {
+        Ok(runtime) => runtime,
+        Err(e) => {
+            eprintln!("创建异步运行时失败: {}", e);
+            return Some(1);
+        }
+    };
+
+    let mut code = 0;
+    for app in apps {
+        match runtime.block_on(call("reapplyCurrent", json!({ "app": app }))) {
+            Ok(current) => {
+                let name = current.get("name").and_then(|v| v.as_str()).unwrap_or("-");
+                println!("{}: {}", app, name);
+            }
+            Err(e) => {
+                eprintln!("{}: {}", app, e);
+                code = 1;
+            }
+        }
+    }
+    Some(code)
+}
@@ -0,0 +1,108 @@
+//! 本地应答 Anthropic `/v1/messages/count_tokens`：不少中转未实现该接口，转发后逐个供应商 404
+//! 并触发整轮故障转移。开启后由代理按字符数在本地估算 token 数并直接返回，不再转发给上游。
+//! 估算值不等于官方分词结果，仅供客户端判断上下文长度；相同请求体的结果会被缓存。
+
+use std::collections::hash_map::DefaultHasher;
+use std::collections::HashMap;
+use std::hash::{Hash, Hasher};
+use std::sync::{Mutex, OnceLock};
+
+use axum::body::Body;
+use axum::http::{header, Response, StatusCode};
+use serde_json::{json, Value};
+
+/// 缓存条目上限，超出后整体清空
+const CACHE_CAPACITY: usize = 256;
+
+/// 每条消息的固定开销（角色与分隔符）
+const MESSAGE_OVERHEAD: u64 = 3;
+
+/// 请求体哈希 -> 估算结果
+static CACHE: OnceLock<Mutex<HashMap<u64, u64>>> = OnceLock::new();
+
+/// 是否为 count_tokens 请求
+pub fn is_count_tokens(path: &str) -> bool {
+    let path = path.split('?').next().unwrap_or(path);
+    path.strip_prefix("/v1").unwrap_or(path) == "/messages/count_tokens"
+}
+
+/// 文本的 token 估算：ASCII 约 4 个字符一个 token，其余字符（如中日韩文字）按每字一个计
+fn text_tokens(text: &str) -> u64 {
+    let (ascii, other) = text.chars().fold((0u64, 0u64), |(ascii, other), c| {
+        if c.is_ascii() {
+            (ascii + 1, other)
+        } else {
+            (ascii, other + 1)
+        }
+    });
+    ascii.div_ceil(4) + other
+}
+
+/// 内容块（字符串或块数组）的 token 估算；非文本块按其 JSON 文本估算
+fn content_tokens(content: &Value) -> u64 {
+    match content {
+        Value::String(text) => text_tokens(text),
+        Value::Array(blocks) => blocks.iter().map(content_tokens).sum(),
+        Value::Object(block) => match block.get("type").and_then(Value::as_str) {
+            Some("text") => block.get("text").map(content_tokens).unwrap_or(0),
+            Some("tool_result") => block.get("content").map(content_tokens).unwrap_or(0),
+            // 图片等二进制内容没有可靠的字符估算，按固定值计
+            Some("image") | Some("document") => 1_000,
+            _ => text_tokens(&content.to_string()),
+        },
+        Value::Null => 0,
+        other => text_tokens(&other.to_string()),
+    }
+}
+
+/// 估算请求的输入 token 数；请求体不是 JSON 对象时返回 None
+pub fn estimate(body: &[u8]) -> Option<u64> {
+    let value: Value = serde_json::from_slice(body).ok()?;
+    let request = value.as_object()?;
+    let mut tokens = request.get("system").map(content_tokens).unwrap_or(0);
+    if let Some(messages) = request.get("messages").and_then(Value::as_array) {
+        tokens += messages
+            .iter()
+            .map(|message| {
+                MESSAGE_OVERHEAD + message.get("content").map(content_tokens).unwrap_or(0)
+            })
+            .sum::<u64>();
+    }
+    if let Some(tools) = request.get("tools").and_then(Value::as_array) {
+        tokens += tools
+            .iter()
+            .map(|tool| text_tokens(&tool.to_string()))
+            .sum::<u64>();
+    }
+    Some(tokens)
+}
+
+/// 本地应答 count_tokens 请求；无法解析请求体时返回 None，交由上游处理
+pub fn respond(body: &[u8]) -> Option<Response<Body>> {
+    let mut hasher = DefaultHasher::new();
+    body.hash(&mut hasher);
+    let key = hasher.finish();
+
+    let cache = CACHE.get_or_init(Default::default);
+    let cached = cache.lock().ok().and_then(|cache| cache.get(&key).copied());
+    let tokens = match cached {
+        Some(tokens) => tokens,
+        None => {
+            let tokens = estimate(body)?;
+            if let Ok(mut cache) = cache.lock() {
+                if cache.len() >= CACHE_CAPACITY {
+                    cache.clear();
+                }
+                cache.insert(key, tokens);
+            }
+            tokens
+        }
+    };
+    tracing::debug!("本地估算 count_tokens: {}", tokens);
+
+    Response::builder()
+        .status(StatusCode::OK)
+        .header(header::CONTENT_TYPE, "application/json")
+        .body(Body::from(json!({ "input_tokens": tokens }).to_string()))
+        .ok()
+}
@@ -0,0 +1,205 @@
+//! 当前供应商状态文件：每次切换后重写 `~/.cc-switch/current.json`，供 shell 提示符、状态栏与脚本
+//! 读取当前使用的中转，无需解析完整配置。文件只包含名称、地址与时间，不包含 API Key。
+//!
+//! 格式（`version` 变化表示不兼容改动）：
+//!
+//! ```json
+//! {
+//!   "version": 1,
+//!   "appType": "claude",
+//!   "providerId": "…",
+//!   "providerName": "My Relay",
+//!   "baseUrl": "https://relay.example.com",
+//!   "switchedAt": 1760000000000,
+//!   "operationMode": "write",
+//!   "updatedAt": 1760000000000,
+//!   "apps": {
+//!     "claude": { "appType": "claude", "providerId": "…", "providerName": "…", "baseUrl": "…", "switchedAt": 0,
+//!                 "balance": { "planName": "…", "remaining": 12.5, "unit": "USD", "queriedAt": 0 } },
+//!     "codex": { … }
+//!   }
+//! }
+//! ```
+//!
+//! 顶层的 `appType` ~ `switchedAt` 为最近一次切换的应用，`apps` 按应用列出各自的当前供应商；
+//! 时间均为毫秒时间戳。`baseUrl` 为供应商配置中的地址，代理模式下实际请求经本地代理转发（见 `operationMode`）。
+//! `balance` 为最近一次用量查询得到的剩余额度（未查询或查询结果不含剩余额度时省略），切换到其他供应商后清空。
+
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::path::PathBuf;
+
+use crate::app_config::{AppType, MultiAppConfig};
+use crate::config::{get_app_config_dir, write_json_file};
+use crate::provider::UsageData;
+use crate::store::AppState;
+
+/// 状态文件名
+const FILE_NAME: &str = "current.json";
+
+/// 状态文件格式版本
+const VERSION: u32 = 1;
+
+/// 单个应用的当前供应商
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CurrentProvider {
+    pub app_type: String,
+    pub provider_id: String,
+    pub provider_name: String,
+    pub base_url: Option<String>,
+    /// 切换到该供应商的时间（毫秒时间戳）
+    pub switched_at: i64,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub balance: Option<Balance>,
+}
+
+/// 最近一次用量查询得到的剩余额度
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Balance {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub plan_name: Option<String>,
+    pub remaining: f64,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub unit: Option<String>,
+    /// 查询时间（毫秒时间戳）
+    pub queried_at: i64,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CurrentStatus {
+    pub version: u32,
+    /// 最近一次切换的应用
+    #[serde(flatten)]
+    pub last: Option<CurrentProvider>,
+    pub operation_mode: String,
+    pub updated_at: i64,
+    #[serde(default)]
+    pub apps: BTreeMap<String, CurrentProvider>,
+}
+
+fn file_path() -> PathBuf {
+    get_app_config_dir().join(FILE_NAME)
+}
+
+/// 读取现有状态文件（不存在或无法解析时为空）
+pub fn read() -> Option<CurrentStatus> {
+    let path = file_path();
+    if !path.exists() {
+        return None;
+    }
+    crate::config::read_json_file(&path).ok()
+}
+
+fn build(config: &MultiAppConfig, switched: Option<&AppType>) -> CurrentStatus {
+    let now = chrono::Utc::now().timestamp_millis();
+    let existing = read();
+
+    let mut apps = BTreeMap::new();
+    for app_type in [AppType::Claude, AppType::Codex] {
+        let Some(manager) = config.get_manager(&app_type) else {
+            continue;
+        };
+        let Some(provider) = manager.providers.get(&manager.current) else {
+            continue;
+        };
+        let key = app_type.as_str().to_string();
+        let is_switched = switched.is_some_and(|s| s.as_str() == app_type.as_str());
+        let previous = existing
+            .as_ref()
+            .and_then(|status| status.apps.get(&key))
+            .filter(|previous| previous.provider_id == provider.id);
+        // 供应商未变化时保留原切换时间，避免启动或编辑时刷新
+        let switched_at = previous
+            .filter(|_| !is_switched)
+            .map_or(now, |previous| previous.switched_at);
+        apps.insert(
+            key.clone(),
+            CurrentProvider {
+                app_type: key,
+                provider_id: provider.id.clone(),
+                provider_name: provider.name.clone(),
+                base_url: provider.base_url(&app_type),
+                switched_at,
+                balance: previous.and_then(|previous| previous.balance.clone()),
+            },
+        );
+    }
+
+    let last_app = switched
+        .map(|app_type| app_type.as_str().to_string())
+        .or_else(|| {
+            existing
+                .and_then(|status| status.last)
+                .map(|last| last.app_type)
+        })
+        .filter(|app| apps.contains_key(app))
+        .or_else(|| apps.keys().next().cloned());
+    CurrentStatus {
+        version: VERSION,
+        last: last_app.and_then(|app| apps.get(&app).cloned()),
+        operation_mode: crate::settings::get_settings()
+            .operation_mode
+            .as_str()
+            .to_string(),
+        updated_at: now,
+        apps,
+    }
+}
+
+/// 按当前配置重写状态文件；`switched` 为刚切换的应用（启动、编辑或切换运行模式时为空）
+pub fn refresh(config: &MultiAppConfig, switched: Option<&AppType>) {
+    if let Err(e) = write_json_file(&file_path(), &build(config, switched)) {
+        tracing::warn!("更新当前供应商状态文件失败: {}", e);
+    }
+}
+
+/// 记录一次用量查询结果：仅当查询的是该应用的当前供应商时更新剩余额度
+pub fn record_usage(
+    config: &MultiAppConfig,
+    app_type: &AppType,
+    provider_id: &str,
+    usage: &[UsageData],
+) {
+    let is_current = config
+        .get_manager(app_type)
+        .is_some_and(|manager| manager.current == provider_id);
+    if !is_current {
+        return;
+    }
+    // 与状态栏一致：取第一个带剩余额度的套餐
+    let balance = usage
+        .iter()
+        .find(|plan| plan.remaining.is_some())
+        .map(|plan| Balance {
+            plan_name: plan.plan_name.clone(),
+            remaining: plan.remaining.unwrap_or_default(),
+            unit: plan.unit.clone(),
+            queried_at: chrono::Utc::now().timestamp_millis(),
+        });
+
+    let mut status = build(config, None);
+    if let Some(entry) = status.apps.get_mut(app_type.as_str()) {
+        entry.balance = balance.clone();
+    }
+    if let Some(last) = status
+        .last
+        .as_mut()
+        .filter(|last| last.app_type == app_type.as_str())
+    {
+        last.balance = balance;
+    }
+    if let Err(e) = write_json_file(&file_path(), &status) {
+        tracing::warn!("更新当前供应商状态文件失败: {}", e);
+    }
+}
+
+/// 从应用状态读取配置并重写状态文件（启动与切换运行模式时调用）
+pub fn sync(app_state: &AppState) {
+    match app_state.config.lock() {
+        Ok(config) => refresh(&config, None),
+        Err(e) => tracing::warn!("读取配置失败: {}", e),
+    }
+}
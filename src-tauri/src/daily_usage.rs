@@ -0,0 +1,212 @@
+//! 每日用量汇总：代理模式下按日期（本地时区）与供应商累计请求数、失败数与 token 用量，
+//! 写入应用数据库的 `daily_usage` 表，用于前端的用量日历。与逐条的请求记录不同，
+//! 每个供应商每天只有一行，可长期保留。计数先在内存中累加，定期与退出时批量写入。
+
+use std::collections::HashMap;
+use std::sync::{Mutex, OnceLock};
+use std::time::Duration;
+
+use chrono::{Local, NaiveDate};
+use rusqlite::params;
+use serde::Serialize;
+
+use crate::app_config::AppType;
+use crate::database::db_err;
+use crate::error::CcSwitchError;
+use crate::i18n::t_args;
+use crate::provider::Provider;
+
+/// 写入数据库的间隔
+const FLUSH_INTERVAL: Duration = Duration::from_secs(60);
+
+/// 单次查询允许的最大天数
+const MAX_RANGE_DAYS: i64 = 366;
+
+/// (日期, 应用类型, 供应商 ID)
+type DayKey = (String, String, String);
+
+/// 尚未写入数据库的增量
+static PENDING: OnceLock<Mutex<HashMap<DayKey, Counter>>> = OnceLock::new();
+
+#[derive(Debug, Clone, Default)]
+struct Counter {
+    provider_name: String,
+    requests: u64,
+    failures: u64,
+    tokens: u64,
+}
+
+/// 某天某个供应商的用量汇总
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DailyUsage {
+    /// 本地日期（YYYY-MM-DD）
+    pub date: String,
+    pub app_type: String,
+    pub provider_id: String,
+    pub provider_name: String,
+    pub requests: u64,
+    pub failures: u64,
+    pub tokens: u64,
+}
+
+fn with_counter(app_type: &AppType, provider: &Provider, f: impl FnOnce(&mut Counter)) {
+    let Ok(mut pending) = PENDING.get_or_init(Default::default).lock() else {
+        return;
+    };
+    let key = (
+        Local::now().format("%Y-%m-%d").to_string(),
+        app_type.as_str().to_string(),
+        provider.id.clone(),
+    );
+    let counter = pending.entry(key).or_default();
+    counter.provider_name = provider.name.clone();
+    f(counter);
+}
+
+/// 记录一次发往上游的请求
+pub fn record(app_type: &AppType, provider: &Provider, success: bool) {
+    with_counter(app_type, provider, |counter| {
+        counter.requests += 1;
+        if !success {
+            counter.failures += 1;
+        }
+    });
+}
+
+/// 记录一次响应消耗的 token
+pub fn record_tokens(app_type: &AppType, provider: &Provider, tokens: u64) {
+    if tokens > 0 {
+        with_counter(app_type, provider, |counter| counter.tokens += tokens);
+    }
+}
+
+/// 将内存中的增量写入数据库；写入失败时放回，下次重试
+pub fn flush() -> Result<(), String> {
+    let pending = match PENDING.get() {
+        Some(pending) => {
+            let mut pending = pending.lock().map_err(|e| format!("获取锁失败: {}", e))?;
+            std::mem::take(&mut *pending)
+        }
+        None => return Ok(()),
+    };
+    if pending.is_empty() {
+        return Ok(());
+    }
+    if let Err(e) = write(&pending) {
+        if let Some(Ok(mut current)) = PENDING.get().map(Mutex::lock) {
+            for (key, counter) in pending {
+                let entry = current.entry(key).or_default();
+                entry.provider_name = counter.provider_name;
+                entry.requests += counter.requests;
+                entry.failures += counter.failures;
+                entry.tokens += counter.tokens;
+            }
+        }
+        return Err(e);
+    }
+    Ok(())
+}
+
+fn write(pending: &HashMap<DayKey, Counter>) -> Result<(), String> {
+    let mut conn = crate::database::open()?;
+    let tx = conn.transaction().map_err(db_err)?;
+    for ((date, app_type, provider_id), counter) in pending {
+        tx.execute(
+            "INSERT INTO daily_usage
+                 (date, app_type, provider_id, provider_name, requests, failures, tokens)
+             VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7)
+             ON CONFLICT (date, app_type, provider_id) DO UPDATE SET
+                 provider_name = excluded.provider_name,
+                 requests = requests + excluded.requests,
+                 failures = failures + excluded.failures,
+                 tokens = tokens + excluded.tokens",
+            params![
+                date,
+                app_type,
+                provider_id,
+                counter.provider_name,
+                counter.requests as i64,
+                counter.failures as i64,
+                counter.tokens as i64
+            ],
+        )
+        .map_err(db_err)?;
+    }
+    tx.commit().map_err(db_err)?;
+    Ok(())
+}
+
+/// 后台定期写入数据库
+pub fn start_daily_usage_flush() {
+    tauri::async_runtime::spawn(async {
+        loop {
+            tokio::time::sleep(FLUSH_INTERVAL).await;
+            if let Err(e) = flush() {
+                tracing::warn!("写入每日用量汇总失败: {}", e);
+            }
+        }
+    });
+}
+
+fn parse_date(value: &str) -> Result<NaiveDate, String> {
+    NaiveDate::parse_from_str(value.trim(), "%Y-%m-%d")
+        .map_err(|_| t_args("errors.invalidDate", &[("date", &value)]))
+}
+
+/// 查询日期范围内（含首尾，YYYY-MM-DD）的每日用量汇总，按日期与请求数排序
+pub fn query(start: &str, end: &str) -> Result<Vec<DailyUsage>, String> {
+    let (start, end) = (parse_date(start)?, parse_date(end)?);
+    if end < start || (end - start).num_days() >= MAX_RANGE_DAYS {
+        return Err(t_args(
+            "errors.dateRangeInvalid",
+            &[("days", &MAX_RANGE_DAYS)],
+        ));
+    }
+    // 先写入尚在内存中的计数，保证当天数据是最新的
+    if let Err(e) = flush() {
+        tracing::warn!("写入每日用量汇总失败: {}", e);
+    }
+
+    let conn = crate::database::open()?;
+    let mut stmt = conn
+        .prepare(
+            "SELECT date, app_type, provider_id, provider_name, requests, failures, tokens
+             FROM daily_usage WHERE date >= ?1 AND date <= ?2
+             ORDER BY date, requests DESC",
+        )
+        .map_err(db_err)?;
+    let rows = stmt
+        .query_map(
+            params![
+                start.format("%Y-%m-%d").to_string(),
+                end.format("%Y-%m-%d").to_string()
+            ],
+            |row| {
+                Ok(DailyUsage {
+                    date: row.get(0)?,
+                    app_type: row.get(1)?,
+                    provider_id: row.get(2)?,
+                    provider_name: row.get(3)?,
+                    requests: row.get::<_, i64>(4)? as u64,
+                    failures: row.get::<_, i64>(5)? as u64,
+                    tokens: row.get::<_, i64>(6)? as u64,
+                })
+            },
+        )
+        .map_err(db_err)?;
+    let mut summary = Vec::new();
+    for row in rows {
+        summary.push(row.map_err(db_err)?);
+    }
+    Ok(summary)
+}
+
+/// 获取日期范围内（含首尾，YYYY-MM-DD）的每日用量汇总
+#[tauri::command]
+pub async fn get_daily_summary(
+    start: String,
+    end: String,
+) -> Result<Vec<DailyUsage>, CcSwitchError> {
+    Ok(query(&start, &end)?)
+}
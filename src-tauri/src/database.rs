@@ -0,0 +1,334 @@
+//! 嵌入式 SQLite 存储：供应商、当前选中项、MCP 与项目绑定等配置，以及审计日志与每日用量汇总，统一保存在
+//! 应用配置目录下的 `cc-switch.db`。供应商按行存储，保存时只写入内容发生变化的行；
+//! 数据库以 WAL 模式打开并设置 busy_timeout，GUI 与 CLI 进程可以同时读写。
+//! 每次保存递增 `meta.revision`：写入前核对修订号，拒绝覆盖其他进程在此期间写入的配置。
+//! 配置的读取与“读修订号-核对-写入”全程持有 `config.lock` 上的系统建议锁，多个进程不会交错执行。
+//! 旧版 config.json / audit.log 在首次启动时由 `store` 与 `audit` 导入。
+
+use std::collections::HashSet;
+use std::fs::{self, File, OpenOptions};
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::time::Duration;
+
+use fs2::FileExt;
+use rusqlite::{params, Connection, OptionalExtension, Transaction, TransactionBehavior};
+use serde_json::{json, Map, Value};
+
+use crate::app_config::MultiAppConfig;
+use crate::config::get_app_db_path;
+
+/// 其他进程持有写锁时的最长等待时间
+const BUSY_TIMEOUT: Duration = Duration::from_secs(5);
+
+/// 配置读写锁文件名（与数据库同目录）
+const LOCK_FILE: &str = "config.lock";
+
+/// 本进程最近一次加载或写入的配置修订号；数据库中的修订号与之不同说明配置被其他进程修改过
+static KNOWN_REVISION: AtomicU64 = AtomicU64::new(0);
+
+/// 数据库结构迁移：第 i 项负责 user_version i → i+1；结构变更时在末尾追加
+const SCHEMA: [&str; 2] = [
+    r#"
+CREATE TABLE IF NOT EXISTS meta (
+    key TEXT PRIMARY KEY,
+    value TEXT NOT NULL
+);
+CREATE TABLE IF NOT EXISTS apps (
+    app_type TEXT PRIMARY KEY,
+    current TEXT NOT NULL DEFAULT ''
+);
+CREATE TABLE IF NOT EXISTS providers (
+    app_type TEXT NOT NULL,
+    id TEXT NOT NULL,
+    data TEXT NOT NULL,
+    PRIMARY KEY (app_type, id)
+);
+CREATE TABLE IF NOT EXISTS audit_log (
+    id INTEGER PRIMARY KEY AUTOINCREMENT,
+    timestamp INTEGER NOT NULL,
+    user TEXT NOT NULL,
+    action TEXT NOT NULL,
+    app_type TEXT,
+    target TEXT,
+    detail TEXT
+);
+CREATE INDEX IF NOT EXISTS idx_audit_log_timestamp ON audit_log (timestamp);
+"#,
+    r#"
+CREATE TABLE IF NOT EXISTS daily_usage (
+    date TEXT NOT NULL,
+    app_type TEXT NOT NULL,
+    provider_id TEXT NOT NULL,
+    provider_name TEXT NOT NULL,
+    requests INTEGER NOT NULL DEFAULT 0,
+    failures INTEGER NOT NULL DEFAULT 0,
+    tokens INTEGER NOT NULL DEFAULT 0,
+    PRIMARY KEY (date, app_type, provider_id)
+);
+"#,
+];
+
+/// 配置文件建议锁：持有期间其他进程无法进入配置的读写流程，离开作用域时释放
+struct ConfigFileLock(File);
+
+impl Drop for ConfigFileLock {
+    fn drop(&mut self) {
+        if let Err(e) = FileExt::unlock(&self.0) {
+            tracing::warn!("释放配置文件锁失败: {}", e);
+        }
+    }
+}
+
+/// 获取配置文件建议锁：读取使用共享锁，写入使用排他锁
+fn lock_config(exclusive: bool) -> Result<ConfigFileLock, String> {
+    let path = get_app_db_path().with_file_name(LOCK_FILE);
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent)
+            .map_err(|e| format!("创建目录失败: {}: {}", parent.display(), e))?;
+    }
+    let file = OpenOptions::new()
+        .create(true)
+        .truncate(false)
+        .read(true)
+        .write(true)
+        .open(&path)
+        .map_err(|e| format!("打开配置锁文件失败: {}: {}", path.display(), e))?;
+    let result = if exclusive {
+        FileExt::lock_exclusive(&file)
+    } else {
+        FileExt::lock_shared(&file)
+    };
+    result.map_err(|e| format!("获取配置文件锁失败: {}: {}", path.display(), e))?;
+    Ok(ConfigFileLock(file))
+}
+
+pub fn db_err(e: rusqlite::Error) -> String {
+    format!("数据库操作失败: {}", e)
+}
+
+/// 打开数据库（不存在时创建），并按需升级表结构
+pub fn open() -> Result<Connection, String> {
+    let path = get_app_db_path();
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent)
+            .map_err(|e| format!("创建目录失败: {}: {}", parent.display(), e))?;
+    }
+    let mut conn = Connection::open(&path)
+        .map_err(|e| format!("打开数据库失败: {}: {}", path.display(), e))?;
+    conn.busy_timeout(BUSY_TIMEOUT).map_err(db_err)?;
+    conn.pragma_update(None, "journal_mode", "WAL")
+        .map_err(db_err)?;
+    migrate_schema(&mut conn)?;
+    Ok(conn)
+}
+
+fn migrate_schema(conn: &mut Connection) -> Result<(), String> {
+    let version: usize = conn
+        .pragma_query_value(None, "user_version", |row| row.get(0))
+        .map_err(db_err)?;
+    if version > SCHEMA.len() {
+        return Err(format!(
+            "数据库版本 (v{}) 高于当前应用支持的版本 (v{})，请升级 cc-switch",
+            version,
+            SCHEMA.len()
+        ));
+    }
+    for (index, sql) in SCHEMA.iter().enumerate().skip(version) {
+        let tx = conn.transaction().map_err(db_err)?;
+        tx.execute_batch(sql).map_err(db_err)?;
+        tx.pragma_update(None, "user_version", index + 1)
+            .map_err(db_err)?;
+        tx.commit().map_err(db_err)?;
+        tracing::info!("数据库结构已升级到 v{}", index + 1);
+    }
+    Ok(())
+}
+
+fn get_meta(conn: &Connection, key: &str) -> Result<Option<String>, String> {
+    conn.query_row("SELECT value FROM meta WHERE key = ?1", [key], |row| {
+        row.get(0)
+    })
+    .optional()
+    .map_err(db_err)
+}
+
+fn set_meta(tx: &Transaction, key: &str, value: &str) -> Result<(), String> {
+    tx.execute(
+        "INSERT INTO meta (key, value) VALUES (?1, ?2)
+         ON CONFLICT (key) DO UPDATE SET value = excluded.value
+         WHERE value != excluded.value",
+        params![key, value],
+    )
+    .map_err(db_err)?;
+    Ok(())
+}
+
+fn read_revision(conn: &Connection) -> Result<u64, String> {
+    Ok(get_meta(conn, "revision")?
+        .and_then(|v| v.parse().ok())
+        .unwrap_or(0))
+}
+
+/// 数据库中当前的配置修订号
+pub fn current_revision() -> Result<u64, String> {
+    read_revision(&open()?)
+}
+
+/// 本进程已知（最近加载或写入）的配置修订号
+pub fn known_revision() -> u64 {
+    KNOWN_REVISION.load(Ordering::SeqCst)
+}
+
+fn parse_json(key: &str, raw: &str) -> Result<Value, String> {
+    serde_json::from_str(raw).map_err(|e| format!("数据库中的 {} 不是有效 JSON: {}", key, e))
+}
+
+fn to_json<T: serde::Serialize>(value: &T) -> Result<String, String> {
+    serde_json::to_string(value).map_err(|e| format!("序列化配置失败: {}", e))
+}
+
+/// 读取配置并组装为与 config.json 同构的 JSON，交由 `store` 做版本校验与迁移；
+/// 数据库中尚无配置（从未保存或导入）时返回 None
+pub fn load_config_value() -> Result<Option<Value>, String> {
+    let _lock = lock_config(false)?;
+    let mut conn = open()?;
+    // 在同一个读事务内读取各表，保证看到的是某次保存后的完整快照
+    let tx = conn.transaction().map_err(db_err)?;
+    let Some(version) = get_meta(&tx, "config_version")? else {
+        return Ok(None);
+    };
+    KNOWN_REVISION.store(read_revision(&tx)?, Ordering::SeqCst);
+
+    let mut root = Map::new();
+    root.insert(
+        "config_version".to_string(),
+        parse_json("config_version", &version)?,
+    );
+    for key in ["mcp", "projects"] {
+        if let Some(raw) = get_meta(&tx, key)? {
+            root.insert(key.to_string(), parse_json(key, &raw)?);
+        }
+    }
+
+    let mut stmt = tx
+        .prepare("SELECT app_type, current FROM apps")
+        .map_err(db_err)?;
+    let apps = stmt
+        .query_map([], |row| {
+            Ok((row.get::<_, String>(0)?, row.get::<_, String>(1)?))
+        })
+        .map_err(db_err)?;
+    for app in apps {
+        let (app_type, current) = app.map_err(db_err)?;
+        root.insert(app_type, json!({ "providers": {}, "current": current }));
+    }
+
+    let mut stmt = tx
+        .prepare("SELECT app_type, id, data FROM providers")
+        .map_err(db_err)?;
+    let providers = stmt
+        .query_map([], |row| {
+            Ok((
+                row.get::<_, String>(0)?,
+                row.get::<_, String>(1)?,
+                row.get::<_, String>(2)?,
+            ))
+        })
+        .map_err(db_err)?;
+    for provider in providers {
+        let (app_type, id, data) = provider.map_err(db_err)?;
+        let data = parse_json(&format!("供应商 {}", id), &data)?;
+        let app = root
+            .entry(app_type)
+            .or_insert_with(|| json!({ "providers": {}, "current": "" }));
+        app["providers"][id] = data;
+    }
+
+    Ok(Some(Value::Object(root)))
+}
+
+/// 在一个事务内保存配置：只写入内容变化的行，并删除已不存在的应用与供应商。
+/// 以 IMMEDIATE 事务立即取得数据库写锁；若其他进程已写入更新的修订，则拒绝覆盖
+pub fn save_config(config: &MultiAppConfig) -> Result<(), String> {
+    write_config(config, true)
+}
+
+/// 忽略修订号核对直接保存：用户在冲突提示中选择保留本地修改时使用
+pub fn overwrite_config(config: &MultiAppConfig) -> Result<(), String> {
+    write_config(config, false)
+}
+
+fn write_config(config: &MultiAppConfig, check_revision: bool) -> Result<(), String> {
+    let _lock = lock_config(true)?;
+    let mut conn = open()?;
+    let tx = conn
+        .transaction_with_behavior(TransactionBehavior::Immediate)
+        .map_err(db_err)?;
+
+    let revision = read_revision(&tx)?;
+    if check_revision && revision != known_revision() {
+        return Err(crate::i18n::t("errors.configChangedExternally"));
+    }
+    set_meta(&tx, "revision", &(revision + 1).to_string())?;
+    set_meta(&tx, "config_version", &config.version.to_string())?;
+    set_meta(&tx, "mcp", &to_json(&config.mcp)?)?;
+    set_meta(&tx, "projects", &to_json(&config.projects)?)?;
+
+    for (app_type, manager) in &config.apps {
+        tx.execute(
+            "INSERT INTO apps (app_type, current) VALUES (?1, ?2)
+             ON CONFLICT (app_type) DO UPDATE SET current = excluded.current
+             WHERE current != excluded.current",
+            params![app_type, manager.current],
+        )
+        .map_err(db_err)?;
+        for (id, provider) in &manager.providers {
+            tx.execute(
+                "INSERT INTO providers (app_type, id, data) VALUES (?1, ?2, ?3)
+                 ON CONFLICT (app_type, id) DO UPDATE SET data = excluded.data
+                 WHERE data != excluded.data",
+                params![app_type, id, to_json(provider)?],
+            )
+            .map_err(db_err)?;
+        }
+    }
+
+    let stored: Vec<(String, String)> = {
+        let mut stmt = tx
+            .prepare("SELECT app_type, id FROM providers")
+            .map_err(db_err)?;
+        let rows = stmt
+            .query_map([], |row| Ok((row.get(0)?, row.get(1)?)))
+            .map_err(db_err)?;
+        rows.collect::<Result<_, _>>().map_err(db_err)?
+    };
+    for (app_type, id) in stored {
+        let exists = config
+            .apps
+            .get(&app_type)
+            .is_some_and(|manager| manager.providers.contains_key(&id));
+        if !exists {
+            tx.execute(
+                "DELETE FROM providers WHERE app_type = ?1 AND id = ?2",
+                params![app_type, id],
+            )
+            .map_err(db_err)?;
+        }
+    }
+
+    let stored_apps: HashSet<String> = {
+        let mut stmt = tx.prepare("SELECT app_type FROM apps").map_err(db_err)?;
+        let rows = stmt.query_map([], |row| row.get(0)).map_err(db_err)?;
+        rows.collect::<Result<_, _>>().map_err(db_err)?
+    };
+    for app_type in stored_apps {
+        if !config.apps.contains_key(&app_type) {
+            tx.execute("DELETE FROM apps WHERE app_type = ?1", [&app_type])
+                .map_err(db_err)?;
+        }
+    }
+
+    tx.commit().map_err(db_err)?;
+    KNOWN_REVISION.store(revision + 1, Ordering::SeqCst);
+    Ok(())
+}
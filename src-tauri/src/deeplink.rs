@@ -0,0 +1,249 @@
+//! `ccswitch://` 深链接：供中转服务商提供一键切换/导入链接。
+//! - `ccswitch://switch?app=claude&provider=<id 或名称>`
+//! - `ccswitch://add?app=claude&config=<分享字符串>`（格式见 share 模块）
+//!
+//! 链接在后端解析并校验后暂存，由前端弹窗确认后再调用常规命令执行，不会静默修改配置。
+
+use serde::Serialize;
+use std::sync::Mutex;
+use tauri::{Emitter, Manager};
+
+use crate::app_config::{AppType, MultiAppConfig};
+use crate::error::CcSwitchError;
+use crate::i18n::t_args;
+use crate::provider::Provider;
+use crate::store::AppState;
+
+/// 深链接协议名
+pub const DEEP_LINK_SCHEME: &str = "ccswitch";
+
+/// 收到深链接时通知前端读取待确认请求
+pub const DEEP_LINK_EVENT: &str = "deep-link-request";
+
+/// 等待前端确认的请求（冷启动时前端可能尚未开始监听，因此需要暂存）
+static PENDING: Mutex<Option<DeepLinkRequest>> = Mutex::new(None);
+
+/// 解析后的深链接请求
+#[derive(Debug, Clone, Serialize)]
+#[serde(tag = "action", rename_all = "camelCase")]
+pub enum DeepLinkRequest {
+    /// 切换到已存在的供应商
+    #[serde(rename_all = "camelCase")]
+    Switch {
+        app_type: String,
+        provider_id: String,
+        provider_name: String,
+    },
+    /// 新增供应商（ID 已生成；密钥被清空时需用户补填后再添加）
+    #[serde(rename_all = "camelCase")]
+    Add {
+        app_type: String,
+        provider: Box<Provider>,
+        key_stripped: bool,
+        /// 链接配置中未导入的键（会执行命令或不在白名单内）
+        removed_keys: Vec<String>,
+    },
+    /// 链接无效，仅提示用户
+    #[serde(rename_all = "camelCase")]
+    Invalid { url: String, message: String },
+}
+
+/// 是否为本应用的深链接
+pub fn is_deep_link(arg: &str) -> bool {
+    arg.to_ascii_lowercase()
+        .starts_with(&format!("{}://", DEEP_LINK_SCHEME))
+}
+
+/// 在当前配置中按 ID 或名称（忽略大小写）查找供应商
+fn parse_switch(
+    config: &MultiAppConfig,
+    query: &[(String, String)],
+    app_type: &AppType,
+) -> Result<(String, String), String> {
+    let target = query
+        .iter()
+        .find(|(k, _)| k == "provider" || k == "id")
+        .map(|(_, v)| v.trim().to_string())
+        .ok_or_else(|| t_args("errors.missingParam", &[("name", &"provider")]))?;
+    let manager = config
+        .get_manager(app_type)
+        .ok_or_else(|| t_args("errors.appTypeNotFound", &[("app", &app_type.as_str())]))?;
+    manager
+        .providers
+        .get(&target)
+        .or_else(|| {
+            manager
+                .providers
+                .values()
+                .find(|p| p.name.eq_ignore_ascii_case(&target))
+        })
+        .map(|p| (p.id.clone(), p.name.clone()))
+        .ok_or_else(|| t_args("errors.providerNotFound", &[("id", &target)]))
+}
+
+/// 解析并校验深链接
+pub fn parse(config: &MultiAppConfig, raw: &str) -> Result<DeepLinkRequest, String> {
+    let url = url::Url::parse(raw.trim())
+        .map_err(|e| t_args("errors.deepLinkInvalid", &[("error", &e)]))?;
+    // `ccswitch://switch?...` 中的操作名位于 host，兼容 `ccswitch:switch?...` 写法
+    let action = url
+        .host_str()
+        .map(str::to_string)
+        .unwrap_or_else(|| url.path().trim_matches('/').to_string());
+    let query: Vec<(String, String)> = url
+        .query_pairs()
+        .map(|(k, v)| (k.into_owned(), v.into_owned()))
+        .collect();
+    let app = query
+        .iter()
+        .find(|(k, _)| k == "app")
+        .map(|(_, v)| v.as_str());
+
+    match action.to_ascii_lowercase().as_str() {
+        "switch" => {
+            let app_type = AppType::from(app.unwrap_or("claude"));
+            let (provider_id, provider_name) = parse_switch(config, &query, &app_type)?;
+            Ok(DeepLinkRequest::Switch {
+                app_type: app_type.as_str().to_string(),
+                provider_id,
+                provider_name,
+            })
+        }
+        "add" => {
+            let decoded = crate::share::decode(raw)?;
+            Ok(DeepLinkRequest::Add {
+                app_type: decoded.app_type,
+                provider: Box::new(decoded.provider),
+                key_stripped: decoded.key_stripped,
+                removed_keys: decoded.removed_keys,
+            })
+        }
+        other => Err(t_args(
+            "errors.deepLinkUnsupportedAction",
+            &[("action", &other)],
+        )),
+    }
+}
+
+/// 处理命令行参数或系统传入的链接：解析后暂存并通知前端，同时唤起主窗口
+pub fn handle_urls<I, S>(app: &tauri::AppHandle, urls: I)
+where
+    I: IntoIterator<Item = S>,
+    S: AsRef<str>,
+{
+    for raw in urls {
+        let raw = raw.as_ref();
+        if !is_deep_link(raw) {
+            continue;
+        }
+        let request = match app.try_state::<AppState>() {
+            Some(state) => match state.config.lock() {
+                Ok(config) => parse(&config, raw),
+                Err(e) => Err(t_args("errors.lockFailed", &[("error", &e)])),
+            },
+            None => Err("应用状态尚未就绪".to_string()),
+        }
+        .unwrap_or_else(|message| {
+            tracing::warn!("深链接无效: {}: {}", raw, message);
+            DeepLinkRequest::Invalid {
+                url: raw.to_string(),
+                message,
+            }
+        });
+        tracing::info!("收到深链接请求: {}", raw.split('?').next().unwrap_or(raw));
+
+        if let Ok(mut pending) = PENDING.lock() {
+            *pending = Some(request);
+        }
+        crate::tray::show_main_window(app);
+        if let Err(e) = app.emit(DEEP_LINK_EVENT, ()) {
+            tracing::warn!("发射深链接事件失败: {}", e);
+        }
+    }
+}
+
+/// 取出待确认的深链接请求（取出后清空）
+#[tauri::command]
+pub async fn take_pending_deep_link() -> Result<Option<DeepLinkRequest>, CcSwitchError> {
+    let mut pending = PENDING
+        .lock()
+        .map_err(|e| t_args("errors.lockFailed", &[("error", &e)]))?;
+    Ok(pending.take())
+}
+
+/// 在系统中注册 `ccswitch://` 协议（Windows 写入当前用户注册表，Linux 写入 .desktop 并设为默认处理程序；
+/// macOS 通过 Info.plist 中的 CFBundleURLTypes 声明）。失败仅记录日志
+pub fn register_scheme() {
+    if let Err(e) = register_scheme_impl() {
+        tracing::warn!("注册 {}:// 协议失败: {}", DEEP_LINK_SCHEME, e);
+    }
+}
+
+#[cfg(target_os = "windows")]
+fn register_scheme_impl() -> Result<(), String> {
+    let exe = std::env::current_exe().map_err(|e| format!("获取可执行文件路径失败: {}", e))?;
+    let key = format!(r"HKCU\Software\Classes\{}", DEEP_LINK_SCHEME);
+    let command = format!("\"{}\" \"%1\"", exe.display());
+    let entries: [(String, Option<&str>, String); 3] = [
+        (key.clone(), None, "URL:CC Switch".to_string()),
+        (key.clone(), Some("URL Protocol"), String::new()),
+        (format!(r"{}\shell\open\command", key), None, command),
+    ];
+    for (path, name, value) in entries {
+        let mut cmd = std::process::Command::new("reg");
+        cmd.args(["add", &path, "/f"]);
+        match name {
+            Some(name) => cmd.args(["/v", name]),
+            None => cmd.arg("/ve"),
+        };
+        cmd.args(["/d", &value]);
+        {
+            use std::os::windows::process::CommandExt;
+            const CREATE_NO_WINDOW: u32 = 0x0800_0000;
+            cmd.creation_flags(CREATE_NO_WINDOW);
+        }
+        let status = cmd
+            .status()
+            .map_err(|e| format!("执行 reg 命令失败: {}", e))?;
+        if !status.success() {
+            return Err(format!("写入注册表失败: {}", path));
+        }
+    }
+    Ok(())
+}
+
+#[cfg(target_os = "linux")]
+fn register_scheme_impl() -> Result<(), String> {
+    const DESKTOP_FILE: &str = "cc-switch-handler.desktop";
+    // AppImage 运行时 current_exe 指向临时挂载目录，需使用 APPIMAGE 指向的原始文件
+    let exe = match std::env::var_os("APPIMAGE") {
+        Some(path) => std::path::PathBuf::from(path),
+        None => std::env::current_exe().map_err(|e| format!("获取可执行文件路径失败: {}", e))?,
+    };
+    let dir = dirs::data_dir()
+        .ok_or("无法获取数据目录")?
+        .join("applications");
+    let content = format!(
+        "[Desktop Entry]\nType=Application\nName=CC Switch\nExec=\"{}\" %u\nNoDisplay=true\nMimeType=x-scheme-handler/{};\n",
+        exe.display(),
+        DEEP_LINK_SCHEME
+    );
+    crate::config::write_text_file(&dir.join(DESKTOP_FILE), &content)?;
+    let status = std::process::Command::new("xdg-mime")
+        .args([
+            "default",
+            DESKTOP_FILE,
+            &format!("x-scheme-handler/{}", DEEP_LINK_SCHEME),
+        ])
+        .status()
+        .map_err(|e| format!("执行 xdg-mime 失败: {}", e))?;
+    if !status.success() {
+        return Err("xdg-mime 设置默认处理程序失败".to_string());
+    }
+    Ok(())
+}
+
+#[cfg(not(any(target_os = "windows", target_os = "linux")))]
+fn register_scheme_impl() -> Result<(), String> {
+    Ok(())
+}
@@ -0,0 +1,155 @@
+use chrono::Utc;
+use regex::Regex;
+use serde_json::{json, Value};
+use std::fs;
+use std::io::Write;
+use std::path::PathBuf;
+use std::sync::OnceLock;
+use zip::write::SimpleFileOptions;
+
+use crate::error::CcSwitchError;
+use crate::i18n::t_args;
+use crate::store::AppState;
+use crate::usage_script::mask_secret;
+
+/// 诊断包中附带的日志行数
+const DIAGNOSTIC_LOG_LINES: usize = 2000;
+
+/// 键名包含以下片段即视为敏感字段（不区分大小写）
+const SENSITIVE_KEY_PARTS: [&str; 6] = [
+    "KEY",
+    "TOKEN",
+    "SECRET",
+    "PASSWORD",
+    "AUTHORIZATION",
+    "COOKIE",
+];
+
+fn is_sensitive_key(key: &str) -> bool {
+    let upper = key.to_ascii_uppercase();
+    SENSITIVE_KEY_PARTS.iter().any(|part| upper.contains(part))
+}
+
+/// 对 TOML 文本（Codex config.toml）中的敏感赋值打码
+fn sanitize_toml_text(text: &str) -> String {
+    static RE: OnceLock<Regex> = OnceLock::new();
+    let re = RE.get_or_init(|| {
+        Regex::new(r#"(?m)^(\s*[\w.-]+\s*=\s*)(["'])([^"'\n]*)(["'])"#).expect("内置正则无效")
+    });
+    re.replace_all(text, |caps: &regex::Captures| {
+        let key = caps[1].split('=').next().unwrap_or_default().trim();
+        if is_sensitive_key(key) && !caps[3].is_empty() {
+            format!(
+                "{}{}{}{}",
+                &caps[1],
+                &caps[2],
+                mask_secret(&caps[3]),
+                &caps[4]
+            )
+        } else {
+            caps[0].to_string()
+        }
+    })
+    .into_owned()
+}
+
+/// 递归打码 JSON 中的敏感字段，保留结构以便排查
+fn sanitize_value(value: &mut Value) {
+    match value {
+        Value::Object(map) => {
+            for (key, child) in map.iter_mut() {
+                match child {
+                    Value::String(text) if is_sensitive_key(key) && !text.is_empty() => {
+                        *text = mask_secret(text);
+                    }
+                    Value::String(text) if key == "config" => {
+                        *text = sanitize_toml_text(text);
+                    }
+                    _ => sanitize_value(child),
+                }
+            }
+        }
+        Value::Array(items) => items.iter_mut().for_each(sanitize_value),
+        _ => {}
+    }
+}
+
+fn sanitized_json<T: serde::Serialize>(data: &T) -> Result<String, CcSwitchError> {
+    let mut value = serde_json::to_value(data)?;
+    sanitize_value(&mut value);
+    Ok(serde_json::to_string_pretty(&value)?)
+}
+
+/// 默认的诊断包输出路径：~/.cc-switch/diagnostics/cc-switch-diagnostics-<时间>.zip
+fn default_output_path() -> PathBuf {
+    crate::config::get_app_config_dir()
+        .join("diagnostics")
+        .join(format!(
+            "cc-switch-diagnostics-{}.zip",
+            Utc::now().format("%Y%m%d_%H%M%S")
+        ))
+}
+
+fn write_zip(path: &PathBuf, entries: &[(&str, String)]) -> Result<(), CcSwitchError> {
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent)
+            .map_err(|e| CcSwitchError::io(format!("创建目录失败: {}", e), e))?;
+    }
+    let file = fs::File::create(path)
+        .map_err(|e| CcSwitchError::io(format!("创建诊断包失败: {}", e), e))?;
+    let mut zip = zip::ZipWriter::new(file);
+    let options = SimpleFileOptions::default().compression_method(zip::CompressionMethod::Deflated);
+    for (name, content) in entries {
+        zip.start_file(*name, options)
+            .map_err(|e| format!("写入诊断包失败: {}", e))?;
+        zip.write_all(content.as_bytes())?;
+    }
+    zip.finish().map_err(|e| format!("写入诊断包失败: {}", e))?;
+    Ok(())
+}
+
+/// 导出诊断包（配置与设置已打码），返回生成的 zip 路径
+#[tauri::command]
+pub async fn export_diagnostics(
+    app: tauri::AppHandle,
+    state: tauri::State<'_, AppState>,
+    file_path: Option<String>,
+) -> Result<String, CcSwitchError> {
+    let config = state
+        .config
+        .lock()
+        .map_err(|e| t_args("errors.lockFailed", &[("error", &e)]))?
+        .clone();
+    let settings = crate::settings::get_settings();
+
+    let system = json!({
+        "appVersion": app.package_info().version.to_string(),
+        "tauriVersion": tauri::VERSION,
+        "os": std::env::consts::OS,
+        "osFamily": std::env::consts::FAMILY,
+        "arch": std::env::consts::ARCH,
+        "operationMode": settings.operation_mode,
+        "appConfigDir": crate::config::get_app_config_dir(),
+        "generatedAt": Utc::now().to_rfc3339(),
+    });
+
+    let logs = crate::logging::read_recent_logs(Some(DIAGNOSTIC_LOG_LINES))
+        .unwrap_or_else(|e| vec![format!("读取日志失败: {}", e)]);
+    let proxy_stats = crate::proxy::get_proxy_stats().await;
+
+    let entries = [
+        ("system.json", serde_json::to_string_pretty(&system)?),
+        ("config.json", sanitized_json(&config)?),
+        ("settings.json", sanitized_json(&settings)?),
+        ("proxy-stats.json", sanitized_json(&proxy_stats)?),
+        ("logs.txt", logs.join("\n")),
+    ];
+
+    let path = file_path
+        .map(PathBuf::from)
+        .unwrap_or_else(default_output_path);
+    write_zip(&path, &entries)?;
+    tracing::info!("已导出诊断包: {}", path.display());
+
+    Ok(path.to_string_lossy().to_string())
+}
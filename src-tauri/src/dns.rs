@@ -0,0 +1,277 @@
+//! 上游请求的域名解析：部分中转域名在某些网络下被污染或解析缓慢。代理转发时按以下顺序解析：
+//! 供应商的主机映射（`meta.host_mapping`）→ 设置中的全局主机映射 → DNS-over-HTTPS（已配置时）
+//! → 系统解析。DoH 使用 JSON 接口（`application/dns-json`，Cloudflare、Google 等均支持），
+//! DoH 服务自身的域名仍由系统解析，必要时可直接填写 IP 形式的地址（如 `https://1.1.1.1/dns-query`）。
+//! 连接池按主机复用连接，同一主机在多个供应商中映射到不同 IP 时以先出现的为准。
+//! `localhost` 及 `*.localhost` 不经 DoH，始终由系统解析。
+
+use std::collections::HashMap;
+use std::future::Future;
+use std::net::{IpAddr, SocketAddr};
+use std::pin::Pin;
+use std::sync::{Mutex, OnceLock};
+use std::task::{Context, Poll};
+use std::time::{Duration, Instant};
+
+use arc_swap::ArcSwap;
+use hyper_util::client::legacy::connect::dns::{GaiResolver, Name};
+use serde::Deserialize;
+use tower::Service;
+
+use crate::i18n::t_args;
+
+/// DoH 查询超时
+const DOH_TIMEOUT: Duration = Duration::from_secs(5);
+
+/// DoH 结果的缓存时长范围（秒），在记录的 TTL 基础上取值
+const MIN_CACHE_SECS: u64 = 60;
+const MAX_CACHE_SECS: u64 = 3600;
+
+/// DNS 记录类型
+const RECORD_A: u16 = 1;
+const RECORD_AAAA: u16 = 28;
+
+/// 启用代理的供应商的主机映射（主机名 -> IP），随供应商快照刷新
+static PROVIDER_HOSTS: OnceLock<ArcSwap<HashMap<String, IpAddr>>> = OnceLock::new();
+
+/// DoH 解析结果：地址与过期时间
+type CachedAddrs = (Vec<IpAddr>, Instant);
+
+/// DoH 解析缓存：主机名 -> 解析结果
+static DOH_CACHE: OnceLock<Mutex<HashMap<String, CachedAddrs>>> = OnceLock::new();
+
+type BoxError = Box<dyn std::error::Error + Send + Sync>;
+
+fn normalize_host(host: &str) -> String {
+    host.trim().trim_end_matches('.').to_ascii_lowercase()
+}
+
+/// 是否为本机名称（`localhost` 或 `*.localhost`）
+pub fn is_local_host(host: &str) -> bool {
+    let host = normalize_host(host);
+    host == "localhost" || host.ends_with(".localhost")
+}
+
+/// 解析主机映射表，忽略无法解析为 IP 的条目
+fn parse_hosts<'a>(
+    entries: impl IntoIterator<Item = (&'a String, &'a String)>,
+) -> Vec<(String, IpAddr)> {
+    entries
+        .into_iter()
+        .filter_map(|(host, ip)| match ip.trim().parse::<IpAddr>() {
+            Ok(ip) => Some((normalize_host(host), ip)),
+            Err(_) => {
+                tracing::warn!("忽略无效的主机映射: {} -> {}", host, ip);
+                None
+            }
+        })
+        .collect()
+}
+
+/// 校验主机映射：主机名不能为空，值必须是 IP 地址
+pub fn validate_hosts(hosts: &HashMap<String, String>) -> Result<(), String> {
+    for (host, ip) in hosts {
+        if normalize_host(host).is_empty() || ip.trim().parse::<IpAddr>().is_err() {
+            return Err(t_args(
+                "errors.invalidHostMapping",
+                &[("host", host), ("ip", ip)],
+            ));
+        }
+    }
+    Ok(())
+}
+
+/// 校验 DoH 地址：必须是 https URL
+pub fn validate_doh_url(url: &str) -> Result<(), String> {
+    match url::Url::parse(url.trim()) {
+        Ok(parsed) if parsed.scheme() == "https" && parsed.host().is_some() => Ok(()),
+        _ => Err(t_args("errors.invalidDohUrl", &[("url", &url)])),
+    }
+}
+
+/// 更新供应商主机映射；映射有变化时返回 true（调用方需丢弃连接池中按旧地址建立的连接）
+pub fn set_provider_hosts<'a>(
+    providers: impl IntoIterator<Item = &'a crate::provider::Provider>,
+) -> bool {
+    let mut hosts = HashMap::new();
+    for provider in providers {
+        let Some(meta) = provider.meta.as_ref() else {
+            continue;
+        };
+        for (host, ip) in parse_hosts(&meta.host_mapping) {
+            hosts.entry(host).or_insert(ip);
+        }
+    }
+    let current = PROVIDER_HOSTS.get_or_init(Default::default);
+    if **current.load() == hosts {
+        return false;
+    }
+    current.store(hosts.into());
+    true
+}
+
+/// 清空 DoH 缓存（随上游连接池一起重置）
+pub fn clear_cache() {
+    if let Some(cache) = DOH_CACHE.get() {
+        if let Ok(mut cache) = cache.lock() {
+            cache.clear();
+        }
+    }
+}
+
+/// 主机映射中的地址：供应商映射优先，其次为全局映射
+fn mapped(host: &str) -> Option<IpAddr> {
+    if let Some(ip) = PROVIDER_HOSTS
+        .get()
+        .and_then(|hosts| hosts.load().get(host).copied())
+    {
+        return Some(ip);
+    }
+    let settings = crate::settings::get_settings();
+    parse_hosts(&settings.proxy_dns.hosts)
+        .into_iter()
+        .find(|(mapped, _)| mapped == host)
+        .map(|(_, ip)| ip)
+}
+
+#[derive(Debug, Deserialize)]
+struct DohResponse {
+    #[serde(rename = "Status")]
+    status: u32,
+    #[serde(rename = "Answer", default)]
+    answer: Vec<DohAnswer>,
+}
+
+#[derive(Debug, Deserialize)]
+struct DohAnswer {
+    #[serde(rename = "type")]
+    record_type: u16,
+    #[serde(rename = "TTL", default)]
+    ttl: u64,
+    data: String,
+}
+
+fn doh_client() -> Result<&'static reqwest::Client, String> {
+    static CLIENT: OnceLock<reqwest::Client> = OnceLock::new();
+    if let Some(client) = CLIENT.get() {
+        return Ok(client);
+    }
+    let client = reqwest::Client::builder()
+        .timeout(DOH_TIMEOUT)
+        .build()
+        .map_err(|e| format!("创建 DoH 客户端失败: {}", e))?;
+    Ok(CLIENT.get_or_init(|| client))
+}
+
+/// 查询一种记录类型，返回地址与最小 TTL
+async fn doh_query(
+    doh_url: &str,
+    host: &str,
+    record_type: u16,
+) -> Result<(Vec<IpAddr>, u64), String> {
+    let response = doh_client()?
+        .get(doh_url)
+        .query(&[("name", host), ("type", &record_type.to_string())])
+        .header("accept", "application/dns-json")
+        .send()
+        .await
+        .map_err(|e| format!("DoH 请求失败: {}", e))?
+        .error_for_status()
+        .map_err(|e| format!("DoH 请求失败: {}", e))?
+        .json::<DohResponse>()
+        .await
+        .map_err(|e| format!("解析 DoH 响应失败: {}", e))?;
+    if response.status != 0 {
+        return Err(format!("DoH 返回错误码 {}", response.status));
+    }
+    let answers: Vec<_> = response
+        .answer
+        .iter()
+        .filter(|answer| answer.record_type == record_type)
+        .filter_map(|answer| Some((answer.data.parse::<IpAddr>().ok()?, answer.ttl)))
+        .collect();
+    let ttl = answers.iter().map(|(_, ttl)| *ttl).min().unwrap_or(0);
+    Ok((answers.into_iter().map(|(ip, _)| ip).collect(), ttl))
+}
+
+/// 通过 DoH 解析（优先 IPv4，没有 A 记录时查询 AAAA），结果按 TTL 缓存
+async fn doh_resolve(doh_url: &str, host: &str) -> Result<Vec<IpAddr>, String> {
+    let cache = DOH_CACHE.get_or_init(Default::default);
+    if let Some(ips) = cache.lock().ok().and_then(|cache| {
+        cache
+            .get(host)
+            .filter(|(_, expires)| *expires > Instant::now())
+            .map(|(ips, _)| ips.clone())
+    }) {
+        return Ok(ips);
+    }
+
+    let (mut ips, mut ttl) = doh_query(doh_url, host, RECORD_A).await?;
+    if ips.is_empty() {
+        (ips, ttl) = doh_query(doh_url, host, RECORD_AAAA).await?;
+    }
+    if ips.is_empty() {
+        return Err(format!("DoH 未返回 {} 的地址", host));
+    }
+    let expires = Instant::now() + Duration::from_secs(ttl.clamp(MIN_CACHE_SECS, MAX_CACHE_SECS));
+    if let Ok(mut cache) = cache.lock() {
+        cache.insert(host.to_string(), (ips.clone(), expires));
+    }
+    Ok(ips)
+}
+
+/// 代理上游连接使用的解析器
+#[derive(Clone)]
+pub struct UpstreamResolver {
+    system: GaiResolver,
+}
+
+impl UpstreamResolver {
+    pub fn new() -> Self {
+        Self {
+            system: GaiResolver::new(),
+        }
+    }
+}
+
+impl Service<Name> for UpstreamResolver {
+    type Response = std::vec::IntoIter<SocketAddr>;
+    type Error = BoxError;
+    type Future = Pin<Box<dyn Future<Output = Result<Self::Response, Self::Error>> + Send>>;
+
+    fn poll_ready(&mut self, _cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+        Poll::Ready(Ok(()))
+    }
+
+    fn call(&mut self, name: Name) -> Self::Future {
+        let mut system = self.system.clone();
+        Box::pin(async move {
+            // 端口由连接器在解析后填入
+            let to_addrs = |ips: Vec<IpAddr>| {
+                ips.into_iter()
+                    .map(|ip| SocketAddr::new(ip, 0))
+                    .collect::<Vec<_>>()
+                    .into_iter()
+            };
+            let host = normalize_host(name.as_str());
+            if let Some(ip) = mapped(&host) {
+                tracing::debug!("按主机映射解析 {} -> {}", host, ip);
+                return Ok(to_addrs(vec![ip]));
+            }
+
+            let doh_url = crate::settings::get_settings()
+                .proxy_dns
+                .doh_url
+                .filter(|url| !url.trim().is_empty() && !is_local_host(&host));
+            if let Some(doh_url) = doh_url {
+                match doh_resolve(doh_url.trim(), &host).await {
+                    Ok(ips) => return Ok(to_addrs(ips)),
+                    Err(e) => tracing::warn!("DoH 解析 {} 失败，改用系统解析: {}", host, e),
+                }
+            }
+
+            let addrs = system.call(name).await?;
+            Ok(addrs.collect::<Vec<_>>().into_iter())
+        })
+    }
+}
@@ -0,0 +1,156 @@
+//! 重复供应商检测：添加或导入前，在同一应用下查找 API 地址与密钥相同、或 settings_config 完全一致的供应商，
+//! 由前端提示合并到已有条目，避免列表中出现两个控制同一中转的条目。
+
+#![allow(non_snake_case)]
+
+use serde::Serialize;
+use serde_json::Value;
+use tauri::State;
+
+use crate::app_config::{AppType, MultiAppConfig};
+use crate::error::CcSwitchError;
+use crate::i18n::t_args;
+use crate::provider::{Provider, ProviderManager};
+use crate::store::AppState;
+
+/// 重复类型
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum DuplicateKind {
+    /// settings_config 完全一致
+    Identical,
+    /// API 地址与密钥相同（其余设置不同）
+    SameCredentials,
+}
+
+/// 与待添加供应商重复的已有供应商
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DuplicateProvider {
+    pub id: String,
+    pub name: String,
+    pub kind: DuplicateKind,
+}
+
+/// 导入的配置中互相重复的一对供应商
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ImportedDuplicate {
+    pub app_type: String,
+    pub id: String,
+    pub name: String,
+    pub duplicate: DuplicateProvider,
+}
+
+/// 用于比较的 API 地址（忽略末尾斜杠与大小写）与密钥
+fn credentials<'a>(provider: &'a Provider, app_type: &AppType) -> Option<(String, &'a str)> {
+    let base_url = provider.base_url(app_type)?;
+    let settings = &provider.settings_config;
+    let key = match app_type {
+        AppType::Claude => ["ANTHROPIC_AUTH_TOKEN", "ANTHROPIC_API_KEY"]
+            .iter()
+            .find_map(|key| {
+                settings
+                    .get("env")?
+                    .get(key)?
+                    .as_str()
+                    .filter(|s| !s.is_empty())
+            }),
+        AppType::Codex => settings
+            .get("auth")
+            .and_then(|auth| auth.get("OPENAI_API_KEY"))
+            .and_then(Value::as_str)
+            .filter(|s| !s.is_empty()),
+    }?;
+    let base_url = base_url.trim().trim_end_matches('/').to_ascii_lowercase();
+    Some((base_url, key))
+}
+
+/// 前端提交的密钥可能是脱敏值，按掩码比较
+fn same_key(incoming: &str, existing: &str) -> bool {
+    incoming == existing
+        || (crate::redact::is_masked(incoming) && crate::redact::mask(existing) == incoming)
+}
+
+/// 查找与 provider 重复的已有供应商（排除自身）；完全一致优先于仅凭证相同
+pub fn find_duplicate(
+    manager: &ProviderManager,
+    app_type: &AppType,
+    provider: &Provider,
+) -> Option<DuplicateProvider> {
+    let incoming = credentials(provider, app_type);
+    manager
+        .providers
+        .values()
+        .filter(|existing| existing.id != provider.id)
+        .filter_map(|existing| {
+            let kind = if existing.settings_config == provider.settings_config {
+                DuplicateKind::Identical
+            } else {
+                let (url, key) = incoming.as_ref()?;
+                let (existing_url, existing_key) = credentials(existing, app_type)?;
+                if *url != existing_url || !same_key(key, existing_key) {
+                    return None;
+                }
+                DuplicateKind::SameCredentials
+            };
+            Some(DuplicateProvider {
+                id: existing.id.clone(),
+                name: existing.name.clone(),
+                kind,
+            })
+        })
+        .min_by(|a, b| (a.kind, &a.name).cmp(&(b.kind, &b.name)))
+}
+
+/// 查找整份配置中互相重复的供应商（导入配置文件后提示合并），每对只报告一次
+pub fn find_duplicates(config: &MultiAppConfig) -> Vec<ImportedDuplicate> {
+    let mut found: Vec<ImportedDuplicate> = Vec::new();
+    for app_type in [AppType::Claude, AppType::Codex] {
+        let Some(manager) = config.get_manager(&app_type) else {
+            continue;
+        };
+        for provider in manager.sorted_providers() {
+            let Some(duplicate) = find_duplicate(manager, &app_type, provider) else {
+                continue;
+            };
+            let reported = found.iter().any(|pair| {
+                pair.app_type == app_type.as_str()
+                    && pair.id == duplicate.id
+                    && pair.duplicate.id == provider.id
+            });
+            if !reported {
+                found.push(ImportedDuplicate {
+                    app_type: app_type.as_str().to_string(),
+                    id: provider.id.clone(),
+                    name: provider.name.clone(),
+                    duplicate,
+                });
+            }
+        }
+    }
+    found
+}
+
+/// 添加或导入前检查重复供应商（不写入配置）
+#[tauri::command]
+pub async fn find_duplicate_provider(
+    state: State<'_, AppState>,
+    provider: Provider,
+    app_type: Option<AppType>,
+    app: Option<String>,
+    appType: Option<String>,
+) -> Result<Option<DuplicateProvider>, CcSwitchError> {
+    let app_type = app_type
+        .or_else(|| app.as_deref().map(|s| s.into()))
+        .or_else(|| appType.as_deref().map(|s| s.into()))
+        .unwrap_or(AppType::Claude);
+    let config = state
+        .config
+        .lock()
+        .map_err(|e| t_args("errors.lockFailed", &[("error", &e)]))?;
+    let manager = config
+        .get_manager(&app_type)
+        .ok_or_else(|| t_args("errors.appTypeNotFound", &[("app", &app_type.as_str())]))?;
+    Ok(find_duplicate(manager, &app_type, &provider))
+}
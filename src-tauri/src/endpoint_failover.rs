@@ -0,0 +1,161 @@
+//! 供应商内的端点故障转移：开启后代理在切换到下一个供应商之前，先依次尝试该供应商的
+//! 主地址与 `meta.custom_endpoints` 中的备用地址。顺序可按添加顺序（主地址在前），
+//! 也可按最近一次测速的延迟排序（不可用的端点排在最后）。测速结果只保存在进程内。
+
+#![allow(non_snake_case)]
+
+use std::collections::HashMap;
+use std::sync::{Mutex, OnceLock};
+
+use serde::Serialize;
+use tauri::State;
+
+use crate::app_config::AppType;
+use crate::error::CcSwitchError;
+use crate::i18n::t_args;
+use crate::provider::{EndpointFailover, Provider};
+use crate::speedtest::EndpointLatency;
+use crate::store::AppState;
+
+/// 端点 URL -> 最近一次探测结果
+static PROBES: OnceLock<Mutex<HashMap<String, EndpointProbe>>> = OnceLock::new();
+
+/// 端点的最近一次探测结果
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct EndpointProbe {
+    pub url: String,
+    /// 延迟（毫秒），不可用时为空
+    pub latency_ms: Option<u64>,
+    pub healthy: bool,
+    /// 探测时间（毫秒时间戳）
+    pub checked_at: i64,
+}
+
+pub(crate) fn normalize(url: &str) -> String {
+    url.trim().trim_end_matches('/').to_string()
+}
+
+/// 记录测速结果：有响应即视为可用（即使状态码非 2xx，也说明地址可达）
+pub fn record(result: &EndpointLatency) {
+    let probe = EndpointProbe {
+        url: normalize(&result.url),
+        latency_ms: result.latency.map(|latency| latency as u64),
+        healthy: result.status.is_some() && result.error.is_none(),
+        checked_at: chrono::Utc::now().timestamp_millis(),
+    };
+    if let Ok(mut probes) = PROBES.get_or_init(Default::default).lock() {
+        probes.insert(probe.url.clone(), probe);
+    }
+}
+
+/// 代理请求连接失败时将端点标记为不可用，直到下次测速
+pub fn mark_unhealthy(url: &str) {
+    let url = normalize(url);
+    if let Ok(mut probes) = PROBES.get_or_init(Default::default).lock() {
+        probes.insert(
+            url.clone(),
+            EndpointProbe {
+                url,
+                latency_ms: None,
+                healthy: false,
+                checked_at: chrono::Utc::now().timestamp_millis(),
+            },
+        );
+    }
+}
+
+pub(crate) fn probe_of(url: &str) -> Option<EndpointProbe> {
+    PROBES.get()?.lock().ok()?.get(url).cloned()
+}
+
+/// 供应商的全部端点：主地址在前，其后为按添加时间排序的自定义端点（去重）
+pub(crate) fn all_endpoints(provider: &Provider, base_url: &str) -> Vec<String> {
+    let mut endpoints = vec![normalize(base_url)];
+    if let Some(meta) = provider.meta.as_ref() {
+        let mut custom: Vec<_> = meta.custom_endpoints.values().collect();
+        custom.sort_by_key(|endpoint| endpoint.added_at);
+        for endpoint in custom {
+            let url = normalize(&endpoint.url);
+            if !url.is_empty() && !endpoints.contains(&url) {
+                endpoints.push(url);
+            }
+        }
+    }
+    endpoints
+}
+
+/// 代理按顺序尝试的端点；未开启端点故障转移时只有主地址（开启自动选择时为最快端点）
+pub fn candidates(provider: &Provider, base_url: &str) -> Vec<String> {
+    let mode = provider
+        .meta
+        .as_ref()
+        .and_then(|meta| meta.endpoint_failover);
+    let fastest = crate::endpoint_probe::fastest(provider, base_url);
+    let Some(mode) = mode else {
+        return vec![fastest.unwrap_or_else(|| normalize(base_url))];
+    };
+    let mut endpoints = all_endpoints(provider, base_url);
+    if let Some(fastest) = fastest.filter(|_| mode == EndpointFailover::Ordered) {
+        endpoints.retain(|url| *url != fastest);
+        endpoints.insert(0, fastest);
+    } else if mode == EndpointFailover::Latency {
+        // 可用且已测速的按延迟排在前面，未测速的其次，不可用的最后；同档保持原顺序
+        endpoints.sort_by_key(|url| match probe_of(url) {
+            Some(probe) if !probe.healthy => (2, u64::MAX),
+            Some(probe) => (0, probe.latency_ms.unwrap_or(u64::MAX)),
+            None => (1, 0),
+        });
+    }
+    endpoints
+}
+
+/// 测试供应商的主地址与全部自定义端点，并记录结果供按延迟排序使用
+#[tauri::command]
+pub async fn test_provider_endpoints(
+    state: State<'_, AppState>,
+    provider_id: String,
+    timeout_secs: Option<u64>,
+    app_type: Option<AppType>,
+    app: Option<String>,
+    appType: Option<String>,
+) -> Result<Vec<EndpointLatency>, CcSwitchError> {
+    let app_type = app_type
+        .or_else(|| app.as_deref().map(|s| s.into()))
+        .or_else(|| appType.as_deref().map(|s| s.into()))
+        .unwrap_or(AppType::Claude);
+    let (endpoints, socket_provider) = {
+        let config = state
+            .config
+            .lock()
+            .map_err(|e| t_args("errors.lockFailed", &[("error", &e)]))?;
+        let provider = config
+            .get_manager(&app_type)
+            .and_then(|manager| manager.providers.get(&provider_id))
+            .ok_or_else(|| t_args("errors.providerNotFound", &[("id", &provider_id)]))?;
+        let base_url = provider.base_url(&app_type).unwrap_or_default();
+        let endpoints = all_endpoints(provider, &base_url)
+            .into_iter()
+            .filter(|url| !url.is_empty())
+            .collect::<Vec<_>>();
+        let socket_provider = crate::upstream::uses_unix_socket(provider).then(|| provider.clone());
+        (endpoints, socket_provider)
+    };
+
+    // 经 Unix 套接字连接的供应商只能通过代理的连接器访问
+    let results = match socket_provider {
+        Some(provider) => {
+            futures::future::join_all(
+                endpoints
+                    .iter()
+                    .map(|url| crate::upstream::probe(&provider, url)),
+            )
+            .await
+        }
+        None => crate::speedtest::test_endpoints(endpoints, timeout_secs).await?,
+    };
+    for result in &results {
+        record(result);
+    }
+    Ok(results)
+}
@@ -0,0 +1,179 @@
+//! 端点后台测速与自动选择：定期测试开启了自动选择（`meta.auto_select_endpoint`）或按延迟
+//! 故障转移的供应商的主地址与全部自定义端点，结果记入端点故障转移的探测表。
+//! 开启自动选择的供应商在写入 live 配置时使用延迟最低的可用端点，代理模式下也优先使用该端点；
+//! 回填 live 配置时还原为供应商原本的主地址，自动选择的结果不会覆盖用户配置。
+
+use std::collections::BTreeSet;
+use std::time::Duration;
+
+use serde_json::Value;
+use tauri::Manager;
+
+use crate::app_config::AppType;
+use crate::endpoint_failover::{all_endpoints, normalize, probe_of};
+use crate::provider::{EndpointFailover, Provider};
+use crate::store::AppState;
+
+/// 测速间隔
+const PROBE_INTERVAL: Duration = Duration::from_secs(5 * 60);
+
+/// 启动后首次测速前的等待时间，避免与启动时的其他网络请求争抢
+const STARTUP_DELAY: Duration = Duration::from_secs(20);
+
+/// 超过该时长（毫秒）的测速结果不再参与自动选择
+const PROBE_TTL_MS: i64 = 30 * 60 * 1000;
+
+/// 是否开启了自动选择端点
+pub fn is_auto_select(provider: &Provider) -> bool {
+    provider
+        .meta
+        .as_ref()
+        .is_some_and(|meta| meta.auto_select_endpoint)
+}
+
+/// 是否需要后台测速：有自定义端点，且开启了自动选择或按延迟故障转移
+fn wants_probe(provider: &Provider) -> bool {
+    provider.is_enabled()
+        && provider.meta.as_ref().is_some_and(|meta| {
+            !meta.custom_endpoints.is_empty()
+                && (meta.auto_select_endpoint
+                    || meta.endpoint_failover == Some(EndpointFailover::Latency))
+        })
+}
+
+/// 开启自动选择时延迟最低的可用端点；没有未过期的可用测速结果时返回 None（沿用主地址）
+pub fn fastest(provider: &Provider, base_url: &str) -> Option<String> {
+    if !is_auto_select(provider) {
+        return None;
+    }
+    let now = chrono::Utc::now().timestamp_millis();
+    all_endpoints(provider, base_url)
+        .into_iter()
+        .filter_map(|url| {
+            let probe = probe_of(&url)?;
+            (probe.healthy && now - probe.checked_at <= PROBE_TTL_MS)
+                .then(|| (probe.latency_ms.unwrap_or(u64::MAX), url))
+        })
+        .min_by_key(|(latency, _)| *latency)
+        .map(|(_, url)| url)
+}
+
+/// 修改 settings_config（或同结构的 live 配置）中的上游地址
+fn set_base_url(app_type: &AppType, settings: &mut Value, url: &str) {
+    match app_type {
+        AppType::Claude => {
+            if let Some(env) = settings.get_mut("env").and_then(Value::as_object_mut) {
+                env.insert(
+                    "ANTHROPIC_BASE_URL".to_string(),
+                    Value::String(url.to_string()),
+                );
+            }
+        }
+        AppType::Codex => {
+            let updated = settings
+                .get("config")
+                .and_then(Value::as_str)
+                .and_then(|text| crate::snippet::set_codex_base_url(text, url));
+            if let (Some(updated), Some(obj)) = (updated, settings.as_object_mut()) {
+                obj.insert("config".to_string(), Value::String(updated));
+            }
+        }
+    }
+}
+
+/// 写入 live 前：将主地址替换为当前最快的可用端点
+pub fn apply(app_type: &AppType, provider: &Provider, settings: &mut Value) {
+    let Some(base_url) = provider.base_url(app_type) else {
+        return;
+    };
+    let Some(fastest) = fastest(provider, &base_url) else {
+        return;
+    };
+    if fastest != normalize(&base_url) {
+        tracing::debug!("供应商 {} 自动选择端点: {}", provider.name, fastest);
+        set_base_url(app_type, settings, &fastest);
+    }
+}
+
+/// 回填前：live 中的地址若是该供应商的其他端点（自动选择的结果），还原为原主地址
+pub fn restore(app_type: &AppType, provider: &Provider, live: &mut Value) {
+    if !is_auto_select(provider) {
+        return;
+    }
+    let Some(base_url) = provider.base_url(app_type) else {
+        return;
+    };
+    let Some(live_url) = crate::provider::settings_base_url(app_type, live) else {
+        return;
+    };
+    let live_url = normalize(&live_url);
+    if live_url != normalize(&base_url) && all_endpoints(provider, &base_url).contains(&live_url) {
+        set_base_url(app_type, live, &base_url);
+    }
+}
+
+/// 测试所有需要测速的供应商端点并记录结果
+async fn probe_all(app_state: &AppState) {
+    let (urls, socket_endpoints): (BTreeSet<String>, Vec<(Provider, String)>) = {
+        let Ok(config) = app_state.config.lock() else {
+            return;
+        };
+        let endpoints: Vec<(&Provider, String)> = [AppType::Claude, AppType::Codex]
+            .iter()
+            .filter_map(|app_type| Some((app_type, config.get_manager(app_type)?)))
+            .flat_map(|(app_type, manager)| {
+                manager
+                    .providers
+                    .values()
+                    .filter(|provider| wants_probe(provider))
+                    .flat_map(|provider| {
+                        all_endpoints(provider, &provider.base_url(app_type).unwrap_or_default())
+                            .into_iter()
+                            .map(move |url| (provider, url))
+                    })
+                    .collect::<Vec<_>>()
+            })
+            .filter(|(_, url)| !url.is_empty())
+            .collect();
+        // 经 Unix 套接字连接的供应商通过代理的连接器测速
+        let (socket, tcp): (Vec<_>, Vec<_>) = endpoints
+            .into_iter()
+            .partition(|(provider, _)| crate::upstream::uses_unix_socket(provider));
+        (
+            tcp.into_iter().map(|(_, url)| url).collect(),
+            socket
+                .into_iter()
+                .map(|(provider, url)| (provider.clone(), url))
+                .collect(),
+        )
+    };
+    for (provider, url) in &socket_endpoints {
+        crate::endpoint_failover::record(&crate::upstream::probe(provider, url).await);
+    }
+    if urls.is_empty() {
+        return;
+    }
+
+    match crate::speedtest::test_endpoints(urls.into_iter().collect(), None).await {
+        Ok(results) => {
+            for result in &results {
+                crate::endpoint_failover::record(result);
+            }
+            tracing::debug!("端点后台测速完成: {} 个端点", results.len());
+        }
+        Err(e) => tracing::warn!("端点后台测速失败: {}", e),
+    }
+}
+
+/// 启动端点后台测速
+pub fn start_endpoint_probe(app: tauri::AppHandle) {
+    tauri::async_runtime::spawn(async move {
+        tokio::time::sleep(STARTUP_DELAY).await;
+        loop {
+            if let Some(app_state) = app.try_state::<AppState>() {
+                probe_all(&app_state).await;
+            }
+            tokio::time::sleep(PROBE_INTERVAL).await;
+        }
+    });
+}
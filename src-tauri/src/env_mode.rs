@@ -0,0 +1,155 @@
+use serde::Serialize;
+use std::path::PathBuf;
+
+use crate::app_config::{AppType, MultiAppConfig};
+use crate::config::{get_app_config_dir, write_text_file};
+
+/// 环境变量模式生成的脚本文件名
+const ENV_SH_FILE: &str = "env.sh";
+const ENV_PS1_FILE: &str = "env.ps1";
+
+/// 脚本类型
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum EnvShell {
+    /// bash / zsh 等 POSIX shell
+    Sh,
+    /// PowerShell
+    Ps1,
+}
+
+impl EnvShell {
+    pub fn from_name(name: &str) -> Self {
+        match name.to_ascii_lowercase().as_str() {
+            "ps1" | "powershell" | "pwsh" => EnvShell::Ps1,
+            _ => EnvShell::Sh,
+        }
+    }
+
+    fn file_name(self) -> &'static str {
+        match self {
+            EnvShell::Sh => ENV_SH_FILE,
+            EnvShell::Ps1 => ENV_PS1_FILE,
+        }
+    }
+}
+
+/// 环境变量片段（供前端展示与复制）
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct EnvSnippet {
+    pub shell: EnvShell,
+    /// 生成的脚本文件路径
+    pub path: String,
+    /// 脚本内容（含密钥，仅用于本机展示）
+    pub content: String,
+    /// 加入 shell 配置文件的引用语句
+    pub source_line: String,
+}
+
+/// 生成脚本的路径（位于应用配置目录）
+pub fn env_file_path(shell: EnvShell) -> PathBuf {
+    get_app_config_dir().join(shell.file_name())
+}
+
+/// 仅输出合法的环境变量名，避免键名中的特殊字符被 shell 解释
+fn is_valid_env_name(name: &str) -> bool {
+    !name.is_empty()
+        && !name.starts_with(|c: char| c.is_ascii_digit())
+        && name.chars().all(|c| c.is_ascii_alphanumeric() || c == '_')
+}
+
+/// 当前供应商对应的环境变量（按应用依次输出）
+/// - Claude：settings_config.env 中的全部字符串项（ANTHROPIC_BASE_URL、ANTHROPIC_AUTH_TOKEN 等）
+/// - Codex：auth.json 中的 OPENAI_API_KEY，以及 config.toml 中的 base_url（OPENAI_BASE_URL）
+pub fn current_env_vars(config: &MultiAppConfig) -> Vec<(String, String)> {
+    let current = |app_type: &AppType| {
+        config
+            .get_manager(app_type)
+            .and_then(|m| m.providers.get(&m.current))
+    };
+
+    let mut vars = Vec::new();
+    if let Some(provider) = current(&AppType::Claude) {
+        if let Some(env) = provider
+            .settings_config
+            .get("env")
+            .and_then(|v| v.as_object())
+        {
+            let mut entries: Vec<_> = env
+                .iter()
+                .filter(|(k, _)| is_valid_env_name(k))
+                .filter_map(|(k, v)| v.as_str().map(|s| (k.clone(), s.to_string())))
+                .collect();
+            entries.sort();
+            vars.extend(entries);
+        }
+    }
+    if let Some(provider) = current(&AppType::Codex) {
+        if let Some(key) = provider
+            .settings_config
+            .get("auth")
+            .and_then(|auth| auth.get("OPENAI_API_KEY"))
+            .and_then(|v| v.as_str())
+        {
+            vars.push(("OPENAI_API_KEY".to_string(), key.to_string()));
+        }
+        if let Some(base_url) = provider.base_url(&AppType::Codex) {
+            vars.push(("OPENAI_BASE_URL".to_string(), base_url));
+        }
+    }
+    vars
+}
+
+/// 渲染脚本内容；值使用单引号包裹以避免 shell 展开
+pub fn render(shell: EnvShell, vars: &[(String, String)]) -> String {
+    let mut out = String::from("# 由 cc-switch 生成，切换供应商时自动更新，请勿手动修改\n");
+    for (key, value) in vars {
+        let line = match shell {
+            EnvShell::Sh => format!("export {}='{}'\n", key, value.replace('\'', r"'\''")),
+            EnvShell::Ps1 => format!("$env:{} = '{}'\n", key, value.replace('\'', "''")),
+        };
+        out.push_str(&line);
+    }
+    out
+}
+
+/// 按当前供应商重新生成 env.sh 与 env.ps1
+pub fn write_env_files(config: &MultiAppConfig) -> Result<(), String> {
+    let vars = current_env_vars(config);
+    for shell in [EnvShell::Sh, EnvShell::Ps1] {
+        let path = env_file_path(shell);
+        write_text_file(&path, &render(shell, &vars))?;
+        // 脚本包含密钥：仅允许当前用户读写
+        #[cfg(unix)]
+        {
+            use std::os::unix::fs::PermissionsExt;
+            if let Err(e) = std::fs::set_permissions(&path, std::fs::Permissions::from_mode(0o600))
+            {
+                tracing::warn!("设置 {} 权限失败: {}", path.display(), e);
+            }
+        }
+    }
+    tracing::info!("已更新环境变量脚本: {}", get_app_config_dir().display());
+    Ok(())
+}
+
+/// 生成指定 shell 的环境变量片段
+pub fn snippet(config: &MultiAppConfig, shell: EnvShell) -> EnvSnippet {
+    let path = env_file_path(shell);
+    let source_line = match shell {
+        EnvShell::Sh => format!("[ -f \"{0}\" ] && . \"{0}\"", path.display()),
+        EnvShell::Ps1 => format!("if (Test-Path '{0}') {{ . '{0}' }}", path.display()),
+    };
+    EnvSnippet {
+        shell,
+        path: path.to_string_lossy().to_string(),
+        content: render(shell, &current_env_vars(config)),
+        source_line,
+    }
+}
+
+/// 是否处于环境变量模式（不写入任何 live 配置文件）
+pub fn is_env_mode() -> bool {
+    crate::settings::get_settings().operation_mode == crate::settings::OperationMode::Env
+}
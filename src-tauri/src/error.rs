@@ -0,0 +1,111 @@
+use serde::ser::SerializeStruct;
+use serde::{Serialize, Serializer};
+
+use crate::validation::FieldError;
+
+/// 后端统一错误类型（序列化给前端时包含错误码、面向用户的信息与调试信息）
+#[derive(Debug, thiserror::Error)]
+pub enum CcSwitchError {
+    /// 配置读取/解析/保存相关错误
+    #[error("{0}")]
+    Config(String),
+    /// 文件系统错误
+    #[error("{message}")]
+    Io {
+        message: String,
+        #[source]
+        source: Option<std::io::Error>,
+    },
+    /// 代理服务相关错误
+    #[error("{0}")]
+    Proxy(String),
+    /// 用量脚本相关错误
+    #[error("{0}")]
+    Script(String),
+    /// 供应商配置校验错误（附字段级详情）
+    #[error("{message}")]
+    Validation {
+        message: String,
+        fields: Vec<FieldError>,
+    },
+}
+
+impl CcSwitchError {
+    /// 稳定的错误码，前端据此做本地化与分类处理
+    pub fn code(&self) -> &'static str {
+        match self {
+            CcSwitchError::Config(_) => "CONFIG_ERROR",
+            CcSwitchError::Io { .. } => "IO_ERROR",
+            CcSwitchError::Proxy(_) => "PROXY_ERROR",
+            CcSwitchError::Script(_) => "SCRIPT_ERROR",
+            CcSwitchError::Validation { .. } => "VALIDATION_ERROR",
+        }
+    }
+
+    pub fn io(message: impl Into<String>, source: std::io::Error) -> Self {
+        CcSwitchError::Io {
+            message: message.into(),
+            source: Some(source),
+        }
+    }
+
+    /// 调试信息：包含底层错误来源，仅用于日志或"详情"展开
+    fn debug_detail(&self) -> Option<String> {
+        match self {
+            CcSwitchError::Io {
+                source: Some(source),
+                ..
+            } => Some(format!("{:?}", source)),
+            _ => None,
+        }
+    }
+}
+
+impl Serialize for CcSwitchError {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        let mut state = serializer.serialize_struct("CcSwitchError", 4)?;
+        state.serialize_field("code", self.code())?;
+        state.serialize_field("message", &self.to_string())?;
+        state.serialize_field("debug", &self.debug_detail())?;
+        match self {
+            CcSwitchError::Validation { fields, .. } => state.serialize_field("fields", fields)?,
+            _ => state.skip_field("fields")?,
+        }
+        state.end()
+    }
+}
+
+/// 现有辅助函数大多返回 `Result<_, String>`，默认归为配置错误，便于通过 `?` 逐步迁移
+impl From<String> for CcSwitchError {
+    fn from(message: String) -> Self {
+        CcSwitchError::Config(message)
+    }
+}
+
+impl From<&str> for CcSwitchError {
+    fn from(message: &str) -> Self {
+        CcSwitchError::Config(message.to_string())
+    }
+}
+
+impl From<std::io::Error> for CcSwitchError {
+    fn from(err: std::io::Error) -> Self {
+        CcSwitchError::Io {
+            message: format!("文件操作失败: {}", err),
+            source: Some(err),
+        }
+    }
+}
+
+impl From<serde_json::Error> for CcSwitchError {
+    fn from(err: serde_json::Error) -> Self {
+        CcSwitchError::Config(format!("JSON 处理失败: {}", err))
+    }
+}
+
+/// 供仍返回 `String` 的内部调用方使用
+impl From<CcSwitchError> for String {
+    fn from(err: CcSwitchError) -> Self {
+        err.to_string()
+    }
+}
@@ -0,0 +1,474 @@
+//! 切换钩子：在切换供应商或运行模式前后执行用户配置的 shell 命令或 JS 片段，
+//! 例如重启 tmux 窗格、结束正在运行的 Claude Code 会话、发送终端通知。
+//! 事件信息通过 `CC_SWITCH_*` 环境变量（shell）或全局对象 `hook`（JS）传入；每个钩子都有超时，
+//! 输出截取后保存在最近执行记录中。前置钩子按顺序执行，开启"失败时中止"的钩子失败会取消本次操作；
+//! 后置钩子在后台执行，失败只记录日志。JS 片段在用量脚本同款沙箱中运行，无法访问文件与网络。
+
+use std::collections::VecDeque;
+use std::io::Read;
+use std::process::{Command, Stdio};
+use std::sync::{Arc, Mutex, OnceLock};
+use std::time::{Duration, Instant};
+
+use serde::{Deserialize, Serialize};
+
+use crate::error::CcSwitchError;
+use crate::i18n::t_args;
+use crate::settings::SwitchHook;
+
+/// 未配置超时时的默认值
+const DEFAULT_TIMEOUT_SECS: u64 = 10;
+
+/// 超时上限
+const MAX_TIMEOUT_SECS: u64 = 300;
+
+/// 每个输出流保存的最大字节数
+const MAX_OUTPUT_BYTES: usize = 16 * 1024;
+
+/// 保留的最近执行记录数
+const MAX_RUNS: usize = 50;
+
+/// 进程退出后等待读取剩余输出的时间（后台子进程可能一直占用管道）
+const OUTPUT_GRACE: Duration = Duration::from_millis(200);
+
+static RUNS: OnceLock<Mutex<VecDeque<HookRun>>> = OnceLock::new();
+
+/// 钩子触发时机
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum HookEvent {
+    BeforeSwitch,
+    AfterSwitch,
+    BeforeModeChange,
+    AfterModeChange,
+}
+
+impl HookEvent {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            HookEvent::BeforeSwitch => "before_switch",
+            HookEvent::AfterSwitch => "after_switch",
+            HookEvent::BeforeModeChange => "before_mode_change",
+            HookEvent::AfterModeChange => "after_mode_change",
+        }
+    }
+}
+
+/// 钩子类型
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum HookKind {
+    /// 由 `sh -c`（Windows 为 `cmd /C`）执行
+    #[default]
+    Shell,
+    /// 在 JS 沙箱中执行，最后一个表达式的值为 `false` 时视为失败
+    Js,
+}
+
+/// 传给钩子的事件信息
+#[derive(Debug, Clone, Default, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct HookContext {
+    pub app_type: Option<String>,
+    pub provider_id: Option<String>,
+    pub provider_name: Option<String>,
+    pub previous_provider_id: Option<String>,
+    pub mode: Option<String>,
+    pub previous_mode: Option<String>,
+}
+
+impl HookContext {
+    pub fn switch(app_type: &str, provider_id: &str, provider_name: &str, previous: &str) -> Self {
+        Self {
+            app_type: Some(app_type.to_string()),
+            provider_id: Some(provider_id.to_string()),
+            provider_name: Some(provider_name.to_string()),
+            previous_provider_id: Some(previous.to_string()).filter(|id| !id.is_empty()),
+            ..Default::default()
+        }
+    }
+
+    pub fn mode_change(mode: &str, previous: &str) -> Self {
+        Self {
+            mode: Some(mode.to_string()),
+            previous_mode: Some(previous.to_string()),
+            ..Default::default()
+        }
+    }
+
+    /// 作为环境变量传给 shell 钩子
+    fn env(&self, event: HookEvent) -> Vec<(&'static str, String)> {
+        let mut env = vec![("CC_SWITCH_EVENT", event.as_str().to_string())];
+        let fields = [
+            ("CC_SWITCH_APP", &self.app_type),
+            ("CC_SWITCH_PROVIDER_ID", &self.provider_id),
+            ("CC_SWITCH_PROVIDER_NAME", &self.provider_name),
+            ("CC_SWITCH_PREVIOUS_PROVIDER_ID", &self.previous_provider_id),
+            ("CC_SWITCH_MODE", &self.mode),
+            ("CC_SWITCH_PREVIOUS_MODE", &self.previous_mode),
+        ];
+        for (name, value) in fields {
+            if let Some(value) = value {
+                env.push((name, value.clone()));
+            }
+        }
+        env
+    }
+}
+
+/// 一次钩子执行的结果
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct HookRun {
+    pub hook_id: String,
+    pub name: String,
+    pub event: HookEvent,
+    /// 毫秒时间戳
+    pub timestamp: i64,
+    pub success: bool,
+    pub exit_code: Option<i32>,
+    pub timed_out: bool,
+    pub stdout: String,
+    pub stderr: String,
+    pub error: Option<String>,
+    pub duration_ms: u64,
+}
+
+impl HookRun {
+    /// 失败原因摘要（用于中止提示与日志）
+    fn failure(&self) -> String {
+        if self.timed_out {
+            return "执行超时".to_string();
+        }
+        if let Some(error) = &self.error {
+            return error.clone();
+        }
+        let stderr = self.stderr.trim();
+        match self.exit_code {
+            Some(code) if !stderr.is_empty() => format!("退出码 {}: {}", code, stderr),
+            Some(code) => format!("退出码 {}", code),
+            None => stderr.to_string(),
+        }
+    }
+}
+
+/// 脚本执行的原始结果
+#[derive(Default)]
+struct Outcome {
+    success: bool,
+    exit_code: Option<i32>,
+    timed_out: bool,
+    stdout: String,
+    stderr: String,
+    error: Option<String>,
+}
+
+fn timeout_of(hook: &SwitchHook) -> Duration {
+    Duration::from_secs(
+        hook.timeout_secs
+            .unwrap_or(DEFAULT_TIMEOUT_SECS)
+            .clamp(1, MAX_TIMEOUT_SECS),
+    )
+}
+
+/// 截取前 `MAX_OUTPUT_BYTES` 字节（按字符边界）
+fn truncate_output(bytes: &[u8]) -> String {
+    let text = String::from_utf8_lossy(bytes);
+    if text.len() <= MAX_OUTPUT_BYTES {
+        return text.to_string();
+    }
+    let mut end = MAX_OUTPUT_BYTES;
+    while !text.is_char_boundary(end) {
+        end -= 1;
+    }
+    format!("{}…", &text[..end])
+}
+
+/// 在后台线程中持续读取输出流，进程被结束后仍能取到已输出的部分
+fn capture<R: Read + Send + 'static>(stream: Option<R>) -> Arc<Mutex<Vec<u8>>> {
+    let buffer = Arc::new(Mutex::new(Vec::new()));
+    if let Some(mut stream) = stream {
+        let buffer = buffer.clone();
+        std::thread::spawn(move || {
+            let mut chunk = [0u8; 4096];
+            while let Ok(n) = stream.read(&mut chunk) {
+                if n == 0 {
+                    break;
+                }
+                let Ok(mut buffer) = buffer.lock() else {
+                    break;
+                };
+                if buffer.len() < MAX_OUTPUT_BYTES + 4 {
+                    buffer.extend_from_slice(&chunk[..n]);
+                }
+            }
+        });
+    }
+    buffer
+}
+
+fn run_shell(script: &str, env: &[(&'static str, String)], timeout: Duration) -> Outcome {
+    #[cfg(windows)]
+    let mut command = {
+        use std::os::windows::process::CommandExt;
+        const CREATE_NO_WINDOW: u32 = 0x0800_0000;
+        let mut command = Command::new("cmd");
+        command.args(["/C", script]);
+        command.creation_flags(CREATE_NO_WINDOW);
+        command
+    };
+    #[cfg(not(windows))]
+    let mut command = {
+        let mut command = Command::new("sh");
+        command.args(["-c", script]);
+        command
+    };
+    command
+        .envs(env.iter().map(|(name, value)| (*name, value)))
+        .stdin(Stdio::null())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped());
+    if let Some(home) = dirs::home_dir() {
+        command.current_dir(home);
+    }
+
+    let mut child = match command.spawn() {
+        Ok(child) => child,
+        Err(e) => {
+            return Outcome {
+                error: Some(format!("启动命令失败: {}", e)),
+                ..Default::default()
+            }
+        }
+    };
+    let stdout = capture(child.stdout.take());
+    let stderr = capture(child.stderr.take());
+
+    let deadline = Instant::now() + timeout;
+    let (status, timed_out) = loop {
+        match child.try_wait() {
+            Ok(Some(status)) => break (Some(status), false),
+            Ok(None) if Instant::now() >= deadline => {
+                let _ = child.kill();
+                break (child.wait().ok(), true);
+            }
+            Ok(None) => std::thread::sleep(Duration::from_millis(20)),
+            Err(e) => {
+                let _ = child.kill();
+                return Outcome {
+                    error: Some(format!("等待命令结束失败: {}", e)),
+                    ..Default::default()
+                };
+            }
+        }
+    };
+    std::thread::sleep(OUTPUT_GRACE);
+    let read = |buffer: &Arc<Mutex<Vec<u8>>>| {
+        buffer
+            .lock()
+            .map(|buffer| truncate_output(&buffer))
+            .unwrap_or_default()
+    };
+
+    let exit_code = status.and_then(|status| status.code());
+    Outcome {
+        success: !timed_out && status.is_some_and(|status| status.success()),
+        exit_code,
+        timed_out,
+        stdout: read(&stdout),
+        stderr: read(&stderr),
+        error: None,
+    }
+}
+
+/// JS 钩子的运行环境：`console.*` 的输出收集到 `__hookOutput`
+const JS_PRELUDE: &str = r#"
+globalThis.__hookOutput = [];
+(function () {
+  const log = (...args) =>
+    __hookOutput.push(
+      args.map((a) => (typeof a === "string" ? a : JSON.stringify(a))).join(" "),
+    );
+  globalThis.console = { log, info: log, warn: log, error: log };
+})();
+"#;
+
+fn run_js(script: &str, context: &serde_json::Value, timeout: Duration) -> Outcome {
+    let deadline = Instant::now() + timeout;
+    let (_runtime, js) = match crate::usage_script::create_sandbox(deadline) {
+        Ok(sandbox) => sandbox,
+        Err(e) => {
+            return Outcome {
+                error: Some(e),
+                ..Default::default()
+            }
+        }
+    };
+    js.with(|ctx| {
+        let mut outcome = Outcome::default();
+        let setup = ctx.eval::<(), _>(JS_PRELUDE).and_then(|_| {
+            let hook = ctx.json_parse(context.to_string())?;
+            ctx.globals().set("hook", hook)
+        });
+        if let Err(e) = setup {
+            outcome.error = Some(format!("初始化钩子环境失败: {}", e));
+            return outcome;
+        }
+
+        match ctx.eval::<rquickjs::Value, _>(script.to_string()) {
+            Ok(result) => {
+                outcome.success = result.as_bool() != Some(false);
+                if !outcome.success {
+                    outcome.error = Some("脚本结果为 false".to_string());
+                }
+            }
+            Err(e) => {
+                outcome.timed_out = Instant::now() >= deadline;
+                let detail = match ctx.catch().into_exception() {
+                    Some(exception) => exception.message().unwrap_or_default(),
+                    None => e.to_string(),
+                };
+                outcome.error = Some(format!("脚本执行失败: {}", detail));
+            }
+        }
+        let output: Vec<String> = ctx
+            .globals()
+            .get::<_, rquickjs::Value>("__hookOutput")
+            .ok()
+            .and_then(|value| ctx.json_stringify(value).ok().flatten())
+            .and_then(|json| json.to_string().ok())
+            .and_then(|json| serde_json::from_str(&json).ok())
+            .unwrap_or_default();
+        outcome.stdout = truncate_output(output.join("\n").as_bytes());
+        outcome
+    })
+}
+
+fn record(run: &HookRun) {
+    let Ok(mut runs) = RUNS.get_or_init(|| Mutex::new(VecDeque::new())).lock() else {
+        return;
+    };
+    runs.push_front(run.clone());
+    runs.truncate(MAX_RUNS);
+}
+
+/// 执行单个钩子并记录结果
+async fn execute(hook: &SwitchHook, event: HookEvent, context: &HookContext) -> HookRun {
+    let timeout = timeout_of(hook);
+    let script = hook.script.clone();
+    let kind = hook.kind;
+    let env = context.env(event);
+    let mut js_context = serde_json::to_value(context).unwrap_or_default();
+    js_context["event"] = serde_json::Value::String(event.as_str().to_string());
+
+    let started = Instant::now();
+    let timestamp = chrono::Utc::now().timestamp_millis();
+    let outcome = tauri::async_runtime::spawn_blocking(move || match kind {
+        HookKind::Shell => run_shell(&script, &env, timeout),
+        HookKind::Js => run_js(&script, &js_context, timeout),
+    })
+    .await
+    .unwrap_or_else(|e| Outcome {
+        error: Some(format!("执行钩子失败: {}", e)),
+        ..Default::default()
+    });
+
+    let run = HookRun {
+        hook_id: hook.id.clone(),
+        name: hook.name.clone(),
+        event,
+        timestamp,
+        success: outcome.success,
+        exit_code: outcome.exit_code,
+        timed_out: outcome.timed_out,
+        stdout: outcome.stdout,
+        stderr: outcome.stderr,
+        error: outcome.error,
+        duration_ms: started.elapsed().as_millis() as u64,
+    };
+    if run.success {
+        tracing::info!("钩子 {} ({}) 执行成功", run.name, event.as_str());
+    } else {
+        tracing::warn!(
+            "钩子 {} ({}) 执行失败: {}",
+            run.name,
+            event.as_str(),
+            run.failure()
+        );
+    }
+    record(&run);
+    run
+}
+
+/// 某事件下需要执行的钩子（按配置顺序）
+fn hooks_for(event: HookEvent, context: &HookContext) -> Vec<SwitchHook> {
+    crate::settings::get_settings()
+        .hooks
+        .into_iter()
+        .filter(|hook| hook.enabled && hook.event == event && !hook.script.trim().is_empty())
+        .filter(|hook| {
+            hook.apps.is_empty()
+                || context
+                    .app_type
+                    .as_ref()
+                    .is_none_or(|app| hook.apps.iter().any(|a| a.eq_ignore_ascii_case(app)))
+        })
+        .collect()
+}
+
+/// 按顺序执行前置钩子；开启"失败时中止"的钩子失败时返回错误，调用方应取消本次操作
+pub async fn before(event: HookEvent, context: &HookContext) -> Result<(), String> {
+    for hook in hooks_for(event, context) {
+        let run = execute(&hook, event, context).await;
+        if !run.success && hook.abort_on_failure {
+            return Err(t_args(
+                "errors.hookAborted",
+                &[("name", &hook.name), ("error", &run.failure())],
+            ));
+        }
+    }
+    Ok(())
+}
+
+/// 在后台按顺序执行后置钩子
+pub fn after(event: HookEvent, context: HookContext) {
+    let hooks = hooks_for(event, &context);
+    if hooks.is_empty() {
+        return;
+    }
+    tauri::async_runtime::spawn(async move {
+        for hook in hooks {
+            execute(&hook, event, &context).await;
+        }
+    });
+}
+
+/// 最近的钩子执行记录（新的在前）
+#[tauri::command]
+pub async fn get_hook_runs() -> Result<Vec<HookRun>, CcSwitchError> {
+    Ok(RUNS
+        .get_or_init(|| Mutex::new(VecDeque::new()))
+        .lock()
+        .map(|runs| runs.iter().cloned().collect())
+        .unwrap_or_default())
+}
+
+/// 用示例事件信息试运行钩子（设置尚未保存时也可测试）
+#[tauri::command]
+pub async fn test_hook(hook: SwitchHook) -> Result<HookRun, CcSwitchError> {
+    // 试运行会执行任意命令，锁定时拒绝
+    crate::app_lock::ensure_unlocked()?;
+    let context = match hook.event {
+        HookEvent::BeforeSwitch | HookEvent::AfterSwitch => HookContext::switch(
+            hook.apps.first().map_or("claude", String::as_str),
+            "example",
+            "Example",
+            "",
+        ),
+        HookEvent::BeforeModeChange | HookEvent::AfterModeChange => {
+            let mode = crate::settings::get_settings().operation_mode;
+            HookContext::mode_change(mode.as_str(), mode.as_str())
+        }
+    };
+    Ok(execute(&hook, hook.event, &context).await)
+}
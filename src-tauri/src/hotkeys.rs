@@ -0,0 +1,128 @@
+use tauri::Manager;
+use tauri_plugin_global_shortcut::{GlobalShortcutExt, ShortcutState};
+
+use crate::app_config::AppType;
+use crate::settings::HotkeySettings;
+use crate::store::AppState;
+
+/// 快捷键动作
+#[derive(Debug, Clone, Copy)]
+enum HotkeyAction {
+    Next(AppKind),
+    Previous(AppKind),
+    ToggleProxy,
+}
+
+/// AppType 不是 Copy，这里用轻量枚举在闭包间传递
+#[derive(Debug, Clone, Copy)]
+enum AppKind {
+    Claude,
+    Codex,
+}
+
+impl AppKind {
+    fn app_type(self) -> AppType {
+        match self {
+            AppKind::Claude => AppType::Claude,
+            AppKind::Codex => AppType::Codex,
+        }
+    }
+}
+
+/// 按设置（重新）注册全局快捷键；单个快捷键无效时仅记录日志并跳过
+pub fn register_hotkeys(app: &tauri::AppHandle, settings: &HotkeySettings) -> Result<(), String> {
+    let shortcuts = app.global_shortcut();
+    shortcuts
+        .unregister_all()
+        .map_err(|e| format!("注销全局快捷键失败: {}", e))?;
+
+    if !settings.enabled {
+        return Ok(());
+    }
+
+    let bindings = [
+        (&settings.next_claude, HotkeyAction::Next(AppKind::Claude)),
+        (
+            &settings.prev_claude,
+            HotkeyAction::Previous(AppKind::Claude),
+        ),
+        (&settings.next_codex, HotkeyAction::Next(AppKind::Codex)),
+        (&settings.prev_codex, HotkeyAction::Previous(AppKind::Codex)),
+        (&settings.toggle_proxy, HotkeyAction::ToggleProxy),
+    ];
+
+    for (accelerator, action) in bindings {
+        let Some(accelerator) = accelerator.as_deref().map(str::trim) else {
+            continue;
+        };
+        if accelerator.is_empty() {
+            continue;
+        }
+
+        let result = shortcuts.on_shortcut(accelerator, move |app, _shortcut, event| {
+            if event.state() != ShortcutState::Pressed {
+                return;
+            }
+            let app = app.clone();
+            tauri::async_runtime::spawn(async move {
+                if let Err(e) = run_action(&app, action).await {
+                    tracing::error!("执行快捷键动作失败: {}", e);
+                }
+            });
+        });
+
+        match result {
+            Ok(()) => tracing::info!("已注册全局快捷键 {} -> {:?}", accelerator, action),
+            Err(e) => tracing::warn!("注册全局快捷键 {} 失败: {}", accelerator, e),
+        }
+    }
+
+    Ok(())
+}
+
+async fn run_action(app: &tauri::AppHandle, action: HotkeyAction) -> Result<(), String> {
+    match action {
+        HotkeyAction::Next(kind) => rotate_provider(app, kind.app_type(), 1).await,
+        HotkeyAction::Previous(kind) => rotate_provider(app, kind.app_type(), -1).await,
+        HotkeyAction::ToggleProxy => {
+            let mode = crate::toggle_operation_mode_internal(app).await?;
+            tracing::info!("已通过快捷键切换运行模式: {:?}", mode);
+            Ok(())
+        }
+    }
+}
+
+/// 按界面顺序切换到下一个/上一个供应商（首尾循环）
+async fn rotate_provider(
+    app: &tauri::AppHandle,
+    app_type: AppType,
+    step: isize,
+) -> Result<(), String> {
+    let target_id = {
+        let state = app.try_state::<AppState>().ok_or("应用状态尚未就绪")?;
+        let config = state
+            .config
+            .lock()
+            .map_err(|e| format!("获取锁失败: {}", e))?;
+        let manager = config.get_manager(&app_type).ok_or("应用类型不存在")?;
+
+        // 跳过已停用或维护中的供应商（保留当前供应商用于定位）
+        let providers: Vec<_> = manager
+            .sorted_providers()
+            .into_iter()
+            .filter(|p| (p.is_enabled() && !p.in_maintenance()) || p.id == manager.current)
+            .collect();
+        if providers.len() < 2 {
+            return Ok(());
+        }
+        let len = providers.len() as isize;
+        let next_index = match providers.iter().position(|p| p.id == manager.current) {
+            Some(index) => (index as isize + step).rem_euclid(len),
+            None => 0,
+        };
+        providers[next_index as usize].id.clone()
+    };
+
+    tracing::info!("快捷键切换 {} 供应商: {}", app_type.as_str(), target_id);
+    crate::switch_provider_internal(app, app_type, target_id).await
+}
@@ -0,0 +1,90 @@
+use serde_json::Value;
+use std::collections::HashMap;
+use std::fmt::Display;
+use std::sync::OnceLock;
+
+/// 后端支持的语言
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Locale {
+    Zh,
+    En,
+    Ja,
+}
+
+impl Locale {
+    /// 解析语言标签（zh / zh-CN / en-US / ja 等），无法识别时返回 None
+    pub fn from_tag(tag: &str) -> Option<Self> {
+        let primary = tag.trim().split(['-', '_']).next()?.to_ascii_lowercase();
+        match primary.as_str() {
+            "zh" => Some(Locale::Zh),
+            "en" => Some(Locale::En),
+            "ja" => Some(Locale::Ja),
+            _ => None,
+        }
+    }
+
+    fn bundle_source(self) -> &'static str {
+        match self {
+            Locale::Zh => include_str!("../locales/zh.json"),
+            Locale::En => include_str!("../locales/en.json"),
+            Locale::Ja => include_str!("../locales/ja.json"),
+        }
+    }
+}
+
+/// 默认语言与前端保持一致
+const DEFAULT_LOCALE: Locale = Locale::Zh;
+/// 当前语言缺少某个 key 时回退的语言
+const FALLBACK_LOCALE: Locale = Locale::En;
+
+static BUNDLES: OnceLock<HashMap<Locale, Value>> = OnceLock::new();
+
+fn bundles() -> &'static HashMap<Locale, Value> {
+    BUNDLES.get_or_init(|| {
+        [Locale::Zh, Locale::En, Locale::Ja]
+            .into_iter()
+            .map(|locale| {
+                let bundle = serde_json::from_str(locale.bundle_source())
+                    .expect("内置语言包不是合法的 JSON");
+                (locale, bundle)
+            })
+            .collect()
+    })
+}
+
+/// 当前语言：取自设置中的 language，未设置时使用默认语言
+pub fn current_locale() -> Locale {
+    crate::settings::get_settings()
+        .language
+        .as_deref()
+        .and_then(Locale::from_tag)
+        .unwrap_or(DEFAULT_LOCALE)
+}
+
+/// 按点分隔的 key（如 `errors.lockFailed`）查找文案
+fn lookup(locale: Locale, key: &str) -> Option<&'static str> {
+    key.split('.')
+        .try_fold(bundles().get(&locale)?, |node, segment| node.get(segment))?
+        .as_str()
+}
+
+/// 翻译指定语言的文案；缺失时依次回退到英文与 key 本身
+pub fn translate(locale: Locale, key: &str, args: &[(&str, &dyn Display)]) -> String {
+    let template = lookup(locale, key)
+        .or_else(|| lookup(FALLBACK_LOCALE, key))
+        .unwrap_or(key);
+    args.iter()
+        .fold(template.to_string(), |text, (name, value)| {
+            text.replace(&format!("{{{{{}}}}}", name), &value.to_string())
+        })
+}
+
+/// 按当前语言翻译文案
+pub fn t(key: &str) -> String {
+    translate(current_locale(), key, &[])
+}
+
+/// 按当前语言翻译带插值参数的文案（占位符格式与前端一致：`{{name}}`）
+pub fn t_args(key: &str, args: &[(&str, &dyn Display)]) -> String {
+    translate(current_locale(), key, args)
+}
@@ -3,22 +3,18 @@ use serde_json::{json, Value};
 use std::fs;
 use std::path::PathBuf;
 
+use crate::app_config::MultiAppConfig;
+use crate::error::CcSwitchError;
+
 // 默认仅保留最近 10 份备份，避免目录无限膨胀
 const MAX_BACKUPS: usize = 10;
 
-/// 创建配置文件备份
-pub fn create_backup(config_path: &PathBuf) -> Result<String, String> {
-    if !config_path.exists() {
-        return Ok(String::new());
-    }
-
+/// 将当前配置备份为 JSON 文件
+pub fn create_backup(config: &MultiAppConfig) -> Result<String, String> {
     let timestamp = Utc::now().format("%Y%m%d_%H%M%S");
     let backup_id = format!("backup_{}", timestamp);
 
-    let backup_dir = config_path
-        .parent()
-        .ok_or("Invalid config path")?
-        .join("backups");
+    let backup_dir = crate::config::get_app_config_dir().join("backups");
 
     // 创建备份目录
     fs::create_dir_all(&backup_dir)
@@ -26,8 +22,9 @@ pub fn create_backup(config_path: &PathBuf) -> Result<String, String> {
 
     let backup_path = backup_dir.join(format!("{}.json", backup_id));
 
-    // 复制配置文件到备份
-    fs::copy(config_path, backup_path).map_err(|e| format!("Failed to create backup: {}", e))?;
+    // 写入配置快照到备份
+    crate::config::write_json_file(&backup_path, config)
+        .map_err(|e| format!("Failed to create backup: {}", e))?;
 
     // 备份完成后清理旧的备份文件（仅保留最近 MAX_BACKUPS 份）
     cleanup_old_backups(&backup_dir, MAX_BACKUPS)?;
@@ -68,7 +65,7 @@ fn cleanup_old_backups(backup_dir: &PathBuf, retain: usize) -> Result<(), String
 
     for entry in entries.into_iter().take(remove_count) {
         if let Err(err) = fs::remove_file(entry.path()) {
-            log::warn!(
+            tracing::warn!(
                 "Failed to remove old backup {}: {}",
                 entry.path().display(),
                 err
@@ -81,14 +78,25 @@ fn cleanup_old_backups(backup_dir: &PathBuf, retain: usize) -> Result<(), String
 
 /// 导出配置文件
 #[tauri::command]
-pub async fn export_config_to_file(file_path: String) -> Result<Value, String> {
-    // 读取当前配置文件
-    let config_path = crate::config::get_app_config_path();
-    let config_content = fs::read_to_string(&config_path)
-        .map_err(|e| format!("Failed to read configuration: {}", e))?;
+pub async fn export_config_to_file(
+    file_path: String,
+    state: tauri::State<'_, crate::store::AppState>,
+) -> Result<Value, CcSwitchError> {
+    crate::app_lock::ensure_unlocked()?;
+
+    // 以内存中的当前配置导出（与导入格式一致的 JSON）
+    let config_content = {
+        let config = state
+            .config
+            .lock()
+            .map_err(|e| format!("Failed to lock config: {}", e))?;
+        serde_json::to_string_pretty(&*config)
+            .map_err(|e| format!("Failed to serialize configuration: {}", e))?
+    };
 
     // 写入到指定文件
-    fs::write(&file_path, &config_content).map_err(|e| format!("Failed to write file: {}", e))?;
+    fs::write(&file_path, &config_content)
+        .map_err(|e| CcSwitchError::io(format!("Failed to write file: {}", e), e))?;
 
     Ok(json!({
         "success": true,
@@ -102,51 +110,63 @@ pub async fn export_config_to_file(file_path: String) -> Result<Value, String> {
 pub async fn import_config_from_file(
     file_path: String,
     state: tauri::State<'_, crate::store::AppState>,
-) -> Result<Value, String> {
+) -> Result<Value, CcSwitchError> {
     // 读取导入的文件
-    let import_content =
-        fs::read_to_string(&file_path).map_err(|e| format!("Failed to read import file: {}", e))?;
+    let import_content = fs::read_to_string(&file_path)
+        .map_err(|e| CcSwitchError::io(format!("Failed to read import file: {}", e), e))?;
 
-    // 验证并解析为配置对象
-    let new_config: crate::app_config::MultiAppConfig = serde_json::from_str(&import_content)
+    // 验证并解析为配置对象（旧版本配置按版本逐级迁移，新版本配置拒绝导入）
+    let import_value: Value = serde_json::from_str(&import_content)
+        .map_err(|e| format!("Invalid configuration file: {}", e))?;
+    let (new_config, _) = crate::store::migrate_config_value(import_value)
         .map_err(|e| format!("Invalid configuration file: {}", e))?;
 
-    // 备份当前配置
-    let config_path = crate::config::get_app_config_path();
-    let backup_id = create_backup(&config_path)?;
-
-    // 写入新配置到磁盘
-    fs::write(&config_path, &import_content)
-        .map_err(|e| format!("Failed to write configuration: {}", e))?;
+    // 导入的供应商中 API 地址与密钥相同的条目，交由前端提示合并
+    let duplicates = crate::duplicates::find_duplicates(&new_config);
 
-    // 更新内存中的状态
-    {
+    // 备份当前配置，再写入新配置并更新内存中的状态（统一按当前版本结构保存）
+    let backup_id = {
         let mut config_state = state
             .config
             .lock()
             .map_err(|e| format!("Failed to lock config: {}", e))?;
+        let backup_id = create_backup(&config_state)?;
+        new_config.save()?;
         *config_state = new_config;
-    }
+        backup_id
+    };
+    crate::audit::record(
+        crate::audit::AuditAction::ConfigImported,
+        None,
+        None,
+        Some(file_path.clone()),
+    );
 
     Ok(json!({
         "success": true,
         "message": "Configuration imported successfully",
-        "backupId": backup_id
+        "backupId": backup_id,
+        "duplicates": duplicates
     }))
 }
 
-/// 保存文件对话框
+/// 保存文件对话框（按默认文件名的扩展名设置过滤器，未带扩展名时为 JSON）
 #[tauri::command]
 pub async fn save_file_dialog<R: tauri::Runtime>(
     app: tauri::AppHandle<R>,
     default_name: String,
-) -> Result<Option<String>, String> {
+) -> Result<Option<String>, CcSwitchError> {
     use tauri_plugin_dialog::DialogExt;
 
+    let extension = std::path::Path::new(&default_name)
+        .extension()
+        .and_then(|ext| ext.to_str())
+        .unwrap_or("json")
+        .to_ascii_lowercase();
     let dialog = app.dialog();
     let result = dialog
         .file()
-        .add_filter("JSON", &["json"])
+        .add_filter(extension.to_ascii_uppercase(), &[extension.as_str()])
         .set_file_name(&default_name)
         .blocking_save_file();
 
@@ -157,7 +177,7 @@ pub async fn save_file_dialog<R: tauri::Runtime>(
 #[tauri::command]
 pub async fn open_file_dialog<R: tauri::Runtime>(
     app: tauri::AppHandle<R>,
-) -> Result<Option<String>, String> {
+) -> Result<Option<String>, CcSwitchError> {
     use tauri_plugin_dialog::DialogExt;
 
     let dialog = app.dialog();
@@ -0,0 +1,131 @@
+//! 代理模式下按 Key 统计用量：记录每个供应商实际使用的 Key 的请求数、失败数与最近使用时间，
+//! 便于确认中转的哪个 Key 已耗尽（限流 / 429）或被封禁（鉴权失败）。
+//! Key 在内存中按原值区分、对外只返回掩码；统计仅保存在进程内，重启后清零。
+
+use serde::Serialize;
+use std::collections::HashMap;
+use std::sync::{Mutex, OnceLock};
+use tauri::State;
+
+use crate::app_config::AppType;
+use crate::error::CcSwitchError;
+use crate::i18n::t_args;
+use crate::provider::Provider;
+use crate::store::AppState;
+
+/// 应用类型:供应商 ID:Key -> 统计
+static STATS: OnceLock<Mutex<HashMap<String, KeyCounter>>> = OnceLock::new();
+
+#[derive(Debug, Clone)]
+struct KeyCounter {
+    app_type: AppType,
+    provider_id: String,
+    key: String,
+    provider_name: String,
+    requests: u64,
+    failures: u64,
+    last_status: Option<u16>,
+    last_used: i64,
+    last_failure: Option<i64>,
+}
+
+/// 单个 Key 的用量统计
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct KeyUsage {
+    pub app_type: AppType,
+    pub provider_id: String,
+    pub provider_name: String,
+    /// 掩码后的 Key
+    pub key: String,
+    pub requests: u64,
+    pub failures: u64,
+    /// 最近一次上游状态码（网络错误时为空）
+    pub last_status: Option<u16>,
+    /// 最近使用时间（毫秒时间戳）
+    pub last_used: i64,
+    pub last_failure: Option<i64>,
+    /// 处于鉴权失败冷却期
+    pub blocked: bool,
+    /// 已达到供应商的限流上限
+    pub limited: bool,
+}
+
+/// 记录一次发往上游的请求；status 为空表示网络错误或超时
+pub fn record(app_type: &AppType, provider: &Provider, api_key: &str, status: Option<u16>) {
+    let Ok(mut stats) = STATS.get_or_init(Default::default).lock() else {
+        return;
+    };
+    let now = chrono::Utc::now().timestamp_millis();
+    let counter = stats
+        .entry(format!("{}:{}:{}", app_type.as_str(), provider.id, api_key))
+        .or_insert_with(|| KeyCounter {
+            app_type: app_type.clone(),
+            provider_id: provider.id.clone(),
+            key: api_key.to_string(),
+            provider_name: provider.name.clone(),
+            requests: 0,
+            failures: 0,
+            last_status: None,
+            last_used: now,
+            last_failure: None,
+        });
+    counter.provider_name = provider.name.clone();
+    counter.requests += 1;
+    counter.last_status = status;
+    counter.last_used = now;
+    if status != Some(200) {
+        counter.failures += 1;
+        counter.last_failure = Some(now);
+    }
+}
+
+/// 获取各 Key 的用量统计（按应用与供应商名称排序）
+#[tauri::command]
+pub async fn get_key_usage_stats(
+    state: State<'_, AppState>,
+) -> Result<Vec<KeyUsage>, CcSwitchError> {
+    let counters: Vec<_> = STATS
+        .get_or_init(Default::default)
+        .lock()
+        .map_err(|e| t_args("errors.lockFailed", &[("error", &e)]))?
+        .values()
+        .cloned()
+        .collect();
+    let config = state
+        .config
+        .lock()
+        .map_err(|e| t_args("errors.lockFailed", &[("error", &e)]))?;
+
+    let mut usage: Vec<KeyUsage> = counters
+        .into_iter()
+        .map(|counter| {
+            let provider = config
+                .get_manager(&counter.app_type)
+                .and_then(|manager| manager.providers.get(&counter.provider_id));
+            KeyUsage {
+                blocked: provider
+                    .is_some_and(|p| crate::auth_failure::is_blocked(&counter.app_type, p)),
+                limited: provider
+                    .is_some_and(|p| crate::rate_limit::is_limited(&counter.app_type, p)),
+                app_type: counter.app_type,
+                provider_id: counter.provider_id,
+                provider_name: counter.provider_name,
+                key: crate::redact::mask(&counter.key),
+                requests: counter.requests,
+                failures: counter.failures,
+                last_status: counter.last_status,
+                last_used: counter.last_used,
+                last_failure: counter.last_failure,
+            }
+        })
+        .collect();
+    usage.sort_by(|a, b| {
+        (a.app_type.as_str(), &a.provider_name, &a.key).cmp(&(
+            b.app_type.as_str(),
+            &b.provider_name,
+            &b.key,
+        ))
+    });
+    Ok(usage)
+}
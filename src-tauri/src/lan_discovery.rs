@@ -0,0 +1,296 @@
+//! 局域网共享代理的发现：开启局域网共享后通过 mDNS 广播 `_ccswitch._tcp` 服务，
+//! 另一台机器上的 cc-switch 可以搜索到它，并一键添加指向该代理的供应商（Claude 与 Codex 各一个）
+//! 后切换过去，使本机的 live 配置改为经由共享代理转发。
+
+use mdns_sd::{ServiceDaemon, ServiceEvent, ServiceInfo};
+use serde::Serialize;
+use std::collections::HashMap;
+use std::sync::{Mutex, OnceLock};
+use std::time::Duration;
+use tauri::State;
+
+use crate::app_config::AppType;
+use crate::audit::AuditAction;
+use crate::error::CcSwitchError;
+use crate::i18n::t_args;
+use crate::provider::Provider;
+use crate::settings::LanShareSettings;
+use crate::store::AppState;
+
+/// mDNS 服务类型
+const SERVICE_TYPE: &str = "_ccswitch._tcp.local.";
+
+/// 搜索时长
+const BROWSE_DURATION: Duration = Duration::from_secs(3);
+
+/// 连接共享代理时添加的供应商 ID
+const SHARED_PROVIDER_ID: &str = "lan-shared-proxy";
+
+/// Codex config.toml 中共享代理的 model_providers 条目名
+const CODEX_PROVIDER_NAME: &str = "ccswitch_shared";
+
+/// 正在广播的服务：mDNS 守护进程与服务全名
+static ADVERTISED: OnceLock<Mutex<Option<(ServiceDaemon, String)>>> = OnceLock::new();
+
+/// 局域网中发现的共享代理
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SharedProxy {
+    /// 实例名（对方主机名）
+    pub name: String,
+    pub addresses: Vec<String>,
+    pub port: u16,
+    pub tls: bool,
+    /// 按第一个 IPv4 地址拼出的代理地址
+    pub url: String,
+}
+
+/// 本机实例名
+fn instance_name() -> String {
+    std::env::var("HOSTNAME")
+        .or_else(|_| std::env::var("COMPUTERNAME"))
+        .ok()
+        .map(|name| name.trim().trim_end_matches(".local").to_string())
+        .filter(|name| !name.is_empty())
+        .unwrap_or_else(|| "cc-switch".to_string())
+}
+
+fn advertised() -> &'static Mutex<Option<(ServiceDaemon, String)>> {
+    ADVERTISED.get_or_init(|| Mutex::new(None))
+}
+
+/// 广播局域网共享代理（局域网监听启动后调用）
+pub fn advertise(settings: &LanShareSettings, tls: bool) {
+    unadvertise();
+    let daemon = match ServiceDaemon::new() {
+        Ok(daemon) => daemon,
+        Err(e) => {
+            tracing::warn!("启动 mDNS 服务失败: {}", e);
+            return;
+        }
+    };
+    let instance = instance_name();
+    let host = format!("{}.local.", instance);
+    let properties = [
+        ("version", env!("CARGO_PKG_VERSION")),
+        ("tls", if tls { "1" } else { "0" }),
+    ];
+    // 监听所有网卡时由 mDNS 自动填充各网卡地址
+    let all_interfaces = settings.bind_address == "0.0.0.0" || settings.bind_address == "::";
+    let ip = if all_interfaces {
+        ""
+    } else {
+        settings.bind_address.as_str()
+    };
+    let info = match ServiceInfo::new(
+        SERVICE_TYPE,
+        &instance,
+        &host,
+        ip,
+        settings.port,
+        &properties[..],
+    ) {
+        Ok(info) if all_interfaces => info.enable_addr_auto(),
+        Ok(info) => info,
+        Err(e) => {
+            tracing::warn!("构建 mDNS 服务信息失败: {}", e);
+            return;
+        }
+    };
+    let fullname = info.get_fullname().to_string();
+    if let Err(e) = daemon.register(info) {
+        tracing::warn!("广播局域网共享代理失败: {}", e);
+        return;
+    }
+    tracing::info!("已通过 mDNS 广播局域网共享代理: {}", fullname);
+    if let Ok(mut guard) = advertised().lock() {
+        *guard = Some((daemon, fullname));
+    }
+}
+
+/// 停止广播（代理停止时调用）
+pub fn unadvertise() {
+    let Some((daemon, fullname)) = advertised().lock().ok().and_then(|mut guard| guard.take())
+    else {
+        return;
+    };
+    if let Err(e) = daemon.unregister(&fullname) {
+        tracing::warn!("取消 mDNS 广播失败: {}", e);
+    }
+    if let Err(e) = daemon.shutdown() {
+        tracing::warn!("关闭 mDNS 服务失败: {}", e);
+    }
+}
+
+/// 在局域网中搜索共享代理（排除本机广播的服务）
+async fn browse() -> Result<Vec<SharedProxy>, String> {
+    let own = advertised()
+        .lock()
+        .ok()
+        .and_then(|guard| guard.as_ref().map(|(_, fullname)| fullname.clone()));
+    let daemon = ServiceDaemon::new().map_err(|e| format!("启动 mDNS 服务失败: {}", e))?;
+    let receiver = daemon
+        .browse(SERVICE_TYPE)
+        .map_err(|e| format!("搜索局域网共享代理失败: {}", e))?;
+
+    let mut found: HashMap<String, SharedProxy> = HashMap::new();
+    let deadline = tokio::time::Instant::now() + BROWSE_DURATION;
+    while let Ok(Ok(event)) = tokio::time::timeout_at(deadline, receiver.recv_async()).await {
+        let ServiceEvent::ServiceResolved(info) = event else {
+            continue;
+        };
+        if own.as_deref() == Some(info.get_fullname()) {
+            continue;
+        }
+        let mut addresses: Vec<String> = info
+            .get_addresses()
+            .iter()
+            .map(|ip| ip.to_string())
+            .collect();
+        addresses.sort();
+        let tls = info.get_property_val_str("tls") == Some("1");
+        let host = info
+            .get_addresses_v4()
+            .into_iter()
+            .min()
+            .map(|ip| ip.to_string())
+            .or_else(|| addresses.first().map(|ip| format!("[{}]", ip)))
+            .unwrap_or_else(|| info.get_hostname().trim_end_matches('.').to_string());
+        let scheme = if tls { "https" } else { "http" };
+        let name = info
+            .get_fullname()
+            .trim_end_matches(SERVICE_TYPE)
+            .trim_end_matches('.')
+            .to_string();
+        found.insert(
+            info.get_fullname().to_string(),
+            SharedProxy {
+                url: format!("{}://{}:{}", scheme, host, info.get_port()),
+                name,
+                addresses,
+                port: info.get_port(),
+                tls,
+            },
+        );
+    }
+    if let Err(e) = daemon.stop_browse(SERVICE_TYPE) {
+        tracing::debug!("停止 mDNS 搜索失败: {}", e);
+    }
+    if let Err(e) = daemon.shutdown() {
+        tracing::debug!("关闭 mDNS 服务失败: {}", e);
+    }
+
+    let mut proxies: Vec<SharedProxy> = found.into_values().collect();
+    proxies.sort_by(|a, b| a.name.cmp(&b.name));
+    Ok(proxies)
+}
+
+/// 指向共享代理的供应商配置
+fn shared_settings(app_type: &AppType, url: &str, token: &str) -> serde_json::Value {
+    match app_type {
+        AppType::Claude => serde_json::json!({
+            "env": {
+                "ANTHROPIC_BASE_URL": url,
+                "ANTHROPIC_AUTH_TOKEN": token,
+            }
+        }),
+        AppType::Codex => {
+            let mut provider = toml_edit::Table::new();
+            provider.insert("name", toml_edit::value("cc-switch LAN"));
+            provider.insert("base_url", toml_edit::value(url));
+            provider.insert("requires_openai_auth", toml_edit::value(true));
+            provider.insert("wire_api", toml_edit::value("responses"));
+            let mut providers = toml_edit::Table::new();
+            providers.set_implicit(true);
+            providers.insert(CODEX_PROVIDER_NAME, toml_edit::Item::Table(provider));
+            let mut doc = toml_edit::DocumentMut::new();
+            doc.insert("model_provider", toml_edit::value(CODEX_PROVIDER_NAME));
+            doc.insert("model_providers", toml_edit::Item::Table(providers));
+            serde_json::json!({
+                "auth": { "OPENAI_API_KEY": token },
+                "config": doc.to_string(),
+            })
+        }
+    }
+}
+
+/// 添加（或更新）指向共享代理的供应商
+fn upsert_shared_provider(
+    app_state: &AppState,
+    app_type: &AppType,
+    name: &str,
+    url: &str,
+    token: &str,
+) -> Result<(), CcSwitchError> {
+    let settings_config = shared_settings(app_type, url, token);
+    crate::validation::ensure_valid(app_type, &settings_config)?;
+    let mut config = app_state
+        .config
+        .lock()
+        .map_err(|e| t_args("errors.lockFailed", &[("error", &e)]))?;
+    let manager = config
+        .get_manager_mut(app_type)
+        .ok_or_else(|| t_args("errors.appTypeNotFound", &[("app", &app_type.as_str())]))?;
+    let action = match manager.providers.get_mut(SHARED_PROVIDER_ID) {
+        Some(existing) => {
+            existing.name = name.to_string();
+            existing.settings_config = settings_config;
+            AuditAction::ProviderUpdated
+        }
+        None => {
+            let mut provider = Provider::with_id(
+                SHARED_PROVIDER_ID.to_string(),
+                name.to_string(),
+                settings_config,
+                None,
+            );
+            provider.created_at = Some(chrono::Utc::now().timestamp_millis());
+            manager
+                .providers
+                .insert(SHARED_PROVIDER_ID.to_string(), provider);
+            AuditAction::ProviderAdded
+        }
+    };
+    if let Some(provider) = manager.providers.get_mut(SHARED_PROVIDER_ID) {
+        if matches!(app_type, AppType::Codex) {
+            crate::codex_config::sync_credential_kind(provider);
+        }
+    }
+    crate::audit::record(action, Some(app_type), Some(name), Some(url.to_string()));
+    Ok(())
+}
+
+/// 搜索局域网中的共享代理
+#[tauri::command]
+pub async fn discover_shared_proxies() -> Result<Vec<SharedProxy>, CcSwitchError> {
+    Ok(browse().await?)
+}
+
+/// 连接共享代理：为 Claude 与 Codex 各添加一个指向该代理的供应商并切换过去
+#[tauri::command]
+pub async fn connect_shared_proxy(
+    handle: tauri::AppHandle,
+    state: State<'_, AppState>,
+    name: String,
+    url: String,
+    token: String,
+) -> Result<bool, CcSwitchError> {
+    let url = url.trim().trim_end_matches('/').to_string();
+    let token = token.trim().to_string();
+    let name = format!("LAN · {}", name.trim());
+    for app_type in [AppType::Claude, AppType::Codex] {
+        upsert_shared_provider(&state, &app_type, &name, &url, &token)?;
+    }
+    state.save_now()?;
+    for app_type in [AppType::Claude, AppType::Codex] {
+        crate::switch_provider_internal(&handle, app_type.clone(), SHARED_PROVIDER_ID.to_string())
+            .await?;
+        crate::bulk::emit_providers_updated(
+            &handle,
+            &app_type,
+            "lan_connect",
+            vec![SHARED_PROVIDER_ID.to_string()],
+        );
+    }
+    Ok(true)
+}
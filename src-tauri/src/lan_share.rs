@@ -0,0 +1,93 @@
+//! 代理的局域网共享：开启后在单独的端口上对局域网开放代理，使团队成员或另一台机器
+//! （如运行 Claude Code 的无界面服务器）共用本机的供应商池。
+//! 局域网请求必须携带设置中的访问令牌，且来源 IP 须在允许列表内；本机监听不受影响。
+
+use axum::http::{HeaderMap, StatusCode};
+use std::net::{IpAddr, SocketAddr};
+
+use crate::i18n::t;
+use crate::settings::LanShareSettings;
+
+/// 来源 IP 是否匹配单条规则（单个 IP 或 CIDR 网段）
+fn ip_matches(ip: &IpAddr, rule: &str) -> bool {
+    let (addr, prefix) = match rule.split_once('/') {
+        Some((addr, prefix)) => (addr, prefix.parse::<u32>().ok()),
+        None => (rule, None),
+    };
+    let Ok(network) = addr.trim().parse::<IpAddr>() else {
+        return false;
+    };
+    match (ip.to_canonical(), network) {
+        (IpAddr::V4(ip), IpAddr::V4(network)) => {
+            let prefix = prefix.unwrap_or(32).min(32);
+            let mask = u32::MAX.checked_shl(32 - prefix).unwrap_or(0);
+            u32::from(ip) & mask == u32::from(network) & mask
+        }
+        (IpAddr::V6(ip), IpAddr::V6(network)) => {
+            let prefix = prefix.unwrap_or(128).min(128);
+            let mask = u128::MAX.checked_shl(128 - prefix).unwrap_or(0);
+            u128::from(ip) & mask == u128::from(network) & mask
+        }
+        _ => false,
+    }
+}
+
+/// 来源 IP 是否在允许列表内（列表为空表示不限制）
+fn ip_allowed(settings: &LanShareSettings, ip: &IpAddr) -> bool {
+    settings.allowed_ips.is_empty() || settings.allowed_ips.iter().any(|rule| ip_matches(ip, rule))
+}
+
+/// 请求携带的访问令牌：`Authorization: Bearer <token>` 或 `x-api-key: <token>`
+fn request_token(headers: &HeaderMap) -> Option<&str> {
+    let bearer = headers
+        .get("authorization")
+        .and_then(|v| v.to_str().ok())
+        .and_then(|v| v.strip_prefix("Bearer "));
+    bearer.or_else(|| headers.get("x-api-key").and_then(|v| v.to_str().ok()))
+}
+
+/// 逐字节比较，耗时与内容无关
+fn token_equals(a: &str, b: &str) -> bool {
+    a.len() == b.len()
+        && a.bytes()
+            .zip(b.bytes())
+            .fold(0u8, |acc, (x, y)| acc | (x ^ y))
+            == 0
+}
+
+/// 校验局域网请求：通过后去掉 x-api-key（令牌不应转发给上游），失败时返回状态码与提示
+pub fn authorize(addr: &SocketAddr, headers: &mut HeaderMap) -> Result<(), (StatusCode, String)> {
+    let settings = crate::settings::get_settings().lan_share;
+    if !settings.enabled {
+        return Err((StatusCode::FORBIDDEN, t("proxy.lanShareDisabled")));
+    }
+    if !ip_allowed(&settings, &addr.ip()) {
+        tracing::warn!("拒绝来自 {} 的局域网请求：不在允许列表内", addr.ip());
+        return Err((StatusCode::FORBIDDEN, t("proxy.lanShareIpDenied")));
+    }
+    let authorized = !settings.token.is_empty()
+        && request_token(headers).is_some_and(|token| token_equals(token, &settings.token));
+    if !authorized {
+        tracing::warn!("拒绝来自 {} 的局域网请求：令牌无效", addr.ip());
+        return Err((StatusCode::UNAUTHORIZED, t("proxy.lanShareUnauthorized")));
+    }
+    headers.remove("x-api-key");
+    Ok(())
+}
+
+/// 局域网监听地址；未开启或未设置令牌时返回 None（令牌为必填项）
+pub fn listen_addr() -> Option<String> {
+    let settings = crate::settings::get_settings().lan_share;
+    if !settings.enabled {
+        return None;
+    }
+    if settings.token.is_empty() {
+        tracing::warn!("局域网共享未设置访问令牌，已跳过局域网监听");
+        return None;
+    }
+    let host = match settings.bind_address.parse::<IpAddr>() {
+        Ok(IpAddr::V6(ip)) => format!("[{}]", ip),
+        _ => settings.bind_address.clone(),
+    };
+    Some(format!("{}:{}", host, settings.port))
+}
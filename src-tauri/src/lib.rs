@@ -1,257 +1,97 @@
 mod app_config;
+mod app_lock;
 mod app_store;
+mod audit;
+mod auth_failure;
+mod auto_rank;
+#[cfg(desktop)]
+mod autostart;
+mod bulk;
+mod ca_certs;
+mod capabilities;
+mod capture;
 mod claude_mcp;
+mod claude_oauth;
 mod claude_plugin;
+mod cli;
+mod client_cert;
 mod codex_config;
+mod codex_single;
 mod commands;
+mod common_config;
 mod config;
+mod config_merge;
+mod config_sync;
+mod config_watcher;
+mod control;
+mod count_tokens;
+mod current_status;
+mod daily_usage;
+mod database;
+mod deeplink;
+mod diagnostics;
+mod dns;
+mod duplicates;
+mod endpoint_failover;
+mod endpoint_probe;
+mod env_mode;
+mod error;
+mod hooks;
+mod hotkeys;
+mod i18n;
 mod import_export;
+mod key_stats;
+mod lan_discovery;
+mod lan_share;
+mod live_config;
+mod logging;
+mod maintenance;
+mod managed;
 mod mcp;
+mod mcp_server;
 mod migration;
+mod model_discovery;
+mod network_watch;
+mod portable;
+mod project;
+mod protocol_detect;
 mod provider;
+mod provider_banner;
+mod provider_query;
+mod provider_wizard;
 mod proxy;
+mod proxy_supervisor;
+mod proxy_tls;
+mod rate_limit;
+mod redact;
+mod request_routes;
+mod routing;
+mod running_sessions;
+mod schema;
+mod self_check;
 mod settings;
+mod share;
+mod snippet;
 mod speedtest;
-mod usage_script;
+mod statusline;
 mod store;
+mod translate;
+mod tray;
+mod ttfb;
+mod upstream;
+mod usage_alert;
+mod usage_client;
+mod usage_report;
+mod usage_script;
+mod validation;
+mod vscode_targets;
+mod webhook;
 
 use store::AppState;
-use tauri::{
-    menu::{CheckMenuItem, Menu, MenuBuilder, MenuItem},
-    tray::{TrayIconBuilder, TrayIconEvent},
-};
-#[cfg(target_os = "macos")]
-use tauri::{ActivationPolicy, RunEvent};
+use tauri::tray::{TrayIconBuilder, TrayIconEvent};
+use tauri::RunEvent;
 use tauri::{Emitter, Manager};
 
-/// 创建动态托盘菜单
-fn create_tray_menu(
-    app: &tauri::AppHandle,
-    app_state: &AppState,
-) -> Result<Menu<tauri::Wry>, String> {
-    let config = app_state
-        .config
-        .lock()
-        .map_err(|e| format!("获取锁失败: {}", e))?;
-
-    let mut menu_builder = MenuBuilder::new(app);
-
-    // 顶部：打开主界面
-    let show_main_item = MenuItem::with_id(app, "show_main", "打开主界面", true, None::<&str>)
-        .map_err(|e| format!("创建打开主界面菜单失败: {}", e))?;
-    menu_builder = menu_builder.item(&show_main_item).separator();
-
-    // 直接添加所有供应商到主菜单（扁平化结构，更简单可靠）
-    if let Some(claude_manager) = config.get_manager(&crate::app_config::AppType::Claude) {
-        // 添加Claude标题（禁用状态，仅作为分组标识）
-        let claude_header =
-            MenuItem::with_id(app, "claude_header", "─── Claude ───", false, None::<&str>)
-                .map_err(|e| format!("创建Claude标题失败: {}", e))?;
-        menu_builder = menu_builder.item(&claude_header);
-
-        if !claude_manager.providers.is_empty() {
-            // Sort providers by sortIndex, then by createdAt, then by name
-            let mut sorted_providers: Vec<_> = claude_manager.providers.iter().collect();
-            sorted_providers.sort_by(|(_, a), (_, b)| {
-                // Priority 1: sortIndex
-                match (a.sort_index, b.sort_index) {
-                    (Some(idx_a), Some(idx_b)) => return idx_a.cmp(&idx_b),
-                    (Some(_), None) => return std::cmp::Ordering::Less,
-                    (None, Some(_)) => return std::cmp::Ordering::Greater,
-                    _ => {}
-                }
-                // Priority 2: createdAt
-                match (a.created_at, b.created_at) {
-                    (Some(time_a), Some(time_b)) => return time_a.cmp(&time_b),
-                    (Some(_), None) => return std::cmp::Ordering::Greater,
-                    (None, Some(_)) => return std::cmp::Ordering::Less,
-                    _ => {}
-                }
-                // Priority 3: name
-                a.name.cmp(&b.name)
-            });
-
-            for (id, provider) in sorted_providers {
-                let is_current = claude_manager.current == *id;
-                let item = CheckMenuItem::with_id(
-                    app,
-                    format!("claude_{}", id),
-                    &provider.name,
-                    true,
-                    is_current,
-                    None::<&str>,
-                )
-                .map_err(|e| format!("创建菜单项失败: {}", e))?;
-                menu_builder = menu_builder.item(&item);
-            }
-        } else {
-            // 没有供应商时显示提示
-            let empty_hint = MenuItem::with_id(
-                app,
-                "claude_empty",
-                "  (无供应商，请在主界面添加)",
-                false,
-                None::<&str>,
-            )
-            .map_err(|e| format!("创建Claude空提示失败: {}", e))?;
-            menu_builder = menu_builder.item(&empty_hint);
-        }
-    }
-
-    if let Some(codex_manager) = config.get_manager(&crate::app_config::AppType::Codex) {
-        // 添加Codex标题（禁用状态，仅作为分组标识）
-        let codex_header =
-            MenuItem::with_id(app, "codex_header", "─── Codex ───", false, None::<&str>)
-                .map_err(|e| format!("创建Codex标题失败: {}", e))?;
-        menu_builder = menu_builder.item(&codex_header);
-
-        if !codex_manager.providers.is_empty() {
-            // Sort providers by sortIndex, then by createdAt, then by name
-            let mut sorted_providers: Vec<_> = codex_manager.providers.iter().collect();
-            sorted_providers.sort_by(|(_, a), (_, b)| {
-                // Priority 1: sortIndex
-                match (a.sort_index, b.sort_index) {
-                    (Some(idx_a), Some(idx_b)) => return idx_a.cmp(&idx_b),
-                    (Some(_), None) => return std::cmp::Ordering::Less,
-                    (None, Some(_)) => return std::cmp::Ordering::Greater,
-                    _ => {}
-                }
-                // Priority 2: createdAt
-                match (a.created_at, b.created_at) {
-                    (Some(time_a), Some(time_b)) => return time_a.cmp(&time_b),
-                    (Some(_), None) => return std::cmp::Ordering::Greater,
-                    (None, Some(_)) => return std::cmp::Ordering::Less,
-                    _ => {}
-                }
-                // Priority 3: name
-                a.name.cmp(&b.name)
-            });
-
-            for (id, provider) in sorted_providers {
-                let is_current = codex_manager.current == *id;
-                let item = CheckMenuItem::with_id(
-                    app,
-                    format!("codex_{}", id),
-                    &provider.name,
-                    true,
-                    is_current,
-                    None::<&str>,
-                )
-                .map_err(|e| format!("创建菜单项失败: {}", e))?;
-                menu_builder = menu_builder.item(&item);
-            }
-        } else {
-            // 没有供应商时显示提示
-            let empty_hint = MenuItem::with_id(
-                app,
-                "codex_empty",
-                "  (无供应商，请在主界面添加)",
-                false,
-                None::<&str>,
-            )
-            .map_err(|e| format!("创建Codex空提示失败: {}", e))?;
-            menu_builder = menu_builder.item(&empty_hint);
-        }
-    }
-
-    // 分隔符和退出菜单
-    let quit_item = MenuItem::with_id(app, "quit", "退出", true, None::<&str>)
-        .map_err(|e| format!("创建退出菜单失败: {}", e))?;
-
-    menu_builder = menu_builder.separator().item(&quit_item);
-
-    menu_builder
-        .build()
-        .map_err(|e| format!("构建菜单失败: {}", e))
-}
-
-#[cfg(target_os = "macos")]
-fn apply_tray_policy(app: &tauri::AppHandle, dock_visible: bool) {
-    let desired_policy = if dock_visible {
-        ActivationPolicy::Regular
-    } else {
-        ActivationPolicy::Accessory
-    };
-
-    if let Err(err) = app.set_dock_visibility(dock_visible) {
-        log::warn!("设置 Dock 显示状态失败: {}", err);
-    }
-
-    if let Err(err) = app.set_activation_policy(desired_policy) {
-        log::warn!("设置激活策略失败: {}", err);
-    }
-}
-
-/// 处理托盘菜单事件
-fn handle_tray_menu_event(app: &tauri::AppHandle, event_id: &str) {
-    log::info!("处理托盘菜单事件: {}", event_id);
-
-    match event_id {
-        "show_main" => {
-            if let Some(window) = app.get_webview_window("main") {
-                #[cfg(target_os = "windows")]
-                {
-                    let _ = window.set_skip_taskbar(false);
-                }
-                let _ = window.unminimize();
-                let _ = window.show();
-                let _ = window.set_focus();
-                #[cfg(target_os = "macos")]
-                {
-                    apply_tray_policy(app, true);
-                }
-            }
-        }
-        "quit" => {
-            log::info!("退出应用");
-            app.exit(0);
-        }
-        id if id.starts_with("claude_") => {
-            let provider_id = id.strip_prefix("claude_").unwrap();
-            log::info!("切换到Claude供应商: {}", provider_id);
-
-            // 执行切换
-            let app_handle = app.clone();
-            let provider_id = provider_id.to_string();
-            tauri::async_runtime::spawn(async move {
-                if let Err(e) = switch_provider_internal(
-                    &app_handle,
-                    crate::app_config::AppType::Claude,
-                    provider_id,
-                )
-                .await
-                {
-                    log::error!("切换Claude供应商失败: {}", e);
-                }
-            });
-        }
-        id if id.starts_with("codex_") => {
-            let provider_id = id.strip_prefix("codex_").unwrap();
-            log::info!("切换到Codex供应商: {}", provider_id);
-
-            // 执行切换
-            let app_handle = app.clone();
-            let provider_id = provider_id.to_string();
-            tauri::async_runtime::spawn(async move {
-                if let Err(e) = switch_provider_internal(
-                    &app_handle,
-                    crate::app_config::AppType::Codex,
-                    provider_id,
-                )
-                .await
-                {
-                    log::error!("切换Codex供应商失败: {}", e);
-                }
-            });
-        }
-        _ => {
-            log::warn!("未处理的菜单事件: {}", event_id);
-        }
-    }
-}
-
-//
-
 /// 内部切换供应商函数
 async fn switch_provider_internal(
     app: &tauri::AppHandle,
@@ -273,12 +113,8 @@ async fn switch_provider_internal(
         .await?;
 
         // 切换成功后重新创建托盘菜单
-        if let Ok(new_menu) = create_tray_menu(app, app_state.inner()) {
-            if let Some(tray) = app.tray_by_id("main") {
-                if let Err(e) = tray.set_menu(Some(new_menu)) {
-                    log::error!("更新托盘菜单失败: {}", e);
-                }
-            }
+        if let Err(e) = tray::refresh_tray_menu(app) {
+            tracing::error!("更新托盘菜单失败: {}", e);
         }
 
         // 发射事件到前端，通知供应商已切换
@@ -287,40 +123,72 @@ async fn switch_provider_internal(
             "providerId": provider_id_clone
         });
         if let Err(e) = app.emit("provider-switched", event_data) {
-            log::error!("发射供应商切换事件失败: {}", e);
+            tracing::error!("发射供应商切换事件失败: {}", e);
         }
     }
     Ok(())
 }
 
-/// 更新托盘菜单的Tauri命令
-#[tauri::command]
-async fn update_tray_menu(
-    app: tauri::AppHandle,
-    state: tauri::State<'_, AppState>,
-) -> Result<bool, String> {
-    if let Ok(new_menu) = create_tray_menu(&app, state.inner()) {
-        if let Some(tray) = app.tray_by_id("main") {
-            tray.set_menu(Some(new_menu))
-                .map_err(|e| format!("更新托盘菜单失败: {}", e))?;
-            return Ok(true);
-        }
+/// 内部切换运行模式函数（托盘、快捷键共用）
+async fn toggle_operation_mode_internal(
+    app: &tauri::AppHandle,
+) -> Result<settings::OperationMode, String> {
+    app_lock::ensure_unlocked()?;
+    let app_state = app.try_state::<AppState>().ok_or("应用状态尚未就绪")?;
+    let mode = proxy::toggle_operation_mode(app_state.inner()).await?;
+    audit::record(
+        audit::AuditAction::ModeChanged,
+        None,
+        None,
+        Some(mode.as_str().to_string()),
+    );
+
+    if let Err(e) = tray::refresh_tray_menu(app) {
+        tracing::error!("更新托盘菜单失败: {}", e);
+    }
+
+    // 发射事件到前端，通知运行模式已变更
+    if let Err(e) = app.emit(
+        "operation-mode-changed",
+        serde_json::json!({ "operationMode": mode }),
+    ) {
+        tracing::error!("发射运行模式变更事件失败: {}", e);
     }
-    Ok(false)
+    Ok(mode)
 }
 
 #[cfg_attr(mobile, tauri::mobile_entry_point)]
 pub fn run() {
+    // `cc-switch mcp`：以 MCP stdio 服务运行，不启动界面
+    #[cfg(desktop)]
+    if mcp_server::is_mcp_invocation() {
+        mcp_server::run();
+        return;
+    }
+
+    // `cc-switch reapply [claude|codex]`：请求正在运行的应用重新写入当前配置后退出
+    #[cfg(desktop)]
+    if let Some(code) = control::run_reapply_cli() {
+        std::process::exit(code);
+    }
+
+    // `cc-switch completions <shell>` / `cc-switch prompt`：输出补全脚本或提示符片段后退出
+    #[cfg(desktop)]
+    if let Some(code) = cli::run() {
+        std::process::exit(code);
+    }
+
     let mut builder = tauri::Builder::default();
 
     #[cfg(any(target_os = "macos", target_os = "windows", target_os = "linux"))]
     {
-        builder = builder.plugin(tauri_plugin_single_instance::init(|app, _args, _cwd| {
-            if let Some(window) = app.get_webview_window("main") {
-                let _ = window.unminimize();
-                let _ = window.show();
-                let _ = window.set_focus();
-            }
+        // 必须最先注册：第二个进程在此处把参数转交给已运行的实例后直接退出，
+        // 不会继续初始化并重复绑定代理端口
+        builder = builder.plugin(tauri_plugin_single_instance::init(|app, args, _cwd| {
+            tracing::info!("检测到重复启动，激活已运行的实例");
+            tray::show_main_window(app);
+            // Windows/Linux 下点击深链接会以链接为参数启动新实例，转交给已运行的实例处理
+            deeplink::handle_urls(app, args.iter().skip(1));
         }));
     }
 
@@ -339,7 +207,7 @@ pub fn run() {
                     }
                     #[cfg(target_os = "macos")]
                     {
-                        apply_tray_policy(window.app_handle(), false);
+                        tray::apply_tray_policy(window.app_handle(), false);
                     }
                 } else {
                     window.app_handle().exit(0);
@@ -350,9 +218,21 @@ pub fn run() {
         .plugin(tauri_plugin_dialog::init())
         .plugin(tauri_plugin_opener::init())
         .plugin(tauri_plugin_store::Builder::new().build())
+        .plugin(tauri_plugin_notification::init())
         .setup(|app| {
             // 设置全局 AppHandle 以供 Store 使用
             app_store::set_app_handle(app.handle().clone());
+            // 初始化日志（写入应用配置目录下按天轮转的日志文件）
+            if let Err(e) = logging::init_logging() {
+                eprintln!("初始化日志失败: {}", e);
+            }
+            // 导入旧版审计日志文件并清理过期的审计记录
+            if let Err(e) = audit::import_legacy_log() {
+                tracing::warn!("导入旧版审计日志失败: {}", e);
+            }
+            if let Err(e) = audit::prune(crate::settings::get_settings().audit_retention_days) {
+                tracing::warn!("清理审计日志失败: {}", e);
+            }
             // 注册 Updater 插件（桌面端）
             #[cfg(desktop)]
             {
@@ -361,8 +241,24 @@ pub fn run() {
                     .plugin(tauri_plugin_updater::Builder::new().build())
                 {
                     // 若配置不完整（如缺少 pubkey），跳过 Updater 而不中断应用
-                    log::warn!("初始化 Updater 插件失败，已跳过：{}", e);
+                    tracing::warn!("初始化 Updater 插件失败，已跳过：{}", e);
                 }
+
+                // 注册全局快捷键插件，并按设置绑定快捷键
+                if let Err(e) = app
+                    .handle()
+                    .plugin(tauri_plugin_global_shortcut::Builder::new().build())
+                {
+                    tracing::warn!("初始化全局快捷键插件失败，已跳过：{}", e);
+                } else if let Err(e) =
+                    hotkeys::register_hotkeys(app.handle(), &crate::settings::get_settings().hotkeys)
+                {
+                    tracing::warn!("注册全局快捷键失败: {}", e);
+                }
+
+                // 注册开机自启插件；由自启拉起时按设置隐藏到托盘
+                autostart::init(app.handle());
+                autostart::hide_on_startup(app.handle());
             }
             #[cfg(target_os = "macos")]
             {
@@ -394,29 +290,30 @@ pub fn run() {
                 }
             }
 
-            // 初始化日志
-            if cfg!(debug_assertions) {
-                app.handle().plugin(
-                    tauri_plugin_log::Builder::default()
-                        .level(log::LevelFilter::Info)
-                        .build(),
-                )?;
-            }
+            // 迁移旧的 app_config_dir 配置到 Store（需先于加载配置，以便从正确目录读取）
+            let migrated_config_dir =
+                match app_store::migrate_app_config_dir_from_settings(app.handle()) {
+                    Ok(dir) => dir,
+                    Err(e) => {
+                        tracing::warn!("迁移 app_config_dir 失败: {}", e);
+                        None
+                    }
+                };
 
             // 初始化应用状态（仅创建一次，并在本函数末尾注入 manage）
-            let app_state = AppState::new();
-
-            // 迁移旧的 app_config_dir 配置到 Store
-            if let Err(e) = app_store::migrate_app_config_dir_from_settings(&app.handle()) {
-                log::warn!("迁移 app_config_dir 失败: {}", e);
-            }
+            let (app_state, config_warning) = AppState::load();
 
             // 首次启动迁移：扫描副本文件，合并到 config.json，并归档副本；旧 config.json 先归档
             {
                 let mut config_guard = app_state.config.lock().unwrap();
-                let migrated = migration::migrate_copies_into_config(&mut config_guard)?;
-                if migrated {
-                    log::info!("已将副本文件导入到 config.json，并完成归档");
+                if let Some(report) =
+                    migration::run_legacy_migrations(&mut config_guard, migrated_config_dir)?
+                {
+                    tracing::info!(
+                        "旧版布局迁移完成：导入 {} 个供应商，归档 {} 个文件",
+                        report.providers.len(),
+                        report.archived_files.len()
+                    );
                 }
                 // 确保两个 App 条目存在
                 config_guard.ensure_app(&app_config::AppType::Claude);
@@ -427,18 +324,18 @@ pub fn run() {
             let _ = app_state.save();
 
             // 创建动态托盘菜单
-            let menu = create_tray_menu(app.handle(), &app_state)?;
+            let menu = tray::create_tray_menu(app.handle(), &app_state)?;
 
             // 构建托盘
-            let mut tray_builder = TrayIconBuilder::with_id("main")
+            let mut tray_builder = TrayIconBuilder::with_id(tray::TRAY_ID)
                 .on_tray_icon_event(|_tray, event| match event {
                     // 左键点击已通过 show_menu_on_left_click(true) 打开菜单，这里不再额外处理
                     TrayIconEvent::Click { .. } => {}
-                    _ => log::debug!("unhandled event {event:?}"),
+                    _ => tracing::debug!("unhandled event {event:?}"),
                 })
                 .menu(&menu)
                 .on_menu_event(|app, event| {
-                    handle_tray_menu_event(app, &event.id.0);
+                    tray::handle_tray_menu_event(app, &event.id.0);
                 })
                 .show_menu_on_left_click(true);
 
@@ -449,6 +346,61 @@ pub fn run() {
             // 将同一个实例注入到全局状态，避免重复创建导致的不一致
             app.manage(app_state);
 
+            // 配置文件由更高版本写入时提示用户（原文件已备份，不做降级读取）
+            if let Some(message) = config_warning {
+                use tauri_plugin_dialog::{DialogExt, MessageDialogKind};
+                app.dialog()
+                    .message(message)
+                    .kind(MessageDialogKind::Warning)
+                    .show(|_| {});
+            }
+
+            // 核对 live 配置与记录的当前供应商是否一致
+            self_check::run_on_startup(app.handle());
+
+            // 按设置安装或移除 Claude Code 状态栏
+            statusline::sync(&app.state::<AppState>());
+
+            // 写入当前供应商状态文件，供提示符与脚本读取
+            current_status::sync(&app.state::<AppState>());
+
+            // 供应商健康状态变化时刷新托盘
+            tray::start_health_refresh(app.handle().clone());
+
+            // 监听 live 配置文件的外部修改
+            config_watcher::start_config_watcher(app.handle().clone());
+
+            // 其他进程修改配置后自动重新加载
+            config_sync::start_config_sync(app.handle().clone());
+
+            // 网络变化或睡眠唤醒后重置连接池并确认代理监听
+            network_watch::start_network_watch(app.handle().clone());
+
+            // 拉取团队托管配置并定期刷新
+            managed::start_managed_refresh(app.handle().clone());
+
+            // 按设置定时探测并重排供应商
+            auto_rank::start_auto_rank(app.handle().clone());
+
+            // 定期测速自定义端点，供自动选择端点使用
+            endpoint_probe::start_endpoint_probe(app.handle().clone());
+
+            // 定期写入每日用量汇总
+            daily_usage::start_daily_usage_flush();
+
+            // 供应商维护模式到期后自动恢复
+            maintenance::start_maintenance_expiry(app.handle().clone());
+
+            // 写入模式下按时间段规则自动切换供应商
+            routing::start_routing_auto_switch(app.handle().clone());
+
+            // 启动本地控制接口（供外部脚本切换供应商、查询状态）
+            control::start_control_server(app.handle().clone());
+
+            // 注册 ccswitch:// 协议，并处理以深链接启动时携带的参数
+            deeplink::register_scheme();
+            deeplink::handle_urls(app.handle(), std::env::args().skip(1));
+
             // 初始化代理服务器
             proxy::init_proxy_server();
 
@@ -457,17 +409,23 @@ pub fn run() {
             if settings.operation_mode == crate::settings::OperationMode::Proxy {
                 let app_state_for_proxy = app.state::<AppState>();
 
-                // 启动时没有通用配置，传入 None
-                if let Err(e) = proxy::switch_to_proxy_mode(app_state_for_proxy.inner(), None, None) {
-                    log::warn!("切换到代理模式失败: {}", e);
+                if let Err(e) = proxy::switch_to_proxy_mode(app_state_for_proxy.inner()) {
+                    tracing::warn!("切换到代理模式失败: {}", e);
                 }
 
                 let app_state_clone = app_state_for_proxy.inner().clone();
                 tauri::async_runtime::spawn(async move {
                     if let Err(e) = proxy::start_proxy_server(&app_state_clone).await {
-                        log::error!("启动代理服务器失败: {}", e);
+                        tracing::error!("启动代理服务器失败: {}", e);
                     }
                 });
+            } else if settings.operation_mode == crate::settings::OperationMode::Env {
+                // 环境变量模式：启动时按当前供应商刷新 env 脚本
+                let app_state_for_env = app.state::<AppState>();
+                let config = app_state_for_env.config.lock().unwrap();
+                if let Err(e) = env_mode::write_env_files(&config) {
+                    tracing::warn!("生成环境变量脚本失败: {}", e);
+                }
             }
             Ok(())
         })
@@ -478,6 +436,12 @@ pub fn run() {
             commands::update_provider,
             commands::delete_provider,
             commands::switch_provider,
+            commands::resolve_config_drift,
+            commands::reapply_current,
+            commands::sync_from_live,
+            commands::preview_switch,
+            commands::validate_provider_config,
+            commands::get_provider_schema,
             commands::import_default_config,
             commands::get_claude_config_status,
             commands::get_config_status,
@@ -486,6 +450,11 @@ pub fn run() {
             commands::open_config_folder,
             commands::pick_directory,
             commands::open_external,
+            commands::open_provider_dashboard,
+            commands::get_migration_report,
+            commands::acknowledge_migration_report,
+            commands::get_recent_logs,
+            diagnostics::export_diagnostics,
             commands::get_app_config_path,
             commands::open_app_config_folder,
             commands::read_live_provider_settings,
@@ -494,6 +463,13 @@ pub fn run() {
             commands::restart_app,
             commands::check_for_updates,
             commands::is_portable_mode,
+            commands::get_portable_data_dir,
+            commands::get_env_snippet,
+            project::get_projects,
+            project::add_project,
+            project::remove_project,
+            project::switch_project_provider,
+            project::clear_project_provider,
             commands::get_claude_plugin_status,
             commands::read_claude_plugin_config,
             commands::apply_claude_plugin_config,
@@ -506,6 +482,8 @@ pub fn run() {
             commands::validate_mcp_command,
             // usage query
             commands::query_provider_usage,
+            commands::test_usage_script,
+            commands::clear_usage_script_cookies,
             // New MCP via config.json (SSOT)
             commands::get_mcp_config,
             commands::upsert_mcp_server_in_config,
@@ -517,6 +495,17 @@ pub fn run() {
             commands::import_mcp_from_codex,
             // ours: endpoint speed test + custom endpoint management
             commands::test_api_endpoints,
+            endpoint_failover::test_provider_endpoints,
+            model_discovery::discover_provider_models,
+            model_discovery::discover_models,
+            protocol_detect::probe_endpoint,
+            provider_wizard::wizard_check_url,
+            provider_wizard::wizard_check_key,
+            provider_wizard::wizard_templates,
+            provider_wizard::wizard_preview,
+            ca_certs::get_ca_certificates,
+            ca_certs::reload_ca_certificates,
+            ca_certs::open_ca_certificates_folder,
             commands::get_custom_endpoints,
             commands::add_custom_endpoint,
             commands::remove_custom_endpoint,
@@ -525,17 +514,59 @@ pub fn run() {
             commands::get_app_config_dir_override,
             commands::set_app_config_dir_override,
             // provider sort order management
-            commands::update_providers_sort_order,
             // proxy mode management
             commands::toggle_proxy_provider,
+            commands::set_provider_enabled,
+            commands::set_provider_locked,
+            commands::set_provider_pinned,
+            maintenance::set_provider_maintenance,
+            bulk::bulk_set_proxy_enabled,
+            bulk::bulk_delete_providers,
+            bulk::bulk_set_category,
+            bulk::reorder_providers,
+            provider_query::query_providers,
+            redact::reveal_provider_secret,
+            app_lock::get_app_lock_status,
+            app_lock::unlock_app,
+            app_lock::lock_app,
+            app_lock::set_app_lock_passcode,
+            audit::get_audit_log,
+            managed::refresh_managed_providers,
+            auto_rank::auto_rank_providers,
+            lan_discovery::discover_shared_proxies,
+            lan_discovery::connect_shared_proxy,
+            capture::get_captured_requests,
+            capture::clear_captured_requests,
+            capture::replay_request,
+            key_stats::get_key_usage_stats,
+            ttfb::get_ttfb_stats,
+            daily_usage::get_daily_summary,
+            usage_report::export_usage_report,
+            common_config::get_common_fragments,
+            common_config::save_common_fragment,
+            common_config::delete_common_fragment,
+            common_config::preview_common_merge,
+            self_check::get_config_self_check,
+            self_check::repair_config_issue,
+            webhook::test_webhook,
+            hooks::get_hook_runs,
+            hooks::test_hook,
+            running_sessions::get_running_sessions,
+            config_sync::resolve_config_conflict,
             commands::handle_operation_mode_change,
-            commands::sync_proxy_common_config,
             // theirs: config import/export and dialogs
             import_export::export_config_to_file,
             import_export::import_config_from_file,
             import_export::save_file_dialog,
             import_export::open_file_dialog,
-            update_tray_menu,
+            tray::update_tray_menu,
+            deeplink::take_pending_deep_link,
+            share::encode_provider_share,
+            share::decode_provider_share,
+            snippet::parse_provider_snippet,
+            snippet::merge_provider_snippet,
+            duplicates::find_duplicate_provider,
+            claude_oauth::import_claude_oauth_account,
         ]);
 
     let app = builder
@@ -546,21 +577,23 @@ pub fn run() {
         #[cfg(target_os = "macos")]
         // macOS 在 Dock 图标被点击并重新激活应用时会触发 Reopen 事件，这里手动恢复主窗口
         if let RunEvent::Reopen { .. } = event {
-            if let Some(window) = app_handle.get_webview_window("main") {
-                #[cfg(target_os = "windows")]
-                {
-                    let _ = window.set_skip_taskbar(false);
-                }
-                let _ = window.unminimize();
-                let _ = window.show();
-                let _ = window.set_focus();
-                apply_tray_policy(app_handle, true);
-            }
+            tray::show_main_window(app_handle);
         }
 
-        #[cfg(not(target_os = "macos"))]
-        {
-            let _ = (app_handle, event);
+        // macOS 通过系统事件传入深链接
+        #[cfg(target_os = "macos")]
+        if let RunEvent::Opened { urls } = &event {
+            deeplink::handle_urls(app_handle, urls.iter().map(|u| u.as_str()));
+        }
+
+        // 退出前写入尚在去抖中的配置修改与每日用量计数
+        if let RunEvent::Exit = event {
+            if let Err(e) = app_handle.state::<AppState>().flush() {
+                tracing::error!("退出时保存配置失败: {}", e);
+            }
+            if let Err(e) = daily_usage::flush() {
+                tracing::error!("退出时写入每日用量汇总失败: {}", e);
+            }
         }
     });
-}
\ No newline at end of file
+}
@@ -0,0 +1,287 @@
+use serde::Serialize;
+use serde_json::Value;
+use std::path::{Path, PathBuf};
+
+use crate::app_config::{AppType, MultiAppConfig};
+use crate::codex_config::{get_codex_config_dir, write_codex_live_atomic_in};
+use crate::config::{claude_settings_path_in, get_claude_config_dir, read_json_file};
+use crate::provider::Provider;
+use crate::redact;
+
+/// 供应商的 live 配置目录：优先使用供应商自定义目录，否则为全局目录（含设置中的目录覆盖）
+pub fn live_dir(app_type: &AppType, provider: Option<&Provider>) -> PathBuf {
+    provider
+        .and_then(Provider::live_config_dir)
+        .unwrap_or_else(|| match app_type {
+            AppType::Claude => get_claude_config_dir(),
+            AppType::Codex => get_codex_config_dir(),
+        })
+}
+
+/// 当前供应商的 live 配置目录
+pub fn current_live_dir(config: &MultiAppConfig, app_type: &AppType) -> PathBuf {
+    let current = config
+        .get_manager(app_type)
+        .and_then(|manager| manager.providers.get(&manager.current));
+    live_dir(app_type, current)
+}
+
+/// 指定目录下的 live 配置文件路径列表
+pub fn live_paths(app_type: &AppType, dir: &Path) -> Vec<PathBuf> {
+    match app_type {
+        AppType::Claude => vec![claude_settings_path_in(dir)],
+        AppType::Codex => vec![dir.join("auth.json"), dir.join("config.toml")],
+    }
+}
+
+/// 读取指定目录下的 live 配置，结构与供应商 settings_config 一致
+/// - Claude: settings.json 内容
+/// - Codex: { "auth": auth.json, "config": config.toml 文本 }
+pub fn read_live_settings(app_type: &AppType, dir: &Path) -> Result<Value, String> {
+    match app_type {
+        AppType::Claude => {
+            let path = claude_settings_path_in(dir);
+            if !path.exists() {
+                return Err(format!("Claude 配置文件不存在: {}", path.display()));
+            }
+            read_json_file(&path)
+        }
+        AppType::Codex => {
+            let auth_path = dir.join("auth.json");
+            if !auth_path.exists() {
+                return Err(format!("Codex 配置文件不存在: {}", auth_path.display()));
+            }
+            let auth: Value = read_json_file(&auth_path)?;
+            let config_text =
+                crate::codex_config::read_config_text_from_path(&dir.join("config.toml"))?;
+            Ok(serde_json::json!({
+                "auth": auth,
+                "config": config_text,
+            }))
+        }
+    }
+}
+
+/// 将供应商 settings_config 写入指定目录下的 live 配置
+pub fn write_live_settings(
+    app_type: &AppType,
+    dir: &Path,
+    settings_config: &Value,
+) -> Result<(), String> {
+    match app_type {
+        AppType::Claude => crate::config::write_claude_settings_atomic_at(
+            &claude_settings_path_in(dir),
+            settings_config,
+        ),
+        AppType::Codex => {
+            let auth = settings_config
+                .get("auth")
+                .ok_or_else(|| "供应商缺少 auth 配置".to_string())?;
+            let config_text = settings_config.get("config").and_then(|v| v.as_str());
+            write_codex_live_atomic_in(dir, auth, config_text)
+        }
+    }
+}
+
+/// 判断 live 配置与供应商配置是否语义一致（Codex 的 TOML 按解析结果比较，忽略格式差异）
+pub fn live_matches(app_type: &AppType, expected: &Value, live: &Value) -> bool {
+    match app_type {
+        AppType::Claude => expected == live,
+        AppType::Codex => {
+            if expected.get("auth") != live.get("auth") {
+                return false;
+            }
+            let expected_text = expected
+                .get("config")
+                .and_then(|v| v.as_str())
+                .unwrap_or("");
+            let live_text = live.get("config").and_then(|v| v.as_str()).unwrap_or("");
+            match (
+                toml::from_str::<toml::Table>(expected_text),
+                toml::from_str::<toml::Table>(live_text),
+            ) {
+                (Ok(a), Ok(b)) => a == b,
+                _ => expected_text.trim() == live_text.trim(),
+            }
+        }
+    }
+}
+
+/// 单个配置键的变更
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct KeyChange {
+    /// 以点分隔的键路径，如 `env.ANTHROPIC_BASE_URL`、`config.model`
+    pub path: String,
+    /// added / removed / modified
+    pub kind: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub old_value: Option<Value>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub new_value: Option<Value>,
+}
+
+/// 单个 live 文件的前后内容
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct FileDiff {
+    pub path: String,
+    pub old_content: String,
+    pub new_content: String,
+    pub changed: bool,
+}
+
+/// 配置差异（旧 vs 新）
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ConfigDiff {
+    pub changed: bool,
+    pub changes: Vec<KeyChange>,
+    pub files: Vec<FileDiff>,
+}
+
+/// 递归比较两个 JSON 值，叶子节点（或类型不同的节点）记录为变更
+fn diff_values(prefix: &str, old: Option<&Value>, new: Option<&Value>, out: &mut Vec<KeyChange>) {
+    match (old, new) {
+        (Some(Value::Object(a)), Some(Value::Object(b))) => {
+            let mut keys: Vec<&String> = a.keys().chain(b.keys()).collect();
+            keys.sort();
+            keys.dedup();
+            for key in keys {
+                let path = if prefix.is_empty() {
+                    key.clone()
+                } else {
+                    format!("{}.{}", prefix, key)
+                };
+                diff_values(&path, a.get(key), b.get(key), out);
+            }
+        }
+        (Some(a), Some(b)) if a == b => {}
+        (None, None) => {}
+        (old, new) => {
+            let kind = match (old, new) {
+                (None, Some(_)) => "added",
+                (Some(_), None) => "removed",
+                _ => "modified",
+            };
+            out.push(KeyChange {
+                path: prefix.to_string(),
+                kind: kind.to_string(),
+                old_value: old.cloned(),
+                new_value: new.cloned(),
+            });
+        }
+    }
+}
+
+/// 将 Codex 的 config.toml 文本解析为 JSON 以便按键比较；无法解析时按原文本比较
+fn codex_config_as_json(settings: &Value) -> Option<Value> {
+    let text = settings
+        .get("config")
+        .and_then(|v| v.as_str())
+        .unwrap_or("");
+    match toml::from_str::<toml::Table>(text) {
+        Ok(table) => serde_json::to_value(table).ok(),
+        Err(_) => Some(Value::String(text.to_string())),
+    }
+}
+
+/// 生成 live 文件内容（与写入时的格式一致）
+fn render_files(app_type: &AppType, settings: Option<&Value>) -> Vec<String> {
+    let pretty = |v: Option<&Value>| {
+        v.and_then(|v| serde_json::to_string_pretty(v).ok())
+            .unwrap_or_default()
+    };
+    match app_type {
+        AppType::Claude => vec![pretty(settings)],
+        AppType::Codex => vec![
+            pretty(settings.and_then(|s| s.get("auth"))),
+            settings
+                .and_then(|s| s.get("config"))
+                .and_then(|v| v.as_str())
+                .unwrap_or("")
+                .to_string(),
+        ],
+    }
+}
+
+/// 计算两份 settings_config 的结构化差异（old 为 None 表示文件尚不存在），dir 为 live 配置目录。
+/// 差异按原值计算，返回给前端的键值与文件内容中的凭证字段均已脱敏（查看明文走 `reveal_provider_secret`）
+pub fn diff_settings(
+    app_type: &AppType,
+    dir: &Path,
+    old: Option<&Value>,
+    new: &Value,
+) -> ConfigDiff {
+    let mut changes = Vec::new();
+    match app_type {
+        AppType::Claude => diff_values("", old, Some(new), &mut changes),
+        AppType::Codex => {
+            diff_values(
+                "auth",
+                old.and_then(|o| o.get("auth")),
+                new.get("auth"),
+                &mut changes,
+            );
+            let old_config = old.and_then(codex_config_as_json);
+            let new_config = codex_config_as_json(new);
+            diff_values(
+                "config",
+                old_config.as_ref(),
+                new_config.as_ref(),
+                &mut changes,
+            );
+        }
+    }
+
+    for change in &mut changes {
+        for value in [&mut change.old_value, &mut change.new_value]
+            .into_iter()
+            .flatten()
+        {
+            redact::redact_at_path(app_type, &change.path, value);
+        }
+    }
+
+    let redacted_old = old.map(|o| redact::redact_settings(app_type, o));
+    let redacted_new = redact::redact_settings(app_type, new);
+    let files: Vec<FileDiff> = live_paths(app_type, dir)
+        .into_iter()
+        .zip(
+            render_files(app_type, old)
+                .into_iter()
+                .zip(render_files(app_type, Some(new))),
+        )
+        .zip(
+            render_files(app_type, redacted_old.as_ref())
+                .into_iter()
+                .zip(render_files(app_type, Some(&redacted_new))),
+        )
+        .map(
+            |((path, (raw_old, raw_new)), (old_content, new_content))| FileDiff {
+                path: path.display().to_string(),
+                changed: raw_old != raw_new,
+                old_content,
+                new_content,
+            },
+        )
+        .collect();
+
+    ConfigDiff {
+        changed: !changes.is_empty(),
+        changes,
+        files,
+    }
+}
+
+/// 切换供应商前的预览：当前 live 配置 -> 目标供应商配置
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SwitchPreview {
+    pub provider_id: String,
+    pub provider_name: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub current_provider_id: Option<String>,
+    #[serde(flatten)]
+    pub diff: ConfigDiff,
+}
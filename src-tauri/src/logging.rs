@@ -0,0 +1,100 @@
+use std::fs;
+use std::path::PathBuf;
+use std::sync::OnceLock;
+use tracing_appender::non_blocking::WorkerGuard;
+use tracing_appender::rolling::{RollingFileAppender, Rotation};
+use tracing_subscriber::filter::LevelFilter;
+use tracing_subscriber::layer::SubscriberExt;
+use tracing_subscriber::util::SubscriberInitExt;
+
+/// 日志文件名前缀，轮转后形如 `cc-switch.2025-01-01.log`
+const LOG_FILE_PREFIX: &str = "cc-switch";
+const LOG_FILE_SUFFIX: &str = "log";
+/// 最多保留的日志文件数量（按天轮转，即最近 7 天）
+const MAX_LOG_FILES: usize = 7;
+/// 日志查看器默认/最大返回行数
+const DEFAULT_RECENT_LINES: usize = 500;
+const MAX_RECENT_LINES: usize = 5000;
+
+/// 非阻塞写入器的后台线程守卫，需在进程生命周期内保持存活，否则日志会丢失
+static LOG_GUARD: OnceLock<WorkerGuard> = OnceLock::new();
+
+/// 获取日志目录（位于应用配置目录下）
+pub fn get_log_dir() -> PathBuf {
+    crate::config::get_app_config_dir().join("logs")
+}
+
+/// 初始化 tracing：写入按天轮转的日志文件，调试构建下同时输出到终端
+pub fn init_logging() -> Result<(), String> {
+    let log_dir = get_log_dir();
+    fs::create_dir_all(&log_dir).map_err(|e| format!("创建日志目录失败: {}", e))?;
+
+    let appender = RollingFileAppender::builder()
+        .rotation(Rotation::DAILY)
+        .filename_prefix(LOG_FILE_PREFIX)
+        .filename_suffix(LOG_FILE_SUFFIX)
+        .max_log_files(MAX_LOG_FILES)
+        .build(&log_dir)
+        .map_err(|e| format!("创建日志文件失败: {}", e))?;
+    let (writer, guard) = tracing_appender::non_blocking(appender);
+    let _ = LOG_GUARD.set(guard);
+
+    let file_layer = tracing_subscriber::fmt::layer()
+        .with_writer(writer)
+        .with_ansi(false)
+        .with_target(false);
+    let stdout_layer =
+        cfg!(debug_assertions).then(|| tracing_subscriber::fmt::layer().with_target(false));
+
+    // try_init 同时桥接 `log` 记录（来自 Tauri 及其它依赖）
+    tracing_subscriber::registry()
+        .with(LevelFilter::INFO)
+        .with(file_layer)
+        .with(stdout_layer)
+        .try_init()
+        .map_err(|e| format!("注册日志订阅器失败: {}", e))
+}
+
+/// 按时间从新到旧列出日志文件（文件名包含日期，字典序即时间序）
+fn list_log_files() -> Vec<PathBuf> {
+    let Ok(entries) = fs::read_dir(get_log_dir()) else {
+        return Vec::new();
+    };
+    let mut files: Vec<PathBuf> = entries
+        .filter_map(|entry| entry.ok().map(|e| e.path()))
+        .filter(|path| {
+            path.file_name()
+                .and_then(|name| name.to_str())
+                .is_some_and(|name| {
+                    name.starts_with(LOG_FILE_PREFIX) && name.ends_with(LOG_FILE_SUFFIX)
+                })
+        })
+        .collect();
+    files.sort();
+    files.reverse();
+    files
+}
+
+/// 读取最近的日志行（按时间正序），必要时跨越多个轮转文件
+pub fn read_recent_logs(lines: Option<usize>) -> Result<Vec<String>, String> {
+    let limit = lines
+        .unwrap_or(DEFAULT_RECENT_LINES)
+        .clamp(1, MAX_RECENT_LINES);
+
+    let mut collected: Vec<String> = Vec::with_capacity(limit);
+    for path in list_log_files() {
+        let content = fs::read_to_string(&path)
+            .map_err(|e| format!("读取日志文件失败: {}: {}", path.display(), e))?;
+        for line in content.lines().rev() {
+            if collected.len() >= limit {
+                break;
+            }
+            collected.push(line.to_string());
+        }
+        if collected.len() >= limit {
+            break;
+        }
+    }
+    collected.reverse();
+    Ok(collected)
+}
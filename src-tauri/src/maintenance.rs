@@ -0,0 +1,129 @@
+//! 供应商维护模式：中转公告维护窗口时临时静音某个供应商 N 分钟，
+//! 期间不参与代理故障转移、时间段自动切换、自动排序与快捷键轮换，到期后自动恢复。
+
+#![allow(non_snake_case)]
+
+use std::time::Duration;
+
+use tauri::{Manager, State};
+
+use crate::app_config::AppType;
+use crate::error::CcSwitchError;
+use crate::i18n::t_args;
+use crate::store::AppState;
+
+/// 到期检查间隔
+const CHECK_INTERVAL: Duration = Duration::from_secs(30);
+
+/// 最长维护时长（分钟）
+const MAX_MINUTES: u64 = 7 * 24 * 60;
+
+/// 让供应商进入维护模式 `minutes` 分钟（0 表示立即结束维护），返回维护结束时间（毫秒时间戳）
+#[tauri::command]
+pub async fn set_provider_maintenance(
+    handle: tauri::AppHandle,
+    state: State<'_, AppState>,
+    provider_id: String,
+    minutes: u64,
+    app_type: Option<AppType>,
+    app: Option<String>,
+    appType: Option<String>,
+) -> Result<Option<i64>, CcSwitchError> {
+    let app_type = app_type
+        .or_else(|| app.as_deref().map(|s| s.into()))
+        .or_else(|| appType.as_deref().map(|s| s.into()))
+        .unwrap_or(AppType::Claude);
+    if minutes > MAX_MINUTES {
+        return Err(t_args(
+            "errors.maintenanceTooLong",
+            &[("hours", &(MAX_MINUTES / 60))],
+        )
+        .into());
+    }
+    let until =
+        (minutes > 0).then(|| chrono::Utc::now().timestamp_millis() + minutes as i64 * 60_000);
+
+    let name = {
+        let mut config = state
+            .config
+            .lock()
+            .map_err(|e| t_args("errors.lockFailed", &[("error", &e)]))?;
+        let manager = config
+            .get_manager_mut(&app_type)
+            .ok_or_else(|| t_args("errors.appTypeNotFound", &[("app", &app_type.as_str())]))?;
+        let provider = manager
+            .providers
+            .get_mut(&provider_id)
+            .ok_or_else(|| t_args("errors.providerNotFound", &[("id", &provider_id)]))?;
+        provider.maintenance_until = until;
+        provider.name.clone()
+    };
+    state.save_now()?;
+    match until {
+        Some(_) => tracing::info!("供应商 {} 进入维护模式 {} 分钟", name, minutes),
+        None => tracing::info!("供应商 {} 已结束维护模式", name),
+    }
+
+    if let Err(e) = crate::tray::refresh_tray_menu(&handle) {
+        tracing::warn!("刷新托盘菜单失败: {}", e);
+    }
+    Ok(until)
+}
+
+/// 清除已到期的维护标记，返回各应用中恢复的供应商 ID
+fn clear_expired(state: &AppState) -> Vec<(AppType, Vec<String>)> {
+    let now = chrono::Utc::now().timestamp_millis();
+    let Ok(mut config) = state.config.lock() else {
+        return Vec::new();
+    };
+    let mut restored = Vec::new();
+    for app_type in [AppType::Claude, AppType::Codex] {
+        let Some(manager) = config.get_manager_mut(&app_type) else {
+            continue;
+        };
+        let ids: Vec<String> = manager
+            .providers
+            .values_mut()
+            .filter(|p| p.maintenance_until.is_some_and(|until| until <= now))
+            .map(|p| {
+                p.maintenance_until = None;
+                p.id.clone()
+            })
+            .collect();
+        if !ids.is_empty() {
+            restored.push((app_type, ids));
+        }
+    }
+    restored
+}
+
+/// 后台定期检查维护到期的供应商并自动恢复
+pub fn start_maintenance_expiry(app: tauri::AppHandle) {
+    tauri::async_runtime::spawn(async move {
+        loop {
+            tokio::time::sleep(CHECK_INTERVAL).await;
+            let Some(state) = app.try_state::<AppState>() else {
+                continue;
+            };
+            let restored = clear_expired(&state);
+            if restored.is_empty() {
+                continue;
+            }
+            if let Err(e) = state.save() {
+                tracing::warn!("保存维护模式到期状态失败: {}", e);
+                continue;
+            }
+            for (app_type, ids) in restored {
+                tracing::info!(
+                    "{} 供应商维护到期，已自动恢复: {}",
+                    app_type.as_str(),
+                    ids.join(", ")
+                );
+                crate::bulk::emit_providers_updated(&app, &app_type, "maintenance-ended", ids);
+            }
+            if let Err(e) = crate::tray::refresh_tray_menu(&app) {
+                tracing::warn!("刷新托盘菜单失败: {}", e);
+            }
+        }
+    });
+}
@@ -0,0 +1,318 @@
+//! 团队托管配置：从管理员提供的地址（可带访问令牌）拉取供应商列表，以只读、锁定的条目合并到本地。
+//! 启动时拉取一次，之后按设置的间隔定期刷新；远端移除的条目随之删除（当前使用中的除外），
+//! 当前供应商的配置变化时重新写入 live 配置，便于团队集中分发与轮换中转地址。
+//!
+//! 远端返回 JSON：`{ "claude": [Provider...], "codex": [Provider...] }`，
+//! 条目字段与本地供应商一致（id、name、settingsConfig，可选 websiteUrl、category）。
+
+use std::collections::HashSet;
+use std::time::Duration;
+
+use serde::{Deserialize, Serialize};
+use tauri::Manager;
+
+use crate::app_config::{AppType, MultiAppConfig};
+use crate::audit::AuditAction;
+use crate::error::CcSwitchError;
+use crate::i18n::{t, t_args};
+use crate::provider::{Provider, ProviderMeta};
+use crate::settings::{ManagedSource, OperationMode};
+use crate::store::AppState;
+
+/// 托管供应商的 ID 前缀，避免与本地供应商冲突
+const ID_PREFIX: &str = "managed-";
+
+const FETCH_TIMEOUT: Duration = Duration::from_secs(30);
+
+/// 最短刷新间隔（分钟）
+const MIN_REFRESH_MINUTES: u32 = 5;
+
+/// 未配置托管源时重新检查设置的间隔
+const IDLE_CHECK_INTERVAL: Duration = Duration::from_secs(60);
+
+#[derive(Debug, Default, Deserialize)]
+struct ManagedPayload {
+    #[serde(default)]
+    claude: Vec<Provider>,
+    #[serde(default)]
+    codex: Vec<Provider>,
+}
+
+/// 单个应用的同步结果
+#[derive(Debug, Clone, Default, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ManagedSyncResult {
+    pub added: Vec<String>,
+    pub updated: Vec<String>,
+    pub removed: Vec<String>,
+    /// 无效或与本地冲突而跳过的条目
+    pub skipped: Vec<String>,
+    #[serde(skip)]
+    current_changed: bool,
+}
+
+impl ManagedSyncResult {
+    fn changed_ids(&self) -> Vec<String> {
+        self.added
+            .iter()
+            .chain(&self.updated)
+            .chain(&self.removed)
+            .cloned()
+            .collect()
+    }
+
+    fn is_empty(&self) -> bool {
+        self.added.is_empty() && self.updated.is_empty() && self.removed.is_empty()
+    }
+}
+
+/// 各应用的同步结果
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct ManagedSyncReport {
+    pub claude: ManagedSyncResult,
+    pub codex: ManagedSyncResult,
+}
+
+/// 令牌只允许通过 HTTPS 发送（本机地址除外，便于调试）
+fn validate_url(raw: &str) -> Result<reqwest::Url, String> {
+    let url = reqwest::Url::parse(raw.trim())
+        .map_err(|e| t_args("errors.managedUrlInvalid", &[("error", &e)]))?;
+    let local = matches!(url.host_str(), Some("localhost" | "127.0.0.1" | "::1"));
+    if url.scheme() != "https" && !(url.scheme() == "http" && local) {
+        return Err(t("errors.managedUrlInsecure"));
+    }
+    Ok(url)
+}
+
+async fn fetch(source: &ManagedSource) -> Result<ManagedPayload, String> {
+    let url = validate_url(&source.url)?;
+    let client = crate::usage_client::shared_client()?;
+    let mut request = client.get(url).timeout(FETCH_TIMEOUT);
+    if let Some(token) = source.token.as_deref().filter(|t| !t.is_empty()) {
+        request = request.bearer_auth(token);
+    }
+    let response = request
+        .send()
+        .await
+        .map_err(|e| t_args("errors.managedFetchFailed", &[("error", &e)]))?;
+    let status = response.status();
+    if !status.is_success() {
+        return Err(t_args("errors.managedFetchFailed", &[("error", &status)]));
+    }
+    response
+        .json::<ManagedPayload>()
+        .await
+        .map_err(|e| t_args("errors.managedPayloadInvalid", &[("error", &e)]))
+}
+
+fn managed_id(id: &str) -> String {
+    if id.starts_with(ID_PREFIX) {
+        id.to_string()
+    } else {
+        format!("{}{}", ID_PREFIX, id)
+    }
+}
+
+/// 将远端条目合并到单个应用：新增、更新（保留本地排序、启用状态与用量脚本等元数据）并移除远端已删除的条目
+fn merge_app(
+    config: &mut MultiAppConfig,
+    app_type: &AppType,
+    remote: Vec<Provider>,
+) -> Result<ManagedSyncResult, String> {
+    let manager = config
+        .get_manager_mut(app_type)
+        .ok_or_else(|| t_args("errors.appTypeNotFound", &[("app", &app_type.as_str())]))?;
+    let mut result = ManagedSyncResult::default();
+    let mut seen = HashSet::new();
+
+    for entry in remote {
+        let id = managed_id(&entry.id);
+        if !seen.insert(id.clone()) {
+            continue;
+        }
+        if let Err(e) = crate::validation::ensure_valid(app_type, &entry.settings_config) {
+            tracing::warn!("托管供应商 {} 配置无效，已跳过: {}", entry.name, e);
+            result.skipped.push(id);
+            continue;
+        }
+
+        match manager.providers.get_mut(&id) {
+            Some(existing) if !existing.is_managed() => {
+                tracing::warn!("托管供应商 {} 与本地供应商 ID 冲突，已跳过", entry.name);
+                result.skipped.push(id);
+            }
+            Some(existing) => {
+                let changed = existing.name != entry.name
+                    || existing.settings_config != entry.settings_config
+                    || existing.website_url != entry.website_url
+                    || existing.category != entry.category;
+                if changed {
+                    existing.name = entry.name;
+                    existing.settings_config = entry.settings_config;
+                    existing.website_url = entry.website_url;
+                    existing.category = entry.category;
+                    if matches!(app_type, AppType::Codex) {
+                        crate::codex_config::sync_credential_kind(existing);
+                    }
+                    result.current_changed |= manager.current == id;
+                    result.updated.push(id);
+                }
+                // 托管条目始终保持锁定
+                existing.locked = Some(true);
+            }
+            None => {
+                let mut provider = Provider::with_id(
+                    id.clone(),
+                    entry.name,
+                    entry.settings_config,
+                    entry.website_url,
+                );
+                provider.category = entry.category;
+                provider.created_at = Some(chrono::Utc::now().timestamp_millis());
+                provider.locked = Some(true);
+                provider.meta = Some(ProviderMeta {
+                    managed: true,
+                    ..Default::default()
+                });
+                if matches!(app_type, AppType::Codex) {
+                    crate::codex_config::sync_credential_kind(&mut provider);
+                }
+                manager.providers.insert(id.clone(), provider);
+                result.added.push(id);
+            }
+        }
+    }
+
+    let stale: Vec<String> = manager
+        .providers
+        .values()
+        .filter(|p| p.is_managed() && !seen.contains(&p.id))
+        .map(|p| p.id.clone())
+        .collect();
+    for id in stale {
+        // 当前使用中的条目保留，切换到其他供应商后的下一次刷新再移除
+        if manager.current == id {
+            tracing::warn!("托管供应商 {} 已从远端移除，但仍在使用中，暂不删除", id);
+            continue;
+        }
+        manager.providers.remove(&id);
+        result.removed.push(id);
+    }
+    Ok(result)
+}
+
+/// 当前供应商的托管配置变化后，按运行模式重新应用（代理模式下由代理按内存配置转发，无需写入）
+fn reapply_current(config: &MultiAppConfig, app_type: &AppType) -> Result<(), String> {
+    match crate::settings::get_settings().operation_mode {
+        OperationMode::Proxy => Ok(()),
+        OperationMode::Env => crate::env_mode::write_env_files(config),
+        OperationMode::Write => {
+            let Some(manager) = config.get_manager(app_type) else {
+                return Ok(());
+            };
+            let Some(provider) = manager.providers.get(&manager.current) else {
+                return Ok(());
+            };
+            let dir = crate::live_config::live_dir(app_type, Some(provider));
+            let settings = crate::common_config::live_settings(app_type, manager, provider, &dir);
+            crate::live_config::write_live_settings(app_type, &dir, &settings)
+        }
+    }
+}
+
+/// 拉取托管配置并合并到本地；有变更时保存、刷新托盘并通知前端
+pub async fn sync(
+    app: &tauri::AppHandle,
+    source: &ManagedSource,
+) -> Result<ManagedSyncReport, String> {
+    let payload = fetch(source).await?;
+    let state = app
+        .try_state::<AppState>()
+        .ok_or_else(|| "应用状态尚未就绪".to_string())?;
+
+    let report = {
+        let mut config = state
+            .config
+            .lock()
+            .map_err(|e| t_args("errors.lockFailed", &[("error", &e)]))?;
+        let report = ManagedSyncReport {
+            claude: merge_app(&mut config, &AppType::Claude, payload.claude)?,
+            codex: merge_app(&mut config, &AppType::Codex, payload.codex)?,
+        };
+        for (app_type, result) in [
+            (AppType::Claude, &report.claude),
+            (AppType::Codex, &report.codex),
+        ] {
+            if result.current_changed {
+                if let Err(e) = reapply_current(&config, &app_type) {
+                    tracing::error!("应用托管供应商的新配置失败: {}", e);
+                }
+            }
+        }
+        report
+    };
+
+    if report.claude.is_empty() && report.codex.is_empty() {
+        return Ok(report);
+    }
+    state.save_now()?;
+    if let Err(e) = crate::tray::refresh_tray_menu(app) {
+        tracing::warn!("刷新托盘菜单失败: {}", e);
+    }
+    for (app_type, result) in [
+        (AppType::Claude, &report.claude),
+        (AppType::Codex, &report.codex),
+    ] {
+        if result.is_empty() {
+            continue;
+        }
+        tracing::info!(
+            "已同步 {} 托管供应商：新增 {}，更新 {}，移除 {}",
+            app_type.as_str(),
+            result.added.len(),
+            result.updated.len(),
+            result.removed.len()
+        );
+        crate::audit::record(
+            AuditAction::ManagedSync,
+            Some(&app_type),
+            None,
+            Some(format!(
+                "+{} ~{} -{}",
+                result.added.len(),
+                result.updated.len(),
+                result.removed.len()
+            )),
+        );
+        crate::bulk::emit_providers_updated(app, &app_type, "managed", result.changed_ids());
+    }
+    Ok(report)
+}
+
+/// 后台定期刷新：启动时立即拉取一次，之后按设置的间隔刷新（每轮重新读取设置）
+pub fn start_managed_refresh(app: tauri::AppHandle) {
+    tauri::async_runtime::spawn(async move {
+        loop {
+            let Some(source) = crate::settings::get_settings().managed_source else {
+                tokio::time::sleep(IDLE_CHECK_INTERVAL).await;
+                continue;
+            };
+            if let Err(e) = sync(&app, &source).await {
+                tracing::warn!("同步托管配置失败: {}", e);
+            }
+            let minutes = source.refresh_minutes.max(MIN_REFRESH_MINUTES);
+            tokio::time::sleep(Duration::from_secs(u64::from(minutes) * 60)).await;
+        }
+    });
+}
+
+/// 立即同步托管配置
+#[tauri::command]
+pub async fn refresh_managed_providers(
+    handle: tauri::AppHandle,
+) -> Result<ManagedSyncReport, CcSwitchError> {
+    let source = crate::settings::get_settings()
+        .managed_source
+        .ok_or_else(|| t("errors.managedSourceMissing"))?;
+    Ok(sync(&handle, &source).await?)
+}
@@ -121,7 +121,7 @@ fn normalize_server_keys(map: &mut HashMap<String, Value>) -> usize {
             continue;
         }
         if map.contains_key(&new_key) {
-            log::warn!(
+            tracing::warn!(
                 "MCP 条目 '{}' 的内部 id '{}' 与现有键冲突，回退为原键",
                 old_key,
                 new_key
@@ -145,7 +145,7 @@ fn normalize_server_keys(map: &mut HashMap<String, Value>) -> usize {
             if let Some(obj) = value.as_object_mut() {
                 obj.insert("id".into(), json!(new_key.clone()));
             }
-            log::info!("MCP 条目键名已自动修复: '{}' -> '{}'", old_key, new_key);
+            tracing::info!("MCP 条目键名已自动修复: '{}' -> '{}'", old_key, new_key);
             map.insert(new_key, value);
             change_count += 1;
         }
@@ -190,7 +190,7 @@ fn collect_enabled_servers(cfg: &McpConfig) -> HashMap<String, Value> {
                 out.insert(id.clone(), spec);
             }
             Err(err) => {
-                log::warn!("跳过无效的 MCP 条目 '{}': {}", id, err);
+                tracing::warn!("跳过无效的 MCP 条目 '{}': {}", id, err);
             }
         }
     }
@@ -205,7 +205,7 @@ pub fn get_servers_snapshot_for(
     let mut snapshot = config.mcp_for(app).servers.clone();
     snapshot.retain(|id, value| {
         let Some(obj) = value.as_object_mut() else {
-            log::warn!("跳过无效的 MCP 条目 '{}': 必须为 JSON 对象", id);
+            tracing::warn!("跳过无效的 MCP 条目 '{}': 必须为 JSON 对象", id);
             return false;
         };
 
@@ -214,7 +214,7 @@ pub fn get_servers_snapshot_for(
         match validate_mcp_entry(value) {
             Ok(()) => true,
             Err(err) => {
-                log::error!("config.json 中存在无效的 MCP 条目 '{}': {}", id, err);
+                tracing::error!("config.json 中存在无效的 MCP 条目 '{}': {}", id, err);
                 false
             }
         }
@@ -352,7 +352,7 @@ pub fn import_from_claude(config: &mut MultiAppConfig) -> Result<usize, String>
             Entry::Occupied(mut occ) => {
                 let value = occ.get_mut();
                 let Some(existing) = value.as_object_mut() else {
-                    log::warn!("MCP 条目 '{}' 不是 JSON 对象，覆盖为导入数据", id);
+                    tracing::warn!("MCP 条目 '{}' 不是 JSON 对象，覆盖为导入数据", id);
                     let mut obj = serde_json::Map::new();
                     obj.insert(String::from("id"), json!(id));
                     obj.insert(String::from("name"), json!(id));
@@ -373,12 +373,12 @@ pub fn import_from_claude(config: &mut MultiAppConfig) -> Result<usize, String>
                     modified = true;
                 }
                 if existing.get("server").is_none() {
-                    log::warn!("MCP 条目 '{}' 缺少 server 字段，覆盖为导入数据", id);
+                    tracing::warn!("MCP 条目 '{}' 缺少 server 字段，覆盖为导入数据", id);
                     existing.insert(String::from("server"), spec.clone());
                     modified = true;
                 }
                 if existing.get("id").is_none() {
-                    log::warn!("MCP 条目 '{}' 缺少 id 字段，自动填充", id);
+                    tracing::warn!("MCP 条目 '{}' 缺少 id 字段，自动填充", id);
                     existing.insert(String::from("id"), json!(id));
                     modified = true;
                 }
@@ -477,7 +477,7 @@ pub fn import_from_codex(config: &mut MultiAppConfig) -> Result<usize, String> {
 
             // 校验
             if let Err(e) = validate_server_spec(&spec_v) {
-                log::warn!("跳过无效 Codex MCP 项 '{}': {}", id, e);
+                tracing::warn!("跳过无效 Codex MCP 项 '{}': {}", id, e);
                 continue;
             }
 
@@ -500,7 +500,7 @@ pub fn import_from_codex(config: &mut MultiAppConfig) -> Result<usize, String> {
                 Entry::Occupied(mut occ) => {
                     let value = occ.get_mut();
                     let Some(existing) = value.as_object_mut() else {
-                        log::warn!("MCP 条目 '{}' 不是 JSON 对象，覆盖为导入数据", id);
+                        tracing::warn!("MCP 条目 '{}' 不是 JSON 对象，覆盖为导入数据", id);
                         let mut obj = serde_json::Map::new();
                         obj.insert(String::from("id"), json!(id));
                         obj.insert(String::from("name"), json!(id));
@@ -521,12 +521,12 @@ pub fn import_from_codex(config: &mut MultiAppConfig) -> Result<usize, String> {
                         modified = true;
                     }
                     if existing.get("server").is_none() {
-                        log::warn!("MCP 条目 '{}' 缺少 server 字段，覆盖为导入数据", id);
+                        tracing::warn!("MCP 条目 '{}' 缺少 server 字段，覆盖为导入数据", id);
                         existing.insert(String::from("server"), spec_v.clone());
                         modified = true;
                     }
                     if existing.get("id").is_none() {
-                        log::warn!("MCP 条目 '{}' 缺少 id 字段，自动填充", id);
+                        tracing::warn!("MCP 条目 '{}' 缺少 id 字段，自动填充", id);
                         existing.insert(String::from("id"), json!(id));
                         modified = true;
                     }
@@ -571,8 +571,10 @@ pub fn sync_enabled_to_codex(config: &MultiAppConfig) -> Result<(), String> {
     // 1) 收集启用项（Codex 维度）
     let enabled = collect_enabled_servers(&config.mcp.codex);
 
-    // 2) 读取现有 config.toml 并解析为 Table（允许空文件）
-    let base_text = crate::codex_config::read_and_validate_codex_config_text()?;
+    // 2) 读取当前供应商 live 目录下的 config.toml 并解析为 Table（允许空文件）
+    let path = crate::live_config::current_live_dir(config, &crate::app_config::AppType::Codex)
+        .join("config.toml");
+    let base_text = crate::codex_config::read_and_validate_config_from_path(&path)?;
     let mut root: TomlTable = if base_text.trim().is_empty() {
         TomlTable::new()
     } else {
@@ -725,7 +727,6 @@ pub fn sync_enabled_to_codex(config: &MultiAppConfig) -> Result<(), String> {
     // 4) 序列化并写回 config.toml（仅改 TOML，不触碰 auth.json）
     let new_text = toml::to_string(&TomlValue::Table(root))
         .map_err(|e| format!("序列化 config.toml 失败: {}", e))?;
-    let path = crate::codex_config::get_codex_config_path();
     crate::config::write_text_file(&path, &new_text)?;
 
     Ok(())
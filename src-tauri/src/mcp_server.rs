@@ -0,0 +1,247 @@
+//! MCP（Model Context Protocol）stdio 服务：以 `cc-switch mcp` 子命令启动，
+//! 供 Claude Code 等客户端调用 cc-switch 的操作（列出/切换供应商、查询用量）。
+//! 本进程不直接读写配置，所有操作都转发到正在运行的应用的本地控制接口，
+//! 与 GUI 共用同一套切换逻辑。
+//!
+//! 注册示例：`claude mcp add cc-switch -- /path/to/cc-switch mcp`
+
+use serde_json::{json, Value};
+use std::io::{BufRead, Write};
+
+/// 启动 MCP 服务的子命令名
+pub const MCP_SUBCOMMAND: &str = "mcp";
+
+/// 未指定客户端版本时使用的协议版本
+const DEFAULT_PROTOCOL_VERSION: &str = "2024-11-05";
+
+const METHOD_NOT_FOUND: i64 = -32601;
+const INVALID_PARAMS: i64 = -32602;
+const PARSE_ERROR: i64 = -32700;
+
+/// 命令行中是否包含 MCP 子命令
+pub fn is_mcp_invocation() -> bool {
+    std::env::args().skip(1).any(|arg| arg == MCP_SUBCOMMAND)
+}
+
+/// 运行 MCP 服务：逐行读取 stdin 的 JSON-RPC 消息，响应写入 stdout，stdin 关闭时退出
+pub fn run() {
+    let runtime = match tokio::runtime::Builder::new_current_thread()
+        .enable_all()
+        .build()
+    {
+        Ok(runtime) => runtime,
+        Err(e) => {
+            eprintln!("创建异步运行时失败: {}", e);
+            return;
+        }
+    };
+
+    let stdin = std::io::stdin();
+    let mut stdout = std::io::stdout();
+    for line in stdin.lock().lines() {
+        let Ok(line) = line else {
+            break;
+        };
+        if line.trim().is_empty() {
+            continue;
+        }
+        let Some(response) = runtime.block_on(handle_message(line.trim())) else {
+            continue;
+        };
+        if writeln!(stdout, "{}", response).is_err() || stdout.flush().is_err() {
+            break;
+        }
+    }
+}
+
+fn error_response(id: Value, code: i64, message: impl Into<String>) -> Value {
+    json!({
+        "jsonrpc": "2.0",
+        "id": id,
+        "error": { "code": code, "message": message.into() }
+    })
+}
+
+/// 处理单条消息；通知（无 id）不返回响应
+async fn handle_message(line: &str) -> Option<Value> {
+    let message: Value = match serde_json::from_str(line) {
+        Ok(message) => message,
+        Err(e) => {
+            return Some(error_response(
+                Value::Null,
+                PARSE_ERROR,
+                format!("解析请求失败: {}", e),
+            ))
+        }
+    };
+    let id = message.get("id").cloned().unwrap_or(Value::Null);
+    if id.is_null() {
+        return None;
+    }
+    let method = message.get("method").and_then(|m| m.as_str()).unwrap_or("");
+    let params = message.get("params").cloned().unwrap_or(Value::Null);
+
+    let result = match method {
+        "initialize" => initialize(&params),
+        "ping" => json!({}),
+        "tools/list" => json!({ "tools": tool_definitions() }),
+        "tools/call" => {
+            let Some(name) = params.get("name").and_then(|n| n.as_str()) else {
+                return Some(error_response(id, INVALID_PARAMS, "缺少工具名称"));
+            };
+            let arguments = params.get("arguments").cloned().unwrap_or(json!({}));
+            match call_tool(name, &arguments).await {
+                Ok(text) => tool_result(text, false),
+                Err(e) => tool_result(e, true),
+            }
+        }
+        _ => {
+            return Some(error_response(
+                id,
+                METHOD_NOT_FOUND,
+                format!("未知方法: {}", method),
+            ))
+        }
+    };
+    Some(json!({ "jsonrpc": "2.0", "id": id, "result": result }))
+}
+
+fn initialize(params: &Value) -> Value {
+    let protocol_version = params
+        .get("protocolVersion")
+        .and_then(|v| v.as_str())
+        .unwrap_or(DEFAULT_PROTOCOL_VERSION);
+    json!({
+        "protocolVersion": protocol_version,
+        "capabilities": { "tools": {} },
+        "serverInfo": {
+            "name": "cc-switch",
+            "version": env!("CARGO_PKG_VERSION"),
+        },
+    })
+}
+
+fn tool_result(text: String, is_error: bool) -> Value {
+    json!({
+        "content": [{ "type": "text", "text": text }],
+        "isError": is_error,
+    })
+}
+
+/// 工具描述（面向模型，使用英文）
+fn tool_definitions() -> Value {
+    let app_property = json!({
+        "type": "string",
+        "enum": ["claude", "codex"],
+        "description": "Target app, defaults to claude",
+    });
+    json!([
+        {
+            "name": "list_providers",
+            "description": "List the providers configured in cc-switch and mark the current one.",
+            "inputSchema": {
+                "type": "object",
+                "properties": { "app": app_property },
+            },
+        },
+        {
+            "name": "switch_provider",
+            "description": "Switch the active provider in cc-switch. Accepts a provider id or its display name (case-insensitive). New sessions pick up the change.",
+            "inputSchema": {
+                "type": "object",
+                "properties": {
+                    "app": app_property,
+                    "provider": {
+                        "type": "string",
+                        "description": "Provider id or name, as returned by list_providers",
+                    },
+                },
+                "required": ["provider"],
+            },
+        },
+        {
+            "name": "get_usage",
+            "description": "Query balance/usage of a provider via its configured usage script. Defaults to the current provider.",
+            "inputSchema": {
+                "type": "object",
+                "properties": {
+                    "app": app_property,
+                    "provider": {
+                        "type": "string",
+                        "description": "Provider id or name, defaults to the current provider",
+                    },
+                },
+            },
+        },
+    ])
+}
+
+fn app_of(arguments: &Value) -> String {
+    arguments
+        .get("app")
+        .and_then(|v| v.as_str())
+        .unwrap_or("claude")
+        .to_string()
+}
+
+/// 按 ID 或名称（忽略大小写）查找供应商 ID
+async fn resolve_provider_id(app: &str, provider: &str) -> Result<String, String> {
+    let providers = crate::control::call("listProviders", json!({ "app": app })).await?;
+    let list = providers.as_array().cloned().unwrap_or_default();
+    let field = |p: &Value, key: &str| {
+        p.get(key)
+            .and_then(|v| v.as_str())
+            .unwrap_or_default()
+            .to_string()
+    };
+    let found = list
+        .iter()
+        .find(|p| field(p, "id") == provider)
+        .or_else(|| {
+            list.iter()
+                .find(|p| field(p, "name").eq_ignore_ascii_case(provider.trim()))
+        });
+    match found {
+        Some(p) => Ok(field(p, "id")),
+        None => {
+            let names: Vec<String> = list.iter().map(|p| field(p, "name")).collect();
+            Err(format!(
+                "未找到供应商 \"{}\"，可用: {}",
+                provider,
+                names.join(", ")
+            ))
+        }
+    }
+}
+
+fn pretty(value: &Value) -> String {
+    serde_json::to_string_pretty(value).unwrap_or_else(|_| value.to_string())
+}
+
+async fn call_tool(name: &str, arguments: &Value) -> Result<String, String> {
+    let app = app_of(arguments);
+    let provider = arguments.get("provider").and_then(|v| v.as_str());
+    match name {
+        "list_providers" => {
+            let providers = crate::control::call("listProviders", json!({ "app": app })).await?;
+            Ok(pretty(&providers))
+        }
+        "switch_provider" => {
+            let provider = provider.ok_or("缺少 provider 参数")?;
+            let id = resolve_provider_id(&app, provider).await?;
+            let current =
+                crate::control::call("switchProvider", json!({ "app": app, "providerId": id }))
+                    .await?;
+            Ok(pretty(&current))
+        }
+        "get_usage" => {
+            let mut params = json!({ "app": app });
+            if let Some(provider) = provider {
+                params["providerId"] = json!(resolve_provider_id(&app, provider).await?);
+            }
+            let usage = crate::control::call("getUsage", params).await?;
+            Ok(pretty(&usage))
+        }
+        _ => Err(format!("未知工具: {}", name)),
+    }
+}
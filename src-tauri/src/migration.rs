@@ -2,10 +2,131 @@ use crate::app_config::{AppType, MultiAppConfig};
 use crate::config::{
     archive_file, delete_file, get_app_config_dir, get_app_config_path, get_claude_config_dir,
 };
+use serde::{Deserialize, Serialize};
 use serde_json::Value;
 use std::collections::{HashMap, HashSet};
 use std::fs;
 use std::path::PathBuf;
+use std::sync::Mutex;
+
+/// 迁移报告中的单个供应商条目
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct MigratedProvider {
+    pub app: String,
+    pub id: String,
+    pub name: String,
+    /// 来源文件（live 配置或旧版副本文件）
+    pub source: String,
+    /// true 表示合并到了已存在的同名同密钥供应商
+    pub merged: bool,
+}
+
+/// 旧版布局迁移报告（供首次启动向导展示）
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct MigrationReport {
+    /// 生成时间（毫秒时间戳）
+    pub migrated_at: i64,
+    #[serde(default)]
+    pub providers: Vec<MigratedProvider>,
+    /// 已归档的旧文件（归档后原文件会被删除）
+    #[serde(default)]
+    pub archived_files: Vec<String>,
+    /// 迁移阶段去重移除的供应商数量
+    #[serde(default)]
+    pub deduplicated: usize,
+    /// 从旧 settings.json 迁移到 Store 的 app_config_dir
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub app_config_dir: Option<String>,
+    /// v1 格式 config.json 的备份路径
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub legacy_config_backup: Option<String>,
+    /// 用户是否已在向导中确认
+    #[serde(default)]
+    pub acknowledged: bool,
+}
+
+impl MigrationReport {
+    pub fn is_empty(&self) -> bool {
+        self.providers.is_empty()
+            && self.archived_files.is_empty()
+            && self.app_config_dir.is_none()
+            && self.legacy_config_backup.is_none()
+    }
+
+    fn record_provider(
+        &mut self,
+        app: &AppType,
+        id: &str,
+        name: &str,
+        source: String,
+        merged: bool,
+    ) {
+        self.providers.push(MigratedProvider {
+            app: app.as_str().to_string(),
+            id: id.to_string(),
+            name: name.to_string(),
+            source,
+            merged,
+        });
+    }
+
+    fn record_archived(&mut self, path: &std::path::Path) {
+        self.archived_files.push(path.display().to_string());
+    }
+}
+
+/// 加载配置时若发生 v1 → v2 迁移，在此记录备份路径，稍后并入迁移报告
+static LEGACY_CONFIG_BACKUP: Mutex<Option<String>> = Mutex::new(None);
+
+pub fn record_legacy_config_backup(path: &std::path::Path) {
+    if let Ok(mut guard) = LEGACY_CONFIG_BACKUP.lock() {
+        *guard = Some(path.display().to_string());
+    }
+}
+
+fn get_report_path() -> PathBuf {
+    get_app_config_dir().join("migration-report.json")
+}
+
+/// 读取最近一次迁移报告
+pub fn load_report() -> Option<MigrationReport> {
+    let path = get_report_path();
+    if !path.exists() {
+        return None;
+    }
+    crate::config::read_json_file(&path)
+        .map_err(|e| tracing::warn!("读取迁移报告失败: {}", e))
+        .ok()
+}
+
+/// 保存迁移报告
+pub fn save_report(report: &MigrationReport) -> Result<(), String> {
+    crate::config::write_json_file(&get_report_path(), report)
+}
+
+/// 启动时执行旧版布局迁移：导入副本文件、汇总 app_config_dir 与 v1 配置迁移结果，并生成报告
+pub fn run_legacy_migrations(
+    config: &mut MultiAppConfig,
+    app_config_dir: Option<String>,
+) -> Result<Option<MigrationReport>, String> {
+    let mut report = MigrationReport {
+        app_config_dir,
+        legacy_config_backup: LEGACY_CONFIG_BACKUP.lock().ok().and_then(|mut g| g.take()),
+        ..Default::default()
+    };
+    let migrated = migrate_copies_into_config(config, &mut report)?;
+    if migrated {
+        tracing::info!("已将副本文件导入到 config.json，并完成归档");
+    }
+    if report.is_empty() {
+        return Ok(None);
+    }
+    report.migrated_at = chrono::Utc::now().timestamp_millis();
+    save_report(&report)?;
+    Ok(Some(report))
+}
 
 fn now_ts() -> u64 {
     std::time::SystemTime::now()
@@ -126,7 +247,7 @@ fn scan_codex_copies() -> Vec<(String, Option<PathBuf>, Option<PathBuf>, Value)>
                     match crate::codex_config::read_and_validate_config_from_path(cfgp) {
                         Ok(s) => s,
                         Err(e) => {
-                            log::warn!("跳过无效 Codex config-{}.toml: {}", name, e);
+                            tracing::warn!("跳过无效 Codex config-{}.toml: {}", name, e);
                             String::new()
                         }
                     }
@@ -144,7 +265,10 @@ fn scan_codex_copies() -> Vec<(String, Option<PathBuf>, Option<PathBuf>, Value)>
     items
 }
 
-pub fn migrate_copies_into_config(config: &mut MultiAppConfig) -> Result<bool, String> {
+pub fn migrate_copies_into_config(
+    config: &mut MultiAppConfig,
+    report: &mut MigrationReport,
+) -> Result<bool, String> {
     // 如果已迁移过则跳过；若目录不存在则先创建，避免新装用户写入标记时失败
     let marker = get_marker_path();
     if let Some(parent) = marker.parent() {
@@ -170,13 +294,13 @@ pub fn migrate_copies_into_config(config: &mut MultiAppConfig) -> Result<bool, S
     }
 
     // 读取 live：Claude（settings.json / claude.json）
+    let claude_settings_path = crate::config::get_claude_settings_path();
     let live_claude: Option<(String, Value)> = {
-        let settings_path = crate::config::get_claude_settings_path();
-        if settings_path.exists() {
-            match crate::config::read_json_file::<Value>(&settings_path) {
+        if claude_settings_path.exists() {
+            match crate::config::read_json_file::<Value>(&claude_settings_path) {
                 Ok(val) => Some(("default".to_string(), val)),
                 Err(e) => {
-                    log::warn!("读取 Claude live 配置失败: {}", e);
+                    tracing::warn!("读取 Claude live 配置失败: {}", e);
                     None
                 }
             }
@@ -203,8 +327,15 @@ pub fn migrate_copies_into_config(config: &mut MultiAppConfig) -> Result<bool, S
         });
         if let Some(exist_id) = exist_id {
             if let Some(prov) = manager.providers.get_mut(&exist_id) {
-                log::info!("合并到已存在 Claude 供应商 '{}' (by name+key)", name);
+                tracing::info!("合并到已存在 Claude 供应商 '{}' (by name+key)", name);
                 prov.settings_config = value.clone();
+                report.record_provider(
+                    &AppType::Claude,
+                    &exist_id,
+                    name,
+                    claude_settings_path.display().to_string(),
+                    true,
+                );
                 live_claude_id = Some(exist_id);
             }
         } else {
@@ -213,6 +344,13 @@ pub fn migrate_copies_into_config(config: &mut MultiAppConfig) -> Result<bool, S
             let provider =
                 crate::provider::Provider::with_id(id.clone(), name.clone(), value.clone(), None);
             manager.providers.insert(provider.id.clone(), provider);
+            report.record_provider(
+                &AppType::Claude,
+                &id,
+                name,
+                claude_settings_path.display().to_string(),
+                false,
+            );
             live_claude_id = Some(id);
         }
     }
@@ -228,12 +366,19 @@ pub fn migrate_copies_into_config(config: &mut MultiAppConfig) -> Result<bool, S
         });
         if let Some(exist_id) = exist_id {
             if let Some(prov) = manager.providers.get_mut(&exist_id) {
-                log::info!(
+                tracing::info!(
                     "覆盖 Claude 供应商 '{}' 来自 {} (by name+key)",
                     name,
                     path.display()
                 );
                 prov.settings_config = value.clone();
+                report.record_provider(
+                    &AppType::Claude,
+                    &exist_id,
+                    name,
+                    path.display().to_string(),
+                    true,
+                );
             }
         } else {
             let id = next_unique_id(&ids, name);
@@ -241,19 +386,26 @@ pub fn migrate_copies_into_config(config: &mut MultiAppConfig) -> Result<bool, S
             let provider =
                 crate::provider::Provider::with_id(id.clone(), name.clone(), value.clone(), None);
             manager.providers.insert(provider.id.clone(), provider);
+            report.record_provider(
+                &AppType::Claude,
+                &id,
+                name,
+                path.display().to_string(),
+                false,
+            );
         }
     }
 
     // 读取 live：Codex（auth.json 必需，config.toml 可空）
+    let codex_auth_path = crate::codex_config::get_codex_auth_path();
     let live_codex: Option<(String, Value)> = {
-        let auth_path = crate::codex_config::get_codex_auth_path();
-        if auth_path.exists() {
-            match crate::config::read_json_file::<Value>(&auth_path) {
+        if codex_auth_path.exists() {
+            match crate::config::read_json_file::<Value>(&codex_auth_path) {
                 Ok(auth) => {
                     let cfg = match crate::codex_config::read_and_validate_codex_config_text() {
                         Ok(s) => s,
                         Err(e) => {
-                            log::warn!("读取/校验 Codex live config.toml 失败: {}", e);
+                            tracing::warn!("读取/校验 Codex live config.toml 失败: {}", e);
                             String::new()
                         }
                     };
@@ -263,7 +415,7 @@ pub fn migrate_copies_into_config(config: &mut MultiAppConfig) -> Result<bool, S
                     ))
                 }
                 Err(e) => {
-                    log::warn!("读取 Codex live auth.json 失败: {}", e);
+                    tracing::warn!("读取 Codex live auth.json 失败: {}", e);
                     None
                 }
             }
@@ -290,16 +442,32 @@ pub fn migrate_copies_into_config(config: &mut MultiAppConfig) -> Result<bool, S
         });
         if let Some(exist_id) = exist_id {
             if let Some(prov) = manager.providers.get_mut(&exist_id) {
-                log::info!("合并到已存在 Codex 供应商 '{}' (by name+key)", name);
+                tracing::info!("合并到已存在 Codex 供应商 '{}' (by name+key)", name);
                 prov.settings_config = value.clone();
+                crate::codex_config::sync_credential_kind(prov);
+                report.record_provider(
+                    &AppType::Codex,
+                    &exist_id,
+                    name,
+                    codex_auth_path.display().to_string(),
+                    true,
+                );
                 live_codex_id = Some(exist_id);
             }
         } else {
             let id = next_unique_id(&ids, name);
             ids.insert(id.clone());
-            let provider =
+            let mut provider =
                 crate::provider::Provider::with_id(id.clone(), name.clone(), value.clone(), None);
+            crate::codex_config::sync_credential_kind(&mut provider);
             manager.providers.insert(provider.id.clone(), provider);
+            report.record_provider(
+                &AppType::Codex,
+                &id,
+                name,
+                codex_auth_path.display().to_string(),
+                false,
+            );
             live_codex_id = Some(id);
         }
     }
@@ -315,20 +483,42 @@ pub fn migrate_copies_into_config(config: &mut MultiAppConfig) -> Result<bool, S
         });
         if let Some(exist_id) = exist_id {
             if let Some(prov) = manager.providers.get_mut(&exist_id) {
-                log::info!(
+                tracing::info!(
                     "覆盖 Codex 供应商 '{}' 来自 {:?}/{:?} (by name+key)",
                     name,
                     authp,
                     cfgp
                 );
                 prov.settings_config = value.clone();
+                crate::codex_config::sync_credential_kind(prov);
+                report.record_provider(
+                    &AppType::Codex,
+                    &exist_id,
+                    name,
+                    authp
+                        .as_ref()
+                        .map(|p| p.display().to_string())
+                        .unwrap_or_default(),
+                    true,
+                );
             }
         } else {
             let id = next_unique_id(&ids, name);
             ids.insert(id.clone());
-            let provider =
+            let mut provider =
                 crate::provider::Provider::with_id(id.clone(), name.clone(), value.clone(), None);
+            crate::codex_config::sync_credential_kind(&mut provider);
             manager.providers.insert(provider.id.clone(), provider);
+            report.record_provider(
+                &AppType::Codex,
+                &id,
+                name,
+                authp
+                    .as_ref()
+                    .map(|p| p.display().to_string())
+                    .unwrap_or_default(),
+                false,
+            );
         }
     }
 
@@ -355,6 +545,7 @@ pub fn migrate_copies_into_config(config: &mut MultiAppConfig) -> Result<bool, S
         match archive_file(ts, "claude", &p) {
             Ok(Some(_)) => {
                 let _ = delete_file(&p);
+                report.record_archived(&p);
             }
             _ => {
                 // 归档失败则不要删除原文件，保守处理
@@ -365,11 +556,13 @@ pub fn migrate_copies_into_config(config: &mut MultiAppConfig) -> Result<bool, S
         if let Some(ap) = ap {
             if let Ok(Some(_)) = archive_file(ts, "codex", &ap) {
                 let _ = delete_file(&ap);
+                report.record_archived(&ap);
             }
         }
         if let Some(cp) = cp {
             if let Ok(Some(_)) = archive_file(ts, "codex", &cp) {
                 let _ = delete_file(&cp);
+                report.record_archived(&cp);
             }
         }
     }
@@ -377,8 +570,9 @@ pub fn migrate_copies_into_config(config: &mut MultiAppConfig) -> Result<bool, S
     // 标记完成
     // 仅在迁移阶段执行一次全量去重（忽略大小写的名称 + API Key）
     let removed = dedupe_config(config);
+    report.deduplicated = removed;
     if removed > 0 {
-        log::info!("迁移阶段已去重重复供应商 {} 个", removed);
+        tracing::info!("迁移阶段已去重重复供应商 {} 个", removed);
     }
 
     fs::write(&marker, b"done").map_err(|e| format!("写入迁移标记失败: {}", e))?;
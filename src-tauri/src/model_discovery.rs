@@ -0,0 +1,170 @@
+//! 模型发现：本地模型服务（Ollama、LM Studio、llama.cpp 等）与多数中转都提供模型列表接口。
+//! 依次尝试 OpenAI 兼容的 `/v1/models` 与 Ollama 的 `/api/tags`，发现的模型保存在
+//! `meta.discovered_models` 中，供表单的模型输入提示使用。
+//! 请求经代理的上游连接器发出，主机映射、Unix 套接字与客户端证书同样生效。
+
+#![allow(non_snake_case)]
+
+use std::collections::BTreeSet;
+use std::time::Duration;
+
+use axum::body::Body;
+use axum::http::Request;
+use http_body_util::BodyExt;
+use serde_json::Value;
+use tauri::State;
+
+use crate::app_config::AppType;
+use crate::error::CcSwitchError;
+use crate::i18n::t_args;
+use crate::store::AppState;
+use crate::upstream::{ConnectorOptions, UpstreamClient};
+
+/// 单个接口的请求超时
+const DISCOVERY_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// 依次尝试的模型列表接口；地址以 `/v1` 结尾时去掉后再拼接
+fn candidate_urls(base_url: &str) -> Vec<String> {
+    let base = crate::endpoint_failover::normalize(base_url);
+    let root = base.strip_suffix("/v1").unwrap_or(&base);
+    vec![format!("{}/v1/models", root), format!("{}/api/tags", root)]
+}
+
+/// 从响应中提取模型名：OpenAI / Anthropic 格式为 `data[].id`，Ollama 为 `models[].name`
+pub(crate) fn parse_models(body: &Value) -> Vec<String> {
+    let from = |key: &str, field: &str| {
+        body.get(key)
+            .and_then(Value::as_array)
+            .map(|items| {
+                items
+                    .iter()
+                    .filter_map(|item| item.get(field)?.as_str())
+                    .map(str::to_string)
+                    .collect::<Vec<_>>()
+            })
+            .unwrap_or_default()
+    };
+    let mut models = from("data", "id");
+    models.extend(from("models", "name"));
+    models
+}
+
+async fn fetch(client: &UpstreamClient, url: &str, api_key: &str) -> Result<Vec<String>, String> {
+    let mut request = Request::get(url);
+    if !api_key.is_empty() {
+        request = request
+            .header("authorization", format!("Bearer {}", api_key))
+            .header("x-api-key", api_key)
+            .header("anthropic-version", "2023-06-01");
+    }
+    let request = request
+        .body(Body::empty())
+        .map_err(|e| format!("URL 无效: {}", e))?;
+    let response = tokio::time::timeout(DISCOVERY_TIMEOUT, client.request(request))
+        .await
+        .map_err(|_| "请求超时".to_string())?
+        .map_err(|e| e.to_string())?;
+    let status = response.status();
+    if !status.is_success() {
+        return Err(format!("HTTP {}", status));
+    }
+    let bytes = response
+        .into_body()
+        .collect()
+        .await
+        .map_err(|e| format!("读取响应失败: {}", e))?
+        .to_bytes();
+    let body: Value = serde_json::from_slice(&bytes).map_err(|e| format!("解析响应失败: {}", e))?;
+    Ok(parse_models(&body))
+}
+
+/// 查询可用模型（去重并排序）；所有接口都失败或没有返回模型时报错
+async fn discover(
+    client: &UpstreamClient,
+    base_url: &str,
+    api_key: &str,
+) -> Result<Vec<String>, String> {
+    let mut last_error = String::new();
+    for url in candidate_urls(base_url) {
+        match fetch(client, &url, api_key).await {
+            Ok(models) if !models.is_empty() => {
+                let models: BTreeSet<String> = models.into_iter().collect();
+                tracing::info!("从 {} 发现 {} 个模型", url, models.len());
+                return Ok(models.into_iter().collect());
+            }
+            Ok(_) => last_error = format!("{} 未返回任何模型", url),
+            Err(e) => {
+                tracing::debug!("查询模型列表 {} 失败: {}", url, e);
+                last_error = format!("{}: {}", url, e);
+            }
+        }
+    }
+    Err(t_args(
+        "errors.modelDiscoveryFailed",
+        &[("error", &last_error)],
+    ))
+}
+
+/// 发现供应商的可用模型，并保存到 `meta.discovered_models`
+#[tauri::command]
+pub async fn discover_provider_models(
+    state: State<'_, AppState>,
+    provider_id: String,
+    app_type: Option<AppType>,
+    app: Option<String>,
+    appType: Option<String>,
+) -> Result<Vec<String>, CcSwitchError> {
+    let app_type = app_type
+        .or_else(|| app.as_deref().map(|s| s.into()))
+        .or_else(|| appType.as_deref().map(|s| s.into()))
+        .unwrap_or(AppType::Claude);
+    let (client, api_key, base_url) = {
+        let config = state
+            .config
+            .lock()
+            .map_err(|e| t_args("errors.lockFailed", &[("error", &e)]))?;
+        let provider = config
+            .get_manager(&app_type)
+            .and_then(|manager| manager.providers.get(&provider_id))
+            .ok_or_else(|| t_args("errors.providerNotFound", &[("id", &provider_id)]))?;
+        let (api_key, base_url) = crate::commands::extract_credentials(provider, &app_type)?;
+        (crate::upstream::client_for(provider)?, api_key, base_url)
+    };
+
+    let models = discover(&client, &base_url, &api_key).await?;
+
+    {
+        let mut config = state
+            .config
+            .lock()
+            .map_err(|e| t_args("errors.lockFailed", &[("error", &e)]))?;
+        let Some(provider) = config
+            .get_manager_mut(&app_type)
+            .and_then(|manager| manager.providers.get_mut(&provider_id))
+        else {
+            return Ok(models);
+        };
+        let meta = provider.meta.get_or_insert_with(Default::default);
+        if meta.discovered_models == models {
+            return Ok(models);
+        }
+        meta.discovered_models = models.clone();
+    }
+    state.save_now()?;
+    Ok(models)
+}
+
+/// 按地址与 API Key 发现可用模型（新建供应商时使用，结果不保存）
+#[tauri::command]
+pub async fn discover_models(
+    base_url: String,
+    api_key: Option<String>,
+) -> Result<Vec<String>, CcSwitchError> {
+    let client = crate::upstream::client(&ConnectorOptions::default())?;
+    Ok(discover(
+        &client,
+        &base_url,
+        api_key.as_deref().unwrap_or_default().trim(),
+    )
+    .await?)
+}
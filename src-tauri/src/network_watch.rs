@@ -0,0 +1,89 @@
+//! 网络变化与睡眠唤醒感知：定期比较本机网络地址，并通过时钟跳变判断系统是否刚从睡眠中恢复。
+//! 检测到变化后丢弃上游连接池中已失效的 keep-alive 连接、重置并重新探测供应商健康状态、
+//! 确认代理监听仍然可用，避免笔记本唤醒后的第一批请求集中失败。
+
+use std::collections::BTreeSet;
+use std::time::{Duration, Instant, SystemTime};
+
+use tauri::Manager;
+
+use crate::app_config::AppType;
+use crate::store::AppState;
+
+/// 检查间隔
+const CHECK_INTERVAL: Duration = Duration::from_secs(10);
+
+/// 实际经过时间超出检查间隔达到该值时视为刚从睡眠中恢复
+const RESUME_THRESHOLD: Duration = Duration::from_secs(30);
+
+/// 本机非回环网络地址，作为网络环境的指纹
+fn network_fingerprint() -> BTreeSet<String> {
+    match if_addrs::get_if_addrs() {
+        Ok(interfaces) => interfaces
+            .into_iter()
+            .filter(|iface| !iface.is_loopback())
+            .map(|iface| format!("{}:{}", iface.name, iface.ip()))
+            .collect(),
+        Err(e) => {
+            tracing::debug!("读取网络接口失败: {}", e);
+            BTreeSet::new()
+        }
+    }
+}
+
+/// 启动后台检测任务
+pub fn start_network_watch(app: tauri::AppHandle) {
+    tauri::async_runtime::spawn(async move {
+        let mut fingerprint = network_fingerprint();
+        loop {
+            let wall_before = SystemTime::now();
+            let mono_before = Instant::now();
+            tokio::time::sleep(CHECK_INTERVAL).await;
+
+            // 部分平台的单调时钟在睡眠期间不计时，同时比较墙上时钟
+            let wall_elapsed = wall_before.elapsed().unwrap_or_default();
+            let elapsed = mono_before.elapsed().max(wall_elapsed);
+            let resumed = elapsed > CHECK_INTERVAL + RESUME_THRESHOLD;
+
+            let current = network_fingerprint();
+            let network_changed = current != fingerprint;
+            fingerprint = current;
+
+            if !resumed && !network_changed {
+                continue;
+            }
+            if resumed {
+                tracing::info!("检测到系统从睡眠中恢复（约 {} 秒）", elapsed.as_secs());
+            }
+            if network_changed {
+                tracing::info!("检测到网络环境变化");
+            }
+            recover(&app).await;
+        }
+    });
+}
+
+/// 重置连接池与健康状态，确认代理监听，并按设置重新探测供应商
+async fn recover(app: &tauri::AppHandle) {
+    crate::proxy::reset_upstream_pool();
+    crate::proxy::reset_provider_health();
+    if let Err(e) = crate::tray::refresh_tray_menu(app) {
+        tracing::warn!("刷新托盘菜单失败: {}", e);
+    }
+
+    let Some(state) = app.try_state::<AppState>() else {
+        return;
+    };
+    if let Err(e) = crate::proxy::verify_proxy_listener(&state).await {
+        tracing::error!("恢复代理监听失败: {}", e);
+    }
+
+    // 开启了自动排序时立即重新探测，使故障转移顺序反映新的网络状况
+    if crate::settings::get_settings().auto_rank.enabled {
+        for app_type in [AppType::Claude, AppType::Codex] {
+            if let Err(e) = crate::auto_rank::rank(app, &state, &app_type).await {
+                tracing::warn!("重新探测 {} 供应商失败: {}", app_type.as_str(), e);
+            }
+        }
+    }
+}
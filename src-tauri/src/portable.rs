@@ -0,0 +1,33 @@
+use std::path::PathBuf;
+use std::sync::OnceLock;
+
+/// 可执行文件同目录下存在任一标记文件即启用便携模式（portable.ini 为旧版绿色版标记）
+const PORTABLE_MARKERS: [&str; 2] = ["portable.marker", "portable.ini"];
+/// 命令行启用便携模式
+const PORTABLE_FLAG: &str = "--portable";
+/// 便携模式下的数据目录名（位于可执行文件旁）
+const PORTABLE_DATA_DIR: &str = "cc-switch-data";
+
+static PORTABLE_DIR: OnceLock<Option<PathBuf>> = OnceLock::new();
+
+fn detect_portable_dir() -> Option<PathBuf> {
+    let exe_dir = std::env::current_exe().ok()?.parent()?.to_path_buf();
+    let by_flag = std::env::args().skip(1).any(|arg| arg == PORTABLE_FLAG);
+    let by_marker = PORTABLE_MARKERS
+        .iter()
+        .any(|marker| exe_dir.join(marker).is_file());
+    if !by_flag && !by_marker {
+        return None;
+    }
+    Some(exe_dir.join(PORTABLE_DATA_DIR))
+}
+
+/// 便携模式的数据目录（cc-switch.db、settings.json、Store、日志与备份均存放于此）；
+/// 未启用便携模式时返回 None。进程内只检测一次
+pub fn portable_data_dir() -> Option<PathBuf> {
+    PORTABLE_DIR.get_or_init(detect_portable_dir).clone()
+}
+
+pub fn is_portable() -> bool {
+    portable_data_dir().is_some()
+}
@@ -0,0 +1,254 @@
+use serde::{Deserialize, Serialize};
+use serde_json::{Map, Value};
+use std::path::{Path, PathBuf};
+use tauri::State;
+
+use crate::app_config::{AppType, MultiAppConfig};
+use crate::config::{delete_file, read_json_file, write_json_file};
+use crate::error::CcSwitchError;
+use crate::i18n::t_args;
+use crate::store::AppState;
+
+/// 项目级绑定：切换时写入 `<项目>/.claude/settings.local.json`，全局配置保持不变
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ProjectBinding {
+    /// 项目根目录
+    pub path: String,
+    /// 当前固定到该项目的 Claude 供应商
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub provider_id: Option<String>,
+    /// 由 cc-switch 写入的键（`env.ANTHROPIC_BASE_URL` 或顶层键名），解除绑定或换绑时据此清理，
+    /// 不触碰用户在 settings.local.json 中的其他配置
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub managed_keys: Vec<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub updated_at: Option<i64>,
+}
+
+/// 项目内的本地设置文件路径
+pub fn local_settings_path(project: &Path) -> PathBuf {
+    project.join(".claude").join("settings.local.json")
+}
+
+/// 供应商配置会写入的键：对象值按子键记录（如 `env.X`），其余记录顶层键
+fn managed_keys_of(settings: &Value) -> Vec<String> {
+    let Some(obj) = settings.as_object() else {
+        return Vec::new();
+    };
+    obj.iter()
+        .flat_map(|(key, value)| match value.as_object() {
+            Some(children) => children
+                .keys()
+                .map(|child| format!("{}.{}", key, child))
+                .collect::<Vec<_>>(),
+            None => vec![key.clone()],
+        })
+        .collect()
+}
+
+/// 移除之前写入的键；子对象被清空时一并移除
+fn strip_keys(local: &mut Map<String, Value>, keys: &[String]) {
+    for key in keys {
+        match key.split_once('.') {
+            Some((parent, child)) => {
+                if let Some(Value::Object(children)) = local.get_mut(parent) {
+                    children.remove(child);
+                    if children.is_empty() {
+                        local.remove(parent);
+                    }
+                }
+            }
+            None => {
+                local.remove(key);
+            }
+        }
+    }
+}
+
+/// 按两级合并写入供应商配置：对象值逐个子键覆盖，保留用户已有的其他子键
+fn merge_settings(local: &mut Map<String, Value>, settings: &Value) {
+    let Some(obj) = settings.as_object() else {
+        return;
+    };
+    for (key, value) in obj {
+        match value.as_object() {
+            Some(children) => {
+                let entry = local
+                    .entry(key.clone())
+                    .or_insert_with(|| Value::Object(Map::new()));
+                if !entry.is_object() {
+                    *entry = Value::Object(Map::new());
+                }
+                if let Some(target) = entry.as_object_mut() {
+                    for (child, child_value) in children {
+                        target.insert(child.clone(), child_value.clone());
+                    }
+                }
+            }
+            None => {
+                local.insert(key.clone(), value.clone());
+            }
+        }
+    }
+}
+
+fn read_local_settings(path: &Path) -> Result<Map<String, Value>, String> {
+    if !path.exists() {
+        return Ok(Map::new());
+    }
+    match read_json_file::<Value>(path)? {
+        Value::Object(map) => Ok(map),
+        _ => Err(format!("{} 不是 JSON 对象", path.display())),
+    }
+}
+
+/// 更新项目的 settings.local.json：先清理旧的托管键，再写入新配置；结果为空时删除文件
+fn write_local_settings(
+    project: &Path,
+    previous_keys: &[String],
+    settings: Option<&Value>,
+) -> Result<(), String> {
+    let path = local_settings_path(project);
+    let mut local = read_local_settings(&path)?;
+    strip_keys(&mut local, previous_keys);
+    if let Some(settings) = settings {
+        merge_settings(&mut local, settings);
+    }
+    if local.is_empty() {
+        delete_file(&path)
+    } else {
+        write_json_file(&path, &Value::Object(local))
+    }
+}
+
+fn normalize_project_path(path: &str) -> Result<String, String> {
+    let resolved = crate::settings::resolve_override_path(path.trim());
+    if !resolved.is_dir() {
+        return Err(t_args(
+            "errors.projectDirNotFound",
+            &[("path", &resolved.display())],
+        ));
+    }
+    Ok(resolved.to_string_lossy().to_string())
+}
+
+fn find_project<'a>(
+    config: &'a mut MultiAppConfig,
+    path: &str,
+) -> Result<&'a mut ProjectBinding, String> {
+    config
+        .projects
+        .iter_mut()
+        .find(|p| p.path == path)
+        .ok_or_else(|| t_args("errors.projectNotFound", &[("path", &path)]))
+}
+
+/// 获取已记住的项目列表
+#[tauri::command]
+pub async fn get_projects(
+    state: State<'_, AppState>,
+) -> Result<Vec<ProjectBinding>, CcSwitchError> {
+    let config = state
+        .config
+        .lock()
+        .map_err(|e| t_args("errors.lockFailed", &[("error", &e)]))?;
+    Ok(config.projects.clone())
+}
+
+/// 记住一个项目目录（已存在时忽略）
+#[tauri::command]
+pub async fn add_project(
+    state: State<'_, AppState>,
+    path: String,
+) -> Result<Vec<ProjectBinding>, CcSwitchError> {
+    let path = normalize_project_path(&path)?;
+    let projects = {
+        let mut config = state
+            .config
+            .lock()
+            .map_err(|e| t_args("errors.lockFailed", &[("error", &e)]))?;
+        if !config.projects.iter().any(|p| p.path == path) {
+            config.projects.push(ProjectBinding {
+                path,
+                provider_id: None,
+                managed_keys: Vec::new(),
+                updated_at: None,
+            });
+        }
+        config.projects.clone()
+    };
+    state.save_now()?;
+    Ok(projects)
+}
+
+/// 从列表中移除项目（不修改项目中的文件，需要时先调用 clear_project_provider）
+#[tauri::command]
+pub async fn remove_project(
+    state: State<'_, AppState>,
+    path: String,
+) -> Result<Vec<ProjectBinding>, CcSwitchError> {
+    let projects = {
+        let mut config = state
+            .config
+            .lock()
+            .map_err(|e| t_args("errors.lockFailed", &[("error", &e)]))?;
+        config.projects.retain(|p| p.path != path);
+        config.projects.clone()
+    };
+    state.save_now()?;
+    Ok(projects)
+}
+
+/// 将 Claude 供应商固定到项目：写入 `<项目>/.claude/settings.local.json`
+#[tauri::command]
+pub async fn switch_project_provider(
+    state: State<'_, AppState>,
+    path: String,
+    provider_id: String,
+) -> Result<ProjectBinding, CcSwitchError> {
+    let binding = {
+        let mut config = state
+            .config
+            .lock()
+            .map_err(|e| t_args("errors.lockFailed", &[("error", &e)]))?;
+        let settings = config
+            .get_manager(&AppType::Claude)
+            .and_then(|m| m.providers.get(&provider_id))
+            .map(|p| p.settings_config.clone())
+            .ok_or_else(|| t_args("errors.providerNotFound", &[("id", &provider_id)]))?;
+        crate::validation::ensure_valid(&AppType::Claude, &settings)?;
+
+        let project = find_project(&mut config, &path)?;
+        write_local_settings(Path::new(&path), &project.managed_keys, Some(&settings))?;
+        project.provider_id = Some(provider_id);
+        project.managed_keys = managed_keys_of(&settings);
+        project.updated_at = Some(chrono::Utc::now().timestamp_millis());
+        project.clone()
+    };
+    state.save_now()?;
+    tracing::info!("已将供应商写入项目本地配置: {}", binding.path);
+    Ok(binding)
+}
+
+/// 解除项目的供应商绑定：清理此前写入 settings.local.json 的键，保留用户的其他配置
+#[tauri::command]
+pub async fn clear_project_provider(
+    state: State<'_, AppState>,
+    path: String,
+) -> Result<ProjectBinding, CcSwitchError> {
+    let binding = {
+        let mut config = state
+            .config
+            .lock()
+            .map_err(|e| t_args("errors.lockFailed", &[("error", &e)]))?;
+        let project = find_project(&mut config, &path)?;
+        write_local_settings(Path::new(&path), &project.managed_keys, None)?;
+        project.provider_id = None;
+        project.managed_keys.clear();
+        project.updated_at = Some(chrono::Utc::now().timestamp_millis());
+        project.clone()
+    };
+    state.save_now()?;
+    Ok(binding)
+}
@@ -0,0 +1,295 @@
+//! 接口格式检测：新建供应商时根据 base_url 判断上游支持 Anthropic Messages、OpenAI Chat Completions
+//! 还是 OpenAI Responses 接口，并给出应用类型、wire_api 与配置模板建议。
+//! 检测请求使用不存在的模型名，只确认接口路由是否存在，不会产生计费；同时请求一个不存在的路径作为对照，
+//! 对任意路径都返回相同结果的网关（如先鉴权再路由）不会被误判为支持全部格式。
+
+use std::time::Duration;
+
+use axum::body::Body;
+use axum::http::{Method, Request, StatusCode};
+use http_body_util::BodyExt;
+use serde::Serialize;
+use serde_json::{json, Value};
+
+use crate::app_config::AppType;
+use crate::error::CcSwitchError;
+use crate::provider::ApiFormat;
+use crate::upstream::{ConnectorOptions, UpstreamClient};
+
+/// 单个检测请求的超时
+const DETECT_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// 检测请求使用的模型名（不存在，上游不会真正调用模型）
+pub(crate) const PROBE_MODEL: &str = "cc-switch-probe";
+
+/// 对照路径：正常的上游应返回 404
+const CONTROL_PATH: &str = "/cc-switch-probe-not-found";
+
+/// 上游接口格式
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum WireProtocol {
+    Anthropic,
+    OpenaiChat,
+    OpenaiResponses,
+}
+
+/// 单个接口格式的检测结果
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ProtocolCheck {
+    pub protocol: WireProtocol,
+    pub url: String,
+    pub supported: bool,
+    pub status: Option<u16>,
+    pub error: Option<String>,
+}
+
+/// 按检测结果建议的供应商配置
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ProtocolSuggestion {
+    pub app_type: AppType,
+    /// Codex 的 wire_api（responses / chat）
+    pub wire_api: Option<String>,
+    /// 需要代理转换协议时的上游接口格式（对应 `meta.api_format`）
+    pub api_format: Option<ApiFormat>,
+    pub base_url: String,
+    /// 供应商 settings_config 模板
+    pub settings_config: Value,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ProtocolDetection {
+    pub checks: Vec<ProtocolCheck>,
+    /// 按推荐程度排序，原生支持的在前
+    pub suggestions: Vec<ProtocolSuggestion>,
+}
+
+/// 地址去掉末尾的 `/v1` 与 `/`，Claude Code 会自行拼接 `/v1/messages`
+pub(crate) fn root_url(base_url: &str) -> String {
+    let base = base_url.trim().trim_end_matches('/');
+    base.strip_suffix("/v1").unwrap_or(base).to_string()
+}
+
+/// Codex 的 base_url：以 `/v1` 结尾，Codex 直接拼接 `/responses` 或 `/chat/completions`
+pub(crate) fn codex_url(base_url: &str) -> String {
+    format!("{}/v1", root_url(base_url))
+}
+
+/// 携带 API Key（同时以 Bearer 与 x-api-key 形式）发送请求，返回状态码与响应体
+pub(crate) async fn send(
+    client: &UpstreamClient,
+    method: Method,
+    url: &str,
+    api_key: &str,
+    body: Option<&Value>,
+) -> Result<(StatusCode, String), String> {
+    let request = Request::builder()
+        .method(method)
+        .uri(url)
+        .header("content-type", "application/json")
+        .header("authorization", format!("Bearer {}", api_key))
+        .header("x-api-key", api_key)
+        .header("anthropic-version", "2023-06-01")
+        .body(body.map_or_else(Body::empty, |body| Body::from(body.to_string())))
+        .map_err(|e| format!("URL 无效: {}", e))?;
+    let response = tokio::time::timeout(DETECT_TIMEOUT, client.request(request))
+        .await
+        .map_err(|_| "请求超时".to_string())?
+        .map_err(|e| e.to_string())?;
+    let status = response.status();
+    let bytes = response
+        .into_body()
+        .collect()
+        .await
+        .map_err(|e| format!("读取响应失败: {}", e))?
+        .to_bytes();
+    Ok((status, String::from_utf8_lossy(&bytes).to_string()))
+}
+
+/// 接口路由是否存在：响应须为 JSON；404 仅在提示模型不存在时算作存在
+pub(crate) fn route_exists(status: StatusCode, body: &str) -> bool {
+    if serde_json::from_str::<Value>(body).is_err() {
+        return false;
+    }
+    match status {
+        StatusCode::NOT_FOUND => body.to_ascii_lowercase().contains("model"),
+        StatusCode::METHOD_NOT_ALLOWED => false,
+        _ => true,
+    }
+}
+
+async fn check(
+    client: &UpstreamClient,
+    protocol: WireProtocol,
+    base_url: &str,
+    api_key: &str,
+    control: Option<StatusCode>,
+) -> ProtocolCheck {
+    let (url, body) = match protocol {
+        WireProtocol::Anthropic => (
+            format!("{}/v1/messages", root_url(base_url)),
+            json!({
+                "model": PROBE_MODEL,
+                "max_tokens": 1,
+                "messages": [{ "role": "user", "content": "ping" }],
+            }),
+        ),
+        WireProtocol::OpenaiChat => (
+            format!("{}/chat/completions", codex_url(base_url)),
+            json!({
+                "model": PROBE_MODEL,
+                "max_tokens": 1,
+                "messages": [{ "role": "user", "content": "ping" }],
+            }),
+        ),
+        WireProtocol::OpenaiResponses => (
+            format!("{}/responses", codex_url(base_url)),
+            json!({ "model": PROBE_MODEL, "max_output_tokens": 16, "input": "ping" }),
+        ),
+    };
+    match send(client, Method::POST, &url, api_key, Some(&body)).await {
+        Ok((status, text)) => ProtocolCheck {
+            protocol,
+            url,
+            // 与对照路径的状态码相同时无法区分，视为不支持
+            supported: route_exists(status, &text) && control != Some(status),
+            status: Some(status.as_u16()),
+            error: None,
+        },
+        Err(error) => ProtocolCheck {
+            protocol,
+            url,
+            supported: false,
+            status: None,
+            error: Some(error),
+        },
+    }
+}
+
+pub(crate) fn claude_settings(base_url: &str, api_key: &str) -> Value {
+    json!({
+        "env": {
+            "ANTHROPIC_BASE_URL": base_url,
+            "ANTHROPIC_AUTH_TOKEN": api_key,
+        }
+    })
+}
+
+pub(crate) fn codex_settings(base_url: &str, api_key: &str, wire_api: &str) -> Value {
+    let name = crate::snippet::name_from_url(base_url).unwrap_or_default();
+    json!({
+        "auth": { "OPENAI_API_KEY": api_key },
+        "config": crate::snippet::build_codex_config(None, base_url, &name, wire_api),
+    })
+}
+
+/// 由检测结果生成建议：原生支持的配置在前，需要代理转换协议的在后
+fn suggest(checks: &[ProtocolCheck], base_url: &str, api_key: &str) -> Vec<ProtocolSuggestion> {
+    let supports = |protocol| {
+        checks
+            .iter()
+            .any(|check| check.protocol == protocol && check.supported)
+    };
+    let anthropic = supports(WireProtocol::Anthropic);
+    let chat = supports(WireProtocol::OpenaiChat);
+    let responses = supports(WireProtocol::OpenaiResponses);
+    let root = root_url(base_url);
+    let codex = codex_url(base_url);
+
+    let mut suggestions = Vec::new();
+    if anthropic {
+        suggestions.push(ProtocolSuggestion {
+            app_type: AppType::Claude,
+            wire_api: None,
+            api_format: None,
+            base_url: root.clone(),
+            settings_config: claude_settings(&root, api_key),
+        });
+    }
+    if responses || chat {
+        let wire_api = if responses { "responses" } else { "chat" };
+        suggestions.push(ProtocolSuggestion {
+            app_type: AppType::Codex,
+            wire_api: Some(wire_api.to_string()),
+            api_format: None,
+            base_url: codex.clone(),
+            settings_config: codex_settings(&codex, api_key, wire_api),
+        });
+    }
+    if chat && !anthropic {
+        suggestions.push(ProtocolSuggestion {
+            app_type: AppType::Claude,
+            wire_api: None,
+            api_format: Some(ApiFormat::OpenaiChat),
+            base_url: root.clone(),
+            settings_config: claude_settings(&root, api_key),
+        });
+    }
+    if anthropic && !responses && !chat {
+        suggestions.push(ProtocolSuggestion {
+            app_type: AppType::Codex,
+            wire_api: Some("responses".to_string()),
+            api_format: Some(ApiFormat::Anthropic),
+            base_url: codex.clone(),
+            settings_config: codex_settings(&codex, api_key, "responses"),
+        });
+    }
+    suggestions
+}
+
+/// 检测地址支持的接口格式，并给出供应商配置建议
+#[tauri::command]
+pub async fn probe_endpoint(
+    url: String,
+    key: Option<String>,
+) -> Result<ProtocolDetection, CcSwitchError> {
+    Ok(detect(&url, key.unwrap_or_default().trim()).await?)
+}
+
+pub(crate) async fn detect(url: &str, api_key: &str) -> Result<ProtocolDetection, String> {
+    let client = crate::upstream::client(&ConnectorOptions::default())?;
+    let control = send(
+        &client,
+        Method::POST,
+        &format!("{}{}", root_url(url), CONTROL_PATH),
+        api_key,
+        Some(&json!({})),
+    )
+    .await
+    .ok()
+    .filter(|(status, text)| route_exists(*status, text))
+    .map(|(status, _)| status);
+    if let Some(status) = control {
+        tracing::debug!("{} 对不存在的路径返回 {}，检测结果以此为对照", url, status);
+    }
+
+    let (anthropic, chat, responses) = futures::join!(
+        check(&client, WireProtocol::Anthropic, url, api_key, control),
+        check(&client, WireProtocol::OpenaiChat, url, api_key, control),
+        check(
+            &client,
+            WireProtocol::OpenaiResponses,
+            url,
+            api_key,
+            control
+        ),
+    );
+    let checks = vec![anthropic, chat, responses];
+    let suggestions = suggest(&checks, url, api_key);
+    tracing::info!(
+        "接口格式检测 {}: {}",
+        url,
+        checks
+            .iter()
+            .map(|check| format!("{:?}={}", check.protocol, check.supported))
+            .collect::<Vec<_>>()
+            .join(", ")
+    );
+    Ok(ProtocolDetection {
+        checks,
+        suggestions,
+    })
+}
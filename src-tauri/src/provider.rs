@@ -1,6 +1,10 @@
 use serde::{Deserialize, Serialize};
 use serde_json::Value;
 use std::collections::HashMap;
+use std::path::PathBuf;
+
+use crate::app_config::AppType;
+use crate::i18n::t_args;
 
 // SSOT 模式：不再写供应商副本文件
 
@@ -22,13 +26,26 @@ pub struct Provider {
     #[serde(skip_serializing_if = "Option::is_none")]
     #[serde(rename = "sortIndex")]
     pub sort_index: Option<usize>,
-    /// 供应商元数据（不写入 live 配置，仅存于 ~/.cc-switch/config.json）
+    /// 供应商元数据（不写入 live 配置，仅存于 ~/.cc-switch/cc-switch.db）
     #[serde(skip_serializing_if = "Option::is_none")]
     pub meta: Option<ProviderMeta>,
     /// 代理模式下是否启用此供应商（仅在代理模式下有效）
     #[serde(skip_serializing_if = "Option::is_none")]
     #[serde(rename = "proxyEnabled")]
     pub proxy_enabled: Option<bool>,
+    /// 是否启用（未设置视为启用）；停用的供应商保留在列表中，但不可切换、不参与代理与用量刷新
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub enabled: Option<bool>,
+    /// 是否锁定；锁定的供应商拒绝编辑与删除，需通过 `set_provider_locked` 显式解锁
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub locked: Option<bool>,
+    /// 是否置顶；置顶的供应商在代理故障转移中始终排在最前（不受 sortIndex 与时间段优先规则影响）
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub pinned: Option<bool>,
+    /// 维护模式结束时间（毫秒时间戳）；到期前不参与代理与自动切换，到期后自动清除
+    #[serde(skip_serializing_if = "Option::is_none")]
+    #[serde(rename = "maintenanceUntil")]
+    pub maintenance_until: Option<i64>,
 }
 
 impl Provider {
@@ -49,7 +66,65 @@ impl Provider {
             sort_index: None,
             meta: None,
             proxy_enabled: None,
+            enabled: None,
+            locked: None,
+            pinned: None,
+            maintenance_until: None,
+        }
+    }
+
+    /// 供应商自定义的 live 配置目录（支持 ~ 开头），未设置时返回 None
+    pub fn live_config_dir(&self) -> Option<PathBuf> {
+        let raw = self.meta.as_ref()?.live_config_dir.as_deref()?.trim();
+        if raw.is_empty() {
+            return None;
+        }
+        Some(crate::settings::resolve_override_path(raw))
+    }
+
+    /// 供应商的 API 地址：Claude 取 env.ANTHROPIC_BASE_URL；
+    /// Codex 取 config.toml 中 model_provider 指向的 model_providers 条目的 base_url
+    pub fn base_url(&self, app_type: &AppType) -> Option<String> {
+        settings_base_url(app_type, &self.settings_config)
+    }
+
+    /// 是否启用（未设置时视为启用）
+    pub fn is_enabled(&self) -> bool {
+        self.enabled.unwrap_or(true)
+    }
+
+    pub fn is_locked(&self) -> bool {
+        self.locked.unwrap_or(false)
+    }
+
+    pub fn is_pinned(&self) -> bool {
+        self.pinned.unwrap_or(false)
+    }
+
+    /// 是否处于维护模式（按当前时间判断，不依赖后台清理）
+    pub fn in_maintenance(&self) -> bool {
+        self.maintenance_until
+            .is_some_and(|until| until > chrono::Utc::now().timestamp_millis())
+    }
+
+    pub fn is_managed(&self) -> bool {
+        self.meta.as_ref().is_some_and(|m| m.managed)
+    }
+
+    /// 锁定的供应商不可编辑或删除
+    pub fn ensure_editable(&self) -> Result<(), String> {
+        if self.is_locked() {
+            return Err(t_args("errors.providerLocked", &[("name", &self.name)]));
         }
+        Ok(())
+    }
+
+    /// 凭证类型（未设置时视为 API Key）
+    pub fn credential_kind(&self) -> CredentialKind {
+        self.meta
+            .as_ref()
+            .and_then(|m| m.credential_kind)
+            .unwrap_or_default()
     }
 }
 
@@ -68,6 +143,24 @@ pub struct UsageScript {
     pub code: String,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub timeout: Option<u64>,
+    /// 额外允许访问的主机（默认仅允许与 baseUrl 同源）
+    #[serde(default, rename = "allowedHosts", skip_serializing_if = "Vec::is_empty")]
+    pub allowed_hosts: Vec<String>,
+    /// 是否在多次执行间持久化 Cookie（加密存储，用于保持登录会话）
+    #[serde(skip_serializing_if = "Option::is_none")]
+    #[serde(rename = "persistCookies")]
+    pub persist_cookies: Option<bool>,
+}
+
+impl UsageScript {
+    /// Cookie 持久化作用域（按应用类型 + 供应商隔离）；未启用时返回 None
+    pub fn cookie_scope(&self, app_type: &crate::app_config::AppType, provider_id: &str) -> Option<String> {
+        if self.persist_cookies.unwrap_or(false) {
+            Some(format!("{}:{}", app_type.as_str(), provider_id))
+        } else {
+            None
+        }
+    }
 }
 
 /// 用量数据
@@ -104,6 +197,28 @@ pub struct UsageResult {
     pub error: Option<String>,
 }
 
+/// 从 settings_config（或同结构的 live 配置）中读取上游地址
+pub fn settings_base_url(app_type: &AppType, settings: &Value) -> Option<String> {
+    match app_type {
+        AppType::Claude => settings
+            .get("env")?
+            .get("ANTHROPIC_BASE_URL")?
+            .as_str()
+            .map(str::to_string),
+        AppType::Codex => {
+            let text = settings.get("config")?.as_str()?;
+            let table = toml::from_str::<toml::Table>(text).ok()?;
+            let provider = table.get("model_provider")?.as_str()?;
+            table
+                .get("model_providers")?
+                .get(provider)?
+                .get("base_url")?
+                .as_str()
+                .map(str::to_string)
+        }
+    }
+}
+
 /// 供应商元数据
 #[derive(Debug, Clone, Serialize, Deserialize, Default)]
 pub struct ProviderMeta {
@@ -113,6 +228,210 @@ pub struct ProviderMeta {
     /// 用量查询脚本配置
     #[serde(skip_serializing_if = "Option::is_none")]
     pub usage_script: Option<UsageScript>,
+    /// 低余额告警配置
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub balance_alert: Option<BalanceAlert>,
+    /// 切换时写入的 live 配置目录（如另一套 CLAUDE_CONFIG_DIR 或项目内的 `.claude`），
+    /// 未设置时使用全局目录
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub live_config_dir: Option<String>,
+    /// 凭证类型：API Key（默认）或订阅账号登录
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub credential_kind: Option<CredentialKind>,
+    /// 订阅账号的 OAuth 凭证（仅 credential_kind 为 OAuth 时使用）
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub oauth: Option<OAuthCredential>,
+    /// 是否来自团队托管配置源（只读，随远端刷新更新或移除）
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub managed: bool,
+    /// 切换到该供应商时一并写入的 VS Code 扩展配置
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub apply_targets: Vec<ApplyTarget>,
+    /// 代理模式下的限流配置
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub rate_limit: Option<RateLimit>,
+    /// 按时间段调整优先级或可用性的路由规则
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub routing_rules: Vec<RoutingRule>,
+    /// 上游接口格式：与客户端不一致时由代理转换请求与响应（未设置时原样转发）
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub api_format: Option<ApiFormat>,
+    /// 打开网站控制台时附加到 website_url 的查询参数模板（如 `token={{apiKey}}`），
+    /// 支持 `{{apiKey}}` 与 `{{baseUrl}}` 占位符
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub dashboard_query: Option<String>,
+    /// 每百万 token 的价格（币种由用户自定），用于在用量报表中估算费用
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub token_price: Option<f64>,
+    /// 可选接口（count_tokens / models / batches）的支持情况，false 表示不支持、代理将跳过；
+    /// 可手动设置，未设置时由代理根据 404/405 响应自动记录
+    #[serde(default, skip_serializing_if = "HashMap::is_empty")]
+    pub capabilities: HashMap<String, bool>,
+    /// 代理模式下的端点故障转移：主地址失败后依次尝试 custom_endpoints，再切换到下一个供应商
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub endpoint_failover: Option<EndpointFailover>,
+    /// 自动选择端点：切换与代理时使用后台测速中延迟最低的可用端点（主地址或 custom_endpoints）
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub auto_select_endpoint: bool,
+    /// 代理模式下的主机映射（主机名 -> IP），绕过 DNS 污染或解析缓慢，优先于全局映射与 DoH
+    #[serde(default, skip_serializing_if = "HashMap::is_empty")]
+    pub host_mapping: HashMap<String, String>,
+    /// 代理模式下 TCP 连接的目标主机（可带端口），请求的 Host 仍为 URL 主机
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub connect_host: Option<String>,
+    /// 代理模式下 TLS 握手使用的 SNI（证书也按该域名校验）
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub sni_host: Option<String>,
+    /// 代理模式下不校验上游证书（仅用于排查问题，连接可能被中间人截获）
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub accept_invalid_certs: bool,
+    /// mTLS 客户端证书，用于代理转发与用量脚本请求
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub client_cert: Option<ClientCertificate>,
+    /// 代理模式下改为连接的 Unix 域套接字（本地模型服务），base_url 的主机仅用于 Host 头
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub unix_socket: Option<String>,
+    /// 从模型列表接口发现的可用模型，供模型输入提示使用
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub discovered_models: Vec<String>,
+}
+
+/// 供应商内端点的尝试顺序
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum EndpointFailover {
+    /// 主地址在前，其后按添加顺序
+    Ordered,
+    /// 按最近一次测速的延迟，不可用的端点排在最后
+    Latency,
+}
+
+/// 供应商上游接口格式
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ApiFormat {
+    /// Anthropic Messages 接口
+    Anthropic,
+    /// OpenAI Chat Completions 接口
+    OpenaiChat,
+}
+
+/// 额外的切换目标：VS Code 中读取同一套 Base URL / API Key 的扩展
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ApplyTarget {
+    Cline,
+    RooCode,
+}
+
+/// 供应商凭证类型
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, Default)]
+#[serde(rename_all = "snake_case")]
+pub enum CredentialKind {
+    /// 通过 settings_config 中的 API Key / Token 访问（中转或官方 API）
+    #[default]
+    ApiKey,
+    /// Claude Pro/Max 订阅账号：切换时换入 `.credentials.json`（macOS 为钥匙串）中的 OAuth 令牌
+    ClaudeOauth,
+    /// Codex ChatGPT 登录：settings_config.auth 中保存带 refresh_token 的 `tokens`，
+    /// 切换时回填刷新后的令牌，且不参与代理的 Bearer Key 改写
+    CodexOauth,
+}
+
+/// 订阅账号的 OAuth 凭证快照
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct OAuthCredential {
+    /// 凭证文件的完整内容（如 `{"claudeAiOauth": {...}}`）
+    pub credentials: Value,
+    /// 账号信息（`.claude.json` 中的 oauthAccount），用于展示与防止回填到错误账号
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub account: Option<Value>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub updated_at: Option<i64>,
+}
+
+impl OAuthCredential {
+    /// 账号邮箱（用于识别同一账号）
+    pub fn email(&self) -> Option<&str> {
+        self.account.as_ref()?.get("emailAddress")?.as_str()
+    }
+}
+
+/// 低余额告警阈值配置（剩余额度或剩余百分比，任一低于阈值即告警）
+#[derive(Debug, Clone, Serialize, Deserialize, Default)]
+#[serde(rename_all = "camelCase")]
+pub struct BalanceAlert {
+    pub enabled: bool,
+    /// 剩余额度低于该值时告警
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub remaining_below: Option<f64>,
+    /// 剩余百分比（0-100）低于该值时告警，需脚本返回 total
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub percent_below: Option<f64>,
+    /// 是否发送系统通知（默认开启）
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub notify: Option<bool>,
+    /// 告警冷却时间（分钟，默认 60）
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub cooldown_minutes: Option<u64>,
+}
+
+/// 代理限流：最近一分钟内的请求数或 token 用量达到上限后跳过该供应商
+#[derive(Debug, Clone, Serialize, Deserialize, Default)]
+#[serde(rename_all = "camelCase")]
+pub struct RateLimit {
+    /// 每分钟请求数上限
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub requests_per_minute: Option<u32>,
+    /// 每分钟 token 上限（按上游响应中的 usage 统计）
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub tokens_per_minute: Option<u32>,
+}
+
+/// mTLS 客户端证书：证书与私钥为 PEM 文件路径或 `keychain:<服务名>`（macOS 钥匙串）
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize, Default)]
+#[serde(rename_all = "camelCase")]
+pub struct ClientCertificate {
+    #[serde(default)]
+    pub cert: String,
+    #[serde(default)]
+    pub key: String,
+}
+
+impl ClientCertificate {
+    /// 证书与私钥均已填写
+    pub fn is_configured(&self) -> bool {
+        !self.cert.trim().is_empty() && !self.key.trim().is_empty()
+    }
+}
+
+/// 时间段路由规则
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RoutingRule {
+    /// 生效的星期（1 为周一，7 为周日），为空表示每天
+    #[serde(default)]
+    pub days: Vec<u32>,
+    /// 开始时间（HH:MM，本地时间）
+    pub start: String,
+    /// 结束时间（HH:MM，不含）；早于开始时间表示跨越午夜
+    pub end: String,
+    pub effect: RoutingEffect,
+}
+
+/// 规则生效时对供应商的影响
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum RoutingEffect {
+    /// 排到其他供应商之前
+    Prefer,
+    /// 排到其他供应商之后
+    Avoid,
+    /// 仅在规则时段内可用
+    Only,
+    /// 规则时段内不可用
+    Exclude,
 }
 
 impl ProviderManager {
@@ -120,4 +439,25 @@ impl ProviderManager {
     pub fn get_all_providers(&self) -> &HashMap<String, Provider> {
         &self.providers
     }
+
+    /// 按界面顺序获取供应商列表（sortIndex → createdAt → name）
+    pub fn sorted_providers(&self) -> Vec<&Provider> {
+        let mut providers: Vec<&Provider> = self.providers.values().collect();
+        providers.sort_by(|a, b| {
+            match (a.sort_index, b.sort_index) {
+                (Some(idx_a), Some(idx_b)) => return idx_a.cmp(&idx_b),
+                (Some(_), None) => return std::cmp::Ordering::Less,
+                (None, Some(_)) => return std::cmp::Ordering::Greater,
+                _ => {}
+            }
+            match (a.created_at, b.created_at) {
+                (Some(time_a), Some(time_b)) => return time_a.cmp(&time_b),
+                (Some(_), None) => return std::cmp::Ordering::Greater,
+                (None, Some(_)) => return std::cmp::Ordering::Less,
+                _ => {}
+            }
+            a.name.cmp(&b.name)
+        });
+        providers
+    }
 }
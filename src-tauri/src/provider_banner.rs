@@ -0,0 +1,80 @@
+//! 代理模式下的供应商标注：开启后在转发给每个供应商的请求中追加一条系统提示，
+//! 写明本次回答由哪个供应商提供，使对话记录与用量日志能追溯到具体中转。
+//! 只处理 Messages、Chat Completions 与 Responses 接口，其余请求原样转发。
+
+use axum::body::Bytes;
+use serde_json::{json, Value};
+
+use crate::i18n::t_args;
+use crate::provider::Provider;
+
+/// 在请求体中注入标明供应商的系统提示；无法识别的请求原样返回
+pub fn inject(path: &str, body: &Bytes, provider: &Provider) -> Bytes {
+    let path = path.split('?').next().unwrap_or(path);
+    let path = path.strip_prefix("/v1").unwrap_or(path);
+    let Ok(mut value) = serde_json::from_slice::<Value>(body) else {
+        return body.clone();
+    };
+    let note = t_args("proxy.providerBanner", &[("name", &provider.name)]);
+    let injected = match path {
+        "/messages" => append_system(&mut value, &note),
+        "/chat/completions" => insert_system_message(&mut value, &note),
+        "/responses" => append_instructions(&mut value, &note),
+        _ => false,
+    };
+    if !injected {
+        return body.clone();
+    }
+    serde_json::to_vec(&value)
+        .map(Bytes::from)
+        .unwrap_or_else(|_| body.clone())
+}
+
+/// Anthropic Messages：追加到 system 末尾（保留前面带 cache_control 的块，不破坏缓存前缀）
+fn append_system(value: &mut Value, note: &str) -> bool {
+    let Some(request) = value.as_object_mut() else {
+        return false;
+    };
+    match request.get_mut("system") {
+        Some(Value::Array(blocks)) => blocks.push(json!({ "type": "text", "text": note })),
+        Some(Value::String(system)) if !system.is_empty() => {
+            system.push_str("\n\n");
+            system.push_str(note);
+        }
+        _ => {
+            request.insert("system".into(), json!(note));
+        }
+    }
+    true
+}
+
+/// Chat Completions：插入到开头的系统消息之后
+fn insert_system_message(value: &mut Value, note: &str) -> bool {
+    let Some(messages) = value.get_mut("messages").and_then(|v| v.as_array_mut()) else {
+        return false;
+    };
+    let index = messages
+        .iter()
+        .take_while(|m| {
+            matches!(
+                m.get("role").and_then(|v| v.as_str()),
+                Some("system") | Some("developer")
+            )
+        })
+        .count();
+    messages.insert(index, json!({ "role": "system", "content": note }));
+    true
+}
+
+/// Responses：追加到 instructions 末尾
+fn append_instructions(value: &mut Value, note: &str) -> bool {
+    let Some(request) = value.as_object_mut() else {
+        return false;
+    };
+    let instructions = match request.get("instructions").and_then(|v| v.as_str()) {
+        Some(existing) if !existing.is_empty() => format!("{}\n\n{}", existing, note),
+        _ => note.to_string(),
+    };
+    request.insert("instructions".into(), json!(instructions));
+    true
+}
@@ -0,0 +1,156 @@
+//! 供应商检索：按关键字、分类与状态过滤并排序，只返回列表展示所需的摘要（不含 settings_config 与密钥），
+//! 供应商较多时前端无需拿到完整配置即可渲染过滤后的列表。
+
+#![allow(non_snake_case)]
+
+use serde::{Deserialize, Serialize};
+use tauri::State;
+
+use crate::app_config::AppType;
+use crate::error::CcSwitchError;
+use crate::i18n::t_args;
+use crate::provider::{CredentialKind, Provider};
+use crate::store::AppState;
+
+/// 排序方式
+#[derive(Debug, Clone, Copy, Default, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum ProviderSort {
+    /// 界面自定义顺序（sortIndex → createdAt → name）
+    #[default]
+    Custom,
+    /// 名称（不区分大小写）
+    Name,
+    /// 创建时间，最新在前
+    CreatedAt,
+}
+
+/// 过滤条件（均为可选，未设置时不过滤）
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(rename_all = "camelCase", default)]
+pub struct ProviderFilter {
+    /// 关键字：匹配名称、API 地址、官网与分类（不区分大小写）
+    pub text: Option<String>,
+    /// 分类（任一匹配即可）
+    pub categories: Vec<String>,
+    /// 按启用状态过滤
+    pub enabled: Option<bool>,
+    pub sort: ProviderSort,
+}
+
+/// 列表展示用的供应商摘要
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ProviderSummary {
+    pub id: String,
+    pub name: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub base_url: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub website_url: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub category: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub sort_index: Option<usize>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub created_at: Option<i64>,
+    pub current: bool,
+    pub enabled: bool,
+    pub locked: bool,
+    pub proxy_enabled: bool,
+    pub credential_kind: CredentialKind,
+}
+
+impl ProviderSummary {
+    fn new(provider: &Provider, app_type: &AppType, current: &str) -> Self {
+        Self {
+            id: provider.id.clone(),
+            name: provider.name.clone(),
+            base_url: provider.base_url(app_type),
+            website_url: provider.website_url.clone(),
+            category: provider.category.clone(),
+            sort_index: provider.sort_index,
+            created_at: provider.created_at,
+            current: provider.id == current,
+            enabled: provider.is_enabled(),
+            locked: provider.is_locked(),
+            proxy_enabled: provider.proxy_enabled.unwrap_or(false),
+            credential_kind: provider.credential_kind(),
+        }
+    }
+
+    fn matches(&self, filter: &ProviderFilter, needle: Option<&str>) -> bool {
+        if let Some(enabled) = filter.enabled {
+            if self.enabled != enabled {
+                return false;
+            }
+        }
+        if !filter.categories.is_empty()
+            && !self
+                .category
+                .as_ref()
+                .is_some_and(|c| filter.categories.contains(c))
+        {
+            return false;
+        }
+        let Some(needle) = needle else {
+            return true;
+        };
+        [
+            Some(&self.name),
+            self.base_url.as_ref(),
+            self.website_url.as_ref(),
+            self.category.as_ref(),
+        ]
+        .into_iter()
+        .flatten()
+        .any(|field| field.to_lowercase().contains(needle))
+    }
+}
+
+/// 按条件检索供应商，返回不含配置与密钥的摘要列表
+#[tauri::command]
+pub async fn query_providers(
+    state: State<'_, AppState>,
+    filter: Option<ProviderFilter>,
+    app_type: Option<AppType>,
+    app: Option<String>,
+    appType: Option<String>,
+) -> Result<Vec<ProviderSummary>, CcSwitchError> {
+    let app_type = app_type
+        .or_else(|| app.as_deref().map(|s| s.into()))
+        .or_else(|| appType.as_deref().map(|s| s.into()))
+        .unwrap_or(AppType::Claude);
+    let filter = filter.unwrap_or_default();
+    let needle = filter
+        .text
+        .as_deref()
+        .map(|s| s.trim().to_lowercase())
+        .filter(|s| !s.is_empty());
+
+    let config = state
+        .config
+        .lock()
+        .map_err(|e| t_args("errors.lockFailed", &[("error", &e)]))?;
+    let manager = config
+        .get_manager(&app_type)
+        .ok_or_else(|| t_args("errors.appTypeNotFound", &[("app", &app_type.as_str())]))?;
+
+    let mut summaries: Vec<ProviderSummary> = manager
+        .sorted_providers()
+        .into_iter()
+        .map(|p| ProviderSummary::new(p, &app_type, &manager.current))
+        .filter(|s| s.matches(&filter, needle.as_deref()))
+        .collect();
+
+    match filter.sort {
+        ProviderSort::Custom => {}
+        ProviderSort::Name => {
+            summaries.sort_by_key(|s| s.name.to_lowercase());
+        }
+        ProviderSort::CreatedAt => {
+            summaries.sort_by_key(|s| std::cmp::Reverse(s.created_at));
+        }
+    }
+    Ok(summaries)
+}
@@ -0,0 +1,425 @@
+//! 新建供应商向导：检查地址可达 → 低成本校验 API Key → 选择配置模板 → 预览生成的配置。
+//! 每一步都是独立命令并返回结构化结果，前端向导只负责展示；确认后仍经 `add_provider` 保存。
+
+#![allow(non_snake_case)]
+
+use axum::http::{Method, StatusCode};
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Value};
+use url::Url;
+
+use crate::app_config::AppType;
+use crate::error::CcSwitchError;
+use crate::i18n::t_args;
+use crate::protocol_detect::{self, WireProtocol, PROBE_MODEL};
+use crate::provider::{ApiFormat, Provider};
+use crate::upstream::ConnectorOptions;
+
+/// 地址检查结果
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct UrlCheck {
+    pub url: String,
+    /// 地址格式是否有效（http / https 且包含主机名）
+    pub valid: bool,
+    /// 是否收到 HTTP 响应（任意状态码都算可达）
+    pub reachable: bool,
+    /// 是否为本机服务
+    pub local: bool,
+    pub latency_ms: Option<u128>,
+    pub status: Option<u16>,
+    pub error: Option<String>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum KeyStatus {
+    Valid,
+    Invalid,
+    /// 上游未给出明确结果（网络错误或无法识别的响应）
+    Unknown,
+}
+
+/// API Key 校验结果
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct KeyCheck {
+    pub status: KeyStatus,
+    pub http_status: Option<u16>,
+    pub message: Option<String>,
+    /// 校验时顺带获取到的模型列表
+    pub models: Vec<String>,
+}
+
+/// 配置模板
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct WizardTemplate {
+    pub id: String,
+    pub app_type: AppType,
+    /// Codex 的 wire_api（responses / chat）
+    pub wire_api: Option<String>,
+    /// 需要代理转换协议时的上游接口格式（对应 `meta.api_format`）
+    pub api_format: Option<ApiFormat>,
+    /// 是否需要经本地代理转换协议
+    pub requires_proxy: bool,
+    /// 上游是否支持该模板所需的接口格式；未检测时为空
+    pub supported: Option<bool>,
+    pub recommended: bool,
+}
+
+/// 预览所需的向导输入
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct WizardInput {
+    pub app_type: AppType,
+    pub template: String,
+    pub name: Option<String>,
+    pub base_url: String,
+    #[serde(default)]
+    pub api_key: String,
+    pub model: Option<String>,
+    pub website_url: Option<String>,
+}
+
+/// 生成的供应商配置预览
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct WizardPreview {
+    pub provider: Provider,
+    /// 保存前校验发现的问题；为空时可直接保存
+    pub issues: Vec<String>,
+    pub requires_proxy: bool,
+}
+
+/// 模板定义，同一应用内按推荐程度排序
+struct TemplateDef {
+    id: &'static str,
+    app_type: AppType,
+    /// 模板所需的上游接口格式
+    protocol: WireProtocol,
+    wire_api: Option<&'static str>,
+    api_format: Option<ApiFormat>,
+}
+
+const TEMPLATES: [TemplateDef; 5] = [
+    TemplateDef {
+        id: "claude_anthropic",
+        app_type: AppType::Claude,
+        protocol: WireProtocol::Anthropic,
+        wire_api: None,
+        api_format: None,
+    },
+    TemplateDef {
+        id: "claude_openai_chat",
+        app_type: AppType::Claude,
+        protocol: WireProtocol::OpenaiChat,
+        wire_api: None,
+        api_format: Some(ApiFormat::OpenaiChat),
+    },
+    TemplateDef {
+        id: "codex_responses",
+        app_type: AppType::Codex,
+        protocol: WireProtocol::OpenaiResponses,
+        wire_api: Some("responses"),
+        api_format: None,
+    },
+    TemplateDef {
+        id: "codex_chat",
+        app_type: AppType::Codex,
+        protocol: WireProtocol::OpenaiChat,
+        wire_api: Some("chat"),
+        api_format: None,
+    },
+    TemplateDef {
+        id: "codex_anthropic",
+        app_type: AppType::Codex,
+        protocol: WireProtocol::Anthropic,
+        wire_api: Some("responses"),
+        api_format: Some(ApiFormat::Anthropic),
+    },
+];
+
+fn resolve_app_type(
+    app_type: Option<AppType>,
+    app: Option<String>,
+    appType: Option<String>,
+) -> AppType {
+    app_type
+        .or_else(|| app.as_deref().map(|s| s.into()))
+        .or_else(|| appType.as_deref().map(|s| s.into()))
+        .unwrap_or(AppType::Claude)
+}
+
+/// 第一步：检查地址格式与可达性
+#[tauri::command]
+pub async fn wizard_check_url(url: String) -> Result<UrlCheck, CcSwitchError> {
+    let url = url.trim().to_string();
+    let invalid = |error: String| UrlCheck {
+        url: url.clone(),
+        valid: false,
+        reachable: false,
+        local: false,
+        latency_ms: None,
+        status: None,
+        error: Some(error),
+    };
+    let parsed = match Url::parse(&url) {
+        Ok(parsed) => parsed,
+        Err(e) => return Ok(invalid(t_args("errors.wizardInvalidUrl", &[("error", &e)]))),
+    };
+    if !matches!(parsed.scheme(), "http" | "https") || parsed.host().is_none() {
+        return Ok(invalid(t_args(
+            "errors.wizardInvalidUrl",
+            &[("error", &"仅支持 http / https 地址")],
+        )));
+    }
+
+    let local = crate::speedtest::is_local_url(&parsed);
+    let result = crate::speedtest::test_endpoints(vec![url.clone()], None)
+        .await?
+        .into_iter()
+        .next();
+    let (latency_ms, status, error) = result
+        .map(|result| (result.latency, result.status, result.error))
+        .unwrap_or_default();
+    Ok(UrlCheck {
+        url,
+        valid: true,
+        reachable: status.is_some(),
+        local,
+        latency_ms,
+        status,
+        error,
+    })
+}
+
+/// 第二步：校验 API Key。先查询模型列表（不计费），接口不可用时再用不存在的模型名发一次请求，
+/// 只根据鉴权结果判断
+#[tauri::command]
+pub async fn wizard_check_key(
+    url: String,
+    key: String,
+    app_type: Option<AppType>,
+    app: Option<String>,
+    appType: Option<String>,
+) -> Result<KeyCheck, CcSwitchError> {
+    let app_type = resolve_app_type(app_type, app, appType);
+    let key = key.trim();
+    let client = crate::upstream::client(&ConnectorOptions::default())?;
+    let root = protocol_detect::root_url(&url);
+    let rejected = |status: StatusCode, text: String| KeyCheck {
+        status: KeyStatus::Invalid,
+        http_status: Some(status.as_u16()),
+        message: Some(text),
+        models: Vec::new(),
+    };
+
+    match protocol_detect::send(
+        &client,
+        Method::GET,
+        &format!("{}/v1/models", root),
+        key,
+        None,
+    )
+    .await
+    {
+        Ok((status, text)) if status.is_success() => {
+            if let Ok(body) = serde_json::from_str::<Value>(&text) {
+                return Ok(KeyCheck {
+                    status: KeyStatus::Valid,
+                    http_status: Some(status.as_u16()),
+                    message: None,
+                    models: crate::model_discovery::parse_models(&body),
+                });
+            }
+        }
+        Ok((status @ (StatusCode::UNAUTHORIZED | StatusCode::FORBIDDEN), text)) => {
+            return Ok(rejected(status, text));
+        }
+        Ok(_) => {}
+        Err(e) => tracing::debug!("向导查询模型列表失败: {}", e),
+    }
+
+    let (probe_url, body) = match app_type {
+        AppType::Codex => (
+            format!("{}/chat/completions", protocol_detect::codex_url(&url)),
+            json!({
+                "model": PROBE_MODEL,
+                "max_tokens": 1,
+                "messages": [{ "role": "user", "content": "ping" }],
+            }),
+        ),
+        _ => (
+            format!("{}/v1/messages", root),
+            json!({
+                "model": PROBE_MODEL,
+                "max_tokens": 1,
+                "messages": [{ "role": "user", "content": "ping" }],
+            }),
+        ),
+    };
+    let check =
+        match protocol_detect::send(&client, Method::POST, &probe_url, key, Some(&body)).await {
+            Ok((status @ (StatusCode::UNAUTHORIZED | StatusCode::FORBIDDEN), text)) => {
+                rejected(status, text)
+            }
+            // 鉴权通过后才会报模型不存在等错误
+            Ok((status, text)) if protocol_detect::route_exists(status, &text) => KeyCheck {
+                status: KeyStatus::Valid,
+                http_status: Some(status.as_u16()),
+                message: None,
+                models: Vec::new(),
+            },
+            Ok((status, text)) => KeyCheck {
+                status: KeyStatus::Unknown,
+                http_status: Some(status.as_u16()),
+                message: Some(text),
+                models: Vec::new(),
+            },
+            Err(e) => KeyCheck {
+                status: KeyStatus::Unknown,
+                http_status: None,
+                message: Some(e),
+                models: Vec::new(),
+            },
+        };
+    tracing::info!("向导校验 API Key {}: {:?}", url, check.status);
+    Ok(check)
+}
+
+/// 第三步：列出应用可用的配置模板；传入地址时先检测接口格式，标注支持情况与推荐模板
+#[tauri::command]
+pub async fn wizard_templates(
+    url: Option<String>,
+    key: Option<String>,
+    app_type: Option<AppType>,
+    app: Option<String>,
+    appType: Option<String>,
+) -> Result<Vec<WizardTemplate>, CcSwitchError> {
+    let app_type = resolve_app_type(app_type, app, appType);
+    let detection = match url.as_deref().map(str::trim).filter(|url| !url.is_empty()) {
+        Some(url) => Some(protocol_detect::detect(url, key.unwrap_or_default().trim()).await?),
+        None => None,
+    };
+    let supports = |protocol: WireProtocol| {
+        detection.as_ref().map(|detection| {
+            detection
+                .checks
+                .iter()
+                .any(|check| check.protocol == protocol && check.supported)
+        })
+    };
+
+    let mut templates: Vec<WizardTemplate> = TEMPLATES
+        .iter()
+        .filter(|def| def.app_type.as_str() == app_type.as_str())
+        .map(|def| WizardTemplate {
+            id: def.id.to_string(),
+            app_type: def.app_type.clone(),
+            wire_api: def.wire_api.map(str::to_string),
+            api_format: def.api_format,
+            requires_proxy: def.api_format.is_some(),
+            supported: supports(def.protocol),
+            recommended: false,
+        })
+        .collect();
+    // 未检测时推荐原生模板，否则推荐第一个受支持的模板
+    if let Some(template) = templates
+        .iter_mut()
+        .find(|template| template.supported.unwrap_or(!template.requires_proxy))
+    {
+        template.recommended = true;
+    }
+    Ok(templates)
+}
+
+/// 按模板生成 settings_config，并写入默认模型
+fn build_settings(
+    app_type: &AppType,
+    wire_api: Option<&str>,
+    base_url: &str,
+    api_key: &str,
+    model: Option<&str>,
+) -> Value {
+    match app_type {
+        AppType::Codex => {
+            let mut settings =
+                protocol_detect::codex_settings(base_url, api_key, wire_api.unwrap_or("responses"));
+            if let Some(model) = model {
+                let config = settings["config"].as_str().unwrap_or_default();
+                if let Ok(mut doc) = config.parse::<toml_edit::DocumentMut>() {
+                    doc.insert("model", toml_edit::value(model));
+                    settings["config"] = Value::String(doc.to_string());
+                }
+            }
+            settings
+        }
+        _ => {
+            let mut settings = protocol_detect::claude_settings(base_url, api_key);
+            if let Some(model) = model {
+                settings["env"]["ANTHROPIC_MODEL"] = Value::String(model.to_string());
+            }
+            settings
+        }
+    }
+}
+
+/// 第四步：按模板预览生成的供应商配置（不保存），并返回保存前校验发现的问题
+#[tauri::command]
+pub async fn wizard_preview(input: WizardInput) -> Result<WizardPreview, CcSwitchError> {
+    let def = TEMPLATES
+        .iter()
+        .find(|def| def.id == input.template && def.app_type.as_str() == input.app_type.as_str())
+        .ok_or_else(|| t_args("errors.wizardUnknownTemplate", &[("id", &input.template)]))?;
+
+    let base_url = match input.app_type {
+        AppType::Codex => protocol_detect::codex_url(&input.base_url),
+        _ => protocol_detect::root_url(&input.base_url),
+    };
+    let model = input
+        .model
+        .as_deref()
+        .map(str::trim)
+        .filter(|model| !model.is_empty());
+    let settings = build_settings(
+        &input.app_type,
+        def.wire_api,
+        &base_url,
+        input.api_key.trim(),
+        model,
+    );
+    let name = input
+        .name
+        .map(|name| name.trim().to_string())
+        .filter(|name| !name.is_empty())
+        .or_else(|| crate::snippet::name_from_url(&base_url))
+        .unwrap_or_else(|| "Custom".to_string());
+    let website_url = input
+        .website_url
+        .map(|url| url.trim().to_string())
+        .filter(|url| !url.is_empty());
+
+    let mut provider = Provider::with_id(
+        uuid::Uuid::new_v4().to_string(),
+        name,
+        settings,
+        website_url,
+    );
+    if def.api_format.is_some() {
+        provider
+            .meta
+            .get_or_insert_with(Default::default)
+            .api_format = def.api_format;
+    }
+    let issues = crate::commands::validate_provider_settings(&input.app_type, &provider)
+        .err()
+        .map(|e| e.to_string())
+        .into_iter()
+        .collect();
+    Ok(WizardPreview {
+        provider,
+        issues,
+        requires_proxy: def.api_format.is_some(),
+    })
+}
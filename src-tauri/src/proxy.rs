@@ -1,29 +1,182 @@
 use axum::{
-    body::Body,
-    extract::State as AxumState,
+    body::{Body, Bytes},
+    extract::{ConnectInfo, State as AxumState},
     http::{Request, Response, StatusCode, Uri},
     Router,
 };
 use http_body_util::BodyExt;
-use hyper_util::{client::legacy::Client, rt::TokioExecutor};
-use hyper_rustls::HttpsConnectorBuilder;
-use std::sync::{Arc, OnceLock};
+use serde::Serialize;
+use arc_swap::ArcSwapOption;
+use axum_server::tls_rustls::RustlsConfig;
+use std::collections::HashMap;
+use std::net::SocketAddr;
+use std::sync::{Arc, Mutex, OnceLock};
+use std::time::{Duration, Instant};
 use tokio::task::JoinHandle;
 use tower::ServiceBuilder;
+use tracing::Instrument;
 
-use crate::app_config::AppType;
-use crate::provider::Provider;
+use crate::app_config::{AppType, MultiAppConfig};
+use crate::i18n::{t, t_args};
+use crate::provider::{CredentialKind, Provider};
 use crate::settings::OperationMode;
 use crate::store::AppState;
+use crate::webhook::WebhookEvent;
+
+/// 代理监听地址
+pub(crate) const PROXY_ADDR: &str = "127.0.0.1:12857";
+
+/// 代理模式下写入 live 配置的固定 token
+pub const PROXY_TOKEN: &str = "ccswitch-proxymode-token";
+
+/// 代理模式下写入 live 配置的本地代理地址（开启 HTTPS 监听时为 https）
+pub fn proxy_url() -> String {
+    let scheme = if crate::proxy_tls::is_enabled() { "https" } else { "http" };
+    format!("{}://{}", scheme, PROXY_ADDR)
+}
+
+/// 开启 HTTPS 监听时额外提供的本机明文端口：Codex 无法通过配置文件信任自签名证书
+pub(crate) const PROXY_PLAIN_ADDR: &str = "127.0.0.1:12858";
+
+/// Codex 代理模式写入的代理地址：始终使用回环地址上的 http
+pub fn codex_proxy_url() -> String {
+    if crate::proxy_tls::is_enabled() {
+        format!("http://{}", PROXY_PLAIN_ADDR)
+    } else {
+        proxy_url()
+    }
+}
 
 /// 代理服务器状态
 pub struct ProxyServer {
     handle: JoinHandle<()>,
+    /// 局域网共享监听（未开启时为空）
+    lan_handle: Option<JoinHandle<()>>,
+    /// 开启 HTTPS 监听时供 Codex 使用的本机明文监听
+    plain_handle: Option<JoinHandle<()>>,
 }
 
 /// 全局代理服务器实例
 static PROXY_SERVER: OnceLock<tokio::sync::RwLock<Option<ProxyServer>>> = OnceLock::new();
 
+/// 单个供应商的代理统计
+#[derive(Debug, Clone, Default, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ProviderProxyStats {
+    pub name: String,
+    pub attempts: u64,
+    pub successes: u64,
+    pub failures: u64,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub last_status: Option<u16>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub last_error: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub last_latency_ms: Option<u64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub last_used_at: Option<i64>,
+    /// 流式响应首字节延迟的滚动 p50 / p95（毫秒，见 `ttfb`）
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub ttfb_p50_ms: Option<u64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub ttfb_p95_ms: Option<u64>,
+}
+
+/// 代理运行统计（进程内累计，重启后清零）
+#[derive(Debug, Clone, Default, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ProxyStats {
+    pub running: bool,
+    pub total_requests: u64,
+    pub failed_requests: u64,
+    /// 应用类型 -> 供应商 ID -> 统计
+    pub providers: HashMap<String, HashMap<String, ProviderProxyStats>>,
+}
+
+/// 全局代理统计
+static PROXY_STATS: OnceLock<Mutex<ProxyStats>> = OnceLock::new();
+
+fn with_stats<F: FnOnce(&mut ProxyStats)>(f: F) {
+    if let Ok(mut stats) = PROXY_STATS
+        .get_or_init(|| Mutex::new(ProxyStats::default()))
+        .lock()
+    {
+        f(&mut stats);
+    }
+}
+
+/// 记录一次上游请求尝试
+fn record_attempt(
+    app_type: &AppType,
+    provider: &Provider,
+    started: Instant,
+    status: Option<StatusCode>,
+    error: Option<String>,
+) {
+    with_stats(|stats| {
+        let entry = stats
+            .providers
+            .entry(app_type.as_str().to_string())
+            .or_default()
+            .entry(provider.id.clone())
+            .or_default();
+        entry.name = provider.name.clone();
+        entry.attempts += 1;
+        if status == Some(StatusCode::OK) {
+            entry.successes += 1;
+            entry.last_error = None;
+        } else {
+            entry.failures += 1;
+            entry.last_error = error.or_else(|| status.map(|s| s.to_string()));
+        }
+        entry.last_status = status.map(|s| s.as_u16());
+        entry.last_latency_ms = Some(started.elapsed().as_millis() as u64);
+        entry.last_used_at = Some(chrono::Utc::now().timestamp_millis());
+    });
+}
+
+/// 供应商最近一次代理请求是否成功（无记录或记录已重置时返回 None）
+pub fn provider_health(app_type: &AppType, provider_id: &str) -> Option<bool> {
+    let stats = PROXY_STATS.get()?.lock().ok()?;
+    let entry = stats.providers.get(app_type.as_str())?.get(provider_id)?;
+    if entry.last_status.is_none() && entry.last_error.is_none() {
+        return None;
+    }
+    Some(entry.last_status == Some(StatusCode::OK.as_u16()))
+}
+
+/// 重置各供应商最近一次请求的结果：网络恢复后，之前的失败不再代表当前的健康状态
+pub fn reset_provider_health() {
+    with_stats(|stats| {
+        for entry in stats.providers.values_mut().flat_map(|m| m.values_mut()) {
+            entry.last_status = None;
+            entry.last_error = None;
+        }
+    });
+}
+
+/// 获取代理运行统计快照
+pub async fn get_proxy_stats() -> ProxyStats {
+    let running = match PROXY_SERVER.get() {
+        Some(server) => server.read().await.is_some(),
+        None => false,
+    };
+    let mut snapshot = PROXY_STATS
+        .get()
+        .and_then(|stats| stats.lock().ok().map(|s| s.clone()))
+        .unwrap_or_default();
+    snapshot.running = running;
+    for (app_type, providers) in snapshot.providers.iter_mut() {
+        for (provider_id, entry) in providers.iter_mut() {
+            if let Some((p50, p95)) = crate::ttfb::provider_percentiles(app_type, provider_id) {
+                entry.ttfb_p50_ms = Some(p50);
+                entry.ttfb_p95_ms = Some(p95);
+            }
+        }
+    }
+    snapshot
+}
+
 /// 初始化代理服务器全局实例
 pub fn init_proxy_server() {
     PROXY_SERVER.get_or_init(|| tokio::sync::RwLock::new(None));
@@ -55,7 +208,7 @@ const HOP_BY_HOP_HEADERS: &[&str] = &[
 /// 根据 User-Agent 判断应用类型
 /// 如果 User-Agent 包含 "claude"（忽略大小写），则为 Claude
 /// 否则为 Codex
-fn detect_app_type_from_user_agent(user_agent: Option<&str>) -> AppType {
+pub(crate) fn detect_app_type_from_user_agent(user_agent: Option<&str>) -> AppType {
     if let Some(ua) = user_agent {
         if ua.to_lowercase().contains("claude") {
             return AppType::Claude;
@@ -131,11 +284,60 @@ async fn get_enabled_proxy_providers(
     app_state: &AppState,
     app_type: &AppType,
 ) -> Result<Vec<Provider>, String> {
-    let config = app_state
-        .config
-        .lock()
-        .map_err(|e| format!("获取锁失败: {}", e))?;
+    // 快照在加载配置时即已生成；仅在尚未生成时读取一次配置
+    let snapshot = match PROVIDER_SNAPSHOT.load_full() {
+        Some(snapshot) => snapshot,
+        None => {
+            let config = app_state
+                .config
+                .lock()
+                .map_err(|e| format!("获取锁失败: {}", e))?;
+            refresh_provider_snapshot(&config)
+        }
+    };
+    let providers = match app_type {
+        AppType::Claude => snapshot.claude.clone(),
+        AppType::Codex => snapshot.codex.clone(),
+    };
+
+    // 维护模式与时间段路由规则随时间变化，按请求应用；置顶的供应商随后整体移到最前（保持相对顺序）
+    let providers = providers.into_iter().filter(|p| !p.in_maintenance()).collect();
+    let (mut ordered, rest): (Vec<Provider>, Vec<Provider>) = crate::routing::apply(providers)
+        .into_iter()
+        .partition(Provider::is_pinned);
+    ordered.extend(rest);
+    Ok(ordered)
+}
+
+/// 启用代理的供应商快照（已排序）：配置每次落盘后整体替换，
+/// 代理热路径只读取快照，不再持有配置锁，也不再逐请求排序
+pub struct ProviderSnapshot {
+    claude: Vec<Provider>,
+    codex: Vec<Provider>,
+}
+
+static PROVIDER_SNAPSHOT: ArcSwapOption<ProviderSnapshot> = ArcSwapOption::const_empty();
+
+/// 按当前配置重建代理供应商快照
+pub fn refresh_provider_snapshot(config: &MultiAppConfig) -> Arc<ProviderSnapshot> {
+    let sorted = |app_type: AppType| sorted_proxy_providers(config, &app_type).unwrap_or_default();
+    let snapshot = Arc::new(ProviderSnapshot {
+        claude: sorted(AppType::Claude),
+        codex: sorted(AppType::Codex),
+    });
+    PROVIDER_SNAPSHOT.store(Some(snapshot.clone()));
+    // 供应商主机映射变化后丢弃按旧地址建立的连接
+    if crate::dns::set_provider_hosts(snapshot.claude.iter().chain(&snapshot.codex)) {
+        reset_upstream_pool();
+    }
+    snapshot
+}
 
+/// 启用代理的供应商（按 sort_index 与创建时间排序）
+fn sorted_proxy_providers(
+    config: &MultiAppConfig,
+    app_type: &AppType,
+) -> Result<Vec<Provider>, String> {
     let manager = config
         .get_manager(app_type)
         .ok_or_else(|| format!("应用类型不存在: {:?}", app_type))?;
@@ -143,11 +345,13 @@ async fn get_enabled_proxy_providers(
     let mut providers: Vec<Provider> = manager
         .providers
         .values()
-        .filter(|p| p.proxy_enabled.unwrap_or(false))
+        // ChatGPT 登录的令牌与账号绑定且会过期，无法改写为 Bearer Key 转发
+        .filter(|p| p.proxy_enabled.unwrap_or(false) && p.is_enabled())
+        .filter(|p| p.credential_kind() != CredentialKind::CodexOauth)
         .cloned()
         .collect();
 
-    // 按 sort_index 排序
+    // 按 sort_index 排序（时间段路由规则在调用方应用）
     providers.sort_by(|a, b| {
         match (a.sort_index, b.sort_index) {
             (Some(idx_a), Some(idx_b)) => idx_a.cmp(&idx_b),
@@ -171,12 +375,275 @@ fn should_forward_header(header_name: &str) -> bool {
     !HOP_BY_HOP_HEADERS.contains(&header_name.to_lowercase().as_str())
 }
 
-/// 代理请求处理器
+/// 描述响应体编码的响应头：转换响应体后不再适用
+fn is_body_header(header_name: &str) -> bool {
+    matches!(
+        header_name.to_lowercase().as_str(),
+        "content-length" | "content-encoding" | "content-type"
+    )
+}
+
+/// 代理自身产生的错误响应：JSON 错误体，message 按当前语言本地化，便于客户端直接展示
+fn error_response(status: StatusCode, message: String) -> Response<Body> {
+    let body = serde_json::json!({
+        "type": "error",
+        "error": {
+            "type": "proxy_error",
+            "message": message,
+        }
+    });
+    let mut response = Response::new(Body::from(body.to_string()));
+    *response.status_mut() = status;
+    response.headers_mut().insert(
+        axum::http::header::CONTENT_TYPE,
+        axum::http::HeaderValue::from_static("application/json"),
+    );
+    response
+}
+
+/// 上游返回的错误响应：全部失败或超出请求时限时原样返回给客户端，比笼统的代理错误更有助于排查
+struct UpstreamFailure {
+    status: StatusCode,
+    content_type: Option<axum::http::HeaderValue>,
+    body: Bytes,
+}
+
+impl UpstreamFailure {
+    fn into_response(self) -> Response<Body> {
+        let mut response = Response::new(Body::from(self.body));
+        *response.status_mut() = self.status;
+        if let Some(content_type) = self.content_type {
+            response
+                .headers_mut()
+                .insert(axum::http::header::CONTENT_TYPE, content_type);
+        }
+        response
+    }
+}
+
+/// 因请求时限中断的尝试结果
+const TIMEOUT_OUTCOME: &str = "timeout";
+
+/// 全部失败时附带的尝试摘要响应头
+const ATTEMPTS_HEADER: &str = "x-ccswitch-attempts";
+
+/// 附加诊断响应头（设置中开启）：实际提供响应的供应商、此前失败的尝试次数与总耗时
+fn attach_diagnostics(
+    response: &mut Response<Body>,
+    provider: &Provider,
+    retries: usize,
+    received_at: Instant,
+) {
+    let name: String = url::form_urlencoded::byte_serialize(provider.name.as_bytes()).collect();
+    let latency = received_at.elapsed().as_millis().to_string();
+    let headers = [
+        ("x-ccswitch-provider", name),
+        ("x-ccswitch-retries", retries.to_string()),
+        ("x-ccswitch-latency-ms", latency),
+    ];
+    for (key, value) in headers {
+        if let Ok(value) = axum::http::HeaderValue::from_str(&value) {
+            response.headers_mut().insert(key, value);
+        }
+    }
+}
+
+/// 本次请求对各供应商的尝试结果（按尝试顺序）：状态码，或 error / timeout / skipped 等
+#[derive(Default)]
+struct AttemptLog(Vec<(String, Vec<String>)>);
+
+impl AttemptLog {
+    fn push(&mut self, provider: &Provider, outcome: impl Into<String>) {
+        match self.0.last_mut() {
+            Some((name, outcomes)) if *name == provider.name => outcomes.push(outcome.into()),
+            _ => self.0.push((provider.name.clone(), vec![outcome.into()])),
+        }
+    }
+
+    /// 未成功的尝试次数（含被跳过的供应商）
+    fn failures(&self) -> usize {
+        self.0.iter().map(|(_, outcomes)| outcomes.len()).sum()
+    }
+
+    /// 可读摘要，如 `A: 500, 500; B: 401`
+    fn summary(&self) -> String {
+        self.0
+            .iter()
+            .map(|(name, outcomes)| format!("{}: {}", name, outcomes.join(", ")))
+            .collect::<Vec<_>>()
+            .join("; ")
+    }
+
+    /// 写入响应头：供应商名称按 URL 编码（响应头只能是 ASCII），如 `A=500,500;B=401`
+    fn attach(&self, response: &mut Response<Body>) {
+        let value = self
+            .0
+            .iter()
+            .map(|(name, outcomes)| {
+                let name: String = url::form_urlencoded::byte_serialize(name.as_bytes()).collect();
+                format!("{}={}", name, outcomes.join(","))
+            })
+            .collect::<Vec<_>>()
+            .join(";");
+        if let Ok(value) = axum::http::HeaderValue::from_str(&value) {
+            response.headers_mut().insert(ATTEMPTS_HEADER, value);
+        }
+    }
+}
+
+/// 超出请求时限：返回最近一次上游错误，尚无上游响应时返回 504
+fn deadline_response(last_failure: Option<UpstreamFailure>) -> Response<Body> {
+    match last_failure {
+        Some(failure) => failure.into_response(),
+        None => error_response(StatusCode::GATEWAY_TIMEOUT, t("proxy.deadlineExceeded")),
+    }
+}
+
+/// 读取完整响应体，同时记录首个数据帧到达的耗时（流式响应的首字节延迟）
+async fn collect_timed(
+    mut body: hyper::body::Incoming,
+    started: Instant,
+) -> Result<(Bytes, Option<Duration>), hyper::Error> {
+    let mut data = Vec::new();
+    let mut first_byte = None;
+    while let Some(frame) = body.frame().await {
+        if let Ok(chunk) = frame?.into_data() {
+            if first_byte.is_none() && !chunk.is_empty() {
+                first_byte = Some(started.elapsed());
+            }
+            data.extend_from_slice(&chunk);
+        }
+    }
+    Ok((Bytes::from(data), first_byte))
+}
+
+/// 丢弃上游连接池与 DoH 解析缓存，下次请求重新解析并建立连接
+pub fn reset_upstream_pool() {
+    crate::upstream::reset();
+}
+
+/// 用指定供应商发送一次请求（请求录制的重放）：按代理的方式改写凭证与转换协议，
+/// 不重试也不切换供应商；返回状态码与（转换后的）响应体
+pub async fn send_once(
+    app_type: &AppType,
+    provider: &Provider,
+    method: &str,
+    path: &str,
+    headers: &[(String, String)],
+    body: Bytes,
+) -> Result<(u16, Bytes), String> {
+    let (api_key, base_url) = extract_provider_credentials(provider, app_type)?;
+    let translation = crate::translate::translation_for(app_type, provider, path);
+    let (target_url, body) = match translation {
+        Some(translation) => (
+            crate::translate::target_url(translation, &base_url),
+            Bytes::from(crate::translate::request(translation, provider, &body)?),
+        ),
+        None => (format!("{}{}", base_url.trim_end_matches('/'), path), body),
+    };
+    let stream = translation.is_some() && crate::translate::is_stream(&body);
+
+    let mut req = Request::builder()
+        .method(method)
+        .uri(&target_url)
+        .body(Body::from(body))
+        .map_err(|e| e.to_string())?;
+    for (name, value) in headers {
+        let name = name.to_lowercase();
+        // 录制时已脱敏的凭证头与压缩协商不再发送，响应需以明文展示
+        if !should_forward_header(&name)
+            || matches!(name.as_str(), "authorization" | "accept-encoding" | "content-length")
+            || crate::redact::is_masked(value)
+            || (translation.is_some() && crate::translate::skip_header(&name))
+        {
+            continue;
+        }
+        if let (Ok(name), Ok(value)) = (
+            axum::http::HeaderName::from_bytes(name.as_bytes()),
+            axum::http::HeaderValue::from_str(value),
+        ) {
+            req.headers_mut().insert(name, value);
+        }
+    }
+    req.headers_mut().insert(
+        "authorization",
+        axum::http::HeaderValue::from_str(&format!("Bearer {}", api_key))
+            .map_err(|e| e.to_string())?,
+    );
+    for (name, value) in translation
+        .map(|translation| crate::translate::extra_headers(translation, &api_key))
+        .unwrap_or_default()
+    {
+        if let Ok(value) = axum::http::HeaderValue::from_str(&value) {
+            req.headers_mut().insert(name, value);
+        }
+    }
+
+    let response = crate::upstream::client_for(provider)?
+        .request(req)
+        .await
+        .map_err(|e| e.to_string())?;
+    let status = response.status();
+    let body = response
+        .into_body()
+        .collect()
+        .await
+        .map_err(|e| e.to_string())?
+        .to_bytes();
+    let body = match translation {
+        Some(translation) if status == StatusCode::OK => {
+            Bytes::from(crate::translate::response(translation, &body, stream))
+        }
+        Some(translation) => Bytes::from(crate::translate::error(translation, &body)),
+        None => body,
+    };
+    Ok((status.as_u16(), body))
+}
+
+/// 返回给客户端的请求 ID 响应头，便于与日志对照排查
+const REQUEST_ID_HEADER: &str = "x-cc-switch-request-id";
+
+/// 代理请求入口：分配请求 ID，并让本次请求的全部日志（含重试与多供应商尝试）都携带该 ID
 async fn proxy_handler(
     AxumState(state): AxumState<Arc<ProxyState>>,
     uri: Uri,
     req: Request<Body>,
-) -> Result<Response<Body>, StatusCode> {
+) -> Response<Body> {
+    let request_id = uuid::Uuid::new_v4().simple().to_string()[..12].to_string();
+    let span = tracing::info_span!("proxy", request_id = %request_id);
+
+    let (req, capture) = crate::capture::begin(req).await;
+    let mut response = match forward_request(state, uri, req).instrument(span).await {
+        Ok(response) | Err(response) => response,
+    };
+    if let Some(pending) = capture {
+        response = crate::capture::finish(pending, response).await;
+    }
+    if let Ok(value) = axum::http::HeaderValue::from_str(&request_id) {
+        response.headers_mut().insert(REQUEST_ID_HEADER, value);
+    }
+    response
+}
+
+/// 局域网请求入口：校验来源 IP 与访问令牌后按本机请求处理
+async fn lan_proxy_handler(
+    state: AxumState<Arc<ProxyState>>,
+    ConnectInfo(addr): ConnectInfo<SocketAddr>,
+    uri: Uri,
+    mut req: Request<Body>,
+) -> Response<Body> {
+    if let Err((status, message)) = crate::lan_share::authorize(&addr, req.headers_mut()) {
+        return error_response(status, message);
+    }
+    proxy_handler(state, uri, req).await
+}
+
+/// 按供应商顺序转发请求，失败时重试或切换到下一个供应商
+async fn forward_request(
+    state: Arc<ProxyState>,
+    uri: Uri,
+    req: Request<Body>,
+) -> Result<Response<Body>, Response<Body>> {
     // 获取请求路径（包含查询参数）
     let request_path = uri.path_and_query().map(|pq| pq.as_str()).unwrap_or("/");
 
@@ -187,76 +654,194 @@ async fn proxy_handler(
     let user_agent = parts.headers.get("user-agent").and_then(|v| v.to_str().ok());
     let app_type = detect_app_type_from_user_agent(user_agent);
 
+    // 请求到达时间（诊断响应头中的总耗时从此刻算起）
+    let received_at = Instant::now();
+
     // 读取请求体
     let body_bytes = match body.collect().await {
         Ok(collected) => collected.to_bytes(),
         Err(e) => {
-            log::error!("读取请求体失败: {}", e);
-            return Err(StatusCode::BAD_REQUEST);
+            tracing::error!("读取请求体失败: {}", e);
+            return Err(error_response(
+                StatusCode::BAD_REQUEST,
+                t("proxy.readBodyFailed"),
+            ));
         }
     };
 
     // 读取设置获取重试次数
     let settings = crate::settings::get_settings();
     let retry_count = settings.proxy_retry_count;
+    let deadline = (settings.proxy_deadline_secs > 0)
+        .then(|| Instant::now() + Duration::from_secs(u64::from(settings.proxy_deadline_secs)));
+
+    // 许多中转未实现 count_tokens：按设置在本地估算，避免该接口逐个供应商失败并触发故障转移
+    if settings.proxy_local_count_tokens && crate::count_tokens::is_count_tokens(request_path) {
+        if let Some(response) = crate::count_tokens::respond(&body_bytes) {
+            return Ok(response);
+        }
+    }
 
     // 获取对应应用类型的启用代理供应商
     let providers = match get_enabled_proxy_providers(&state.app_state, &app_type).await {
         Ok(p) => p,
         Err(e) => {
-            log::error!("获取启用代理的供应商失败: {}", e);
-            return Err(StatusCode::INTERNAL_SERVER_ERROR);
+            tracing::error!("获取启用代理的供应商失败: {}", e);
+            return Err(error_response(
+                StatusCode::INTERNAL_SERVER_ERROR,
+                t("proxy.providersLoadFailed"),
+            ));
         }
     };
 
+    // 按请求的模型与路径调整供应商顺序
+    let providers = crate::request_routes::route(&app_type, request_path, &body_bytes, providers);
+
     if providers.is_empty() {
-        log::error!("没有启用代理的 {:?} 供应商", app_type);
-        return Err(StatusCode::SERVICE_UNAVAILABLE);
+        tracing::error!("没有启用代理的 {:?} 供应商", app_type);
+        return Err(error_response(
+            StatusCode::SERVICE_UNAVAILABLE,
+            t_args("proxy.noProviders", &[("app", &app_type.as_str())]),
+        ));
     }
 
-    // 创建支持 HTTP 和 HTTPS 的客户端（.https_or_http() 会自动根据 URL 协议选择）
-    let https_connector = HttpsConnectorBuilder::new()
-        .with_webpki_roots()
-        .https_or_http()  // 同时支持 http:// 和 https://
-        .enable_http1()
-        .enable_http2()
-        .build();
-    let client = Client::builder(TokioExecutor::new()).build(https_connector);
+    with_stats(|stats| stats.total_requests += 1);
+
+    // 请求的可选接口（如 count_tokens）：跳过已知不支持的供应商
+    let endpoint = crate::capabilities::endpoint(request_path);
 
     // 遍历供应商并尝试请求
-    for provider in providers.iter() {
+    let mut limited = 0;
+    let mut unsupported = 0;
+    let mut last_failure: Option<UpstreamFailure> = None;
+    let mut attempts = AttemptLog::default();
+    for (index, provider) in providers.iter().enumerate() {
+        // 近期鉴权失败：Key 大概率配置错误，冷却期内不再尝试
+        if crate::auth_failure::is_blocked(&app_type, provider) {
+            tracing::info!(provider = %provider.name, "供应商处于鉴权失败冷却期，跳过");
+            attempts.push(provider, "auth_blocked");
+            continue;
+        }
+
+        // 已达到限流上限：直接交给下一个供应商，避免触发上游 429
+        if crate::rate_limit::is_limited(&app_type, provider) {
+            tracing::info!(provider = %provider.name, "已达到限流上限，跳过");
+            attempts.push(provider, "rate_limited");
+            limited += 1;
+            continue;
+        }
+
+        if let Some(endpoint) =
+            endpoint.filter(|endpoint| crate::capabilities::is_unsupported(provider, endpoint))
+        {
+            tracing::info!(provider = %provider.name, "供应商不支持 {} 接口，跳过", endpoint);
+            attempts.push(provider, "unsupported");
+            unsupported += 1;
+            continue;
+        }
 
         // 提取凭证
         let (api_key, base_url) = match extract_provider_credentials(provider, &app_type) {
             Ok(creds) => creds,
             Err(e) => {
-                log::warn!("供应商 {} 凭证提取失败: {}", provider.name, e);
+                tracing::warn!(provider = %provider.name, "供应商凭证提取失败: {}", e);
+                attempts.push(provider, "no_credentials");
                 continue;
             }
         };
 
-        // 构建目标URL
-        let target_url = format!("{}{}", base_url.trim_end_matches('/'), request_path);
+        // 供应商的上游客户端（覆盖了连接主机或 SNI 时使用独立的连接池）
+        let client = match crate::upstream::client_for(provider) {
+            Ok(client) => client,
+            Err(e) => {
+                tracing::warn!(provider = %provider.name, "创建上游客户端失败: {}", e);
+                attempts.push(provider, "connector_failed");
+                continue;
+            }
+        };
+
+        // 按需标注供应商
+        let provider_body = if settings.proxy_provider_banner {
+            crate::provider_banner::inject(request_path, &body_bytes, provider)
+        } else {
+            body_bytes.clone()
+        };
+
+        // 上游接口格式与客户端不同时转换请求
+        let translation = crate::translate::translation_for(&app_type, provider, request_path);
+        let upstream_body = match translation {
+            Some(translation) => {
+                match crate::translate::request(translation, provider, &provider_body) {
+                    Ok(body) => Bytes::from(body),
+                    Err(e) => {
+                        tracing::warn!(provider = %provider.name, "请求格式转换失败: {}", e);
+                        attempts.push(provider, "translate_failed");
+                        continue;
+                    }
+                }
+            }
+            None => provider_body,
+        };
+        let stream = translation.is_some() && crate::translate::is_stream(&body_bytes);
+
+        // 构建目标URL：开启端点故障转移时每个端点一个，重试依次轮换端点
+        let endpoints = crate::endpoint_failover::candidates(provider, &base_url);
+        let target_urls: Vec<String> = endpoints
+            .iter()
+            .map(|base_url| match translation {
+                Some(translation) => crate::translate::target_url(translation, base_url),
+                None => format!("{}{}", base_url, request_path),
+            })
+            .collect();
+        // 至少让每个端点都尝试一次
+        let max_retry = retry_count.max(target_urls.len() as u32 - 1);
 
         // 重试逻辑
-        for retry in 0..=retry_count {
+        for retry in 0..=max_retry {
+            let endpoint_index = retry as usize % target_urls.len();
+            let target_url = &target_urls[endpoint_index];
+            if endpoint_index > 0 {
+                tracing::info!(
+                    provider = %provider.name,
+                    retry,
+                    "尝试备用端点: {}",
+                    endpoints[endpoint_index]
+                );
+            }
+
+            // 超出请求总时限：不再尝试，返回最近一次上游错误
+            if deadline.is_some_and(|deadline| Instant::now() >= deadline) {
+                tracing::warn!("已超出请求时限: {}", request_path);
+                with_stats(|stats| stats.failed_requests += 1);
+                let mut response = deadline_response(last_failure);
+                attempts.attach(&mut response);
+                return Err(response);
+            }
+
+            if retry > 0 && crate::rate_limit::is_limited(&app_type, provider) {
+                tracing::info!(provider = %provider.name, retry, "已达到限流上限，停止重试");
+                break;
+            }
 
             // 构建新请求
             let mut new_req = match Request::builder()
                 .method(parts.method.clone())
-                .uri(&target_url)
-                .body(Body::from(body_bytes.clone()))
+                .uri(target_url.as_str())
+                .body(Body::from(upstream_body.clone()))
             {
                 Ok(req) => req,
                 Err(e) => {
-                    log::error!("构建请求失败: {}", e);
+                    tracing::error!(provider = %provider.name, retry, "构建请求失败: {}", e);
                     break; // 跳出重试，尝试下一个供应商
                 }
             };
 
             // 复制 headers，过滤 hop-by-hop headers，并重写 Authorization
             for (name, value) in parts.headers.iter() {
-                if should_forward_header(name.as_str()) && name.as_str().to_lowercase() != "authorization" {
+                if should_forward_header(name.as_str())
+                    && name.as_str().to_lowercase() != "authorization"
+                    && !(translation.is_some() && crate::translate::skip_header(name.as_str()))
+                {
                     new_req.headers_mut().insert(name.clone(), value.clone());
                 }
             }
@@ -266,58 +851,252 @@ async fn proxy_handler(
                 "authorization",
                 format!("Bearer {}", api_key).parse().unwrap(),
             );
+            for (name, value) in translation
+                .map(|translation| crate::translate::extra_headers(translation, &api_key))
+                .unwrap_or_default()
+            {
+                if let Ok(value) = axum::http::HeaderValue::from_str(&value) {
+                    new_req.headers_mut().insert(name, value);
+                }
+            }
 
             // 发送请求
-            match client.request(new_req).await {
+            let started = Instant::now();
+            crate::rate_limit::record_request(&app_type, provider);
+            let sent = client.request(new_req);
+            // 错误附带"是否在建立连接时失败"：此时请求尚未发出，重发是安全的
+            let result = match deadline {
+                Some(deadline) => match tokio::time::timeout_at(deadline.into(), sent).await {
+                    Ok(result) => result.map_err(|e| (e.to_string(), e.is_connect())),
+                    Err(_) => Err((TIMEOUT_OUTCOME.to_string(), false)),
+                },
+                None => sent.await.map_err(|e| (e.to_string(), e.is_connect())),
+            };
+            match result {
                 Ok(response) => {
                     let status = response.status();
+                    record_attempt(&app_type, provider, started, Some(status), None);
+                    crate::key_stats::record(&app_type, provider, &api_key, Some(status.as_u16()));
+                    crate::daily_usage::record(&app_type, provider, status == StatusCode::OK);
 
                     // 只有 200 才算成功，其他状态都重试
                     if status == StatusCode::OK {
                         // 转换响应体
                         let (resp_parts, incoming_body) = response.into_parts();
-                        let body_bytes = match incoming_body.collect().await {
-                            Ok(collected) => collected.to_bytes(),
+                        let streaming = crate::translate::is_stream(&upstream_body)
+                            || resp_parts
+                                .headers
+                                .get(axum::http::header::CONTENT_TYPE)
+                                .and_then(|v| v.to_str().ok())
+                                .is_some_and(|v| v.starts_with("text/event-stream"));
+                        let body_bytes = match collect_timed(incoming_body, started).await {
+                            Ok((body_bytes, ttfb)) => {
+                                if let (true, Some(ttfb)) = (streaming, ttfb) {
+                                    crate::ttfb::record(&app_type, provider, ttfb);
+                                }
+                                body_bytes
+                            }
                             Err(e) => {
-                                log::error!("读取响应体失败: {}", e);
-                                return Err(StatusCode::BAD_GATEWAY);
+                                tracing::error!(provider = %provider.name, "读取响应体失败: {}", e);
+                                return Err(error_response(
+                                    StatusCode::BAD_GATEWAY,
+                                    t("proxy.upstreamBodyFailed"),
+                                ));
                             }
                         };
+                        let tokens = crate::rate_limit::token_usage(&body_bytes);
+                        crate::rate_limit::record_tokens(&app_type, provider, tokens);
+                        crate::daily_usage::record_tokens(&app_type, provider, tokens);
+                        let body_bytes = match translation {
+                            Some(translation) => Bytes::from(crate::translate::response(
+                                translation,
+                                &body_bytes,
+                                stream,
+                            )),
+                            None => body_bytes,
+                        };
 
                         // 构建响应，过滤 hop-by-hop headers
                         let mut final_response = Response::new(Body::from(body_bytes));
                         *final_response.status_mut() = resp_parts.status;
 
                         for (name, value) in resp_parts.headers.iter() {
-                            if should_forward_header(name.as_str()) {
+                            if should_forward_header(name.as_str())
+                                && !(translation.is_some() && is_body_header(name.as_str()))
+                            {
                                 final_response.headers_mut().insert(name.clone(), value.clone());
                             }
                         }
+                        if translation.is_some() {
+                            final_response.headers_mut().insert(
+                                axum::http::header::CONTENT_TYPE,
+                                axum::http::HeaderValue::from_static(
+                                    crate::translate::content_type(stream),
+                                ),
+                            );
+                        }
 
+                        tracing::info!(provider = %provider.name, retry, "{}: {}", request_path, status);
+                        // 前面的供应商均失败，由后续供应商接管
+                        if index > 0 {
+                            let failed: Vec<&str> =
+                                providers[..index].iter().map(|p| p.name.as_str()).collect();
+                            crate::webhook::notify(
+                                WebhookEvent::Failover,
+                                &format!("{}:{}", app_type.as_str(), provider.id),
+                                serde_json::json!({
+                                    "appType": app_type.as_str(),
+                                    "provider": provider.name,
+                                    "failedProviders": failed,
+                                    "path": request_path,
+                                }),
+                            );
+                        }
+                        final_response.extensions_mut().insert(crate::capture::ServedBy {
+                            id: provider.id.clone(),
+                            name: provider.name.clone(),
+                        });
+                        if settings.proxy_diagnostic_headers {
+                            attach_diagnostics(
+                                &mut final_response,
+                                provider,
+                                attempts.failures(),
+                                received_at,
+                            );
+                        }
                         return Ok(final_response);
                     } else {
-                        log::warn!(
-                            "供应商 {} -> {}: {}",
-                            provider.name,
+                        tracing::warn!(
+                            provider = %provider.name,
+                            retry,
+                            "{}: {}",
                             request_path,
                             status
                         );
+                        attempts.push(provider, status.as_u16().to_string());
+                        let (resp_parts, incoming_body) = response.into_parts();
+                        let body = incoming_body
+                            .collect()
+                            .await
+                            .map(|collected| collected.to_bytes())
+                            .unwrap_or_default();
+                        last_failure = Some(match translation {
+                            Some(translation) => UpstreamFailure {
+                                status,
+                                content_type: Some(axum::http::HeaderValue::from_static(
+                                    "application/json",
+                                )),
+                                body: Bytes::from(crate::translate::error(translation, &body)),
+                            },
+                            None => UpstreamFailure {
+                                status,
+                                content_type: resp_parts
+                                    .headers
+                                    .get(axum::http::header::CONTENT_TYPE)
+                                    .cloned(),
+                                body,
+                            },
+                        });
+                        // 可选接口返回 404/405：记为不支持，直接尝试下一个
+                        if let Some(endpoint) =
+                            endpoint.filter(|_| matches!(status.as_u16(), 404 | 405))
+                        {
+                            crate::capabilities::learn_unsupported(
+                                &state.app_state,
+                                &app_type,
+                                &provider.id,
+                                endpoint,
+                            );
+                            break;
+                        }
+                        // 401/403 重试无济于事：拉黑该供应商并直接尝试下一个
+                        if crate::auth_failure::is_auth_failure(status.as_u16()) {
+                            crate::auth_failure::mark(&app_type, provider, status.as_u16());
+                            break;
+                        }
                     }
                 }
-                Err(e) => {
-                    log::warn!("供应商 {} -> {}: {}", provider.name, request_path, e);
+                Err((e, connect_failed)) => {
+                    tracing::warn!(provider = %provider.name, retry, "{}: {}", request_path, e);
+                    let timed_out = e == TIMEOUT_OUTCOME;
+                    attempts.push(provider, if timed_out { TIMEOUT_OUTCOME } else { "error" });
+                    record_attempt(&app_type, provider, started, None, Some(e));
+                    crate::key_stats::record(&app_type, provider, &api_key, None);
+                    if connect_failed {
+                        crate::endpoint_failover::mark_unhealthy(&endpoints[endpoint_index]);
+                    }
+                    crate::daily_usage::record(&app_type, provider, false);
+
+                    // 连接建立后才中断：请求可能已到达上游，非幂等请求重发可能重复计费，
+                    // 除非开启了激进重试，否则不再重试或切换供应商
+                    if !connect_failed
+                        && !timed_out
+                        && !parts.method.is_idempotent()
+                        && !settings.proxy_aggressive_retry
+                    {
+                        tracing::warn!(
+                            provider = %provider.name,
+                            "请求发出后连接中断，为避免重复计费不再重试: {}",
+                            request_path
+                        );
+                        with_stats(|stats| stats.failed_requests += 1);
+                        let mut response = error_response(
+                            StatusCode::BAD_GATEWAY,
+                            t_args("proxy.upstreamInterrupted", &[("provider", &provider.name)]),
+                        );
+                        attempts.attach(&mut response);
+                        return Err(response);
+                    }
                 }
             }
 
             // 重试前等待一小段时间
-            if retry < retry_count {
+            if retry < max_retry {
                 tokio::time::sleep(tokio::time::Duration::from_millis(100)).await;
             }
         }
     }
 
-    // log::error!("所有代理供应商都失败");
-    Err(StatusCode::INTERNAL_SERVER_ERROR)
+    with_stats(|stats| stats.failed_requests += 1);
+    // 全部供应商都因限流被跳过：告知客户端稍后重试
+    if limited == providers.len() {
+        tracing::warn!("所有代理供应商均已达到限流上限: {}", request_path);
+        let mut response = error_response(
+            StatusCode::TOO_MANY_REQUESTS,
+            t("proxy.allProvidersRateLimited"),
+        );
+        attempts.attach(&mut response);
+        return Err(response);
+    }
+    // 全部供应商都已知不支持该接口
+    if let Some(endpoint) = endpoint.filter(|_| unsupported == providers.len()) {
+        let mut response = error_response(
+            StatusCode::NOT_FOUND,
+            t_args("proxy.endpointUnsupported", &[("endpoint", &endpoint)]),
+        );
+        attempts.attach(&mut response);
+        return Err(response);
+    }
+    tracing::error!("所有代理供应商都失败: {}: {}", request_path, attempts.summary());
+    crate::webhook::notify(
+        WebhookEvent::AllProvidersFailed,
+        app_type.as_str(),
+        serde_json::json!({
+            "appType": app_type.as_str(),
+            "providers": providers.iter().map(|p| p.name.as_str()).collect::<Vec<_>>(),
+            "path": request_path,
+        }),
+    );
+    // 原样返回最近一次上游错误，便于客户端展示真实原因；全部为网络错误时返回 502 与尝试摘要
+    let mut response = match last_failure {
+        Some(failure) => failure.into_response(),
+        None => error_response(
+            StatusCode::BAD_GATEWAY,
+            t_args("proxy.allProvidersFailed", &[("attempts", &attempts.summary())]),
+        ),
+    };
+    attempts.attach(&mut response);
+    Err(response)
 }
 
 /// 启动代理服务器
@@ -343,26 +1122,99 @@ pub async fn start_proxy_server(app_state: &AppState) -> Result<(), String> {
 
     let app = Router::new()
         .fallback(proxy_handler)
-        .with_state(proxy_state)
+        .with_state(proxy_state.clone())
         .layer(ServiceBuilder::new());
 
-    let listener = tokio::net::TcpListener::bind("127.0.0.1:12857")
+    let tls = if crate::proxy_tls::is_enabled() {
+        Some(crate::proxy_tls::rustls_config().await?)
+    } else {
+        None
+    };
+
+    let listener = tokio::net::TcpListener::bind(PROXY_ADDR)
         .await
         .map_err(|e| format!("绑定代理端口失败: {}", e))?;
 
-    log::info!("代理服务器启动在 http://127.0.0.1:12857");
+    // HTTPS 监听时为 Codex 保留回环地址上的明文端口
+    let plain_handle = if tls.is_some() {
+        let plain_listener = tokio::net::TcpListener::bind(PROXY_PLAIN_ADDR)
+            .await
+            .map_err(|e| format!("绑定代理端口失败: {}", e))?;
+        tracing::info!("Codex 明文代理监听 {}", codex_proxy_url());
+        Some(spawn_server(plain_listener, app.clone(), None))
+    } else {
+        None
+    };
 
-    let handle = tokio::spawn(async move {
-        if let Err(e) = axum::serve(listener, app).await {
-            log::error!("代理服务器运行错误: {}", e);
+    tracing::info!("代理服务器启动在 {}", proxy_url());
+
+    // 局域网共享：绑定失败不影响本机代理
+    let mut lan_handle = None;
+    if let Some(addr) = crate::lan_share::listen_addr() {
+        match tokio::net::TcpListener::bind(&addr).await {
+            Ok(lan_listener) => {
+                let lan_app = Router::new()
+                    .fallback(lan_proxy_handler)
+                    .with_state(proxy_state)
+                    .layer(ServiceBuilder::new());
+                tracing::info!("代理已对局域网开放: {}", addr);
+                crate::lan_discovery::advertise(&settings.lan_share, tls.is_some());
+                lan_handle = Some(spawn_server(lan_listener, lan_app, tls.clone()));
+            }
+            Err(e) => tracing::warn!("绑定局域网共享端口 {} 失败: {}", addr, e),
         }
-    });
+    }
+
+    // 本机代理由监管任务运行：服务意外退出后自动重新绑定端口
+    let handle = tokio::spawn(crate::proxy_supervisor::supervise(listener, app, tls));
 
-    *server_guard = Some(ProxyServer { handle });
+    *server_guard = Some(ProxyServer {
+        handle,
+        lan_handle,
+        plain_handle,
+    });
 
     Ok(())
 }
 
+/// 在已绑定的端口上运行代理直至服务结束（开启 HTTPS 监听时使用 TLS）
+pub(crate) async fn serve(
+    listener: tokio::net::TcpListener,
+    app: Router,
+    tls: Option<RustlsConfig>,
+) -> std::io::Result<()> {
+    let service = app.into_make_service_with_connect_info::<SocketAddr>();
+    match tls {
+        Some(config) => {
+            axum_server::from_tcp_rustls(listener.into_std()?, config)
+                .serve(service)
+                .await
+        }
+        None => axum::serve(listener, service).await,
+    }
+}
+
+/// 在后台运行代理，出错时只记录日志（局域网共享监听使用）
+fn spawn_server(
+    listener: tokio::net::TcpListener,
+    app: Router,
+    tls: Option<RustlsConfig>,
+) -> JoinHandle<()> {
+    tokio::spawn(async move {
+        if let Err(e) = serve(listener, app, tls).await {
+            tracing::error!("代理服务器运行错误: {}", e);
+        }
+    })
+}
+
+/// 代理服务器是否正在运行
+pub async fn is_proxy_running() -> bool {
+    match PROXY_SERVER.get() {
+        Some(server) => server.read().await.is_some(),
+        None => false,
+    }
+}
+
 /// 停止代理服务器
 pub async fn stop_proxy_server() -> Result<(), String> {
     let mut server_guard = PROXY_SERVER
@@ -373,66 +1225,102 @@ pub async fn stop_proxy_server() -> Result<(), String> {
 
     if let Some(server) = server_guard.take() {
         server.handle.abort();
-        log::info!("代理服务器已停止");
+        if let Some(lan_handle) = server.lan_handle {
+            lan_handle.abort();
+            crate::lan_discovery::unadvertise();
+        }
+        if let Some(plain_handle) = server.plain_handle {
+            plain_handle.abort();
+        }
+        tracing::info!("代理服务器已停止");
     }
 
     Ok(())
 }
 
+/// 确认本机代理端口仍可连接，否则重启代理（睡眠唤醒、网络变化后调用）
+pub async fn verify_proxy_listener(app_state: &AppState) -> Result<(), String> {
+    if crate::settings::get_settings().operation_mode != OperationMode::Proxy
+        || !is_proxy_running().await
+    {
+        return Ok(());
+    }
+    let connect = tokio::net::TcpStream::connect(PROXY_ADDR);
+    if matches!(tokio::time::timeout(Duration::from_secs(2), connect).await, Ok(Ok(_))) {
+        return Ok(());
+    }
+    tracing::warn!("代理端口 {} 无法连接，重启代理服务器", PROXY_ADDR);
+    restart_proxy_server(app_state).await
+}
+
+/// 重启代理服务器（监听相关设置变更后调用，非代理模式下只会停止）
+pub async fn restart_proxy_server(app_state: &AppState) -> Result<(), String> {
+    stop_proxy_server().await?;
+    start_proxy_server(app_state).await
+}
+
+/// 覆盖 auth.json 前，把 live 中刷新过的 ChatGPT 登录令牌回填到当前 Codex 供应商，
+/// 以便退出代理模式时恢复的仍是最新令牌
+fn backfill_codex_oauth(app_state: &AppState) -> Result<(), String> {
+    let auth_path = crate::codex_config::get_codex_auth_path();
+    if !auth_path.exists() {
+        return Ok(());
+    }
+    let auth: serde_json::Value = crate::config::read_json_file(&auth_path)?;
+    if !crate::codex_config::is_chatgpt_auth(&auth) {
+        return Ok(());
+    }
+    {
+        let mut config = app_state
+            .config
+            .lock()
+            .map_err(|e| format!("获取锁失败: {}", e))?;
+        let Some(manager) = config.get_manager_mut(&AppType::Codex) else {
+            return Ok(());
+        };
+        let current = manager.current.clone();
+        let Some(provider) = manager.providers.get_mut(&current) else {
+            return Ok(());
+        };
+        let live = serde_json::json!({ "auth": auth });
+        if provider.credential_kind() != CredentialKind::CodexOauth
+            || !crate::codex_config::can_backfill_auth(&provider.settings_config, &live)
+        {
+            return Ok(());
+        }
+        if let Some(obj) = provider.settings_config.as_object_mut() {
+            obj.insert("auth".to_string(), live["auth"].clone());
+        }
+    }
+    app_state.save_now()
+}
+
 /// 在代理模式下写入配置文件
 /// 将供应商的ANTHROPIC_BASE_URL或base_url替换为代理地址，
-/// 并使用固定token
-pub fn write_proxy_mode_config(
-    app_type: &AppType,
-    _app_state: &AppState,
-    common_config: Option<&str>,
-) -> Result<(), String> {
-    const PROXY_URL: &str = "http://127.0.0.1:12857";
-    const PROXY_TOKEN: &str = "ccswitch-proxymode-token";
-
+/// 并使用固定token；通用配置片段合并到代理模板中（模板中的键优先）
+pub fn write_proxy_mode_config(app_type: &AppType, app_state: &AppState) -> Result<(), String> {
     match app_type {
         AppType::Claude => {
-            let settings_path = crate::config::get_claude_settings_path();
-
             // 创建代理模式配置
             let mut proxy_config = serde_json::json!({
                 "env": {
                     "ANTHROPIC_AUTH_TOKEN": PROXY_TOKEN,
-                    "ANTHROPIC_BASE_URL": PROXY_URL,
+                    "ANTHROPIC_BASE_URL": proxy_url(),
                 }
             });
-
-            // 如果有 Claude 通用配置，合并进去
-            if let Some(common) = common_config {
-                if let Ok(common_obj) = serde_json::from_str::<serde_json::Value>(common) {
-                    if let Some(common_map) = common_obj.as_object() {
-                        if let Some(proxy_map) = proxy_config.as_object_mut() {
-                            for (key, value) in common_map {
-                                if key == "env" {
-                                    // 特殊处理 env 字段：合并但忽略特定的键
-                                    if let Some(common_env) = value.as_object() {
-                                        if let Some(proxy_env) = proxy_map.get_mut("env").and_then(|v| v.as_object_mut()) {
-                                            for (env_key, env_value) in common_env {
-                                                // 忽略 ANTHROPIC_AUTH_TOKEN 和 ANTHROPIC_BASE_URL
-                                                if env_key != "ANTHROPIC_AUTH_TOKEN" && env_key != "ANTHROPIC_BASE_URL" {
-                                                    proxy_env.insert(env_key.clone(), env_value.clone());
-                                                }
-                                            }
-                                        }
-                                    }
-                                } else {
-                                    // 其他字段直接合并
-                                    proxy_map.insert(key.clone(), value.clone());
-                                }
-                            }
-                        }
-                    }
-                }
+            // HTTPS 监听使用自签名证书，让 Claude Code（Node）信任该证书
+            if crate::proxy_tls::is_enabled() {
+                proxy_config["env"]["NODE_EXTRA_CA_CERTS"] =
+                    serde_json::json!(crate::proxy_tls::cert_path().to_string_lossy());
             }
+            let dir = crate::live_config::live_dir(app_type, None);
+            let proxy_config = crate::common_config::merged(app_type, &proxy_config, &dir);
 
-            crate::config::write_json_file(&settings_path, &proxy_config)?;
+            crate::config::write_claude_settings_atomic(&proxy_config)?;
         }
         AppType::Codex => {
+            backfill_codex_oauth(app_state)?;
+
             // 创建auth.json代理配置
             let auth_config = serde_json::json!({
                 "OPENAI_API_KEY": PROXY_TOKEN
@@ -441,26 +1329,17 @@ pub fn write_proxy_mode_config(
             let auth_path = crate::codex_config::get_codex_auth_path();
             crate::config::write_json_file(&auth_path, &auth_config)?;
 
-            // 使用传入的通用配置，如果没有则为空
-            let common_config_text = common_config.unwrap_or("");
-
-            // 构建代理模式配置：固定模板 + 传入的通用配置
-            let proxy_config = format!(
-                r#"model_provider = "ccswitch"
-
-[model_providers.ccswitch]
-base_url = "{}"
-name = "ccswitch"
-requires_openai_auth = true
-wire_api = "responses"
-{}"#,
-                PROXY_URL,
-                if common_config_text.is_empty() {
-                    String::new()
-                } else {
-                    format!("\n{}", common_config_text)
-                }
+            // 构建代理模式配置：固定模板 + 通用配置片段（结构化合并，片段中的同名键不会重复）
+            let proxy_config = codex_proxy_template();
+            let merged = crate::common_config::merged(
+                app_type,
+                &serde_json::json!({ "auth": auth_config, "config": proxy_config }),
+                &crate::live_config::live_dir(app_type, None),
             );
+            let proxy_config = merged
+                .get("config")
+                .and_then(|v| v.as_str())
+                .unwrap_or(&proxy_config);
 
             let config_path = crate::codex_config::get_codex_config_path();
             std::fs::write(&config_path, proxy_config)
@@ -471,115 +1350,113 @@ wire_api = "responses"
     Ok(())
 }
 
+/// Codex 代理模式的 config.toml 模板
+fn codex_proxy_template() -> String {
+    let mut provider = toml_edit::Table::new();
+    provider.insert("base_url", toml_edit::value(codex_proxy_url()));
+    provider.insert("name", toml_edit::value("ccswitch"));
+    provider.insert("requires_openai_auth", toml_edit::value(true));
+    provider.insert("wire_api", toml_edit::value("responses"));
+    let mut providers = toml_edit::Table::new();
+    providers.set_implicit(true);
+    providers.insert("ccswitch", toml_edit::Item::Table(provider));
+
+    let mut doc = toml_edit::DocumentMut::new();
+    doc.insert("model_provider", toml_edit::value("ccswitch"));
+    doc.insert("model_providers", toml_edit::Item::Table(providers));
+    doc.to_string()
+}
+
 /// 切换到代理模式时的配置更新
-pub fn switch_to_proxy_mode(
-    app_state: &AppState,
-    claude_common_config: Option<&str>,
-    codex_common_config: Option<&str>,
-) -> Result<(), String> {
-    write_proxy_mode_config(&AppType::Claude, app_state, claude_common_config)?;
-    write_proxy_mode_config(&AppType::Codex, app_state, codex_common_config)?;
+pub fn switch_to_proxy_mode(app_state: &AppState) -> Result<(), String> {
+    write_proxy_mode_config(&AppType::Claude, app_state)?;
+    write_proxy_mode_config(&AppType::Codex, app_state)?;
     Ok(())
 }
 
+/// 切换运行模式（写入模式 <-> 代理模式），保存设置并启动/停止代理服务器，返回新的运行模式
+pub async fn toggle_operation_mode(app_state: &AppState) -> Result<OperationMode, String> {
+    let mut settings = crate::settings::get_settings();
+    let new_mode = match settings.operation_mode {
+        OperationMode::Write | OperationMode::Env => OperationMode::Proxy,
+        OperationMode::Proxy => OperationMode::Write,
+    };
+    let hook_context = crate::hooks::HookContext::mode_change(
+        new_mode.as_str(),
+        settings.operation_mode.as_str(),
+    );
+    crate::hooks::before(crate::hooks::HookEvent::BeforeModeChange, &hook_context).await?;
+
+    match new_mode {
+        OperationMode::Proxy => {
+            switch_to_proxy_mode(app_state)?;
+            settings.operation_mode = new_mode.clone();
+            crate::settings::update_settings(settings)?;
+            start_proxy_server(app_state).await?;
+        }
+        OperationMode::Write | OperationMode::Env => {
+            stop_proxy_server().await?;
+            switch_to_write_mode(app_state)?;
+            settings.operation_mode = new_mode.clone();
+            crate::settings::update_settings(settings)?;
+        }
+    }
+    crate::current_status::sync(app_state);
+    crate::hooks::after(crate::hooks::HookEvent::AfterModeChange, hook_context);
+    crate::running_sessions::warn_stale(None, crate::running_sessions::ChangeReason::ModeChange);
+
+    Ok(new_mode)
+}
+
 /// 切换到写入模式时的配置恢复
 pub fn switch_to_write_mode(app_state: &AppState) -> Result<(), String> {
-    // 恢复Claude配置
-    {
-        let mut config = app_state
-            .config
-            .lock()
-            .map_err(|e| format!("获取锁失败: {}", e))?;
-
-        if let Some(manager) = config.get_manager_mut(&AppType::Claude) {
-            if !manager.current.is_empty() {
-                // 有当前供应商，写入其配置
-                if let Some(provider) = manager.providers.get(&manager.current) {
-                    let settings_path = crate::config::get_claude_settings_path();
-                    crate::config::write_json_file(&settings_path, &provider.settings_config)?;
-                }
-            } else {
-                // 没有当前供应商，选择第一个供应商（按 sort_index 排序）
-                let mut providers: Vec<_> = manager.providers.iter().collect();
-                providers.sort_by(|a, b| {
-                    match (a.1.sort_index, b.1.sort_index) {
-                        (Some(idx_a), Some(idx_b)) => idx_a.cmp(&idx_b),
-                        (Some(_), None) => std::cmp::Ordering::Less,
-                        (None, Some(_)) => std::cmp::Ordering::Greater,
-                        (None, None) => {
-                            match (a.1.created_at, b.1.created_at) {
-                                (Some(time_a), Some(time_b)) => time_a.cmp(&time_b),
-                                _ => std::cmp::Ordering::Equal,
-                            }
-                        }
-                    }
-                });
-
-                if let Some((provider_id, first_provider)) = providers.first() {
-                    let settings_path = crate::config::get_claude_settings_path();
-                    crate::config::write_json_file(&settings_path, &first_provider.settings_config)?;
-                    // 更新 current 字段
-                    manager.current = provider_id.to_string();
-                } else {
-                    log::warn!("没有可用的 Claude 供应商，跳过配置恢复");
-                }
-            }
-        }
+    let mut config = app_state
+        .config
+        .lock()
+        .map_err(|e| format!("获取锁失败: {}", e))?;
 
-        // 恢复Codex配置
-        if let Some(manager) = config.get_manager_mut(&AppType::Codex) {
-            if !manager.current.is_empty() {
-                // 有当前供应商，写入其配置
-                if let Some(provider) = manager.providers.get(&manager.current) {
-                    let auth = provider
-                        .settings_config
-                        .get("auth")
-                        .ok_or_else(|| "目标供应商缺少 auth 配置".to_string())?;
-                    let cfg_text = provider
-                        .settings_config
-                        .get("config")
-                        .and_then(|v| v.as_str());
-                    crate::codex_config::write_codex_live_atomic(auth, cfg_text)?;
-                }
-            } else {
-                // 没有当前供应商，选择第一个供应商（按 sort_index 排序）
-                let mut providers: Vec<_> = manager.providers.iter().collect();
-                providers.sort_by(|a, b| {
-                    match (a.1.sort_index, b.1.sort_index) {
-                        (Some(idx_a), Some(idx_b)) => idx_a.cmp(&idx_b),
-                        (Some(_), None) => std::cmp::Ordering::Less,
-                        (None, Some(_)) => std::cmp::Ordering::Greater,
-                        (None, None) => {
-                            match (a.1.created_at, b.1.created_at) {
-                                (Some(time_a), Some(time_b)) => time_a.cmp(&time_b),
-                                _ => std::cmp::Ordering::Equal,
-                            }
-                        }
-                    }
-                });
-
-                if let Some((provider_id, first_provider)) = providers.first() {
-                    let auth = first_provider
-                        .settings_config
-                        .get("auth")
-                        .ok_or_else(|| "目标供应商缺少 auth 配置".to_string())?;
-                    let cfg_text = first_provider
-                        .settings_config
-                        .get("config")
-                        .and_then(|v| v.as_str());
-                    crate::codex_config::write_codex_live_atomic(auth, cfg_text)?;
-                    // 更新 current 字段
-                    manager.current = provider_id.to_string();
-                } else {
-                    log::warn!("没有可用的 Codex 供应商，跳过配置恢复");
+    // 恢复 Claude 与 Codex 配置：与切换供应商一致，写入供应商自身的 live 目录
+    for app_type in [AppType::Claude, AppType::Codex] {
+        let Some(manager) = config.get_manager_mut(&app_type) else {
+            continue;
+        };
+        let provider_id = if manager.providers.contains_key(&manager.current) {
+            manager.current.clone()
+        } else {
+            // 没有当前供应商，选择第一个已启用的供应商（按 sort_index 排序）
+            let mut providers: Vec<_> = manager
+                .providers
+                .iter()
+                .filter(|(_, provider)| provider.is_enabled())
+                .collect();
+            providers.sort_by(|a, b| match (a.1.sort_index, b.1.sort_index) {
+                (Some(idx_a), Some(idx_b)) => idx_a.cmp(&idx_b),
+                (Some(_), None) => std::cmp::Ordering::Less,
+                (None, Some(_)) => std::cmp::Ordering::Greater,
+                (None, None) => match (a.1.created_at, b.1.created_at) {
+                    (Some(time_a), Some(time_b)) => time_a.cmp(&time_b),
+                    _ => std::cmp::Ordering::Equal,
+                },
+            });
+            match providers.first() {
+                Some((provider_id, _)) => provider_id.to_string(),
+                None => {
+                    tracing::warn!("没有可用的 {} 供应商，跳过配置恢复", app_type.as_str());
+                    continue;
                 }
             }
-        }
+        };
 
-        // 保存配置
-        drop(config);
-        app_state.save()?;
+        let provider = &manager.providers[&provider_id];
+        let dir = crate::live_config::live_dir(&app_type, Some(provider));
+        let settings = crate::common_config::live_settings(&app_type, manager, provider, &dir);
+        crate::live_config::write_live_settings(&app_type, &dir, &settings)?;
+        // 更新 current 字段
+        manager.current = provider_id;
     }
 
+    // 保存配置
+    drop(config);
+    app_state.save_now()?;
     Ok(())
 }
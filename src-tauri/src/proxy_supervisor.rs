@@ -0,0 +1,114 @@
+//! 代理服务监管：本机代理的 serve 任务意外退出（睡眠唤醒后端口被占、文件描述符耗尽、panic 等）时，
+//! 按指数退避重新绑定端口并恢复服务，同时通过事件与系统通知告知中断与恢复，
+//! 避免代理悄无声息地停止直到重启应用。主动停止代理时监管任务随之被取消。
+
+use std::time::Duration;
+
+use axum::Router;
+use axum_server::tls_rustls::RustlsConfig;
+use serde::Serialize;
+use tauri::Emitter;
+use tauri_plugin_notification::NotificationExt;
+use tokio::task::JoinHandle;
+
+use crate::i18n::{t, t_args};
+
+/// 代理中断/恢复事件名
+pub const PROXY_STATUS_EVENT: &str = "proxy-status-changed";
+
+/// 首次重新绑定前的等待时间，之后每次失败翻倍
+const INITIAL_BACKOFF: Duration = Duration::from_secs(1);
+/// 重新绑定的最长等待间隔
+const MAX_BACKOFF: Duration = Duration::from_secs(60);
+
+#[derive(Debug, Clone, Copy, Serialize)]
+#[serde(rename_all = "snake_case")]
+enum ProxyStatus {
+    Down,
+    Recovered,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+struct ProxyStatusPayload {
+    status: ProxyStatus,
+    message: String,
+}
+
+/// 监管任务被取消（停止代理）时一并终止正在运行的 serve 任务
+struct AbortOnDrop(JoinHandle<std::io::Result<()>>);
+
+impl Drop for AbortOnDrop {
+    fn drop(&mut self) {
+        self.0.abort();
+    }
+}
+
+/// 运行本机代理；服务退出后持续尝试在原地址重新监听，直到被取消
+pub async fn supervise(
+    mut listener: tokio::net::TcpListener,
+    app: Router,
+    tls: Option<RustlsConfig>,
+) {
+    loop {
+        // serve 放在独立任务中运行，panic 也能被感知为服务退出
+        let mut server = AbortOnDrop(tokio::spawn(crate::proxy::serve(
+            listener,
+            app.clone(),
+            tls.clone(),
+        )));
+        let reason = match (&mut server.0).await {
+            Ok(Ok(())) => "服务意外结束".to_string(),
+            Ok(Err(e)) => e.to_string(),
+            Err(e) => e.to_string(),
+        };
+        drop(server);
+        tracing::error!("代理服务器已停止运行: {}，准备重新绑定端口", reason);
+        report(
+            ProxyStatus::Down,
+            t_args("proxy.outage", &[("error", &reason)]),
+        );
+
+        listener = rebind().await;
+        tracing::info!("代理服务器已恢复，监听 {}", crate::proxy::proxy_url());
+        report(ProxyStatus::Recovered, t("proxy.recovered"));
+    }
+}
+
+/// 按指数退避重新绑定代理端口，直到成功
+async fn rebind() -> tokio::net::TcpListener {
+    let mut backoff = INITIAL_BACKOFF;
+    loop {
+        tokio::time::sleep(backoff).await;
+        match tokio::net::TcpListener::bind(crate::proxy::PROXY_ADDR).await {
+            Ok(listener) => return listener,
+            Err(e) => {
+                backoff = (backoff * 2).min(MAX_BACKOFF);
+                tracing::warn!(
+                    "重新绑定代理端口失败: {}，{} 秒后重试",
+                    e,
+                    backoff.as_secs()
+                );
+            }
+        }
+    }
+}
+
+/// 通知前端并发送系统通知（窗口可能隐藏在托盘中）
+fn report(status: ProxyStatus, message: String) {
+    let Some(app) = crate::app_store::get_app_handle() else {
+        return;
+    };
+    if let Err(e) = app
+        .notification()
+        .builder()
+        .title("CC Switch")
+        .body(&message)
+        .show()
+    {
+        tracing::warn!("发送系统通知失败: {}", e);
+    }
+    if let Err(e) = app.emit(PROXY_STATUS_EVENT, ProxyStatusPayload { status, message }) {
+        tracing::warn!("发射代理状态事件失败: {}", e);
+    }
+}
@@ -0,0 +1,62 @@
+//! 代理的 HTTPS 监听：部分工具拒绝使用明文 HTTP 地址，开启后代理改用 TLS 监听。
+//! 首次使用时生成仅供本机使用的自签名证书（`localhost` / `127.0.0.1`），保存在应用配置目录中，
+//! 之后重复使用；删除证书文件即可在下次启动时重新生成。
+//! Codex 无法通过配置文件信任该证书，开启后仍经回环地址上的明文端口访问（见 `proxy::codex_proxy_url`）。
+
+use axum_server::tls_rustls::RustlsConfig;
+use std::path::PathBuf;
+
+use crate::config::{get_app_config_dir, write_text_file};
+
+/// 证书文件名
+const CERT_FILE: &str = "proxy-cert.pem";
+
+/// 私钥文件名
+const KEY_FILE: &str = "proxy-key.pem";
+
+/// 证书覆盖的主机名
+const SUBJECT_ALT_NAMES: &[&str] = &["localhost", "127.0.0.1"];
+
+/// 是否启用 HTTPS 监听
+pub fn is_enabled() -> bool {
+    crate::settings::get_settings().proxy_tls
+}
+
+/// 自签名证书路径（客户端需信任此证书，如 Node 的 NODE_EXTRA_CA_CERTS）
+pub fn cert_path() -> PathBuf {
+    get_app_config_dir().join(CERT_FILE)
+}
+
+fn key_path() -> PathBuf {
+    get_app_config_dir().join(KEY_FILE)
+}
+
+/// 证书或私钥缺失时生成新的自签名证书
+fn ensure_cert() -> Result<(), String> {
+    if cert_path().exists() && key_path().exists() {
+        return Ok(());
+    }
+    let names: Vec<String> = SUBJECT_ALT_NAMES.iter().map(|s| s.to_string()).collect();
+    let certified = rcgen::generate_simple_self_signed(names)
+        .map_err(|e| format!("生成代理证书失败: {}", e))?;
+    write_text_file(&key_path(), &certified.key_pair.serialize_pem())?;
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::PermissionsExt;
+        if let Err(e) = std::fs::set_permissions(key_path(), std::fs::Permissions::from_mode(0o600))
+        {
+            tracing::warn!("设置代理私钥权限失败: {}", e);
+        }
+    }
+    write_text_file(&cert_path(), &certified.cert.pem())?;
+    tracing::info!("已生成代理自签名证书: {}", cert_path().display());
+    Ok(())
+}
+
+/// 加载（必要时先生成）代理监听使用的 TLS 配置
+pub async fn rustls_config() -> Result<RustlsConfig, String> {
+    ensure_cert()?;
+    RustlsConfig::from_pem_file(cert_path(), key_path())
+        .await
+        .map_err(|e| format!("加载代理证书失败: {}", e))
+}
@@ -0,0 +1,139 @@
+//! 代理模式下按供应商限流：在最近 60 秒的滑动窗口内统计发往上游的请求数与响应中的 token 用量，
+//! 达到供应商 meta.rate_limit 设置的上限后跳过该供应商、交给下一个供应商，避免触发上游 429 封禁。
+//! 统计仅保存在进程内，重启后清零。
+
+use serde_json::Value;
+use std::collections::{HashMap, VecDeque};
+use std::sync::{Mutex, OnceLock};
+use std::time::{Duration, Instant};
+
+use crate::app_config::AppType;
+use crate::provider::{Provider, RateLimit};
+
+const WINDOW: Duration = Duration::from_secs(60);
+
+#[derive(Default)]
+struct Window {
+    requests: VecDeque<Instant>,
+    tokens: VecDeque<(Instant, u64)>,
+}
+
+impl Window {
+    fn prune(&mut self, now: Instant) {
+        while self
+            .requests
+            .front()
+            .is_some_and(|t| now.duration_since(*t) >= WINDOW)
+        {
+            self.requests.pop_front();
+        }
+        while self
+            .tokens
+            .front()
+            .is_some_and(|(t, _)| now.duration_since(*t) >= WINDOW)
+        {
+            self.tokens.pop_front();
+        }
+    }
+}
+
+/// 应用类型:供应商 ID -> 滑动窗口
+static WINDOWS: OnceLock<Mutex<HashMap<String, Window>>> = OnceLock::new();
+
+fn key(app_type: &AppType, provider: &Provider) -> String {
+    format!("{}:{}", app_type.as_str(), provider.id)
+}
+
+fn limit_of(provider: &Provider) -> Option<&RateLimit> {
+    provider.meta.as_ref()?.rate_limit.as_ref()
+}
+
+fn with_window<R>(
+    app_type: &AppType,
+    provider: &Provider,
+    f: impl FnOnce(&mut Window) -> R,
+) -> Option<R> {
+    let mut windows = WINDOWS
+        .get_or_init(|| Mutex::new(HashMap::new()))
+        .lock()
+        .ok()?;
+    let window = windows.entry(key(app_type, provider)).or_default();
+    window.prune(Instant::now());
+    Some(f(window))
+}
+
+/// 供应商在当前窗口内是否已达到请求数或 token 上限（未设置上限时始终为 false）
+pub fn is_limited(app_type: &AppType, provider: &Provider) -> bool {
+    let Some(limit) = limit_of(provider) else {
+        return false;
+    };
+    with_window(app_type, provider, |window| {
+        let requests_hit = limit
+            .requests_per_minute
+            .is_some_and(|rpm| window.requests.len() as u64 >= u64::from(rpm));
+        let used: u64 = window.tokens.iter().map(|(_, n)| n).sum();
+        let tokens_hit = limit
+            .tokens_per_minute
+            .is_some_and(|tpm| used >= u64::from(tpm));
+        requests_hit || tokens_hit
+    })
+    .unwrap_or(false)
+}
+
+/// 记录一次发往上游的请求
+pub fn record_request(app_type: &AppType, provider: &Provider) {
+    if limit_of(provider).is_none() {
+        return;
+    }
+    with_window(app_type, provider, |window| {
+        window.requests.push_back(Instant::now())
+    });
+}
+
+/// 记录一次响应消耗的 token（由 `token_usage` 从响应体解析）
+pub fn record_tokens(app_type: &AppType, provider: &Provider, tokens: u64) {
+    let Some(limit) = limit_of(provider) else {
+        return;
+    };
+    if limit.tokens_per_minute.is_none() {
+        return;
+    }
+    if tokens > 0 {
+        with_window(app_type, provider, |window| {
+            window.tokens.push_back((Instant::now(), tokens))
+        });
+    }
+}
+
+/// 从 JSON 对象中取出 usage（顶层或 message / response 之下）
+fn usage_of(value: &Value) -> Option<&Value> {
+    value
+        .get("usage")
+        .or_else(|| value.get("message")?.get("usage"))
+        .or_else(|| value.get("response")?.get("usage"))
+}
+
+/// 响应中的 token 用量：兼容 Anthropic / OpenAI 的 JSON 响应与 SSE 流（流式时取各事件中的最大值）
+pub fn token_usage(body: &[u8]) -> u64 {
+    let text = String::from_utf8_lossy(body);
+    let values: Vec<Value> = match serde_json::from_str::<Value>(&text) {
+        Ok(value) => vec![value],
+        Err(_) => text
+            .lines()
+            .filter_map(|line| line.strip_prefix("data:"))
+            .filter_map(|data| serde_json::from_str(data.trim()).ok())
+            .collect(),
+    };
+    let field = |usage: &Value, names: &[&str]| {
+        names
+            .iter()
+            .find_map(|name| usage.get(*name)?.as_u64())
+            .unwrap_or(0)
+    };
+    let (mut input, mut output) = (0, 0);
+    for usage in values.iter().filter_map(usage_of) {
+        input = input.max(field(usage, &["input_tokens", "prompt_tokens"]));
+        output = output.max(field(usage, &["output_tokens", "completion_tokens"]));
+    }
+    input + output
+}
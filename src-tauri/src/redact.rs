@@ -0,0 +1,187 @@
+//! 密钥脱敏：返回给前端用于列表展示的供应商数据中，凭证字段只保留末 4 位，
+//! 订阅账号的 OAuth 令牌整体隐藏；需要查看明文时走 `reveal_provider_secret`（系统级确认）。
+//! 前端原样提交脱敏值时，保存前按原值还原，避免把掩码写入配置。
+
+#![allow(non_snake_case)]
+
+use serde_json::Value;
+use tauri::State;
+use tauri_plugin_dialog::{DialogExt, MessageDialogButtons, MessageDialogKind};
+
+use crate::app_config::AppType;
+use crate::error::CcSwitchError;
+use crate::i18n::{t, t_args};
+use crate::provider::Provider;
+use crate::store::AppState;
+
+/// 掩码前缀（前端据此识别脱敏值）
+pub const MASK_PREFIX: &str = "••••••••";
+
+/// 保留的明文尾部长度
+const VISIBLE_TAIL: usize = 4;
+
+/// settings_config 中的凭证字段（JSON Pointer）
+pub fn secret_pointers(app_type: &AppType) -> &'static [&'static str] {
+    match app_type {
+        AppType::Claude => &["/env/ANTHROPIC_AUTH_TOKEN", "/env/ANTHROPIC_API_KEY"],
+        AppType::Codex => &[
+            "/auth/OPENAI_API_KEY",
+            "/auth/tokens/access_token",
+            "/auth/tokens/refresh_token",
+            "/auth/tokens/id_token",
+        ],
+    }
+}
+
+pub fn is_masked(value: &str) -> bool {
+    value.starts_with(MASK_PREFIX)
+}
+
+/// 只保留末 4 位；过短的值整体隐藏
+pub fn mask(secret: &str) -> String {
+    let chars: Vec<char> = secret.chars().collect();
+    if chars.len() <= VISIBLE_TAIL * 2 {
+        return MASK_PREFIX.to_string();
+    }
+    let tail: String = chars[chars.len() - VISIBLE_TAIL..].iter().collect();
+    format!("{}{}", MASK_PREFIX, tail)
+}
+
+/// 脱敏 settings_config（空值保持原样，便于前端区分"未填写"）
+pub fn redact_settings(app_type: &AppType, settings: &Value) -> Value {
+    let mut settings = settings.clone();
+    for pointer in secret_pointers(app_type) {
+        if let Some(value) = settings.pointer_mut(pointer) {
+            if let Some(secret) = value.as_str().filter(|s| !s.is_empty()) {
+                *value = Value::String(mask(secret));
+            }
+        }
+    }
+    settings
+}
+
+/// 脱敏点分路径（如 `env`、`auth.tokens`，空串表示整个配置）处的值：该值本身或其下的凭证字段掩码
+pub fn redact_at_path(app_type: &AppType, path: &str, value: &mut Value) {
+    for pointer in secret_pointers(app_type) {
+        let dotted = pointer.trim_start_matches('/').replace('/', ".");
+        let rest = if path.is_empty() {
+            Some(dotted.as_str())
+        } else if dotted == path {
+            Some("")
+        } else {
+            dotted.strip_prefix(path).and_then(|r| r.strip_prefix('.'))
+        };
+        let Some(rest) = rest else {
+            continue;
+        };
+        let target = if rest.is_empty() {
+            Some(&mut *value)
+        } else {
+            value.pointer_mut(&format!("/{}", rest.replace('.', "/")))
+        };
+        if let Some(target) = target {
+            if let Some(secret) = target.as_str().filter(|s| !s.is_empty()) {
+                *target = Value::String(mask(secret));
+            }
+        }
+    }
+}
+
+/// 两份配置中的凭证字段是否不同（用于审计"密钥变更"，不比较其他字段）
+pub fn secrets_differ(app_type: &AppType, a: &Value, b: &Value) -> bool {
+    secret_pointers(app_type)
+        .iter()
+        .any(|pointer| a.pointer(pointer) != b.pointer(pointer))
+}
+
+/// 返回给前端的脱敏副本：凭证字段掩码，OAuth 令牌隐藏（账号信息保留用于展示）
+pub fn redact_provider(app_type: &AppType, provider: &Provider) -> Provider {
+    let mut redacted = provider.clone();
+    redacted.settings_config = redact_settings(app_type, &provider.settings_config);
+    if let Some(oauth) = redacted.meta.as_mut().and_then(|m| m.oauth.as_mut()) {
+        oauth.credentials = Value::Null;
+    }
+    redacted
+}
+
+/// 将前端提交的掩码值还原为原值：依次在 sources（已保存的配置、live 配置）中查找掩码一致的原值；
+/// 仍有无法还原的掩码时返回错误，避免把掩码写入配置
+pub fn restore_secrets(
+    app_type: &AppType,
+    incoming: &mut Value,
+    sources: &[&Value],
+) -> Result<(), String> {
+    for pointer in secret_pointers(app_type) {
+        let Some(value) = incoming.pointer_mut(pointer) else {
+            continue;
+        };
+        let Some(masked) = value.as_str().filter(|s| is_masked(s)) else {
+            continue;
+        };
+        let original = sources
+            .iter()
+            .filter_map(|source| source.pointer(pointer).and_then(|v| v.as_str()))
+            .find(|secret| mask(secret) == masked)
+            .map(str::to_string);
+        match original {
+            Some(original) => *value = Value::String(original),
+            None => {
+                return Err(t_args(
+                    "errors.maskedSecretUnresolved",
+                    &[("field", &pointer.trim_start_matches('/'))],
+                ))
+            }
+        }
+    }
+    Ok(())
+}
+
+/// 查看供应商明文配置：先弹出系统确认框，用户确认后返回未脱敏的 settings_config
+#[tauri::command]
+pub async fn reveal_provider_secret(
+    handle: tauri::AppHandle,
+    state: State<'_, AppState>,
+    id: String,
+    app_type: Option<AppType>,
+    app: Option<String>,
+    appType: Option<String>,
+) -> Result<Value, CcSwitchError> {
+    let app_type = app_type
+        .or_else(|| app.as_deref().map(|s| s.into()))
+        .or_else(|| appType.as_deref().map(|s| s.into()))
+        .unwrap_or(AppType::Claude);
+    crate::app_lock::ensure_unlocked()?;
+
+    let (name, settings) = {
+        let config = state
+            .config
+            .lock()
+            .map_err(|e| t_args("errors.lockFailed", &[("error", &e)]))?;
+        let provider = config
+            .get_manager(&app_type)
+            .ok_or_else(|| t_args("errors.appTypeNotFound", &[("app", &app_type.as_str())]))?
+            .providers
+            .get(&id)
+            .ok_or_else(|| t_args("errors.providerNotFound", &[("id", &id)]))?;
+        (provider.name.clone(), provider.settings_config.clone())
+    };
+
+    let message = t_args("secrets.revealConfirm", &[("name", &name)]);
+    let confirmed = tauri::async_runtime::spawn_blocking(move || {
+        handle
+            .dialog()
+            .message(message)
+            .title(t("secrets.revealTitle"))
+            .kind(MessageDialogKind::Warning)
+            .buttons(MessageDialogButtons::OkCancel)
+            .blocking_show()
+    })
+    .await
+    .map_err(|e| format!("弹出确认框失败: {}", e))?;
+
+    if !confirmed {
+        return Err(t("errors.revealCancelled").into());
+    }
+    tracing::info!("已确认查看供应商 {} 的密钥", name);
+    Ok(settings)
+}
@@ -0,0 +1,82 @@
+//! 按请求分类路由：代理在选择供应商前检查请求路径与请求体中的 `model`，
+//! 命中规则时将规则指定的供应商排到最前（独占规则则只使用这些供应商），
+//! 例如 haiku 系列模型走供应商 A、opus 系列走供应商 B，或按接口路径分流。
+//! 规则按顺序匹配，第一条命中的规则生效；未命中时保持原有顺序。
+
+use regex::Regex;
+use serde_json::Value;
+
+use crate::app_config::AppType;
+use crate::provider::Provider;
+use crate::settings::RequestRoute;
+
+/// 将 `*` 通配的模式转换为不区分大小写的正则
+fn glob_regex(pattern: &str) -> Option<Regex> {
+    let escaped = regex::escape(pattern.trim()).replace(r"\*", ".*");
+    Regex::new(&format!("(?i)^{}$", escaped)).ok()
+}
+
+/// 请求体中的模型名（非 JSON 或未指定时为空）
+fn request_model(body: &[u8]) -> Option<String> {
+    let value: Value = serde_json::from_slice(body).ok()?;
+    value.get("model")?.as_str().map(str::to_string)
+}
+
+fn route_matches(route: &RequestRoute, path: &str, model: Option<&str>) -> bool {
+    let path_ok = match route.path.as_deref().map(str::trim) {
+        Some(prefix) if !prefix.is_empty() => path.starts_with(prefix),
+        _ => true,
+    };
+    let model_ok = match route.model.as_deref().map(str::trim) {
+        Some(pattern) if !pattern.is_empty() => model
+            .zip(glob_regex(pattern))
+            .is_some_and(|(model, re)| re.is_match(model)),
+        _ => true,
+    };
+    path_ok && model_ok
+}
+
+/// 按规则重排供应商：命中规则的供应商按规则中的顺序排在前面，独占规则去掉其余供应商
+pub fn route(
+    app_type: &AppType,
+    path: &str,
+    body: &[u8],
+    providers: Vec<Provider>,
+) -> Vec<Provider> {
+    let settings = crate::settings::get_settings();
+    let routes = match app_type {
+        AppType::Claude => &settings.request_routes.claude,
+        AppType::Codex => &settings.request_routes.codex,
+    };
+    if routes.is_empty() {
+        return providers;
+    }
+    let path = path.split('?').next().unwrap_or(path);
+    let model = request_model(body);
+    let Some(route) = routes
+        .iter()
+        .find(|route| route_matches(route, path, model.as_deref()))
+    else {
+        return providers;
+    };
+    tracing::debug!(
+        "请求命中路由规则 {}（模型 {}）",
+        route.name,
+        model.as_deref().unwrap_or("-")
+    );
+
+    let mut preferred = Vec::new();
+    let mut rest = Vec::new();
+    for provider in providers {
+        match route.providers.iter().position(|id| *id == provider.id) {
+            Some(rank) => preferred.push((rank, provider)),
+            None => rest.push(provider),
+        }
+    }
+    preferred.sort_by_key(|(rank, _)| *rank);
+    let mut result: Vec<Provider> = preferred.into_iter().map(|(_, p)| p).collect();
+    if !route.exclusive {
+        result.extend(rest);
+    }
+    result
+}
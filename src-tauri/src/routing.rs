@@ -0,0 +1,146 @@
+//! 按时间段路由：供应商可配置若干规则（星期几 + 时间段 + 效果），例如在中转站的优惠时段优先使用，
+//! 或只在夜间使用某个供应商。代理模式下在选择供应商时生效；写入模式可在设置中开启自动切换，
+//! 当规则选出的最佳供应商发生变化时切换过去（两次变化之间的手动切换不会被覆盖）。
+
+use chrono::{DateTime, Datelike, Local, NaiveTime, Timelike};
+use std::collections::HashMap;
+use std::sync::{Mutex, OnceLock};
+use std::time::Duration;
+use tauri::Manager;
+
+use crate::app_config::AppType;
+use crate::provider::{Provider, RoutingEffect, RoutingRule};
+use crate::settings::OperationMode;
+use crate::store::AppState;
+
+/// 自动切换的检查间隔
+const CHECK_INTERVAL: Duration = Duration::from_secs(60);
+
+/// 上次规则选出的供应商（按应用类型记录）
+static LAST_TARGETS: OnceLock<Mutex<HashMap<String, String>>> = OnceLock::new();
+
+fn parse_time(value: &str) -> Option<NaiveTime> {
+    NaiveTime::parse_from_str(value.trim(), "%H:%M").ok()
+}
+
+/// 规则在给定时间是否生效：结束时间早于开始时间表示跨越午夜，星期按开始当天计算
+fn rule_active(rule: &RoutingRule, now: &DateTime<Local>) -> bool {
+    let (Some(start), Some(end)) = (parse_time(&rule.start), parse_time(&rule.end)) else {
+        return false;
+    };
+    let time = NaiveTime::from_hms_opt(now.hour(), now.minute(), 0).unwrap_or_default();
+    let today = now.weekday().number_from_monday();
+    let day_matches = |day: u32| rule.days.is_empty() || rule.days.contains(&day);
+    if start <= end {
+        day_matches(today) && time >= start && time < end
+    } else if time >= start {
+        day_matches(today)
+    } else {
+        let yesterday = if today == 1 { 7 } else { today - 1 };
+        time < end && day_matches(yesterday)
+    }
+}
+
+/// 供应商在给定时间的路由权重：None 表示不可用，数值越小越优先（默认 0）
+pub fn weight(provider: &Provider, now: &DateTime<Local>) -> Option<i32> {
+    let rules = match provider.meta.as_ref() {
+        Some(meta) if !meta.routing_rules.is_empty() => &meta.routing_rules,
+        _ => return Some(0),
+    };
+    let active: Vec<RoutingEffect> = rules
+        .iter()
+        .filter(|rule| rule_active(rule, now))
+        .map(|rule| rule.effect)
+        .collect();
+    let has_only = rules.iter().any(|rule| rule.effect == RoutingEffect::Only);
+    if active.contains(&RoutingEffect::Exclude)
+        || (has_only && !active.contains(&RoutingEffect::Only))
+    {
+        return None;
+    }
+    if active.contains(&RoutingEffect::Prefer) {
+        Some(-1)
+    } else if active.contains(&RoutingEffect::Avoid) {
+        Some(1)
+    } else {
+        Some(0)
+    }
+}
+
+/// 按当前时间的规则过滤并重排（已按 sort_index 排好的）供应商列表；同权重保持原顺序
+pub fn apply(providers: Vec<Provider>) -> Vec<Provider> {
+    let now = Local::now();
+    let mut weighted: Vec<(i32, Provider)> = providers
+        .into_iter()
+        .filter_map(|provider| Some((weight(&provider, &now)?, provider)))
+        .collect();
+    weighted.sort_by_key(|(weight, _)| *weight);
+    weighted.into_iter().map(|(_, provider)| provider).collect()
+}
+
+/// 写入模式下规则选出的供应商：存在路由规则时返回排在最前的可用供应商
+fn pick_target(app_state: &AppState, app_type: &AppType) -> Option<(String, String)> {
+    let config = app_state.config.lock().ok()?;
+    let manager = config.get_manager(app_type)?;
+    let providers: Vec<Provider> = manager
+        .sorted_providers()
+        .into_iter()
+        .filter(|p| p.is_enabled() && !p.in_maintenance())
+        .cloned()
+        .collect();
+    let has_rules = providers.iter().any(|p| {
+        p.meta
+            .as_ref()
+            .is_some_and(|meta| !meta.routing_rules.is_empty())
+    });
+    if !has_rules {
+        return None;
+    }
+    let target = apply(providers).into_iter().next()?;
+    Some((target.id, manager.current.clone()))
+}
+
+/// 检查一次：规则选出的供应商与上次不同时切换过去
+async fn check(app: &tauri::AppHandle, app_type: AppType) {
+    let Some(app_state) = app.try_state::<AppState>() else {
+        return;
+    };
+    let Some((target, current)) = pick_target(&app_state, &app_type) else {
+        return;
+    };
+    let changed = match LAST_TARGETS
+        .get_or_init(|| Mutex::new(HashMap::new()))
+        .lock()
+    {
+        Ok(mut last) => {
+            last.insert(app_type.as_str().to_string(), target.clone()) != Some(target.clone())
+        }
+        Err(_) => return,
+    };
+    if !changed || target == current {
+        return;
+    }
+    tracing::info!(
+        "按时间段规则将 {} 切换到供应商 {}",
+        app_type.as_str(),
+        target
+    );
+    if let Err(e) = crate::switch_provider_internal(app, app_type, target).await {
+        tracing::warn!("按时间段规则切换供应商失败: {}", e);
+    }
+}
+
+/// 后台按分钟检查时间段规则（仅写入模式且开启自动切换时生效，每轮重新读取设置）
+pub fn start_routing_auto_switch(app: tauri::AppHandle) {
+    tauri::async_runtime::spawn(async move {
+        loop {
+            let settings = crate::settings::get_settings();
+            if settings.routing_auto_switch && settings.operation_mode == OperationMode::Write {
+                for app_type in [AppType::Claude, AppType::Codex] {
+                    check(&app, app_type).await;
+                }
+            }
+            tokio::time::sleep(CHECK_INTERVAL).await;
+        }
+    });
+}
@@ -0,0 +1,218 @@
+//! 运行中会话检测：切换供应商或运行模式后，已启动的 Claude Code / Codex CLI 会话仍使用启动时读取的
+//! 地址与密钥。切换后在后台检测这类进程，有则通知前端提示重启（可选发送系统通知），避免误以为已切换。
+//! 代理模式下切换供应商由本地代理即时生效，不做提醒；切换运行模式会改变 live 配置，两种应用都提醒。
+
+#![allow(non_snake_case)]
+
+use std::collections::HashSet;
+use std::ffi::OsString;
+
+use serde::Serialize;
+use sysinfo::{ProcessRefreshKind, ProcessesToUpdate, System, UpdateKind};
+use tauri::Emitter;
+use tauri_plugin_notification::NotificationExt;
+
+use crate::app_config::AppType;
+use crate::error::CcSwitchError;
+use crate::i18n::t_args;
+use crate::settings::OperationMode;
+
+/// 需要重启会话的事件名
+pub const RESTART_NEEDED_EVENT: &str = "sessions-restart-needed";
+
+/// 桌面版 Claude 的安装路径特征（与 CLI 同名，需要排除）
+const DESKTOP_APP_MARKERS: [&str; 2] = ["Claude.app", "AnthropicClaude"];
+
+/// 运行中的 CLI 会话
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RunningSession {
+    pub pid: u32,
+    pub app_type: AppType,
+    /// 启动时间（秒级时间戳）
+    pub started_at: u64,
+    /// 工作目录
+    pub cwd: Option<String>,
+}
+
+/// 触发检测的操作
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ChangeReason {
+    Switch,
+    ModeChange,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RestartNeededPayload {
+    pub reason: ChangeReason,
+    /// 切换供应商时为对应应用；切换运行模式时为空
+    pub app_type: Option<AppType>,
+    pub sessions: Vec<RunningSession>,
+    /// 已本地化的提示
+    pub message: String,
+}
+
+/// 按进程名与命令行识别 CLI：原生安装为 `claude` / `codex`，npm 安装的以 node 运行对应包
+fn classify(name: &str, cmd: &[OsString], exe: Option<&std::path::Path>) -> Option<AppType> {
+    let stem = name.strip_suffix(".exe").unwrap_or(name);
+    match stem {
+        "claude" => {
+            let exe = exe
+                .map(|exe| exe.to_string_lossy().to_string())
+                .unwrap_or_default();
+            if DESKTOP_APP_MARKERS
+                .iter()
+                .any(|marker| exe.contains(marker))
+            {
+                return None;
+            }
+            return Some(AppType::Claude);
+        }
+        "codex" => return Some(AppType::Codex),
+        "node" | "bun" => {}
+        _ => return None,
+    }
+    // 只看脚本参数，避免把命令行中提到 claude 的其他 node 程序算进来
+    let script = cmd.get(1)?.to_string_lossy().replace('\\', "/");
+    let file = script.rsplit('/').next().unwrap_or_default();
+    if script.contains("@anthropic-ai/claude-code") || file == "claude" {
+        Some(AppType::Claude)
+    } else if script.contains("@openai/codex") || file == "codex" {
+        Some(AppType::Codex)
+    } else {
+        None
+    }
+}
+
+/// 检测运行中的 Claude Code / Codex 会话（阻塞，应在后台线程调用）
+pub fn detect() -> Vec<RunningSession> {
+    let mut system = System::new();
+    system.refresh_processes_specifics(
+        ProcessesToUpdate::All,
+        true,
+        ProcessRefreshKind::nothing()
+            .without_tasks()
+            .with_cmd(UpdateKind::OnlyIfNotSet)
+            .with_exe(UpdateKind::OnlyIfNotSet)
+            .with_cwd(UpdateKind::OnlyIfNotSet),
+    );
+
+    let mut found = Vec::new();
+    for (pid, process) in system.processes() {
+        let name = process.name().to_string_lossy();
+        if let Some(app_type) = classify(&name, process.cmd(), process.exe()) {
+            found.push((*pid, process.parent(), app_type, process));
+        }
+    }
+    // npm 安装的 Codex 由 node 启动原生进程，同一会话只保留最外层进程
+    let pids: HashSet<_> = found.iter().map(|(pid, ..)| *pid).collect();
+    let mut sessions: Vec<RunningSession> = found
+        .into_iter()
+        .filter(|(_, parent, ..)| !parent.is_some_and(|parent| pids.contains(&parent)))
+        .map(|(pid, _, app_type, process)| RunningSession {
+            pid: pid.as_u32(),
+            app_type,
+            started_at: process.start_time(),
+            cwd: process.cwd().map(|cwd| cwd.to_string_lossy().to_string()),
+        })
+        .collect();
+    sessions.sort_by_key(|session| session.started_at);
+    sessions
+}
+
+fn app_label(app_type: &AppType) -> &'static str {
+    match app_type {
+        AppType::Claude => "Claude Code",
+        AppType::Codex => "Codex",
+    }
+}
+
+/// 切换后在后台检测仍在运行的会话，有则通知前端；开启设置时同时发送系统通知
+pub fn warn_stale(app_type: Option<AppType>, reason: ChangeReason) {
+    let settings = crate::settings::get_settings();
+    if reason == ChangeReason::Switch && settings.operation_mode == OperationMode::Proxy {
+        return;
+    }
+    let Some(app) = crate::app_store::get_app_handle() else {
+        return;
+    };
+    tauri::async_runtime::spawn(async move {
+        let sessions = match tauri::async_runtime::spawn_blocking(detect).await {
+            Ok(sessions) => sessions,
+            Err(e) => {
+                tracing::warn!("检测运行中的会话失败: {}", e);
+                return;
+            }
+        };
+        let sessions: Vec<RunningSession> = sessions
+            .into_iter()
+            .filter(|session| {
+                app_type
+                    .as_ref()
+                    .is_none_or(|app_type| session.app_type.as_str() == app_type.as_str())
+            })
+            .collect();
+        if sessions.is_empty() {
+            return;
+        }
+
+        let mut apps: Vec<&str> = Vec::new();
+        for session in &sessions {
+            let label = app_label(&session.app_type);
+            if !apps.contains(&label) {
+                apps.push(label);
+            }
+        }
+        let message = t_args(
+            "sessions.restartNeeded",
+            &[("count", &sessions.len()), ("app", &apps.join(" / "))],
+        );
+        tracing::info!("{}", message);
+
+        if settings.running_session_notice {
+            if let Err(e) = app
+                .notification()
+                .builder()
+                .title("CC Switch")
+                .body(&message)
+                .show()
+            {
+                tracing::warn!("发送系统通知失败: {}", e);
+            }
+        }
+        let payload = RestartNeededPayload {
+            reason,
+            app_type,
+            sessions,
+            message,
+        };
+        if let Err(e) = app.emit(RESTART_NEEDED_EVENT, &payload) {
+            tracing::warn!("发射会话重启提醒事件失败: {}", e);
+        }
+    });
+}
+
+/// 列出运行中的 Claude Code / Codex 会话（不传应用时返回全部）
+#[tauri::command]
+pub async fn get_running_sessions(
+    app_type: Option<AppType>,
+    app: Option<String>,
+    appType: Option<String>,
+) -> Result<Vec<RunningSession>, CcSwitchError> {
+    let app_type = app_type
+        .or_else(|| app.as_deref().map(|s| s.into()))
+        .or_else(|| appType.as_deref().map(|s| s.into()));
+    let sessions = tauri::async_runtime::spawn_blocking(detect)
+        .await
+        .map_err(|e| format!("检测运行中的会话失败: {}", e))?;
+    Ok(sessions
+        .into_iter()
+        .filter(|session| {
+            app_type
+                .as_ref()
+                .is_none_or(|app_type| session.app_type.as_str() == app_type.as_str())
+        })
+        .collect())
+}
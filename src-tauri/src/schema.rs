@@ -0,0 +1,122 @@
+use jsonschema::Validator;
+use serde_json::{json, Value};
+use std::sync::OnceLock;
+
+use crate::app_config::AppType;
+use crate::validation::FieldError;
+
+static CLAUDE_VALIDATOR: OnceLock<Validator> = OnceLock::new();
+static CODEX_VALIDATOR: OnceLock<Validator> = OnceLock::new();
+
+/// Claude `settings_config`（即 ~/.claude/settings.json）的 JSON Schema
+fn claude_schema() -> Value {
+    json!({
+        "$schema": "http://json-schema.org/draft-07/schema#",
+        "$id": "https://cc-switch.local/schemas/claude-settings.json",
+        "title": "Claude 供应商配置",
+        "description": "写入 ~/.claude/settings.json 的内容",
+        "type": "object",
+        "properties": {
+            "env": {
+                "type": "object",
+                "description": "Claude Code 读取的环境变量",
+                "properties": {
+                    "ANTHROPIC_BASE_URL": {
+                        "type": "string",
+                        "description": "API 请求地址（第三方供应商必填）",
+                        "pattern": "^https?://"
+                    },
+                    "ANTHROPIC_AUTH_TOKEN": {
+                        "type": "string",
+                        "description": "API 密钥（Bearer Token）"
+                    },
+                    "ANTHROPIC_API_KEY": {
+                        "type": "string",
+                        "description": "API 密钥（x-api-key，与 ANTHROPIC_AUTH_TOKEN 二选一）"
+                    },
+                    "ANTHROPIC_MODEL": {
+                        "type": "string",
+                        "description": "主模型"
+                    },
+                    "ANTHROPIC_SMALL_FAST_MODEL": {
+                        "type": "string",
+                        "description": "快速模型"
+                    },
+                    "ANTHROPIC_DEFAULT_HAIKU_MODEL": { "type": "string" },
+                    "ANTHROPIC_DEFAULT_SONNET_MODEL": { "type": "string" },
+                    "ANTHROPIC_DEFAULT_OPUS_MODEL": { "type": "string" }
+                },
+                "additionalProperties": {
+                    "type": ["string", "number", "boolean"]
+                }
+            }
+        },
+        "additionalProperties": true
+    })
+}
+
+/// Codex `settings_config`（auth.json + config.toml）的 JSON Schema
+fn codex_schema() -> Value {
+    json!({
+        "$schema": "http://json-schema.org/draft-07/schema#",
+        "$id": "https://cc-switch.local/schemas/codex-settings.json",
+        "title": "Codex 供应商配置",
+        "type": "object",
+        "required": ["auth"],
+        "properties": {
+            "auth": {
+                "type": "object",
+                "description": "写入 ~/.codex/auth.json 的内容",
+                "properties": {
+                    "OPENAI_API_KEY": {
+                        "type": ["string", "null"],
+                        "description": "API 密钥（第三方供应商必填）"
+                    }
+                },
+                "additionalProperties": true
+            },
+            "config": {
+                "type": ["string", "null"],
+                "description": "写入 ~/.codex/config.toml 的 TOML 文本"
+            }
+        },
+        "additionalProperties": true
+    })
+}
+
+/// 获取指定应用的供应商配置 Schema
+pub fn provider_schema(app_type: &AppType) -> Value {
+    match app_type {
+        AppType::Claude => claude_schema(),
+        AppType::Codex => codex_schema(),
+    }
+}
+
+fn validator(app_type: &AppType) -> &'static Validator {
+    let (cell, schema) = match app_type {
+        AppType::Claude => (&CLAUDE_VALIDATOR, claude_schema as fn() -> Value),
+        AppType::Codex => (&CODEX_VALIDATOR, codex_schema as fn() -> Value),
+    };
+    cell.get_or_init(|| jsonschema::validator_for(&schema()).expect("内置 Schema 无效"))
+}
+
+/// JSON Pointer（/env/KEY）转换为点分隔路径（env.KEY）
+fn pointer_to_field(pointer: &str) -> String {
+    pointer
+        .split('/')
+        .skip(1)
+        .map(|segment| segment.replace("~1", "/").replace("~0", "~"))
+        .collect::<Vec<_>>()
+        .join(".")
+}
+
+/// 按 Schema 校验配置，返回带精确路径的错误
+pub fn validate_against_schema(app_type: &AppType, settings: &Value) -> Vec<FieldError> {
+    validator(app_type)
+        .iter_errors(settings)
+        .map(|e| FieldError {
+            field: pointer_to_field(e.instance_path.as_str()),
+            message: e.to_string(),
+        })
+        .collect()
+}
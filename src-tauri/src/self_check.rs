@@ -0,0 +1,381 @@
+//! 启动自检：核对 `current` 是否指向存在的供应商、live 配置文件能否解析，
+//! 以及 live 配置是否与当前供应商（代理模式下为代理模板）一致。
+//! 发现问题时记录日志并发送事件，由用户选择修复方式，而不是在不一致的状态上继续操作。
+
+use std::path::Path;
+use std::sync::Mutex;
+
+use serde::{Deserialize, Serialize};
+use tauri::{Emitter, Manager, State};
+
+use crate::app_config::{AppType, MultiAppConfig};
+use crate::audit::AuditAction;
+use crate::error::CcSwitchError;
+use crate::i18n::{t, t_args};
+use crate::live_config::{live_dir, live_matches, live_paths, read_live_settings};
+use crate::settings::OperationMode;
+use crate::store::AppState;
+
+/// 自检结果事件名
+pub const SELF_CHECK_EVENT: &str = "config-self-check";
+
+/// 最近一次自检结果（前端晚于启动事件加载时读取）
+static LAST_REPORT: Mutex<Option<SelfCheckReport>> = Mutex::new(None);
+
+/// 问题类型
+#[derive(Debug, Clone, Copy, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum IssueKind {
+    /// `current` 指向不存在的供应商
+    CurrentMissing,
+    /// live 配置文件不存在
+    LiveMissing,
+    /// live 配置文件无法解析（JSON / TOML）
+    LiveInvalid,
+    /// live 配置与当前供应商不一致
+    LiveMismatch,
+    /// 代理模式下 live 配置未指向本地代理
+    ProxyMismatch,
+}
+
+/// 修复方式
+#[derive(Debug, Clone, Copy, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum RepairAction {
+    /// 将 `current` 重置为列表中的第一个供应商并写入 live 配置
+    ResetCurrent,
+    /// 按当前运行模式重新写入 live 配置
+    Reapply,
+    /// 将 live 配置导入当前供应商
+    ImportLive,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SelfCheckIssue {
+    pub app_type: String,
+    pub kind: IssueKind,
+    pub message: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub path: Option<String>,
+    /// 可用的修复方式（第一个为推荐）
+    pub repairs: Vec<RepairAction>,
+}
+
+#[derive(Debug, Clone, Default, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SelfCheckReport {
+    pub issues: Vec<SelfCheckIssue>,
+    /// 毫秒时间戳
+    pub checked_at: i64,
+}
+
+/// 按文件类型解析 live 配置文件，返回第一个解析失败的文件及原因
+fn find_unparseable(paths: &[std::path::PathBuf]) -> Option<(String, String)> {
+    for path in paths.iter().filter(|p| p.exists()) {
+        let result = std::fs::read_to_string(path)
+            .map_err(|e| e.to_string())
+            .and_then(|text| match path.extension().and_then(|e| e.to_str()) {
+                Some("toml") => toml::from_str::<toml::Table>(&text)
+                    .map(|_| ())
+                    .map_err(|e| e.to_string()),
+                _ => serde_json::from_str::<serde_json::Value>(&text)
+                    .map(|_| ())
+                    .map_err(|e| e.to_string()),
+            });
+        if let Err(e) = result {
+            return Some((path.display().to_string(), e));
+        }
+    }
+    None
+}
+
+/// 代理模式下 live 配置是否指向本地代理
+fn points_to_proxy(app_type: &AppType, live: &serde_json::Value) -> bool {
+    match app_type {
+        AppType::Claude => {
+            live.pointer("/env/ANTHROPIC_BASE_URL")
+                .and_then(|v| v.as_str())
+                == Some(crate::proxy::proxy_url().as_str())
+        }
+        AppType::Codex => live
+            .get("config")
+            .and_then(|v| v.as_str())
+            .and_then(|text| toml::from_str::<toml::Table>(text).ok())
+            .is_some_and(|table| {
+                table.get("model_provider").and_then(|v| v.as_str()) == Some("ccswitch")
+            }),
+    }
+}
+
+fn check_app(
+    config: &MultiAppConfig,
+    app_type: &AppType,
+    mode: &OperationMode,
+) -> Vec<SelfCheckIssue> {
+    let app = app_type.as_str().to_string();
+    let issue = |kind, message, path: Option<&Path>, repairs| SelfCheckIssue {
+        app_type: app.clone(),
+        kind,
+        message,
+        path: path.map(|p| p.display().to_string()),
+        repairs,
+    };
+    let Some(manager) = config.get_manager(app_type) else {
+        return Vec::new();
+    };
+
+    let current = manager.providers.get(&manager.current);
+    if current.is_none() && !manager.current.is_empty() {
+        let repairs = if manager.providers.is_empty() {
+            Vec::new()
+        } else {
+            vec![RepairAction::ResetCurrent]
+        };
+        return vec![issue(
+            IssueKind::CurrentMissing,
+            t_args(
+                "selfCheck.currentMissing",
+                &[("app", &app), ("id", &manager.current)],
+            ),
+            None,
+            repairs,
+        )];
+    }
+
+    // 环境变量模式不写入 live 配置；写入模式下没有当前供应商时无从比较
+    let dir = match mode {
+        OperationMode::Env => return Vec::new(),
+        OperationMode::Proxy => live_dir(app_type, None),
+        OperationMode::Write => match current {
+            Some(provider) => live_dir(app_type, Some(provider)),
+            None => return Vec::new(),
+        },
+    };
+    let paths = live_paths(app_type, &dir);
+
+    if let Some((path, error)) = find_unparseable(&paths) {
+        return vec![issue(
+            IssueKind::LiveInvalid,
+            t_args(
+                "selfCheck.liveInvalid",
+                &[("app", &app), ("path", &path), ("error", &error)],
+            ),
+            Some(Path::new(&path)),
+            vec![RepairAction::Reapply],
+        )];
+    }
+
+    let Ok(live) = read_live_settings(app_type, &dir) else {
+        return vec![issue(
+            IssueKind::LiveMissing,
+            t_args("selfCheck.liveMissing", &[("app", &app)]),
+            paths.first().map(|p| p.as_path()),
+            vec![RepairAction::Reapply],
+        )];
+    };
+
+    match (mode, current) {
+        (OperationMode::Proxy, _) if !points_to_proxy(app_type, &live) => vec![issue(
+            IssueKind::ProxyMismatch,
+            t_args("selfCheck.proxyMismatch", &[("app", &app)]),
+            paths.first().map(|p| p.as_path()),
+            vec![RepairAction::Reapply],
+        )],
+        (OperationMode::Write, Some(provider))
+            if !live_matches(
+                app_type,
+                &crate::common_config::live_settings(app_type, manager, provider, &dir),
+                &live,
+            ) =>
+        {
+            let mut repairs = vec![RepairAction::Reapply];
+            if !provider.is_locked() {
+                repairs.push(RepairAction::ImportLive);
+            }
+            vec![issue(
+                IssueKind::LiveMismatch,
+                t_args(
+                    "selfCheck.liveMismatch",
+                    &[("app", &app), ("provider", &provider.name)],
+                ),
+                paths.first().map(|p| p.as_path()),
+                repairs,
+            )]
+        }
+        _ => Vec::new(),
+    }
+}
+
+/// 执行自检并缓存结果
+pub fn run(app_state: &AppState) -> Result<SelfCheckReport, String> {
+    let mode = crate::settings::get_settings().operation_mode;
+    let issues = {
+        let config = app_state
+            .config
+            .lock()
+            .map_err(|e| t_args("errors.lockFailed", &[("error", &e)]))?;
+        [AppType::Claude, AppType::Codex]
+            .iter()
+            .flat_map(|app_type| check_app(&config, app_type, &mode))
+            .collect()
+    };
+    let report = SelfCheckReport {
+        issues,
+        checked_at: chrono::Utc::now().timestamp_millis(),
+    };
+    if let Ok(mut last) = LAST_REPORT.lock() {
+        *last = Some(report.clone());
+    }
+    Ok(report)
+}
+
+/// 启动时自检：有问题时记录日志并通知前端
+pub fn run_on_startup(app: &tauri::AppHandle) {
+    let Some(state) = app.try_state::<AppState>() else {
+        return;
+    };
+    let report = match run(&state) {
+        Ok(report) => report,
+        Err(e) => {
+            tracing::warn!("配置自检失败: {}", e);
+            return;
+        }
+    };
+    if report.issues.is_empty() {
+        return;
+    }
+    for issue in &report.issues {
+        tracing::warn!("配置自检发现问题（{}）: {}", issue.app_type, issue.message);
+    }
+    if let Err(e) = app.emit(SELF_CHECK_EVENT, &report) {
+        tracing::error!("发射配置自检事件失败: {}", e);
+    }
+}
+
+/// 按当前运行模式重新写入 live 配置：写入模式写当前供应商，代理模式写代理模板，环境变量模式写环境变量文件
+pub fn reapply_live(app_state: &AppState, app_type: &AppType) -> Result<(), String> {
+    match crate::settings::get_settings().operation_mode {
+        OperationMode::Proxy => crate::proxy::write_proxy_mode_config(app_type, app_state),
+        OperationMode::Env => {
+            let config = app_state
+                .config
+                .lock()
+                .map_err(|e| t_args("errors.lockFailed", &[("error", &e)]))?;
+            crate::env_mode::write_env_files(&config)
+        }
+        OperationMode::Write => {
+            let config = app_state
+                .config
+                .lock()
+                .map_err(|e| t_args("errors.lockFailed", &[("error", &e)]))?;
+            let manager = config
+                .get_manager(app_type)
+                .ok_or_else(|| t_args("errors.appTypeNotFound", &[("app", &app_type.as_str())]))?;
+            let provider = manager
+                .providers
+                .get(&manager.current)
+                .ok_or_else(|| t("errors.noCurrentProvider"))?;
+            let dir = live_dir(app_type, Some(provider));
+            let settings = crate::common_config::live_settings(app_type, manager, provider, &dir);
+            crate::live_config::write_live_settings(app_type, &dir, &settings)
+        }
+    }
+}
+
+/// 将 `current` 重置为列表中的第一个供应商
+fn reset_current(app_state: &AppState, app_type: &AppType) -> Result<(), String> {
+    {
+        let mut config = app_state
+            .config
+            .lock()
+            .map_err(|e| t_args("errors.lockFailed", &[("error", &e)]))?;
+        let manager = config
+            .get_manager_mut(app_type)
+            .ok_or_else(|| t_args("errors.appTypeNotFound", &[("app", &app_type.as_str())]))?;
+        let first = manager
+            .sorted_providers()
+            .first()
+            .map(|p| p.id.clone())
+            .ok_or_else(|| t("errors.noCurrentProvider"))?;
+        manager.current = first;
+    }
+    app_state.save_now()?;
+    reapply_live(app_state, app_type)
+}
+
+/// 将当前供应商 live 目录中的配置导入当前供应商
+fn import_live(app_state: &AppState, app_type: &AppType) -> Result<(), String> {
+    let name = {
+        let mut config = app_state
+            .config
+            .lock()
+            .map_err(|e| t_args("errors.lockFailed", &[("error", &e)]))?;
+        let manager = config
+            .get_manager_mut(app_type)
+            .ok_or_else(|| t_args("errors.appTypeNotFound", &[("app", &app_type.as_str())]))?;
+        let current = manager.current.clone();
+        let provider = manager
+            .providers
+            .get_mut(&current)
+            .ok_or_else(|| t("errors.noCurrentProvider"))?;
+        provider.ensure_editable()?;
+        let live = read_live_settings(app_type, &live_dir(app_type, Some(provider)))?;
+        let live = crate::common_config::backfill(app_type, provider, live);
+        crate::validation::ensure_valid(app_type, &live).map_err(|e| e.to_string())?;
+        provider.settings_config = live;
+        provider.name.clone()
+    };
+    app_state.save_now()?;
+    crate::audit::record(
+        AuditAction::ProviderUpdated,
+        Some(app_type),
+        Some(&name),
+        Some("live".to_string()),
+    );
+    Ok(())
+}
+
+/// 获取最近一次自检结果（refresh 为 true 或尚未自检时重新执行）
+#[tauri::command]
+pub async fn get_config_self_check(
+    state: State<'_, AppState>,
+    refresh: Option<bool>,
+) -> Result<SelfCheckReport, CcSwitchError> {
+    if !refresh.unwrap_or(false) {
+        if let Some(report) = LAST_REPORT.lock().ok().and_then(|last| last.clone()) {
+            return Ok(report);
+        }
+    }
+    Ok(run(&state)?)
+}
+
+/// 按指定方式修复某个应用的配置问题，返回修复后的自检结果
+#[tauri::command]
+#[allow(non_snake_case)]
+pub async fn repair_config_issue(
+    handle: tauri::AppHandle,
+    state: State<'_, AppState>,
+    app_type: Option<AppType>,
+    app: Option<String>,
+    appType: Option<String>,
+    action: RepairAction,
+) -> Result<SelfCheckReport, CcSwitchError> {
+    let app_type = app_type
+        .or_else(|| app.as_deref().map(|s| s.into()))
+        .or_else(|| appType.as_deref().map(|s| s.into()))
+        .unwrap_or(AppType::Claude);
+
+    match action {
+        RepairAction::ResetCurrent => {
+            reset_current(&state, &app_type)?;
+            if let Err(e) = crate::tray::refresh_tray_menu(&handle) {
+                tracing::warn!("刷新托盘菜单失败: {}", e);
+            }
+        }
+        RepairAction::Reapply => reapply_live(&state, &app_type)?,
+        RepairAction::ImportLive => import_live(&state, &app_type)?,
+    }
+    tracing::info!("已修复 {} 配置问题: {:?}", app_type.as_str(), action);
+    Ok(run(&state)?)
+}
@@ -1,5 +1,5 @@
 use serde::{Deserialize, Serialize};
-use std::collections::HashMap;
+use std::collections::{BTreeMap, HashMap};
 use std::fs;
 use std::path::PathBuf;
 use std::sync::{OnceLock, RwLock};
@@ -20,6 +20,18 @@ pub struct CustomEndpoint {
 pub enum OperationMode {
     Write,
     Proxy,
+    /// 环境变量模式：仅生成 env.sh / env.ps1，不修改 live 配置文件
+    Env,
+}
+
+impl OperationMode {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            OperationMode::Write => "write",
+            OperationMode::Proxy => "proxy",
+            OperationMode::Env => "env",
+        }
+    }
 }
 
 impl Default for OperationMode {
@@ -28,6 +40,319 @@ impl Default for OperationMode {
     }
 }
 
+/// 全局快捷键配置（值为快捷键字符串，如 "CmdOrCtrl+Alt+]"；为空表示不注册）
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct HotkeySettings {
+    #[serde(default)]
+    pub enabled: bool,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub next_claude: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub prev_claude: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub next_codex: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub prev_codex: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub toggle_proxy: Option<String>,
+}
+
+/// 团队托管配置源：从管理员提供的地址拉取供应商，以锁定方式合并到本地
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ManagedSource {
+    pub url: String,
+    /// 访问令牌（以 Bearer 方式发送）
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub token: Option<String>,
+    /// 刷新间隔（分钟）
+    #[serde(default = "default_managed_refresh_minutes")]
+    pub refresh_minutes: u32,
+}
+
+fn default_managed_refresh_minutes() -> u32 {
+    60
+}
+
+/// 定时自动排序：按延迟与错误率重写供应商的 sort_index
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct AutoRankSettings {
+    #[serde(default)]
+    pub enabled: bool,
+    /// 排序间隔（分钟）
+    #[serde(default = "default_auto_rank_interval_minutes")]
+    pub interval_minutes: u32,
+}
+
+fn default_auto_rank_interval_minutes() -> u32 {
+    60
+}
+
+impl Default for AutoRankSettings {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            interval_minutes: default_auto_rank_interval_minutes(),
+        }
+    }
+}
+
+/// 局域网共享：在额外的端口上对局域网开放代理，要求携带访问令牌且来源 IP 在允许列表内
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct LanShareSettings {
+    #[serde(default)]
+    pub enabled: bool,
+    /// 监听地址（`0.0.0.0` 表示所有网卡，也可填写某个网卡的 IP）
+    #[serde(default = "default_lan_share_bind_address")]
+    pub bind_address: String,
+    #[serde(default = "default_lan_share_port")]
+    pub port: u16,
+    /// 访问令牌（客户端以 `Authorization: Bearer` 或 `x-api-key` 携带）
+    #[serde(default)]
+    pub token: String,
+    /// 允许的来源 IP 或网段（如 `192.168.1.0/24`），为空表示不限制
+    #[serde(default)]
+    pub allowed_ips: Vec<String>,
+}
+
+fn default_lan_share_bind_address() -> String {
+    "0.0.0.0".to_string()
+}
+
+fn default_lan_share_port() -> u16 {
+    12858
+}
+
+impl Default for LanShareSettings {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            bind_address: default_lan_share_bind_address(),
+            port: default_lan_share_port(),
+            token: String::new(),
+            allowed_ips: Vec::new(),
+        }
+    }
+}
+
+/// 代理请求录制：保存最近的请求/响应供排查与重放
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ProxyCaptureSettings {
+    #[serde(default)]
+    pub enabled: bool,
+    /// 保留的最近请求条数
+    #[serde(default = "default_capture_max_entries")]
+    pub max_entries: u32,
+    /// 请求体与响应体各自保存的上限（KB），超出部分截断
+    #[serde(default = "default_capture_max_body_kb")]
+    pub max_body_kb: u32,
+}
+
+fn default_capture_max_entries() -> u32 {
+    50
+}
+
+fn default_capture_max_body_kb() -> u32 {
+    64
+}
+
+impl Default for ProxyCaptureSettings {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            max_entries: default_capture_max_entries(),
+            max_body_kb: default_capture_max_body_kb(),
+        }
+    }
+}
+
+/// 代理上游请求的域名解析：DoH 地址与全局主机映射（主机名 -> IP），供应商可另设主机映射
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ProxyDnsSettings {
+    /// DNS-over-HTTPS 地址（JSON 接口），未设置时使用系统解析
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub doh_url: Option<String>,
+    #[serde(default)]
+    pub hosts: HashMap<String, String>,
+}
+
+/// 代理上游连接使用的 HTTP 版本
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum UpstreamHttpVersion {
+    /// 按 ALPN 协商（优先 HTTP/2）
+    #[default]
+    Auto,
+    /// 强制 HTTP/1.1
+    Http1,
+    /// HTTP/2 prior knowledge：只使用 HTTP/2，明文 http:// 也直接发送 HTTP/2
+    Http2,
+}
+
+/// 代理上游连接的协议与连接复用参数（部分中转的 HTTP/2 实现有问题，可强制 HTTP/1.1）
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ProxyConnectionSettings {
+    #[serde(default)]
+    pub http_version: UpstreamHttpVersion,
+    /// 空闲连接在连接池中保留的时长（秒），0 表示不复用空闲连接
+    #[serde(default = "default_pool_idle_timeout_secs")]
+    pub pool_idle_timeout_secs: u64,
+    /// HTTP/2 keep-alive ping 间隔（秒），0 表示不发送
+    #[serde(default)]
+    pub http2_keep_alive_secs: u64,
+}
+
+fn default_pool_idle_timeout_secs() -> u64 {
+    90
+}
+
+impl Default for ProxyConnectionSettings {
+    fn default() -> Self {
+        Self {
+            http_version: UpstreamHttpVersion::default(),
+            pool_idle_timeout_secs: default_pool_idle_timeout_secs(),
+            http2_keep_alive_secs: 0,
+        }
+    }
+}
+
+/// 命名的通用配置片段：写入 live 配置时合并进去（供应商自身的配置优先）
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CommonFragment {
+    pub name: String,
+    /// Claude 为 JSON 对象文本，Codex 为 TOML 文本
+    pub content: String,
+    #[serde(default = "default_fragment_enabled")]
+    pub enabled: bool,
+}
+
+fn default_fragment_enabled() -> bool {
+    true
+}
+
+/// 各应用的通用配置片段（按列表顺序合并，靠后的片段优先）
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct CommonFragments {
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub claude: Vec<CommonFragment>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub codex: Vec<CommonFragment>,
+}
+
+/// 配置合并策略（按键路径生效，未配置的子路径继承最近的上级路径）
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum MergeStrategy {
+    /// 新值覆盖旧值（对象仍逐键递归合并）
+    Override,
+    /// 保留已有值，仅在缺失时使用新值
+    Preserve,
+    /// 数组追加去重，对象递归合并
+    Append,
+}
+
+/// 各应用的合并策略：键为点分隔路径（如 permissions.allow）
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct MergeStrategies {
+    #[serde(default)]
+    pub claude: BTreeMap<String, MergeStrategy>,
+    #[serde(default)]
+    pub codex: BTreeMap<String, MergeStrategy>,
+}
+
+impl Default for MergeStrategies {
+    /// 默认保留用户在 live 配置中自定义的权限、钩子与状态栏
+    fn default() -> Self {
+        let claude = ["permissions", "hooks", "statusLine"]
+            .into_iter()
+            .map(|key| (key.to_string(), MergeStrategy::Preserve))
+            .collect();
+        Self {
+            claude,
+            codex: BTreeMap::new(),
+        }
+    }
+}
+
+/// 代理按请求分类路由的规则（按应用分别配置，按顺序匹配）
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct RequestRoutes {
+    #[serde(default)]
+    pub claude: Vec<RequestRoute>,
+    #[serde(default)]
+    pub codex: Vec<RequestRoute>,
+}
+
+/// 单条请求路由规则：模型与路径条件同时满足时命中，未填写的条件不做限制
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RequestRoute {
+    #[serde(default)]
+    pub name: String,
+    /// 模型名（支持 `*` 通配，不区分大小写），如 `*haiku*`
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub model: Option<String>,
+    /// 请求路径前缀，如 `/v1/messages`
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub path: Option<String>,
+    /// 命中后优先使用的供应商 ID（按顺序）
+    #[serde(default)]
+    pub providers: Vec<String>,
+    /// 只使用上述供应商，不再回退到其余供应商
+    #[serde(default)]
+    pub exclusive: bool,
+}
+
+/// Webhook 通知设置
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct WebhookSettings {
+    pub url: String,
+    /// 签名密钥（可选）
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub secret: Option<String>,
+    #[serde(default)]
+    pub format: crate::webhook::WebhookFormat,
+    /// 订阅的事件，为空表示全部
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub events: Vec<crate::webhook::WebhookEvent>,
+}
+
+fn default_hook_enabled() -> bool {
+    true
+}
+
+/// 切换钩子：在切换供应商或运行模式前后执行的 shell 命令或 JS 片段
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SwitchHook {
+    pub id: String,
+    #[serde(default)]
+    pub name: String,
+    #[serde(default = "default_hook_enabled")]
+    pub enabled: bool,
+    pub event: crate::hooks::HookEvent,
+    #[serde(default)]
+    pub kind: crate::hooks::HookKind,
+    pub script: String,
+    /// 限定的应用（claude / codex），为空表示全部；运行模式变更不区分应用
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub apps: Vec<String>,
+    /// 超时（秒），未设置时使用默认值
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub timeout_secs: Option<u64>,
+    /// 前置钩子失败（非零退出、超时或 JS 结果为 false）时取消本次操作
+    #[serde(default)]
+    pub abort_on_failure: bool,
+}
+
 /// 应用设置结构，允许覆盖默认配置目录
 #[derive(Debug, Clone, Serialize, Deserialize)]
 #[serde(rename_all = "camelCase")]
@@ -36,6 +361,12 @@ pub struct AppSettings {
     pub show_in_tray: bool,
     #[serde(default = "default_minimize_to_tray_on_close")]
     pub minimize_to_tray_on_close: bool,
+    /// 登录系统时自动启动
+    #[serde(default)]
+    pub launch_at_login: bool,
+    /// 开机自启时隐藏主窗口，仅保留托盘图标（代理模式下代理照常启动）
+    #[serde(default)]
+    pub start_minimized: bool,
     /// 是否启用 Claude 插件联动
     #[serde(default)]
     pub enable_claude_plugin_integration: bool,
@@ -57,6 +388,78 @@ pub struct AppSettings {
     /// 代理模式下的重试次数，默认1，允许0
     #[serde(default = "default_proxy_retry_count")]
     pub proxy_retry_count: u32,
+    /// 代理模式下单个请求（含全部重试与供应商切换）的总时限（秒），0 表示不限制
+    #[serde(default)]
+    pub proxy_deadline_secs: u32,
+    /// 代理模式下请求发出后连接中断时仍重试或切换供应商（可能导致非幂等请求重复计费）
+    #[serde(default)]
+    pub proxy_aggressive_retry: bool,
+    /// 代理模式下在响应中附加 x-ccswitch-provider 等诊断响应头
+    #[serde(default)]
+    pub proxy_diagnostic_headers: bool,
+    /// 代理模式下在转发的请求中注入标明供应商的系统提示
+    #[serde(default)]
+    pub proxy_provider_banner: bool,
+    /// 代理模式下在本地估算并应答 `/v1/messages/count_tokens`，不再转发给上游
+    #[serde(default)]
+    pub proxy_local_count_tokens: bool,
+    /// 代理使用本地自签名证书以 HTTPS 监听
+    #[serde(default)]
+    pub proxy_tls: bool,
+    /// 代理的局域网共享
+    #[serde(default)]
+    pub lan_share: LanShareSettings,
+    /// 代理请求录制
+    #[serde(default)]
+    pub proxy_capture: ProxyCaptureSettings,
+    /// 代理上游请求的域名解析
+    #[serde(default)]
+    pub proxy_dns: ProxyDnsSettings,
+    /// 代理上游连接的协议与连接复用参数
+    #[serde(default)]
+    pub proxy_connection: ProxyConnectionSettings,
+    /// Codex 写入模式下在单个 config.toml 中维护全部供应商条目，切换时只改写 model_provider
+    #[serde(default)]
+    pub codex_single_config: bool,
+    /// 在 Claude Code 状态栏中显示当前供应商与剩余额度
+    #[serde(default)]
+    pub claude_statusline: bool,
+    /// VS Code 用户 settings.json 路径（供应商写入 Cline / Roo Code 配置时使用，未设置时使用 VS Code 默认位置）
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub vscode_settings_path: Option<String>,
+    /// 按探测结果定时重排供应商
+    #[serde(default)]
+    pub auto_rank: AutoRankSettings,
+    /// 写入模式下按时间段路由规则自动切换供应商
+    #[serde(default)]
+    pub routing_auto_switch: bool,
+    /// 代理按请求模型与路径选择供应商的规则
+    #[serde(default)]
+    pub request_routes: RequestRoutes,
+    /// 全局快捷键
+    #[serde(default)]
+    pub hotkeys: HotkeySettings,
+    /// 审计日志保留天数，0 表示永久保留
+    #[serde(default = "default_audit_retention_days")]
+    pub audit_retention_days: u32,
+    /// 团队托管配置源（未设置时不拉取）
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub managed_source: Option<ManagedSource>,
+    /// Webhook 通知（未设置时不发送）
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub webhook: Option<WebhookSettings>,
+    /// 切换供应商与运行模式前后执行的钩子
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub hooks: Vec<SwitchHook>,
+    /// 切换后检测到仍在运行的 Claude Code / Codex 会话时发送系统通知（界面提示始终显示）
+    #[serde(default)]
+    pub running_session_notice: bool,
+    /// 通用配置片段（仅通过片段管理命令修改）
+    #[serde(default)]
+    pub common_fragments: CommonFragments,
+    /// 写入 live 配置与合并通用配置片段时的按键合并策略
+    #[serde(default)]
+    pub merge_strategies: MergeStrategies,
 }
 
 fn default_show_in_tray() -> bool {
@@ -71,11 +474,17 @@ fn default_proxy_retry_count() -> u32 {
     1
 }
 
+fn default_audit_retention_days() -> u32 {
+    90
+}
+
 impl Default for AppSettings {
     fn default() -> Self {
         Self {
             show_in_tray: true,
             minimize_to_tray_on_close: true,
+            launch_at_login: false,
+            start_minimized: false,
             enable_claude_plugin_integration: false,
             claude_config_dir: None,
             codex_config_dir: None,
@@ -84,6 +493,30 @@ impl Default for AppSettings {
             custom_endpoints_codex: HashMap::new(),
             operation_mode: OperationMode::default(),
             proxy_retry_count: default_proxy_retry_count(),
+            proxy_deadline_secs: 0,
+            proxy_aggressive_retry: false,
+            proxy_diagnostic_headers: false,
+            proxy_provider_banner: false,
+            proxy_local_count_tokens: false,
+            proxy_tls: false,
+            lan_share: LanShareSettings::default(),
+            proxy_capture: ProxyCaptureSettings::default(),
+            proxy_dns: ProxyDnsSettings::default(),
+            proxy_connection: ProxyConnectionSettings::default(),
+            codex_single_config: false,
+            claude_statusline: false,
+            vscode_settings_path: None,
+            auto_rank: AutoRankSettings::default(),
+            routing_auto_switch: false,
+            request_routes: RequestRoutes::default(),
+            hotkeys: HotkeySettings::default(),
+            audit_retention_days: default_audit_retention_days(),
+            managed_source: None,
+            webhook: None,
+            hooks: Vec::new(),
+            running_session_notice: false,
+            common_fragments: CommonFragments::default(),
+            merge_strategies: MergeStrategies::default(),
         }
     }
 }
@@ -92,6 +525,9 @@ impl AppSettings {
     fn settings_path() -> PathBuf {
         // settings.json 必须使用固定路径，不能被 app_config_dir 覆盖
         // 否则会造成循环依赖：读取 settings 需要知道路径，但路径在 settings 中
+        if let Some(portable) = crate::portable::portable_data_dir() {
+            return portable.join("settings.json");
+        }
         dirs::home_dir()
             .expect("无法获取用户主目录")
             .join(".cc-switch")
@@ -113,11 +549,34 @@ impl AppSettings {
             .filter(|s| !s.is_empty())
             .map(|s| s.to_string());
 
+        self.vscode_settings_path = self
+            .vscode_settings_path
+            .as_ref()
+            .map(|s| s.trim())
+            .filter(|s| !s.is_empty())
+            .map(|s| s.to_string());
+
+        self.lan_share.bind_address = self.lan_share.bind_address.trim().to_string();
+        if self.lan_share.bind_address.is_empty() {
+            self.lan_share.bind_address = default_lan_share_bind_address();
+        }
+        self.lan_share.token = self.lan_share.token.trim().to_string();
+        self.lan_share.allowed_ips = self
+            .lan_share
+            .allowed_ips
+            .iter()
+            .map(|s| s.trim().to_string())
+            .filter(|s| !s.is_empty())
+            .collect();
+
+        self.proxy_capture.max_entries = self.proxy_capture.max_entries.clamp(1, 500);
+        self.proxy_capture.max_body_kb = self.proxy_capture.max_body_kb.clamp(1, 4096);
+
         self.language = self
             .language
             .as_ref()
             .map(|s| s.trim())
-            .filter(|s| matches!(*s, "en" | "zh"))
+            .filter(|s| matches!(*s, "en" | "zh" | "ja"))
             .map(|s| s.to_string());
     }
 
@@ -130,7 +589,7 @@ impl AppSettings {
                     settings
                 }
                 Err(err) => {
-                    log::warn!(
+                    tracing::warn!(
                         "解析设置文件失败，将使用默认设置。路径: {}, 错误: {}",
                         path.display(),
                         err
@@ -159,12 +618,18 @@ impl AppSettings {
     }
 }
 
+/// settings.json 的固定路径（不受 app_config_dir 覆盖影响）
+pub fn get_settings_file_path() -> PathBuf {
+    AppSettings::settings_path()
+}
+
 fn settings_store() -> &'static RwLock<AppSettings> {
     static STORE: OnceLock<RwLock<AppSettings>> = OnceLock::new();
     STORE.get_or_init(|| RwLock::new(AppSettings::load()))
 }
 
-fn resolve_override_path(raw: &str) -> PathBuf {
+/// 解析用户填写的目录（支持 ~ 开头）
+pub fn resolve_override_path(raw: &str) -> PathBuf {
     if raw == "~" {
         if let Some(home) = dirs::home_dir() {
             return home;
@@ -203,6 +668,14 @@ pub fn get_claude_override_dir() -> Option<PathBuf> {
         .map(|p| resolve_override_path(p))
 }
 
+pub fn get_vscode_settings_override() -> Option<PathBuf> {
+    let settings = settings_store().read().ok()?;
+    settings
+        .vscode_settings_path
+        .as_ref()
+        .map(|p| resolve_override_path(p))
+}
+
 pub fn get_codex_override_dir() -> Option<PathBuf> {
     let settings = settings_store().read().ok()?;
     settings
@@ -0,0 +1,388 @@
+//! 供应商分享：将供应商配置编码为单行 base64 字符串（可选去除密钥）并生成二维码，
+//! 同事粘贴该字符串或打开对应的 `ccswitch://add` 链接即可添加相同配置。
+//! 分享内容仅包含名称、官网、分类与 settings_config，不含 meta（用量脚本、自定义目录等）。
+//! 链接任何人都能构造，导入时 settings_config 只保留地址、密钥与模型字段：`apiKeyHelper`、`hooks`、
+//! `statusLine`、Codex 的 `notify` / `mcp_servers` 等会执行命令的键一律丢弃，并在确认时列出。
+
+#![allow(non_snake_case)]
+
+use base64::Engine;
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Value};
+use tauri::State;
+
+use crate::app_config::AppType;
+use crate::deeplink::{is_deep_link, DEEP_LINK_SCHEME};
+use crate::error::CcSwitchError;
+use crate::i18n::t_args;
+use crate::provider::Provider;
+use crate::store::AppState;
+
+/// 导入时保留的 Codex config.toml 顶层键
+const CODEX_CONFIG_KEYS: &[&str] = &["model_provider", "model", "model_reasoning_effort"];
+
+/// 导入时保留的 Codex `model_providers.<name>` 字段
+const CODEX_PROVIDER_KEYS: &[&str] = &["name", "base_url", "wire_api", "requires_openai_auth"];
+
+/// 分享格式版本
+const SHARE_VERSION: u32 = 1;
+
+/// 二维码渲染参数：每模块像素数与留白模块数
+const QR_SCALE: usize = 6;
+const QR_BORDER: usize = 4;
+
+fn default_version() -> u32 {
+    SHARE_VERSION
+}
+
+fn is_false(value: &bool) -> bool {
+    !*value
+}
+
+/// base64 中承载的 JSON
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct SharePayload {
+    #[serde(default = "default_version")]
+    v: u32,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    app: Option<String>,
+    name: String,
+    #[serde(alias = "settings_config")]
+    settings_config: Value,
+    #[serde(
+        default,
+        alias = "website_url",
+        skip_serializing_if = "Option::is_none"
+    )]
+    website_url: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    category: Option<String>,
+    /// 密钥已被清空，导入时需要用户补填
+    #[serde(default, skip_serializing_if = "is_false")]
+    key_stripped: bool,
+}
+
+/// 编码结果
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ProviderShare {
+    /// base64 字符串
+    pub payload: String,
+    /// `ccswitch://add?config=...` 链接
+    pub link: String,
+    /// 链接的二维码（PNG data URL），内容过长时为空
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub qr_png: Option<String>,
+    pub key_stripped: bool,
+}
+
+/// 解码结果：provider 已分配新 ID，确认后可直接添加
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DecodedShare {
+    pub app_type: String,
+    pub provider: Provider,
+    pub key_stripped: bool,
+    /// 导入时被丢弃的配置键（点分路径），确认时展示给用户
+    pub removed_keys: Vec<String>,
+}
+
+/// 清空密钥字段（仅清空已存在的键，保留结构以便导入后补填）
+fn strip_secrets(app_type: &AppType, settings: &Value) -> Value {
+    let mut settings = settings.clone();
+    for pointer in crate::redact::secret_pointers(app_type) {
+        if let Some(value) = settings.pointer_mut(pointer) {
+            *value = Value::String(String::new());
+        }
+    }
+    settings
+}
+
+/// Claude env 中允许导入的键：地址、密钥与模型
+fn is_allowed_claude_env(key: &str) -> bool {
+    matches!(
+        key,
+        "ANTHROPIC_BASE_URL" | "ANTHROPIC_AUTH_TOKEN" | "ANTHROPIC_API_KEY"
+    ) || (key.starts_with("ANTHROPIC_") && key.ends_with("_MODEL"))
+}
+
+/// 按白名单过滤 Codex config.toml；无法解析时整体丢弃
+fn sanitize_codex_config(text: &str, removed: &mut Vec<String>) -> String {
+    let Ok(table) = toml::from_str::<toml::Table>(text) else {
+        if !text.trim().is_empty() {
+            removed.push("config".to_string());
+        }
+        return String::new();
+    };
+    let mut kept = toml::Table::new();
+    for (key, value) in table {
+        if CODEX_CONFIG_KEYS.contains(&key.as_str()) {
+            kept.insert(key, value);
+            continue;
+        }
+        let providers = match value {
+            toml::Value::Table(providers) if key == "model_providers" => providers,
+            _ => {
+                removed.push(format!("config.{}", key));
+                continue;
+            }
+        };
+        let mut kept_providers = toml::Table::new();
+        for (name, provider) in providers {
+            let toml::Value::Table(fields) = provider else {
+                removed.push(format!("config.model_providers.{}", name));
+                continue;
+            };
+            let mut kept_fields = toml::Table::new();
+            for (field, value) in fields {
+                if CODEX_PROVIDER_KEYS.contains(&field.as_str()) {
+                    kept_fields.insert(field, value);
+                } else {
+                    removed.push(format!("config.model_providers.{}.{}", name, field));
+                }
+            }
+            kept_providers.insert(name, toml::Value::Table(kept_fields));
+        }
+        kept.insert(key, toml::Value::Table(kept_providers));
+    }
+    toml::to_string(&kept).unwrap_or_default()
+}
+
+/// 只保留白名单中的字段，返回过滤后的配置与被丢弃的键
+fn sanitize_settings(app_type: &AppType, settings: &Value) -> (Value, Vec<String>) {
+    let mut removed = Vec::new();
+    let Some(object) = settings.as_object() else {
+        return (json!({}), vec!["settings_config".to_string()]);
+    };
+    let keep = |section: &str, allowed: &dyn Fn(&str) -> bool, removed: &mut Vec<String>| {
+        let mut kept = serde_json::Map::new();
+        match object.get(section) {
+            Some(Value::Object(entries)) => {
+                for (key, value) in entries {
+                    if allowed(key) && value.is_string() {
+                        kept.insert(key.clone(), value.clone());
+                    } else {
+                        removed.push(format!("{}.{}", section, key));
+                    }
+                }
+            }
+            Some(_) => removed.push(section.to_string()),
+            None => {}
+        }
+        Value::Object(kept)
+    };
+
+    let sanitized = match app_type {
+        AppType::Claude => json!({ "env": keep("env", &is_allowed_claude_env, &mut removed) }),
+        AppType::Codex => {
+            let auth = keep("auth", &|key| key == "OPENAI_API_KEY", &mut removed);
+            let config = match object.get("config") {
+                Some(Value::String(text)) => sanitize_codex_config(text, &mut removed),
+                Some(_) => {
+                    removed.push("config".to_string());
+                    String::new()
+                }
+                None => String::new(),
+            };
+            json!({ "auth": auth, "config": config })
+        }
+    };
+    let sections: &[&str] = match app_type {
+        AppType::Claude => &["env"],
+        AppType::Codex => &["auth", "config"],
+    };
+    removed.extend(
+        object
+            .keys()
+            .filter(|key| !sections.contains(&key.as_str()))
+            .cloned(),
+    );
+    (sanitized, removed)
+}
+
+/// 兼容标准与 URL-safe 两种 base64，填充可省略
+fn decode_base64(input: &str) -> Result<Vec<u8>, String> {
+    use base64::engine::general_purpose::{STANDARD_NO_PAD, URL_SAFE_NO_PAD};
+    let trimmed = input.trim().trim_end_matches('=');
+    URL_SAFE_NO_PAD
+        .decode(trimmed)
+        .or_else(|_| STANDARD_NO_PAD.decode(trimmed))
+        .map_err(|e| e.to_string())
+}
+
+/// 编码供应商为分享字符串
+pub fn encode(
+    app_type: &AppType,
+    provider: &Provider,
+    include_key: bool,
+) -> Result<String, String> {
+    let payload = SharePayload {
+        v: SHARE_VERSION,
+        app: Some(app_type.as_str().to_string()),
+        name: provider.name.clone(),
+        settings_config: if include_key {
+            provider.settings_config.clone()
+        } else {
+            strip_secrets(app_type, &provider.settings_config)
+        },
+        website_url: provider.website_url.clone(),
+        category: provider.category.clone(),
+        key_stripped: !include_key,
+    };
+    let json = serde_json::to_vec(&payload).map_err(|e| format!("序列化分享内容失败: {}", e))?;
+    Ok(base64::engine::general_purpose::URL_SAFE_NO_PAD.encode(json))
+}
+
+/// 分享字符串对应的深链接（URL-safe base64 无需再转义）
+pub fn share_link(payload: &str) -> String {
+    format!("{}://add?config={}", DEEP_LINK_SCHEME, payload)
+}
+
+/// 解码分享字符串，兼容直接粘贴 `ccswitch://add?...` 链接；未去除密钥时校验配置
+pub fn decode(input: &str) -> Result<DecodedShare, String> {
+    let input = input.trim();
+    let invalid = |e: &dyn std::fmt::Display| t_args("errors.shareConfigInvalid", &[("error", e)]);
+
+    let (encoded, link_app) = if is_deep_link(input) {
+        let url = url::Url::parse(input).map_err(|e| invalid(&e))?;
+        let query = |name: &str| {
+            url.query_pairs()
+                .find(|(k, _)| k == name)
+                .map(|(_, v)| v.into_owned())
+        };
+        let config =
+            query("config").ok_or_else(|| t_args("errors.missingParam", &[("name", &"config")]))?;
+        (config, query("app"))
+    } else {
+        (input.to_string(), None)
+    };
+
+    let bytes = decode_base64(&encoded).map_err(|e| invalid(&e))?;
+    let payload: SharePayload = serde_json::from_slice(&bytes).map_err(|e| invalid(&e))?;
+    if payload.v > SHARE_VERSION {
+        return Err(invalid(&format!("不支持的分享格式版本 {}", payload.v)));
+    }
+
+    let app_type = AppType::from(
+        link_app
+            .as_deref()
+            .or(payload.app.as_deref())
+            .unwrap_or("claude"),
+    );
+    let (settings_config, removed_keys) = sanitize_settings(&app_type, &payload.settings_config);
+    if !removed_keys.is_empty() {
+        tracing::warn!("分享配置中的以下键未导入: {}", removed_keys.join(", "));
+    }
+    if !payload.key_stripped {
+        crate::validation::ensure_valid(&app_type, &settings_config).map_err(|e| invalid(&e))?;
+    }
+
+    let mut provider = Provider::with_id(
+        uuid::Uuid::new_v4().to_string(),
+        payload.name.trim().to_string(),
+        settings_config,
+        payload.website_url,
+    );
+    provider.category = payload.category;
+    provider.created_at = Some(chrono::Utc::now().timestamp_millis());
+    Ok(DecodedShare {
+        app_type: app_type.as_str().to_string(),
+        provider,
+        key_stripped: payload.key_stripped,
+        removed_keys,
+    })
+}
+
+/// 将二维码渲染为灰度 PNG（四周留白 QR_BORDER 个模块，每模块 QR_SCALE 像素）
+fn qr_png(text: &str) -> Result<Vec<u8>, String> {
+    let code = qrcode::QrCode::with_error_correction_level(text.as_bytes(), qrcode::EcLevel::M)
+        .map_err(|e| format!("生成二维码失败: {}", e))?;
+    let size = code.width();
+    let colors = code.to_colors();
+    let dim = (size + QR_BORDER * 2) * QR_SCALE;
+    let mut pixels = vec![0xFFu8; dim * dim];
+    for (index, color) in colors.iter().enumerate() {
+        if *color != qrcode::Color::Dark {
+            continue;
+        }
+        let (x, y) = (index % size, index / size);
+        for dy in 0..QR_SCALE {
+            let start = ((y + QR_BORDER) * QR_SCALE + dy) * dim + (x + QR_BORDER) * QR_SCALE;
+            pixels[start..start + QR_SCALE].fill(0);
+        }
+    }
+
+    let mut out = Vec::new();
+    let mut encoder = png::Encoder::new(&mut out, dim as u32, dim as u32);
+    encoder.set_color(png::ColorType::Grayscale);
+    encoder.set_depth(png::BitDepth::Eight);
+    let mut writer = encoder
+        .write_header()
+        .map_err(|e| format!("写入 PNG 头失败: {}", e))?;
+    writer
+        .write_image_data(&pixels)
+        .map_err(|e| format!("写入 PNG 数据失败: {}", e))?;
+    writer
+        .finish()
+        .map_err(|e| format!("写入 PNG 失败: {}", e))?;
+    Ok(out)
+}
+
+/// 生成链接二维码（PNG data URL）；内容超出二维码容量时返回 None
+fn qr_data_url(text: &str) -> Option<String> {
+    let png = qr_png(text)
+        .map_err(|e| tracing::warn!("生成分享二维码失败: {}", e))
+        .ok()?;
+    Some(format!(
+        "data:image/png;base64,{}",
+        base64::engine::general_purpose::STANDARD.encode(png)
+    ))
+}
+
+/// 生成供应商分享字符串与二维码（默认去除密钥）
+#[tauri::command]
+pub async fn encode_provider_share(
+    state: State<'_, AppState>,
+    id: String,
+    include_key: Option<bool>,
+    app_type: Option<AppType>,
+    app: Option<String>,
+    appType: Option<String>,
+) -> Result<ProviderShare, CcSwitchError> {
+    let app_type = app_type
+        .or_else(|| app.as_deref().map(|s| s.into()))
+        .or_else(|| appType.as_deref().map(|s| s.into()))
+        .unwrap_or(AppType::Claude);
+    let include_key = include_key.unwrap_or(false);
+    if include_key {
+        crate::app_lock::ensure_unlocked()?;
+    }
+
+    let payload = {
+        let config = state
+            .config
+            .lock()
+            .map_err(|e| t_args("errors.lockFailed", &[("error", &e)]))?;
+        let provider = config
+            .get_manager(&app_type)
+            .ok_or_else(|| t_args("errors.appTypeNotFound", &[("app", &app_type.as_str())]))?
+            .providers
+            .get(&id)
+            .ok_or_else(|| t_args("errors.providerNotFound", &[("id", &id)]))?;
+        encode(&app_type, provider, include_key)?
+    };
+    let link = share_link(&payload);
+    Ok(ProviderShare {
+        qr_png: qr_data_url(&link),
+        payload,
+        link,
+        key_stripped: !include_key,
+    })
+}
+
+/// 解析分享字符串（不写入配置，由前端确认后调用 add_provider）
+#[tauri::command]
+pub async fn decode_provider_share(payload: String) -> Result<DecodedShare, CcSwitchError> {
+    Ok(decode(&payload)?)
+}
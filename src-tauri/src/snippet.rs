@@ -0,0 +1,596 @@
+//! 剪贴板导入：识别中转商常见的配置片段——`export ANTHROPIC_BASE_URL=...` 等 shell/PowerShell/cmd
+//! 环境变量行、JSON env 块（含不完整的键值片段）、Codex config.toml 片段——
+//! 生成预填的供应商草稿交由用户确认，不写入配置。
+//! 中转商更换地址或密钥后，也可粘贴新片段匹配到已有供应商，只更新 API 地址与凭证，保留其余设置。
+
+#![allow(non_snake_case)]
+
+use regex::Regex;
+use serde::Serialize;
+use serde_json::{Map, Value};
+use std::collections::BTreeMap;
+use std::sync::OnceLock;
+use tauri::State;
+
+use crate::app_config::AppType;
+use crate::error::CcSwitchError;
+use crate::i18n::{t, t_args};
+use crate::provider::Provider;
+use crate::store::AppState;
+
+/// 根据 base_url 生成 Codex 配置时使用的 model_providers 键名回退值
+const DEFAULT_CODEX_PROVIDER: &str = "custom";
+
+/// 解析结果
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ProviderSnippet {
+    pub app_type: String,
+    pub provider: Provider,
+    /// 片段中未包含、需要用户补填的字段（如 `env.ANTHROPIC_AUTH_TOKEN`）
+    pub missing: Vec<String>,
+}
+
+/// 从片段中提取出的原始字段
+#[derive(Debug, Default)]
+pub(crate) struct Extracted {
+    /// 环境变量（键统一为大写）
+    pub env: BTreeMap<String, String>,
+    /// Codex config.toml 片段
+    pub codex_config: Option<String>,
+}
+
+/// Claude 配置中保留的环境变量
+fn is_claude_key(key: &str) -> bool {
+    key.starts_with("ANTHROPIC_") || key.starts_with("CLAUDE_CODE_") || key == "API_TIMEOUT_MS"
+}
+
+/// 是否为关心的环境变量，用于从任意片段中过滤无关的赋值
+fn is_relevant_key(key: &str) -> bool {
+    is_claude_key(key) || key.starts_with("OPENAI_")
+}
+
+/// 形如环境变量赋值的行（`export`、`set`、`setx`、`$env:` 或大写变量名开头）
+fn shell_line_pattern() -> &'static Regex {
+    static RE: OnceLock<Regex> = OnceLock::new();
+    RE.get_or_init(|| {
+        Regex::new(r#"^\s*(?:(?i:export|set|setx)\s|\$env:|"?[A-Z][A-Z0-9_]*\s*=)"#)
+            .expect("内置正则无效")
+    })
+}
+
+/// 单个赋值：`KEY=value`、`KEY="value"`、`$env:KEY = "value"`、`set "KEY=value"`、`setx KEY value`
+fn assignment_pattern() -> &'static Regex {
+    static RE: OnceLock<Regex> = OnceLock::new();
+    RE.get_or_init(|| {
+        Regex::new(
+            r#"(?i:setx\s+([A-Za-z_][A-Za-z0-9_]*)\s+|([A-Za-z_][A-Za-z0-9_]*)\s*=\s*)("[^"]*"|'[^']*'|[^\s;&"']+)"#,
+        )
+        .expect("内置正则无效")
+    })
+}
+
+/// JSON 键值片段：`"KEY": "value"`
+fn json_pair_pattern() -> &'static Regex {
+    static RE: OnceLock<Regex> = OnceLock::new();
+    RE.get_or_init(|| {
+        Regex::new(r#""([A-Za-z_][A-Za-z0-9_]*)"\s*:\s*"((?:[^"\\]|\\.)*)""#).expect("内置正则无效")
+    })
+}
+
+fn unquote(value: &str) -> &str {
+    value
+        .strip_prefix('"')
+        .and_then(|v| v.strip_suffix('"'))
+        .or_else(|| value.strip_prefix('\'').and_then(|v| v.strip_suffix('\'')))
+        .unwrap_or(value)
+}
+
+fn insert_env(env: &mut BTreeMap<String, String>, key: &str, value: &str) {
+    let key = key.to_ascii_uppercase();
+    let value = value.trim();
+    if is_relevant_key(&key) && !value.is_empty() {
+        env.insert(key, value.to_string());
+    }
+}
+
+/// 收集 JSON 对象中的环境变量：支持 `{"env": {...}}`、Codex 的 `{"auth": {...}, "config": "..."}`
+/// 以及直接平铺的 `{"ANTHROPIC_BASE_URL": "..."}`
+fn collect_json(obj: &Map<String, Value>, extracted: &mut Extracted) {
+    for (key, value) in obj {
+        match value {
+            Value::Object(inner) if key == "env" || key == "auth" => {
+                collect_json(inner, extracted);
+            }
+            Value::String(text) if key == "config" => {
+                extracted.codex_config = Some(text.clone());
+            }
+            Value::String(text) => insert_env(&mut extracted.env, key, text),
+            _ => {}
+        }
+    }
+}
+
+/// 尝试按 JSON 解析；不完整的片段（缺少外层花括号、末尾多余逗号）先补全再解析
+fn parse_json(text: &str) -> Option<Map<String, Value>> {
+    let text = text.trim().trim_end_matches(',');
+    serde_json::from_str::<Value>(text)
+        .ok()
+        .or_else(|| serde_json::from_str::<Value>(&format!("{{{}}}", text)).ok())
+        .and_then(|v| match v {
+            Value::Object(obj) => Some(obj),
+            _ => None,
+        })
+}
+
+/// 是否像 Codex config.toml 片段
+fn is_codex_toml(table: &toml::Table) -> bool {
+    ["model_provider", "model_providers", "base_url"]
+        .iter()
+        .any(|key| table.contains_key(*key))
+}
+
+/// 从片段中提取环境变量与 Codex 配置
+pub(crate) fn extract(text: &str) -> Extracted {
+    let mut extracted = Extracted::default();
+
+    // 先取出环境变量行，其余部分再按 JSON / TOML 识别（Codex 片段常是 TOML 加一行 export）
+    let mut rest = Vec::new();
+    for line in text.lines() {
+        let trimmed = line.trim();
+        if trimmed.starts_with('#') || trimmed.starts_with("//") {
+            continue;
+        }
+        if !shell_line_pattern().is_match(line) {
+            rest.push(line);
+            continue;
+        }
+        for caps in assignment_pattern().captures_iter(line) {
+            let Some(key) = caps.get(1).or_else(|| caps.get(2)) else {
+                continue;
+            };
+            insert_env(&mut extracted.env, key.as_str(), unquote(&caps[3]));
+        }
+    }
+
+    let rest = rest.join("\n");
+    if rest.trim().is_empty() {
+        return extracted;
+    }
+    if let Some(obj) = parse_json(&rest) {
+        collect_json(&obj, &mut extracted);
+    } else if toml::from_str::<toml::Table>(&rest)
+        .ok()
+        .is_some_and(|table| is_codex_toml(&table))
+    {
+        extracted.codex_config = Some(rest.trim().to_string());
+    } else {
+        for caps in json_pair_pattern().captures_iter(&rest) {
+            let value = serde_json::from_str::<String>(&format!("\"{}\"", &caps[2]))
+                .unwrap_or_else(|_| caps[2].to_string());
+            insert_env(&mut extracted.env, &caps[1], &value);
+        }
+    }
+    extracted
+}
+
+/// 由 base_url 推断供应商名称：去掉 `api.`、`www.` 前缀的主机名
+pub(crate) fn name_from_url(base_url: &str) -> Option<String> {
+    let url = url::Url::parse(base_url.trim()).ok()?;
+    let host = url.host_str()?;
+    let host = host
+        .strip_prefix("api.")
+        .or_else(|| host.strip_prefix("www."))
+        .unwrap_or(host);
+    Some(host.to_string())
+}
+
+/// Codex 片段中 model_provider 指向的 base_url（片段只有顶层 base_url 时取顶层）
+pub(crate) fn codex_base_url(config: &str) -> Option<String> {
+    let table = toml::from_str::<toml::Table>(config).ok()?;
+    let nested = table
+        .get("model_provider")
+        .and_then(|v| v.as_str())
+        .and_then(|name| table.get("model_providers")?.get(name)?.get("base_url"));
+    nested
+        .or_else(|| table.get("base_url"))
+        .and_then(|v| v.as_str())
+        .map(str::to_string)
+}
+
+/// 生成最简的 Codex config.toml：沿用片段中的 model 等顶层设置，补全 model_providers 条目
+pub(crate) fn build_codex_config(
+    fragment: Option<&str>,
+    base_url: &str,
+    name: &str,
+    wire_api: &str,
+) -> String {
+    let mut doc = fragment
+        .and_then(|text| text.parse::<toml_edit::DocumentMut>().ok())
+        .unwrap_or_default();
+    doc.remove("base_url");
+    let key = name
+        .to_ascii_lowercase()
+        .chars()
+        .map(|c| if c.is_ascii_alphanumeric() { c } else { '_' })
+        .collect::<String>();
+    let key = key.trim_matches('_');
+    let key = if key.is_empty() {
+        DEFAULT_CODEX_PROVIDER
+    } else {
+        key
+    };
+
+    let mut provider = toml_edit::Table::new();
+    provider.insert("name", toml_edit::value(key));
+    provider.insert("base_url", toml_edit::value(base_url));
+    provider.insert("wire_api", toml_edit::value(wire_api));
+    provider.insert("requires_openai_auth", toml_edit::value(true));
+    let mut providers = toml_edit::Table::new();
+    providers.set_implicit(true);
+    providers.insert(key, toml_edit::Item::Table(provider));
+    doc.insert("model_provider", toml_edit::value(key));
+    doc.insert("model_providers", toml_edit::Item::Table(providers));
+    doc.to_string()
+}
+
+/// 解析片段为供应商草稿；既没有 API 地址也没有密钥时返回错误
+pub fn parse(text: &str, app_type: &AppType) -> Result<ProviderSnippet, String> {
+    let extracted = extract(text);
+    let mut missing = Vec::new();
+
+    let (settings_config, base_url) = match app_type {
+        AppType::Claude => {
+            let env: Map<String, Value> = extracted
+                .env
+                .iter()
+                .filter(|(key, _)| is_claude_key(key))
+                .map(|(key, value)| (key.clone(), Value::String(value.clone())))
+                .collect();
+            let base_url = env
+                .get("ANTHROPIC_BASE_URL")
+                .and_then(|v| v.as_str())
+                .map(str::to_string);
+            let has_key =
+                env.contains_key("ANTHROPIC_AUTH_TOKEN") || env.contains_key("ANTHROPIC_API_KEY");
+            if base_url.is_none() && !has_key {
+                return Err(t("errors.snippetUnrecognized"));
+            }
+            if base_url.is_none() {
+                missing.push("env.ANTHROPIC_BASE_URL".to_string());
+            }
+            if !has_key {
+                missing.push("env.ANTHROPIC_AUTH_TOKEN".to_string());
+            }
+            (serde_json::json!({ "env": env }), base_url)
+        }
+        AppType::Codex => {
+            let api_key = extracted.env.get("OPENAI_API_KEY").cloned();
+            let base_url = extracted
+                .codex_config
+                .as_deref()
+                .and_then(codex_base_url)
+                .or_else(|| extracted.env.get("OPENAI_BASE_URL").cloned());
+            if base_url.is_none() && api_key.is_none() {
+                return Err(t("errors.snippetUnrecognized"));
+            }
+
+            // 片段自带完整的 model_providers 时原样保留，否则按 base_url 补全
+            let fragment = extracted.codex_config.as_deref();
+            let complete = fragment
+                .and_then(|text| toml::from_str::<toml::Table>(text).ok())
+                .is_some_and(|table| table.contains_key("model_providers"));
+            let config = match (&base_url, fragment) {
+                (_, Some(text)) if complete => text.to_string(),
+                (Some(url), _) => {
+                    let name = name_from_url(url).unwrap_or_default();
+                    build_codex_config(fragment, url, &name, "responses")
+                }
+                (None, _) => {
+                    missing.push("config.base_url".to_string());
+                    String::new()
+                }
+            };
+
+            let mut auth = Map::new();
+            match api_key {
+                Some(key) => {
+                    auth.insert("OPENAI_API_KEY".to_string(), Value::String(key));
+                }
+                None => missing.push("auth.OPENAI_API_KEY".to_string()),
+            }
+            (
+                serde_json::json!({ "auth": auth, "config": config }),
+                base_url,
+            )
+        }
+    };
+
+    let name = base_url
+        .as_deref()
+        .and_then(name_from_url)
+        .unwrap_or_default();
+    let mut provider = Provider::with_id(
+        uuid::Uuid::new_v4().to_string(),
+        name,
+        settings_config,
+        None,
+    );
+    provider.created_at = Some(chrono::Utc::now().timestamp_millis());
+    Ok(ProviderSnippet {
+        app_type: app_type.as_str().to_string(),
+        provider,
+        missing,
+    })
+}
+
+/// 解析粘贴的配置片段（不写入配置，由前端确认后调用 add_provider）
+#[tauri::command]
+pub async fn parse_provider_snippet(
+    text: String,
+    app_type: Option<AppType>,
+    app: Option<String>,
+    appType: Option<String>,
+) -> Result<ProviderSnippet, CcSwitchError> {
+    let app_type = app_type
+        .or_else(|| app.as_deref().map(|s| s.into()))
+        .or_else(|| appType.as_deref().map(|s| s.into()))
+        .unwrap_or(AppType::Claude);
+    Ok(parse(&text, &app_type)?)
+}
+
+/// 按新片段更新已有供应商时的单个字段变化
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct FieldChange {
+    /// 以点分隔的字段路径，如 `env.ANTHROPIC_BASE_URL`
+    pub field: String,
+    /// 原值与新值（凭证已脱敏）
+    pub old: Option<String>,
+    pub new: String,
+}
+
+/// 更新合并预览
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SnippetMerge {
+    pub app_type: String,
+    /// 合并后的供应商：仅替换 API 地址与凭证，其余设置保持不变；
+    /// 未变化的凭证保持脱敏，由 update_provider 保存时还原
+    pub provider: Provider,
+    pub changes: Vec<FieldChange>,
+}
+
+/// 片段中可用于更新的字段：Claude 为 env 中的地址与凭证，Codex 为 OPENAI_API_KEY 与 base_url
+fn update_fields(extracted: &Extracted, app_type: &AppType) -> Vec<(&'static str, String)> {
+    let mut fields = Vec::new();
+    match app_type {
+        AppType::Claude => {
+            for (field, key) in [
+                ("env.ANTHROPIC_BASE_URL", "ANTHROPIC_BASE_URL"),
+                ("env.ANTHROPIC_AUTH_TOKEN", "ANTHROPIC_AUTH_TOKEN"),
+                ("env.ANTHROPIC_API_KEY", "ANTHROPIC_API_KEY"),
+            ] {
+                if let Some(value) = extracted.env.get(key) {
+                    fields.push((field, value.clone()));
+                }
+            }
+        }
+        AppType::Codex => {
+            let base_url = extracted
+                .codex_config
+                .as_deref()
+                .and_then(codex_base_url)
+                .or_else(|| extracted.env.get("OPENAI_BASE_URL").cloned());
+            if let Some(url) = base_url {
+                fields.push(("config.base_url", url));
+            }
+            if let Some(key) = extracted.env.get("OPENAI_API_KEY") {
+                fields.push(("auth.OPENAI_API_KEY", key.clone()));
+            }
+        }
+    }
+    fields
+}
+
+/// 主域名（主机名的最后两段），用于识别同一中转商更换的子域名
+fn root_domain(host: &str) -> &str {
+    let mut dots = host.rmatch_indices('.').map(|(i, _)| i);
+    dots.next();
+    match dots.next() {
+        Some(i) => &host[i + 1..],
+        None => host,
+    }
+}
+
+/// 供应商与片段的相似度：同一 API 主机、同一主域名、官网同域、名称包含域名、沿用同一密钥分别加分
+fn match_score(provider: &Provider, app_type: &AppType, fields: &[(&'static str, String)]) -> u32 {
+    let value = |field: &str| {
+        fields
+            .iter()
+            .find(|(f, _)| *f == field)
+            .map(|(_, v)| v.as_str())
+    };
+    let new_url = value("env.ANTHROPIC_BASE_URL").or_else(|| value("config.base_url"));
+    let mut score = 0;
+
+    if let Some(new_host) = new_url.and_then(name_from_url) {
+        let new_root = root_domain(&new_host);
+        match provider
+            .base_url(app_type)
+            .as_deref()
+            .and_then(name_from_url)
+        {
+            Some(old_host) if old_host == new_host => score += 4,
+            Some(old_host) if root_domain(&old_host) == new_root => score += 2,
+            _ => {}
+        }
+        if let Some(site) = provider.website_url.as_deref().and_then(name_from_url) {
+            if root_domain(&site) == new_root {
+                score += 2;
+            }
+        }
+        let label = new_root.split('.').next().unwrap_or(new_root);
+        if label.len() >= 3 && provider.name.to_lowercase().contains(label) {
+            score += 1;
+        }
+    }
+
+    let same_key = [
+        "env.ANTHROPIC_AUTH_TOKEN",
+        "env.ANTHROPIC_API_KEY",
+        "auth.OPENAI_API_KEY",
+    ]
+    .iter()
+    .any(|field| {
+        let pointer = format!("/{}", field.replace('.', "/"));
+        value(field).is_some_and(|new| {
+            provider
+                .settings_config
+                .pointer(&pointer)
+                .and_then(|v| v.as_str())
+                == Some(new)
+        })
+    });
+    if same_key {
+        score += 3;
+    }
+    score
+}
+
+/// 可被片段更新的供应商：托管、锁定与订阅登录的供应商不参与
+fn is_updatable(provider: &Provider) -> bool {
+    !provider.is_managed()
+        && !provider.is_locked()
+        && provider.credential_kind() == crate::provider::CredentialKind::ApiKey
+}
+
+/// 设置 Codex 配置中 model_provider 指向条目的 base_url（只改该字段，保留注释与其他设置）
+pub(crate) fn set_codex_base_url(config: &str, base_url: &str) -> Option<String> {
+    let mut doc = config.parse::<toml_edit::DocumentMut>().ok()?;
+    let name = doc.get("model_provider")?.as_str()?.to_string();
+    let provider = doc
+        .get_mut("model_providers")?
+        .get_mut(&name)?
+        .as_table_like_mut()?;
+    provider.insert("base_url", toml_edit::value(base_url));
+    Some(doc.to_string())
+}
+
+/// 计算合并结果与字段变化
+fn merge(
+    provider: &Provider,
+    app_type: &AppType,
+    fields: &[(&'static str, String)],
+) -> SnippetMerge {
+    let secret = |field: &str| field != "env.ANTHROPIC_BASE_URL" && field != "config.base_url";
+    let display = |field: &str, value: &str| {
+        if secret(field) {
+            crate::redact::mask(value)
+        } else {
+            value.to_string()
+        }
+    };
+
+    let mut merged = crate::redact::redact_provider(app_type, provider);
+    let mut changes = Vec::new();
+    for (field, new) in fields {
+        if *field == "config.base_url" {
+            let old = provider.base_url(app_type);
+            if old.as_deref() == Some(new.as_str()) {
+                continue;
+            }
+            let config = merged
+                .settings_config
+                .get("config")
+                .and_then(|v| v.as_str())
+                .and_then(|text| set_codex_base_url(text, new));
+            let Some(config) = config else {
+                continue;
+            };
+            merged.settings_config["config"] = Value::String(config);
+            changes.push(FieldChange {
+                field: field.to_string(),
+                old,
+                new: new.clone(),
+            });
+            continue;
+        }
+
+        let pointer = format!("/{}", field.replace('.', "/"));
+        let old = provider
+            .settings_config
+            .pointer(&pointer)
+            .and_then(|v| v.as_str())
+            .map(str::to_string);
+        if old.as_deref() == Some(new.as_str()) {
+            continue;
+        }
+        let (section, key) = field.split_once('.').unwrap_or(("env", field));
+        if !merged.settings_config[section].is_object() {
+            merged.settings_config[section] = Value::Object(Map::new());
+        }
+        merged.settings_config[section][key] = Value::String(new.clone());
+        changes.push(FieldChange {
+            field: field.to_string(),
+            old: old.as_deref().map(|v| display(field, v)),
+            new: display(field, new),
+        });
+    }
+
+    SnippetMerge {
+        app_type: app_type.as_str().to_string(),
+        provider: merged,
+        changes,
+    }
+}
+
+/// 用粘贴的新片段更新已有供应商的预览：未指定供应商时按 API 地址、名称与密钥匹配最相近的一个。
+/// 不写入配置，由前端展示变化并确认后调用 update_provider
+#[tauri::command]
+pub async fn merge_provider_snippet(
+    state: State<'_, AppState>,
+    text: String,
+    provider_id: Option<String>,
+    providerId: Option<String>,
+    app_type: Option<AppType>,
+    app: Option<String>,
+    appType: Option<String>,
+) -> Result<SnippetMerge, CcSwitchError> {
+    let app_type = app_type
+        .or_else(|| app.as_deref().map(|s| s.into()))
+        .or_else(|| appType.as_deref().map(|s| s.into()))
+        .unwrap_or(AppType::Claude);
+    let fields = update_fields(&extract(&text), &app_type);
+    if fields.is_empty() {
+        return Err(t("errors.snippetUnrecognized").into());
+    }
+
+    let config = state
+        .config
+        .lock()
+        .map_err(|e| t_args("errors.lockFailed", &[("error", &e)]))?;
+    let manager = config
+        .get_manager(&app_type)
+        .ok_or_else(|| t_args("errors.appTypeNotFound", &[("app", &app_type.as_str())]))?;
+    let provider = match provider_id.or(providerId) {
+        Some(id) => {
+            let provider = manager
+                .providers
+                .get(&id)
+                .ok_or_else(|| t_args("errors.providerNotFound", &[("id", &id)]))?;
+            provider.ensure_editable()?;
+            provider
+        }
+        None => manager
+            .providers
+            .values()
+            .filter(|p| is_updatable(p))
+            .map(|p| (match_score(p, &app_type, &fields), p))
+            .filter(|(score, _)| *score > 0)
+            .max_by_key(|(score, p)| (*score, p.id == manager.current))
+            .map(|(_, p)| p)
+            .ok_or_else(|| t("errors.snippetNoMatch"))?,
+    };
+    Ok(merge(provider, &app_type, &fields))
+}
@@ -7,6 +7,9 @@ const DEFAULT_TIMEOUT_SECS: u64 = 8;
 const MAX_TIMEOUT_SECS: u64 = 30;
 const MIN_TIMEOUT_SECS: u64 = 2;
 
+/// 本地服务的测速超时：本地服务要么立即响应，要么没有运行
+pub(crate) const LOCAL_TIMEOUT_SECS: u64 = 3;
+
 #[derive(Debug, Clone, Serialize)]
 pub struct EndpointLatency {
     pub url: String,
@@ -15,7 +18,7 @@ pub struct EndpointLatency {
     pub error: Option<String>,
 }
 
-fn build_client(timeout_secs: u64) -> Result<Client, String> {
+pub(crate) fn build_client(timeout_secs: u64) -> Result<Client, String> {
     Client::builder()
         .timeout(Duration::from_secs(timeout_secs))
         .redirect(reqwest::redirect::Policy::limited(5))
@@ -24,6 +27,27 @@ fn build_client(timeout_secs: u64) -> Result<Client, String> {
         .map_err(|e| format!("创建 HTTP 客户端失败: {e}"))
 }
 
+/// 本地服务（localhost / 回环地址）的测速客户端：不经系统代理
+fn build_local_client() -> Result<Client, String> {
+    Client::builder()
+        .timeout(Duration::from_secs(LOCAL_TIMEOUT_SECS))
+        .redirect(reqwest::redirect::Policy::limited(5))
+        .user_agent("cc-switch-speedtest/1.0")
+        .no_proxy()
+        .build()
+        .map_err(|e| format!("创建 HTTP 客户端失败: {e}"))
+}
+
+/// 是否为本机地址（Ollama、LM Studio 等本地模型服务）
+pub(crate) fn is_local_url(url: &Url) -> bool {
+    match url.host() {
+        Some(url::Host::Domain(host)) => crate::dns::is_local_host(host),
+        Some(url::Host::Ipv4(ip)) => ip.is_loopback() || ip.is_unspecified(),
+        Some(url::Host::Ipv6(ip)) => ip.is_loopback() || ip.is_unspecified(),
+        None => false,
+    }
+}
+
 fn sanitize_timeout(timeout_secs: Option<u64>) -> u64 {
     let secs = timeout_secs.unwrap_or(DEFAULT_TIMEOUT_SECS);
     secs.clamp(MIN_TIMEOUT_SECS, MAX_TIMEOUT_SECS)
@@ -39,9 +63,11 @@ pub async fn test_endpoints(
 
     let timeout = sanitize_timeout(timeout_secs);
     let client = build_client(timeout)?;
+    let local_client = build_local_client()?;
 
     let tasks = urls.into_iter().map(|raw_url| {
         let client = client.clone();
+        let local_client = local_client.clone();
         async move {
             let trimmed = raw_url.trim().to_string();
             if trimmed.is_empty() {
@@ -65,8 +91,15 @@ pub async fn test_endpoints(
                 }
             };
 
-            // 先进行一次“热身”请求，忽略其结果，仅用于复用连接/绕过首包惩罚
-            let _ = client.get(parsed_url.clone()).send().await;
+            // 本地服务不经系统代理、也无需热身；其余地址先进行一次“热身”请求，
+            // 忽略其结果，仅用于复用连接/绕过首包惩罚
+            let local = is_local_url(&parsed_url);
+            let client = if local {
+                local_client
+            } else {
+                let _ = client.get(parsed_url.clone()).send().await;
+                client
+            };
 
             // 第二次请求开始计时，并将其作为结果返回
             let start = Instant::now();
@@ -84,6 +117,8 @@ pub async fn test_endpoints(
                     let status = err.status().map(|s| s.as_u16());
                     let error_message = if err.is_timeout() {
                         "请求超时".to_string()
+                    } else if err.is_connect() && local {
+                        "本地服务未运行".to_string()
                     } else if err.is_connect() {
                         "连接失败".to_string()
                     } else {
@@ -0,0 +1,210 @@
+//! Claude Code 状态栏联动：在 settings.json 中安装 `statusLine`，指向 cc-switch 生成的脚本，
+//! 脚本输出状态文件中的当前供应商名称与最近一次查询到的剩余额度。
+//! 切换供应商、查询用量时由后端重写状态文件；写入 live 配置时始终带上（或去掉）该 statusLine，
+//! 因此不依赖合并策略也能在切换后保持正确。
+
+use serde_json::{json, Map, Value};
+use std::collections::HashMap;
+use std::path::PathBuf;
+use std::sync::{Mutex, OnceLock};
+
+use crate::app_config::{AppType, MultiAppConfig};
+use crate::config::{get_app_config_dir, get_claude_settings_path, write_text_file};
+use crate::i18n::t_args;
+use crate::provider::UsageData;
+use crate::store::AppState;
+
+/// 状态文件名
+const TEXT_FILE: &str = "statusline.txt";
+
+/// 脚本文件名
+#[cfg(windows)]
+const SCRIPT_FILE: &str = "statusline.cmd";
+#[cfg(not(windows))]
+const SCRIPT_FILE: &str = "statusline.sh";
+
+/// 最近一次查询到的 Claude 供应商用量（按供应商 id 记录）
+static LAST_USAGE: OnceLock<Mutex<HashMap<String, Vec<UsageData>>>> = OnceLock::new();
+
+/// 是否启用状态栏联动
+pub fn is_enabled() -> bool {
+    crate::settings::get_settings().claude_statusline
+}
+
+fn text_path() -> PathBuf {
+    get_app_config_dir().join(TEXT_FILE)
+}
+
+fn script_path() -> PathBuf {
+    get_app_config_dir().join(SCRIPT_FILE)
+}
+
+/// settings.json 中 statusLine 使用的命令
+fn command() -> String {
+    let path = script_path().to_string_lossy().to_string();
+    if cfg!(windows) {
+        format!("\"{}\"", path)
+    } else {
+        format!("sh '{}'", path.replace('\'', r"'\''"))
+    }
+}
+
+fn render_script() -> String {
+    let path = text_path().to_string_lossy().to_string();
+    if cfg!(windows) {
+        format!(
+            "@echo off\r\nrem 由 cc-switch 生成，请勿手动修改\r\ntype \"{}\" 2>nul\r\n",
+            path
+        )
+    } else {
+        format!(
+            "#!/bin/sh\n# 由 cc-switch 生成，请勿手动修改\ncat '{}' 2>/dev/null\n",
+            path.replace('\'', r"'\''")
+        )
+    }
+}
+
+fn status_line() -> Value {
+    json!({ "type": "command", "command": command(), "padding": 0 })
+}
+
+/// 是否为 cc-switch 安装的 statusLine
+fn is_managed(value: &Value) -> bool {
+    value.get("command").and_then(|v| v.as_str()) == Some(command().as_str())
+}
+
+/// 写入 live 配置前调用：启用时写入 statusLine，关闭时去掉 cc-switch 安装的 statusLine
+pub fn apply(settings: &mut Value) {
+    let Some(obj) = settings.as_object_mut() else {
+        return;
+    };
+    if is_enabled() {
+        obj.insert("statusLine".to_string(), status_line());
+    } else if obj.get("statusLine").is_some_and(is_managed) {
+        obj.remove("statusLine");
+    }
+}
+
+/// 从 live 回填到供应商前去掉 cc-switch 安装的 statusLine
+pub fn strip(map: &mut Map<String, Value>) {
+    if map.get("statusLine").is_some_and(is_managed) {
+        map.remove("statusLine");
+    }
+}
+
+/// 额度摘要：取第一个带剩余额度的套餐
+fn quota_text(usage: &[UsageData]) -> Option<String> {
+    let plan = usage.iter().find(|plan| plan.remaining.is_some())?;
+    let remaining = format!(
+        "{}{}",
+        plan.remaining.unwrap_or_default(),
+        plan.unit.as_deref().unwrap_or("")
+    );
+    let text = match &plan.plan_name {
+        Some(name) => t_args(
+            "statusline.planRemaining",
+            &[("plan", name), ("remaining", &remaining)],
+        ),
+        None => t_args("statusline.remaining", &[("remaining", &remaining)]),
+    };
+    Some(text)
+}
+
+fn render_text(config: &MultiAppConfig) -> String {
+    let Some(manager) = config.get_manager(&AppType::Claude) else {
+        return String::new();
+    };
+    let Some(provider) = manager.providers.get(&manager.current) else {
+        return String::new();
+    };
+    let mut text = t_args("statusline.provider", &[("name", &provider.name)]);
+    let quota = LAST_USAGE
+        .get()
+        .and_then(|cache| cache.lock().ok())
+        .and_then(|cache| cache.get(&provider.id).and_then(|usage| quota_text(usage)));
+    if let Some(quota) = quota {
+        text.push_str(" · ");
+        text.push_str(&quota);
+    }
+    text
+}
+
+/// 按当前 Claude 供应商重写状态文件（未启用时不写）
+pub fn refresh(config: &MultiAppConfig) {
+    if !is_enabled() {
+        return;
+    }
+    if let Err(e) = write_text_file(&text_path(), &render_text(config)) {
+        tracing::warn!("更新状态栏文件失败: {}", e);
+    }
+}
+
+/// 记录一次 Claude 供应商的用量查询结果并刷新状态文件
+pub fn record_usage(config: &MultiAppConfig, provider_id: &str, usage: &[UsageData]) {
+    if let Ok(mut cache) = LAST_USAGE.get_or_init(|| Mutex::new(HashMap::new())).lock() {
+        cache.insert(provider_id.to_string(), usage.to_vec());
+    }
+    refresh(config);
+}
+
+fn write_script() -> Result<(), String> {
+    let path = script_path();
+    write_text_file(&path, &render_script())?;
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::PermissionsExt;
+        if let Err(e) = std::fs::set_permissions(&path, std::fs::Permissions::from_mode(0o755)) {
+            tracing::warn!("设置 {} 权限失败: {}", path.display(), e);
+        }
+    }
+    Ok(())
+}
+
+fn remove_files() {
+    for path in [script_path(), text_path()] {
+        if path.exists() {
+            if let Err(e) = std::fs::remove_file(&path) {
+                tracing::warn!("删除 {} 失败: {}", path.display(), e);
+            }
+        }
+    }
+}
+
+/// 在 Claude settings.json 中安装或移除 statusLine（不改动其余内容）
+fn install(enabled: bool) -> Result<(), String> {
+    let path = get_claude_settings_path();
+    let mut settings: Value = if path.exists() {
+        crate::config::read_json_file(&path)?
+    } else if enabled {
+        json!({})
+    } else {
+        return Ok(());
+    };
+    let before = settings.clone();
+    apply(&mut settings);
+    if settings != before {
+        crate::config::write_claude_settings_atomic(&settings)?;
+    }
+    Ok(())
+}
+
+/// 按设置同步状态栏：启用时生成脚本与状态文件并安装 statusLine，关闭时移除（启动与设置变更时调用）
+pub fn sync(app_state: &AppState) {
+    let enabled = is_enabled();
+    if enabled {
+        if let Err(e) = write_script() {
+            tracing::warn!("生成状态栏脚本失败: {}", e);
+            return;
+        }
+        match app_state.config.lock() {
+            Ok(config) => refresh(&config),
+            Err(e) => tracing::warn!("读取配置失败: {}", e),
+        }
+    }
+    if let Err(e) = install(enabled) {
+        tracing::warn!("更新 Claude statusLine 失败: {}", e);
+    }
+    if !enabled {
+        remove_files();
+    }
+}
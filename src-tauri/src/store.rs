@@ -1,32 +1,394 @@
 use crate::app_config::MultiAppConfig;
-use std::sync::{Arc, Mutex};
+use crate::config::{get_app_config_dir, get_app_config_path, write_json_file};
+use parking_lot::{Mutex, MutexGuard};
+use serde::Serialize;
+use serde_json::{json, Value};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+use std::time::Duration;
+use tauri::Emitter;
+
+/// 当前 config.json 结构版本；结构变更时递增，并在 MIGRATIONS 末尾追加迁移步骤
+pub const CONFIG_VERSION: u32 = 3;
+
+/// 单步迁移：将配置从版本 N 升级到 N+1
+type MigrationStep = fn(&mut Value) -> Result<(), String>;
+
+/// 第 i 项负责 v(i+1) → v(i+2)
+const MIGRATIONS: [MigrationStep; (CONFIG_VERSION - 1) as usize] =
+    [migrate_v1_to_v2, migrate_v2_to_v3];
+
+/// v1：单应用 ProviderManager（顶层即 providers/current）→ v2：按应用分组
+fn migrate_v1_to_v2(value: &mut Value) -> Result<(), String> {
+    let claude = value.take();
+    *value = json!({
+        "version": 2,
+        "claude": claude,
+        "codex": { "providers": {}, "current": "" },
+    });
+    Ok(())
+}
+
+/// v2 → v3：版本字段更名为显式的 config_version
+fn migrate_v2_to_v3(value: &mut Value) -> Result<(), String> {
+    let obj = value
+        .as_object_mut()
+        .ok_or("配置文件格式错误：根节点必须是对象")?;
+    obj.remove("version");
+    obj.insert("config_version".to_string(), json!(3));
+    Ok(())
+}
+
+/// 识别配置版本：优先 config_version，其次旧的 version 字段；均缺失且顶层为 providers 时视为 v1
+fn detect_version(value: &Value) -> u32 {
+    let read = |key: &str| value.get(key).and_then(|v| v.as_u64()).map(|v| v as u32);
+    read("config_version")
+        .or_else(|| read("version"))
+        .unwrap_or_else(|| {
+            if value.get("providers").is_some() {
+                1
+            } else {
+                CONFIG_VERSION
+            }
+        })
+}
+
+/// 配置版本相关错误
+#[derive(Debug)]
+pub enum ConfigVersionError {
+    /// 由更新版本的 cc-switch 写入，当前版本无法安全读取
+    TooNew(u32),
+    Invalid(String),
+}
+
+impl std::fmt::Display for ConfigVersionError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            ConfigVersionError::TooNew(version) => write!(
+                f,
+                "配置文件版本 (v{}) 高于当前应用支持的版本 (v{})，请升级 cc-switch",
+                version, CONFIG_VERSION
+            ),
+            ConfigVersionError::Invalid(message) => write!(f, "{}", message),
+        }
+    }
+}
+
+/// 将任意版本的配置 JSON 升级到当前版本，返回配置及原始版本
+pub fn migrate_config_value(mut value: Value) -> Result<(MultiAppConfig, u32), ConfigVersionError> {
+    let from = detect_version(&value);
+    if from > CONFIG_VERSION {
+        return Err(ConfigVersionError::TooNew(from));
+    }
+    for (index, step) in MIGRATIONS.iter().enumerate().skip(from.max(1) as usize - 1) {
+        step(&mut value).map_err(|e| {
+            ConfigVersionError::Invalid(format!("配置从 v{} 升级失败: {}", index + 1, e))
+        })?;
+    }
+    let config = serde_json::from_value(value)
+        .map_err(|e| ConfigVersionError::Invalid(format!("解析配置文件失败: {}", e)))?;
+    Ok((config, from))
+}
+
+/// 将迁移前的配置备份到配置目录：config.v{版本}.{标签}.{时间戳}.json
+fn backup_config_file(version: u32, label: &str, value: &Value) -> Option<std::path::PathBuf> {
+    let ts = chrono::Utc::now().timestamp();
+    let backup_path =
+        get_app_config_dir().join(format!("config.v{}.{}.{}.json", version, label, ts));
+    match write_json_file(&backup_path, value) {
+        Ok(()) => {
+            tracing::info!("已备份配置文件: {}", backup_path.display());
+            Some(backup_path)
+        }
+        Err(e) => {
+            tracing::warn!("备份配置文件失败: {}", e);
+            None
+        }
+    }
+}
+
+/// 读取尚未导入数据库的旧版 config.json
+fn read_legacy_config() -> Option<Value> {
+    let config_path = get_app_config_path();
+    if !config_path.exists() {
+        tracing::info!("配置文件不存在，创建新的多应用配置");
+        return None;
+    }
+
+    match crate::config::read_json_file::<Value>(&config_path) {
+        Ok(value) => Some(value),
+        Err(e) => {
+            tracing::warn!("加载配置失败: {}, 使用默认配置", e);
+            None
+        }
+    }
+}
+
+/// 旧版 config.json 导入数据库后改名保留为 config.json.imported，避免重复导入
+fn retire_legacy_config() {
+    let config_path = get_app_config_path();
+    let imported_path = get_app_config_dir().join("config.json.imported");
+    match std::fs::rename(&config_path, &imported_path) {
+        Ok(()) => tracing::info!(
+            "已将 config.json 导入数据库，原文件保留为 {}",
+            imported_path.display()
+        ),
+        Err(e) => tracing::warn!("重命名已导入的 config.json 失败: {}", e),
+    }
+}
+
+/// 从数据库加载配置（首次启动时导入旧版 config.json）并按需执行版本迁移；
+/// 返回配置与需要提示用户的警告
+fn load_config() -> (MultiAppConfig, Option<String>) {
+    let (value, legacy) = match crate::database::load_config_value() {
+        Ok(Some(value)) => (value, false),
+        Ok(None) => match read_legacy_config() {
+            Some(value) => (value, true),
+            None => return (MultiAppConfig::default(), None),
+        },
+        Err(e) => {
+            tracing::error!("读取配置数据库失败: {}, 使用默认配置", e);
+            return (MultiAppConfig::default(), None);
+        }
+    };
+
+    match migrate_config_value(value.clone()) {
+        Ok((config, from)) => {
+            if from < CONFIG_VERSION {
+                tracing::info!("检测到 v{} 配置，已迁移到 v{}", from, CONFIG_VERSION);
+                // 迁移前备份旧版配置
+                if let Some(backup) = backup_config_file(from, "backup", &value) {
+                    if from == 1 {
+                        crate::migration::record_legacy_config_backup(&backup);
+                    }
+                }
+            }
+            if legacy || from < CONFIG_VERSION {
+                match config.save() {
+                    Ok(()) if legacy => retire_legacy_config(),
+                    Ok(()) => {}
+                    Err(e) => tracing::warn!("保存迁移后的配置失败: {}", e),
+                }
+            }
+            (config, None)
+        }
+        Err(ConfigVersionError::TooNew(version)) => {
+            // 拒绝降级读取：先完整备份，避免旧版本覆盖新版本写入的数据
+            let backup = backup_config_file(version, "newer", &value);
+            let message = match backup {
+                Some(path) => format!(
+                    "{}。原配置已备份到 {}，本次将使用空配置启动。",
+                    ConfigVersionError::TooNew(version),
+                    path.display()
+                ),
+                None => ConfigVersionError::TooNew(version).to_string(),
+            };
+            tracing::error!("{}", message);
+            (MultiAppConfig::default(), Some(message))
+        }
+        Err(e) => {
+            tracing::warn!("加载配置失败: {}, 使用默认配置", e);
+            (MultiAppConfig::default(), None)
+        }
+    }
+}
+
+/// 等待配置锁的最长时间：超时视为锁争用并返回错误，而不是无限阻塞
+const LOCK_TIMEOUT: Duration = Duration::from_secs(5);
+
+/// 等待配置锁超时
+#[derive(Debug)]
+pub struct LockTimeout;
+
+impl std::fmt::Display for LockTimeout {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", crate::i18n::t("errors.configBusy"))
+    }
+}
+
+impl std::error::Error for LockTimeout {}
+
+/// 配置锁：基于 parking_lot，持锁线程 panic 不会让锁永久中毒；获取时带超时。
+/// 与 std Mutex 一样通过 `lock()` 返回 Result，调用方的错误处理保持不变
+pub struct ConfigLock(Mutex<MultiAppConfig>);
+
+impl ConfigLock {
+    pub fn new(config: MultiAppConfig) -> Self {
+        Self(Mutex::new(config))
+    }
+
+    pub fn lock(&self) -> Result<MutexGuard<'_, MultiAppConfig>, LockTimeout> {
+        self.0.try_lock_for(LOCK_TIMEOUT).ok_or(LockTimeout)
+    }
+}
+
+/// 保存去抖时间：窗口内的多次保存合并为一次写盘
+const SAVE_DEBOUNCE: Duration = Duration::from_millis(500);
+
+/// 延迟写盘失败的事件名
+pub const SAVE_FAILED_EVENT: &str = "config-save-failed";
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+struct SaveFailedPayload {
+    message: String,
+}
+
+/// `AppState::reload_if_changed` 的结果
+#[derive(Debug, Clone, Copy)]
+pub enum ReloadOutcome {
+    /// 数据库中的配置没有变化
+    Unchanged,
+    /// 已重新加载其他进程写入的配置（附新的修订号）
+    Reloaded(u64),
+    /// 其他进程写入了新配置，但本地有尚未写盘的修改（附数据库中的修订号）
+    Conflict(u64),
+}
+
+/// 从数据库读取配置并升级到当前版本
+fn load_from_database() -> Result<MultiAppConfig, String> {
+    let value = crate::database::load_config_value()?.ok_or("数据库中没有配置")?;
+    let (config, _) = migrate_config_value(value).map_err(|e| e.to_string())?;
+    Ok(config)
+}
+
+/// 通知前端延迟写盘失败
+fn notify_save_failed(message: String) {
+    let Some(app) = crate::app_store::get_app_handle() else {
+        return;
+    };
+    if let Err(e) = app.emit(SAVE_FAILED_EVENT, SaveFailedPayload { message }) {
+        tracing::warn!("发送配置保存失败事件失败: {}", e);
+    }
+}
 
 /// 全局应用状态
 #[derive(Clone)]
 pub struct AppState {
-    pub config: Arc<Mutex<MultiAppConfig>>,
+    pub config: Arc<ConfigLock>,
+    /// 内存配置是否有尚未写盘的修改
+    dirty: Arc<AtomicBool>,
+    /// 是否已有等待去抖的后台写盘任务
+    pending: Arc<AtomicBool>,
 }
 
 impl AppState {
-    /// 创建新的应用状态
-    pub fn new() -> Self {
-        let config = MultiAppConfig::load().unwrap_or_else(|e| {
-            log::warn!("加载配置失败: {}, 使用默认配置", e);
-            MultiAppConfig::default()
-        });
+    /// 加载配置并创建应用状态，同时返回需要提示用户的警告（如配置版本过新）
+    pub fn load() -> (Self, Option<String>) {
+        let (config, warning) = load_config();
+        crate::proxy::refresh_provider_snapshot(&config);
+
+        (
+            Self {
+                config: Arc::new(ConfigLock::new(config)),
+                dirty: Arc::new(AtomicBool::new(false)),
+                pending: Arc::new(AtomicBool::new(false)),
+            },
+            warning,
+        )
+    }
 
-        Self {
-            config: Arc::new(Mutex::new(config)),
+    /// 立即写盘并返回写入结果：用户发起的操作使用，写入失败（磁盘已满、权限、并发冲突等）时报告给调用方
+    pub fn save_now(&self) -> Result<(), String> {
+        let config = self
+            .config
+            .lock()
+            .map_err(|e| format!("获取锁失败: {}", e))?;
+        self.dirty.store(false, Ordering::SeqCst);
+        if let Err(e) = config.save() {
+            // 保留未写盘标记，等待后续保存或退出前写入
+            self.dirty.store(true, Ordering::SeqCst);
+            return Err(e);
         }
+        Ok(())
     }
 
-    /// 保存配置到文件
+    /// 标记配置已修改并安排去抖写盘：代理快照立即刷新，文件在去抖窗口结束后统一写入一次。
+    /// 仅用于后台任务等无法把错误返回给用户的路径，写入失败时发送 `config-save-failed` 事件
     pub fn save(&self) -> Result<(), String> {
-        let config = self
+        {
+            let config = self
+                .config
+                .lock()
+                .map_err(|e| format!("获取锁失败: {}", e))?;
+            crate::proxy::refresh_provider_snapshot(&config);
+        }
+        self.dirty.store(true, Ordering::SeqCst);
+
+        // 已有等待中的写盘任务时直接合并，不再额外启动
+        if self.pending.swap(true, Ordering::SeqCst) {
+            return Ok(());
+        }
+        let state = self.clone();
+        std::thread::spawn(move || {
+            std::thread::sleep(SAVE_DEBOUNCE);
+            state.pending.store(false, Ordering::SeqCst);
+            if let Err(e) = state.flush() {
+                tracing::error!("后台保存配置失败: {}", e);
+                // 外部修改导致的失败由 config_sync 以冲突提示处理，不再重复通知
+                let conflict = crate::database::current_revision()
+                    .is_ok_and(|revision| revision != crate::database::known_revision());
+                if !conflict {
+                    notify_save_failed(e);
+                }
+            }
+        });
+        Ok(())
+    }
+
+    /// 其他进程写入了更新的配置时，从数据库重新加载并替换内存中的配置；
+    /// 本地有尚未写盘的修改时不覆盖，返回冲突交由用户选择（见 `resolve_conflict`）
+    pub fn reload_if_changed(&self) -> Result<ReloadOutcome, String> {
+        let mut config = self
+            .config
+            .lock()
+            .map_err(|e| format!("获取锁失败: {}", e))?;
+        // 持锁后再核对修订号：本进程的保存都在持锁期间完成，不会被误判为外部修改
+        let revision = crate::database::current_revision()?;
+        if revision == crate::database::known_revision() {
+            return Ok(ReloadOutcome::Unchanged);
+        }
+        if self.dirty.load(Ordering::SeqCst) {
+            return Ok(ReloadOutcome::Conflict(revision));
+        }
+        let reloaded = load_from_database()?;
+        crate::proxy::refresh_provider_snapshot(&reloaded);
+        *config = reloaded;
+        Ok(ReloadOutcome::Reloaded(crate::database::known_revision()))
+    }
+
+    /// 解决外部修改冲突：`keep_local` 为真时用内存中的配置覆盖其他进程写入的配置，
+    /// 否则丢弃本地尚未写盘的修改并重新加载
+    pub fn resolve_conflict(&self, keep_local: bool) -> Result<(), String> {
+        let mut config = self
             .config
             .lock()
             .map_err(|e| format!("获取锁失败: {}", e))?;
+        if keep_local {
+            crate::database::overwrite_config(&config)?;
+            crate::proxy::refresh_provider_snapshot(&config);
+        } else {
+            let reloaded = load_from_database()?;
+            crate::proxy::refresh_provider_snapshot(&reloaded);
+            *config = reloaded;
+        }
+        self.dirty.store(false, Ordering::SeqCst);
+        Ok(())
+    }
 
-        config.save()
+    /// 立即把未写盘的修改保存到文件（退出前、读取磁盘配置前调用）；没有修改时不写盘
+    pub fn flush(&self) -> Result<(), String> {
+        if !self.dirty.swap(false, Ordering::SeqCst) {
+            return Ok(());
+        }
+        let result = self
+            .config
+            .lock()
+            .map_err(|e| format!("获取锁失败: {}", e))
+            .and_then(|config| config.save());
+        if result.is_err() {
+            self.dirty.store(true, Ordering::SeqCst);
+        }
+        result
     }
 }
@@ -0,0 +1,1213 @@
+//! 代理的协议转换：供应商的上游接口格式与客户端不一致时，在代理中转换请求与响应（含工具调用
+//! 与流式 SSE），由供应商 meta.api_format 开启。
+//! - Claude 供应商只提供 OpenAI 兼容接口：`/v1/messages` 转换为 Chat Completions；
+//! - Codex 供应商为 Anthropic 中转：Responses 与 Chat Completions 转换为 Messages。
+
+use serde_json::{json, Map, Value};
+
+use crate::app_config::AppType;
+use crate::provider::{ApiFormat, Provider};
+
+/// Anthropic Messages 接口路径
+const MESSAGES_PATH: &str = "/v1/messages";
+/// Codex 请求的接口路径（不含 `/v1` 前缀）
+const CHAT_COMPLETIONS_PATH: &str = "/chat/completions";
+const RESPONSES_PATH: &str = "/responses";
+/// 发往 Anthropic 接口时使用的 API 版本
+const ANTHROPIC_VERSION: &str = "2023-06-01";
+/// 客户端未指定输出上限时的 max_tokens（Anthropic 接口要求必填）
+const DEFAULT_MAX_TOKENS: u64 = 8192;
+
+/// 转换方向
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Translation {
+    /// Anthropic Messages 请求转发给 OpenAI Chat Completions 接口
+    AnthropicToOpenai,
+    /// Chat Completions 请求转发给 Anthropic Messages 接口
+    ChatToAnthropic,
+    /// Responses 请求转发给 Anthropic Messages 接口
+    ResponsesToAnthropic,
+}
+
+/// 本次请求发往该供应商时需要的转换（不需要时为 None）
+pub fn translation_for(app_type: &AppType, provider: &Provider, path: &str) -> Option<Translation> {
+    let format = provider.meta.as_ref()?.api_format?;
+    let path = path.split('?').next().unwrap_or(path);
+    match (app_type, format) {
+        (AppType::Claude, ApiFormat::OpenaiChat) if path == MESSAGES_PATH => {
+            Some(Translation::AnthropicToOpenai)
+        }
+        (AppType::Codex, ApiFormat::Anthropic) => match path.strip_prefix("/v1").unwrap_or(path) {
+            CHAT_COMPLETIONS_PATH => Some(Translation::ChatToAnthropic),
+            RESPONSES_PATH => Some(Translation::ResponsesToAnthropic),
+            _ => None,
+        },
+        _ => None,
+    }
+}
+
+/// 转换后的上游地址：base_url 已以 `/v1` 结尾时不再重复
+pub fn target_url(translation: Translation, base_url: &str) -> String {
+    let base = base_url.trim_end_matches('/');
+    let path = match translation {
+        Translation::AnthropicToOpenai => CHAT_COMPLETIONS_PATH,
+        Translation::ChatToAnthropic | Translation::ResponsesToAnthropic => "/messages",
+    };
+    if base.ends_with("/v1") {
+        format!("{}{}", base, path)
+    } else {
+        format!("{}/v1{}", base, path)
+    }
+}
+
+/// 转换请求时不转发的请求头：请求体长度会变化，且需要未压缩的响应才能转换
+pub fn skip_header(name: &str) -> bool {
+    matches!(
+        name.to_ascii_lowercase().as_str(),
+        "content-length"
+            | "accept-encoding"
+            | "anthropic-version"
+            | "anthropic-beta"
+            | "x-api-key"
+            | "openai-beta"
+    )
+}
+
+/// 转换后需要补充的请求头：Anthropic 接口以 x-api-key 认证，并要求 anthropic-version
+pub fn extra_headers(translation: Translation, api_key: &str) -> Vec<(&'static str, String)> {
+    match translation {
+        Translation::AnthropicToOpenai => Vec::new(),
+        Translation::ChatToAnthropic | Translation::ResponsesToAnthropic => vec![
+            ("x-api-key", api_key.to_string()),
+            ("anthropic-version", ANTHROPIC_VERSION.to_string()),
+        ],
+    }
+}
+
+/// 请求是否为流式
+pub fn is_stream(body: &[u8]) -> bool {
+    serde_json::from_slice::<Value>(body)
+        .ok()
+        .and_then(|v| v.get("stream")?.as_bool())
+        .unwrap_or(false)
+}
+
+/// 转换请求体
+pub fn request(
+    translation: Translation,
+    provider: &Provider,
+    body: &[u8],
+) -> Result<Vec<u8>, String> {
+    let value: Value =
+        serde_json::from_slice(body).map_err(|e| format!("解析请求体失败: {}", e))?;
+    let converted = match translation {
+        Translation::AnthropicToOpenai => anthropic_to_openai_request(provider, &value),
+        Translation::ChatToAnthropic => chat_to_anthropic_request(&value),
+        Translation::ResponsesToAnthropic => responses_to_anthropic_request(&value),
+    };
+    serde_json::to_vec(&converted).map_err(|e| format!("序列化请求体失败: {}", e))
+}
+
+/// 转换成功响应的响应体；无法解析时原样返回
+pub fn response(translation: Translation, body: &[u8], stream: bool) -> Vec<u8> {
+    match translation {
+        Translation::AnthropicToOpenai if stream => openai_stream_to_anthropic(body).into_bytes(),
+        Translation::AnthropicToOpenai => match serde_json::from_slice::<Value>(body) {
+            Ok(value) => openai_to_anthropic_response(&value)
+                .to_string()
+                .into_bytes(),
+            Err(_) => body.to_vec(),
+        },
+        Translation::ChatToAnthropic | Translation::ResponsesToAnthropic => {
+            let message = if stream {
+                anthropic_stream_to_message(body)
+            } else {
+                match serde_json::from_slice::<Value>(body) {
+                    Ok(value) => value,
+                    Err(_) => return body.to_vec(),
+                }
+            };
+            let converted = match (translation, stream) {
+                (Translation::ChatToAnthropic, true) => chat_stream(&message),
+                (Translation::ChatToAnthropic, false) => {
+                    anthropic_to_chat_response(&message).to_string()
+                }
+                (_, true) => responses_stream(&message),
+                (_, false) => anthropic_to_responses_response(&message).to_string(),
+            };
+            converted.into_bytes()
+        }
+    }
+}
+
+/// 转换后响应的 Content-Type
+pub fn content_type(stream: bool) -> &'static str {
+    if stream {
+        "text/event-stream"
+    } else {
+        "application/json"
+    }
+}
+
+/// 转换上游错误响应，使客户端能按自身格式展示；无法解析时原样返回
+pub fn error(translation: Translation, body: &[u8]) -> Vec<u8> {
+    let Ok(value) = serde_json::from_slice::<Value>(body) else {
+        return body.to_vec();
+    };
+    let error = value.get("error").unwrap_or(&value);
+    let message = error
+        .get("message")
+        .and_then(|v| v.as_str())
+        .map(str::to_string)
+        .unwrap_or_else(|| error.to_string());
+    let kind = error
+        .get("type")
+        .and_then(|v| v.as_str())
+        .unwrap_or("api_error");
+    let converted = match translation {
+        Translation::AnthropicToOpenai => {
+            json!({ "type": "error", "error": { "type": kind, "message": message } })
+        }
+        Translation::ChatToAnthropic | Translation::ResponsesToAnthropic => {
+            json!({ "error": { "message": message, "type": kind, "code": null } })
+        }
+    };
+    converted.to_string().into_bytes()
+}
+
+/// 按供应商 env 中的模型设置映射 Claude 模型名（未设置时原样使用）
+fn map_model(provider: &Provider, model: &str) -> String {
+    let env = provider.settings_config.get("env");
+    let pick = |key: &str| {
+        env.and_then(|env| env.get(key))
+            .and_then(|v| v.as_str())
+            .map(str::trim)
+            .filter(|s| !s.is_empty())
+    };
+    let lower = model.to_lowercase();
+    let specific = if lower.contains("haiku") {
+        pick("ANTHROPIC_DEFAULT_HAIKU_MODEL").or_else(|| pick("ANTHROPIC_SMALL_FAST_MODEL"))
+    } else if lower.contains("opus") {
+        pick("ANTHROPIC_DEFAULT_OPUS_MODEL")
+    } else if lower.contains("sonnet") {
+        pick("ANTHROPIC_DEFAULT_SONNET_MODEL")
+    } else {
+        None
+    };
+    specific
+        .or_else(|| pick("ANTHROPIC_MODEL"))
+        .unwrap_or(model)
+        .to_string()
+}
+
+/// 文本块数组拼接为字符串
+fn joined_text(blocks: &[Value]) -> String {
+    blocks
+        .iter()
+        .filter_map(|block| block.get("text")?.as_str())
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+/// tool_result 的内容：字符串或文本块数组
+fn tool_result_text(content: Option<&Value>) -> String {
+    match content {
+        Some(Value::String(text)) => text.clone(),
+        Some(Value::Array(blocks)) => joined_text(blocks),
+        Some(other) => other.to_string(),
+        None => String::new(),
+    }
+}
+
+/// Anthropic 图片块转换为 image_url 内容
+fn image_part(block: &Value) -> Option<Value> {
+    let source = block.get("source")?;
+    let url = match source.get("type")?.as_str()? {
+        "base64" => format!(
+            "data:{};base64,{}",
+            source.get("media_type")?.as_str()?,
+            source.get("data")?.as_str()?
+        ),
+        "url" => source.get("url")?.as_str()?.to_string(),
+        _ => return None,
+    };
+    Some(json!({ "type": "image_url", "image_url": { "url": url } }))
+}
+
+/// 转换一条带内容块的消息：tool_result 拆为 tool 消息，tool_use 转为 tool_calls
+fn push_block_message(role: &str, blocks: &[Value], messages: &mut Vec<Value>) {
+    let mut parts = Vec::new();
+    let mut tool_calls = Vec::new();
+    for block in blocks {
+        match block.get("type").and_then(|v| v.as_str()) {
+            Some("text") => {
+                if let Some(text) = block.get("text").and_then(|v| v.as_str()) {
+                    parts.push(json!({ "type": "text", "text": text }));
+                }
+            }
+            Some("image") => parts.extend(image_part(block)),
+            Some("tool_use") => tool_calls.push(json!({
+                "id": block.get("id").cloned().unwrap_or_default(),
+                "type": "function",
+                "function": {
+                    "name": block.get("name").cloned().unwrap_or_default(),
+                    "arguments": block.get("input").cloned().unwrap_or_else(|| json!({})).to_string(),
+                }
+            })),
+            // tool 消息须紧跟在对应的 tool_calls 之后，因此先于本条消息的其余内容
+            Some("tool_result") => messages.push(json!({
+                "role": "tool",
+                "tool_call_id": block.get("tool_use_id").cloned().unwrap_or_default(),
+                "content": tool_result_text(block.get("content")),
+            })),
+            // thinking 等其余块在 Chat Completions 中没有对应项
+            _ => {}
+        }
+    }
+
+    let all_text = parts
+        .iter()
+        .all(|part| part.get("type").and_then(|v| v.as_str()) == Some("text"));
+    let content = if parts.is_empty() {
+        Value::Null
+    } else if all_text {
+        Value::String(joined_text(&parts))
+    } else {
+        Value::Array(parts)
+    };
+    if role == "assistant" {
+        if content.is_null() && tool_calls.is_empty() {
+            return;
+        }
+        let mut message = json!({ "role": "assistant", "content": content });
+        if !tool_calls.is_empty() {
+            message["tool_calls"] = Value::Array(tool_calls);
+        }
+        messages.push(message);
+    } else if !content.is_null() {
+        messages.push(json!({ "role": role, "content": content }));
+    }
+}
+
+fn anthropic_to_openai_request(provider: &Provider, req: &Value) -> Value {
+    let mut messages = Vec::new();
+    match req.get("system") {
+        Some(Value::String(text)) if !text.is_empty() => {
+            messages.push(json!({ "role": "system", "content": text }));
+        }
+        Some(Value::Array(blocks)) if !blocks.is_empty() => {
+            messages.push(json!({ "role": "system", "content": joined_text(blocks) }));
+        }
+        _ => {}
+    }
+    for message in req
+        .get("messages")
+        .and_then(|v| v.as_array())
+        .into_iter()
+        .flatten()
+    {
+        let role = message
+            .get("role")
+            .and_then(|v| v.as_str())
+            .unwrap_or("user");
+        match message.get("content") {
+            Some(Value::String(text)) => messages.push(json!({ "role": role, "content": text })),
+            Some(Value::Array(blocks)) => push_block_message(role, blocks, &mut messages),
+            _ => {}
+        }
+    }
+
+    let model = req
+        .get("model")
+        .and_then(|v| v.as_str())
+        .unwrap_or_default();
+    let mut out = Map::new();
+    out.insert("model".into(), json!(map_model(provider, model)));
+    out.insert("messages".into(), Value::Array(messages));
+    for key in ["max_tokens", "temperature", "top_p"] {
+        if let Some(value) = req.get(key) {
+            out.insert(key.into(), value.clone());
+        }
+    }
+    if let Some(stop) = req.get("stop_sequences") {
+        out.insert("stop".into(), stop.clone());
+    }
+    if req.get("stream").and_then(|v| v.as_bool()) == Some(true) {
+        out.insert("stream".into(), json!(true));
+        out.insert("stream_options".into(), json!({ "include_usage": true }));
+    }
+    if let Some(tools) = req.get("tools").and_then(|v| v.as_array()) {
+        let tools: Vec<Value> = tools
+            .iter()
+            .map(|tool| {
+                json!({
+                    "type": "function",
+                    "function": {
+                        "name": tool.get("name").cloned().unwrap_or_default(),
+                        "description": tool.get("description").cloned().unwrap_or_default(),
+                        "parameters": tool.get("input_schema").cloned().unwrap_or_else(|| json!({ "type": "object" })),
+                    }
+                })
+            })
+            .collect();
+        if !tools.is_empty() {
+            out.insert("tools".into(), Value::Array(tools));
+        }
+    }
+    if let Some(choice) = req.get("tool_choice") {
+        let mapped = match choice.get("type").and_then(|v| v.as_str()) {
+            Some("auto") => Some(json!("auto")),
+            Some("any") => Some(json!("required")),
+            Some("none") => Some(json!("none")),
+            Some("tool") => Some(json!({
+                "type": "function",
+                "function": { "name": choice.get("name").cloned().unwrap_or_default() }
+            })),
+            _ => None,
+        };
+        if let Some(mapped) = mapped {
+            out.insert("tool_choice".into(), mapped);
+        }
+    }
+    Value::Object(out)
+}
+
+/// finish_reason 映射为 stop_reason
+fn stop_reason(finish_reason: Option<&str>) -> &'static str {
+    match finish_reason {
+        Some("length") => "max_tokens",
+        Some("tool_calls") | Some("function_call") => "tool_use",
+        _ => "end_turn",
+    }
+}
+
+/// 生成 Anthropic 风格的消息 ID
+fn message_id(id: Option<&str>) -> String {
+    match id {
+        Some(id) if !id.is_empty() => format!("msg_{}", id.trim_start_matches("chatcmpl-")),
+        _ => format!("msg_{}", uuid::Uuid::new_v4().simple()),
+    }
+}
+
+fn usage_json(usage: Option<&Value>) -> Value {
+    let field = |key: &str| {
+        usage
+            .and_then(|u| u.get(key))
+            .and_then(|v| v.as_u64())
+            .unwrap_or(0)
+    };
+    json!({
+        "input_tokens": field("prompt_tokens"),
+        "output_tokens": field("completion_tokens"),
+    })
+}
+
+fn openai_to_anthropic_response(resp: &Value) -> Value {
+    let choice = resp.pointer("/choices/0");
+    let message = choice.and_then(|c| c.get("message"));
+    let mut content = Vec::new();
+    if let Some(text) = message
+        .and_then(|m| m.get("content"))
+        .and_then(|v| v.as_str())
+        .filter(|text| !text.is_empty())
+    {
+        content.push(json!({ "type": "text", "text": text }));
+    }
+    for call in message
+        .and_then(|m| m.get("tool_calls"))
+        .and_then(|v| v.as_array())
+        .into_iter()
+        .flatten()
+    {
+        let arguments = call
+            .pointer("/function/arguments")
+            .and_then(|v| v.as_str())
+            .unwrap_or("{}");
+        content.push(json!({
+            "type": "tool_use",
+            "id": call.get("id").cloned().unwrap_or_default(),
+            "name": call.pointer("/function/name").cloned().unwrap_or_default(),
+            "input": serde_json::from_str::<Value>(arguments).unwrap_or_else(|_| json!({})),
+        }));
+    }
+    json!({
+        "id": message_id(resp.get("id").and_then(|v| v.as_str())),
+        "type": "message",
+        "role": "assistant",
+        "model": resp.get("model").cloned().unwrap_or_default(),
+        "content": content,
+        "stop_reason": stop_reason(choice.and_then(|c| c.get("finish_reason")).and_then(|v| v.as_str())),
+        "stop_sequence": null,
+        "usage": usage_json(resp.get("usage")),
+    })
+}
+
+/// 追加一条以 type 字段为事件名的 SSE 事件
+fn push_event(out: &mut String, data: &Value) {
+    let kind = data
+        .get("type")
+        .and_then(|v| v.as_str())
+        .unwrap_or_default();
+    out.push_str(&format!("event: {}\ndata: {}\n\n", kind, data));
+}
+
+/// 逐个生成 Anthropic SSE 事件
+#[derive(Default)]
+struct AnthropicStream {
+    out: String,
+    /// 当前打开的内容块：(块序号, 是否为工具调用块)
+    open: Option<(usize, bool)>,
+    next_index: usize,
+    /// OpenAI 工具调用序号 -> 内容块序号
+    tool_blocks: Vec<(u64, usize)>,
+}
+
+impl AnthropicStream {
+    fn event(&mut self, data: Value) {
+        push_event(&mut self.out, &data);
+    }
+
+    fn close_block(&mut self) {
+        if let Some((index, _)) = self.open.take() {
+            self.event(json!({ "type": "content_block_stop", "index": index }));
+        }
+    }
+
+    fn open_block(&mut self, block: Value, is_tool: bool) -> usize {
+        self.close_block();
+        let index = self.next_index;
+        self.next_index += 1;
+        self.event(
+            json!({ "type": "content_block_start", "index": index, "content_block": block }),
+        );
+        self.open = Some((index, is_tool));
+        index
+    }
+
+    fn text(&mut self, text: &str) {
+        let index = match self.open {
+            Some((index, false)) => index,
+            _ => self.open_block(json!({ "type": "text", "text": "" }), false),
+        };
+        self.event(json!({
+            "type": "content_block_delta",
+            "index": index,
+            "delta": { "type": "text_delta", "text": text },
+        }));
+    }
+
+    fn tool_call(&mut self, call: &Value) {
+        let tool_index = call.get("index").and_then(|v| v.as_u64()).unwrap_or(0);
+        let known = self
+            .tool_blocks
+            .iter()
+            .find(|(tool, _)| *tool == tool_index)
+            .map(|(_, block)| *block);
+        let index = match known {
+            Some(index) if self.open == Some((index, true)) => index,
+            // 工具调用块已关闭（中间穿插了其他内容）时无法继续追加，忽略其余参数
+            Some(_) => return,
+            None => {
+                let block = json!({
+                    "type": "tool_use",
+                    "id": call.get("id").cloned().unwrap_or_else(|| json!(format!("toolu_{}", uuid::Uuid::new_v4().simple()))),
+                    "name": call.pointer("/function/name").cloned().unwrap_or_default(),
+                    "input": {},
+                });
+                let index = self.open_block(block, true);
+                self.tool_blocks.push((tool_index, index));
+                index
+            }
+        };
+        if let Some(arguments) = call
+            .pointer("/function/arguments")
+            .and_then(|v| v.as_str())
+            .filter(|s| !s.is_empty())
+        {
+            self.event(json!({
+                "type": "content_block_delta",
+                "index": index,
+                "delta": { "type": "input_json_delta", "partial_json": arguments },
+            }));
+        }
+    }
+}
+
+/// 将 OpenAI 流式响应（已完整读取）转换为 Anthropic SSE 事件序列
+fn openai_stream_to_anthropic(body: &[u8]) -> String {
+    let text = String::from_utf8_lossy(body);
+    let chunks: Vec<Value> = text
+        .lines()
+        .filter_map(|line| line.strip_prefix("data:"))
+        .map(str::trim)
+        .filter(|data| *data != "[DONE]")
+        .filter_map(|data| serde_json::from_str(data).ok())
+        .collect();
+
+    let first = chunks.first();
+    let mut stream = AnthropicStream::default();
+    stream.event(json!({
+        "type": "message_start",
+        "message": {
+            "id": message_id(first.and_then(|c| c.get("id")).and_then(|v| v.as_str())),
+            "type": "message",
+            "role": "assistant",
+            "model": first.and_then(|c| c.get("model")).cloned().unwrap_or_default(),
+            "content": [],
+            "stop_reason": null,
+            "stop_sequence": null,
+            "usage": { "input_tokens": 0, "output_tokens": 0 },
+        }
+    }));
+
+    let mut finish_reason = None;
+    let mut usage = None;
+    for chunk in &chunks {
+        if let Some(value) = chunk.get("usage").filter(|u| !u.is_null()) {
+            usage = Some(value.clone());
+        }
+        let Some(choice) = chunk.pointer("/choices/0") else {
+            continue;
+        };
+        let delta = choice.get("delta");
+        if let Some(text) = delta
+            .and_then(|d| d.get("content"))
+            .and_then(|v| v.as_str())
+            .filter(|s| !s.is_empty())
+        {
+            stream.text(text);
+        }
+        for call in delta
+            .and_then(|d| d.get("tool_calls"))
+            .and_then(|v| v.as_array())
+            .into_iter()
+            .flatten()
+        {
+            stream.tool_call(call);
+        }
+        if let Some(reason) = choice.get("finish_reason").and_then(|v| v.as_str()) {
+            finish_reason = Some(reason.to_string());
+        }
+    }
+
+    stream.close_block();
+    stream.event(json!({
+        "type": "message_delta",
+        "delta": { "stop_reason": stop_reason(finish_reason.as_deref()), "stop_sequence": null },
+        "usage": usage_json(usage.as_ref()),
+    }));
+    stream.event(json!({ "type": "message_stop" }));
+    stream.out
+}
+
+/// 组装 Anthropic 消息：相邻的同角色消息合并（Anthropic 要求 user/assistant 交替）
+#[derive(Default)]
+struct AnthropicMessages {
+    system: Vec<String>,
+    messages: Vec<Value>,
+}
+
+impl AnthropicMessages {
+    fn push(&mut self, role: &str, blocks: Vec<Value>) {
+        if blocks.is_empty() {
+            return;
+        }
+        if let Some(last) = self.messages.last_mut() {
+            if last.get("role").and_then(|v| v.as_str()) == Some(role) {
+                if let Some(content) = last.get_mut("content").and_then(|v| v.as_array_mut()) {
+                    content.extend(blocks);
+                    return;
+                }
+            }
+        }
+        self.messages
+            .push(json!({ "role": role, "content": blocks }));
+    }
+
+    fn push_system(&mut self, text: String) {
+        if !text.is_empty() {
+            self.system.push(text);
+        }
+    }
+
+    /// 生成请求体，采样参数与工具定义取自原请求
+    fn into_request(self, req: &Value, max_tokens_key: &str) -> Value {
+        let mut out = Map::new();
+        out.insert(
+            "model".into(),
+            req.get("model").cloned().unwrap_or_default(),
+        );
+        out.insert("messages".into(), Value::Array(self.messages));
+        if !self.system.is_empty() {
+            out.insert("system".into(), json!(self.system.join("\n\n")));
+        }
+        let max_tokens = req
+            .get(max_tokens_key)
+            .or_else(|| req.get("max_completion_tokens"))
+            .and_then(|v| v.as_u64())
+            .unwrap_or(DEFAULT_MAX_TOKENS);
+        out.insert("max_tokens".into(), json!(max_tokens));
+        for key in ["temperature", "top_p"] {
+            if let Some(value) = req.get(key).filter(|v| !v.is_null()) {
+                out.insert(key.into(), value.clone());
+            }
+        }
+        match req.get("stop") {
+            Some(Value::String(stop)) => {
+                out.insert("stop_sequences".into(), json!([stop]));
+            }
+            Some(Value::Array(stops)) if !stops.is_empty() => {
+                out.insert("stop_sequences".into(), Value::Array(stops.clone()));
+            }
+            _ => {}
+        }
+        if req.get("stream").and_then(|v| v.as_bool()) == Some(true) {
+            out.insert("stream".into(), json!(true));
+        }
+        let tools = anthropic_tools(req.get("tools"));
+        if !tools.is_empty() {
+            out.insert("tools".into(), Value::Array(tools));
+            if let Some(choice) = req.get("tool_choice").and_then(anthropic_tool_choice) {
+                out.insert("tool_choice".into(), choice);
+            }
+        }
+        Value::Object(out)
+    }
+}
+
+/// OpenAI 工具定义转换为 Anthropic 工具：Chat Completions 嵌套在 function 中，
+/// Responses 直接平铺；非函数工具（如内置搜索）没有对应项
+fn anthropic_tools(tools: Option<&Value>) -> Vec<Value> {
+    tools
+        .and_then(|v| v.as_array())
+        .into_iter()
+        .flatten()
+        .filter(|tool| tool.get("type").and_then(|v| v.as_str()) == Some("function"))
+        .map(|tool| {
+            let function = tool.get("function").unwrap_or(tool);
+            json!({
+                "name": function.get("name").cloned().unwrap_or_default(),
+                "description": function.get("description").cloned().unwrap_or_default(),
+                "input_schema": function
+                    .get("parameters")
+                    .filter(|v| !v.is_null())
+                    .cloned()
+                    .unwrap_or_else(|| json!({ "type": "object" })),
+            })
+        })
+        .collect()
+}
+
+fn anthropic_tool_choice(choice: &Value) -> Option<Value> {
+    match choice {
+        Value::String(kind) => match kind.as_str() {
+            "auto" => Some(json!({ "type": "auto" })),
+            "required" => Some(json!({ "type": "any" })),
+            "none" => Some(json!({ "type": "none" })),
+            _ => None,
+        },
+        Value::Object(_) => {
+            let name = choice
+                .pointer("/function/name")
+                .or_else(|| choice.get("name"))?;
+            Some(json!({ "type": "tool", "name": name }))
+        }
+        _ => None,
+    }
+}
+
+/// OpenAI 图片地址转换为 Anthropic 图片块（data URL 转为 base64 来源）
+fn anthropic_image(url: &str) -> Option<Value> {
+    let source = match url.strip_prefix("data:") {
+        Some(data_url) => {
+            let (media_type, data) = data_url.split_once(";base64,")?;
+            json!({ "type": "base64", "media_type": media_type, "data": data })
+        }
+        None => json!({ "type": "url", "url": url }),
+    };
+    Some(json!({ "type": "image", "source": source }))
+}
+
+/// OpenAI 消息内容（字符串或内容片段数组）转换为 Anthropic 内容块
+fn anthropic_blocks(content: Option<&Value>) -> Vec<Value> {
+    match content {
+        Some(Value::String(text)) if !text.is_empty() => {
+            vec![json!({ "type": "text", "text": text })]
+        }
+        Some(Value::Array(parts)) => parts
+            .iter()
+            .filter_map(|part| match part.get("type").and_then(|v| v.as_str())? {
+                "text" | "input_text" | "output_text" => {
+                    let text = part.get("text")?.as_str()?;
+                    Some(json!({ "type": "text", "text": text }))
+                }
+                "image_url" | "input_image" => {
+                    let image = part.get("image_url")?;
+                    let url = image.get("url").unwrap_or(image).as_str()?;
+                    anthropic_image(url)
+                }
+                _ => None,
+            })
+            .collect(),
+        _ => Vec::new(),
+    }
+}
+
+/// OpenAI 消息内容中的纯文本
+fn openai_text(content: Option<&Value>) -> String {
+    match content {
+        Some(Value::String(text)) => text.clone(),
+        Some(Value::Array(parts)) => joined_text(parts),
+        Some(Value::Null) | None => String::new(),
+        Some(other) => other.to_string(),
+    }
+}
+
+fn tool_use_block(id: Option<&Value>, name: Option<&Value>, arguments: Option<&Value>) -> Value {
+    let arguments = arguments.and_then(|v| v.as_str()).unwrap_or("{}");
+    json!({
+        "type": "tool_use",
+        "id": id.cloned().unwrap_or_default(),
+        "name": name.cloned().unwrap_or_default(),
+        "input": serde_json::from_str::<Value>(arguments).unwrap_or_else(|_| json!({})),
+    })
+}
+
+fn tool_result_block(id: Option<&Value>, output: Option<&Value>) -> Value {
+    json!({
+        "type": "tool_result",
+        "tool_use_id": id.cloned().unwrap_or_default(),
+        "content": openai_text(output),
+    })
+}
+
+fn chat_to_anthropic_request(req: &Value) -> Value {
+    let mut out = AnthropicMessages::default();
+    for message in req
+        .get("messages")
+        .and_then(|v| v.as_array())
+        .into_iter()
+        .flatten()
+    {
+        let content = message.get("content");
+        match message.get("role").and_then(|v| v.as_str()) {
+            Some("system") | Some("developer") => out.push_system(openai_text(content)),
+            Some("assistant") => {
+                let mut blocks = anthropic_blocks(content);
+                for call in message
+                    .get("tool_calls")
+                    .and_then(|v| v.as_array())
+                    .into_iter()
+                    .flatten()
+                {
+                    blocks.push(tool_use_block(
+                        call.get("id"),
+                        call.pointer("/function/name"),
+                        call.pointer("/function/arguments"),
+                    ));
+                }
+                out.push("assistant", blocks);
+            }
+            Some("tool") => out.push(
+                "user",
+                vec![tool_result_block(message.get("tool_call_id"), content)],
+            ),
+            _ => out.push("user", anthropic_blocks(content)),
+        }
+    }
+    out.into_request(req, "max_tokens")
+}
+
+fn responses_to_anthropic_request(req: &Value) -> Value {
+    let mut out = AnthropicMessages::default();
+    out.push_system(openai_text(req.get("instructions")));
+    match req.get("input") {
+        Some(Value::String(text)) => out.push("user", anthropic_blocks(Some(&json!(text)))),
+        Some(Value::Array(items)) => {
+            for item in items {
+                match item
+                    .get("type")
+                    .and_then(|v| v.as_str())
+                    .unwrap_or("message")
+                {
+                    "message" => {
+                        let content = item.get("content");
+                        match item.get("role").and_then(|v| v.as_str()) {
+                            Some("system") | Some("developer") => {
+                                out.push_system(openai_text(content))
+                            }
+                            Some("assistant") => out.push("assistant", anthropic_blocks(content)),
+                            _ => out.push("user", anthropic_blocks(content)),
+                        }
+                    }
+                    "function_call" => out.push(
+                        "assistant",
+                        vec![tool_use_block(
+                            item.get("call_id"),
+                            item.get("name"),
+                            item.get("arguments"),
+                        )],
+                    ),
+                    "function_call_output" => out.push(
+                        "user",
+                        vec![tool_result_block(item.get("call_id"), item.get("output"))],
+                    ),
+                    // reasoning 等其余条目在 Messages 接口中没有对应项
+                    _ => {}
+                }
+            }
+        }
+        _ => {}
+    }
+    out.into_request(req, "max_output_tokens")
+}
+
+/// 将 Anthropic 流式响应（已完整读取）还原为完整消息
+fn anthropic_stream_to_message(body: &[u8]) -> Value {
+    let text = String::from_utf8_lossy(body);
+    let mut message = json!({});
+    // 内容块及工具调用块累积的参数 JSON
+    let mut blocks: Vec<(Value, String)> = Vec::new();
+    for event in text
+        .lines()
+        .filter_map(|line| line.strip_prefix("data:"))
+        .filter_map(|data| serde_json::from_str::<Value>(data.trim()).ok())
+    {
+        let index = event.get("index").and_then(|v| v.as_u64()).unwrap_or(0) as usize;
+        match event.get("type").and_then(|v| v.as_str()) {
+            Some("message_start") => {
+                if let Some(start) = event.get("message") {
+                    message = start.clone();
+                }
+            }
+            Some("content_block_start") => {
+                if blocks.len() <= index {
+                    blocks.resize(index + 1, (Value::Null, String::new()));
+                }
+                blocks[index].0 = event.get("content_block").cloned().unwrap_or_default();
+            }
+            Some("content_block_delta") => {
+                let (Some((block, partial)), Some(delta)) =
+                    (blocks.get_mut(index), event.get("delta"))
+                else {
+                    continue;
+                };
+                match delta.get("type").and_then(|v| v.as_str()) {
+                    Some("text_delta") => {
+                        let text = delta.get("text").and_then(|v| v.as_str()).unwrap_or("");
+                        let current = block.get("text").and_then(|v| v.as_str()).unwrap_or("");
+                        block["text"] = json!(format!("{}{}", current, text));
+                    }
+                    Some("input_json_delta") => partial.push_str(
+                        delta
+                            .get("partial_json")
+                            .and_then(|v| v.as_str())
+                            .unwrap_or(""),
+                    ),
+                    _ => {}
+                }
+            }
+            Some("message_delta") => {
+                if let Some(reason) = event.pointer("/delta/stop_reason") {
+                    message["stop_reason"] = reason.clone();
+                }
+                if let Some(Value::Object(usage)) = event.get("usage") {
+                    for (key, value) in usage {
+                        if !value.is_null() {
+                            message["usage"][key] = value.clone();
+                        }
+                    }
+                }
+            }
+            _ => {}
+        }
+    }
+    let content: Vec<Value> = blocks
+        .into_iter()
+        .filter(|(block, _)| block.is_object())
+        .map(|(mut block, partial)| {
+            if !partial.is_empty() {
+                block["input"] = serde_json::from_str(&partial).unwrap_or_else(|_| json!({}));
+            }
+            block
+        })
+        .collect();
+    message["content"] = Value::Array(content);
+    message
+}
+
+/// Anthropic 消息中的文本与工具调用（id、名称、参数 JSON）
+fn anthropic_output(message: &Value) -> (String, Vec<(Value, Value, String)>) {
+    let mut text = String::new();
+    let mut calls = Vec::new();
+    for block in message
+        .get("content")
+        .and_then(|v| v.as_array())
+        .into_iter()
+        .flatten()
+    {
+        match block.get("type").and_then(|v| v.as_str()) {
+            Some("text") => text.push_str(block.get("text").and_then(|v| v.as_str()).unwrap_or("")),
+            Some("tool_use") => calls.push((
+                block.get("id").cloned().unwrap_or_default(),
+                block.get("name").cloned().unwrap_or_default(),
+                block
+                    .get("input")
+                    .cloned()
+                    .unwrap_or_else(|| json!({}))
+                    .to_string(),
+            )),
+            _ => {}
+        }
+    }
+    (text, calls)
+}
+
+/// stop_reason 映射为 finish_reason
+fn finish_reason(message: &Value) -> &'static str {
+    match message.get("stop_reason").and_then(|v| v.as_str()) {
+        Some("max_tokens") => "length",
+        Some("tool_use") => "tool_calls",
+        _ => "stop",
+    }
+}
+
+/// Anthropic 用量：(输入 token 含缓存命中与写入, 输出 token)
+fn anthropic_usage(message: &Value) -> (u64, u64) {
+    let field = |key: &str| {
+        message
+            .get("usage")
+            .and_then(|u| u.get(key))
+            .and_then(|v| v.as_u64())
+            .unwrap_or(0)
+    };
+    (
+        field("input_tokens")
+            + field("cache_read_input_tokens")
+            + field("cache_creation_input_tokens"),
+        field("output_tokens"),
+    )
+}
+
+/// 去掉 Anthropic 消息 ID 的 `msg_` 前缀，作为 OpenAI 侧 ID 的后缀
+fn id_suffix(message: &Value) -> String {
+    match message.get("id").and_then(|v| v.as_str()) {
+        Some(id) if !id.is_empty() => id.trim_start_matches("msg_").to_string(),
+        _ => uuid::Uuid::new_v4().simple().to_string(),
+    }
+}
+
+fn unix_now() -> u64 {
+    chrono::Utc::now().timestamp().max(0) as u64
+}
+
+fn chat_usage(message: &Value) -> Value {
+    let (input, output) = anthropic_usage(message);
+    json!({ "prompt_tokens": input, "completion_tokens": output, "total_tokens": input + output })
+}
+
+fn anthropic_to_chat_response(message: &Value) -> Value {
+    let (text, calls) = anthropic_output(message);
+    let mut reply = json!({ "role": "assistant", "content": text });
+    if !calls.is_empty() {
+        reply["tool_calls"] = calls
+            .into_iter()
+            .map(|(id, name, arguments)| {
+                json!({ "id": id, "type": "function", "function": { "name": name, "arguments": arguments } })
+            })
+            .collect();
+    }
+    json!({
+        "id": format!("chatcmpl-{}", id_suffix(message)),
+        "object": "chat.completion",
+        "created": unix_now(),
+        "model": message.get("model").cloned().unwrap_or_default(),
+        "choices": [{ "index": 0, "message": reply, "finish_reason": finish_reason(message) }],
+        "usage": chat_usage(message),
+    })
+}
+
+/// 由完整消息生成 Chat Completions 流式响应
+fn chat_stream(message: &Value) -> String {
+    let id = format!("chatcmpl-{}", id_suffix(message));
+    let created = unix_now();
+    let model = message.get("model").cloned().unwrap_or_default();
+    let chunk = |choices: Value| {
+        json!({
+            "id": id,
+            "object": "chat.completion.chunk",
+            "created": created,
+            "model": model,
+            "choices": choices,
+        })
+    };
+    let delta = |delta: Value, finish: Value| {
+        chunk(json!([{ "index": 0, "delta": delta, "finish_reason": finish }]))
+    };
+
+    let (text, calls) = anthropic_output(message);
+    let mut chunks = vec![delta(
+        json!({ "role": "assistant", "content": "" }),
+        Value::Null,
+    )];
+    if !text.is_empty() {
+        chunks.push(delta(json!({ "content": text }), Value::Null));
+    }
+    for (index, (id, name, arguments)) in calls.into_iter().enumerate() {
+        chunks.push(delta(
+            json!({ "tool_calls": [{
+                "index": index,
+                "id": id,
+                "type": "function",
+                "function": { "name": name, "arguments": arguments },
+            }] }),
+            Value::Null,
+        ));
+    }
+    chunks.push(delta(json!({}), json!(finish_reason(message))));
+    let mut usage = chunk(json!([]));
+    usage["usage"] = chat_usage(message);
+    chunks.push(usage);
+
+    let mut out = String::new();
+    for chunk in chunks {
+        out.push_str(&format!("data: {}\n\n", chunk));
+    }
+    out.push_str("data: [DONE]\n\n");
+    out
+}
+
+fn anthropic_to_responses_response(message: &Value) -> Value {
+    let suffix = id_suffix(message);
+    let (text, calls) = anthropic_output(message);
+    let mut output = Vec::new();
+    if !text.is_empty() {
+        output.push(json!({
+            "type": "message",
+            "id": format!("msg_{}", suffix),
+            "status": "completed",
+            "role": "assistant",
+            "content": [{ "type": "output_text", "text": text, "annotations": [] }],
+        }));
+    }
+    for (id, name, arguments) in calls {
+        output.push(json!({
+            "type": "function_call",
+            "id": format!("fc_{}", id.as_str().unwrap_or_default()),
+            "call_id": id,
+            "name": name,
+            "arguments": arguments,
+            "status": "completed",
+        }));
+    }
+    let (input, output_tokens) = anthropic_usage(message);
+    let incomplete = finish_reason(message) == "length";
+    json!({
+        "id": format!("resp_{}", suffix),
+        "object": "response",
+        "created_at": unix_now(),
+        "status": if incomplete { "incomplete" } else { "completed" },
+        "incomplete_details": if incomplete { json!({ "reason": "max_output_tokens" }) } else { Value::Null },
+        "model": message.get("model").cloned().unwrap_or_default(),
+        "output": output,
+        "usage": {
+            "input_tokens": input,
+            "output_tokens": output_tokens,
+            "total_tokens": input + output_tokens,
+        },
+    })
+}
+
+/// 由完整消息生成 Responses 流式事件序列
+fn responses_stream(message: &Value) -> String {
+    let response = anthropic_to_responses_response(message);
+    let mut out = String::new();
+    let mut sequence = 0u64;
+    let mut emit = |mut data: Value| {
+        data["sequence_number"] = json!(sequence);
+        sequence += 1;
+        push_event(&mut out, &data);
+    };
+
+    let mut created = response.clone();
+    created["status"] = json!("in_progress");
+    created["output"] = json!([]);
+    emit(json!({ "type": "response.created", "response": created }));
+
+    let items = response
+        .get("output")
+        .and_then(|v| v.as_array())
+        .cloned()
+        .unwrap_or_default();
+    for (output_index, item) in items.into_iter().enumerate() {
+        let item_id = item.get("id").cloned().unwrap_or_default();
+        let mut added = item.clone();
+        added["status"] = json!("in_progress");
+        if item.get("type").and_then(|v| v.as_str()) == Some("message") {
+            let text = item.pointer("/content/0/text").cloned().unwrap_or_default();
+            added["content"] = json!([]);
+            emit(
+                json!({ "type": "response.output_item.added", "output_index": output_index, "item": added }),
+            );
+            let part =
+                |text: Value| json!({ "type": "output_text", "text": text, "annotations": [] });
+            emit(json!({
+                "type": "response.content_part.added",
+                "item_id": item_id,
+                "output_index": output_index,
+                "content_index": 0,
+                "part": part(json!("")),
+            }));
+            emit(json!({
+                "type": "response.output_text.delta",
+                "item_id": item_id,
+                "output_index": output_index,
+                "content_index": 0,
+                "delta": text,
+            }));
+            emit(json!({
+                "type": "response.output_text.done",
+                "item_id": item_id,
+                "output_index": output_index,
+                "content_index": 0,
+                "text": text,
+            }));
+            emit(json!({
+                "type": "response.content_part.done",
+                "item_id": item_id,
+                "output_index": output_index,
+                "content_index": 0,
+                "part": part(text),
+            }));
+        } else {
+            let arguments = item.get("arguments").cloned().unwrap_or_default();
+            added["arguments"] = json!("");
+            emit(
+                json!({ "type": "response.output_item.added", "output_index": output_index, "item": added }),
+            );
+            emit(json!({
+                "type": "response.function_call_arguments.delta",
+                "item_id": item_id,
+                "output_index": output_index,
+                "delta": arguments,
+            }));
+            emit(json!({
+                "type": "response.function_call_arguments.done",
+                "item_id": item_id,
+                "output_index": output_index,
+                "arguments": arguments,
+            }));
+        }
+        emit(
+            json!({ "type": "response.output_item.done", "output_index": output_index, "item": item }),
+        );
+    }
+
+    let kind = if response.get("status").and_then(|v| v.as_str()) == Some("incomplete") {
+        "response.incomplete"
+    } else {
+        "response.completed"
+    };
+    emit(json!({ "type": kind, "response": response }));
+    out
+}
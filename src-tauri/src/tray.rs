@@ -0,0 +1,290 @@
+use tauri::menu::{CheckMenuItem, Menu, MenuBuilder, MenuItem};
+#[cfg(target_os = "macos")]
+use tauri::ActivationPolicy;
+use tauri::Manager;
+
+use crate::app_config::AppType;
+use crate::error::CcSwitchError;
+use crate::i18n::{t, t_args};
+use crate::settings::OperationMode;
+use crate::store::AppState;
+
+/// 托盘图标 ID
+pub const TRAY_ID: &str = "main";
+
+/// 健康状态轮询间隔（秒）：仅在状态变化时重建菜单
+const HEALTH_REFRESH_INTERVAL_SECS: u64 = 15;
+
+/// 托盘中展示的应用分组：（应用类型, 菜单 ID 前缀, 标题）
+const TRAY_SECTIONS: [(AppType, &str, &str); 2] = [
+    (AppType::Claude, "claude", "Claude"),
+    (AppType::Codex, "codex", "Codex"),
+];
+
+/// 供应商健康标识：最近一次代理请求成功为绿点，失败为红点，无记录时不显示
+fn health_prefix(app_type: &AppType, provider_id: &str) -> &'static str {
+    match crate::proxy::provider_health(app_type, provider_id) {
+        Some(true) => "🟢 ",
+        Some(false) => "🔴 ",
+        None => "",
+    }
+}
+
+/// 创建动态托盘菜单
+pub fn create_tray_menu(
+    app: &tauri::AppHandle,
+    app_state: &AppState,
+) -> Result<Menu<tauri::Wry>, String> {
+    let config = app_state
+        .config
+        .lock()
+        .map_err(|e| t_args("errors.lockFailed", &[("error", &e)]))?;
+
+    let mut menu_builder = MenuBuilder::new(app);
+
+    // 顶部：打开主界面
+    let show_main_item =
+        MenuItem::with_id(app, "show_main", t("tray.showMain"), true, None::<&str>)
+            .map_err(|e| format!("创建打开主界面菜单失败: {}", e))?;
+    menu_builder = menu_builder.item(&show_main_item).separator();
+
+    // 直接添加所有供应商到主菜单（扁平化结构，更简单可靠）
+    for (app_type, prefix, title) in TRAY_SECTIONS.iter() {
+        let Some(manager) = config.get_manager(app_type) else {
+            continue;
+        };
+
+        // 添加分组标题（禁用状态，仅作为分组标识）
+        let header = MenuItem::with_id(
+            app,
+            format!("{}_header", prefix),
+            format!("─── {} ───", title),
+            false,
+            None::<&str>,
+        )
+        .map_err(|e| format!("创建{}标题失败: {}", title, e))?;
+        menu_builder = menu_builder.item(&header);
+
+        if manager.providers.is_empty() {
+            // 没有供应商时显示提示
+            let empty_hint = MenuItem::with_id(
+                app,
+                format!("{}_empty", prefix),
+                t("tray.noProviders"),
+                false,
+                None::<&str>,
+            )
+            .map_err(|e| format!("创建{}空提示失败: {}", title, e))?;
+            menu_builder = menu_builder.item(&empty_hint);
+            continue;
+        }
+
+        // 停用的供应商不出现在切换列表中（当前供应商除外）
+        for provider in manager
+            .sorted_providers()
+            .into_iter()
+            .filter(|p| p.is_enabled() || p.id == manager.current)
+        {
+            let item = CheckMenuItem::with_id(
+                app,
+                format!("{}_{}", prefix, provider.id),
+                format!("{}{}", health_prefix(app_type, &provider.id), provider.name),
+                true,
+                manager.current == provider.id,
+                None::<&str>,
+            )
+            .map_err(|e| format!("创建菜单项失败: {}", e))?;
+            menu_builder = menu_builder.item(&item);
+        }
+    }
+    drop(config);
+
+    // 代理模式开关
+    let proxy_mode = crate::settings::get_settings().operation_mode == OperationMode::Proxy;
+    let proxy_item = CheckMenuItem::with_id(
+        app,
+        "toggle_proxy",
+        t("tray.proxyMode"),
+        true,
+        proxy_mode,
+        None::<&str>,
+    )
+    .map_err(|e| format!("创建代理模式菜单失败: {}", e))?;
+
+    // 重新写入当前配置（其他工具覆盖配置文件后使用）
+    let reapply_item = MenuItem::with_id(
+        app,
+        "reapply_current",
+        t("tray.reapplyCurrent"),
+        true,
+        None::<&str>,
+    )
+    .map_err(|e| format!("创建重新应用菜单失败: {}", e))?;
+
+    // 分隔符和退出菜单
+    let quit_item = MenuItem::with_id(app, "quit", t("tray.quit"), true, None::<&str>)
+        .map_err(|e| format!("创建退出菜单失败: {}", e))?;
+
+    menu_builder = menu_builder
+        .separator()
+        .item(&proxy_item)
+        .item(&reapply_item)
+        .separator()
+        .item(&quit_item);
+
+    menu_builder
+        .build()
+        .map_err(|e| format!("构建菜单失败: {}", e))
+}
+
+/// 按当前配置重建托盘菜单；托盘不存在时返回 false
+pub fn refresh_tray_menu(app: &tauri::AppHandle) -> Result<bool, String> {
+    let Some(app_state) = app.try_state::<AppState>() else {
+        return Ok(false);
+    };
+    let Some(tray) = app.tray_by_id(TRAY_ID) else {
+        return Ok(false);
+    };
+    let menu = create_tray_menu(app, app_state.inner())?;
+    tray.set_menu(Some(menu))
+        .map_err(|e| format!("更新托盘菜单失败: {}", e))?;
+    Ok(true)
+}
+
+/// 后台轮询供应商健康状态，变化时重建托盘菜单
+pub fn start_health_refresh(app: tauri::AppHandle) {
+    tauri::async_runtime::spawn(async move {
+        let mut last_signature = String::new();
+        loop {
+            tokio::time::sleep(std::time::Duration::from_secs(HEALTH_REFRESH_INTERVAL_SECS)).await;
+
+            let signature = match app.try_state::<AppState>() {
+                Some(state) => health_signature(state.inner()),
+                None => continue,
+            };
+            if signature == last_signature {
+                continue;
+            }
+            last_signature = signature;
+
+            if let Err(e) = refresh_tray_menu(&app) {
+                tracing::warn!("刷新托盘健康状态失败: {}", e);
+            }
+        }
+    });
+}
+
+/// 汇总所有供应商的健康标识，用于判断是否需要重建菜单
+fn health_signature(app_state: &AppState) -> String {
+    let Ok(config) = app_state.config.lock() else {
+        return String::new();
+    };
+    let mut signature = String::new();
+    for (app_type, _, _) in TRAY_SECTIONS.iter() {
+        if let Some(manager) = config.get_manager(app_type) {
+            for provider in manager.sorted_providers() {
+                signature.push_str(&provider.id);
+                signature.push_str(health_prefix(app_type, &provider.id));
+                signature.push('|');
+            }
+        }
+    }
+    signature
+}
+
+#[cfg(target_os = "macos")]
+pub fn apply_tray_policy(app: &tauri::AppHandle, dock_visible: bool) {
+    let desired_policy = if dock_visible {
+        ActivationPolicy::Regular
+    } else {
+        ActivationPolicy::Accessory
+    };
+
+    if let Err(err) = app.set_dock_visibility(dock_visible) {
+        tracing::warn!("设置 Dock 显示状态失败: {}", err);
+    }
+
+    if let Err(err) = app.set_activation_policy(desired_policy) {
+        tracing::warn!("设置激活策略失败: {}", err);
+    }
+}
+
+/// 显示并聚焦主窗口（托盘、Dock 重新激活与第二次启动共用），同时恢复任务栏/Dock 显示
+pub fn show_main_window(app: &tauri::AppHandle) {
+    if let Some(window) = app.get_webview_window("main") {
+        #[cfg(target_os = "windows")]
+        {
+            let _ = window.set_skip_taskbar(false);
+        }
+        let _ = window.unminimize();
+        let _ = window.show();
+        let _ = window.set_focus();
+        #[cfg(target_os = "macos")]
+        {
+            apply_tray_policy(app, true);
+        }
+    }
+}
+
+/// 处理托盘菜单事件
+pub fn handle_tray_menu_event(app: &tauri::AppHandle, event_id: &str) {
+    tracing::info!("处理托盘菜单事件: {}", event_id);
+
+    match event_id {
+        "show_main" => show_main_window(app),
+        "quit" => {
+            tracing::info!("退出应用");
+            app.exit(0);
+        }
+        "toggle_proxy" => {
+            let app_handle = app.clone();
+            tauri::async_runtime::spawn(async move {
+                if let Err(e) = crate::toggle_operation_mode_internal(&app_handle).await {
+                    tracing::error!("切换运行模式失败: {}", e);
+                    // 还原菜单勾选状态
+                    let _ = refresh_tray_menu(&app_handle);
+                }
+            });
+        }
+        "reapply_current" => {
+            let Some(state) = app.try_state::<AppState>() else {
+                return;
+            };
+            for (app_type, _, _) in TRAY_SECTIONS.iter() {
+                if let Err(e) = crate::self_check::reapply_live(&state, app_type) {
+                    tracing::error!("重新应用{}当前配置失败: {}", app_type.as_str(), e);
+                }
+            }
+        }
+        id => {
+            let Some((app_type, provider_id)) = TRAY_SECTIONS.iter().find_map(|(t, prefix, _)| {
+                id.strip_prefix(prefix)
+                    .and_then(|rest| rest.strip_prefix('_'))
+                    .map(|pid| (t.clone(), pid.to_string()))
+            }) else {
+                tracing::warn!("未处理的菜单事件: {}", event_id);
+                return;
+            };
+            tracing::info!("切换到{}供应商: {}", app_type.as_str(), provider_id);
+
+            // 执行切换
+            let app_handle = app.clone();
+            tauri::async_runtime::spawn(async move {
+                let app_type_str = app_type.as_str().to_string();
+                if let Err(e) =
+                    crate::switch_provider_internal(&app_handle, app_type, provider_id).await
+                {
+                    tracing::error!("切换{}供应商失败: {}", app_type_str, e);
+                    // 切换失败时恢复原勾选状态
+                    let _ = refresh_tray_menu(&app_handle);
+                }
+            });
+        }
+    }
+}
+
+/// 更新托盘菜单的Tauri命令
+#[tauri::command]
+pub async fn update_tray_menu(app: tauri::AppHandle) -> Result<bool, CcSwitchError> {
+    refresh_tray_menu(&app).map_err(Into::into)
+}
@@ -0,0 +1,107 @@
+//! 流式响应的首字节延迟（TTFB）统计：按供应商保留最近若干次流式请求从发出到收到首个数据帧的耗时，
+//! 给出滚动的 p50 / p95，帮助按交互时的实际响应速度（而不仅是成功率）选择中转。
+//! 统计仅保存在进程内，重启后清零。
+
+use serde::Serialize;
+use std::collections::{HashMap, VecDeque};
+use std::sync::{Mutex, OnceLock};
+use std::time::Duration;
+
+use crate::app_config::AppType;
+use crate::error::CcSwitchError;
+use crate::i18n::t_args;
+use crate::provider::Provider;
+
+/// 每个供应商保留的样本数
+const WINDOW: usize = 100;
+
+/// 应用类型:供应商 ID -> 样本
+static SAMPLES: OnceLock<Mutex<HashMap<String, ProviderSamples>>> = OnceLock::new();
+
+#[derive(Debug, Clone)]
+struct ProviderSamples {
+    app_type: AppType,
+    provider_id: String,
+    provider_name: String,
+    /// 最近的 TTFB（毫秒），最旧的在前
+    values: VecDeque<u64>,
+}
+
+/// 单个供应商的 TTFB 统计
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ProviderTtfb {
+    pub app_type: AppType,
+    pub provider_id: String,
+    pub provider_name: String,
+    pub samples: usize,
+    pub p50_ms: u64,
+    pub p95_ms: u64,
+}
+
+fn key(app_type: &AppType, provider_id: &str) -> String {
+    format!("{}:{}", app_type.as_str(), provider_id)
+}
+
+/// 记录一次流式响应的首字节延迟
+pub fn record(app_type: &AppType, provider: &Provider, ttfb: Duration) {
+    let Ok(mut samples) = SAMPLES.get_or_init(Default::default).lock() else {
+        return;
+    };
+    let entry = samples
+        .entry(key(app_type, &provider.id))
+        .or_insert_with(|| ProviderSamples {
+            app_type: app_type.clone(),
+            provider_id: provider.id.clone(),
+            provider_name: provider.name.clone(),
+            values: VecDeque::with_capacity(WINDOW),
+        });
+    entry.provider_name = provider.name.clone();
+    if entry.values.len() == WINDOW {
+        entry.values.pop_front();
+    }
+    entry.values.push_back(ttfb.as_millis() as u64);
+}
+
+/// 计算 p50 / p95（最近邻取值）
+fn percentiles(values: &VecDeque<u64>) -> Option<(u64, u64)> {
+    if values.is_empty() {
+        return None;
+    }
+    let mut sorted: Vec<u64> = values.iter().copied().collect();
+    sorted.sort_unstable();
+    let at = |p: usize| sorted[((sorted.len() * p).div_ceil(100)).saturating_sub(1)];
+    Some((at(50), at(95)))
+}
+
+/// 指定供应商的 TTFB p50 / p95（没有样本时返回 None）
+pub fn provider_percentiles(app_type: &str, provider_id: &str) -> Option<(u64, u64)> {
+    let samples = SAMPLES.get()?.lock().ok()?;
+    let entry = samples.get(&format!("{}:{}", app_type, provider_id))?;
+    percentiles(&entry.values)
+}
+
+/// 获取各供应商的流式首字节延迟统计（按 p50 从快到慢排序）
+#[tauri::command]
+pub async fn get_ttfb_stats() -> Result<Vec<ProviderTtfb>, CcSwitchError> {
+    let samples = SAMPLES
+        .get_or_init(Default::default)
+        .lock()
+        .map_err(|e| t_args("errors.lockFailed", &[("error", &e)]))?;
+    let mut stats: Vec<ProviderTtfb> = samples
+        .values()
+        .filter_map(|entry| {
+            let (p50_ms, p95_ms) = percentiles(&entry.values)?;
+            Some(ProviderTtfb {
+                app_type: entry.app_type.clone(),
+                provider_id: entry.provider_id.clone(),
+                provider_name: entry.provider_name.clone(),
+                samples: entry.values.len(),
+                p50_ms,
+                p95_ms,
+            })
+        })
+        .collect();
+    stats.sort_by(|a, b| (a.app_type.as_str(), a.p50_ms).cmp(&(b.app_type.as_str(), b.p50_ms)));
+    Ok(stats)
+}
@@ -0,0 +1,371 @@
+//! 代理上游连接：按供应商的连接参数构建 HTTP 客户端。使用默认参数的供应商共用一个客户端与连接池；
+//! 设置了连接主机（`meta.connect_host`）或 SNI（`meta.sni_host`）的供应商使用各自的客户端——
+//! 连接池按 URL 主机复用连接，覆盖连接目标后不能与默认客户端共享。
+//! 连接主机只改变 TCP 连接的目标，请求中的 Host / :authority 仍为 URL 主机；
+//! SNI 覆盖同时决定证书按哪个域名校验，用于中转发布了备用接入域名以绕过地区封锁的场景。
+//! HTTP 版本与连接复用参数取自设置 `proxy_connection`，对所有客户端生效。
+//! 配置了 mTLS 客户端证书（`meta.client_cert`）的供应商同样使用各自的客户端。
+//! 本地模型服务（Ollama、LM Studio、llama.cpp 等）可直接使用 `http://localhost:端口` 作为地址；
+//! 监听 Unix 域套接字的服务设置 `meta.unix_socket` 后，连接改走该套接字，URL 主机仅用于 Host 头。
+
+use std::collections::HashMap;
+use std::future::Future;
+use std::pin::Pin;
+use std::str::FromStr;
+use std::sync::{Mutex, OnceLock};
+use std::task::{Context, Poll};
+use std::time::{Duration, Instant};
+
+use axum::body::Body;
+use axum::http::uri::{Authority, Uri};
+use axum::http::Request;
+use hyper_rustls::{FixedServerNameResolver, HttpsConnector, HttpsConnectorBuilder};
+use hyper_util::client::legacy::connect::{Connected, Connection, HttpConnector};
+use hyper_util::client::legacy::Client;
+use hyper_util::rt::{TokioExecutor, TokioIo, TokioTimer};
+use rustls::pki_types::ServerName;
+use tokio::net::TcpStream;
+use tower::Service;
+
+use crate::dns::UpstreamResolver;
+use crate::i18n::t_args;
+use crate::provider::{ClientCertificate, Provider};
+use crate::settings::UpstreamHttpVersion;
+use crate::speedtest::EndpointLatency;
+
+type BoxError = Box<dyn std::error::Error + Send + Sync>;
+
+/// 发往上游的 HTTP 客户端
+pub type UpstreamClient = Client<HttpsConnector<ConnectHostOverride>, Body>;
+
+/// 影响上游连接的参数；相同参数的供应商共用客户端与连接池
+#[derive(Debug, Clone, Default, PartialEq, Eq, Hash)]
+pub struct ConnectorOptions {
+    /// TCP 连接的目标主机（可带端口），未设置时连接 URL 主机
+    pub connect_host: Option<String>,
+    /// TLS 握手使用的 SNI（同时用于证书校验），未设置时使用 URL 主机
+    pub sni_host: Option<String>,
+    /// 不校验上游证书
+    pub accept_invalid_certs: bool,
+    /// 握手时出示的客户端证书
+    pub client_cert: Option<ClientCertificate>,
+    /// 改为连接的 Unix 域套接字路径
+    pub unix_socket: Option<String>,
+}
+
+impl ConnectorOptions {
+    pub fn for_provider(provider: &Provider) -> Self {
+        let trimmed = |value: &Option<String>| {
+            value
+                .as_deref()
+                .map(str::trim)
+                .filter(|value| !value.is_empty())
+                .map(str::to_string)
+        };
+        match provider.meta.as_ref() {
+            Some(meta) => Self {
+                connect_host: trimmed(&meta.connect_host),
+                sni_host: trimmed(&meta.sni_host),
+                accept_invalid_certs: meta.accept_invalid_certs,
+                client_cert: meta
+                    .client_cert
+                    .clone()
+                    .filter(ClientCertificate::is_configured),
+                unix_socket: trimmed(&meta.unix_socket),
+            },
+            None => Self::default(),
+        }
+    }
+}
+
+/// 连接参数 -> 客户端；网络变化或睡眠唤醒后清空，丢弃已失效的 keep-alive 连接
+static CLIENTS: OnceLock<Mutex<HashMap<ConnectorOptions, UpstreamClient>>> = OnceLock::new();
+
+/// 校验供应商的连接参数
+pub fn validate(provider: &Provider) -> Result<(), String> {
+    let options = ConnectorOptions::for_provider(provider);
+    if let Some(host) = options.connect_host.as_deref() {
+        if Authority::from_str(host).is_err() {
+            return Err(t_args("errors.invalidConnectHost", &[("host", &host)]));
+        }
+    }
+    if let Some(host) = options.sni_host.as_deref() {
+        if ServerName::try_from(host).is_err() {
+            return Err(t_args("errors.invalidSniHost", &[("host", &host)]));
+        }
+    }
+    if let Some(cert) = options.client_cert.as_ref() {
+        crate::ca_certs::client_config(false, Some(cert))?;
+    }
+    if let Some(path) = options.unix_socket.as_deref() {
+        if !cfg!(unix) {
+            return Err(t_args("errors.unixSocketUnsupported", &[("path", &path)]));
+        }
+        if !std::path::Path::new(path).is_absolute() {
+            return Err(t_args("errors.invalidUnixSocket", &[("path", &path)]));
+        }
+    }
+    Ok(())
+}
+
+/// 供应商是否通过 Unix 域套接字连接（此时端点无法按 URL 直接测速）
+pub fn uses_unix_socket(provider: &Provider) -> bool {
+    ConnectorOptions::for_provider(provider)
+        .unix_socket
+        .is_some()
+}
+
+/// 通过供应商自己的上游客户端测试端点：用于 Unix 套接字等只有代理连接器能访问的地址
+pub async fn probe(provider: &Provider, url: &str) -> EndpointLatency {
+    let result = async {
+        let client = client_for(provider)?;
+        let request = Request::get(url)
+            .body(Body::empty())
+            .map_err(|e| format!("URL 无效: {}", e))?;
+        let started = Instant::now();
+        let timeout = Duration::from_secs(crate::speedtest::LOCAL_TIMEOUT_SECS);
+        match tokio::time::timeout(timeout, client.request(request)).await {
+            Ok(Ok(response)) => Ok((response.status().as_u16(), started.elapsed().as_millis())),
+            Ok(Err(e)) if e.is_connect() => Err("本地服务未运行".to_string()),
+            Ok(Err(e)) => Err(e.to_string()),
+            Err(_) => Err("请求超时".to_string()),
+        }
+    }
+    .await;
+    match result {
+        Ok((status, latency)) => EndpointLatency {
+            url: url.to_string(),
+            latency: Some(latency),
+            status: Some(status),
+            error: None,
+        },
+        Err(error) => EndpointLatency {
+            url: url.to_string(),
+            latency: None,
+            status: None,
+            error: Some(error),
+        },
+    }
+}
+
+/// 获取供应商使用的客户端（按连接参数缓存）
+pub fn client_for(provider: &Provider) -> Result<UpstreamClient, String> {
+    client(&ConnectorOptions::for_provider(provider))
+}
+
+/// 获取指定连接参数的客户端（按参数缓存）
+pub fn client(options: &ConnectorOptions) -> Result<UpstreamClient, String> {
+    let clients = CLIENTS.get_or_init(Default::default);
+    if let Some(client) = clients.lock().ok().and_then(|c| c.get(options).cloned()) {
+        return Ok(client);
+    }
+    let client = build(options)?;
+    if let Ok(mut clients) = clients.lock() {
+        clients.insert(options.clone(), client.clone());
+    }
+    Ok(client)
+}
+
+/// 丢弃全部客户端与 DoH 解析缓存，下次请求重新解析并建立连接
+pub fn reset() {
+    if let Some(clients) = CLIENTS.get() {
+        if let Ok(mut clients) = clients.lock() {
+            clients.clear();
+        }
+    }
+    crate::dns::clear_cache();
+}
+
+fn build(options: &ConnectorOptions) -> Result<UpstreamClient, String> {
+    // 使用支持主机映射与 DoH 的解析器
+    let mut http = HttpConnector::new_with_resolver(UpstreamResolver::new());
+    http.enforce_http(false);
+    let connector = ConnectHostOverride {
+        inner: http,
+        connect_host: options.connect_host.clone(),
+        unix_socket: options.unix_socket.clone(),
+    };
+
+    // 内置根证书加上 ca-certs 目录中的额外根证书
+    if options.accept_invalid_certs {
+        tracing::warn!("已创建不校验证书的上游连接，连接内容可能被中间人截获");
+    }
+    let tls_config =
+        crate::ca_certs::client_config(options.accept_invalid_certs, options.client_cert.as_ref())?;
+    let builder = HttpsConnectorBuilder::new()
+        .with_tls_config(tls_config)
+        .https_or_http(); // 同时支持 http:// 和 https://
+    let builder = match options.sni_host.as_deref() {
+        Some(sni_host) => {
+            let name = ServerName::try_from(sni_host.to_string())
+                .map_err(|_| t_args("errors.invalidSniHost", &[("host", &sni_host)]))?;
+            builder.with_server_name_resolver(FixedServerNameResolver::new(name))
+        }
+        None => builder,
+    };
+    // HTTP 版本与连接复用参数来自设置，设置变更后会重置客户端
+    let settings = crate::settings::get_settings().proxy_connection;
+    let https = match settings.http_version {
+        UpstreamHttpVersion::Auto => builder
+            .enable_http1()
+            .enable_http2()
+            .wrap_connector(connector),
+        UpstreamHttpVersion::Http1 => builder.enable_http1().wrap_connector(connector),
+        UpstreamHttpVersion::Http2 => builder.enable_http2().wrap_connector(connector),
+    };
+
+    let mut client = Client::builder(TokioExecutor::new());
+    client
+        .timer(TokioTimer::new())
+        .pool_timer(TokioTimer::new())
+        .pool_idle_timeout(Duration::from_secs(settings.pool_idle_timeout_secs))
+        .http2_only(settings.http_version == UpstreamHttpVersion::Http2);
+    if settings.pool_idle_timeout_secs == 0 {
+        client.pool_max_idle_per_host(0);
+    }
+    if settings.http2_keep_alive_secs > 0 {
+        client
+            .http2_keep_alive_interval(Duration::from_secs(settings.http2_keep_alive_secs))
+            .http2_keep_alive_while_idle(true);
+    }
+    Ok(client.build(https))
+}
+
+/// 将 TCP 连接目标替换为连接主机（或改为连接 Unix 套接字）的连接器，未设置时原样连接 URL 主机
+#[derive(Clone)]
+pub struct ConnectHostOverride {
+    inner: HttpConnector<UpstreamResolver>,
+    connect_host: Option<String>,
+    unix_socket: Option<String>,
+}
+
+impl ConnectHostOverride {
+    /// 连接目标：连接主机未带端口时沿用 URL 中的端口
+    fn target(&self, dst: &Uri) -> Option<Uri> {
+        let host = self.connect_host.as_deref()?;
+        let authority = match (Authority::from_str(host).ok()?.port(), dst.port_u16()) {
+            (None, Some(port)) => format!("{}:{}", host, port),
+            _ => host.to_string(),
+        };
+        let mut parts = dst.clone().into_parts();
+        parts.authority = Some(Authority::from_str(&authority).ok()?);
+        Uri::from_parts(parts).ok()
+    }
+}
+
+impl Service<Uri> for ConnectHostOverride {
+    type Response = UpstreamStream;
+    type Error = BoxError;
+    type Future = Pin<Box<dyn Future<Output = Result<Self::Response, Self::Error>> + Send>>;
+
+    fn poll_ready(&mut self, cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+        self.inner.poll_ready(cx).map_err(Into::into)
+    }
+
+    fn call(&mut self, dst: Uri) -> Self::Future {
+        if let Some(path) = self.unix_socket.clone() {
+            tracing::debug!("连接 {} 时改为连接 Unix 套接字 {}", dst, path);
+            return Box::pin(connect_unix(path));
+        }
+        let connecting = match self.target(&dst) {
+            Some(target) => {
+                tracing::debug!("连接 {} 时改为连接 {}", dst, target);
+                self.inner.call(target)
+            }
+            None => self.inner.call(dst),
+        };
+        Box::pin(async move { Ok(UpstreamStream::Tcp(connecting.await?)) })
+    }
+}
+
+#[cfg(unix)]
+async fn connect_unix(path: String) -> Result<UpstreamStream, BoxError> {
+    let stream = tokio::net::UnixStream::connect(&path).await?;
+    Ok(UpstreamStream::Unix(TokioIo::new(stream)))
+}
+
+#[cfg(not(unix))]
+async fn connect_unix(path: String) -> Result<UpstreamStream, BoxError> {
+    Err(t_args("errors.unixSocketUnsupported", &[("path", &path)]).into())
+}
+
+/// 上游连接：TCP 或 Unix 域套接字
+pub enum UpstreamStream {
+    Tcp(TokioIo<TcpStream>),
+    #[cfg(unix)]
+    Unix(TokioIo<tokio::net::UnixStream>),
+}
+
+impl Connection for UpstreamStream {
+    fn connected(&self) -> Connected {
+        match self {
+            Self::Tcp(io) => io.connected(),
+            #[cfg(unix)]
+            Self::Unix(io) => io.connected(),
+        }
+    }
+}
+
+impl hyper::rt::Read for UpstreamStream {
+    fn poll_read(
+        self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+        buf: hyper::rt::ReadBufCursor<'_>,
+    ) -> Poll<std::io::Result<()>> {
+        match self.get_mut() {
+            Self::Tcp(io) => Pin::new(io).poll_read(cx, buf),
+            #[cfg(unix)]
+            Self::Unix(io) => Pin::new(io).poll_read(cx, buf),
+        }
+    }
+}
+
+impl hyper::rt::Write for UpstreamStream {
+    fn poll_write(
+        self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+        buf: &[u8],
+    ) -> Poll<std::io::Result<usize>> {
+        match self.get_mut() {
+            Self::Tcp(io) => Pin::new(io).poll_write(cx, buf),
+            #[cfg(unix)]
+            Self::Unix(io) => Pin::new(io).poll_write(cx, buf),
+        }
+    }
+
+    fn poll_flush(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<std::io::Result<()>> {
+        match self.get_mut() {
+            Self::Tcp(io) => Pin::new(io).poll_flush(cx),
+            #[cfg(unix)]
+            Self::Unix(io) => Pin::new(io).poll_flush(cx),
+        }
+    }
+
+    fn poll_shutdown(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<std::io::Result<()>> {
+        match self.get_mut() {
+            Self::Tcp(io) => Pin::new(io).poll_shutdown(cx),
+            #[cfg(unix)]
+            Self::Unix(io) => Pin::new(io).poll_shutdown(cx),
+        }
+    }
+
+    fn is_write_vectored(&self) -> bool {
+        match self {
+            Self::Tcp(io) => io.is_write_vectored(),
+            #[cfg(unix)]
+            Self::Unix(io) => io.is_write_vectored(),
+        }
+    }
+
+    fn poll_write_vectored(
+        self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+        bufs: &[std::io::IoSlice<'_>],
+    ) -> Poll<std::io::Result<usize>> {
+        match self.get_mut() {
+            Self::Tcp(io) => Pin::new(io).poll_write_vectored(cx, bufs),
+            #[cfg(unix)]
+            Self::Unix(io) => Pin::new(io).poll_write_vectored(cx, bufs),
+        }
+    }
+}
@@ -0,0 +1,152 @@
+use serde::Serialize;
+use std::collections::HashMap;
+use std::sync::{Mutex, OnceLock};
+use std::time::{Duration, Instant};
+use tauri::Emitter;
+use tauri_plugin_notification::NotificationExt;
+
+use crate::app_config::AppType;
+use crate::provider::{BalanceAlert, UsageData};
+use crate::webhook::WebhookEvent;
+
+/// 默认告警冷却时间（分钟）
+const DEFAULT_COOLDOWN_MINUTES: u64 = 60;
+
+/// 低余额事件名
+pub const BALANCE_LOW_EVENT: &str = "usage-balance-low";
+
+/// 最近一次告警时间（按 应用类型:供应商 记录）
+static LAST_ALERTS: OnceLock<Mutex<HashMap<String, Instant>>> = OnceLock::new();
+
+/// 低余额告警事件负载
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct BalanceLowPayload {
+    pub app_type: String,
+    pub provider_id: String,
+    pub provider_name: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub plan_name: Option<String>,
+    pub remaining: f64,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub total: Option<f64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub percent: Option<f64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub unit: Option<String>,
+}
+
+/// 找出第一个低于阈值的套餐
+fn find_low_plan(alert: &BalanceAlert, usage: &[UsageData]) -> Option<(usize, Option<f64>)> {
+    usage.iter().enumerate().find_map(|(idx, plan)| {
+        let remaining = plan.remaining?;
+        let percent = match plan.total {
+            Some(total) if total > 0.0 => Some(remaining / total * 100.0),
+            _ => None,
+        };
+        let below_abs = alert
+            .remaining_below
+            .map(|threshold| remaining < threshold)
+            .unwrap_or(false);
+        let below_pct = match (alert.percent_below, percent) {
+            (Some(threshold), Some(p)) => p < threshold,
+            _ => false,
+        };
+        if below_abs || below_pct {
+            Some((idx, percent))
+        } else {
+            None
+        }
+    })
+}
+
+/// 用量数据更新后评估低余额阈值；命中且不在冷却期内时发送事件与（可选）系统通知
+pub fn evaluate(
+    app: &tauri::AppHandle,
+    app_type: &AppType,
+    provider_id: &str,
+    provider_name: &str,
+    alert: &BalanceAlert,
+    usage: &[UsageData],
+) {
+    if !alert.enabled {
+        return;
+    }
+
+    let key = format!("{}:{}", app_type.as_str(), provider_id);
+    let Ok(mut last_alerts) = LAST_ALERTS
+        .get_or_init(|| Mutex::new(HashMap::new()))
+        .lock()
+    else {
+        return;
+    };
+
+    let Some((idx, percent)) = find_low_plan(alert, usage) else {
+        // 余额恢复后清除冷却记录，下次跌破阈值时立即告警
+        last_alerts.remove(&key);
+        return;
+    };
+
+    let cooldown =
+        Duration::from_secs(alert.cooldown_minutes.unwrap_or(DEFAULT_COOLDOWN_MINUTES) * 60);
+    if let Some(last) = last_alerts.get(&key) {
+        if last.elapsed() < cooldown {
+            return;
+        }
+    }
+    last_alerts.insert(key.clone(), Instant::now());
+    drop(last_alerts);
+
+    let plan = &usage[idx];
+    let payload = BalanceLowPayload {
+        app_type: app_type.as_str().to_string(),
+        provider_id: provider_id.to_string(),
+        provider_name: provider_name.to_string(),
+        plan_name: plan.plan_name.clone(),
+        remaining: plan.remaining.unwrap_or_default(),
+        total: plan.total,
+        percent,
+        unit: plan.unit.clone(),
+    };
+
+    tracing::warn!(
+        "供应商 {} 余额过低: 剩余 {}{}",
+        provider_name,
+        payload.remaining,
+        payload.unit.as_deref().unwrap_or("")
+    );
+
+    if let Err(e) = app.emit(BALANCE_LOW_EVENT, &payload) {
+        tracing::error!("发射低余额事件失败: {}", e);
+    }
+    if let Ok(data) = serde_json::to_value(&payload) {
+        crate::webhook::notify(WebhookEvent::BalanceLow, &key, data);
+    }
+
+    if alert.notify.unwrap_or(true) {
+        let body = match percent {
+            Some(p) => format!(
+                "{} 剩余 {}{}（{:.1}%）",
+                provider_name,
+                payload.remaining,
+                payload.unit.as_deref().unwrap_or(""),
+                p
+            ),
+            None => format!(
+                "{} 剩余 {}{}",
+                provider_name,
+                payload.remaining,
+                payload.unit.as_deref().unwrap_or("")
+            ),
+        };
+        if let Err(e) = app
+            .notification()
+            .builder()
+            .title("CC Switch 余额不足")
+            .body(body)
+            .show()
+        {
+            tracing::warn!("发送系统通知失败: {}", e);
+        }
+    }
+}
@@ -0,0 +1,299 @@
+use reqwest::header::{HeaderMap, SET_COOKIE};
+use reqwest::{Client, Url};
+use ring::aead::{Aad, LessSafeKey, Nonce, UnboundKey, AES_256_GCM, NONCE_LEN};
+use ring::rand::{SecureRandom, SystemRandom};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::fs;
+use std::path::PathBuf;
+use std::sync::{Mutex, OnceLock};
+use std::time::Duration;
+
+use crate::config::{atomic_write, get_app_config_dir};
+use crate::provider::ClientCertificate;
+
+/// 用量脚本共享的 HTTP 客户端（复用连接池；重定向由调用方手动处理以便逐跳校验白名单）
+static SHARED_CLIENT: OnceLock<Client> = OnceLock::new();
+
+/// 已加载的 Cookie 罐（按供应商作用域）
+static COOKIE_JARS: OnceLock<Mutex<HashMap<String, CookieJar>>> = OnceLock::new();
+
+/// 已解析的 Cookie 加密密钥（避免每次读写都访问系统钥匙串）
+static COOKIE_KEY: OnceLock<LessSafeKey> = OnceLock::new();
+
+/// 获取共享 HTTP 客户端
+pub fn shared_client() -> Result<&'static Client, String> {
+    if let Some(client) = SHARED_CLIENT.get() {
+        return Ok(client);
+    }
+    let client = Client::builder()
+        .redirect(reqwest::redirect::Policy::none())
+        .pool_idle_timeout(Duration::from_secs(90))
+        .build()
+        .map_err(|e| format!("创建客户端失败: {}", e))?;
+    Ok(SHARED_CLIENT.get_or_init(|| client))
+}
+
+/// 获取用量脚本使用的客户端：配置了客户端证书时新建带证书的客户端（每次重新读取证书，
+/// 证书更新后无需重启），否则使用共享客户端
+pub fn client_for(client_cert: Option<&ClientCertificate>) -> Result<Client, String> {
+    let Some(cert) = client_cert else {
+        return shared_client().cloned();
+    };
+    let identity = reqwest::Identity::from_pem(&crate::client_cert::identity_pem(cert)?)
+        .map_err(|e| crate::i18n::t_args("errors.invalidClientCert", &[("error", &e)]))?;
+    Client::builder()
+        .redirect(reqwest::redirect::Policy::none())
+        .identity(identity)
+        .build()
+        .map_err(|e| format!("创建客户端失败: {}", e))
+}
+
+/// 简易 Cookie 罐：domain -> (name -> value)
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct CookieJar {
+    #[serde(default)]
+    domains: HashMap<String, HashMap<String, String>>,
+}
+
+impl CookieJar {
+    /// 生成请求 URL 对应的 Cookie 头
+    pub fn header_for(&self, url: &Url) -> Option<String> {
+        let host = url.host_str()?.to_lowercase();
+        let pairs: Vec<String> = self
+            .domains
+            .iter()
+            .filter(|(domain, _)| host == **domain || host.ends_with(&format!(".{}", domain)))
+            .flat_map(|(_, cookies)| cookies.iter().map(|(k, v)| format!("{}={}", k, v)))
+            .collect();
+        if pairs.is_empty() {
+            None
+        } else {
+            Some(pairs.join("; "))
+        }
+    }
+
+    /// 从响应头中的 Set-Cookie 更新 Cookie 罐，返回是否有变更
+    pub fn store_from_headers(&mut self, url: &Url, headers: &HeaderMap) -> bool {
+        let Some(host) = url.host_str().map(|h| h.to_lowercase()) else {
+            return false;
+        };
+        let mut changed = false;
+        for value in headers.get_all(SET_COOKIE).iter() {
+            let Ok(raw) = value.to_str() else {
+                continue;
+            };
+            let mut parts = raw.split(';');
+            let Some((name, val)) = parts.next().and_then(|p| p.split_once('=')) else {
+                continue;
+            };
+            let name = name.trim().to_string();
+            let val = val.trim().to_string();
+            if name.is_empty() {
+                continue;
+            }
+
+            let mut domain = host.clone();
+            let mut expired = false;
+            for attr in parts {
+                let (key, attr_val) = attr.split_once('=').unwrap_or((attr, ""));
+                match key.trim().to_lowercase().as_str() {
+                    "domain" => {
+                        let d = attr_val.trim().trim_start_matches('.').to_lowercase();
+                        // 仅接受当前主机或其父域，避免为其他站点设置 Cookie
+                        if !d.is_empty() && (host == d || host.ends_with(&format!(".{}", d))) {
+                            domain = d;
+                        }
+                    }
+                    "max-age" => {
+                        expired |= attr_val
+                            .trim()
+                            .parse::<i64>()
+                            .map(|v| v <= 0)
+                            .unwrap_or(false);
+                    }
+                    "expires" => {
+                        expired |= chrono::DateTime::parse_from_rfc2822(attr_val.trim())
+                            .map(|t| t < chrono::Utc::now())
+                            .unwrap_or(false);
+                    }
+                    _ => {}
+                }
+            }
+
+            let cookies = self.domains.entry(domain).or_default();
+            if expired {
+                changed |= cookies.remove(&name).is_some();
+            } else if cookies.get(&name) != Some(&val) {
+                cookies.insert(name, val);
+                changed = true;
+            }
+        }
+        self.domains.retain(|_, cookies| !cookies.is_empty());
+        changed
+    }
+}
+
+fn cookie_store_path() -> PathBuf {
+    get_app_config_dir().join("usage_cookies.enc")
+}
+
+fn cookie_key_path() -> PathBuf {
+    get_app_config_dir().join("usage_cookies.key")
+}
+
+/// 系统钥匙串中 Cookie 密钥的服务名与账户名
+const KEYRING_SERVICE: &str = "cc-switch";
+const KEYRING_ACCOUNT: &str = "usage-cookies-key";
+
+fn generate_key() -> Result<Vec<u8>, String> {
+    let mut key = vec![0u8; AES_256_GCM.key_len()];
+    SystemRandom::new()
+        .fill(&mut key)
+        .map_err(|_| "生成 Cookie 密钥失败".to_string())?;
+    Ok(key)
+}
+
+/// 从系统钥匙串读取（或首次生成并写入）密钥；旧版本保存在文件中的密钥迁移到钥匙串后删除文件
+fn keyring_key() -> keyring::Result<Vec<u8>> {
+    let entry = keyring::Entry::new(KEYRING_SERVICE, KEYRING_ACCOUNT)?;
+    match entry.get_secret() {
+        Ok(key) => return Ok(key),
+        Err(keyring::Error::NoEntry) => {}
+        Err(e) => return Err(e),
+    }
+    let path = cookie_key_path();
+    let key = match fs::read(&path) {
+        Ok(key) => key,
+        Err(_) => generate_key().map_err(|e| keyring::Error::PlatformFailure(e.into()))?,
+    };
+    entry.set_secret(&key)?;
+    if path.exists() {
+        if let Err(e) = fs::remove_file(&path) {
+            tracing::warn!("删除旧 Cookie 密钥文件失败: {}", e);
+        }
+    }
+    Ok(key)
+}
+
+/// 钥匙串不可用时（如无 Secret Service 的 Linux）退回到仅当前用户可读的密钥文件
+fn file_key() -> Result<Vec<u8>, String> {
+    use std::io::Write;
+
+    let path = cookie_key_path();
+    if path.exists() {
+        return fs::read(&path).map_err(|e| format!("读取 Cookie 密钥失败: {}", e));
+    }
+    let key = generate_key()?;
+    let mut options = fs::OpenOptions::new();
+    options.write(true).create_new(true);
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::OpenOptionsExt;
+        options.mode(0o600);
+    }
+    let mut file = options
+        .open(&path)
+        .map_err(|e| format!("创建 Cookie 密钥文件失败: {}", e))?;
+    file.write_all(&key)
+        .and_then(|_| file.sync_all())
+        .map_err(|e| format!("写入 Cookie 密钥失败: {}", e))?;
+    Ok(key)
+}
+
+/// 读取（或首次生成）Cookie 加密密钥：优先保存在系统钥匙串中。
+/// 钥匙串暂时不可用（如 Secret Service 未解锁）而已有加密的 Cookie 文件时报错，不生成新密钥，避免已保存的会话失效
+fn load_or_create_key() -> Result<&'static LessSafeKey, String> {
+    if let Some(key) = COOKIE_KEY.get() {
+        return Ok(key);
+    }
+    let bytes = match keyring_key() {
+        Ok(key) => key,
+        Err(e) if cookie_key_path().exists() || !cookie_store_path().exists() => {
+            tracing::warn!("系统钥匙串不可用，Cookie 密钥改存文件: {}", e);
+            file_key()?
+        }
+        Err(e) => {
+            tracing::warn!("读取系统钥匙串中的 Cookie 密钥失败: {}", e);
+            return Err(format!("读取系统钥匙串中的 Cookie 密钥失败: {}", e));
+        }
+    };
+    let unbound =
+        UnboundKey::new(&AES_256_GCM, &bytes).map_err(|_| "Cookie 密钥格式无效".to_string())?;
+    Ok(COOKIE_KEY.get_or_init(|| LessSafeKey::new(unbound)))
+}
+
+/// 从磁盘解密读取全部 Cookie 罐；文件不存在或无法解密时返回空集合
+fn read_all_jars() -> HashMap<String, CookieJar> {
+    let path = cookie_store_path();
+    if !path.exists() {
+        return HashMap::new();
+    }
+    let result = (|| -> Result<HashMap<String, CookieJar>, String> {
+        let key = load_or_create_key()?;
+        let mut data = fs::read(&path).map_err(|e| format!("读取 Cookie 文件失败: {}", e))?;
+        if data.len() < NONCE_LEN {
+            return Err("Cookie 文件已损坏".to_string());
+        }
+        let mut nonce = [0u8; NONCE_LEN];
+        nonce.copy_from_slice(&data[..NONCE_LEN]);
+        let plain = key
+            .open_in_place(
+                Nonce::assume_unique_for_key(nonce),
+                Aad::empty(),
+                &mut data[NONCE_LEN..],
+            )
+            .map_err(|_| "解密 Cookie 文件失败".to_string())?;
+        serde_json::from_slice(plain).map_err(|e| format!("解析 Cookie 文件失败: {}", e))
+    })();
+    result.unwrap_or_else(|e| {
+        tracing::warn!("{}，将使用空 Cookie 罐", e);
+        HashMap::new()
+    })
+}
+
+/// 加密写入全部 Cookie 罐
+fn write_all_jars(jars: &HashMap<String, CookieJar>) -> Result<(), String> {
+    let key = load_or_create_key()?;
+    let mut nonce = [0u8; NONCE_LEN];
+    SystemRandom::new()
+        .fill(&mut nonce)
+        .map_err(|_| "生成随机数失败".to_string())?;
+    let mut data = serde_json::to_vec(jars).map_err(|e| format!("序列化 Cookie 失败: {}", e))?;
+    key.seal_in_place_append_tag(Nonce::assume_unique_for_key(nonce), Aad::empty(), &mut data)
+        .map_err(|_| "加密 Cookie 失败".to_string())?;
+
+    let mut out = nonce.to_vec();
+    out.extend_from_slice(&data);
+    atomic_write(&cookie_store_path(), &out)
+}
+
+fn jars() -> &'static Mutex<HashMap<String, CookieJar>> {
+    COOKIE_JARS.get_or_init(|| Mutex::new(read_all_jars()))
+}
+
+/// 获取指定作用域（供应商）的 Cookie 罐副本
+pub fn load_jar(scope: &str) -> CookieJar {
+    jars()
+        .lock()
+        .ok()
+        .and_then(|map| map.get(scope).cloned())
+        .unwrap_or_default()
+}
+
+/// 保存指定作用域的 Cookie 罐并持久化
+pub fn save_jar(scope: &str, jar: CookieJar) -> Result<(), String> {
+    let mut map = jars().lock().map_err(|e| format!("获取锁失败: {}", e))?;
+    map.insert(scope.to_string(), jar);
+    write_all_jars(&map)
+}
+
+/// 清除指定作用域的 Cookie（例如需要重新登录时）
+pub fn clear_jar(scope: &str) -> Result<bool, String> {
+    let mut map = jars().lock().map_err(|e| format!("获取锁失败: {}", e))?;
+    let existed = map.remove(scope).is_some();
+    if existed {
+        write_all_jars(&map)?;
+    }
+    Ok(existed)
+}
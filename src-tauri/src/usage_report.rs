@@ -0,0 +1,140 @@
+//! 用量报表导出：把每日用量汇总按供应商导出为 CSV 或 JSON 文件，附带按供应商 `token_price`
+//! （每百万 token 价格）估算的费用，用于报销与团队内部分摊。未设置价格的供应商费用留空。
+
+use std::fs;
+
+use serde::{Deserialize, Serialize};
+use tauri::State;
+
+use crate::app_config::AppType;
+use crate::daily_usage::DailyUsage;
+use crate::error::CcSwitchError;
+use crate::i18n::t_args;
+use crate::store::AppState;
+
+/// 报表文件格式
+#[derive(Debug, Clone, Copy, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ReportFormat {
+    Csv,
+    Json,
+}
+
+/// 报表中的一行：某天某个供应商的用量与估算费用
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct UsageReportRow {
+    #[serde(flatten)]
+    pub usage: DailyUsage,
+    /// 每百万 token 价格（未设置时为空）
+    pub token_price: Option<f64>,
+    pub estimated_cost: Option<f64>,
+}
+
+/// 导出结果
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct UsageReportResult {
+    pub file_path: String,
+    pub rows: usize,
+    pub total_tokens: u64,
+    /// 已设置价格的供应商的估算费用合计
+    pub total_cost: f64,
+}
+
+/// CSV 字段转义：包含逗号、引号或换行时加引号
+fn csv_field(value: &str) -> String {
+    if value.contains([',', '"', '\n', '\r']) {
+        format!("\"{}\"", value.replace('"', "\"\""))
+    } else {
+        value.to_string()
+    }
+}
+
+fn to_csv(rows: &[UsageReportRow]) -> String {
+    let mut out = String::from(
+        "date,app_type,provider_id,provider_name,requests,failures,tokens,token_price,estimated_cost\n",
+    );
+    let optional = |value: Option<f64>| value.map(|v| v.to_string()).unwrap_or_default();
+    for row in rows {
+        let usage = &row.usage;
+        let fields = [
+            usage.date.clone(),
+            usage.app_type.clone(),
+            csv_field(&usage.provider_id),
+            csv_field(&usage.provider_name),
+            usage.requests.to_string(),
+            usage.failures.to_string(),
+            usage.tokens.to_string(),
+            optional(row.token_price),
+            optional(
+                row.estimated_cost
+                    .map(|cost| (cost * 10_000.0).round() / 10_000.0),
+            ),
+        ];
+        out.push_str(&fields.join(","));
+        out.push('\n');
+    }
+    out
+}
+
+/// 导出日期范围内（含首尾，YYYY-MM-DD）的用量报表到指定文件
+#[tauri::command]
+pub async fn export_usage_report(
+    state: State<'_, AppState>,
+    start: String,
+    end: String,
+    format: ReportFormat,
+    file_path: String,
+) -> Result<UsageReportResult, CcSwitchError> {
+    let summary = crate::daily_usage::query(&start, &end)?;
+
+    // 价格取自当前配置；已删除的供应商没有价格
+    let rows: Vec<UsageReportRow> = {
+        let config = state
+            .config
+            .lock()
+            .map_err(|e| t_args("errors.lockFailed", &[("error", &e)]))?;
+        summary
+            .into_iter()
+            .map(|usage| {
+                let app_type = AppType::from(usage.app_type.as_str());
+                let token_price = config
+                    .get_manager(&app_type)
+                    .and_then(|manager| manager.providers.get(&usage.provider_id))
+                    .and_then(|provider| provider.meta.as_ref()?.token_price);
+                let estimated_cost =
+                    token_price.map(|price| usage.tokens as f64 / 1_000_000.0 * price);
+                UsageReportRow {
+                    usage,
+                    token_price,
+                    estimated_cost,
+                }
+            })
+            .collect()
+    };
+
+    let content = match format {
+        ReportFormat::Csv => to_csv(&rows),
+        ReportFormat::Json => {
+            serde_json::to_string_pretty(&rows).map_err(|e| format!("序列化用量报表失败: {}", e))?
+        }
+    };
+    fs::write(&file_path, content).map_err(|e| {
+        CcSwitchError::io(
+            t_args(
+                "errors.writeFileFailed",
+                &[("path", &file_path), ("error", &e)],
+            ),
+            e,
+        )
+    })?;
+    tracing::info!("已导出用量报表（{} 行）: {}", rows.len(), file_path);
+
+    Ok(UsageReportResult {
+        file_path,
+        rows: rows.len(),
+        total_tokens: rows.iter().map(|row| row.usage.tokens).sum(),
+        total_cost: rows.iter().filter_map(|row| row.estimated_cost).sum(),
+    })
+}
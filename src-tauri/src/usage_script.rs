@@ -1,30 +1,253 @@
-use reqwest::Client;
+use reqwest::Url;
 use rquickjs::{Context, Runtime, Function};
+use serde::{Deserialize, Serialize};
 use serde_json::Value;
 use std::collections::HashMap;
-use std::time::Duration;
+use std::time::{Duration, Instant};
+
+use crate::provider::ClientCertificate;
+
+/// JS 运行时内存上限（字节）
+const JS_MEMORY_LIMIT: usize = 32 * 1024 * 1024;
+/// JS 运行时栈大小上限（字节）
+const JS_MAX_STACK_SIZE: usize = 1024 * 1024;
+
+/// 沙箱初始化脚本：移除可动态执行代码的全局对象
+const SANDBOX_PRELUDE: &str = r#"
+(function () {
+  const protos = [
+    Function.prototype,
+    Object.getPrototypeOf(async function () {}),
+    Object.getPrototypeOf(function* () {}),
+    Object.getPrototypeOf(async function* () {}),
+  ];
+  for (const proto of protos) {
+    Object.defineProperty(proto, "constructor", {
+      value: undefined,
+      writable: false,
+      configurable: false,
+    });
+  }
+  delete globalThis.eval;
+  delete globalThis.Function;
+})();
+"#;
+
+/// 创建受限的 JS 运行时与上下文：限制内存/栈大小，超过截止时间即中断执行
+pub(crate) fn create_sandbox(deadline: Instant) -> Result<(Runtime, Context), String> {
+    let runtime = Runtime::new().map_err(|e| format!("创建 JS 运行时失败: {}", e))?;
+    runtime.set_memory_limit(JS_MEMORY_LIMIT);
+    runtime.set_max_stack_size(JS_MAX_STACK_SIZE);
+    runtime.set_interrupt_handler(Some(Box::new(move || Instant::now() >= deadline)));
+
+    let context = Context::full(&runtime).map_err(|e| format!("创建 JS 上下文失败: {}", e))?;
+    context.with(|ctx| {
+        ctx.eval::<(), _>(SANDBOX_PRELUDE)
+            .map_err(|e| format!("初始化 JS 沙箱失败: {}", e))
+    })?;
+
+    Ok((runtime, context))
+}
 
 /// 执行用量查询脚本
+///
+/// `allowed_hosts` 为额外允许访问的主机（支持 `*.example.com` 通配），
+/// 未配置时仅允许访问与 baseUrl 同源的地址；`cookie_scope` 非空时在多次执行间保持 Cookie 会话；
+/// `client_cert` 为供应商的 mTLS 客户端证书
 pub async fn execute_usage_script(
     script_code: &str,
     api_key: &str,
     base_url: &str,
     timeout_secs: u64,
+    allowed_hosts: &[String],
+    cookie_scope: Option<&str>,
+    client_cert: Option<&ClientCertificate>,
 ) -> Result<Value, String> {
+    // 每个 JS 执行阶段的时间预算（HTTP 请求另有超时）
+    let script_budget = Duration::from_secs(timeout_secs);
+
+    // 1. 替换变量
+    let replaced = substitute_variables(script_code, api_key, base_url);
+
+    // 2. 提取 request 配置
+    let request = extract_request_config(&replaced, script_budget)?;
+
+    // 3. 校验目标地址并发送 HTTP 请求
+    let policy = UrlPolicy::new(base_url, allowed_hosts)?;
+    policy.check(&request.url)?;
+    let response =
+        send_http_request(&request, timeout_secs, &policy, cookie_scope, client_cert).await?;
+    if !response.status.is_success() {
+        return Err(format!(
+            "HTTP {} : {}",
+            response.status,
+            truncate_text(&response.body, 200)
+        ));
+    }
+
+    // 4. 执行 extractor
+    let result = run_extractor(&replaced, &response, script_budget)?;
+
+    // 5. 验证返回值格式
+    validate_result(&result)?;
+
+    Ok(result)
+}
+
+/// 调试追踪中响应体的最大保留长度
+const TRACE_BODY_LIMIT: usize = 2000;
+
+/// 用量脚本调试追踪：记录每个阶段的中间结果，便于定位失败环节
+#[derive(Debug, Clone, Default, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct UsageScriptTrace {
+    /// 是否全部阶段执行成功
+    pub success: bool,
+    /// 失败所在阶段：substitute / request / http / extractor / validate
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub failed_stage: Option<String>,
+    /// 失败原因
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+    /// 变量替换后的脚本（API Key 已打码）
+    pub substituted_script: String,
+    /// 解析出的 request 配置（headers/body 中的 API Key 已打码）
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub request: Option<Value>,
+    /// HTTP 状态码
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub http_status: Option<u16>,
+    /// 响应头
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub response_headers: Option<HashMap<String, String>>,
+    /// 响应体是否为 JSON（否则以原始文本传给 extractor）
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub response_is_json: Option<bool>,
+    /// 响应体（已截断）
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub response_body: Option<String>,
+    /// 请求耗时（毫秒）
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub elapsed_ms: Option<u128>,
+    /// extractor 原始返回值
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub extractor_output: Option<Value>,
+    /// 返回值格式校验错误
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub validation_error: Option<String>,
+}
+
+impl UsageScriptTrace {
+    fn fail(mut self, stage: &str, error: String) -> Self {
+        self.success = false;
+        self.failed_stage = Some(stage.to_string());
+        self.error = Some(error);
+        self
+    }
+}
+
+/// 以调试模式执行用量脚本，返回逐阶段的追踪信息（不会因某阶段失败而返回 Err）
+pub async fn trace_usage_script(
+    script_code: &str,
+    api_key: &str,
+    base_url: &str,
+    timeout_secs: u64,
+    allowed_hosts: &[String],
+    cookie_scope: Option<&str>,
+    client_cert: Option<&ClientCertificate>,
+) -> UsageScriptTrace {
+    let script_budget = Duration::from_secs(timeout_secs);
+    let masked_key = mask_secret(api_key);
+    let mask = |text: &str| {
+        if api_key.is_empty() {
+            text.to_string()
+        } else {
+            text.replace(api_key, &masked_key)
+        }
+    };
+
     // 1. 替换变量
-    let replaced = script_code
+    let replaced = substitute_variables(script_code, api_key, base_url);
+    let mut trace = UsageScriptTrace {
+        substituted_script: mask(&replaced),
+        ..Default::default()
+    };
+
+    // 2. 提取 request 配置
+    let request = match extract_request_config(&replaced, script_budget) {
+        Ok(r) => r,
+        Err(e) => return trace.fail("request", e),
+    };
+    trace.request = serde_json::to_value(&request)
+        .ok()
+        .map(|v| serde_json::from_str(&mask(&v.to_string())).unwrap_or(v));
+
+    // 3. 校验目标地址并发送 HTTP 请求
+    let policy = match UrlPolicy::new(base_url, allowed_hosts) {
+        Ok(p) => p,
+        Err(e) => return trace.fail("request", e),
+    };
+    if let Err(e) = policy.check(&request.url) {
+        return trace.fail("request", e);
+    }
+    let started = Instant::now();
+    let response = match send_http_request(
+        &request,
+        timeout_secs,
+        &policy,
+        cookie_scope,
+        client_cert,
+    )
+    .await
+    {
+        Ok(r) => r,
+        Err(e) => {
+            trace.elapsed_ms = Some(started.elapsed().as_millis());
+            return trace.fail("http", e);
+        }
+    };
+    trace.elapsed_ms = Some(started.elapsed().as_millis());
+    trace.http_status = Some(response.status.as_u16());
+    trace.response_headers = Some(response.headers.clone());
+    trace.response_is_json = Some(response.is_json());
+    trace.response_body = Some(mask(&truncate_text(&response.body, TRACE_BODY_LIMIT)));
+    if !response.status.is_success() {
+        return trace.fail("http", format!("HTTP {}", response.status));
+    }
+
+    // 4. 执行 extractor
+    let result = match run_extractor(&replaced, &response, script_budget) {
+        Ok(v) => v,
+        Err(e) => return trace.fail("extractor", e),
+    };
+    trace.extractor_output = Some(result.clone());
+
+    // 5. 验证返回值格式
+    if let Err(e) = validate_result(&result) {
+        trace.validation_error = Some(e.clone());
+        return trace.fail("validate", e);
+    }
+
+    trace.success = true;
+    trace
+}
+
+/// 替换脚本中的模板变量
+fn substitute_variables(script_code: &str, api_key: &str, base_url: &str) -> String {
+    script_code
         .replace("{{apiKey}}", api_key)
-        .replace("{{baseUrl}}", base_url);
+        .replace("{{baseUrl}}", base_url)
+}
 
-    // 2. 在独立作用域中提取 request 配置（确保 Runtime/Context 在 await 前释放）
+/// 在独立沙箱中执行脚本并提取 request 配置（确保 Runtime/Context 在 await 前释放）
+fn extract_request_config(replaced: &str, budget: Duration) -> Result<RequestConfig, String> {
     let request_config = {
-        let runtime = Runtime::new().map_err(|e| format!("创建 JS 运行时失败: {}", e))?;
-        let context = Context::full(&runtime).map_err(|e| format!("创建 JS 上下文失败: {}", e))?;
+        let (_runtime, context) = create_sandbox(Instant::now() + budget)?;
 
         context.with(|ctx| {
             // 执行用户代码，获取配置对象
             let config: rquickjs::Object = ctx
-                .eval(replaced.clone())
+                .eval(replaced.to_string())
                 .map_err(|e| format!("解析配置失败: {}", e))?;
 
             // 提取 request 配置
@@ -44,60 +267,91 @@ pub async fn execute_usage_script(
         })?
     }; // Runtime 和 Context 在这里被 drop
 
-    // 3. 解析 request 配置
-    let request: RequestConfig = serde_json::from_str(&request_config)
-        .map_err(|e| format!("request 配置格式错误: {}", e))?;
-
-    // 4. 发送 HTTP 请求
-    let response_data = send_http_request(&request, timeout_secs).await?;
-
-    // 5. 在独立作用域中执行 extractor（确保 Runtime/Context 在函数结束前释放）
-    let result: Value = {
-        let runtime = Runtime::new().map_err(|e| format!("创建 JS 运行时失败: {}", e))?;
-        let context = Context::full(&runtime).map_err(|e| format!("创建 JS 上下文失败: {}", e))?;
+    serde_json::from_str(&request_config).map_err(|e| format!("request 配置格式错误: {}", e))
+}
 
-        context.with(|ctx| {
-            // 重新 eval 获取配置对象
-            let config: rquickjs::Object = ctx
-                .eval(replaced.clone())
-                .map_err(|e| format!("重新解析配置失败: {}", e))?;
-
-            // 提取 extractor 函数
-            let extractor: Function = config
-                .get("extractor")
-                .map_err(|e| format!("缺少 extractor 函数: {}", e))?;
-
-            // 将响应数据转换为 JS 值
-            let response_js: rquickjs::Value = ctx
-                .json_parse(response_data.as_str())
-                .map_err(|e| format!("解析响应 JSON 失败: {}", e))?;
-
-            // 调用 extractor(response)
-            let result_js: rquickjs::Value = extractor
-                .call((response_js,))
-                .map_err(|e| format!("执行 extractor 失败: {}", e))?;
-
-            // 转换为 JSON 字符串
-            let result_json: String = ctx
-                .json_stringify(result_js)
-                .map_err(|e| format!("序列化结果失败: {}", e))?
-                .ok_or("序列化返回 None")?
-                .get()
-                .map_err(|e| format!("获取字符串失败: {}", e))?;
+/// 在独立沙箱中执行 extractor（确保 Runtime/Context 在函数结束前释放）
+///
+/// 调用形式为 `extractor(response, meta)`：JSON 响应会被解析为对象，
+/// 其他响应（HTML/纯文本）以原始字符串传入；`meta` 包含 status、headers 与原始 body
+fn run_extractor(
+    replaced: &str,
+    response: &HttpResponse,
+    budget: Duration,
+) -> Result<Value, String> {
+    let (_runtime, context) = create_sandbox(Instant::now() + budget)?;
+
+    context.with(|ctx| {
+        // 重新 eval 获取配置对象
+        let config: rquickjs::Object = ctx
+            .eval(replaced.to_string())
+            .map_err(|e| format!("重新解析配置失败: {}", e))?;
+
+        // 提取 extractor 函数
+        let extractor: Function = config
+            .get("extractor")
+            .map_err(|e| format!("缺少 extractor 函数: {}", e))?;
+
+        // 将响应数据转换为 JS 值（非 JSON 响应以原始字符串传入）
+        let response_js: rquickjs::Value = if response.is_json() {
+            ctx.json_parse(response.body.as_str())
+                .map_err(|e| format!("解析响应 JSON 失败: {}", e))?
+        } else {
+            rquickjs::String::from_str(ctx.clone(), &response.body)
+                .map_err(|e| format!("转换响应文本失败: {}", e))?
+                .into_value()
+        };
 
-            // 解析为 serde_json::Value
-            serde_json::from_str(&result_json).map_err(|e| format!("JSON 解析失败: {}", e))
-        })?
-    }; // Runtime 和 Context 在这里被 drop
+        // 构造 meta：{ status, headers, body }
+        let meta_json = serde_json::json!({
+            "status": response.status.as_u16(),
+            "headers": response.headers,
+            "body": response.body,
+        })
+        .to_string();
+        let meta_js: rquickjs::Value = ctx
+            .json_parse(meta_json)
+            .map_err(|e| format!("构造响应元信息失败: {}", e))?;
+
+        // 调用 extractor(response, meta)
+        let result_js: rquickjs::Value = extractor
+            .call((response_js, meta_js))
+            .map_err(|e| format!("执行 extractor 失败: {}", e))?;
+
+        // 转换为 JSON 字符串
+        let result_json: String = ctx
+            .json_stringify(result_js)
+            .map_err(|e| format!("序列化结果失败: {}", e))?
+            .ok_or("序列化返回 None")?
+            .get()
+            .map_err(|e| format!("获取字符串失败: {}", e))?;
+
+        // 解析为 serde_json::Value
+        serde_json::from_str(&result_json).map_err(|e| format!("JSON 解析失败: {}", e))
+    })
+}
 
-    // 6. 验证返回值格式
-    validate_result(&result)?;
+/// 按字符截断文本，超出部分以省略号表示
+fn truncate_text(text: &str, max_chars: usize) -> String {
+    if text.chars().count() > max_chars {
+        format!("{}...", text.chars().take(max_chars).collect::<String>())
+    } else {
+        text.to_string()
+    }
+}
 
-    Ok(result)
+/// 密钥打码：仅保留末 4 位
+pub(crate) fn mask_secret(secret: &str) -> String {
+    let chars: Vec<char> = secret.chars().collect();
+    if chars.len() <= 4 {
+        return "****".to_string();
+    }
+    let tail: String = chars[chars.len() - 4..].iter().collect();
+    format!("****{}", tail)
 }
 
 /// 请求配置结构
-#[derive(Debug, serde::Deserialize)]
+#[derive(Debug, Serialize, Deserialize)]
 struct RequestConfig {
     url: String,
     method: String,
@@ -107,52 +361,213 @@ struct RequestConfig {
     body: Option<String>,
 }
 
-/// 发送 HTTP 请求
-async fn send_http_request(config: &RequestConfig, timeout_secs: u64) -> Result<String, String> {
-    let client = Client::builder()
-        .timeout(Duration::from_secs(timeout_secs))
-        .build()
-        .map_err(|e| format!("创建客户端失败: {}", e))?;
+/// 出站地址白名单：默认仅允许与 baseUrl 同源，可额外放行指定主机
+#[derive(Debug, Clone)]
+struct UrlPolicy {
+    origin: Option<Url>,
+    allowed_hosts: Vec<String>,
+}
+
+impl UrlPolicy {
+    fn new(base_url: &str, allowed_hosts: &[String]) -> Result<Self, String> {
+        let origin = if base_url.trim().is_empty() {
+            None
+        } else {
+            Some(Url::parse(base_url.trim()).map_err(|e| format!("baseUrl 无效: {}", e))?)
+        };
+        let allowed_hosts = allowed_hosts
+            .iter()
+            .map(|h| h.trim().to_lowercase())
+            .filter(|h| !h.is_empty())
+            .collect();
+        Ok(Self {
+            origin,
+            allowed_hosts,
+        })
+    }
+
+    fn is_allowed(&self, url: &Url) -> bool {
+        if !matches!(url.scheme(), "http" | "https") {
+            return false;
+        }
+        if let Some(origin) = &self.origin {
+            if origin.origin() == url.origin() {
+                return true;
+            }
+        }
+        let Some(host) = url.host_str().map(|h| h.to_lowercase()) else {
+            return false;
+        };
+        self.allowed_hosts.iter().any(|pattern| {
+            if let Some(suffix) = pattern.strip_prefix("*.") {
+                host == suffix || host.ends_with(&format!(".{}", suffix))
+            } else {
+                host == *pattern
+            }
+        })
+    }
+
+    fn check(&self, raw_url: &str) -> Result<(), String> {
+        let url = Url::parse(raw_url).map_err(|e| format!("请求 URL 无效: {}", e))?;
+        if self.is_allowed(&url) {
+            Ok(())
+        } else {
+            Err(format!(
+                "请求地址不在白名单内: {}（默认仅允许与 baseUrl 同源）",
+                url.host_str().unwrap_or(raw_url)
+            ))
+        }
+    }
+}
+
+/// HTTP 响应（状态码、响应头与原始文本）
+#[derive(Debug)]
+struct HttpResponse {
+    status: reqwest::StatusCode,
+    headers: HashMap<String, String>,
+    body: String,
+}
 
-    let method = config
+impl HttpResponse {
+    /// 判断响应体是否为 JSON：优先依据 Content-Type，其次尝试解析
+    fn is_json(&self) -> bool {
+        let declared = self
+            .headers
+            .get("content-type")
+            .map(|ct| ct.to_lowercase().contains("json"))
+            .unwrap_or(false);
+        if declared {
+            return serde_json::from_str::<Value>(&self.body).is_ok();
+        }
+        let trimmed = self.body.trim_start();
+        (trimmed.starts_with('{') || trimmed.starts_with('['))
+            && serde_json::from_str::<Value>(&self.body).is_ok()
+    }
+}
+
+/// 最大重定向跳数
+const MAX_REDIRECTS: usize = 5;
+
+/// 发送 HTTP 请求
+///
+/// 使用共享客户端复用连接；重定向逐跳校验白名单。
+/// 传入 `cookie_scope` 时会携带并持久化该作用域的 Cookie，用于保持登录会话；
+/// 传入 `client_cert` 时在 TLS 握手中出示客户端证书
+async fn send_http_request(
+    config: &RequestConfig,
+    timeout_secs: u64,
+    policy: &UrlPolicy,
+    cookie_scope: Option<&str>,
+    client_cert: Option<&ClientCertificate>,
+) -> Result<HttpResponse, String> {
+    let client = crate::usage_client::client_for(client_cert)?;
+    let mut jar = cookie_scope.map(crate::usage_client::load_jar);
+    let mut jar_changed = false;
+    let has_cookie_header = config
+        .headers
+        .keys()
+        .any(|k| k.eq_ignore_ascii_case("cookie"));
+
+    let mut method = config
         .method
         .parse()
         .unwrap_or(reqwest::Method::GET);
+    let mut url = Url::parse(&config.url).map_err(|e| format!("请求 URL 无效: {}", e))?;
+    let mut body = config.body.clone();
+
+    let mut hops = 0;
+    let resp = loop {
+        let mut req = client
+            .request(method.clone(), url.clone())
+            .timeout(Duration::from_secs(timeout_secs));
+
+        // 添加请求头
+        for (k, v) in &config.headers {
+            req = req.header(k, v);
+        }
 
-    let mut req = client.request(method.clone(), &config.url);
+        // 携带已保存的 Cookie（脚本显式设置 Cookie 头时不覆盖）
+        if !has_cookie_header {
+            if let Some(cookie) = jar.as_ref().and_then(|j| j.header_for(&url)) {
+                req = req.header(reqwest::header::COOKIE, cookie);
+            }
+        }
 
-    // 添加请求头
-    for (k, v) in &config.headers {
-        req = req.header(k, v);
-    }
+        // 添加请求体
+        if let Some(body) = &body {
+            req = req.body(body.clone());
+        }
 
-    // 添加请求体
-    if let Some(body) = &config.body {
-        req = req.body(body.clone());
-    }
+        // 发送请求
+        let resp = req
+            .send()
+            .await
+            .map_err(|e| format!("请求失败: {}", e))?;
 
-    // 发送请求
-    let resp = req
-        .send()
-        .await
-        .map_err(|e| format!("请求失败: {}", e))?;
+        if let Some(jar) = jar.as_mut() {
+            jar_changed |= jar.store_from_headers(&url, resp.headers());
+        }
+
+        // 处理重定向（同样受白名单约束，避免被跳转到任意主机）
+        let location = resp
+            .headers()
+            .get(reqwest::header::LOCATION)
+            .and_then(|v| v.to_str().ok());
+        match location {
+            Some(location) if resp.status().is_redirection() => {
+                hops += 1;
+                if hops > MAX_REDIRECTS {
+                    return Err("重定向次数过多".to_string());
+                }
+                let next = url
+                    .join(location)
+                    .map_err(|e| format!("重定向地址无效: {}", e))?;
+                if !policy.is_allowed(&next) {
+                    return Err(format!(
+                        "重定向目标不在白名单内: {}",
+                        next.host_str().unwrap_or(location)
+                    ));
+                }
+                // 303 以及 POST 上的 301/302 按浏览器惯例改为 GET
+                let status = resp.status().as_u16();
+                if status == 303 || ((status == 301 || status == 302) && method == reqwest::Method::POST) {
+                    method = reqwest::Method::GET;
+                    body = None;
+                }
+                url = next;
+            }
+            _ => break resp,
+        }
+    };
+
+    if let (Some(scope), Some(jar)) = (cookie_scope, jar) {
+        if jar_changed {
+            if let Err(e) = crate::usage_client::save_jar(scope, jar) {
+                tracing::warn!("保存用量脚本 Cookie 失败: {}", e);
+            }
+        }
+    }
 
     let status = resp.status();
+    let headers = resp
+        .headers()
+        .iter()
+        .filter_map(|(k, v)| {
+            v.to_str()
+                .ok()
+                .map(|v| (k.as_str().to_lowercase(), v.to_string()))
+        })
+        .collect();
     let text = resp
         .text()
         .await
         .map_err(|e| format!("读取响应失败: {}", e))?;
 
-    if !status.is_success() {
-        let preview = if text.len() > 200 {
-            format!("{}...", &text[..200])
-        } else {
-            text.clone()
-        };
-        return Err(format!("HTTP {} : {}", status, preview));
-    }
-
-    Ok(text)
+    Ok(HttpResponse {
+        status,
+        headers,
+        body: text,
+    })
 }
 
 /// 验证脚本返回值（支持单对象或数组）
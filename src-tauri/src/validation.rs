@@ -0,0 +1,207 @@
+use serde::Serialize;
+use serde_json::{Map, Value};
+
+use crate::app_config::AppType;
+use crate::error::CcSwitchError;
+use crate::i18n::t_args;
+
+/// 字段级校验错误
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct FieldError {
+    /// 以点分隔的字段路径，如 `env.ANTHROPIC_AUTH_TOKEN`、`config.model_providers.x.base_url`
+    pub field: String,
+    pub message: String,
+}
+
+impl FieldError {
+    fn new(field: impl Into<String>, message: impl Into<String>) -> Self {
+        Self {
+            field: field.into(),
+            message: message.into(),
+        }
+    }
+}
+
+/// 校验 URL：必须可解析且为 http/https
+fn check_url(field: &str, value: &str, errors: &mut Vec<FieldError>) {
+    match url::Url::parse(value.trim()) {
+        Ok(url) if matches!(url.scheme(), "http" | "https") && url.host_str().is_some() => {}
+        Ok(_) => errors.push(FieldError::new(field, "仅支持 http/https 地址")),
+        Err(e) => errors.push(FieldError::new(field, format!("URL 格式错误: {}", e))),
+    }
+}
+
+/// 校验字段存在且为非空字符串
+fn check_required_string(
+    obj: &Map<String, Value>,
+    key: &str,
+    field: &str,
+    errors: &mut Vec<FieldError>,
+) {
+    match obj.get(key) {
+        None | Some(Value::Null) => errors.push(FieldError::new(field, "缺少必填字段")),
+        Some(Value::String(s)) if s.trim().is_empty() => {
+            errors.push(FieldError::new(field, "不能为空"))
+        }
+        Some(Value::String(_)) => {}
+        Some(_) => errors.push(FieldError::new(field, "必须是字符串")),
+    }
+}
+
+fn validate_claude(settings: &Value, errors: &mut Vec<FieldError>) {
+    let Some(root) = settings.as_object() else {
+        errors.push(FieldError::new("", "Claude 配置必须是 JSON 对象"));
+        return;
+    };
+
+    let env = match root.get("env") {
+        None => return,
+        Some(Value::Object(env)) => env,
+        Some(_) => {
+            errors.push(FieldError::new("env", "env 必须是 JSON 对象"));
+            return;
+        }
+    };
+
+    for (key, value) in env {
+        if value.is_object() || value.is_array() {
+            errors.push(FieldError::new(
+                format!("env.{}", key),
+                "环境变量值必须是字符串",
+            ));
+        }
+    }
+
+    // 仅第三方供应商（配置了 ANTHROPIC_BASE_URL）需要显式凭证；官方登录的 env 可以为空
+    if let Some(base_url) = env.get("ANTHROPIC_BASE_URL") {
+        match base_url.as_str() {
+            Some(url) if !url.trim().is_empty() => check_url("env.ANTHROPIC_BASE_URL", url, errors),
+            _ => errors.push(FieldError::new("env.ANTHROPIC_BASE_URL", "不能为空")),
+        }
+        let has_api_key = env
+            .get("ANTHROPIC_API_KEY")
+            .and_then(|v| v.as_str())
+            .is_some_and(|s| !s.trim().is_empty());
+        if !has_api_key {
+            check_required_string(
+                env,
+                "ANTHROPIC_AUTH_TOKEN",
+                "env.ANTHROPIC_AUTH_TOKEN",
+                errors,
+            );
+        }
+    }
+}
+
+fn validate_codex(settings: &Value, errors: &mut Vec<FieldError>) {
+    let Some(root) = settings.as_object() else {
+        errors.push(FieldError::new("", "Codex 配置必须是 JSON 对象"));
+        return;
+    };
+
+    let auth = match root.get("auth") {
+        Some(Value::Object(auth)) => Some(auth),
+        Some(_) => {
+            errors.push(FieldError::new("auth", "auth 必须是 JSON 对象"));
+            None
+        }
+        None => {
+            errors.push(FieldError::new("auth", "缺少 auth 字段"));
+            None
+        }
+    };
+
+    let config_text = match root.get("config") {
+        None | Some(Value::Null) => return,
+        Some(Value::String(text)) => text,
+        Some(_) => {
+            errors.push(FieldError::new("config", "config 必须是字符串"));
+            return;
+        }
+    };
+    if config_text.trim().is_empty() {
+        return;
+    }
+
+    let table = match toml::from_str::<toml::Table>(config_text) {
+        Ok(table) => table,
+        Err(e) => {
+            errors.push(FieldError::new(
+                "config",
+                format!("config.toml 语法错误: {}", e),
+            ));
+            return;
+        }
+    };
+
+    // 第三方供应商：model_provider 必须指向存在且带 base_url 的 [model_providers.<name>]
+    let Some(provider_name) = table.get("model_provider") else {
+        return;
+    };
+    let Some(provider_name) = provider_name.as_str() else {
+        errors.push(FieldError::new("config.model_provider", "必须是字符串"));
+        return;
+    };
+    let section_field = format!("config.model_providers.{}", provider_name);
+    let Some(section) = table
+        .get("model_providers")
+        .and_then(|v| v.get(provider_name))
+        .and_then(|v| v.as_table())
+    else {
+        errors.push(FieldError::new(
+            section_field,
+            format!("缺少 [model_providers.{}] 配置", provider_name),
+        ));
+        return;
+    };
+
+    let base_url_field = format!("{}.base_url", section_field);
+    match section.get("base_url").and_then(|v| v.as_str()) {
+        Some(url) if !url.trim().is_empty() => check_url(&base_url_field, url, errors),
+        _ => errors.push(FieldError::new(base_url_field, "缺少 base_url 配置")),
+    }
+
+    if let Some(auth) = auth {
+        check_required_string(auth, "OPENAI_API_KEY", "auth.OPENAI_API_KEY", errors);
+    }
+}
+
+/// 校验供应商 settings_config，返回全部字段级错误（为空表示通过）
+pub fn validate_settings_config(app_type: &AppType, settings: &Value) -> Vec<FieldError> {
+    // 先按 JSON Schema 校验结构，结构不符时不再做语义校验，避免重复报错
+    let mut errors = crate::schema::validate_against_schema(app_type, settings);
+    if !errors.is_empty() {
+        return errors;
+    }
+    match app_type {
+        AppType::Claude => validate_claude(settings, &mut errors),
+        AppType::Codex => validate_codex(settings, &mut errors),
+    }
+    errors
+}
+
+/// 校验失败时返回汇总的错误信息，并附带字段级详情
+pub fn ensure_valid(app_type: &AppType, settings: &Value) -> Result<(), CcSwitchError> {
+    let errors = validate_settings_config(app_type, settings);
+    if errors.is_empty() {
+        return Ok(());
+    }
+    let details: Vec<String> = errors
+        .iter()
+        .map(|e| {
+            if e.field.is_empty() {
+                e.message.clone()
+            } else {
+                format!("{}: {}", e.field, e.message)
+            }
+        })
+        .collect();
+    Err(CcSwitchError::Validation {
+        message: t_args(
+            "errors.validationFailed",
+            &[("details", &details.join("; "))],
+        ),
+        fields: errors,
+    })
+}
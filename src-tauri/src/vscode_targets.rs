@@ -0,0 +1,165 @@
+//! VS Code 扩展联动：切换供应商时，将其 Base URL 与 API Key 一并写入 VS Code 用户 settings.json 中
+//! Cline / Roo Code 的配置项（按供应商 meta.apply_targets 选择），使多个 AI 编码工具同时切换。
+//! Claude 供应商写入 Anthropic 相关键，Codex 供应商写入 OpenAI 兼容相关键；其余设置保持不变。
+
+use serde_json::{Map, Value};
+use std::path::PathBuf;
+
+use crate::app_config::{AppType, MultiAppConfig};
+use crate::provider::{ApplyTarget, Provider};
+
+/// settings.json 中的配置项前缀
+fn prefix(target: ApplyTarget) -> &'static str {
+    match target {
+        ApplyTarget::Cline => "cline",
+        ApplyTarget::RooCode => "roo-cline",
+    }
+}
+
+/// VS Code 用户 settings.json 路径（可在设置中覆盖，如 Cursor、VSCodium）
+pub fn settings_path() -> PathBuf {
+    if let Some(path) = crate::settings::get_vscode_settings_override() {
+        return path;
+    }
+    dirs::config_dir()
+        .unwrap_or_else(|| dirs::home_dir().unwrap_or_default().join(".config"))
+        .join("Code")
+        .join("User")
+        .join("settings.json")
+}
+
+/// 去掉 JSONC 中的注释与尾逗号（VS Code 的 settings.json 允许二者）
+fn strip_jsonc(text: &str) -> String {
+    let chars: Vec<char> = text.chars().collect();
+    let mut out = String::with_capacity(text.len());
+    let mut i = 0;
+    let mut in_string = false;
+    while i < chars.len() {
+        let c = chars[i];
+        if in_string {
+            out.push(c);
+            if c == '\\' && i + 1 < chars.len() {
+                out.push(chars[i + 1]);
+                i += 1;
+            } else if c == '"' {
+                in_string = false;
+            }
+            i += 1;
+            continue;
+        }
+        match (c, chars.get(i + 1)) {
+            ('"', _) => {
+                in_string = true;
+                out.push(c);
+            }
+            ('/', Some('/')) => {
+                while i < chars.len() && chars[i] != '\n' {
+                    i += 1;
+                }
+                continue;
+            }
+            ('/', Some('*')) => {
+                i += 2;
+                while i < chars.len() && !(chars[i] == '*' && chars.get(i + 1) == Some(&'/')) {
+                    i += 1;
+                }
+                i += 2;
+                continue;
+            }
+            (',', _) => {
+                let next = chars[i + 1..].iter().find(|c| !c.is_whitespace());
+                if !matches!(next, Some('}') | Some(']')) {
+                    out.push(c);
+                }
+            }
+            _ => out.push(c),
+        }
+        i += 1;
+    }
+    out
+}
+
+/// 读取 settings.json；含注释或尾逗号时先归档原文件，写回后注释将丢失
+fn read_settings(path: &std::path::Path) -> Result<Map<String, Value>, String> {
+    if !path.exists() {
+        return Ok(Map::new());
+    }
+    let text = std::fs::read_to_string(path)
+        .map_err(|e| format!("读取 VS Code 配置失败: {}: {}", path.display(), e))?;
+    if text.trim().is_empty() {
+        return Ok(Map::new());
+    }
+    let value = match serde_json::from_str::<Value>(&text) {
+        Ok(value) => value,
+        Err(_) => {
+            let value = serde_json::from_str::<Value>(&strip_jsonc(&text))
+                .map_err(|e| format!("解析 VS Code 配置失败: {}: {}", path.display(), e))?;
+            let ts = chrono::Utc::now().timestamp() as u64;
+            crate::config::archive_file(ts, "vscode", path)?;
+            tracing::info!("VS Code 配置含注释，已归档原文件后写入: {}", path.display());
+            value
+        }
+    };
+    match value {
+        Value::Object(map) => Ok(map),
+        _ => Err(format!("VS Code 配置不是 JSON 对象: {}", path.display())),
+    }
+}
+
+/// 供应商在目标扩展中对应的配置项
+fn target_entries(
+    target: ApplyTarget,
+    app_type: &AppType,
+    api_key: &str,
+    base_url: &str,
+) -> Vec<(String, Value)> {
+    let key = |name: &str| format!("{}.{}", prefix(target), name);
+    let (provider, url_key, key_key) = match app_type {
+        AppType::Claude => ("anthropic", "anthropicBaseUrl", "apiKey"),
+        AppType::Codex => ("openai", "openAiBaseUrl", "openAiApiKey"),
+    };
+    vec![
+        (key("apiProvider"), Value::String(provider.to_string())),
+        (key(url_key), Value::String(base_url.to_string())),
+        (key(key_key), Value::String(api_key.to_string())),
+    ]
+}
+
+/// 将供应商写入其勾选的扩展配置；未勾选任何扩展时不做处理
+pub fn apply(provider: &Provider, app_type: &AppType) -> Result<(), String> {
+    let targets = provider
+        .meta
+        .as_ref()
+        .map(|meta| meta.apply_targets.as_slice())
+        .unwrap_or_default();
+    if targets.is_empty() {
+        return Ok(());
+    }
+    let (api_key, base_url) = crate::commands::extract_credentials(provider, app_type)?;
+    let path = settings_path();
+    let mut settings = read_settings(&path)?;
+    let before = settings.clone();
+    for target in targets {
+        for (key, value) in target_entries(*target, app_type, &api_key, &base_url) {
+            settings.insert(key, value);
+        }
+    }
+    if settings != before {
+        crate::config::write_json_file(&path, &Value::Object(settings))?;
+        tracing::info!("已将供应商 {} 写入 VS Code 扩展配置", provider.name);
+    }
+    Ok(())
+}
+
+/// 切换成功后写入当前供应商的扩展配置；失败只记录日志，不影响切换结果
+pub fn apply_current(config: &MultiAppConfig, app_type: &AppType) {
+    let Some(provider) = config
+        .get_manager(app_type)
+        .and_then(|manager| manager.providers.get(&manager.current))
+    else {
+        return;
+    };
+    if let Err(e) = apply(provider, app_type) {
+        tracing::warn!("写入 VS Code 扩展配置失败: {}", e);
+    }
+}
@@ -0,0 +1,215 @@
+//! Webhook 通知：在所有代理供应商失败、发生故障转移、余额低于阈值或切换供应商时，
+//! 向设置中的地址 POST JSON，便于团队把告警接入 Slack / 飞书 / 钉钉等。
+//! 通用格式附带 `X-CC-Switch-Signature`（HMAC-SHA256）签名；飞书、钉钉按各自的加签规则签名。
+//! 发送在后台进行，失败只记录日志；同类事件在冷却期内只发送一次，避免代理故障时刷屏。
+
+use std::collections::HashMap;
+use std::sync::{Mutex, OnceLock};
+use std::time::{Duration, Instant};
+
+use base64::engine::general_purpose::STANDARD;
+use base64::Engine;
+use ring::hmac;
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Value};
+
+use crate::error::CcSwitchError;
+use crate::i18n::{t, t_args};
+use crate::settings::WebhookSettings;
+
+const SEND_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// 同一事件（同一对象）的最短发送间隔
+const EVENT_COOLDOWN: Duration = Duration::from_secs(60);
+
+const SIGNATURE_HEADER: &str = "X-CC-Switch-Signature";
+
+static LAST_SENT: OnceLock<Mutex<HashMap<String, Instant>>> = OnceLock::new();
+
+/// Webhook 事件类型
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum WebhookEvent {
+    AllProvidersFailed,
+    Failover,
+    BalanceLow,
+    ProviderSwitched,
+    /// 设置界面中的测试消息
+    Test,
+}
+
+/// 消息格式（决定请求体结构与签名方式）
+#[derive(Debug, Clone, Copy, Default, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum WebhookFormat {
+    /// `{ event, timestamp, text, data }`
+    #[default]
+    Json,
+    Slack,
+    Feishu,
+    Dingtalk,
+}
+
+fn hmac_sha256(key: &[u8], message: &[u8]) -> Vec<u8> {
+    let key = hmac::Key::new(hmac::HMAC_SHA256, key);
+    hmac::sign(&key, message).as_ref().to_vec()
+}
+
+fn to_hex(bytes: &[u8]) -> String {
+    bytes.iter().map(|b| format!("{:02x}", b)).collect()
+}
+
+/// 按事件生成一行可读摘要（各平台消息正文）
+fn summary(event: WebhookEvent, data: &Value) -> String {
+    let field = |key: &str| data.get(key).and_then(|v| v.as_str()).unwrap_or_default();
+    match event {
+        WebhookEvent::AllProvidersFailed => {
+            t_args("webhook.allProvidersFailed", &[("app", &field("appType"))])
+        }
+        WebhookEvent::Failover => t_args(
+            "webhook.failover",
+            &[("app", &field("appType")), ("provider", &field("provider"))],
+        ),
+        WebhookEvent::BalanceLow => t_args(
+            "webhook.balanceLow",
+            &[
+                ("provider", &field("providerName")),
+                (
+                    "remaining",
+                    &data.get("remaining").cloned().unwrap_or_default(),
+                ),
+            ],
+        ),
+        WebhookEvent::ProviderSwitched => t_args(
+            "webhook.providerSwitched",
+            &[("app", &field("appType")), ("provider", &field("provider"))],
+        ),
+        WebhookEvent::Test => t("webhook.test"),
+    }
+}
+
+/// 待发送的请求：地址、请求体与额外请求头
+struct WebhookRequest {
+    url: reqwest::Url,
+    body: Value,
+    headers: Vec<(&'static str, String)>,
+}
+
+/// 按消息格式构建请求并签名
+fn build_request(
+    settings: &WebhookSettings,
+    event: WebhookEvent,
+    data: Value,
+) -> Result<WebhookRequest, String> {
+    let mut url = reqwest::Url::parse(settings.url.trim())
+        .map_err(|e| t_args("errors.webhookUrlInvalid", &[("error", &e)]))?;
+    let secret = settings.secret.as_deref().filter(|s| !s.is_empty());
+    let text = format!("[CC Switch] {}", summary(event, &data));
+    let now = chrono::Utc::now();
+    let mut headers = Vec::new();
+
+    let body = match settings.format {
+        WebhookFormat::Json => {
+            let body = json!({
+                "event": event,
+                "timestamp": now.timestamp_millis(),
+                "text": text,
+                "data": data,
+            });
+            if let Some(secret) = secret {
+                let signature = hmac_sha256(secret.as_bytes(), body.to_string().as_bytes());
+                headers.push((SIGNATURE_HEADER, format!("sha256={}", to_hex(&signature))));
+            }
+            body
+        }
+        WebhookFormat::Slack => json!({ "text": text }),
+        WebhookFormat::Feishu => {
+            let mut body = json!({ "msg_type": "text", "content": { "text": text } });
+            if let Some(secret) = secret {
+                // 飞书：以 "timestamp\nsecret" 为密钥对空串签名（秒级时间戳）
+                let timestamp = now.timestamp().to_string();
+                let key = format!("{}\n{}", timestamp, secret);
+                body["timestamp"] = json!(timestamp);
+                body["sign"] = json!(STANDARD.encode(hmac_sha256(key.as_bytes(), b"")));
+            }
+            body
+        }
+        WebhookFormat::Dingtalk => {
+            if let Some(secret) = secret {
+                // 钉钉：对 "timestamp\nsecret" 签名，并以查询参数附加（毫秒级时间戳）
+                let timestamp = now.timestamp_millis().to_string();
+                let message = format!("{}\n{}", timestamp, secret);
+                let sign = STANDARD.encode(hmac_sha256(secret.as_bytes(), message.as_bytes()));
+                url.query_pairs_mut()
+                    .append_pair("timestamp", &timestamp)
+                    .append_pair("sign", &sign);
+            }
+            json!({ "msgtype": "text", "text": { "content": text } })
+        }
+    };
+    Ok(WebhookRequest { url, body, headers })
+}
+
+async fn send(settings: &WebhookSettings, event: WebhookEvent, data: Value) -> Result<(), String> {
+    let WebhookRequest { url, body, headers } = build_request(settings, event, data)?;
+    let client = crate::usage_client::shared_client()?;
+    let mut request = client.post(url).timeout(SEND_TIMEOUT).json(&body);
+    for (name, value) in headers {
+        request = request.header(name, value);
+    }
+    let response = request
+        .send()
+        .await
+        .map_err(|e| t_args("errors.webhookSendFailed", &[("error", &e)]))?;
+    let status = response.status();
+    if !status.is_success() {
+        return Err(t_args("errors.webhookSendFailed", &[("error", &status)]));
+    }
+    Ok(())
+}
+
+/// 冷却期内同一 key 的事件不重复发送
+fn in_cooldown(key: String) -> bool {
+    let Ok(mut last_sent) = LAST_SENT.get_or_init(|| Mutex::new(HashMap::new())).lock() else {
+        return false;
+    };
+    if last_sent
+        .get(&key)
+        .is_some_and(|last| last.elapsed() < EVENT_COOLDOWN)
+    {
+        return true;
+    }
+    last_sent.insert(key, Instant::now());
+    false
+}
+
+/// 触发事件：未配置、未订阅该事件或处于冷却期时忽略；发送在后台进行
+/// `dedupe_key` 用于区分同类事件的不同对象（如应用类型、供应商）
+pub fn notify(event: WebhookEvent, dedupe_key: &str, data: Value) {
+    let Some(settings) = crate::settings::get_settings().webhook else {
+        return;
+    };
+    if settings.url.trim().is_empty()
+        || (!settings.events.is_empty() && !settings.events.contains(&event))
+    {
+        return;
+    }
+    // 切换由用户主动触发，不做冷却
+    if event != WebhookEvent::ProviderSwitched && in_cooldown(format!("{:?}:{}", event, dedupe_key))
+    {
+        return;
+    }
+    tauri::async_runtime::spawn(async move {
+        match send(&settings, event, data).await {
+            Ok(()) => tracing::debug!("已发送 webhook 事件 {:?}", event),
+            Err(e) => tracing::warn!("发送 webhook 事件 {:?} 失败: {}", event, e),
+        }
+    });
+}
+
+/// 用给定设置发送一条测试消息（设置尚未保存时也可测试）
+#[tauri::command]
+pub async fn test_webhook(webhook: WebhookSettings) -> Result<bool, CcSwitchError> {
+    send(&webhook, WebhookEvent::Test, json!({})).await?;
+    Ok(true)
+}
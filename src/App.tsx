@@ -1,6 +1,12 @@
 import { useState, useEffect, useRef } from "react";
 import { useTranslation } from "react-i18next";
-import { Provider } from "./types";
+import {
+  Provider,
+  DeepLinkRequest,
+  DecodedShare,
+  ProviderSnippet,
+  SnippetMerge,
+} from "./types";
 import { AppType } from "./lib/tauri-api";
 import ProviderList from "./components/ProviderList";
 import AddProviderModal from "./components/AddProviderModal";
@@ -9,8 +15,22 @@ import { ConfirmDialog } from "./components/ConfirmDialog";
 import { AppSwitcher } from "./components/AppSwitcher";
 import SettingsModal from "./components/SettingsModal";
 import { UpdateBadge } from "./components/UpdateBadge";
-import { Plus, Settings, Moon, Sun } from "lucide-react";
+import {
+  Plus,
+  Settings,
+  Moon,
+  Sun,
+  FolderGit2,
+  Download,
+  UserPlus,
+  RotateCw,
+} from "lucide-react";
 import McpPanel from "./components/mcp/McpPanel";
+import ProjectPanel from "./components/ProjectPanel";
+import ImportShareModal from "./components/ImportShareModal";
+import SelfCheckBanner from "./components/SelfCheckBanner";
+import { UnlockModal } from "./components/UnlockModal";
+import { registerUnlockHandler } from "./lib/appLock";
 import { buttonStyles } from "./lib/styles";
 import { useDarkMode } from "./hooks/useDarkMode";
 import { extractErrorMessage } from "./utils/errorUtils";
@@ -22,6 +42,9 @@ function App() {
   const [providers, setProviders] = useState<Record<string, Provider>>({});
   const [currentProviderId, setCurrentProviderId] = useState<string>("");
   const [isAddModalOpen, setIsAddModalOpen] = useState(false);
+  // 从分享导入但密钥被去除时，预填添加表单
+  const [addInitialData, setAddInitialData] = useState<Provider | null>(null);
+  const [isImportShareOpen, setIsImportShareOpen] = useState(false);
   const [editingProviderId, setEditingProviderId] = useState<string | null>(
     null,
   );
@@ -34,12 +57,20 @@ function App() {
     isOpen: boolean;
     title: string;
     message: string;
+    confirmText?: string;
+    cancelText?: string;
     onConfirm: () => void;
+    onCancel?: () => void;
   } | null>(null);
   const [isSettingsOpen, setIsSettingsOpen] = useState(false);
   const [isMcpOpen, setIsMcpOpen] = useState(false);
+  const [isProjectsOpen, setIsProjectsOpen] = useState(false);
   const [operationMode, setProxyMode] = useState<"write" | "proxy">("write");
   const timeoutRef = useRef<ReturnType<typeof setTimeout> | null>(null);
+  const [isUnlockOpen, setIsUnlockOpen] = useState(false);
+  const unlockResolverRef = useRef<((unlocked: boolean) => void) | null>(
+    null,
+  );
 
   // 设置通知的辅助函数
   const showNotification = (
@@ -70,8 +101,28 @@ function App() {
   // 加载代理模式设置
   useEffect(() => {
     loadProxyMode();
+    migrateCommonSnippets();
+  }, []);
+
+  // 注册应用锁解锁弹窗
+  useEffect(() => {
+    registerUnlockHandler(
+      () =>
+        new Promise<boolean>((resolve) => {
+          unlockResolverRef.current?.(false);
+          unlockResolverRef.current = resolve;
+          setIsUnlockOpen(true);
+        }),
+    );
+    return () => registerUnlockHandler(null);
   }, []);
 
+  const finishUnlock = (unlocked: boolean) => {
+    setIsUnlockOpen(false);
+    unlockResolverRef.current?.(unlocked);
+    unlockResolverRef.current = null;
+  };
+
   // 加载供应商列表
   useEffect(() => {
     loadProviders();
@@ -82,32 +133,41 @@ function App() {
       const settings = await window.api.getSettings();
       const mode = (settings as any).operationMode ?? "write";
       setProxyMode(mode);
-
-      // 如果是代理模式，立即同步 localStorage 的通用配置到后端
-      if (mode === "proxy") {
-        try {
-          let claudeCommonConfig: string | undefined;
-          let codexCommonConfig: string | undefined;
-
-          try {
-            claudeCommonConfig = window.localStorage.getItem("cc-switch:common-config-snippet") || undefined;
-            codexCommonConfig = window.localStorage.getItem("cc-switch:codex-common-config-snippet") || undefined;
-          } catch {
-            // ignore localStorage errors
-          }
-
-          console.log("[App] 代理模式启动，同步通用配置到后端");
-          await window.api.syncProxyCommonConfig(claudeCommonConfig, codexCommonConfig);
-          console.log("[App] 通用配置同步完成");
-        } catch (error) {
-          console.error("[App] 同步代理模式通用配置失败:", error);
-        }
-      }
     } catch (error) {
       console.error("加载代理模式失败:", error);
     }
   };
 
+  // 旧版本把代理模式的通用配置存在 localStorage，后端尚无片段时迁移为 "default" 片段
+  const migrateCommonSnippets = async () => {
+    const flagKey = "cc-switch:common-fragments-migrated";
+    try {
+      if (window.localStorage.getItem(flagKey)) return;
+      window.localStorage.setItem(flagKey, "1");
+    } catch {
+      return;
+    }
+    const legacy: [AppType, string][] = [
+      ["claude", "cc-switch:common-config-snippet"],
+      ["codex", "cc-switch:codex-common-config-snippet"],
+    ];
+    for (const [app, key] of legacy) {
+      try {
+        const content = window.localStorage.getItem(key)?.trim();
+        if (!content) continue;
+        const existing = await window.api.getCommonFragments(app);
+        if (existing.length > 0) continue;
+        await window.api.saveCommonFragment(
+          { name: "default", content, enabled: true },
+          undefined,
+          app,
+        );
+      } catch (error) {
+        console.warn(`[App] 迁移 ${app} 通用配置失败:`, error);
+      }
+    }
+  };
+
   // 清理定时器
   useEffect(() => {
     return () => {
@@ -153,6 +213,448 @@ function App() {
     };
   }, [activeApp]);
 
+  // 批量操作完成后（可能来自其他入口）刷新当前列表
+  useEffect(() => {
+    let unlisten: (() => void) | null = null;
+
+    const setupListener = async () => {
+      try {
+        unlisten = await window.api.onProvidersUpdated(async (data) => {
+          if (data.appType === activeApp) {
+            await loadProviders();
+          }
+        });
+      } catch (error) {
+        console.error(t("console.setupListenerFailed"), error);
+      }
+    };
+
+    setupListener();
+
+    return () => {
+      if (unlisten) {
+        unlisten();
+      }
+    };
+  }, [activeApp]);
+
+  // 本地代理意外中断或自动恢复
+  useEffect(() => {
+    let unlisten: (() => void) | null = null;
+
+    const setupListener = async () => {
+      try {
+        unlisten = await window.api.onProxyStatusChanged((data) => {
+          showNotification(
+            data.message,
+            data.status === "down" ? "error" : "success",
+            data.status === "down" ? 8000 : 3000,
+          );
+        });
+      } catch (error) {
+        console.error(t("console.setupListenerFailed"), error);
+      }
+    };
+
+    setupListener();
+
+    return () => {
+      if (unlisten) {
+        unlisten();
+      }
+    };
+  }, []);
+
+  // 其他 cc-switch 进程修改了配置：后端已重新加载，刷新当前列表
+  useEffect(() => {
+    let unlisten: (() => void) | null = null;
+
+    const setupListener = async () => {
+      try {
+        unlisten = await window.api.onConfigReloaded(async () => {
+          await loadProviders();
+          showNotification(t("notifications.configReloaded"), "success", 3000);
+        });
+      } catch (error) {
+        console.error(t("console.setupListenerFailed"), error);
+      }
+    };
+
+    setupListener();
+
+    return () => {
+      if (unlisten) {
+        unlisten();
+      }
+    };
+  }, [activeApp]);
+
+  // 其他进程修改了配置且本地有未保存的修改：由用户选择保留本地修改或使用外部配置
+  useEffect(() => {
+    let unlisten: (() => void) | null = null;
+
+    const resolve = async (keepLocal: boolean) => {
+      setConfirmDialog(null);
+      try {
+        await window.api.resolveConfigConflict(keepLocal);
+        await loadProviders();
+        showNotification(
+          t(
+            keepLocal
+              ? "notifications.configConflictKeptLocal"
+              : "notifications.configReloaded",
+          ),
+          "success",
+          3000,
+        );
+      } catch (error) {
+        showNotification(extractErrorMessage(error), "error", 5000);
+      }
+    };
+
+    const setupListener = async () => {
+      try {
+        unlisten = await window.api.onConfigConflict(() => {
+          setConfirmDialog({
+            isOpen: true,
+            title: t("confirm.configConflictTitle"),
+            message: t("confirm.configConflictMessage"),
+            confirmText: t("confirm.configConflictKeepLocal"),
+            cancelText: t("confirm.configConflictUseExternal"),
+            onConfirm: () => resolve(true),
+            onCancel: () => resolve(false),
+          });
+        });
+      } catch (error) {
+        console.error(t("console.setupListenerFailed"), error);
+      }
+    };
+
+    setupListener();
+
+    return () => {
+      if (unlisten) {
+        unlisten();
+      }
+    };
+  }, [activeApp]);
+
+  // 后台延迟写盘失败：提示用户，避免误以为修改已保存
+  useEffect(() => {
+    let unlisten: (() => void) | null = null;
+
+    const setupListener = async () => {
+      try {
+        unlisten = await window.api.onConfigSaveFailed((data) => {
+          showNotification(
+            t("notifications.configSaveFailed", { error: data.message }),
+            "error",
+            5000,
+          );
+        });
+      } catch (error) {
+        console.error(t("console.setupListenerFailed"), error);
+      }
+    };
+
+    setupListener();
+
+    return () => {
+      if (unlisten) {
+        unlisten();
+      }
+    };
+  }, []);
+
+  // 代理遇到 401/403：提示用户修正该供应商的 Key
+  useEffect(() => {
+    let unlisten: (() => void) | null = null;
+
+    const setupListener = async () => {
+      try {
+        unlisten = await window.api.onProxyAuthFailed((data) => {
+          showNotification(
+            t("notifications.proxyAuthFailed", {
+              name: data.providerName,
+              status: data.status,
+              minutes: data.cooldownMinutes,
+            }),
+            "error",
+            8000,
+          );
+        });
+      } catch (error) {
+        console.error(t("console.setupListenerFailed"), error);
+      }
+    };
+
+    setupListener();
+
+    return () => {
+      if (unlisten) {
+        unlisten();
+      }
+    };
+  }, []);
+
+  // 切换后仍有 CLI 会话使用旧配置：提示重启
+  useEffect(() => {
+    let unlisten: (() => void) | null = null;
+
+    const setupListener = async () => {
+      try {
+        unlisten = await window.api.onSessionsRestartNeeded((data) => {
+          showNotification(data.message, "error", 6000);
+        });
+      } catch (error) {
+        console.error(t("console.setupListenerFailed"), error);
+      }
+    };
+
+    setupListener();
+
+    return () => {
+      if (unlisten) {
+        unlisten();
+      }
+    };
+  }, []);
+
+  // 处理 ccswitch:// 深链接：解析结果需用户确认后才执行
+  useEffect(() => {
+    let unlisten: (() => void) | null = null;
+
+    const consumePending = async () => {
+      const request = await window.api.takePendingDeepLink();
+      if (request) {
+        handleDeepLinkRequest(request);
+      }
+    };
+
+    const setupListener = async () => {
+      try {
+        unlisten = await window.api.onDeepLinkRequest(consumePending);
+      } catch (error) {
+        console.error(t("console.setupListenerFailed"), error);
+      }
+      // 冷启动时链接可能先于监听到达
+      await consumePending();
+    };
+
+    setupListener();
+
+    return () => {
+      if (unlisten) {
+        unlisten();
+      }
+    };
+  }, [activeApp]);
+
+  const handleDeepLinkRequest = (request: DeepLinkRequest) => {
+    if (request.action === "invalid") {
+      showNotification(
+        t("deepLink.invalid", { error: request.message }),
+        "error",
+        6000,
+      );
+      return;
+    }
+
+    const app = request.appType as AppType;
+    const removedNotice =
+      request.action === "add" && request.removedKeys.length > 0
+        ? t("deepLink.removedKeys", { keys: request.removedKeys.join(", ") })
+        : "";
+    if (request.action === "add" && request.keyStripped) {
+      openPrefilledAdd(
+        app,
+        request.provider,
+        removedNotice
+          ? `${t("share.fillKey")} · ${removedNotice}`
+          : t("share.fillKey"),
+      );
+      return;
+    }
+    const appName = t(`apps.${app}`);
+    const isSwitch = request.action === "switch";
+    const name = isSwitch ? request.providerName : request.provider.name;
+    const message = t(
+      isSwitch ? "deepLink.switchMessage" : "deepLink.addMessage",
+      { name, appName },
+    );
+
+    setConfirmDialog({
+      isOpen: true,
+      title: t(isSwitch ? "deepLink.switchTitle" : "deepLink.addTitle"),
+      message: removedNotice ? `${message}\n\n${removedNotice}` : message,
+      onConfirm: async () => {
+        setConfirmDialog(null);
+        try {
+          if (request.action === "switch") {
+            await window.api.switchProvider(request.providerId, app);
+            showNotification(
+              t("notifications.switchSuccess", { appName }),
+              "success",
+              2000,
+            );
+          } else {
+            if (await confirmDuplicate(app, request.provider)) return;
+            await window.api.addProvider(request.provider, app);
+            showNotification(t("deepLink.added", { name }), "success", 2000);
+          }
+          if (app === activeApp) {
+            await loadProviders();
+          } else {
+            setActiveApp(app);
+          }
+          await window.api.updateTrayMenu();
+        } catch (error) {
+          const detail = extractErrorMessage(error);
+          showNotification(
+            t("deepLink.failed", { error: detail || t("common.unknown") }),
+            "error",
+            6000,
+          );
+        }
+      },
+    });
+  };
+
+  // 分享内容不含密钥：切换到对应应用并打开预填的添加表单，由用户补填 API Key
+  const openPrefilledAdd = (
+    app: AppType,
+    provider: Provider,
+    hint: string = t("share.fillKey"),
+  ) => {
+    setActiveApp(app);
+    setAddInitialData(provider);
+    setIsAddModalOpen(true);
+    showNotification(hint, "success", 4000);
+  };
+
+  // 已有 API 地址与密钥相同的供应商时，提示合并到该条目而不是再添加一条；返回 true 表示已处理
+  const confirmDuplicate = async (
+    app: AppType,
+    provider: Provider,
+  ): Promise<boolean> => {
+    const duplicate = await window.api.findDuplicateProvider(provider, app);
+    if (!duplicate) return false;
+    if (duplicate.kind === "identical") {
+      showNotification(
+        t("duplicate.identical", { name: duplicate.name }),
+        "error",
+        5000,
+      );
+      return true;
+    }
+    setConfirmDialog({
+      isOpen: true,
+      title: t("duplicate.title"),
+      message: t("duplicate.message", { name: duplicate.name }),
+      confirmText: t("duplicate.merge"),
+      onConfirm: async () => {
+        setConfirmDialog(null);
+        try {
+          const existing = (await window.api.getProviders(app))[duplicate.id];
+          // 保留已有条目的名称、排序与 meta，只采用新的 settingsConfig
+          await window.api.updateProvider(
+            {
+              ...existing,
+              settingsConfig: provider.settingsConfig,
+              websiteUrl: existing.websiteUrl || provider.websiteUrl,
+            },
+            app,
+          );
+          if (app === activeApp) {
+            await loadProviders();
+          } else {
+            setActiveApp(app);
+          }
+          showNotification(
+            t("duplicate.merged", { name: duplicate.name }),
+            "success",
+            2000,
+          );
+          await window.api.updateTrayMenu();
+        } catch (error) {
+          const detail = extractErrorMessage(error);
+          showNotification(
+            t("notifications.saveFailed", {
+              error: detail || t("common.unknown"),
+            }),
+            "error",
+            6000,
+          );
+        }
+      },
+    });
+    return true;
+  };
+
+  const handleShareDecoded = (decoded: DecodedShare) => {
+    setIsImportShareOpen(false);
+    handleDeepLinkRequest({ action: "add", ...decoded });
+  };
+
+  // 配置片段解析结果总是先进入预填的添加表单，由用户确认名称并补全缺失字段
+  const handleSnippetParsed = (snippet: ProviderSnippet) => {
+    setIsImportShareOpen(false);
+    openPrefilledAdd(
+      snippet.appType as AppType,
+      snippet.provider,
+      snippet.missing.length > 0
+        ? t("share.snippetMissing", { fields: snippet.missing.join(", ") })
+        : t("share.snippetParsed"),
+    );
+  };
+
+  // 仅更新 API 地址与凭证：列出变化，确认后走常规的 updateProvider 保存（当前供应商会同步 live 配置）
+  const handleSnippetMerge = (merge: SnippetMerge) => {
+    setIsImportShareOpen(false);
+    const { provider, changes } = merge;
+    if (changes.length === 0) {
+      showNotification(
+        t("share.updateUnchanged", { name: provider.name }),
+        "success",
+        3000,
+      );
+      return;
+    }
+    const app = merge.appType as AppType;
+    const lines = changes.map(
+      (change) =>
+        `${change.field}: ${change.old ?? t("share.updateEmpty")} → ${change.new}`,
+    );
+    setConfirmDialog({
+      isOpen: true,
+      title: t("share.updateTitle", { name: provider.name }),
+      message: lines.join("\n"),
+      onConfirm: async () => {
+        setConfirmDialog(null);
+        try {
+          await window.api.updateProvider(provider, app);
+          await loadProviders();
+          showNotification(t("notifications.providerSaved"), "success", 2000);
+          await window.api.updateTrayMenu();
+        } catch (error) {
+          const detail = extractErrorMessage(error);
+          showNotification(
+            t("notifications.saveFailed", {
+              error: detail || t("common.unknown"),
+            }),
+            "error",
+            6000,
+          );
+        }
+      },
+    });
+  };
+
+  const closeAddModal = () => {
+    setIsAddModalOpen(false);
+    setAddInitialData(null);
+  };
+
   const loadProviders = async () => {
     const loadedProviders = await window.api.getProviders(activeApp);
     const currentId = await window.api.getCurrentProvider(activeApp);
@@ -176,9 +678,13 @@ function App() {
       id: generateId(),
       createdAt: Date.now(), // 添加创建时间戳
     };
+    if (await confirmDuplicate(activeApp, newProvider)) {
+      closeAddModal();
+      return;
+    }
     await window.api.addProvider(newProvider, activeApp);
     await loadProviders();
-    setIsAddModalOpen(false);
+    closeAddModal();
     // 更新托盘菜单
     await window.api.updateTrayMenu();
   };
@@ -282,6 +788,46 @@ function App() {
     }
   };
 
+  // 导入 Claude Code 当前登录的订阅账号（同一账号重复导入时仅刷新令牌）
+  const handleImportOAuthAccount = async () => {
+    try {
+      const provider = await window.api.importClaudeOAuthAccount();
+      await loadProviders();
+      await window.api.updateTrayMenu();
+      showNotification(
+        t("oauth.imported", { name: provider.name }),
+        "success",
+        3000,
+      );
+    } catch (error) {
+      const detail = extractErrorMessage(error);
+      showNotification(
+        detail
+          ? `${t("oauth.importFailed")}: ${detail}`
+          : t("oauth.importFailed"),
+        "error",
+        detail ? 6000 : 3000,
+      );
+    }
+  };
+
+  // 其他工具覆盖了配置文件时，按当前供应商（代理模式为代理模板）重新写入
+  const handleReapplyCurrent = async () => {
+    try {
+      await window.api.reapplyCurrent(activeApp);
+      showNotification(t("header.reapplied"), "success", 2000);
+    } catch (error) {
+      const detail = extractErrorMessage(error);
+      showNotification(
+        detail
+          ? `${t("header.reapplyFailed")}: ${detail}`
+          : t("header.reapplyFailed"),
+        "error",
+        detail ? 6000 : 3000,
+      );
+    }
+  };
+
   const handleImportSuccess = async () => {
     await loadProviders();
     await loadProxyMode();
@@ -351,6 +897,42 @@ function App() {
           <div className="flex items-center gap-4">
             <AppSwitcher activeApp={activeApp} onSwitch={setActiveApp} />
 
+            {activeApp === "claude" && (
+              <button
+                onClick={() => setIsProjectsOpen(true)}
+                className={buttonStyles.icon}
+                title={t("projects.title")}
+              >
+                <FolderGit2 size={18} />
+              </button>
+            )}
+
+            {activeApp === "claude" && (
+              <button
+                onClick={handleImportOAuthAccount}
+                className={buttonStyles.icon}
+                title={t("oauth.importAccount")}
+              >
+                <UserPlus size={18} />
+              </button>
+            )}
+
+            <button
+              onClick={handleReapplyCurrent}
+              className={buttonStyles.icon}
+              title={t("header.reapplyCurrent")}
+            >
+              <RotateCw size={18} />
+            </button>
+
+            <button
+              onClick={() => setIsImportShareOpen(true)}
+              className={buttonStyles.icon}
+              title={t("share.importTitle")}
+            >
+              <Download size={18} />
+            </button>
+
             <button
               onClick={() => setIsMcpOpen(true)}
               className="inline-flex items-center gap-2 px-7 py-2 text-sm font-medium rounded-lg transition-colors bg-emerald-500 text-white hover:bg-emerald-600 dark:bg-emerald-600 dark:hover:bg-emerald-700"
@@ -386,6 +968,11 @@ function App() {
               </div>
             )}
 
+            <SelfCheckBanner
+              onRepaired={loadProviders}
+              onNotify={showNotification}
+            />
+
             <ProviderList
               providers={providers}
               currentProviderId={currentProviderId}
@@ -404,8 +991,19 @@ function App() {
       {isAddModalOpen && (
         <AddProviderModal
           appType={activeApp}
+          initialData={addInitialData ?? undefined}
           onAdd={handleAddProvider}
-          onClose={() => setIsAddModalOpen(false)}
+          onClose={closeAddModal}
+        />
+      )}
+
+      {isImportShareOpen && (
+        <ImportShareModal
+          appType={activeApp}
+          onDecoded={handleShareDecoded}
+          onSnippet={handleSnippetParsed}
+          onMerge={handleSnippetMerge}
+          onClose={() => setIsImportShareOpen(false)}
         />
       )}
 
@@ -423,8 +1021,10 @@ function App() {
           isOpen={confirmDialog.isOpen}
           title={confirmDialog.title}
           message={confirmDialog.message}
+          confirmText={confirmDialog.confirmText}
+          cancelText={confirmDialog.cancelText}
           onConfirm={confirmDialog.onConfirm}
-          onCancel={() => setConfirmDialog(null)}
+          onCancel={confirmDialog.onCancel ?? (() => setConfirmDialog(null))}
         />
       )}
 
@@ -441,6 +1041,14 @@ function App() {
         />
       )}
 
+      {isProjectsOpen && activeApp === "claude" && (
+        <ProjectPanel
+          providers={providers}
+          onClose={() => setIsProjectsOpen(false)}
+          onNotify={showNotification}
+        />
+      )}
+
       {isMcpOpen && (
         <McpPanel
           appType={activeApp}
@@ -448,6 +1056,12 @@ function App() {
           onNotify={showNotification}
         />
       )}
+
+      <UnlockModal
+        isOpen={isUnlockOpen}
+        onUnlocked={() => finishUnlock(true)}
+        onCancel={() => finishUnlock(false)}
+      />
     </div>
   );
 }
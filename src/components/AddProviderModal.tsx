@@ -6,12 +6,15 @@ import ProviderForm from "./ProviderForm";
 
 interface AddProviderModalProps {
   appType: AppType;
+  // 从分享导入且密钥被去除时，预填其余配置供用户补全
+  initialData?: Provider;
   onAdd: (provider: Omit<Provider, "id">) => void;
   onClose: () => void;
 }
 
 const AddProviderModal: React.FC<AddProviderModalProps> = ({
   appType,
+  initialData,
   onAdd,
   onClose,
 }) => {
@@ -27,7 +30,8 @@ const AddProviderModal: React.FC<AddProviderModalProps> = ({
       appType={appType}
       title={title}
       submitText={t("common.add")}
-      showPresets={true}
+      initialData={initialData}
+      showPresets={!initialData}
       onSubmit={onAdd}
       onClose={onClose}
     />
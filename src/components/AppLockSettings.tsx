@@ -0,0 +1,148 @@
+import React, { useEffect, useState } from "react";
+import { useTranslation } from "react-i18next";
+import { Lock } from "lucide-react";
+import { AppLockStatus } from "../types";
+import { extractErrorMessage } from "../utils/errorUtils";
+
+interface AppLockSettingsProps {
+  onNotify?: (
+    message: string,
+    type: "success" | "error",
+    duration?: number,
+  ) => void;
+}
+
+const inputClass =
+  "w-full px-3 py-2 text-xs bg-white dark:bg-gray-800 border border-gray-200 dark:border-gray-700 rounded-lg focus:outline-none focus:ring-2 focus:ring-blue-500/40";
+
+// 设置中的应用锁区域：设置、修改或移除访问密码
+const AppLockSettings: React.FC<AppLockSettingsProps> = ({ onNotify }) => {
+  const { t } = useTranslation();
+  const [status, setStatus] = useState<AppLockStatus | null>(null);
+  const [current, setCurrent] = useState("");
+  const [passcode, setPasscode] = useState("");
+  const [confirm, setConfirm] = useState("");
+  const [saving, setSaving] = useState(false);
+
+  useEffect(() => {
+    window.api
+      .getAppLockStatus()
+      .then(setStatus)
+      .catch((error) => console.error("获取应用锁状态失败:", error));
+  }, []);
+
+  const reset = () => {
+    setCurrent("");
+    setPasscode("");
+    setConfirm("");
+  };
+
+  const submit = async (next: string | null) => {
+    if (next !== null && next !== confirm) {
+      onNotify?.(t("appLock.mismatch"), "error", 3000);
+      return;
+    }
+    setSaving(true);
+    try {
+      const updated = await window.api.setAppLockPasscode(
+        status?.enabled ? current : null,
+        next,
+      );
+      setStatus(updated);
+      reset();
+      onNotify?.(
+        updated.enabled ? t("appLock.passcodeSaved") : t("appLock.passcodeRemoved"),
+        "success",
+        2000,
+      );
+    } catch (error) {
+      onNotify?.(
+        `${t("appLock.saveFailed")}: ${extractErrorMessage(error)}`,
+        "error",
+        4000,
+      );
+    } finally {
+      setSaving(false);
+    }
+  };
+
+  const handleLockNow = async () => {
+    try {
+      setStatus(await window.api.lockApp());
+    } catch (error) {
+      console.error("锁定应用失败:", error);
+    }
+  };
+
+  if (!status) return null;
+
+  return (
+    <div>
+      <h3 className="text-sm font-medium text-gray-900 dark:text-gray-100 mb-2">
+        {t("appLock.title")}
+      </h3>
+      <p className="text-xs text-gray-500 dark:text-gray-400 mb-3 leading-relaxed">
+        {t("appLock.description")}
+      </p>
+      <div className="p-4 bg-gray-100 dark:bg-gray-800 rounded-lg space-y-2">
+        {status.enabled && (
+          <input
+            type="password"
+            value={current}
+            onChange={(e) => setCurrent(e.target.value)}
+            placeholder={t("appLock.currentPlaceholder")}
+            className={inputClass}
+          />
+        )}
+        <input
+          type="password"
+          value={passcode}
+          onChange={(e) => setPasscode(e.target.value)}
+          placeholder={t("appLock.newPlaceholder")}
+          className={inputClass}
+        />
+        <input
+          type="password"
+          value={confirm}
+          onChange={(e) => setConfirm(e.target.value)}
+          placeholder={t("appLock.confirmPlaceholder")}
+          className={inputClass}
+        />
+        <div className="flex gap-2 pt-1">
+          <button
+            type="button"
+            onClick={() => submit(passcode)}
+            disabled={saving || !passcode || (status.enabled && !current)}
+            className="flex-1 flex items-center justify-center gap-2 px-3 py-2 text-xs font-medium rounded-lg transition-colors bg-blue-500 hover:bg-blue-600 text-white disabled:opacity-50"
+          >
+            <Lock size={12} />
+            {status.enabled ? t("appLock.changePasscode") : t("appLock.setPasscode")}
+          </button>
+          {status.enabled && (
+            <>
+              <button
+                type="button"
+                onClick={() => submit(null)}
+                disabled={saving || !current}
+                className="px-3 py-2 text-xs font-medium rounded-lg transition-colors bg-gray-500 hover:bg-gray-600 dark:bg-gray-600 dark:hover:bg-gray-700 text-white disabled:opacity-50"
+              >
+                {t("appLock.removePasscode")}
+              </button>
+              {status.unlocked && (
+                <button
+                  type="button"
+                  onClick={handleLockNow}
+                  className="px-3 py-2 text-xs font-medium rounded-lg transition-colors bg-gray-500 hover:bg-gray-600 dark:bg-gray-600 dark:hover:bg-gray-700 text-white"
+                >
+                  {t("appLock.lockNow")}
+                </button>
+              )}
+            </>
+          )}
+        </div>
+      </div>
+    </div>
+  );
+};
+
+export default AppLockSettings;
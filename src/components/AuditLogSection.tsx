@@ -0,0 +1,114 @@
+import React, { useState } from "react";
+import { useTranslation } from "react-i18next";
+import { History, RefreshCw } from "lucide-react";
+import { AuditEntry } from "../types";
+
+interface AuditLogSectionProps {
+  retentionDays: number;
+  onRetentionChange: (days: number) => void;
+}
+
+// 每次加载的条数
+const PAGE_SIZE = 100;
+
+// 设置中的审计日志区域：保留天数与最近记录
+const AuditLogSection: React.FC<AuditLogSectionProps> = ({
+  retentionDays,
+  onRetentionChange,
+}) => {
+  const { t } = useTranslation();
+  const [entries, setEntries] = useState<AuditEntry[] | null>(null);
+  const [loading, setLoading] = useState(false);
+
+  const loadEntries = async () => {
+    setLoading(true);
+    try {
+      setEntries(await window.api.getAuditLog(PAGE_SIZE));
+    } catch (error) {
+      console.error("读取审计日志失败:", error);
+      setEntries([]);
+    } finally {
+      setLoading(false);
+    }
+  };
+
+  return (
+    <div>
+      <h3 className="text-sm font-medium text-gray-900 dark:text-gray-100 mb-2">
+        {t("audit.title")}
+      </h3>
+      <p className="text-xs text-gray-500 dark:text-gray-400 mb-3 leading-relaxed">
+        {t("audit.description")}
+      </p>
+      <div className="p-4 bg-gray-100 dark:bg-gray-800 rounded-lg space-y-3">
+        <div className="flex items-center gap-2">
+          <label className="text-sm text-gray-500 dark:text-gray-400">
+            {t("audit.retentionDays")}
+          </label>
+          <input
+            type="number"
+            min="0"
+            step="1"
+            value={retentionDays}
+            onChange={(e) => {
+              const value = parseInt(e.target.value);
+              if (!isNaN(value) && value >= 0) {
+                onRetentionChange(value);
+              }
+            }}
+            className="w-20 px-2 py-1 text-sm bg-white dark:bg-gray-800 border border-gray-200 dark:border-gray-700 rounded-md focus:outline-none focus:ring-2 focus:ring-blue-500/40"
+          />
+          <span className="text-xs text-gray-400 dark:text-gray-500">
+            {t("audit.retentionHint")}
+          </span>
+        </div>
+
+        <button
+          type="button"
+          onClick={loadEntries}
+          disabled={loading}
+          className="w-full flex items-center justify-center gap-2 px-3 py-2 text-xs font-medium rounded-lg transition-colors bg-gray-500 hover:bg-gray-600 dark:bg-gray-600 dark:hover:bg-gray-700 text-white disabled:opacity-50"
+        >
+          {entries === null ? <History size={12} /> : <RefreshCw size={12} />}
+          {entries === null ? t("audit.view") : t("audit.refresh")}
+        </button>
+
+        {entries !== null &&
+          (entries.length === 0 ? (
+            <p className="text-xs text-gray-400 dark:text-gray-500 text-center">
+              {t("audit.empty")}
+            </p>
+          ) : (
+            <ul className="max-h-60 overflow-y-auto divide-y divide-gray-200 dark:divide-gray-700 text-xs">
+              {entries.map((entry, index) => (
+                <li key={`${entry.timestamp}-${index}`} className="py-1.5">
+                  <div className="flex items-center justify-between gap-2">
+                    <span className="font-medium text-gray-900 dark:text-gray-100">
+                      {t(`audit.actions.${entry.action}`, {
+                        defaultValue: entry.action,
+                      })}
+                      {entry.target && (
+                        <span className="ml-1 font-normal text-gray-600 dark:text-gray-300">
+                          {entry.target}
+                        </span>
+                      )}
+                    </span>
+                    <span className="shrink-0 text-gray-400 dark:text-gray-500">
+                      {new Date(entry.timestamp).toLocaleString()}
+                    </span>
+                  </div>
+                  <div className="text-gray-500 dark:text-gray-400 truncate">
+                    {[entry.user, entry.appType, entry.detail]
+                      .filter(Boolean)
+                      .join(" · ")}
+                  </div>
+                </li>
+              ))}
+            </ul>
+          ))}
+      </div>
+    </div>
+  );
+};
+
+export default AuditLogSection;
@@ -0,0 +1,154 @@
+import React, { useState } from "react";
+import { useTranslation } from "react-i18next";
+import { ArrowDownUp } from "lucide-react";
+import { AppType } from "../lib/tauri-api";
+import { AutoRankSettings as AutoRankConfig, ProviderRank } from "../types";
+import { extractErrorMessage } from "../utils/errorUtils";
+
+interface AutoRankSettingsProps {
+  autoRank?: AutoRankConfig;
+  onChange: (autoRank: AutoRankConfig) => void;
+  onRanked?: () => void | Promise<void>;
+  onNotify?: (
+    message: string,
+    type: "success" | "error",
+    duration?: number,
+  ) => void;
+}
+
+const APPS: AppType[] = ["claude", "codex"];
+
+// 设置中的自动排序区域：定时开关、间隔，以及立即按探测结果重排
+const AutoRankSettings: React.FC<AutoRankSettingsProps> = ({
+  autoRank,
+  onChange,
+  onRanked,
+  onNotify,
+}) => {
+  const { t } = useTranslation();
+  const [app, setApp] = useState<AppType>("claude");
+  const [ranking, setRanking] = useState(false);
+  const [ranks, setRanks] = useState<ProviderRank[] | null>(null);
+  const current: AutoRankConfig = autoRank ?? {
+    enabled: false,
+    intervalMinutes: 60,
+  };
+
+  const update = (patch: Partial<AutoRankConfig>) =>
+    onChange({ ...current, ...patch });
+
+  const handleRankNow = async () => {
+    setRanking(true);
+    try {
+      setRanks(await window.api.autoRankProviders(app));
+      await onRanked?.();
+    } catch (error) {
+      onNotify?.(
+        `${t("autoRank.failed")}: ${extractErrorMessage(error)}`,
+        "error",
+        5000,
+      );
+    } finally {
+      setRanking(false);
+    }
+  };
+
+  return (
+    <div>
+      <h3 className="text-sm font-medium text-gray-900 dark:text-gray-100 mb-2">
+        {t("autoRank.title")}
+      </h3>
+      <p className="text-xs text-gray-500 dark:text-gray-400 mb-3 leading-relaxed">
+        {t("autoRank.description")}
+      </p>
+      <div className="p-4 bg-gray-100 dark:bg-gray-800 rounded-lg space-y-2">
+        <label className="flex items-center justify-between text-sm text-gray-700 dark:text-gray-300">
+          {t("autoRank.enabled")}
+          <input
+            type="checkbox"
+            checked={current.enabled}
+            onChange={(e) => update({ enabled: e.target.checked })}
+            className="w-4 h-4 text-blue-500 rounded focus:ring-blue-500/20"
+          />
+        </label>
+        <div className="flex items-center gap-2">
+          <label className="text-sm text-gray-500 dark:text-gray-400">
+            {t("autoRank.intervalMinutes")}
+          </label>
+          <input
+            type="number"
+            min="10"
+            step="1"
+            value={current.intervalMinutes}
+            onChange={(e) => {
+              const value = parseInt(e.target.value);
+              if (!isNaN(value) && value > 0) {
+                update({ intervalMinutes: value });
+              }
+            }}
+            className="w-20 px-2 py-1 text-sm bg-white dark:bg-gray-800 border border-gray-200 dark:border-gray-700 rounded-md focus:outline-none focus:ring-2 focus:ring-blue-500/40"
+          />
+        </div>
+
+        <div className="flex gap-2">
+          {APPS.map((item) => (
+            <button
+              key={item}
+              type="button"
+              onClick={() => {
+                setApp(item);
+                setRanks(null);
+              }}
+              className={`px-3 py-1 text-xs font-medium rounded-md transition-colors ${
+                app === item
+                  ? "bg-blue-500 text-white"
+                  : "bg-white dark:bg-gray-700 text-gray-600 dark:text-gray-300"
+              }`}
+            >
+              {t(`apps.${item}`)}
+            </button>
+          ))}
+        </div>
+        <button
+          type="button"
+          onClick={handleRankNow}
+          disabled={ranking}
+          className="w-full flex items-center justify-center gap-2 px-3 py-2 text-xs font-medium rounded-lg transition-colors bg-gray-500 hover:bg-gray-600 dark:bg-gray-600 dark:hover:bg-gray-700 text-white disabled:opacity-50"
+        >
+          <ArrowDownUp size={12} />
+          {ranking ? t("autoRank.ranking") : t("autoRank.rankNow")}
+        </button>
+        {ranks && (
+          <ol className="text-xs space-y-0.5">
+            {ranks.length === 0 && (
+              <li className="text-gray-400 dark:text-gray-500">
+                {t("autoRank.empty")}
+              </li>
+            )}
+            {ranks.map((rank) => (
+              <li
+                key={rank.id}
+                className="flex justify-between gap-2 text-gray-700 dark:text-gray-300"
+              >
+                <span className="truncate">
+                  {rank.sortIndex + 1}. {rank.name}
+                </span>
+                <span className="shrink-0 font-mono text-gray-500 dark:text-gray-400">
+                  {rank.latency != null ? `${rank.latency}ms` : "—"} ·{" "}
+                  {t("autoRank.errorRate", {
+                    rate: Math.round(rank.errorRate * 100),
+                  })}
+                </span>
+              </li>
+            ))}
+          </ol>
+        )}
+        <p className="text-xs text-gray-400 dark:text-gray-500">
+          {t("autoRank.hint")}
+        </p>
+      </div>
+    </div>
+  );
+};
+
+export default AutoRankSettings;
@@ -0,0 +1,271 @@
+import React, { useEffect, useState } from "react";
+import { useTranslation } from "react-i18next";
+import { Eye, Pencil, Plus, Trash2 } from "lucide-react";
+import { AppType } from "../lib/tauri-api";
+import { CommonFragment, CommonMergePreview } from "../types";
+import { extractErrorMessage } from "../utils/errorUtils";
+
+interface CommonFragmentsSettingsProps {
+  onNotify?: (
+    message: string,
+    type: "success" | "error",
+    duration?: number,
+  ) => void;
+}
+
+interface Draft {
+  originalName?: string;
+  name: string;
+  content: string;
+  enabled: boolean;
+}
+
+const APPS: AppType[] = ["claude", "codex"];
+
+const inputClass =
+  "w-full px-3 py-2 text-xs bg-white dark:bg-gray-800 border border-gray-200 dark:border-gray-700 rounded-lg focus:outline-none focus:ring-2 focus:ring-blue-500/40";
+
+const formatValue = (value: unknown) =>
+  value === undefined ? "∅" : JSON.stringify(value);
+
+// 设置中的通用配置片段区域：按应用管理片段，切换供应商时由后端合并写入
+const CommonFragmentsSettings: React.FC<CommonFragmentsSettingsProps> = ({
+  onNotify,
+}) => {
+  const { t } = useTranslation();
+  const [app, setApp] = useState<AppType>("claude");
+  const [fragments, setFragments] = useState<CommonFragment[]>([]);
+  const [draft, setDraft] = useState<Draft | null>(null);
+  const [preview, setPreview] = useState<CommonMergePreview | null>(null);
+  const [busy, setBusy] = useState(false);
+
+  useEffect(() => {
+    setDraft(null);
+    setPreview(null);
+    window.api
+      .getCommonFragments(app)
+      .then(setFragments)
+      .catch((error) => console.error("读取通用配置片段失败:", error));
+  }, [app]);
+
+  const notifyError = (key: string, error: unknown) =>
+    onNotify?.(`${t(key)}: ${extractErrorMessage(error)}`, "error", 5000);
+
+  const run = async (action: () => Promise<void>, errorKey: string) => {
+    setBusy(true);
+    try {
+      await action();
+    } catch (error) {
+      notifyError(errorKey, error);
+    } finally {
+      setBusy(false);
+    }
+  };
+
+  const handleSave = () =>
+    run(async () => {
+      if (!draft) return;
+      const { originalName, ...fragment } = draft;
+      setFragments(
+        await window.api.saveCommonFragment(fragment, originalName, app),
+      );
+      setDraft(null);
+      setPreview(null);
+      onNotify?.(t("commonFragments.saved"), "success", 2000);
+    }, "commonFragments.saveFailed");
+
+  const handleToggle = (fragment: CommonFragment) =>
+    run(async () => {
+      setFragments(
+        await window.api.saveCommonFragment(
+          { ...fragment, enabled: !fragment.enabled },
+          fragment.name,
+          app,
+        ),
+      );
+    }, "commonFragments.saveFailed");
+
+  const handleDelete = (fragment: CommonFragment) =>
+    run(async () => {
+      if (!window.confirm(t("commonFragments.confirmDelete", fragment))) {
+        return;
+      }
+      setFragments(await window.api.deleteCommonFragment(fragment.name, app));
+      setPreview(null);
+    }, "commonFragments.deleteFailed");
+
+  // 编辑中的片段替换原片段后预览，无需先保存
+  const handlePreview = () =>
+    run(async () => {
+      let candidates = fragments;
+      if (draft) {
+        const { originalName, ...fragment } = draft;
+        candidates = [
+          ...fragments.filter((f) => f.name !== originalName),
+          fragment,
+        ];
+      }
+      setPreview(
+        await window.api.previewCommonMerge(app, undefined, candidates),
+      );
+    }, "commonFragments.previewFailed");
+
+  return (
+    <div>
+      <h3 className="text-sm font-medium text-gray-900 dark:text-gray-100 mb-2">
+        {t("commonFragments.title")}
+      </h3>
+      <p className="text-xs text-gray-500 dark:text-gray-400 mb-3 leading-relaxed">
+        {t("commonFragments.description")}
+      </p>
+      <div className="p-4 bg-gray-100 dark:bg-gray-800 rounded-lg space-y-2">
+        <div className="flex gap-2">
+          {APPS.map((item) => (
+            <button
+              key={item}
+              type="button"
+              onClick={() => setApp(item)}
+              className={`px-3 py-1 text-xs font-medium rounded-md transition-colors ${
+                app === item
+                  ? "bg-blue-500 text-white"
+                  : "bg-white dark:bg-gray-700 text-gray-600 dark:text-gray-300"
+              }`}
+            >
+              {t(`apps.${item}`)}
+            </button>
+          ))}
+        </div>
+
+        {fragments.length === 0 && !draft && (
+          <p className="text-xs text-gray-400 dark:text-gray-500">
+            {t("commonFragments.empty")}
+          </p>
+        )}
+        {fragments.map((fragment) => (
+          <div
+            key={fragment.name}
+            className="flex items-center justify-between gap-2 text-xs"
+          >
+            <label className="flex items-center gap-2 text-gray-700 dark:text-gray-300 min-w-0">
+              <input
+                type="checkbox"
+                checked={fragment.enabled}
+                disabled={busy}
+                onChange={() => handleToggle(fragment)}
+              />
+              <span className="truncate">{fragment.name}</span>
+            </label>
+            <span className="flex shrink-0 gap-1">
+              <button
+                type="button"
+                onClick={() => {
+                  setDraft({ ...fragment, originalName: fragment.name });
+                  setPreview(null);
+                }}
+                title={t("common.edit")}
+                className="p-1 rounded-md text-gray-500 hover:bg-gray-200 dark:hover:bg-gray-700"
+              >
+                <Pencil size={12} />
+              </button>
+              <button
+                type="button"
+                onClick={() => handleDelete(fragment)}
+                disabled={busy}
+                title={t("common.delete")}
+                className="p-1 rounded-md text-red-500 hover:bg-red-100 dark:hover:bg-red-900/30"
+              >
+                <Trash2 size={12} />
+              </button>
+            </span>
+          </div>
+        ))}
+
+        {draft ? (
+          <div className="space-y-2">
+            <input
+              type="text"
+              value={draft.name}
+              onChange={(e) => setDraft({ ...draft, name: e.target.value })}
+              placeholder={t("commonFragments.namePlaceholder")}
+              className={inputClass}
+            />
+            <textarea
+              value={draft.content}
+              onChange={(e) => setDraft({ ...draft, content: e.target.value })}
+              placeholder={t(`commonFragments.contentPlaceholder.${app}`)}
+              rows={6}
+              className={`${inputClass} font-mono`}
+            />
+            <div className="flex gap-2">
+              <button
+                type="button"
+                onClick={handleSave}
+                disabled={busy || !draft.name.trim()}
+                className="flex-1 px-3 py-2 text-xs font-medium rounded-lg bg-blue-500 hover:bg-blue-600 text-white disabled:opacity-50"
+              >
+                {t("common.save")}
+              </button>
+              <button
+                type="button"
+                onClick={() => {
+                  setDraft(null);
+                  setPreview(null);
+                }}
+                className="flex-1 px-3 py-2 text-xs font-medium rounded-lg bg-white dark:bg-gray-700 text-gray-600 dark:text-gray-300"
+              >
+                {t("common.cancel")}
+              </button>
+            </div>
+          </div>
+        ) : (
+          <button
+            type="button"
+            onClick={() => setDraft({ name: "", content: "", enabled: true })}
+            className="w-full flex items-center justify-center gap-2 px-3 py-2 text-xs font-medium rounded-lg transition-colors bg-gray-500 hover:bg-gray-600 dark:bg-gray-600 dark:hover:bg-gray-700 text-white"
+          >
+            <Plus size={12} />
+            {t("commonFragments.add")}
+          </button>
+        )}
+
+        <button
+          type="button"
+          onClick={handlePreview}
+          disabled={busy}
+          className="w-full flex items-center justify-center gap-2 px-3 py-2 text-xs font-medium rounded-lg transition-colors bg-white dark:bg-gray-700 text-gray-600 dark:text-gray-300 disabled:opacity-50"
+        >
+          <Eye size={12} />
+          {t("commonFragments.preview")}
+        </button>
+        {preview && (
+          <div className="text-xs space-y-1">
+            <p className="text-gray-500 dark:text-gray-400">
+              {t("commonFragments.previewTitle", {
+                name: preview.providerName,
+              })}
+            </p>
+            {preview.changes.length === 0 ? (
+              <p className="text-gray-400 dark:text-gray-500">
+                {t("commonFragments.noChanges")}
+              </p>
+            ) : (
+              <ul className="font-mono space-y-0.5 break-all">
+                {preview.changes.map((change) => (
+                  <li
+                    key={change.path}
+                    className="text-gray-700 dark:text-gray-300"
+                  >
+                    {change.path}: {formatValue(change.oldValue)} →{" "}
+                    {formatValue(change.newValue)}
+                  </li>
+                ))}
+              </ul>
+            )}
+          </div>
+        )}
+      </div>
+    </div>
+  );
+};
+
+export default CommonFragmentsSettings;
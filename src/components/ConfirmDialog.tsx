@@ -56,7 +56,7 @@ export const ConfirmDialog: React.FC<ConfirmDialogProps> = ({
 
         {/* Content */}
         <div className="p-6">
-          <p className="text-gray-500 dark:text-gray-400 leading-relaxed">
+          <p className="text-gray-500 dark:text-gray-400 leading-relaxed whitespace-pre-line">
             {message}
           </p>
         </div>
@@ -2,6 +2,7 @@ import React, { useEffect, useState } from "react";
 import { useTranslation } from "react-i18next";
 import { Provider } from "../types";
 import { AppType } from "../lib/tauri-api";
+import { ensureAppUnlocked } from "../lib/appLock";
 import ProviderForm from "./ProviderForm";
 
 interface EditProviderModalProps {
@@ -52,6 +53,17 @@ const EditProviderModal: React.FC<EditProviderModalProps> = ({
     });
   };
 
+  // 明文密钥需后端弹出系统确认，用户取消时返回 null
+  const handleRevealSecrets = async () => {
+    if (!(await ensureAppUnlocked())) return null;
+    try {
+      return await window.api.revealProviderSecret(provider.id, appType);
+    } catch (error) {
+      console.error("获取明文密钥失败:", error);
+      return null;
+    }
+  };
+
   const title =
     appType === "claude"
       ? t("provider.editClaudeProvider")
@@ -66,6 +78,7 @@ const EditProviderModal: React.FC<EditProviderModalProps> = ({
       showPresets={false}
       onSubmit={handleSubmit}
       onClose={onClose}
+      onRevealSecrets={handleRevealSecrets}
     />
   );
 };
@@ -0,0 +1,303 @@
+import React, { useEffect, useState } from "react";
+import { useTranslation } from "react-i18next";
+import { Play, Plus, Trash2 } from "lucide-react";
+import { AppType } from "../lib/tauri-api";
+import { HookEvent, HookKind, HookRun, SwitchHook } from "../types";
+import { extractErrorMessage } from "../utils/errorUtils";
+
+interface HooksSettingsProps {
+  hooks: SwitchHook[];
+  onChange: (hooks: SwitchHook[]) => void;
+  onNotify?: (
+    message: string,
+    type: "success" | "error",
+    duration?: number,
+  ) => void;
+}
+
+const EVENTS: HookEvent[] = [
+  "before_switch",
+  "after_switch",
+  "before_mode_change",
+  "after_mode_change",
+];
+
+const KINDS: HookKind[] = ["shell", "js"];
+
+const APPS: AppType[] = ["claude", "codex"];
+
+const inputClass =
+  "px-2 py-1 text-xs bg-white dark:bg-gray-800 border border-gray-200 dark:border-gray-700 rounded-md focus:outline-none focus:ring-2 focus:ring-blue-500/40";
+
+const isBefore = (event: HookEvent) => event.startsWith("before_");
+const isSwitch = (event: HookEvent) => event.endsWith("_switch");
+
+// 设置中的切换钩子区域：切换供应商或运行模式前后执行的 shell 命令 / JS 片段
+const HooksSettings: React.FC<HooksSettingsProps> = ({
+  hooks,
+  onChange,
+  onNotify,
+}) => {
+  const { t } = useTranslation();
+  const [testing, setTesting] = useState<string | null>(null);
+  const [results, setResults] = useState<Record<string, HookRun>>({});
+
+  // 展示每个钩子最近一次的执行结果
+  useEffect(() => {
+    window.api
+      .getHookRuns()
+      .then((runs) => {
+        const latest: Record<string, HookRun> = {};
+        for (const run of runs) {
+          latest[run.hookId] ??= run;
+        }
+        setResults(latest);
+      })
+      .catch((error) => console.error("加载钩子执行记录失败:", error));
+  }, []);
+
+  const updateHook = (index: number, patch: Partial<SwitchHook>) =>
+    onChange(
+      hooks.map((hook, i) => (i === index ? { ...hook, ...patch } : hook)),
+    );
+
+  const toggleApp = (index: number, app: AppType) => {
+    const apps = hooks[index].apps ?? [];
+    updateHook(index, {
+      apps: apps.includes(app)
+        ? apps.filter((item) => item !== app)
+        : [...apps, app],
+    });
+  };
+
+  const handleAdd = () =>
+    onChange([
+      ...hooks,
+      {
+        id: crypto.randomUUID(),
+        name: "",
+        enabled: true,
+        event: "after_switch",
+        kind: "shell",
+        script: "",
+      },
+    ]);
+
+  // 试运行使用当前填写的内容，无需先保存
+  const handleTest = async (hook: SwitchHook) => {
+    setTesting(hook.id);
+    try {
+      const run = await window.api.testHook(hook);
+      setResults((prev) => ({ ...prev, [hook.id]: run }));
+      if (run.success) {
+        onNotify?.(t("hooks.testSuccess"), "success", 2000);
+      } else {
+        onNotify?.(
+          `${t("hooks.testFailed")}: ${run.error ?? run.stderr.trim()}`,
+          "error",
+          5000,
+        );
+      }
+    } catch (error) {
+      onNotify?.(
+        `${t("hooks.testFailed")}: ${extractErrorMessage(error)}`,
+        "error",
+        5000,
+      );
+    } finally {
+      setTesting(null);
+    }
+  };
+
+  return (
+    <div>
+      <h3 className="text-sm font-medium text-gray-900 dark:text-gray-100 mb-2">
+        {t("hooks.title")}
+      </h3>
+      <p className="text-xs text-gray-500 dark:text-gray-400 mb-3 leading-relaxed">
+        {t("hooks.description")}
+      </p>
+      <div className="p-4 bg-gray-100 dark:bg-gray-800 rounded-lg space-y-2">
+        {hooks.length === 0 && (
+          <p className="text-xs text-gray-400 dark:text-gray-500">
+            {t("hooks.empty")}
+          </p>
+        )}
+        {hooks.map((hook, index) => {
+          const result = results[hook.id];
+          return (
+            <div
+              key={hook.id}
+              className="p-2 space-y-2 bg-white dark:bg-gray-900 rounded-md"
+            >
+              <div className="flex items-center gap-2">
+                <input
+                  type="checkbox"
+                  checked={hook.enabled}
+                  onChange={(e) =>
+                    updateHook(index, { enabled: e.target.checked })
+                  }
+                  title={t("hooks.enabled")}
+                  className="w-3 h-3 text-blue-500 rounded focus:ring-blue-500/20"
+                />
+                <input
+                  type="text"
+                  value={hook.name}
+                  onChange={(e) =>
+                    updateHook(index, { name: e.target.value })
+                  }
+                  placeholder={t("hooks.namePlaceholder")}
+                  className={`flex-1 ${inputClass}`}
+                />
+                <button
+                  type="button"
+                  onClick={() => handleTest(hook)}
+                  disabled={testing !== null || !hook.script.trim()}
+                  title={t("hooks.test")}
+                  className="p-1 rounded-md text-gray-500 hover:bg-gray-200 dark:hover:bg-gray-700 disabled:opacity-50"
+                >
+                  <Play size={12} />
+                </button>
+                <button
+                  type="button"
+                  onClick={() => onChange(hooks.filter((_, i) => i !== index))}
+                  title={t("common.delete")}
+                  className="p-1 rounded-md text-red-500 hover:bg-red-100 dark:hover:bg-red-900/30"
+                >
+                  <Trash2 size={12} />
+                </button>
+              </div>
+              <div className="flex items-center gap-2">
+                <select
+                  value={hook.event}
+                  onChange={(e) =>
+                    updateHook(index, { event: e.target.value as HookEvent })
+                  }
+                  className={`flex-1 ${inputClass}`}
+                >
+                  {EVENTS.map((event) => (
+                    <option key={event} value={event}>
+                      {t(`hooks.events.${event}`)}
+                    </option>
+                  ))}
+                </select>
+                <select
+                  value={hook.kind}
+                  onChange={(e) =>
+                    updateHook(index, { kind: e.target.value as HookKind })
+                  }
+                  className={inputClass}
+                >
+                  {KINDS.map((kind) => (
+                    <option key={kind} value={kind}>
+                      {t(`hooks.kinds.${kind}`)}
+                    </option>
+                  ))}
+                </select>
+                <input
+                  type="number"
+                  min={1}
+                  max={300}
+                  value={hook.timeoutSecs ?? ""}
+                  onChange={(e) =>
+                    updateHook(index, {
+                      timeoutSecs: e.target.value
+                        ? Number(e.target.value)
+                        : undefined,
+                    })
+                  }
+                  placeholder="10"
+                  title={t("hooks.timeout")}
+                  className={`w-16 ${inputClass}`}
+                />
+              </div>
+              <textarea
+                value={hook.script}
+                onChange={(e) =>
+                  updateHook(index, { script: e.target.value })
+                }
+                placeholder={t(`hooks.scriptPlaceholder.${hook.kind}`)}
+                rows={3}
+                className={`w-full font-mono ${inputClass}`}
+              />
+              <div className="flex flex-wrap items-center gap-x-3 gap-y-1">
+                {isSwitch(hook.event) &&
+                  APPS.map((app) => (
+                    <label
+                      key={app}
+                      className="flex items-center gap-1 text-xs text-gray-700 dark:text-gray-300"
+                    >
+                      <input
+                        type="checkbox"
+                        checked={(hook.apps ?? []).includes(app)}
+                        onChange={() => toggleApp(index, app)}
+                        className="w-3 h-3 text-blue-500 rounded focus:ring-blue-500/20"
+                      />
+                      {t(`apps.${app}`)}
+                    </label>
+                  ))}
+                {isBefore(hook.event) && (
+                  <label className="flex items-center gap-1 text-xs text-gray-500 dark:text-gray-400">
+                    <input
+                      type="checkbox"
+                      checked={hook.abortOnFailure ?? false}
+                      onChange={(e) =>
+                        updateHook(index, {
+                          abortOnFailure: e.target.checked,
+                        })
+                      }
+                      className="w-3 h-3 text-blue-500 rounded focus:ring-blue-500/20"
+                    />
+                    {t("hooks.abortOnFailure")}
+                  </label>
+                )}
+              </div>
+              {result && (
+                <div className="text-xs">
+                  <p
+                    className={
+                      result.success
+                        ? "text-green-600 dark:text-green-400"
+                        : "text-red-500"
+                    }
+                  >
+                    {t(
+                      result.success ? "hooks.lastSuccess" : "hooks.lastFailed",
+                      {
+                        time: new Date(result.timestamp).toLocaleString(),
+                        duration: result.durationMs,
+                      },
+                    )}
+                    {result.timedOut && ` · ${t("hooks.timedOut")}`}
+                    {result.error && ` · ${result.error}`}
+                  </p>
+                  {(result.stdout || result.stderr) && (
+                    <pre className="mt-1 p-2 max-h-32 overflow-auto whitespace-pre-wrap font-mono text-[11px] text-gray-600 dark:text-gray-300 bg-gray-50 dark:bg-gray-800 rounded">
+                      {[result.stdout, result.stderr]
+                        .filter((text) => text.trim())
+                        .join("\n")}
+                    </pre>
+                  )}
+                </div>
+              )}
+            </div>
+          );
+        })}
+
+        <button
+          type="button"
+          onClick={handleAdd}
+          className="flex items-center gap-1 px-2 py-1 text-xs rounded-md text-gray-600 dark:text-gray-300 hover:bg-gray-200 dark:hover:bg-gray-700"
+        >
+          <Plus size={12} />
+          {t("hooks.add")}
+        </button>
+        <p className="text-xs text-gray-400 dark:text-gray-500">
+          {t("hooks.hint")}
+        </p>
+      </div>
+    </div>
+  );
+};
+
+export default HooksSettings;
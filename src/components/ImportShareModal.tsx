@@ -0,0 +1,140 @@
+import React, { useState } from "react";
+import { useTranslation } from "react-i18next";
+import { X, Download, RefreshCw } from "lucide-react";
+import { AppType } from "../lib/tauri-api";
+import { DecodedShare, ProviderSnippet, SnippetMerge } from "../types";
+import { buttonStyles } from "../lib/styles";
+import { extractErrorMessage } from "../utils/errorUtils";
+
+interface ImportShareModalProps {
+  appType: AppType;
+  onDecoded: (decoded: DecodedShare) => void;
+  onSnippet: (snippet: ProviderSnippet) => void;
+  onMerge: (merge: SnippetMerge) => void;
+  onClose: () => void;
+}
+
+// 分享字符串为单行 base64（或 ccswitch:// 链接），其余内容按配置片段解析
+const isShareString = (text: string) =>
+  text.startsWith("ccswitch://") || /^[A-Za-z0-9+/=_-]+$/.test(text);
+
+/**
+ * 从分享字符串或配置片段导入供应商
+ * 支持直接粘贴 base64 字符串、ccswitch://add 链接，或中转商提供的环境变量 / JSON / TOML 片段，
+ * 解析后交由调用方确认添加；配置片段也可用于更新已有供应商的地址与密钥
+ */
+const ImportShareModal: React.FC<ImportShareModalProps> = ({
+  appType,
+  onDecoded,
+  onSnippet,
+  onMerge,
+  onClose,
+}) => {
+  const { t } = useTranslation();
+  const [input, setInput] = useState("");
+  const [error, setError] = useState("");
+  const [decoding, setDecoding] = useState(false);
+
+  const handleImport = async () => {
+    const text = input.trim();
+    if (!text) return;
+    setDecoding(true);
+    setError("");
+    try {
+      if (isShareString(text)) {
+        onDecoded(await window.api.decodeProviderShare(text));
+      } else {
+        onSnippet(await window.api.parseProviderSnippet(text, appType));
+      }
+    } catch (err) {
+      setError(extractErrorMessage(err) || t("share.decodeFailed"));
+    } finally {
+      setDecoding(false);
+    }
+  };
+
+  // 中转商更换地址或密钥：匹配已有供应商并预览变化
+  const handleUpdateExisting = async () => {
+    const text = input.trim();
+    if (!text) return;
+    setDecoding(true);
+    setError("");
+    try {
+      onMerge(await window.api.mergeProviderSnippet(text, appType));
+    } catch (err) {
+      setError(extractErrorMessage(err) || t("share.decodeFailed"));
+    } finally {
+      setDecoding(false);
+    }
+  };
+
+  const canUpdate = !!input.trim() && !isShareString(input.trim());
+
+  return (
+    <div className="fixed inset-0 z-50 flex items-center justify-center">
+      {/* Backdrop */}
+      <div
+        className="absolute inset-0 bg-black/50 backdrop-blur-sm"
+        onClick={onClose}
+      />
+
+      <div className="relative bg-white dark:bg-gray-900 rounded-xl shadow-lg max-w-lg w-full mx-4 overflow-hidden flex flex-col">
+        {/* Header */}
+        <div className="flex-shrink-0 flex items-center justify-between p-6 border-b border-gray-200 dark:border-gray-800">
+          <h3 className="text-lg font-semibold text-gray-900 dark:text-gray-100">
+            {t("share.importTitle")}
+          </h3>
+          <button
+            onClick={onClose}
+            className="p-1 text-gray-500 hover:text-gray-900 hover:bg-gray-100 dark:text-gray-400 dark:hover:text-gray-100 dark:hover:bg-gray-800 rounded-md transition-colors"
+          >
+            <X size={18} />
+          </button>
+        </div>
+
+        {/* Content */}
+        <div className="p-6 space-y-3">
+          <p className="text-sm text-gray-500 dark:text-gray-400">
+            {t("share.importDescription")}
+          </p>
+          <textarea
+            value={input}
+            onChange={(e) => setInput(e.target.value)}
+            rows={5}
+            placeholder={t("share.importPlaceholder")}
+            className="w-full px-3 py-2 text-xs font-mono bg-white dark:bg-gray-800 border border-gray-200 dark:border-gray-700 rounded-lg text-gray-900 dark:text-gray-100 focus:outline-none focus:ring-2 focus:ring-blue-500/40 resize-none"
+          />
+          {error && (
+            <p className="text-xs text-red-500 dark:text-red-400">{error}</p>
+          )}
+        </div>
+
+        {/* Footer */}
+        <div className="flex-shrink-0 flex items-center justify-end gap-3 p-6 border-t border-gray-200 dark:border-gray-800 bg-gray-100 dark:bg-gray-800">
+          <button onClick={onClose} className={buttonStyles.secondary}>
+            {t("common.cancel")}
+          </button>
+          <button
+            onClick={handleUpdateExisting}
+            disabled={decoding || !canUpdate}
+            className={`inline-flex items-center gap-2 ${buttonStyles.secondary}`}
+            title={t("share.updateExistingHint")}
+          >
+            <RefreshCw size={16} />
+            {t("share.updateExisting")}
+          </button>
+          <button
+            onClick={handleImport}
+            disabled={decoding || !input.trim()}
+            className={`inline-flex items-center gap-2 ${buttonStyles.primary}`}
+          >
+            <Download size={16} />
+            {t("share.import")}
+          </button>
+        </div>
+      </div>
+    </div>
+  );
+};
+
+export default ImportShareModal;
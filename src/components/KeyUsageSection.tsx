@@ -0,0 +1,107 @@
+import React, { useState } from "react";
+import { useTranslation } from "react-i18next";
+import { RefreshCw } from "lucide-react";
+import { KeyUsage } from "../types";
+import { extractErrorMessage } from "../utils/errorUtils";
+
+interface KeyUsageSectionProps {
+  onNotify?: (
+    message: string,
+    type: "success" | "error",
+    duration?: number,
+  ) => void;
+}
+
+// 设置中的 Key 用量区域：代理模式下各供应商 Key 的请求数、失败数与状态
+const KeyUsageSection: React.FC<KeyUsageSectionProps> = ({ onNotify }) => {
+  const { t } = useTranslation();
+  const [usage, setUsage] = useState<KeyUsage[] | null>(null);
+
+  const handleRefresh = async () => {
+    try {
+      setUsage(await window.api.getKeyUsageStats());
+    } catch (error) {
+      onNotify?.(extractErrorMessage(error), "error", 5000);
+    }
+  };
+
+  const statusOf = (item: KeyUsage) => {
+    if (item.blocked) return t("keyUsage.blocked");
+    if (item.limited) return t("keyUsage.limited");
+    return item.lastStatus ?? t("keyUsage.networkError");
+  };
+
+  return (
+    <div>
+      <h3 className="text-sm font-medium text-gray-900 dark:text-gray-100 mb-2">
+        {t("keyUsage.title")}
+      </h3>
+      <p className="text-xs text-gray-500 dark:text-gray-400 mb-3 leading-relaxed">
+        {t("keyUsage.description")}
+      </p>
+      <div className="p-4 bg-gray-100 dark:bg-gray-800 rounded-lg space-y-2">
+        <button
+          type="button"
+          onClick={handleRefresh}
+          className="w-full flex items-center justify-center gap-2 px-3 py-2 text-xs font-medium rounded-lg transition-colors bg-gray-500 hover:bg-gray-600 dark:bg-gray-600 dark:hover:bg-gray-700 text-white"
+        >
+          <RefreshCw size={12} />
+          {t("keyUsage.refresh")}
+        </button>
+        {usage && usage.length === 0 && (
+          <p className="text-xs text-gray-400 dark:text-gray-500">
+            {t("keyUsage.empty")}
+          </p>
+        )}
+        {usage && usage.length > 0 && (
+          <table className="w-full text-xs text-gray-700 dark:text-gray-300">
+            <thead className="text-gray-500 dark:text-gray-400">
+              <tr>
+                <th className="text-left font-normal">
+                  {t("keyUsage.provider")}
+                </th>
+                <th className="text-left font-normal">{t("keyUsage.key")}</th>
+                <th className="text-right font-normal">
+                  {t("keyUsage.requests")}
+                </th>
+                <th className="text-right font-normal">
+                  {t("keyUsage.failures")}
+                </th>
+                <th className="text-right font-normal">
+                  {t("keyUsage.lastStatus")}
+                </th>
+                <th className="text-right font-normal">
+                  {t("keyUsage.lastUsed")}
+                </th>
+              </tr>
+            </thead>
+            <tbody>
+              {usage.map((item) => (
+                <tr key={`${item.appType}:${item.providerId}:${item.key}`}>
+                  <td className="truncate">
+                    {t(`apps.${item.appType}`)} · {item.providerName}
+                  </td>
+                  <td className="font-mono">{item.key}</td>
+                  <td className="text-right">{item.requests}</td>
+                  <td className="text-right">{item.failures}</td>
+                  <td
+                    className={`text-right ${
+                      item.blocked || item.limited ? "text-red-500" : ""
+                    }`}
+                  >
+                    {statusOf(item)}
+                  </td>
+                  <td className="text-right">
+                    {new Date(item.lastUsed).toLocaleTimeString()}
+                  </td>
+                </tr>
+              ))}
+            </tbody>
+          </table>
+        )}
+      </div>
+    </div>
+  );
+};
+
+export default KeyUsageSection;
@@ -0,0 +1,225 @@
+import React, { useState } from "react";
+import { useTranslation } from "react-i18next";
+import { Link, RefreshCw, Search } from "lucide-react";
+import { LanShareSettings as LanShareConfig, SharedProxy } from "../types";
+import { extractErrorMessage } from "../utils/errorUtils";
+
+interface LanShareSettingsProps {
+  lanShare?: LanShareConfig;
+  onChange: (lanShare: LanShareConfig) => void;
+  onConnected?: () => void | Promise<void>;
+  onNotify?: (
+    message: string,
+    type: "success" | "error",
+    duration?: number,
+  ) => void;
+}
+
+const inputClass =
+  "px-2 py-1 text-sm bg-white dark:bg-gray-800 border border-gray-200 dark:border-gray-700 rounded-md focus:outline-none focus:ring-2 focus:ring-blue-500/40";
+
+// 生成随机访问令牌
+const generateToken = () => `ccs-${crypto.randomUUID().replace(/-/g, "")}`;
+
+// 设置中的局域网共享区域：监听地址、端口、访问令牌与来源 IP 允许列表
+const LanShareSettings: React.FC<LanShareSettingsProps> = ({
+  lanShare,
+  onChange,
+  onConnected,
+  onNotify,
+}) => {
+  const { t } = useTranslation();
+  const [discovering, setDiscovering] = useState(false);
+  const [proxies, setProxies] = useState<SharedProxy[] | null>(null);
+  const [remoteToken, setRemoteToken] = useState("");
+  const [connecting, setConnecting] = useState<string | null>(null);
+  const current: LanShareConfig = lanShare ?? {
+    enabled: false,
+    bindAddress: "0.0.0.0",
+    port: 12858,
+    token: "",
+    allowedIps: [],
+  };
+
+  const update = (patch: Partial<LanShareConfig>) =>
+    onChange({ ...current, ...patch });
+
+  const handleDiscover = async () => {
+    setDiscovering(true);
+    try {
+      setProxies(await window.api.discoverSharedProxies());
+    } catch (error) {
+      onNotify?.(
+        `${t("lanShare.discoverFailed")}: ${extractErrorMessage(error)}`,
+        "error",
+        5000,
+      );
+    } finally {
+      setDiscovering(false);
+    }
+  };
+
+  const handleConnect = async (proxy: SharedProxy) => {
+    setConnecting(proxy.url);
+    try {
+      await window.api.connectSharedProxy(proxy.name, proxy.url, remoteToken);
+      onNotify?.(t("lanShare.connected", { name: proxy.name }), "success");
+      await onConnected?.();
+    } catch (error) {
+      onNotify?.(
+        `${t("lanShare.connectFailed")}: ${extractErrorMessage(error)}`,
+        "error",
+        5000,
+      );
+    } finally {
+      setConnecting(null);
+    }
+  };
+
+  return (
+    <div>
+      <h3 className="text-sm font-medium text-gray-900 dark:text-gray-100 mb-2">
+        {t("lanShare.title")}
+      </h3>
+      <p className="text-xs text-gray-500 dark:text-gray-400 mb-3 leading-relaxed">
+        {t("lanShare.description")}
+      </p>
+      <div className="p-4 bg-gray-100 dark:bg-gray-800 rounded-lg space-y-2">
+        <label className="flex items-center justify-between text-sm text-gray-700 dark:text-gray-300">
+          {t("lanShare.enabled")}
+          <input
+            type="checkbox"
+            checked={current.enabled}
+            onChange={(e) =>
+              update({
+                enabled: e.target.checked,
+                token:
+                  e.target.checked && !current.token
+                    ? generateToken()
+                    : current.token,
+              })
+            }
+            className="w-4 h-4 text-blue-500 rounded focus:ring-blue-500/20"
+          />
+        </label>
+        <div className="flex items-center gap-2">
+          <label className="w-20 text-sm text-gray-500 dark:text-gray-400">
+            {t("lanShare.bindAddress")}
+          </label>
+          <input
+            type="text"
+            value={current.bindAddress}
+            onChange={(e) => update({ bindAddress: e.target.value })}
+            placeholder="0.0.0.0"
+            className={`flex-1 font-mono ${inputClass}`}
+          />
+          <input
+            type="number"
+            min="1"
+            max="65535"
+            value={current.port}
+            onChange={(e) => {
+              const value = parseInt(e.target.value);
+              if (!isNaN(value) && value > 0 && value <= 65535) {
+                update({ port: value });
+              }
+            }}
+            className={`w-24 ${inputClass}`}
+          />
+        </div>
+        <div className="flex items-center gap-2">
+          <label className="w-20 text-sm text-gray-500 dark:text-gray-400">
+            {t("lanShare.token")}
+          </label>
+          <input
+            type="text"
+            value={current.token}
+            onChange={(e) => update({ token: e.target.value })}
+            className={`flex-1 font-mono ${inputClass}`}
+          />
+          <button
+            type="button"
+            onClick={() => update({ token: generateToken() })}
+            title={t("lanShare.generateToken")}
+            className="p-1.5 rounded-md text-gray-600 dark:text-gray-300 hover:bg-gray-200 dark:hover:bg-gray-700"
+          >
+            <RefreshCw size={14} />
+          </button>
+        </div>
+        <div>
+          <label className="block text-sm text-gray-500 dark:text-gray-400 mb-1">
+            {t("lanShare.allowedIps")}
+          </label>
+          <textarea
+            rows={3}
+            value={current.allowedIps.join("\n")}
+            onChange={(e) =>
+              update({ allowedIps: e.target.value.split("\n") })
+            }
+            placeholder={t("lanShare.allowedIpsPlaceholder")}
+            className={`w-full font-mono ${inputClass}`}
+          />
+        </div>
+        <p className="text-xs text-gray-400 dark:text-gray-500">
+          {t("lanShare.hint")}
+        </p>
+
+        <div className="pt-2 border-t border-gray-200 dark:border-gray-700 space-y-2">
+          <button
+            type="button"
+            onClick={handleDiscover}
+            disabled={discovering}
+            className="w-full flex items-center justify-center gap-2 px-3 py-2 text-xs font-medium rounded-lg transition-colors bg-gray-500 hover:bg-gray-600 dark:bg-gray-600 dark:hover:bg-gray-700 text-white disabled:opacity-50"
+          >
+            <Search size={12} />
+            {discovering ? t("lanShare.discovering") : t("lanShare.discover")}
+          </button>
+          {proxies && (
+            <>
+              {proxies.length === 0 && (
+                <p className="text-xs text-gray-400 dark:text-gray-500">
+                  {t("lanShare.noneFound")}
+                </p>
+              )}
+              {proxies.length > 0 && (
+                <input
+                  type="text"
+                  value={remoteToken}
+                  onChange={(e) => setRemoteToken(e.target.value)}
+                  placeholder={t("lanShare.remoteTokenPlaceholder")}
+                  className={`w-full font-mono ${inputClass}`}
+                />
+              )}
+              {proxies.map((proxy) => (
+                <div
+                  key={proxy.url}
+                  className="flex items-center justify-between gap-2 text-xs text-gray-700 dark:text-gray-300"
+                >
+                  <span className="truncate">
+                    {proxy.name}{" "}
+                    <span className="font-mono text-gray-500 dark:text-gray-400">
+                      {proxy.url}
+                    </span>
+                  </span>
+                  <button
+                    type="button"
+                    onClick={() => handleConnect(proxy)}
+                    disabled={!remoteToken.trim() || connecting !== null}
+                    className="shrink-0 flex items-center gap-1 px-2 py-1 rounded-md bg-blue-500 hover:bg-blue-600 text-white disabled:opacity-50"
+                  >
+                    <Link size={12} />
+                    {connecting === proxy.url
+                      ? t("lanShare.connecting")
+                      : t("lanShare.connect")}
+                  </button>
+                </div>
+              ))}
+            </>
+          )}
+        </div>
+      </div>
+    </div>
+  );
+};
+
+export default LanShareSettings;
@@ -0,0 +1,121 @@
+import React, { useState } from "react";
+import { useTranslation } from "react-i18next";
+import { CloudDownload } from "lucide-react";
+import { ManagedSource } from "../types";
+import { extractErrorMessage } from "../utils/errorUtils";
+
+interface ManagedSourceSettingsProps {
+  source?: ManagedSource;
+  onChange: (source: ManagedSource) => void;
+  onSynced?: () => void | Promise<void>;
+  onNotify?: (
+    message: string,
+    type: "success" | "error",
+    duration?: number,
+  ) => void;
+}
+
+const inputClass =
+  "w-full px-3 py-2 text-xs bg-white dark:bg-gray-800 border border-gray-200 dark:border-gray-700 rounded-lg focus:outline-none focus:ring-2 focus:ring-blue-500/40";
+
+// 设置中的团队托管配置区域：地址、访问令牌与刷新间隔
+const ManagedSourceSettings: React.FC<ManagedSourceSettingsProps> = ({
+  source,
+  onChange,
+  onSynced,
+  onNotify,
+}) => {
+  const { t } = useTranslation();
+  const [syncing, setSyncing] = useState(false);
+  const current: ManagedSource = source ?? { url: "", refreshMinutes: 60 };
+
+  const update = (patch: Partial<ManagedSource>) =>
+    onChange({ ...current, ...patch });
+
+  // 同步使用已保存的设置
+  const handleSyncNow = async () => {
+    setSyncing(true);
+    try {
+      const report = await window.api.refreshManagedProviders();
+      const count = (key: "added" | "updated" | "removed") =>
+        report.claude[key].length + report.codex[key].length;
+      onNotify?.(
+        t("managed.synced", {
+          added: count("added"),
+          updated: count("updated"),
+          removed: count("removed"),
+        }),
+        "success",
+        3000,
+      );
+      await onSynced?.();
+    } catch (error) {
+      onNotify?.(
+        `${t("managed.syncFailed")}: ${extractErrorMessage(error)}`,
+        "error",
+        5000,
+      );
+    } finally {
+      setSyncing(false);
+    }
+  };
+
+  return (
+    <div>
+      <h3 className="text-sm font-medium text-gray-900 dark:text-gray-100 mb-2">
+        {t("managed.title")}
+      </h3>
+      <p className="text-xs text-gray-500 dark:text-gray-400 mb-3 leading-relaxed">
+        {t("managed.description")}
+      </p>
+      <div className="p-4 bg-gray-100 dark:bg-gray-800 rounded-lg space-y-2">
+        <input
+          type="text"
+          value={current.url}
+          onChange={(e) => update({ url: e.target.value })}
+          placeholder={t("managed.urlPlaceholder")}
+          className={`${inputClass} font-mono`}
+        />
+        <input
+          type="password"
+          value={current.token ?? ""}
+          onChange={(e) => update({ token: e.target.value })}
+          placeholder={t("managed.tokenPlaceholder")}
+          className={inputClass}
+        />
+        <div className="flex items-center gap-2">
+          <label className="text-sm text-gray-500 dark:text-gray-400">
+            {t("managed.refreshMinutes")}
+          </label>
+          <input
+            type="number"
+            min="5"
+            step="1"
+            value={current.refreshMinutes}
+            onChange={(e) => {
+              const value = parseInt(e.target.value);
+              if (!isNaN(value) && value > 0) {
+                update({ refreshMinutes: value });
+              }
+            }}
+            className="w-20 px-2 py-1 text-sm bg-white dark:bg-gray-800 border border-gray-200 dark:border-gray-700 rounded-md focus:outline-none focus:ring-2 focus:ring-blue-500/40"
+          />
+        </div>
+        <button
+          type="button"
+          onClick={handleSyncNow}
+          disabled={syncing || !current.url.trim()}
+          className="w-full flex items-center justify-center gap-2 px-3 py-2 text-xs font-medium rounded-lg transition-colors bg-gray-500 hover:bg-gray-600 dark:bg-gray-600 dark:hover:bg-gray-700 text-white disabled:opacity-50"
+        >
+          <CloudDownload size={12} />
+          {t("managed.syncNow")}
+        </button>
+        <p className="text-xs text-gray-400 dark:text-gray-500">
+          {t("managed.syncHint")}
+        </p>
+      </div>
+    </div>
+  );
+};
+
+export default ManagedSourceSettings;
@@ -0,0 +1,150 @@
+import React, { useState } from "react";
+import { useTranslation } from "react-i18next";
+import { Plus, Trash2 } from "lucide-react";
+import { AppType } from "../lib/tauri-api";
+import { MergeStrategies, MergeStrategy } from "../types";
+
+interface MergeStrategiesSettingsProps {
+  strategies?: MergeStrategies;
+  onChange: (strategies: MergeStrategies) => void;
+}
+
+const APPS: AppType[] = ["claude", "codex"];
+
+const STRATEGIES: MergeStrategy[] = ["override", "preserve", "append"];
+
+const selectClass =
+  "px-2 py-1 text-xs bg-white dark:bg-gray-800 border border-gray-200 dark:border-gray-700 rounded-md focus:outline-none focus:ring-2 focus:ring-blue-500/40";
+
+// 设置中的合并策略区域：按键路径指定切换时如何处理 live 中已有的内容
+const MergeStrategiesSettings: React.FC<MergeStrategiesSettingsProps> = ({
+  strategies,
+  onChange,
+}) => {
+  const { t } = useTranslation();
+  const [app, setApp] = useState<AppType>("claude");
+  const [newPath, setNewPath] = useState("");
+  const [newStrategy, setNewStrategy] = useState<MergeStrategy>("preserve");
+  const current: MergeStrategies = strategies ?? { claude: {}, codex: {} };
+  const entries = Object.entries(current[app] ?? {});
+
+  const updateApp = (next: Record<string, MergeStrategy>) =>
+    onChange({ ...current, [app]: next });
+
+  const handleAdd = () => {
+    const path = newPath.trim();
+    if (!path) return;
+    updateApp({ ...current[app], [path]: newStrategy });
+    setNewPath("");
+  };
+
+  const handleRemove = (path: string) => {
+    const next = { ...current[app] };
+    delete next[path];
+    updateApp(next);
+  };
+
+  return (
+    <div>
+      <h3 className="text-sm font-medium text-gray-900 dark:text-gray-100 mb-2">
+        {t("mergeStrategies.title")}
+      </h3>
+      <p className="text-xs text-gray-500 dark:text-gray-400 mb-3 leading-relaxed">
+        {t("mergeStrategies.description")}
+      </p>
+      <div className="p-4 bg-gray-100 dark:bg-gray-800 rounded-lg space-y-2">
+        <div className="flex gap-2">
+          {APPS.map((item) => (
+            <button
+              key={item}
+              type="button"
+              onClick={() => setApp(item)}
+              className={`px-3 py-1 text-xs font-medium rounded-md transition-colors ${
+                app === item
+                  ? "bg-blue-500 text-white"
+                  : "bg-white dark:bg-gray-700 text-gray-600 dark:text-gray-300"
+              }`}
+            >
+              {t(`apps.${item}`)}
+            </button>
+          ))}
+        </div>
+
+        {entries.length === 0 && (
+          <p className="text-xs text-gray-400 dark:text-gray-500">
+            {t("mergeStrategies.empty")}
+          </p>
+        )}
+        {entries.map(([path, strategy]) => (
+          <div key={path} className="flex items-center gap-2 text-xs">
+            <span className="flex-1 font-mono truncate text-gray-700 dark:text-gray-300">
+              {path}
+            </span>
+            <select
+              value={strategy}
+              onChange={(e) =>
+                updateApp({
+                  ...current[app],
+                  [path]: e.target.value as MergeStrategy,
+                })
+              }
+              className={selectClass}
+            >
+              {STRATEGIES.map((item) => (
+                <option key={item} value={item}>
+                  {t(`mergeStrategies.strategies.${item}`)}
+                </option>
+              ))}
+            </select>
+            <button
+              type="button"
+              onClick={() => handleRemove(path)}
+              title={t("common.delete")}
+              className="p-1 rounded-md text-red-500 hover:bg-red-100 dark:hover:bg-red-900/30"
+            >
+              <Trash2 size={12} />
+            </button>
+          </div>
+        ))}
+
+        <div className="flex items-center gap-2">
+          <input
+            type="text"
+            value={newPath}
+            onChange={(e) => setNewPath(e.target.value)}
+            onKeyDown={(e) => {
+              if (e.key === "Enter") handleAdd();
+            }}
+            placeholder={t("mergeStrategies.pathPlaceholder")}
+            className={`flex-1 font-mono ${selectClass}`}
+          />
+          <select
+            value={newStrategy}
+            onChange={(e) => setNewStrategy(e.target.value as MergeStrategy)}
+            className={selectClass}
+          >
+            {STRATEGIES.map((item) => (
+              <option key={item} value={item}>
+                {t(`mergeStrategies.strategies.${item}`)}
+              </option>
+            ))}
+          </select>
+          <button
+            type="button"
+            onClick={handleAdd}
+            disabled={!newPath.trim()}
+            title={t("mergeStrategies.add")}
+            className="p-1 rounded-md text-gray-600 dark:text-gray-300 hover:bg-gray-200 dark:hover:bg-gray-700 disabled:opacity-50"
+          >
+            <Plus size={14} />
+          </button>
+        </div>
+        <p className="text-xs text-gray-400 dark:text-gray-500">
+          {t("mergeStrategies.hint")}
+        </p>
+      </div>
+    </div>
+  );
+};
+
+export default MergeStrategiesSettings;
@@ -0,0 +1,231 @@
+import React, { useEffect, useState } from "react";
+import { useTranslation } from "react-i18next";
+import { X, Plus, FolderOpen, Check, Trash2, Eraser } from "lucide-react";
+import { Provider, ProjectBinding } from "../types";
+import { buttonStyles } from "../lib/styles";
+import { extractErrorMessage } from "../utils/errorUtils";
+
+interface ProjectPanelProps {
+  // 项目级切换仅支持 Claude，供应商来自 Claude 列表
+  providers: Record<string, Provider>;
+  onClose: () => void;
+  onNotify?: (
+    message: string,
+    type: "success" | "error",
+    duration?: number,
+  ) => void;
+}
+
+/**
+ * 项目级切换面板
+ * 将供应商写入项目目录下的 .claude/settings.local.json，全局配置保持不变
+ */
+const ProjectPanel: React.FC<ProjectPanelProps> = ({
+  providers,
+  onClose,
+  onNotify,
+}) => {
+  const { t } = useTranslation();
+  const [projects, setProjects] = useState<ProjectBinding[]>([]);
+  const [loading, setLoading] = useState(true);
+
+  const notifyError = (key: string, error: unknown) => {
+    const detail = extractErrorMessage(error);
+    onNotify?.(detail ? `${t(key)}: ${detail}` : t(key), "error", 5000);
+  };
+
+  useEffect(() => {
+    const load = async () => {
+      try {
+        setProjects(await window.api.getProjects());
+      } catch (error) {
+        notifyError("projects.loadFailed", error);
+      } finally {
+        setLoading(false);
+      }
+    };
+    load();
+  }, []);
+
+  const handleAdd = async () => {
+    const selected = await window.api.selectConfigDirectory();
+    if (!selected || selected.trim() === "") return;
+    try {
+      setProjects(await window.api.addProject(selected.trim()));
+    } catch (error) {
+      notifyError("projects.addFailed", error);
+    }
+  };
+
+  const replaceProject = (binding: ProjectBinding) => {
+    setProjects((prev) =>
+      prev.map((p) => (p.path === binding.path ? binding : p)),
+    );
+  };
+
+  const handleSwitch = async (path: string, providerId: string) => {
+    try {
+      if (providerId) {
+        replaceProject(await window.api.switchProjectProvider(path, providerId));
+        onNotify?.(
+          t("projects.switched", { name: providers[providerId]?.name ?? "" }),
+          "success",
+        );
+      } else {
+        replaceProject(await window.api.clearProjectProvider(path));
+        onNotify?.(t("projects.cleared"), "success");
+      }
+    } catch (error) {
+      notifyError("projects.switchFailed", error);
+    }
+  };
+
+  const handleRemove = async (project: ProjectBinding) => {
+    try {
+      // 移除前先清理已写入的配置，避免项目中残留密钥
+      if (project.providerId) {
+        await window.api.clearProjectProvider(project.path);
+      }
+      setProjects(await window.api.removeProject(project.path));
+    } catch (error) {
+      notifyError("projects.removeFailed", error);
+    }
+  };
+
+  const providerOptions = Object.values(providers).sort((a, b) =>
+    a.name.localeCompare(b.name),
+  );
+
+  return (
+    <div className="fixed inset-0 z-50 flex items-center justify-center">
+      {/* Backdrop */}
+      <div
+        className="absolute inset-0 bg-black/50 backdrop-blur-sm"
+        onClick={onClose}
+      />
+
+      {/* Panel */}
+      <div className="relative bg-white dark:bg-gray-900 rounded-xl shadow-lg max-w-3xl w-full mx-4 overflow-hidden flex flex-col max-h-[85vh] min-h-[400px]">
+        {/* Header */}
+        <div className="flex-shrink-0 flex items-center justify-between p-6 border-b border-gray-200 dark:border-gray-800">
+          <h3 className="text-lg font-semibold text-gray-900 dark:text-gray-100">
+            {t("projects.title")}
+          </h3>
+
+          <div className="flex items-center gap-3">
+            <button
+              onClick={handleAdd}
+              className={`inline-flex items-center gap-2 ${buttonStyles.primary}`}
+            >
+              <Plus size={16} />
+              {t("projects.add")}
+            </button>
+            <button
+              onClick={onClose}
+              className="p-1 text-gray-500 hover:text-gray-900 hover:bg-gray-100 dark:text-gray-400 dark:hover:text-gray-100 dark:hover:bg-gray-800 rounded-md transition-colors"
+            >
+              <X size={18} />
+            </button>
+          </div>
+        </div>
+
+        {/* Info Section */}
+        <div className="flex-shrink-0 px-6 pt-4 pb-2">
+          <p className="text-sm text-gray-500 dark:text-gray-400">
+            {t("projects.description")}
+          </p>
+        </div>
+
+        {/* Content - Scrollable */}
+        <div className="flex-1 overflow-y-auto px-6 py-4">
+          {loading ? (
+            <div className="text-center py-12 text-gray-500 dark:text-gray-400">
+              {t("projects.loading")}
+            </div>
+          ) : projects.length === 0 ? (
+            <div className="text-center py-12">
+              <div className="w-16 h-16 mx-auto mb-4 bg-gray-100 dark:bg-gray-800 rounded-full flex items-center justify-center">
+                <FolderOpen
+                  size={24}
+                  className="text-gray-400 dark:text-gray-500"
+                />
+              </div>
+              <h3 className="text-lg font-medium text-gray-900 dark:text-gray-100 mb-2">
+                {t("projects.empty")}
+              </h3>
+              <p className="text-gray-500 dark:text-gray-400 text-sm">
+                {t("projects.emptyDescription")}
+              </p>
+            </div>
+          ) : (
+            <div className="space-y-3">
+              {projects.map((project) => (
+                <div
+                  key={project.path}
+                  className="flex items-center gap-3 p-4 rounded-lg border border-gray-200 dark:border-gray-700 bg-white dark:bg-gray-800"
+                >
+                  <div className="flex-1 min-w-0">
+                    <div
+                      className="text-sm font-mono text-gray-900 dark:text-gray-100 truncate"
+                      title={project.path}
+                    >
+                      {project.path}
+                    </div>
+                    {project.providerId && !providers[project.providerId] && (
+                      <div className="text-xs text-amber-600 dark:text-amber-400 mt-1">
+                        {t("projects.providerMissing")}
+                      </div>
+                    )}
+                  </div>
+                  <select
+                    value={project.providerId ?? ""}
+                    onChange={(e) => handleSwitch(project.path, e.target.value)}
+                    className="px-3 py-2 text-sm bg-white dark:bg-gray-800 border border-gray-200 dark:border-gray-700 rounded-lg focus:outline-none focus:ring-2 focus:ring-blue-500/40"
+                  >
+                    <option value="">{t("projects.useGlobal")}</option>
+                    {providerOptions.map((provider) => (
+                      <option key={provider.id} value={provider.id}>
+                        {provider.name}
+                      </option>
+                    ))}
+                  </select>
+                  {project.providerId && (
+                    <button
+                      type="button"
+                      onClick={() => handleSwitch(project.path, "")}
+                      className={buttonStyles.icon}
+                      title={t("projects.clear")}
+                    >
+                      <Eraser size={16} />
+                    </button>
+                  )}
+                  <button
+                    type="button"
+                    onClick={() => handleRemove(project)}
+                    className={buttonStyles.icon}
+                    title={t("projects.remove")}
+                  >
+                    <Trash2 size={16} />
+                  </button>
+                </div>
+              ))}
+            </div>
+          )}
+        </div>
+
+        {/* Footer */}
+        <div className="flex-shrink-0 flex items-center justify-end p-6 border-t border-gray-200 dark:border-gray-800 bg-gray-100 dark:bg-gray-800">
+          <button
+            onClick={onClose}
+            className={`inline-flex items-center gap-2 ${buttonStyles.primary}`}
+          >
+            <Check size={16} />
+            {t("common.done")}
+          </button>
+        </div>
+      </div>
+    </div>
+  );
+};
+
+export default ProjectPanel;
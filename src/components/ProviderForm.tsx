@@ -1,7 +1,17 @@
 import React, { useState, useEffect, useRef, useMemo } from "react";
 import { useTranslation } from "react-i18next";
-import { Provider, ProviderCategory, CustomEndpoint } from "../types";
-import { AppType } from "../lib/tauri-api";
+import {
+  ApiFormat,
+  ApplyTarget,
+  Provider,
+  ProviderCategory,
+  CustomEndpoint,
+  RateLimit,
+  RoutingRule,
+  EndpointFailover,
+} from "../types";
+import { AppType, EndpointLatencyResult } from "../lib/tauri-api";
+import { formatHostMapping, parseHostMapping } from "../utils/hostMapping";
 import {
   updateCommonConfigSnippet,
   hasCommonConfigSnippet,
@@ -14,6 +24,7 @@ import {
   applyTemplateValues,
   extractCodexBaseUrl,
   setCodexBaseUrl as setCodexBaseUrlInConfig,
+  setCodexWireApi,
 } from "../utils/providerConfigUtils";
 import { providerPresets } from "../config/providerPresets";
 import type { TemplateValueConfig } from "../config/providerPresets";
@@ -24,11 +35,14 @@ import {
 } from "../config/codexProviderPresets";
 import PresetSelector from "./ProviderForm/PresetSelector";
 import ApiKeyInput from "./ProviderForm/ApiKeyInput";
+import RoutingRulesEditor from "./ProviderForm/RoutingRulesEditor";
 import ClaudeConfigEditor from "./ProviderForm/ClaudeConfigEditor";
 import CodexConfigEditor from "./ProviderForm/CodexConfigEditor";
 import KimiModelSelector from "./ProviderForm/KimiModelSelector";
-import { X, AlertCircle, Save, Zap } from "lucide-react";
-import { isLinux } from "../lib/platform";
+import ProtocolDetector from "./ProviderForm/ProtocolDetector";
+import { X, AlertCircle, Save, Zap, ShieldAlert } from "lucide-react";
+import { isLinux, isWindows } from "../lib/platform";
+import { extractErrorMessage } from "../utils/errorUtils";
 import EndpointSpeedTest, {
   EndpointCandidate,
 } from "./ProviderForm/EndpointSpeedTest";
@@ -36,6 +50,11 @@ import EndpointSpeedTest, {
 
 type TemplateValueMap = Record<string, TemplateValueConfig>;
 
+const APPLY_TARGETS: ApplyTarget[] = ["cline", "roo_code"];
+
+// 可在供应商上标记支持情况的可选接口（与后端 capabilities::endpoint 一致）
+const CAPABILITY_ENDPOINTS = ["count_tokens", "models", "batches"];
+
 type TemplatePath = Array<string | number>;
 
 const collectTemplatePaths = (
@@ -180,6 +199,8 @@ interface ProviderFormProps {
   showPresets?: boolean;
   onSubmit: (data: Omit<Provider, "id">) => void;
   onClose: () => void;
+  // 编辑已有供应商时获取未脱敏的配置（返回 null 表示取消）
+  onRevealSecrets?: () => Promise<Record<string, any> | null>;
 }
 
 const ProviderForm: React.FC<ProviderFormProps> = ({
@@ -190,6 +211,7 @@ const ProviderForm: React.FC<ProviderFormProps> = ({
   showPresets = false,
   onSubmit,
   onClose,
+  onRevealSecrets,
 }) => {
   const { t } = useTranslation();
   // 对于 Codex，需要分离 auth 和 config
@@ -220,10 +242,83 @@ const ProviderForm: React.FC<ProviderFormProps> = ({
   const [category, setCategory] = useState<ProviderCategory | undefined>(
     initialData?.category,
   );
+  const [dashboardQuery, setDashboardQuery] = useState(
+    initialData?.meta?.dashboard_query ?? "",
+  );
+  // 自定义 live 配置目录（如另一套 CLAUDE_CONFIG_DIR 或项目内的 .claude 目录）
+  const [liveConfigDir, setLiveConfigDir] = useState(
+    initialData?.meta?.live_config_dir ?? "",
+  );
+  // 切换时一并写入的 VS Code 扩展（Cline / Roo Code）
+  const [applyTargets, setApplyTargets] = useState<ApplyTarget[]>(
+    initialData?.meta?.apply_targets ?? [],
+  );
+  // 代理限流（留空表示不限制）
+  const [rpmLimit, setRpmLimit] = useState(
+    initialData?.meta?.rate_limit?.requestsPerMinute?.toString() ?? "",
+  );
+  const [tpmLimit, setTpmLimit] = useState(
+    initialData?.meta?.rate_limit?.tokensPerMinute?.toString() ?? "",
+  );
+  // 每百万 token 价格（用量报表估算费用）
+  const [tokenPrice, setTokenPrice] = useState(
+    initialData?.meta?.token_price?.toString() ?? "",
+  );
+  // 可选接口支持情况（未设置的由代理根据 404/405 自动记录）
+  const [capabilities, setCapabilities] = useState<Record<string, boolean>>(
+    initialData?.meta?.capabilities ?? {},
+  );
+  // 供应商内端点故障转移
+  const [endpointFailover, setEndpointFailover] = useState<
+    EndpointFailover | ""
+  >(initialData?.meta?.endpoint_failover ?? "");
+  const [autoSelectEndpoint, setAutoSelectEndpoint] = useState(
+    !!initialData?.meta?.auto_select_endpoint,
+  );
+  // 主机映射（每行一条“主机名 IP”）
+  const [hostMappingText, setHostMappingText] = useState(() =>
+    formatHostMapping(initialData?.meta?.host_mapping),
+  );
+  // 连接主机与 SNI 覆盖
+  const [connectHost, setConnectHost] = useState(
+    initialData?.meta?.connect_host ?? "",
+  );
+  const [sniHost, setSniHost] = useState(initialData?.meta?.sni_host ?? "");
+  const [unixSocket, setUnixSocket] = useState(
+    initialData?.meta?.unix_socket ?? "",
+  );
+  const [acceptInvalidCerts, setAcceptInvalidCerts] = useState(
+    !!initialData?.meta?.accept_invalid_certs,
+  );
+  // mTLS 客户端证书
+  const [clientCertPath, setClientCertPath] = useState(
+    initialData?.meta?.client_cert?.cert ?? "",
+  );
+  const [clientKeyPath, setClientKeyPath] = useState(
+    initialData?.meta?.client_cert?.key ?? "",
+  );
+  const [endpointResults, setEndpointResults] = useState<
+    EndpointLatencyResult[] | null
+  >(null);
+  const [testingEndpoints, setTestingEndpoints] = useState(false);
+  // 上游接口格式（代理模式下按需转换协议）
+  const [apiFormat, setApiFormat] = useState<ApiFormat | "">(
+    initialData?.meta?.api_format ?? "",
+  );
+  // 时间段路由规则
+  const [routingRules, setRoutingRules] = useState<RoutingRule[]>(
+    initialData?.meta?.routing_rules ?? [],
+  );
 
   // Claude 模型配置状态
   const [claudeModel, setClaudeModel] = useState("");
   const [claudeSmallFastModel, setClaudeSmallFastModel] = useState("");
+  // 从模型列表接口发现的模型，用作模型输入提示
+  const [discoveredModels, setDiscoveredModels] = useState<string[]>(
+    initialData?.meta?.discovered_models ?? [],
+  );
+  const [discoveringModels, setDiscoveringModels] = useState(false);
+  const [modelDiscoveryError, setModelDiscoveryError] = useState("");
   const [baseUrl, setBaseUrl] = useState(""); // 新增：基础 URL 状态
   // 模板变量状态
   const [templateValues, setTemplateValues] = useState<
@@ -622,12 +717,81 @@ const ProviderForm: React.FC<ProviderFormProps> = ({
     }
 
     // 构造基础提交数据
+    const trimmedLiveConfigDir = liveConfigDir.trim();
+    const trimmedDashboardQuery = dashboardQuery.trim();
+    const parseLimit = (value: string) => {
+      const parsed = parseInt(value.trim());
+      return !isNaN(parsed) && parsed > 0 ? parsed : undefined;
+    };
+    const rateLimit: RateLimit = {
+      requestsPerMinute: parseLimit(rpmLimit),
+      tokensPerMinute: parseLimit(tpmLimit),
+    };
+    const hasRateLimit =
+      rateLimit.requestsPerMinute !== undefined ||
+      rateLimit.tokensPerMinute !== undefined;
+    const parsedTokenPrice = parseFloat(tokenPrice.trim());
+    const hostMapping = parseHostMapping(hostMappingText);
+    const validTokenPrice =
+      !isNaN(parsedTokenPrice) && parsedTokenPrice >= 0
+        ? parsedTokenPrice
+        : undefined;
+    const withMeta =
+      !!initialData?.meta ||
+      validTokenPrice !== undefined ||
+      Object.keys(capabilities).length > 0 ||
+      !!endpointFailover ||
+      autoSelectEndpoint ||
+      Object.keys(hostMapping).length > 0 ||
+      !!connectHost.trim() ||
+      !!sniHost.trim() ||
+      !!unixSocket.trim() ||
+      discoveredModels.length > 0 ||
+      acceptInvalidCerts ||
+      !!clientCertPath.trim() ||
+      !!trimmedLiveConfigDir ||
+      !!trimmedDashboardQuery ||
+      applyTargets.length > 0 ||
+      hasRateLimit ||
+      routingRules.length > 0 ||
+      !!apiFormat;
     const basePayload: Omit<Provider, "id"> = {
       name: formData.name,
       websiteUrl: formData.websiteUrl,
       settingsConfig,
       // 仅在用户选择了预设或手动选择“自定义”时持久化分类
       ...(category ? { category } : {}),
+      // 携带完整 meta，避免更新时丢失用量脚本等已有配置
+      ...(withMeta
+        ? {
+            meta: {
+              ...initialData?.meta,
+              live_config_dir: trimmedLiveConfigDir || undefined,
+              dashboard_query: trimmedDashboardQuery || undefined,
+              apply_targets: applyTargets.length > 0 ? applyTargets : undefined,
+              rate_limit: hasRateLimit ? rateLimit : undefined,
+              routing_rules: routingRules.length > 0 ? routingRules : undefined,
+              api_format: apiFormat || undefined,
+              token_price: validTokenPrice,
+              capabilities:
+                Object.keys(capabilities).length > 0 ? capabilities : undefined,
+              endpoint_failover: endpointFailover || undefined,
+              auto_select_endpoint: autoSelectEndpoint || undefined,
+              host_mapping:
+                Object.keys(hostMapping).length > 0 ? hostMapping : undefined,
+              connect_host: connectHost.trim() || undefined,
+              sni_host: sniHost.trim() || undefined,
+              unix_socket: unixSocket.trim() || undefined,
+              discovered_models:
+                discoveredModels.length > 0 ? discoveredModels : undefined,
+              accept_invalid_certs: acceptInvalidCerts || undefined,
+              client_cert:
+                clientCertPath.trim() && clientKeyPath.trim()
+                  ? { cert: clientCertPath.trim(), key: clientKeyPath.trim() }
+                  : undefined,
+            },
+          }
+        : {}),
     };
 
     // 若为"新建供应商"，将端点候选一并随提交落盘到 meta.custom_endpoints：
@@ -682,7 +846,10 @@ const ProviderForm: React.FC<ProviderFormProps> = ({
             customMap[url] = { url, addedAt: now, lastUsed: undefined };
           }
         }
-        onSubmit({ ...basePayload, meta: { custom_endpoints: customMap } });
+        onSubmit({
+          ...basePayload,
+          meta: { ...basePayload.meta, custom_endpoints: customMap },
+        });
         return;
       }
     }
@@ -1360,6 +1527,22 @@ const ProviderForm: React.FC<ProviderFormProps> = ({
           codexProviderPresets[selectedCodexPreset]?.category ===
             "third_party")));
 
+  // 发现可用模型：编辑时使用已保存的供应商配置（含 Unix 套接字、客户端证书），新建时使用当前填写的地址
+  const handleDiscoverModels = async () => {
+    setDiscoveringModels(true);
+    setModelDiscoveryError("");
+    try {
+      const models = initialData?.id
+        ? await window.api.discoverProviderModels(initialData.id, appType)
+        : await window.api.discoverModels(baseUrl, apiKey);
+      setDiscoveredModels(models);
+    } catch (error) {
+      setModelDiscoveryError(extractErrorMessage(error));
+    } finally {
+      setDiscoveringModels(false);
+    }
+  };
+
   // 处理模型输入变化，自动更新 JSON 配置
   const handleModelChange = (
     field: "ANTHROPIC_MODEL" | "ANTHROPIC_SMALL_FAST_MODEL",
@@ -1566,11 +1749,468 @@ const ProviderForm: React.FC<ProviderFormProps> = ({
               />
             </div>
 
+            <div className="space-y-2">
+              <label
+                htmlFor="dashboardQuery"
+                className="block text-sm font-medium text-gray-900 dark:text-gray-100"
+              >
+                {t("providerForm.dashboardQuery")}
+              </label>
+              <input
+                type="text"
+                id="dashboardQuery"
+                value={dashboardQuery}
+                onChange={(e) => setDashboardQuery(e.target.value)}
+                placeholder="token={{apiKey}}"
+                autoComplete="off"
+                className="w-full px-3 py-2 border border-gray-200 dark:border-gray-700 dark:bg-gray-800 dark:text-gray-100 rounded-lg text-sm font-mono focus:outline-none focus:ring-2 focus:ring-blue-500/20 dark:focus:ring-blue-400/20 focus:border-blue-500 dark:focus:border-blue-400 transition-colors"
+              />
+              <p className="text-xs text-gray-500 dark:text-gray-400">
+                {t("providerForm.dashboardQueryHint", {
+                  apiKey: "{{apiKey}}",
+                  baseUrl: "{{baseUrl}}",
+                })}
+              </p>
+            </div>
+
+            <div className="space-y-2">
+              <label
+                htmlFor="liveConfigDir"
+                className="block text-sm font-medium text-gray-900 dark:text-gray-100"
+              >
+                {t("providerForm.liveConfigDir")}
+              </label>
+              <input
+                type="text"
+                id="liveConfigDir"
+                value={liveConfigDir}
+                onChange={(e) => setLiveConfigDir(e.target.value)}
+                placeholder={t(
+                  isCodex
+                    ? "providerForm.liveConfigDirPlaceholderCodex"
+                    : "providerForm.liveConfigDirPlaceholderClaude",
+                )}
+                autoComplete="off"
+                className="w-full px-3 py-2 border border-gray-200 dark:border-gray-700 dark:bg-gray-800 dark:text-gray-100 rounded-lg text-sm font-mono focus:outline-none focus:ring-2 focus:ring-blue-500/20 dark:focus:ring-blue-400/20 focus:border-blue-500 dark:focus:border-blue-400 transition-colors"
+              />
+              <p className="text-xs text-gray-500 dark:text-gray-400">
+                {t("providerForm.liveConfigDirHint")}
+              </p>
+            </div>
+
+            <div className="space-y-2">
+              <span className="block text-sm font-medium text-gray-900 dark:text-gray-100">
+                {t("providerForm.applyTargets")}
+              </span>
+              <div className="flex gap-4">
+                {APPLY_TARGETS.map((target) => (
+                  <label
+                    key={target}
+                    className="flex items-center gap-2 text-sm text-gray-700 dark:text-gray-300"
+                  >
+                    <input
+                      type="checkbox"
+                      checked={applyTargets.includes(target)}
+                      onChange={(e) =>
+                        setApplyTargets((prev) =>
+                          e.target.checked
+                            ? [...prev, target]
+                            : prev.filter((item) => item !== target),
+                        )
+                      }
+                      className="w-4 h-4 text-blue-500 rounded focus:ring-blue-500/20"
+                    />
+                    {t(`providerForm.applyTarget.${target}`)}
+                  </label>
+                ))}
+              </div>
+              <p className="text-xs text-gray-500 dark:text-gray-400">
+                {t("providerForm.applyTargetsHint")}
+              </p>
+            </div>
+
+            <div className="space-y-2">
+              <span className="block text-sm font-medium text-gray-900 dark:text-gray-100">
+                {t("providerForm.rateLimit")}
+              </span>
+              <div className="flex gap-2">
+                <input
+                  type="number"
+                  min="1"
+                  value={rpmLimit}
+                  onChange={(e) => setRpmLimit(e.target.value)}
+                  placeholder={t("providerForm.requestsPerMinute")}
+                  className="flex-1 px-3 py-2 border border-gray-200 dark:border-gray-700 dark:bg-gray-800 dark:text-gray-100 rounded-lg text-sm focus:outline-none focus:ring-2 focus:ring-blue-500/20 dark:focus:ring-blue-400/20 focus:border-blue-500 dark:focus:border-blue-400 transition-colors"
+                />
+                <input
+                  type="number"
+                  min="1"
+                  value={tpmLimit}
+                  onChange={(e) => setTpmLimit(e.target.value)}
+                  placeholder={t("providerForm.tokensPerMinute")}
+                  className="flex-1 px-3 py-2 border border-gray-200 dark:border-gray-700 dark:bg-gray-800 dark:text-gray-100 rounded-lg text-sm focus:outline-none focus:ring-2 focus:ring-blue-500/20 dark:focus:ring-blue-400/20 focus:border-blue-500 dark:focus:border-blue-400 transition-colors"
+                />
+              </div>
+              <p className="text-xs text-gray-500 dark:text-gray-400">
+                {t("providerForm.rateLimitHint")}
+              </p>
+            </div>
+
+            <div className="space-y-2">
+              <label
+                htmlFor="tokenPrice"
+                className="block text-sm font-medium text-gray-900 dark:text-gray-100"
+              >
+                {t("providerForm.tokenPrice")}
+              </label>
+              <input
+                type="number"
+                id="tokenPrice"
+                min="0"
+                step="any"
+                value={tokenPrice}
+                onChange={(e) => setTokenPrice(e.target.value)}
+                placeholder={t("providerForm.tokenPricePlaceholder")}
+                className="w-full px-3 py-2 border border-gray-200 dark:border-gray-700 dark:bg-gray-800 dark:text-gray-100 rounded-lg text-sm focus:outline-none focus:ring-2 focus:ring-blue-500/20 dark:focus:ring-blue-400/20 focus:border-blue-500 dark:focus:border-blue-400 transition-colors"
+              />
+              <p className="text-xs text-gray-500 dark:text-gray-400">
+                {t("providerForm.tokenPriceHint")}
+              </p>
+            </div>
+
+            <div className="space-y-2">
+              <span className="block text-sm font-medium text-gray-900 dark:text-gray-100">
+                {t("providerForm.capabilities")}
+              </span>
+              <div className="grid grid-cols-3 gap-2">
+                {CAPABILITY_ENDPOINTS.map((endpoint) => (
+                  <label
+                    key={endpoint}
+                    className="space-y-1 text-xs text-gray-500 dark:text-gray-400"
+                  >
+                    <span className="block font-mono">{endpoint}</span>
+                    <select
+                      value={
+                        endpoint in capabilities
+                          ? String(capabilities[endpoint])
+                          : ""
+                      }
+                      onChange={(e) => {
+                        const next = { ...capabilities };
+                        if (e.target.value === "") {
+                          delete next[endpoint];
+                        } else {
+                          next[endpoint] = e.target.value === "true";
+                        }
+                        setCapabilities(next);
+                      }}
+                      className="w-full px-2 py-1.5 border border-gray-200 dark:border-gray-700 dark:bg-gray-800 dark:text-gray-100 rounded-lg text-sm focus:outline-none focus:ring-2 focus:ring-blue-500/20 dark:focus:ring-blue-400/20 focus:border-blue-500 dark:focus:border-blue-400 transition-colors"
+                    >
+                      <option value="">{t("providerForm.capabilityAuto")}</option>
+                      <option value="true">
+                        {t("providerForm.capabilitySupported")}
+                      </option>
+                      <option value="false">
+                        {t("providerForm.capabilityUnsupported")}
+                      </option>
+                    </select>
+                  </label>
+                ))}
+              </div>
+              <p className="text-xs text-gray-500 dark:text-gray-400">
+                {t("providerForm.capabilitiesHint")}
+              </p>
+            </div>
+
+            <RoutingRulesEditor
+              rules={routingRules}
+              onChange={setRoutingRules}
+            />
+
+            <div className="space-y-2">
+              <label
+                htmlFor="apiFormat"
+                className="block text-sm font-medium text-gray-900 dark:text-gray-100"
+              >
+                {t("providerForm.apiFormat")}
+              </label>
+              <select
+                id="apiFormat"
+                value={apiFormat}
+                onChange={(e) => setApiFormat(e.target.value as ApiFormat | "")}
+                className="w-full px-3 py-2 border border-gray-200 dark:border-gray-700 dark:bg-gray-800 dark:text-gray-100 rounded-lg text-sm focus:outline-none focus:ring-2 focus:ring-blue-500/20 dark:focus:ring-blue-400/20 focus:border-blue-500 dark:focus:border-blue-400 transition-colors"
+              >
+                <option value="">{t("providerForm.apiFormatNative")}</option>
+                {isCodex ? (
+                  <option value="anthropic">
+                    {t("providerForm.apiFormatAnthropic")}
+                  </option>
+                ) : (
+                  <option value="openai_chat">
+                    {t("providerForm.apiFormatOpenaiChat")}
+                  </option>
+                )}
+              </select>
+              <p className="text-xs text-gray-500 dark:text-gray-400">
+                {t(
+                  isCodex
+                    ? "providerForm.apiFormatHintCodex"
+                    : "providerForm.apiFormatHintClaude",
+                )}
+              </p>
+            </div>
+
+            <div className="space-y-2">
+              <label
+                htmlFor="endpointFailover"
+                className="block text-sm font-medium text-gray-900 dark:text-gray-100"
+              >
+                {t("providerForm.endpointFailover")}
+              </label>
+              <div className="flex gap-2">
+                <select
+                  id="endpointFailover"
+                  value={endpointFailover}
+                  onChange={(e) =>
+                    setEndpointFailover(e.target.value as EndpointFailover | "")
+                  }
+                  className="flex-1 px-3 py-2 border border-gray-200 dark:border-gray-700 dark:bg-gray-800 dark:text-gray-100 rounded-lg text-sm focus:outline-none focus:ring-2 focus:ring-blue-500/20 dark:focus:ring-blue-400/20 focus:border-blue-500 dark:focus:border-blue-400 transition-colors"
+                >
+                  <option value="">
+                    {t("providerForm.endpointFailoverOff")}
+                  </option>
+                  <option value="ordered">
+                    {t("providerForm.endpointFailoverOrdered")}
+                  </option>
+                  <option value="latency">
+                    {t("providerForm.endpointFailoverLatency")}
+                  </option>
+                </select>
+                {initialData?.id && (
+                  <button
+                    type="button"
+                    disabled={testingEndpoints}
+                    onClick={async () => {
+                      setTestingEndpoints(true);
+                      try {
+                        setEndpointResults(
+                          await window.api.testProviderEndpoints(
+                            initialData.id,
+                            appType,
+                          ),
+                        );
+                      } catch (error) {
+                        console.error("测试供应商端点失败:", error);
+                        setEndpointResults(null);
+                      } finally {
+                        setTestingEndpoints(false);
+                      }
+                    }}
+                    className="inline-flex items-center gap-1 px-3 py-2 text-sm rounded-lg border border-gray-200 dark:border-gray-700 text-gray-700 dark:text-gray-300 hover:bg-gray-100 dark:hover:bg-gray-800 disabled:opacity-50"
+                  >
+                    <Zap className="h-3.5 w-3.5" />
+                    {t("providerForm.testEndpoints")}
+                  </button>
+                )}
+              </div>
+              {endpointResults && (
+                <ul className="text-xs text-gray-600 dark:text-gray-400 space-y-1">
+                  {endpointResults.map((result) => (
+                    <li
+                      key={result.url}
+                      className="flex justify-between gap-2 font-mono"
+                    >
+                      <span className="truncate">{result.url}</span>
+                      <span
+                        className={
+                          result.latency !== null && !result.error
+                            ? "text-green-600 dark:text-green-400"
+                            : "text-red-500 dark:text-red-400"
+                        }
+                      >
+                        {result.latency !== null && !result.error
+                          ? `${result.latency}ms`
+                          : t("providerForm.endpointUnreachable")}
+                      </span>
+                    </li>
+                  ))}
+                </ul>
+              )}
+              <p className="text-xs text-gray-500 dark:text-gray-400">
+                {t("providerForm.endpointFailoverHint")}
+              </p>
+              <label className="flex items-center gap-2 text-sm text-gray-700 dark:text-gray-300">
+                <input
+                  type="checkbox"
+                  checked={autoSelectEndpoint}
+                  onChange={(e) => setAutoSelectEndpoint(e.target.checked)}
+                  className="w-4 h-4 text-blue-500 rounded focus:ring-blue-500/20"
+                />
+                {t("providerForm.autoSelectEndpoint")}
+              </label>
+              <p className="text-xs text-gray-500 dark:text-gray-400">
+                {t("providerForm.autoSelectEndpointHint")}
+              </p>
+            </div>
+
+            <div className="space-y-2">
+              <label
+                htmlFor="hostMapping"
+                className="block text-sm font-medium text-gray-900 dark:text-gray-100"
+              >
+                {t("providerForm.hostMapping")}
+              </label>
+              <textarea
+                id="hostMapping"
+                rows={2}
+                value={hostMappingText}
+                onChange={(e) => setHostMappingText(e.target.value)}
+                placeholder={t("providerForm.hostMappingPlaceholder")}
+                className="w-full px-3 py-2 border border-gray-200 dark:border-gray-700 dark:bg-gray-800 dark:text-gray-100 rounded-lg text-sm font-mono focus:outline-none focus:ring-2 focus:ring-blue-500/20 dark:focus:ring-blue-400/20 focus:border-blue-500 dark:focus:border-blue-400 transition-colors"
+              />
+              <p className="text-xs text-gray-500 dark:text-gray-400">
+                {t("providerForm.hostMappingHint")}
+              </p>
+            </div>
+
+            <div className="space-y-2">
+              <div className="grid grid-cols-2 gap-2">
+                <div>
+                  <label
+                    htmlFor="connectHost"
+                    className="block text-sm font-medium text-gray-900 dark:text-gray-100 mb-1"
+                  >
+                    {t("providerForm.connectHost")}
+                  </label>
+                  <input
+                    type="text"
+                    id="connectHost"
+                    value={connectHost}
+                    onChange={(e) => setConnectHost(e.target.value)}
+                    placeholder="edge.example.com"
+                    className="w-full px-3 py-2 border border-gray-200 dark:border-gray-700 dark:bg-gray-800 dark:text-gray-100 rounded-lg text-sm focus:outline-none focus:ring-2 focus:ring-blue-500/20 dark:focus:ring-blue-400/20 focus:border-blue-500 dark:focus:border-blue-400 transition-colors"
+                  />
+                </div>
+                <div>
+                  <label
+                    htmlFor="sniHost"
+                    className="block text-sm font-medium text-gray-900 dark:text-gray-100 mb-1"
+                  >
+                    {t("providerForm.sniHost")}
+                  </label>
+                  <input
+                    type="text"
+                    id="sniHost"
+                    value={sniHost}
+                    onChange={(e) => setSniHost(e.target.value)}
+                    placeholder="cdn.example.com"
+                    className="w-full px-3 py-2 border border-gray-200 dark:border-gray-700 dark:bg-gray-800 dark:text-gray-100 rounded-lg text-sm focus:outline-none focus:ring-2 focus:ring-blue-500/20 dark:focus:ring-blue-400/20 focus:border-blue-500 dark:focus:border-blue-400 transition-colors"
+                  />
+                </div>
+              </div>
+              <p className="text-xs text-gray-500 dark:text-gray-400">
+                {t("providerForm.connectHostHint")}
+              </p>
+            </div>
+
+            {!isWindows() && (
+              <div className="space-y-2">
+                <label
+                  htmlFor="unixSocket"
+                  className="block text-sm font-medium text-gray-900 dark:text-gray-100"
+                >
+                  {t("providerForm.unixSocket")}
+                </label>
+                <input
+                  type="text"
+                  id="unixSocket"
+                  value={unixSocket}
+                  onChange={(e) => setUnixSocket(e.target.value)}
+                  placeholder="/tmp/llama.sock"
+                  className="w-full px-3 py-2 border border-gray-200 dark:border-gray-700 dark:bg-gray-800 dark:text-gray-100 rounded-lg text-sm font-mono focus:outline-none focus:ring-2 focus:ring-blue-500/20 dark:focus:ring-blue-400/20 focus:border-blue-500 dark:focus:border-blue-400 transition-colors"
+                />
+                <p className="text-xs text-gray-500 dark:text-gray-400">
+                  {t("providerForm.unixSocketHint")}
+                </p>
+              </div>
+            )}
+
+            <div className="space-y-2">
+              <label className="flex items-center gap-2 text-sm text-gray-700 dark:text-gray-300">
+                <input
+                  type="checkbox"
+                  checked={acceptInvalidCerts}
+                  onChange={(e) => setAcceptInvalidCerts(e.target.checked)}
+                  className="w-4 h-4 text-blue-500 rounded focus:ring-blue-500/20"
+                />
+                {t("providerForm.acceptInvalidCerts")}
+              </label>
+              {acceptInvalidCerts && (
+                <div className="flex items-start gap-2 p-3 rounded-lg bg-red-50 dark:bg-red-900/20 border border-red-200 dark:border-red-800 text-xs text-red-700 dark:text-red-300">
+                  <ShieldAlert size={14} className="flex-shrink-0 mt-0.5" />
+                  <span>{t("providerForm.acceptInvalidCertsWarning")}</span>
+                </div>
+              )}
+            </div>
+
+            <div className="space-y-2">
+              <div className="grid grid-cols-2 gap-2">
+                <div>
+                  <label
+                    htmlFor="clientCertPath"
+                    className="block text-sm font-medium text-gray-900 dark:text-gray-100 mb-1"
+                  >
+                    {t("providerForm.clientCert")}
+                  </label>
+                  <input
+                    type="text"
+                    id="clientCertPath"
+                    value={clientCertPath}
+                    onChange={(e) => setClientCertPath(e.target.value)}
+                    placeholder="/path/to/client.crt"
+                    className="w-full px-3 py-2 border border-gray-200 dark:border-gray-700 dark:bg-gray-800 dark:text-gray-100 rounded-lg text-sm font-mono focus:outline-none focus:ring-2 focus:ring-blue-500/20 dark:focus:ring-blue-400/20 focus:border-blue-500 dark:focus:border-blue-400 transition-colors"
+                  />
+                </div>
+                <div>
+                  <label
+                    htmlFor="clientKeyPath"
+                    className="block text-sm font-medium text-gray-900 dark:text-gray-100 mb-1"
+                  >
+                    {t("providerForm.clientKey")}
+                  </label>
+                  <input
+                    type="text"
+                    id="clientKeyPath"
+                    value={clientKeyPath}
+                    onChange={(e) => setClientKeyPath(e.target.value)}
+                    placeholder="keychain:corp-gateway-key"
+                    className="w-full px-3 py-2 border border-gray-200 dark:border-gray-700 dark:bg-gray-800 dark:text-gray-100 rounded-lg text-sm font-mono focus:outline-none focus:ring-2 focus:ring-blue-500/20 dark:focus:ring-blue-400/20 focus:border-blue-500 dark:focus:border-blue-400 transition-colors"
+                  />
+                </div>
+              </div>
+              {!!clientCertPath.trim() !== !!clientKeyPath.trim() && (
+                <p className="text-xs text-amber-600 dark:text-amber-400">
+                  {t("providerForm.clientCertIncomplete")}
+                </p>
+              )}
+              <p className="text-xs text-gray-500 dark:text-gray-400">
+                {t("providerForm.clientCertHint")}
+              </p>
+            </div>
+
             {!isCodex && showApiKey && (
               <div className="space-y-1">
                 <ApiKeyInput
                   value={apiKey}
                   onChange={handleApiKeyChange}
+                  onReveal={
+                    onRevealSecrets &&
+                    (async () => {
+                      const settings = await onRevealSecrets();
+                      const env = settings?.env;
+                      return env?.ANTHROPIC_AUTH_TOKEN ?? env?.ANTHROPIC_API_KEY ?? null;
+                    })
+                  }
                   required={!isOfficialPreset}
                   placeholder={
                     isOfficialPreset
@@ -1696,6 +2336,15 @@ const ProviderForm: React.FC<ProviderFormProps> = ({
                   autoComplete="off"
                   className="w-full px-3 py-2 border border-gray-200 dark:border-gray-700 dark:bg-gray-800 dark:text-gray-100 rounded-lg text-sm focus:outline-none focus:ring-2 focus:ring-blue-500/20 dark:focus:ring-blue-400/20 focus:border-blue-500 dark:focus:border-blue-400 transition-colors"
                 />
+                <ProtocolDetector
+                  appType={appType}
+                  baseUrl={baseUrl}
+                  apiKey={apiKey}
+                  onApply={(suggestion) => {
+                    handleBaseUrlChange(suggestion.baseUrl);
+                    setApiFormat(suggestion.apiFormat ?? "");
+                  }}
+                />
                 <div className="p-3 bg-amber-50 dark:bg-amber-900/20 border border-amber-200 dark:border-amber-700 rounded-lg">
                   <p className="text-xs text-amber-600 dark:text-amber-400">
                     {t("providerForm.apiHint")}
@@ -1735,6 +2384,13 @@ const ProviderForm: React.FC<ProviderFormProps> = ({
                   label="API Key"
                   value={codexApiKey}
                   onChange={handleCodexApiKeyChange}
+                  onReveal={
+                    onRevealSecrets &&
+                    (async () => {
+                      const settings = await onRevealSecrets();
+                      return settings?.auth?.OPENAI_API_KEY ?? null;
+                    })
+                  }
                   placeholder={
                     isCodexOfficialPreset
                       ? t("providerForm.codexOfficialNoApiKey")
@@ -1789,6 +2445,19 @@ const ProviderForm: React.FC<ProviderFormProps> = ({
                   autoComplete="off"
                   className="w-full px-3 py-2 border border-gray-200 dark:border-gray-700 dark:bg-gray-800 dark:text-gray-100 rounded-lg text-sm focus:outline-none focus:ring-2 focus:ring-blue-500/20 dark:focus:ring-blue-400/20 focus:border-blue-500 dark:focus:border-blue-400 transition-colors"
                 />
+                <ProtocolDetector
+                  appType={appType}
+                  baseUrl={codexBaseUrl}
+                  apiKey={codexApiKey}
+                  onApply={(suggestion) => {
+                    handleCodexBaseUrlChange(suggestion.baseUrl);
+                    if (suggestion.wireApi) {
+                      const wireApi = suggestion.wireApi;
+                      setCodexConfig((prev) => setCodexWireApi(prev, wireApi));
+                    }
+                    setApiFormat(suggestion.apiFormat ?? "");
+                  }}
+                />
                 <div className="p-3 bg-amber-50 dark:bg-amber-900/20 border border-amber-200 dark:border-amber-700 rounded-lg">
                   <p className="text-xs text-amber-600 dark:text-amber-400">
                     {t("providerForm.codexApiHint")}
@@ -1876,6 +2545,7 @@ const ProviderForm: React.FC<ProviderFormProps> = ({
                             handleModelChange("ANTHROPIC_MODEL", e.target.value)
                           }
                           placeholder={t("providerForm.mainModelPlaceholder")}
+                          list="discovered-models"
                           autoComplete="off"
                           className="w-full px-3 py-2 border border-gray-200 dark:border-gray-700 dark:bg-gray-800 dark:text-gray-100 rounded-lg text-sm focus:outline-none focus:ring-2 focus:ring-blue-500/20 dark:focus:ring-blue-400/20 focus:border-blue-500 dark:focus:border-blue-400 transition-colors"
                         />
@@ -1899,12 +2569,47 @@ const ProviderForm: React.FC<ProviderFormProps> = ({
                             )
                           }
                           placeholder={t("providerForm.fastModelPlaceholder")}
+                          list="discovered-models"
                           autoComplete="off"
                           className="w-full px-3 py-2 border border-gray-200 dark:border-gray-700 dark:bg-gray-800 dark:text-gray-100 rounded-lg text-sm focus:outline-none focus:ring-2 focus:ring-blue-500/20 dark:focus:ring-blue-400/20 focus:border-blue-500 dark:focus:border-blue-400 transition-colors"
                         />
                       </div>
                     </div>
 
+                    <datalist id="discovered-models">
+                      {discoveredModels.map((model) => (
+                        <option key={model} value={model} />
+                      ))}
+                    </datalist>
+                    <div className="flex items-center gap-3">
+                      <button
+                        type="button"
+                        disabled={
+                          discoveringModels ||
+                          (!initialData?.id && !baseUrl.trim())
+                        }
+                        onClick={handleDiscoverModels}
+                        className="px-3 py-1.5 text-xs font-medium rounded-md bg-gray-100 dark:bg-gray-800 text-gray-700 dark:text-gray-300 hover:bg-gray-200 dark:hover:bg-gray-700 disabled:opacity-50 transition-colors"
+                      >
+                        {discoveringModels
+                          ? t("providerForm.discoveringModels")
+                          : t("providerForm.discoverModels")}
+                      </button>
+                      {modelDiscoveryError ? (
+                        <span className="text-xs text-red-500 dark:text-red-400 truncate">
+                          {modelDiscoveryError}
+                        </span>
+                      ) : (
+                        discoveredModels.length > 0 && (
+                          <span className="text-xs text-gray-500 dark:text-gray-400">
+                            {t("providerForm.discoveredModels", {
+                              count: discoveredModels.length,
+                            })}
+                          </span>
+                        )
+                      )}
+                    </div>
+
                     <div className="p-3 bg-amber-50 dark:bg-amber-900/20 border border-amber-200 dark:border-amber-700 rounded-lg">
                       <p className="text-xs text-amber-600 dark:text-amber-400">
                         {t("providerForm.modelHint")}
@@ -1,6 +1,7 @@
 import React, { useState } from "react";
 import { Eye, EyeOff } from "lucide-react";
 import { useTranslation } from "react-i18next";
+import { isMaskedSecret } from "../../utils/providerConfigUtils";
 
 interface ApiKeyInputProps {
   value: string;
@@ -10,6 +11,8 @@ interface ApiKeyInputProps {
   required?: boolean;
   label?: string;
   id?: string;
+  // 值为脱敏掩码时，点击显示改为向后端请求明文（返回 null 表示取消）
+  onReveal?: () => Promise<string | null>;
 }
 
 const ApiKeyInput: React.FC<ApiKeyInputProps> = ({
@@ -20,11 +23,17 @@ const ApiKeyInput: React.FC<ApiKeyInputProps> = ({
   required = false,
   label = "API Key",
   id = "apiKey",
+  onReveal,
 }) => {
   const { t } = useTranslation();
   const [showKey, setShowKey] = useState(false);
 
-  const toggleShowKey = () => {
+  const toggleShowKey = async () => {
+    if (!showKey && onReveal && isMaskedSecret(value)) {
+      const revealed = await onReveal();
+      if (revealed === null) return;
+      onChange(revealed);
+    }
     setShowKey(!showKey);
   };
 
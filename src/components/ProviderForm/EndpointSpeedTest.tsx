@@ -4,6 +4,7 @@ import { Zap, Loader2, Plus, X, AlertCircle, Save } from "lucide-react";
 import { isLinux } from "../../lib/platform";
 
 import type { AppType } from "../../lib/tauri-api";
+import { extractErrorMessage } from "../../utils/errorUtils";
 
 export interface EndpointCandidate {
   id?: string;
@@ -276,7 +277,7 @@ const EndpointSpeedTest: React.FC<EndpointSpeedTestProps> = ({
 
       setCustomUrl("");
     } catch (error) {
-      const message = error instanceof Error ? error.message : String(error);
+      const message = extractErrorMessage(error);
       setAddError(message || t("endpointTest.saveFailed"));
       console.error(t("endpointTest.addEndpointFailed"), error);
     }
@@ -386,7 +387,7 @@ const EndpointSpeedTest: React.FC<EndpointSpeedTestProps> = ({
       const message =
         error instanceof Error
           ? error.message
-          : `${t("endpointTest.testFailed", { error: String(error) })}`;
+          : t("endpointTest.testFailed", { error: extractErrorMessage(error) });
       setLastError(message);
     } finally {
       setIsTesting(false);
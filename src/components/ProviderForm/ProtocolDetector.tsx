@@ -0,0 +1,126 @@
+import React, { useState } from "react";
+import { useTranslation } from "react-i18next";
+import { Check, Loader2, Radar, X } from "lucide-react";
+
+import type { AppType } from "../../lib/tauri-api";
+import type { ProtocolDetection, ProtocolSuggestion } from "../../types";
+import { extractErrorMessage } from "../../utils/errorUtils";
+
+interface ProtocolDetectorProps {
+  appType: AppType;
+  baseUrl: string;
+  apiKey: string;
+  // 应用一条与当前应用类型匹配的建议
+  onApply: (suggestion: ProtocolSuggestion) => void;
+}
+
+// 检测地址支持的接口格式（Anthropic / Chat Completions / Responses），并按当前应用给出配置建议
+const ProtocolDetector: React.FC<ProtocolDetectorProps> = ({
+  appType,
+  baseUrl,
+  apiKey,
+  onApply,
+}) => {
+  const { t } = useTranslation();
+  const [detecting, setDetecting] = useState(false);
+  const [result, setResult] = useState<ProtocolDetection | null>(null);
+  const [error, setError] = useState("");
+
+  const handleDetect = async () => {
+    setDetecting(true);
+    setError("");
+    try {
+      setResult(await window.api.probeEndpoint(baseUrl, apiKey));
+    } catch (err) {
+      setResult(null);
+      setError(extractErrorMessage(err));
+    } finally {
+      setDetecting(false);
+    }
+  };
+
+  const suggestions =
+    result?.suggestions.filter((s) => s.appType === appType) ?? [];
+
+  return (
+    <div className="space-y-2">
+      <button
+        type="button"
+        onClick={handleDetect}
+        disabled={detecting || !baseUrl.trim()}
+        className="flex items-center gap-1 text-xs text-gray-600 dark:text-gray-400 hover:text-gray-900 dark:hover:text-gray-100 disabled:opacity-50 transition-colors"
+      >
+        {detecting ? (
+          <Loader2 className="h-3.5 w-3.5 animate-spin" />
+        ) : (
+          <Radar className="h-3.5 w-3.5" />
+        )}
+        {t("protocolDetect.detect")}
+      </button>
+
+      {error && (
+        <p className="text-xs text-red-500 dark:text-red-400">{error}</p>
+      )}
+
+      {result && (
+        <div className="p-3 rounded-lg bg-gray-50 dark:bg-gray-800/60 border border-gray-200 dark:border-gray-700 space-y-2">
+          <div className="flex flex-wrap gap-3">
+            {result.checks.map((check) => (
+              <span
+                key={check.protocol}
+                title={check.error ?? check.url}
+                className={`flex items-center gap-1 text-xs ${
+                  check.supported
+                    ? "text-green-600 dark:text-green-400"
+                    : "text-gray-400 dark:text-gray-500"
+                }`}
+              >
+                {check.supported ? <Check size={12} /> : <X size={12} />}
+                {t(`protocolDetect.protocols.${check.protocol}`)}
+              </span>
+            ))}
+          </div>
+          {suggestions.length === 0 ? (
+            <p className="text-xs text-amber-600 dark:text-amber-400">
+              {t("protocolDetect.noSuggestion")}
+            </p>
+          ) : (
+            suggestions.map((suggestion, index) => (
+              <div
+                key={index}
+                className="flex items-center justify-between gap-2 text-xs text-gray-700 dark:text-gray-300"
+              >
+                <span className="truncate">
+                  {suggestion.apiFormat
+                    ? t("protocolDetect.suggestTranslated", {
+                        url: suggestion.baseUrl,
+                        format: t(
+                          `protocolDetect.formats.${suggestion.apiFormat}`,
+                        ),
+                      })
+                    : suggestion.wireApi
+                      ? t("protocolDetect.suggestWireApi", {
+                          url: suggestion.baseUrl,
+                          wireApi: suggestion.wireApi,
+                        })
+                      : t("protocolDetect.suggestNative", {
+                          url: suggestion.baseUrl,
+                        })}
+                </span>
+                <button
+                  type="button"
+                  onClick={() => onApply(suggestion)}
+                  className="flex-shrink-0 px-2 py-1 rounded bg-blue-500 text-white hover:bg-blue-600 transition-colors"
+                >
+                  {t("protocolDetect.apply")}
+                </button>
+              </div>
+            ))
+          )}
+        </div>
+      )}
+    </div>
+  );
+};
+
+export default ProtocolDetector;
@@ -0,0 +1,122 @@
+import React from "react";
+import { useTranslation } from "react-i18next";
+import { Plus, Trash2 } from "lucide-react";
+import { RoutingEffect, RoutingRule } from "../../types";
+
+interface RoutingRulesEditorProps {
+  rules: RoutingRule[];
+  onChange: (rules: RoutingRule[]) => void;
+}
+
+const DAYS = [1, 2, 3, 4, 5, 6, 7];
+
+const EFFECTS: RoutingEffect[] = ["prefer", "avoid", "only", "exclude"];
+
+const fieldClass =
+  "px-2 py-1 text-xs border border-gray-200 dark:border-gray-700 dark:bg-gray-800 dark:text-gray-100 rounded-md focus:outline-none focus:ring-2 focus:ring-blue-500/20";
+
+// 供应商的时间段路由规则：星期 + 时间段 + 效果
+const RoutingRulesEditor: React.FC<RoutingRulesEditorProps> = ({
+  rules,
+  onChange,
+}) => {
+  const { t } = useTranslation();
+
+  const update = (index: number, patch: Partial<RoutingRule>) =>
+    onChange(
+      rules.map((rule, i) => (i === index ? { ...rule, ...patch } : rule)),
+    );
+
+  const toggleDay = (index: number, day: number) => {
+    const days = rules[index].days;
+    update(index, {
+      days: days.includes(day)
+        ? days.filter((d) => d !== day)
+        : [...days, day].sort(),
+    });
+  };
+
+  return (
+    <div className="space-y-2">
+      <span className="block text-sm font-medium text-gray-900 dark:text-gray-100">
+        {t("providerForm.routingRules")}
+      </span>
+      {rules.map((rule, index) => (
+        <div
+          key={index}
+          className="p-2 space-y-2 rounded-lg border border-gray-200 dark:border-gray-700"
+        >
+          <div className="flex flex-wrap gap-2">
+            {DAYS.map((day) => (
+              <label
+                key={day}
+                className="flex items-center gap-1 text-xs text-gray-700 dark:text-gray-300"
+              >
+                <input
+                  type="checkbox"
+                  checked={rule.days.includes(day)}
+                  onChange={() => toggleDay(index, day)}
+                />
+                {t(`providerForm.weekdays.${day}`)}
+              </label>
+            ))}
+          </div>
+          <div className="flex items-center gap-2">
+            <input
+              type="time"
+              value={rule.start}
+              onChange={(e) => update(index, { start: e.target.value })}
+              className={fieldClass}
+            />
+            <span className="text-xs text-gray-500">–</span>
+            <input
+              type="time"
+              value={rule.end}
+              onChange={(e) => update(index, { end: e.target.value })}
+              className={fieldClass}
+            />
+            <select
+              value={rule.effect}
+              onChange={(e) =>
+                update(index, { effect: e.target.value as RoutingEffect })
+              }
+              className={`flex-1 ${fieldClass}`}
+            >
+              {EFFECTS.map((effect) => (
+                <option key={effect} value={effect}>
+                  {t(`providerForm.routingEffects.${effect}`)}
+                </option>
+              ))}
+            </select>
+            <button
+              type="button"
+              onClick={() => onChange(rules.filter((_, i) => i !== index))}
+              title={t("common.delete")}
+              className="p-1 rounded-md text-red-500 hover:bg-red-100 dark:hover:bg-red-900/30"
+            >
+              <Trash2 size={12} />
+            </button>
+          </div>
+        </div>
+      ))}
+      <button
+        type="button"
+        onClick={() =>
+          onChange([
+            ...rules,
+            { days: [], start: "00:00", end: "08:00", effect: "prefer" },
+          ])
+        }
+        className="flex items-center gap-1 text-xs text-blue-500 hover:text-blue-600"
+      >
+        <Plus size={12} />
+        {t("providerForm.addRoutingRule")}
+      </button>
+      <p className="text-xs text-gray-500 dark:text-gray-400">
+        {t("providerForm.routingRulesHint")}
+      </p>
+    </div>
+  );
+};
+
+export default RoutingRulesEditor;
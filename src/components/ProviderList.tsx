@@ -1,11 +1,14 @@
-import React, { useState } from "react";
+import React, { useEffect, useState } from "react";
 import { useTranslation } from "react-i18next";
 import { Provider, UsageScript } from "../types";
 import { AppType } from "../lib/tauri-api";
-import { Play, Edit3, Trash2, CheckCircle2, Users, Check, BarChart3, GripVertical } from "lucide-react";
+import { Play, Edit3, Trash2, CheckCircle2, Users, Check, BarChart3, GripVertical, Share2, Power, PowerOff, Search, Lock, LockOpen, Pin, PinOff, Wrench, ShieldAlert } from "lucide-react";
 import { buttonStyles, badgeStyles, cn } from "../lib/styles";
+import { ensureAppUnlocked } from "../lib/appLock";
+import { extractErrorMessage } from "../utils/errorUtils";
 import UsageFooter from "./UsageFooter";
 import UsageScriptModal from "./UsageScriptModal";
+import ProviderShareModal from "./ProviderShareModal";
 import {
   DndContext,
   closestCenter,
@@ -25,6 +28,9 @@ import {
 import { CSS } from "@dnd-kit/utilities";
 // 不再在列表中显示分类徽章，避免造成困惑
 
+// 维护模式可选时长（分钟）
+const MAINTENANCE_DURATIONS = [30, 60, 120, 240, 480];
+
 interface ProviderListProps {
   providers: Record<string, Provider>;
   currentProviderId: string;
@@ -50,10 +56,16 @@ interface SortableProviderItemProps {
   onEdit: (id: string) => void;
   onDelete: (id: string) => void;
   onOpenUsageModal: (id: string) => void;
-  onUrlClick: (url: string) => Promise<void>;
+  onShare: (id: string) => void;
+  onWebsiteClick: (providerId: string) => Promise<void>;
   appType: AppType;
   operationMode: "write" | "proxy";
   onToggleProxy?: (id: string, enabled: boolean) => Promise<void>;
+  onToggleEnabled: (id: string, enabled: boolean) => Promise<void>;
+  onToggleLocked: (id: string, locked: boolean) => Promise<void>;
+  onTogglePinned: (id: string, pinned: boolean) => Promise<void>;
+  onSetMaintenance: (id: string, minutes: number) => Promise<void>;
+  dragDisabled: boolean;
   t: any;
 }
 
@@ -65,12 +77,26 @@ const SortableProviderItem: React.FC<SortableProviderItemProps> = ({
   onEdit,
   onDelete,
   onOpenUsageModal,
-  onUrlClick,
+  onShare,
+  onWebsiteClick,
   appType,
   operationMode,
   onToggleProxy,
+  onToggleEnabled,
+  onToggleLocked,
+  onTogglePinned,
+  onSetMaintenance,
+  dragDisabled,
   t,
 }) => {
+  const isEnabled = provider.enabled !== false;
+  const isLocked = provider.locked === true;
+  const isPinned = provider.pinned === true;
+  const inMaintenance =
+    provider.maintenanceUntil !== undefined &&
+    provider.maintenanceUntil > Date.now();
+  const [maintenanceMenuOpen, setMaintenanceMenuOpen] = useState(false);
+  const isManaged = provider.meta?.managed === true;
   const {
     attributes,
     listeners,
@@ -80,6 +106,7 @@ const SortableProviderItem: React.FC<SortableProviderItemProps> = ({
   } = useSortable({
     id: provider.id,
     animateLayoutChanges: () => false, // Disable layout animations
+    disabled: dragDisabled, // 过滤结果只是部分列表，禁止拖拽排序
   });
 
   const style: React.CSSProperties = {
@@ -104,6 +131,8 @@ const SortableProviderItem: React.FC<SortableProviderItemProps> = ({
         !isDragging && !(operationMode === "write" && isCurrent) && "hover:border-gray-300 hover:shadow-sm dark:hover:border-gray-600",
         // Shadow during drag
         isDragging && "shadow-lg",
+        // 已停用的供应商弱化显示
+        !isEnabled && "opacity-60",
         // Only apply transition when not dragging to prevent conflicts
         !isDragging && "transition-[border-color,box-shadow] duration-200"
       )}
@@ -124,6 +153,57 @@ const SortableProviderItem: React.FC<SortableProviderItemProps> = ({
             <h3 className="font-medium text-gray-900 dark:text-gray-100">
               {provider.name}
             </h3>
+            {!isEnabled && (
+              <div className={badgeStyles.warning}>{t("provider.disabled")}</div>
+            )}
+            {inMaintenance && (
+              <div
+                className={badgeStyles.warning}
+                title={t("provider.maintenanceHint")}
+              >
+                <Wrench size={12} />
+                {t("provider.maintenanceUntil", {
+                  time: new Date(provider.maintenanceUntil!).toLocaleTimeString(
+                    [],
+                    { hour: "2-digit", minute: "2-digit" },
+                  ),
+                })}
+              </div>
+            )}
+            {provider.meta?.accept_invalid_certs && (
+              <div
+                className={badgeStyles.error}
+                title={t("provider.insecureTlsHint")}
+              >
+                <ShieldAlert size={12} />
+                {t("provider.insecureTls")}
+              </div>
+            )}
+            {operationMode === "proxy" && isPinned && (
+              <div className={badgeStyles.info} title={t("provider.pinnedHint")}>
+                <Pin size={12} />
+                {t("provider.pinned")}
+              </div>
+            )}
+            {isManaged ? (
+              <div className={badgeStyles.info} title={t("managed.badgeHint")}>
+                <Lock size={12} />
+                {t("managed.badge")}
+              </div>
+            ) : (
+              isLocked && (
+                <div className={badgeStyles.info}>
+                  <Lock size={12} />
+                  {t("provider.locked")}
+                </div>
+              )
+            )}
+            {provider.meta?.credential_kind === "claude_oauth" && (
+              <div className={badgeStyles.info}>{t("oauth.badge")}</div>
+            )}
+            {provider.meta?.credential_kind === "codex_oauth" && (
+              <div className={badgeStyles.info}>{t("oauth.chatgptBadge")}</div>
+            )}
             {operationMode === "write" && (
               <div
                 className={cn(
@@ -142,7 +222,7 @@ const SortableProviderItem: React.FC<SortableProviderItemProps> = ({
               <button
                 onClick={(e) => {
                   e.preventDefault();
-                  onUrlClick(provider.websiteUrl!);
+                  onWebsiteClick(provider.id);
                 }}
                 className="inline-flex items-center gap-1 text-blue-500 dark:text-blue-400 hover:opacity-90 transition-colors"
                 title={t("providerForm.visitWebsite", {
@@ -170,8 +250,16 @@ const SortableProviderItem: React.FC<SortableProviderItemProps> = ({
                 const newEnabled = !provider.proxyEnabled;
                 onToggleProxy?.(provider.id, newEnabled);
               }}
+              disabled={
+                !isEnabled || provider.meta?.credential_kind === "codex_oauth"
+              }
+              title={
+                provider.meta?.credential_kind === "codex_oauth"
+                  ? t("oauth.proxyUnsupported")
+                  : undefined
+              }
               className={cn(
-                "inline-flex items-center gap-1.5 px-3 py-1.5 text-sm font-medium rounded-md transition-colors min-w-[100px] justify-center whitespace-nowrap",
+                "inline-flex items-center gap-1.5 px-3 py-1.5 text-sm font-medium rounded-md transition-colors min-w-[100px] justify-center whitespace-nowrap disabled:opacity-50 disabled:cursor-not-allowed",
                 provider.proxyEnabled
                   ? "bg-green-500 text-white hover:bg-green-600 dark:bg-green-600 dark:hover:bg-green-700"
                   : "bg-gray-500 text-white hover:bg-gray-600 dark:bg-gray-600 dark:hover:bg-gray-700",
@@ -184,10 +272,10 @@ const SortableProviderItem: React.FC<SortableProviderItemProps> = ({
             // 写入模式：显示启用按钮（单选）
             <button
               onClick={() => onSwitch(provider.id)}
-              disabled={isCurrent}
+              disabled={isCurrent || !isEnabled}
               className={cn(
                 "inline-flex items-center gap-1.5 px-3 py-1.5 text-sm font-medium rounded-md transition-colors w-[90px] justify-center whitespace-nowrap",
-                isCurrent
+                isCurrent || !isEnabled
                   ? "bg-gray-100 text-gray-400 dark:bg-gray-800 dark:text-gray-500 cursor-not-allowed"
                   : "bg-blue-500 text-white hover:bg-blue-600 dark:bg-blue-600 dark:hover:bg-blue-700",
               )}
@@ -197,10 +285,67 @@ const SortableProviderItem: React.FC<SortableProviderItemProps> = ({
             </button>
           )}
 
+          {operationMode === "proxy" && (
+            <button
+              onClick={() => onTogglePinned(provider.id, !isPinned)}
+              className={buttonStyles.icon}
+              title={
+                isPinned ? t("provider.unpinProvider") : t("provider.pinProvider")
+              }
+            >
+              {isPinned ? <PinOff size={16} /> : <Pin size={16} />}
+            </button>
+          )}
+
+          <div className="relative">
+            <button
+              onClick={() =>
+                inMaintenance
+                  ? onSetMaintenance(provider.id, 0)
+                  : setMaintenanceMenuOpen((open) => !open)
+              }
+              className={cn(
+                buttonStyles.icon,
+                inMaintenance && "text-amber-500 dark:text-amber-400",
+              )}
+              title={
+                inMaintenance
+                  ? t("provider.endMaintenance")
+                  : t("provider.startMaintenance")
+              }
+            >
+              <Wrench size={16} />
+            </button>
+            {maintenanceMenuOpen && (
+              <div className="absolute right-0 top-full mt-1 z-20 min-w-[120px] py-1 bg-white dark:bg-gray-800 border border-gray-200 dark:border-gray-700 rounded-md shadow-lg">
+                {MAINTENANCE_DURATIONS.map((minutes) => (
+                  <button
+                    key={minutes}
+                    onClick={() => {
+                      setMaintenanceMenuOpen(false);
+                      onSetMaintenance(provider.id, minutes);
+                    }}
+                    className="w-full text-left px-3 py-1.5 text-sm text-gray-700 dark:text-gray-300 hover:bg-gray-100 dark:hover:bg-gray-700"
+                  >
+                    {minutes < 60
+                      ? t("provider.maintenanceMinutes", { count: minutes })
+                      : t("provider.maintenanceHours", { count: minutes / 60 })}
+                  </button>
+                ))}
+              </div>
+            )}
+          </div>
+
           <button
             onClick={() => onEdit(provider.id)}
-            className={buttonStyles.icon}
-            title={t("provider.editProvider")}
+            disabled={isLocked}
+            className={cn(
+              buttonStyles.icon,
+              isLocked && "opacity-50 cursor-not-allowed",
+            )}
+            title={
+              isLocked ? t("provider.lockedHint") : t("provider.editProvider")
+            }
           >
             <Edit3 size={16} />
           </button>
@@ -213,12 +358,46 @@ const SortableProviderItem: React.FC<SortableProviderItemProps> = ({
             <BarChart3 size={16} />
           </button>
 
+          <button
+            onClick={() => onToggleEnabled(provider.id, !isEnabled)}
+            className={buttonStyles.icon}
+            title={
+              isEnabled
+                ? t("provider.disableProvider")
+                : t("provider.enableProvider")
+            }
+          >
+            {isEnabled ? <PowerOff size={16} /> : <Power size={16} />}
+          </button>
+
+          {!isManaged && (
+            <button
+              onClick={() => onToggleLocked(provider.id, !isLocked)}
+              className={buttonStyles.icon}
+              title={
+                isLocked
+                  ? t("provider.unlockProvider")
+                  : t("provider.lockProvider")
+              }
+            >
+              {isLocked ? <LockOpen size={16} /> : <Lock size={16} />}
+            </button>
+          )}
+
+          <button
+            onClick={() => onShare(provider.id)}
+            className={buttonStyles.icon}
+            title={t("share.shareProvider")}
+          >
+            <Share2 size={16} />
+          </button>
+
           <button
             onClick={() => onDelete(provider.id)}
-            disabled={isCurrent}
+            disabled={isCurrent || isLocked}
             className={cn(
               buttonStyles.icon,
-              isCurrent
+              isCurrent || isLocked
                 ? "text-gray-400 cursor-not-allowed"
                 : "text-gray-500 hover:text-red-500 hover:bg-red-100 dark:text-gray-400 dark:hover:text-red-400 dark:hover:bg-red-500/10",
             )}
@@ -232,7 +411,9 @@ const SortableProviderItem: React.FC<SortableProviderItemProps> = ({
       <UsageFooter
         providerId={provider.id}
         appType={appType}
-        usageEnabled={provider.meta?.usage_script?.enabled || false}
+        usageEnabled={
+          (isEnabled && provider.meta?.usage_script?.enabled) || false
+        }
       />
     </div>
   );
@@ -251,6 +432,33 @@ const ProviderList: React.FC<ProviderListProps> = ({
 }) => {
   const { t, i18n } = useTranslation();
   const [usageModalProviderId, setUsageModalProviderId] = useState<string | null>(null);
+  const [shareProviderId, setShareProviderId] = useState<string | null>(null);
+  const [searchText, setSearchText] = useState("");
+  // 后端检索命中的供应商 ID；为空表示未在检索
+  const [matchedIds, setMatchedIds] = useState<string[] | null>(null);
+
+  useEffect(() => {
+    const text = searchText.trim();
+    if (!text) {
+      setMatchedIds(null);
+      return;
+    }
+    let cancelled = false;
+    const timer = setTimeout(async () => {
+      try {
+        const result = await window.api.queryProviders(appType, { text });
+        if (!cancelled) {
+          setMatchedIds(result.map((p) => p.id));
+        }
+      } catch (error) {
+        console.error("检索供应商失败:", error);
+      }
+    }, 200);
+    return () => {
+      cancelled = true;
+      clearTimeout(timer);
+    };
+  }, [searchText, appType, providers]);
 
   // 处理代理模式下的供应商启用/禁用
   const handleToggleProxy = async (providerId: string, enabled: boolean) => {
@@ -268,12 +476,97 @@ const ProviderList: React.FC<ProviderListProps> = ({
         await onProvidersUpdated();
       }
     } catch (error) {
-      const errorMessage = error instanceof Error ? error.message : String(error);
+      const errorMessage = extractErrorMessage(error);
       console.error("切换代理供应商失败:", error);
       onNotify?.(`操作失败: ${errorMessage}`, "error", 4000);
     }
   };
 
+  // 启用/停用供应商（停用后保留在列表中）
+  const handleToggleEnabled = async (providerId: string, enabled: boolean) => {
+    try {
+      await window.api.setProviderEnabled(providerId, enabled, appType);
+      onNotify?.(
+        enabled
+          ? t("provider.providerEnabled")
+          : t("provider.providerDisabled"),
+        "success",
+        2000,
+      );
+      if (onProvidersUpdated) {
+        await onProvidersUpdated();
+      }
+    } catch (error) {
+      const errorMessage = extractErrorMessage(error);
+      console.error("切换供应商启用状态失败:", error);
+      onNotify?.(`${t("provider.toggleEnabledFailed")}: ${errorMessage}`, "error", 4000);
+    }
+  };
+
+  // 锁定/解锁供应商（解锁属于受保护操作，需先通过应用锁）
+  const handleToggleLocked = async (providerId: string, locked: boolean) => {
+    if (!locked && !(await ensureAppUnlocked())) return;
+    try {
+      await window.api.setProviderLocked(providerId, locked, appType);
+      onNotify?.(
+        locked
+          ? t("provider.providerLocked")
+          : t("provider.providerUnlocked"),
+        "success",
+        2000,
+      );
+      if (onProvidersUpdated) {
+        await onProvidersUpdated();
+      }
+    } catch (error) {
+      const errorMessage = extractErrorMessage(error);
+      console.error("切换供应商锁定状态失败:", error);
+      onNotify?.(`${t("provider.toggleLockedFailed")}: ${errorMessage}`, "error", 4000);
+    }
+  };
+
+  // 置顶/取消置顶：代理故障转移时置顶的供应商始终优先尝试
+  const handleTogglePinned = async (providerId: string, pinned: boolean) => {
+    try {
+      await window.api.setProviderPinned(providerId, pinned, appType);
+      onNotify?.(
+        pinned
+          ? t("provider.providerPinned")
+          : t("provider.providerUnpinned"),
+        "success",
+        2000,
+      );
+      if (onProvidersUpdated) {
+        await onProvidersUpdated();
+      }
+    } catch (error) {
+      const errorMessage = extractErrorMessage(error);
+      console.error("切换供应商置顶状态失败:", error);
+      onNotify?.(`${t("provider.togglePinnedFailed")}: ${errorMessage}`, "error", 4000);
+    }
+  };
+
+  // 维护模式：中转公告维护时临时静音，到期后由后台自动恢复
+  const handleSetMaintenance = async (providerId: string, minutes: number) => {
+    try {
+      await window.api.setProviderMaintenance(providerId, minutes, appType);
+      onNotify?.(
+        minutes > 0
+          ? t("provider.maintenanceStarted")
+          : t("provider.maintenanceEnded"),
+        "success",
+        2000,
+      );
+      if (onProvidersUpdated) {
+        await onProvidersUpdated();
+      }
+    } catch (error) {
+      const errorMessage = extractErrorMessage(error);
+      console.error("设置供应商维护模式失败:", error);
+      onNotify?.(`${t("provider.setMaintenanceFailed")}: ${errorMessage}`, "error", 4000);
+    }
+  };
+
   // Drag and drop sensors
   const sensors = useSensors(
     useSensor(PointerSensor, {
@@ -306,13 +599,13 @@ const ProviderList: React.FC<ProviderListProps> = ({
     }
   };
 
-  const handleUrlClick = async (url: string) => {
+  const handleWebsiteClick = async (providerId: string) => {
     try {
-      await window.api.openExternal(url);
+      await window.api.openProviderDashboard(providerId, appType);
     } catch (error) {
       console.error(t("console.openLinkFailed"), error);
       onNotify?.(
-        `${t("console.openLinkFailed")}: ${String(error)}`,
+        `${t("console.openLinkFailed")}: ${extractErrorMessage(error)}`,
         "error",
         4000,
       );
@@ -367,6 +660,14 @@ const ProviderList: React.FC<ProviderListProps> = ({
     });
   }, [providers, i18n.language]);
 
+  const visibleProviders = React.useMemo(
+    () =>
+      matchedIds === null
+        ? sortedProviders
+        : sortedProviders.filter((p) => matchedIds.includes(p.id)),
+    [sortedProviders, matchedIds],
+  );
+
   // Handle drag end - immediate refresh
   const handleDragEnd = React.useCallback(async (event: DragEndEvent) => {
     const { active, over } = event;
@@ -380,18 +681,14 @@ const ProviderList: React.FC<ProviderListProps> = ({
 
     // Calculate new sort order
     const reorderedProviders = arrayMove(sortedProviders, oldIndex, newIndex);
-    const updates = reorderedProviders.map((provider, index) => ({
-      id: provider.id,
-      sortIndex: index,
-    }));
 
     try {
-      // Save to backend and refresh immediately
-      await window.api.updateProvidersSortOrder(updates, appType);
+      // 后端一次性重写全部 sortIndex，并刷新托盘菜单与代理顺序
+      await window.api.reorderProviders(
+        reorderedProviders.map((provider) => provider.id),
+        appType,
+      );
       onProvidersUpdated?.();
-
-      // Update tray menu to reflect new order
-      await window.api.updateTrayMenu();
     } catch (error) {
       console.error("Failed to update sort order:", error);
       onNotify?.(t("provider.sortUpdateFailed") || "排序更新失败", "error");
@@ -413,42 +710,68 @@ const ProviderList: React.FC<ProviderListProps> = ({
           </p>
         </div>
       ) : (
-        <DndContext
-          sensors={sensors}
-          collisionDetection={closestCenter}
-          onDragEnd={handleDragEnd}
-          autoScroll={true}
-        >
-          <SortableContext
-            items={sortedProviders.map((p) => p.id)}
-            strategy={verticalListSortingStrategy}
+        <>
+          <div className="relative">
+            <Search
+              size={16}
+              className="absolute left-3 top-1/2 -translate-y-1/2 text-gray-400"
+            />
+            <input
+              type="text"
+              value={searchText}
+              onChange={(e) => setSearchText(e.target.value)}
+              placeholder={t("provider.searchPlaceholder")}
+              className="w-full pl-9 pr-3 py-2 text-sm bg-white dark:bg-gray-900 border border-gray-200 dark:border-gray-700 rounded-lg text-gray-900 dark:text-gray-100 focus:outline-none focus:ring-2 focus:ring-blue-500/40"
+            />
+          </div>
+          {visibleProviders.length === 0 && (
+            <p className="text-center py-8 text-sm text-gray-500 dark:text-gray-400">
+              {t("provider.noSearchResults")}
+            </p>
+          )}
+          <DndContext
+            sensors={sensors}
+            collisionDetection={closestCenter}
+            onDragEnd={handleDragEnd}
+            autoScroll={true}
           >
-            <div className="space-y-3">
-              {sortedProviders.map((provider) => {
-                const isCurrent = provider.id === currentProviderId;
-                const apiUrl = getApiUrl(provider);
-
-                return (
-                  <SortableProviderItem
-                    key={provider.id}
-                    provider={provider}
-                    isCurrent={isCurrent}
-                    apiUrl={apiUrl}
-                    onSwitch={onSwitch}
-                    onEdit={onEdit}
-                    onDelete={onDelete}
-                    onOpenUsageModal={setUsageModalProviderId}
-                    onUrlClick={handleUrlClick}
-                    appType={appType}
-                    operationMode={operationMode}
-                    onToggleProxy={handleToggleProxy}
-                    t={t}
-                  />
-                );
-              })}
-            </div>
-          </SortableContext>
-        </DndContext>
+            <SortableContext
+              items={visibleProviders.map((p) => p.id)}
+              strategy={verticalListSortingStrategy}
+            >
+              <div className="space-y-3">
+                {visibleProviders.map((provider) => {
+                  const isCurrent = provider.id === currentProviderId;
+                  const apiUrl = getApiUrl(provider);
+
+                  return (
+                    <SortableProviderItem
+                      key={provider.id}
+                      provider={provider}
+                      isCurrent={isCurrent}
+                      apiUrl={apiUrl}
+                      onSwitch={onSwitch}
+                      onEdit={onEdit}
+                      onDelete={onDelete}
+                      onOpenUsageModal={setUsageModalProviderId}
+                      onShare={setShareProviderId}
+                      onWebsiteClick={handleWebsiteClick}
+                      appType={appType}
+                      operationMode={operationMode}
+                      onToggleProxy={handleToggleProxy}
+                      onToggleEnabled={handleToggleEnabled}
+                      onToggleLocked={handleToggleLocked}
+                      onTogglePinned={handleTogglePinned}
+                      onSetMaintenance={handleSetMaintenance}
+                      dragDisabled={matchedIds !== null}
+                      t={t}
+                    />
+                  );
+                })}
+              </div>
+            </SortableContext>
+          </DndContext>
+        </>
       )}
 
       {/* 用量配置模态框 */}
@@ -463,6 +786,16 @@ const ProviderList: React.FC<ProviderListProps> = ({
           onNotify={onNotify}
         />
       )}
+
+      {/* 分享模态框 */}
+      {shareProviderId && providers[shareProviderId] && (
+        <ProviderShareModal
+          provider={providers[shareProviderId]}
+          appType={appType}
+          onClose={() => setShareProviderId(null)}
+          onNotify={onNotify}
+        />
+      )}
     </div>
   );
 };
@@ -0,0 +1,158 @@
+import React, { useEffect, useState } from "react";
+import { useTranslation } from "react-i18next";
+import { X, Copy, AlertTriangle } from "lucide-react";
+import { Provider, ProviderShare } from "../types";
+import { AppType } from "../lib/tauri-api";
+import { buttonStyles } from "../lib/styles";
+import { ensureAppUnlocked } from "../lib/appLock";
+import { extractErrorMessage } from "../utils/errorUtils";
+
+interface ProviderShareModalProps {
+  provider: Provider;
+  appType: AppType;
+  onClose: () => void;
+  onNotify?: (
+    message: string,
+    type: "success" | "error",
+    duration?: number,
+  ) => void;
+}
+
+/**
+ * 供应商分享弹窗
+ * 生成单行分享字符串（ccswitch://add 链接）与二维码，默认不包含 API Key
+ */
+const ProviderShareModal: React.FC<ProviderShareModalProps> = ({
+  provider,
+  appType,
+  onClose,
+  onNotify,
+}) => {
+  const { t } = useTranslation();
+  const [includeKey, setIncludeKey] = useState(false);
+  const [share, setShare] = useState<ProviderShare | null>(null);
+
+  useEffect(() => {
+    const load = async () => {
+      try {
+        setShare(
+          await window.api.encodeProviderShare(provider.id, appType, includeKey),
+        );
+      } catch (error) {
+        const detail = extractErrorMessage(error);
+        onNotify?.(
+          detail ? `${t("share.encodeFailed")}: ${detail}` : t("share.encodeFailed"),
+          "error",
+          5000,
+        );
+      }
+    };
+    load();
+  }, [provider.id, appType, includeKey]);
+
+  // 携带密钥分享属于受保护操作，需先解锁
+  const handleIncludeKeyChange = async (checked: boolean) => {
+    if (checked && !(await ensureAppUnlocked())) return;
+    setIncludeKey(checked);
+  };
+
+  const handleCopy = async () => {
+    if (!share) return;
+    try {
+      await navigator.clipboard.writeText(share.link);
+      onNotify?.(t("share.copied"), "success", 2000);
+    } catch (error) {
+      console.error("复制分享字符串失败:", error);
+    }
+  };
+
+  return (
+    <div className="fixed inset-0 z-50 flex items-center justify-center">
+      {/* Backdrop */}
+      <div
+        className="absolute inset-0 bg-black/50 backdrop-blur-sm"
+        onClick={onClose}
+      />
+
+      <div className="relative bg-white dark:bg-gray-900 rounded-xl shadow-lg max-w-lg w-full mx-4 overflow-hidden flex flex-col max-h-[85vh]">
+        {/* Header */}
+        <div className="flex-shrink-0 flex items-center justify-between p-6 border-b border-gray-200 dark:border-gray-800">
+          <h3 className="text-lg font-semibold text-gray-900 dark:text-gray-100">
+            {t("share.title", { name: provider.name })}
+          </h3>
+          <button
+            onClick={onClose}
+            className="p-1 text-gray-500 hover:text-gray-900 hover:bg-gray-100 dark:text-gray-400 dark:hover:text-gray-100 dark:hover:bg-gray-800 rounded-md transition-colors"
+          >
+            <X size={18} />
+          </button>
+        </div>
+
+        {/* Content */}
+        <div className="flex-1 overflow-y-auto p-6 space-y-4">
+          <label className="flex items-center gap-2 text-sm text-gray-700 dark:text-gray-300">
+            <input
+              type="checkbox"
+              checked={includeKey}
+              onChange={(e) => handleIncludeKeyChange(e.target.checked)}
+              className="w-4 h-4 text-blue-500 rounded focus:ring-blue-500/20"
+            />
+            {t("share.includeKey")}
+          </label>
+
+          {includeKey ? (
+            <div className="flex items-start gap-2 text-xs text-amber-600 dark:text-amber-400">
+              <AlertTriangle size={14} className="flex-shrink-0 mt-0.5" />
+              <span>{t("share.includeKeyWarning")}</span>
+            </div>
+          ) : (
+            <p className="text-xs text-gray-500 dark:text-gray-400">
+              {t("share.keyStrippedHint")}
+            </p>
+          )}
+
+          <textarea
+            readOnly
+            value={share?.link ?? ""}
+            rows={4}
+            className="w-full px-3 py-2 text-xs font-mono bg-gray-50 dark:bg-gray-800 border border-gray-200 dark:border-gray-700 rounded-lg text-gray-900 dark:text-gray-100 break-all resize-none"
+            onFocus={(e) => e.target.select()}
+          />
+
+          {share?.qrPng ? (
+            <div className="flex justify-center">
+              <img
+                src={share.qrPng}
+                alt={t("share.qrAlt")}
+                className="w-48 h-48 rounded-lg border border-gray-200 dark:border-gray-700"
+              />
+            </div>
+          ) : (
+            share && (
+              <p className="text-xs text-center text-gray-500 dark:text-gray-400">
+                {t("share.qrTooLong")}
+              </p>
+            )
+          )}
+        </div>
+
+        {/* Footer */}
+        <div className="flex-shrink-0 flex items-center justify-end gap-3 p-6 border-t border-gray-200 dark:border-gray-800 bg-gray-100 dark:bg-gray-800">
+          <button onClick={onClose} className={buttonStyles.secondary}>
+            {t("common.close")}
+          </button>
+          <button
+            onClick={handleCopy}
+            disabled={!share}
+            className={`inline-flex items-center gap-2 ${buttonStyles.primary}`}
+          >
+            <Copy size={16} />
+            {t("share.copy")}
+          </button>
+        </div>
+      </div>
+    </div>
+  );
+};
+
+export default ProviderShareModal;
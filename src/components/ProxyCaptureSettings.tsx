@@ -0,0 +1,260 @@
+import React, { useState } from "react";
+import { useTranslation } from "react-i18next";
+import { Play, RefreshCw, Trash2 } from "lucide-react";
+import {
+  CapturedRequest,
+  Provider,
+  ProxyCaptureSettings as ProxyCaptureConfig,
+  ReplayResult,
+} from "../types";
+import { extractErrorMessage } from "../utils/errorUtils";
+
+interface ProxyCaptureSettingsProps {
+  capture?: ProxyCaptureConfig;
+  onChange: (capture: ProxyCaptureConfig) => void;
+  onNotify?: (
+    message: string,
+    type: "success" | "error",
+    duration?: number,
+  ) => void;
+}
+
+const inputClass =
+  "px-2 py-1 text-sm bg-white dark:bg-gray-800 border border-gray-200 dark:border-gray-700 rounded-md focus:outline-none focus:ring-2 focus:ring-blue-500/40";
+
+const bodyClass =
+  "max-h-40 overflow-auto p-2 text-xs font-mono whitespace-pre-wrap break-all bg-white dark:bg-gray-900 rounded-md";
+
+// 设置中的请求录制区域：开关与上限、最近的请求列表，以及用指定供应商重放
+const ProxyCaptureSettings: React.FC<ProxyCaptureSettingsProps> = ({
+  capture,
+  onChange,
+  onNotify,
+}) => {
+  const { t } = useTranslation();
+  const [captures, setCaptures] = useState<CapturedRequest[] | null>(null);
+  const [expanded, setExpanded] = useState<string | null>(null);
+  const [providers, setProviders] = useState<Provider[]>([]);
+  const [replayProvider, setReplayProvider] = useState("");
+  const [replaying, setReplaying] = useState(false);
+  const [replay, setReplay] = useState<ReplayResult | null>(null);
+  const current: ProxyCaptureConfig = capture ?? {
+    enabled: false,
+    maxEntries: 50,
+    maxBodyKb: 64,
+  };
+
+  const update = (patch: Partial<ProxyCaptureConfig>) =>
+    onChange({ ...current, ...patch });
+
+  const updateLimit = (key: "maxEntries" | "maxBodyKb", value: string) => {
+    const parsed = parseInt(value);
+    if (!isNaN(parsed) && parsed > 0) {
+      update({ [key]: parsed });
+    }
+  };
+
+  const handleRefresh = async () => {
+    try {
+      setCaptures(await window.api.getCapturedRequests());
+    } catch (error) {
+      onNotify?.(extractErrorMessage(error), "error", 5000);
+    }
+  };
+
+  const handleClear = async () => {
+    try {
+      await window.api.clearCapturedRequests();
+      setCaptures([]);
+      setExpanded(null);
+    } catch (error) {
+      onNotify?.(extractErrorMessage(error), "error", 5000);
+    }
+  };
+
+  const handleExpand = async (item: CapturedRequest) => {
+    setReplay(null);
+    if (expanded === item.id) {
+      setExpanded(null);
+      return;
+    }
+    setExpanded(item.id);
+    try {
+      const loaded = Object.values(
+        await window.api.getProviders(item.appType),
+      ).sort((a, b) => (a.sortIndex ?? 0) - (b.sortIndex ?? 0));
+      setProviders(loaded);
+      setReplayProvider(item.providerId ?? loaded[0]?.id ?? "");
+    } catch (error) {
+      console.error("加载供应商失败:", error);
+    }
+  };
+
+  const handleReplay = async (item: CapturedRequest) => {
+    setReplaying(true);
+    setReplay(null);
+    try {
+      setReplay(await window.api.replayRequest(item.id, replayProvider));
+    } catch (error) {
+      onNotify?.(
+        `${t("proxyCapture.replayFailed")}: ${extractErrorMessage(error)}`,
+        "error",
+        5000,
+      );
+    } finally {
+      setReplaying(false);
+    }
+  };
+
+  return (
+    <div>
+      <h3 className="text-sm font-medium text-gray-900 dark:text-gray-100 mb-2">
+        {t("proxyCapture.title")}
+      </h3>
+      <p className="text-xs text-gray-500 dark:text-gray-400 mb-3 leading-relaxed">
+        {t("proxyCapture.description")}
+      </p>
+      <div className="p-4 bg-gray-100 dark:bg-gray-800 rounded-lg space-y-2">
+        <label className="flex items-center justify-between text-sm text-gray-700 dark:text-gray-300">
+          {t("proxyCapture.enabled")}
+          <input
+            type="checkbox"
+            checked={current.enabled}
+            onChange={(e) => update({ enabled: e.target.checked })}
+            className="w-4 h-4 text-blue-500 rounded focus:ring-blue-500/20"
+          />
+        </label>
+        <div className="flex items-center gap-2 text-sm text-gray-500 dark:text-gray-400">
+          <label className="flex-1">{t("proxyCapture.maxEntries")}</label>
+          <input
+            type="number"
+            min="1"
+            max="500"
+            value={current.maxEntries}
+            onChange={(e) => updateLimit("maxEntries", e.target.value)}
+            className={`w-24 ${inputClass}`}
+          />
+        </div>
+        <div className="flex items-center gap-2 text-sm text-gray-500 dark:text-gray-400">
+          <label className="flex-1">{t("proxyCapture.maxBodyKb")}</label>
+          <input
+            type="number"
+            min="1"
+            max="4096"
+            value={current.maxBodyKb}
+            onChange={(e) => updateLimit("maxBodyKb", e.target.value)}
+            className={`w-24 ${inputClass}`}
+          />
+        </div>
+        <p className="text-xs text-gray-400 dark:text-gray-500">
+          {t("proxyCapture.hint")}
+        </p>
+
+        <div className="pt-2 border-t border-gray-200 dark:border-gray-700 space-y-2">
+          <div className="flex gap-2">
+            <button
+              type="button"
+              onClick={handleRefresh}
+              className="flex-1 flex items-center justify-center gap-2 px-3 py-2 text-xs font-medium rounded-lg transition-colors bg-gray-500 hover:bg-gray-600 dark:bg-gray-600 dark:hover:bg-gray-700 text-white"
+            >
+              <RefreshCw size={12} />
+              {t("proxyCapture.refresh")}
+            </button>
+            <button
+              type="button"
+              onClick={handleClear}
+              title={t("proxyCapture.clear")}
+              className="px-3 py-2 rounded-lg text-red-500 hover:bg-red-100 dark:hover:bg-red-900/30"
+            >
+              <Trash2 size={12} />
+            </button>
+          </div>
+          {captures && captures.length === 0 && (
+            <p className="text-xs text-gray-400 dark:text-gray-500">
+              {t("proxyCapture.empty")}
+            </p>
+          )}
+          {captures?.map((item) => (
+            <div
+              key={item.id}
+              className="p-2 space-y-2 bg-white dark:bg-gray-900 rounded-md"
+            >
+              <button
+                type="button"
+                onClick={() => handleExpand(item)}
+                className="w-full flex items-center justify-between gap-2 text-xs text-left text-gray-700 dark:text-gray-300"
+              >
+                <span className="truncate font-mono">
+                  {item.method} {item.path}
+                </span>
+                <span className="shrink-0 text-gray-500 dark:text-gray-400">
+                  {item.status} · {item.providerName ?? "-"} ·{" "}
+                  {item.durationMs}ms ·{" "}
+                  {new Date(item.timestamp).toLocaleTimeString()}
+                </span>
+              </button>
+              {expanded === item.id && (
+                <div className="space-y-2">
+                  <p className="text-xs text-gray-500 dark:text-gray-400">
+                    {t("proxyCapture.request")}
+                    {item.requestTruncated &&
+                      ` (${t("proxyCapture.truncated")})`}
+                  </p>
+                  <pre className={bodyClass}>{item.requestBody}</pre>
+                  <p className="text-xs text-gray-500 dark:text-gray-400">
+                    {t("proxyCapture.response")}
+                    {item.responseTruncated &&
+                      ` (${t("proxyCapture.truncated")})`}
+                  </p>
+                  <pre className={bodyClass}>{item.responseBody}</pre>
+                  <div className="flex items-center gap-2">
+                    <select
+                      value={replayProvider}
+                      onChange={(e) => setReplayProvider(e.target.value)}
+                      className={`flex-1 ${inputClass}`}
+                    >
+                      {providers.map((provider) => (
+                        <option key={provider.id} value={provider.id}>
+                          {provider.name}
+                        </option>
+                      ))}
+                    </select>
+                    <button
+                      type="button"
+                      onClick={() => handleReplay(item)}
+                      disabled={
+                        replaying || !replayProvider || item.requestTruncated
+                      }
+                      className="shrink-0 flex items-center gap-1 px-2 py-1 text-xs rounded-md bg-blue-500 hover:bg-blue-600 text-white disabled:opacity-50"
+                    >
+                      <Play size={12} />
+                      {replaying
+                        ? t("proxyCapture.replaying")
+                        : t("proxyCapture.replay")}
+                    </button>
+                  </div>
+                  {replay && (
+                    <>
+                      <p className="text-xs text-gray-500 dark:text-gray-400">
+                        {t("proxyCapture.replayResult", {
+                          provider: replay.providerName,
+                          status: replay.status,
+                          duration: replay.durationMs,
+                        })}
+                        {replay.truncated &&
+                          ` (${t("proxyCapture.truncated")})`}
+                      </p>
+                      <pre className={bodyClass}>{replay.body}</pre>
+                    </>
+                  )}
+                </div>
+              )}
+            </div>
+          ))}
+        </div>
+      </div>
+    </div>
+  );
+};
+
+export default ProxyCaptureSettings;
@@ -0,0 +1,194 @@
+import React, { useEffect, useState } from "react";
+import { useTranslation } from "react-i18next";
+import { FolderOpen, RefreshCw } from "lucide-react";
+import {
+  CaCertificates,
+  ProxyConnectionSettings as ProxyConnectionConfig,
+  UpstreamHttpVersion,
+} from "../types";
+import { extractErrorMessage } from "../utils/errorUtils";
+
+interface ProxyConnectionSettingsProps {
+  connection?: ProxyConnectionConfig;
+  onChange: (connection: ProxyConnectionConfig) => void;
+  onNotify?: (
+    message: string,
+    type: "success" | "error",
+    duration?: number,
+  ) => void;
+}
+
+const inputClass =
+  "px-2 py-1 text-sm bg-white dark:bg-gray-800 border border-gray-200 dark:border-gray-700 rounded-md focus:outline-none focus:ring-2 focus:ring-blue-500/40";
+
+const HTTP_VERSIONS: UpstreamHttpVersion[] = ["auto", "http1", "http2"];
+
+// 设置中的上游连接区域：HTTP 版本、空闲连接保留时长、HTTP/2 keep-alive 与额外根证书
+const ProxyConnectionSettings: React.FC<ProxyConnectionSettingsProps> = ({
+  connection,
+  onChange,
+  onNotify,
+}) => {
+  const { t } = useTranslation();
+  const [caCertificates, setCaCertificates] = useState<CaCertificates | null>(
+    null,
+  );
+
+  useEffect(() => {
+    window.api
+      .getCaCertificates()
+      .then(setCaCertificates)
+      .catch((error) => console.error("读取额外根证书失败:", error));
+  }, []);
+
+  const handleReloadCa = async () => {
+    try {
+      const result = await window.api.reloadCaCertificates();
+      setCaCertificates(result);
+      onNotify?.(
+        t("proxyConnection.caReloaded", {
+          count: result.files.reduce((sum, file) => sum + file.certificates, 0),
+        }),
+        "success",
+        2000,
+      );
+    } catch (error) {
+      onNotify?.(extractErrorMessage(error), "error", 5000);
+    }
+  };
+  const current: ProxyConnectionConfig = connection ?? {
+    httpVersion: "auto",
+    poolIdleTimeoutSecs: 90,
+    http2KeepAliveSecs: 0,
+  };
+
+  const update = (patch: Partial<ProxyConnectionConfig>) =>
+    onChange({ ...current, ...patch });
+
+  // 非负整数输入
+  const parseSeconds = (value: string) => {
+    const parsed = parseInt(value);
+    return !isNaN(parsed) && parsed >= 0 ? parsed : undefined;
+  };
+
+  return (
+    <div>
+      <h3 className="text-sm font-medium text-gray-900 dark:text-gray-100 mb-2">
+        {t("proxyConnection.title")}
+      </h3>
+      <p className="text-xs text-gray-500 dark:text-gray-400 mb-3 leading-relaxed">
+        {t("proxyConnection.description")}
+      </p>
+      <div className="p-4 bg-gray-100 dark:bg-gray-800 rounded-lg space-y-2">
+        <div className="flex items-center gap-2">
+          <label className="flex-1 text-sm text-gray-500 dark:text-gray-400">
+            {t("proxyConnection.httpVersion")}
+          </label>
+          <select
+            value={current.httpVersion}
+            onChange={(e) =>
+              update({ httpVersion: e.target.value as UpstreamHttpVersion })
+            }
+            className={inputClass}
+          >
+            {HTTP_VERSIONS.map((version) => (
+              <option key={version} value={version}>
+                {t(`proxyConnection.httpVersions.${version}`)}
+              </option>
+            ))}
+          </select>
+        </div>
+        <div className="flex items-center gap-2">
+          <label className="flex-1 text-sm text-gray-500 dark:text-gray-400">
+            {t("proxyConnection.poolIdleTimeoutSecs")}
+          </label>
+          <input
+            type="number"
+            min="0"
+            step="1"
+            value={current.poolIdleTimeoutSecs}
+            onChange={(e) => {
+              const value = parseSeconds(e.target.value);
+              if (value !== undefined) update({ poolIdleTimeoutSecs: value });
+            }}
+            className={`w-20 ${inputClass}`}
+          />
+        </div>
+        <div className="flex items-center gap-2">
+          <label className="flex-1 text-sm text-gray-500 dark:text-gray-400">
+            {t("proxyConnection.http2KeepAliveSecs")}
+          </label>
+          <input
+            type="number"
+            min="0"
+            step="1"
+            value={current.http2KeepAliveSecs}
+            onChange={(e) => {
+              const value = parseSeconds(e.target.value);
+              if (value !== undefined) update({ http2KeepAliveSecs: value });
+            }}
+            className={`w-20 ${inputClass}`}
+          />
+        </div>
+        <p className="text-xs text-gray-400 dark:text-gray-500">
+          {t("proxyConnection.hint")}
+        </p>
+
+        <div className="pt-2 border-t border-gray-200 dark:border-gray-700 space-y-2">
+          <div className="flex items-center justify-between">
+            <span className="text-sm text-gray-500 dark:text-gray-400">
+              {t("proxyConnection.caCertificates")}
+            </span>
+            <div className="flex gap-1">
+              <button
+                type="button"
+                onClick={() => window.api.openCaCertificatesFolder()}
+                className="p-1 rounded hover:bg-gray-200 dark:hover:bg-gray-700 text-gray-500 dark:text-gray-400"
+                title={t("proxyConnection.openCaFolder")}
+              >
+                <FolderOpen size={14} />
+              </button>
+              <button
+                type="button"
+                onClick={handleReloadCa}
+                className="p-1 rounded hover:bg-gray-200 dark:hover:bg-gray-700 text-gray-500 dark:text-gray-400"
+                title={t("proxyConnection.reloadCa")}
+              >
+                <RefreshCw size={14} />
+              </button>
+            </div>
+          </div>
+          {caCertificates && caCertificates.files.length === 0 && (
+            <p className="text-xs text-gray-400 dark:text-gray-500">
+              {t("proxyConnection.caEmpty")}
+            </p>
+          )}
+          {caCertificates?.files.map((file) => (
+            <div
+              key={file.file}
+              className="flex justify-between gap-2 text-xs font-mono text-gray-600 dark:text-gray-400"
+            >
+              <span className="truncate">{file.file}</span>
+              {file.error ? (
+                <span className="text-red-500 dark:text-red-400 truncate">
+                  {file.error}
+                </span>
+              ) : (
+                <span>
+                  {t("proxyConnection.caCount", { count: file.certificates })}
+                </span>
+              )}
+            </div>
+          ))}
+          <p className="text-xs text-gray-400 dark:text-gray-500">
+            {t("proxyConnection.caHint", {
+              dir: caCertificates?.dir ?? "ca-certs",
+            })}
+          </p>
+        </div>
+      </div>
+    </div>
+  );
+};
+
+export default ProxyConnectionSettings;
@@ -0,0 +1,78 @@
+import React, { useState } from "react";
+import { useTranslation } from "react-i18next";
+import { ProxyDnsSettings as ProxyDnsConfig } from "../types";
+import { formatHostMapping, parseHostMapping } from "../utils/hostMapping";
+
+interface ProxyDnsSettingsProps {
+  dns?: ProxyDnsConfig;
+  onChange: (dns: ProxyDnsConfig) => void;
+}
+
+const inputClass =
+  "px-2 py-1 text-sm bg-white dark:bg-gray-800 border border-gray-200 dark:border-gray-700 rounded-md focus:outline-none focus:ring-2 focus:ring-blue-500/40";
+
+// 设置中的上游域名解析区域：DoH 地址与全局主机映射
+const ProxyDnsSettings: React.FC<ProxyDnsSettingsProps> = ({
+  dns,
+  onChange,
+}) => {
+  const { t } = useTranslation();
+  const current: ProxyDnsConfig = dns ?? { hosts: {} };
+  // 编辑中的文本单独保存，避免不完整的行在解析时被丢弃
+  const [hostsText, setHostsText] = useState(() =>
+    formatHostMapping(current.hosts),
+  );
+
+  return (
+    <div>
+      <h3 className="text-sm font-medium text-gray-900 dark:text-gray-100 mb-2">
+        {t("proxyDns.title")}
+      </h3>
+      <p className="text-xs text-gray-500 dark:text-gray-400 mb-3 leading-relaxed">
+        {t("proxyDns.description")}
+      </p>
+      <div className="p-4 bg-gray-100 dark:bg-gray-800 rounded-lg space-y-2">
+        <div>
+          <label className="block text-sm text-gray-500 dark:text-gray-400 mb-1">
+            {t("proxyDns.dohUrl")}
+          </label>
+          <input
+            type="url"
+            value={current.dohUrl ?? ""}
+            onChange={(e) =>
+              onChange({
+                ...current,
+                dohUrl: e.target.value.trim() || undefined,
+              })
+            }
+            placeholder="https://cloudflare-dns.com/dns-query"
+            className={`w-full ${inputClass}`}
+          />
+        </div>
+        <div>
+          <label className="block text-sm text-gray-500 dark:text-gray-400 mb-1">
+            {t("proxyDns.hosts")}
+          </label>
+          <textarea
+            rows={3}
+            value={hostsText}
+            onChange={(e) => {
+              setHostsText(e.target.value);
+              onChange({
+                ...current,
+                hosts: parseHostMapping(e.target.value),
+              });
+            }}
+            placeholder={t("proxyDns.hostsPlaceholder")}
+            className={`w-full font-mono ${inputClass}`}
+          />
+        </div>
+        <p className="text-xs text-gray-400 dark:text-gray-500">
+          {t("proxyDns.hint")}
+        </p>
+      </div>
+    </div>
+  );
+};
+
+export default ProxyDnsSettings;
@@ -0,0 +1,179 @@
+import React, { useEffect, useState } from "react";
+import { useTranslation } from "react-i18next";
+import { Plus, Trash2 } from "lucide-react";
+import { AppType } from "../lib/tauri-api";
+import { Provider, RequestRoute, RequestRoutes } from "../types";
+
+interface RequestRoutesSettingsProps {
+  routes?: RequestRoutes;
+  onChange: (routes: RequestRoutes) => void;
+}
+
+const APPS: AppType[] = ["claude", "codex"];
+
+const inputClass =
+  "px-2 py-1 text-xs bg-white dark:bg-gray-800 border border-gray-200 dark:border-gray-700 rounded-md focus:outline-none focus:ring-2 focus:ring-blue-500/40";
+
+// 设置中的请求路由区域：按请求的模型与路径把请求分配给指定供应商
+const RequestRoutesSettings: React.FC<RequestRoutesSettingsProps> = ({
+  routes,
+  onChange,
+}) => {
+  const { t } = useTranslation();
+  const [app, setApp] = useState<AppType>("claude");
+  const [providers, setProviders] = useState<Provider[]>([]);
+  const current: RequestRoutes = routes ?? { claude: [], codex: [] };
+  const list = current[app] ?? [];
+
+  useEffect(() => {
+    window.api
+      .getProviders(app)
+      .then((loaded) =>
+        setProviders(
+          Object.values(loaded).sort(
+            (a, b) => (a.sortIndex ?? 0) - (b.sortIndex ?? 0),
+          ),
+        ),
+      )
+      .catch((error) => console.error("加载供应商失败:", error));
+  }, [app]);
+
+  const updateApp = (next: RequestRoute[]) =>
+    onChange({ ...current, [app]: next });
+
+  const updateRoute = (index: number, patch: Partial<RequestRoute>) =>
+    updateApp(
+      list.map((route, i) => (i === index ? { ...route, ...patch } : route)),
+    );
+
+  const toggleProvider = (index: number, id: string) => {
+    const selected = list[index].providers;
+    updateRoute(index, {
+      providers: selected.includes(id)
+        ? selected.filter((item) => item !== id)
+        : [...selected, id],
+    });
+  };
+
+  const handleAdd = () =>
+    updateApp([
+      ...list,
+      { name: "", model: "", path: "", providers: [], exclusive: false },
+    ]);
+
+  return (
+    <div>
+      <h3 className="text-sm font-medium text-gray-900 dark:text-gray-100 mb-2">
+        {t("requestRoutes.title")}
+      </h3>
+      <p className="text-xs text-gray-500 dark:text-gray-400 mb-3 leading-relaxed">
+        {t("requestRoutes.description")}
+      </p>
+      <div className="p-4 bg-gray-100 dark:bg-gray-800 rounded-lg space-y-2">
+        <div className="flex gap-2">
+          {APPS.map((item) => (
+            <button
+              key={item}
+              type="button"
+              onClick={() => setApp(item)}
+              className={`px-3 py-1 text-xs font-medium rounded-md transition-colors ${
+                app === item
+                  ? "bg-blue-500 text-white"
+                  : "bg-white dark:bg-gray-700 text-gray-600 dark:text-gray-300"
+              }`}
+            >
+              {t(`apps.${item}`)}
+            </button>
+          ))}
+        </div>
+
+        {list.length === 0 && (
+          <p className="text-xs text-gray-400 dark:text-gray-500">
+            {t("requestRoutes.empty")}
+          </p>
+        )}
+        {list.map((route, index) => (
+          <div
+            key={index}
+            className="p-2 space-y-2 bg-white dark:bg-gray-900 rounded-md"
+          >
+            <div className="flex items-center gap-2">
+              <input
+                type="text"
+                value={route.name}
+                onChange={(e) => updateRoute(index, { name: e.target.value })}
+                placeholder={t("requestRoutes.namePlaceholder")}
+                className={`flex-1 ${inputClass}`}
+              />
+              <button
+                type="button"
+                onClick={() => updateApp(list.filter((_, i) => i !== index))}
+                title={t("common.delete")}
+                className="p-1 rounded-md text-red-500 hover:bg-red-100 dark:hover:bg-red-900/30"
+              >
+                <Trash2 size={12} />
+              </button>
+            </div>
+            <div className="flex items-center gap-2">
+              <input
+                type="text"
+                value={route.model ?? ""}
+                onChange={(e) => updateRoute(index, { model: e.target.value })}
+                placeholder={t("requestRoutes.modelPlaceholder")}
+                className={`flex-1 font-mono ${inputClass}`}
+              />
+              <input
+                type="text"
+                value={route.path ?? ""}
+                onChange={(e) => updateRoute(index, { path: e.target.value })}
+                placeholder={t("requestRoutes.pathPlaceholder")}
+                className={`flex-1 font-mono ${inputClass}`}
+              />
+            </div>
+            <div className="flex flex-wrap gap-x-3 gap-y-1">
+              {providers.map((provider) => (
+                <label
+                  key={provider.id}
+                  className="flex items-center gap-1 text-xs text-gray-700 dark:text-gray-300"
+                >
+                  <input
+                    type="checkbox"
+                    checked={route.providers.includes(provider.id)}
+                    onChange={() => toggleProvider(index, provider.id)}
+                    className="w-3 h-3 text-blue-500 rounded focus:ring-blue-500/20"
+                  />
+                  {provider.name}
+                </label>
+              ))}
+            </div>
+            <label className="flex items-center gap-1 text-xs text-gray-500 dark:text-gray-400">
+              <input
+                type="checkbox"
+                checked={route.exclusive}
+                onChange={(e) =>
+                  updateRoute(index, { exclusive: e.target.checked })
+                }
+                className="w-3 h-3 text-blue-500 rounded focus:ring-blue-500/20"
+              />
+              {t("requestRoutes.exclusive")}
+            </label>
+          </div>
+        ))}
+
+        <button
+          type="button"
+          onClick={handleAdd}
+          className="flex items-center gap-1 px-2 py-1 text-xs rounded-md text-gray-600 dark:text-gray-300 hover:bg-gray-200 dark:hover:bg-gray-700"
+        >
+          <Plus size={12} />
+          {t("requestRoutes.add")}
+        </button>
+        <p className="text-xs text-gray-400 dark:text-gray-500">
+          {t("requestRoutes.hint")}
+        </p>
+      </div>
+    </div>
+  );
+};
+
+export default RequestRoutesSettings;
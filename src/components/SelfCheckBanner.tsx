@@ -0,0 +1,119 @@
+import React, { useEffect, useState } from "react";
+import { useTranslation } from "react-i18next";
+import { AlertTriangle, X } from "lucide-react";
+import { AppType } from "../lib/tauri-api";
+import { RepairAction, SelfCheckIssue } from "../types";
+import { extractErrorMessage } from "../utils/errorUtils";
+
+interface SelfCheckBannerProps {
+  onRepaired?: () => void | Promise<void>;
+  onNotify?: (
+    message: string,
+    type: "success" | "error",
+    duration?: number,
+  ) => void;
+}
+
+// 启动自检发现的配置问题，每项提供一键修复
+const SelfCheckBanner: React.FC<SelfCheckBannerProps> = ({
+  onRepaired,
+  onNotify,
+}) => {
+  const { t } = useTranslation();
+  const [issues, setIssues] = useState<SelfCheckIssue[]>([]);
+  const [repairing, setRepairing] = useState<string | null>(null);
+
+  useEffect(() => {
+    let unlisten: (() => void) | null = null;
+
+    const setup = async () => {
+      try {
+        unlisten = await window.api.onConfigSelfCheck((report) =>
+          setIssues(report.issues),
+        );
+        // 启动事件可能早于监听注册，读取缓存的结果
+        const report = await window.api.getConfigSelfCheck();
+        setIssues(report.issues);
+      } catch (error) {
+        console.error("读取配置自检结果失败:", error);
+      }
+    };
+
+    setup();
+
+    return () => {
+      if (unlisten) {
+        unlisten();
+      }
+    };
+  }, []);
+
+  const handleRepair = async (issue: SelfCheckIssue, action: RepairAction) => {
+    setRepairing(`${issue.appType}:${action}`);
+    try {
+      const report = await window.api.repairConfigIssue(
+        action,
+        issue.appType as AppType,
+      );
+      setIssues(report.issues);
+      onNotify?.(t("selfCheck.repaired"), "success", 2000);
+      await onRepaired?.();
+    } catch (error) {
+      onNotify?.(
+        `${t("selfCheck.repairFailed")}: ${extractErrorMessage(error)}`,
+        "error",
+        5000,
+      );
+    } finally {
+      setRepairing(null);
+    }
+  };
+
+  if (issues.length === 0) return null;
+
+  return (
+    <div className="mb-3 p-3 rounded-lg border border-amber-300 bg-amber-50 dark:border-amber-700 dark:bg-amber-900/20">
+      <div className="flex items-center justify-between mb-2">
+        <span className="flex items-center gap-2 text-sm font-medium text-amber-800 dark:text-amber-200">
+          <AlertTriangle size={16} />
+          {t("selfCheck.title")}
+        </span>
+        <button
+          type="button"
+          onClick={() => setIssues([])}
+          title={t("selfCheck.dismiss")}
+          className="p-1 rounded-md text-amber-700 hover:bg-amber-100 dark:text-amber-300 dark:hover:bg-amber-800/40"
+        >
+          <X size={14} />
+        </button>
+      </div>
+      <ul className="space-y-2">
+        {issues.map((issue) => (
+          <li
+            key={`${issue.appType}:${issue.kind}`}
+            className="flex items-center justify-between gap-3 text-xs"
+          >
+            <span className="text-amber-900 dark:text-amber-100 break-all">
+              {issue.message}
+            </span>
+            <span className="flex shrink-0 gap-2">
+              {issue.repairs.map((action) => (
+                <button
+                  key={action}
+                  type="button"
+                  onClick={() => handleRepair(issue, action)}
+                  disabled={repairing !== null}
+                  className="px-2 py-1 rounded-md font-medium bg-amber-500 hover:bg-amber-600 text-white disabled:opacity-50"
+                >
+                  {t(`selfCheck.actions.${action}`)}
+                </button>
+              ))}
+            </span>
+          </li>
+        ))}
+      </ul>
+    </div>
+  );
+};
+
+export default SelfCheckBanner;
@@ -10,16 +10,35 @@ import {
   Undo2,
   FolderSearch,
   Save,
+  Copy,
 } from "lucide-react";
 import { getVersion } from "@tauri-apps/api/app";
 import { ImportProgressModal } from "./ImportProgressModal";
+import AppLockSettings from "./AppLockSettings";
+import AuditLogSection from "./AuditLogSection";
+import ManagedSourceSettings from "./ManagedSourceSettings";
+import WebhookSettingsSection from "./WebhookSettings";
+import HooksSettings from "./HooksSettings";
+import CommonFragmentsSettings from "./CommonFragmentsSettings";
+import MergeStrategiesSettings from "./MergeStrategiesSettings";
+import AutoRankSettings from "./AutoRankSettings";
+import RequestRoutesSettings from "./RequestRoutesSettings";
+import LanShareSettings from "./LanShareSettings";
+import ProxyCaptureSettings from "./ProxyCaptureSettings";
+import ProxyDnsSettings from "./ProxyDnsSettings";
+import ProxyConnectionSettings from "./ProxyConnectionSettings";
+import KeyUsageSection from "./KeyUsageSection";
+import TtfbSection from "./TtfbSection";
+import UsageCalendarSection from "./UsageCalendarSection";
 import { homeDir, join } from "@tauri-apps/api/path";
 import "../lib/tauri-api";
 import { relaunchApp } from "../lib/updater";
 import { useUpdate } from "../contexts/UpdateContext";
-import type { Settings } from "../types";
+import type { EnvSnippet, OperationMode, Settings } from "../types";
 import type { AppType } from "../lib/tauri-api";
 import { isLinux } from "../lib/platform";
+import { ensureAppUnlocked } from "../lib/appLock";
+import { extractErrorMessage } from "../utils/errorUtils";
 
 interface SettingsModalProps {
   onClose: () => void;
@@ -38,13 +57,13 @@ export default function SettingsModal({
 }: SettingsModalProps) {
   const { t, i18n } = useTranslation();
 
-  const normalizeLanguage = (lang?: string | null): "zh" | "en" =>
-    lang === "en" ? "en" : "zh";
+  const normalizeLanguage = (lang?: string | null): "zh" | "en" | "ja" =>
+    lang === "en" || lang === "ja" ? lang : "zh";
 
-  const readPersistedLanguage = (): "zh" | "en" => {
+  const readPersistedLanguage = (): "zh" | "en" | "ja" => {
     if (typeof window !== "undefined") {
       const stored = window.localStorage.getItem("language");
-      if (stored === "en" || stored === "zh") {
+      if (stored === "en" || stored === "zh" || stored === "ja") {
         return stored;
       }
     }
@@ -62,15 +81,18 @@ export default function SettingsModal({
     language: persistedLanguage,
     operationMode: "write",
     proxyRetryCount: 1,
+    auditRetentionDays: 90,
   });
   // appConfigDir 现在从 Store 独立管理
   const [appConfigDir, setAppConfigDir] = useState<string | undefined>(
     undefined,
   );
-  const [initialLanguage, setInitialLanguage] = useState<"zh" | "en">(
+  const [initialLanguage, setInitialLanguage] = useState<"zh" | "en" | "ja">(
     persistedLanguage,
   );
-  const [initialOperationMode, setInitialOperationMode] = useState<"write" | "proxy">("write");
+  const [initialOperationMode, setInitialOperationMode] = useState<OperationMode>("write");
+  const [initialHooks, setInitialHooks] = useState<string>("[]");
+  const [envSnippet, setEnvSnippet] = useState<EnvSnippet | null>(null);
   const [configPath, setConfigPath] = useState<string>("");
   const [version, setVersion] = useState<string>("");
   const [isCheckingUpdate, setIsCheckingUpdate] = useState(false);
@@ -80,6 +102,7 @@ export default function SettingsModal({
   const [resolvedClaudeDir, setResolvedClaudeDir] = useState<string>("");
   const [resolvedCodexDir, setResolvedCodexDir] = useState<string>("");
   const [isPortable, setIsPortable] = useState(false);
+  const [portableDataDir, setPortableDataDir] = useState<string | null>(null);
   const [initialAppConfigDir, setInitialAppConfigDir] = useState<
     string | undefined
   >(undefined);
@@ -105,6 +128,18 @@ export default function SettingsModal({
     loadPortableFlag();
   }, []);
 
+  // 环境变量模式生效后加载 shell 引用语句
+  useEffect(() => {
+    if (initialOperationMode !== "env") {
+      setEnvSnippet(null);
+      return;
+    }
+    window.api
+      .getEnvSnippet()
+      .then(setEnvSnippet)
+      .catch((error) => console.error("获取环境变量片段失败:", error));
+  }, [initialOperationMode]);
+
   const loadVersion = async () => {
     try {
       const appVersion = await getVersion();
@@ -151,11 +186,15 @@ export default function SettingsModal({
           : persistedLanguage,
       );
 
-      const operationMode = (loadedSettings as any)?.operationMode === "proxy" ? "proxy" : "write";
+      const loadedMode = (loadedSettings as any)?.operationMode;
+      const operationMode: OperationMode =
+        loadedMode === "proxy" || loadedMode === "env" ? loadedMode : "write";
 
       setSettings({
         showInTray,
         minimizeToTrayOnClose,
+        launchAtLogin: (loadedSettings as any)?.launchAtLogin === true,
+        startMinimized: (loadedSettings as any)?.startMinimized === true,
         enableClaudePluginIntegration:
           typeof (loadedSettings as any)?.enableClaudePluginIntegration ===
           "boolean"
@@ -175,9 +214,43 @@ export default function SettingsModal({
           typeof (loadedSettings as any)?.proxyRetryCount === "number"
             ? (loadedSettings as any).proxyRetryCount
             : 1,
+        proxyDeadlineSecs:
+          typeof (loadedSettings as any)?.proxyDeadlineSecs === "number"
+            ? (loadedSettings as any).proxyDeadlineSecs
+            : 0,
+        proxyAggressiveRetry:
+          (loadedSettings as any)?.proxyAggressiveRetry === true,
+        proxyDiagnosticHeaders:
+          (loadedSettings as any)?.proxyDiagnosticHeaders === true,
+        proxyProviderBanner:
+          (loadedSettings as any)?.proxyProviderBanner === true,
+        proxyLocalCountTokens:
+          (loadedSettings as any)?.proxyLocalCountTokens === true,
+        proxyTls: (loadedSettings as any)?.proxyTls === true,
+        auditRetentionDays:
+          typeof (loadedSettings as any)?.auditRetentionDays === "number"
+            ? (loadedSettings as any).auditRetentionDays
+            : 90,
+        managedSource: (loadedSettings as any)?.managedSource,
+        webhook: (loadedSettings as any)?.webhook,
+        hooks: (loadedSettings as any)?.hooks ?? [],
+        mergeStrategies: (loadedSettings as any)?.mergeStrategies,
+        autoRank: (loadedSettings as any)?.autoRank,
+        requestRoutes: (loadedSettings as any)?.requestRoutes,
+        lanShare: (loadedSettings as any)?.lanShare,
+        proxyCapture: (loadedSettings as any)?.proxyCapture,
+        proxyDns: (loadedSettings as any)?.proxyDns,
+        proxyConnection: (loadedSettings as any)?.proxyConnection,
+        routingAutoSwitch: (loadedSettings as any)?.routingAutoSwitch === true,
+        codexSingleConfig: (loadedSettings as any)?.codexSingleConfig === true,
+        claudeStatusline: (loadedSettings as any)?.claudeStatusline === true,
+        runningSessionNotice:
+          (loadedSettings as any)?.runningSessionNotice === true,
+        vscodeSettingsPath: (loadedSettings as any)?.vscodeSettingsPath,
       });
       setInitialLanguage(storedLanguage);
       setInitialOperationMode(operationMode);
+      setInitialHooks(JSON.stringify((loadedSettings as any)?.hooks ?? []));
       if (i18n.language !== storedLanguage) {
         void i18n.changeLanguage(storedLanguage);
       }
@@ -214,6 +287,9 @@ export default function SettingsModal({
     try {
       const portable = await window.api.isPortable();
       setIsPortable(portable);
+      if (portable) {
+        setPortableDataDir(await window.api.getPortableDataDir());
+      }
     } catch (error) {
       console.error(t("console.detectPortableFailed"), error);
     }
@@ -221,7 +297,7 @@ export default function SettingsModal({
 
   const saveSettings = async () => {
     try {
-      const selectedLanguage = settings.language === "en" ? "en" : "zh";
+      const selectedLanguage = normalizeLanguage(settings.language);
       const payload: Settings = {
         ...settings,
         claudeConfigDir:
@@ -233,9 +309,30 @@ export default function SettingsModal({
             ? settings.codexConfigDir.trim()
             : undefined,
         language: selectedLanguage,
+        managedSource: settings.managedSource?.url.trim()
+          ? {
+              ...settings.managedSource,
+              url: settings.managedSource.url.trim(),
+              token: settings.managedSource.token?.trim() || undefined,
+            }
+          : undefined,
+        webhook: settings.webhook?.url.trim()
+          ? {
+              ...settings.webhook,
+              url: settings.webhook.url.trim(),
+              secret: settings.webhook.secret?.trim() || undefined,
+            }
+          : undefined,
       };
 
       const operationModeChanged = (payload.operationMode ?? "write") !== initialOperationMode;
+      const hooksChanged = JSON.stringify(payload.hooks ?? []) !== initialHooks;
+      // 切换运行模式与修改钩子属于受保护操作，需先解锁
+      if (
+        (operationModeChanged || hooksChanged) &&
+        !(await ensureAppUnlocked())
+      )
+        return;
 
       await window.api.saveSettings(payload);
 
@@ -260,22 +357,9 @@ export default function SettingsModal({
       // 如果代理模式发生变化，调用代理模式切换处理
       if (operationModeChanged) {
         try {
-          // 从 localStorage 读取通用配置
-          let claudeCommonConfig: string | undefined;
-          let codexCommonConfig: string | undefined;
-
-          try {
-            claudeCommonConfig = window.localStorage.getItem("cc-switch:common-config-snippet") || undefined;
-            codexCommonConfig = window.localStorage.getItem("cc-switch:codex-common-config-snippet") || undefined;
-          } catch {
-            // ignore localStorage 读取失败
-          }
-
-          // 调用后端切换模式，传入通用配置
-          await (window as any).api.handleOperationModeChange(
+          // 通用配置片段由后端保存并合并
+          await window.api.handleOperationModeChange(
             payload.operationMode ?? "write",
-            claudeCommonConfig,
-            codexCommonConfig,
           );
         } catch (e) {
           console.error("[Settings] 代理模式切换失败:", e);
@@ -290,6 +374,7 @@ export default function SettingsModal({
       setSettings(payload);
       setInitialAppConfigDir(normalizedAppConfigDir ?? undefined);
       setInitialOperationMode(payload.operationMode ?? "write");
+      setInitialHooks(JSON.stringify(payload.hooks ?? []));
       try {
         window.localStorage.setItem("language", selectedLanguage);
       } catch (error) {
@@ -345,7 +430,7 @@ export default function SettingsModal({
     onClose();
   };
 
-  const handleLanguageChange = (lang: "zh" | "en") => {
+  const handleLanguageChange = (lang: "zh" | "en" | "ja") => {
     setSettings((prev) => ({ ...prev, language: lang }));
     if (i18n.language !== lang) {
       void i18n.changeLanguage(lang);
@@ -548,6 +633,7 @@ export default function SettingsModal({
   // 导出配置处理函数
   const handleExportConfig = async () => {
     try {
+      if (!(await ensureAppUnlocked())) return;
       const defaultName = `cc-switch-config-${new Date().toISOString().split("T")[0]}.json`;
       const filePath = await window.api.saveFileDialog(defaultName);
 
@@ -572,7 +658,26 @@ export default function SettingsModal({
     } catch (error) {
       console.error(t("settings.exportFailedError"), error);
       onNotify?.(
-        `${t("settings.exportFailed")}: ${String(error)}`,
+        `${t("settings.exportFailed")}: ${extractErrorMessage(error)}`,
+        "error",
+        5000,
+      );
+    }
+  };
+
+  // 导出诊断包（用于反馈问题）
+  const handleExportDiagnostics = async () => {
+    try {
+      const filePath = await window.api.exportDiagnostics();
+      onNotify?.(
+        `${t("settings.diagnosticsExported")}\n${filePath}`,
+        "success",
+        4000,
+      );
+    } catch (error) {
+      console.error(t("settings.exportDiagnosticsFailed"), error);
+      onNotify?.(
+        `${t("settings.exportDiagnosticsFailed")}: ${extractErrorMessage(error)}`,
         "error",
         5000,
       );
@@ -591,7 +696,7 @@ export default function SettingsModal({
     } catch (error) {
       console.error(t("settings.selectFileFailed") + ":", error);
       onNotify?.(
-        `${t("settings.selectFileFailed")}: ${String(error)}`,
+        `${t("settings.selectFileFailed")}: ${extractErrorMessage(error)}`,
         "error",
         5000,
       );
@@ -611,13 +716,19 @@ export default function SettingsModal({
       if (result.success) {
         setImportBackupId(result.backupId || "");
         setImportStatus("success");
+        if (result.duplicates && result.duplicates.length > 0) {
+          const pairs = result.duplicates
+            .map((pair) => `${pair.name} / ${pair.duplicate.name}`)
+            .join(", ");
+          onNotify?.(t("duplicate.imported", { pairs }), "error", 8000);
+        }
         // ImportProgressModal 会在2秒后触发数据刷新回调
       } else {
         setImportError(result.message || t("settings.configCorrupted"));
         setImportStatus("error");
       }
     } catch (error) {
-      setImportError(String(error));
+      setImportError(extractErrorMessage(error));
       setImportStatus("error");
     } finally {
       setIsImporting(false);
@@ -680,6 +791,17 @@ export default function SettingsModal({
               >
                 {t("settings.languageOptionEnglish")}
               </button>
+              <button
+                type="button"
+                onClick={() => handleLanguageChange("ja")}
+                className={`px-4 py-1.5 text-sm font-medium rounded-md transition-all min-w-[80px] ${
+                  settings.language === "ja"
+                    ? "bg-white dark:bg-gray-700 text-gray-900 dark:text-gray-100 shadow-sm"
+                    : "text-gray-600 dark:text-gray-400 hover:text-gray-900 dark:hover:text-gray-200"
+                }`}
+              >
+                {t("settings.languageOptionJapanese")}
+              </button>
             </div>
           </div>
 
@@ -712,6 +834,17 @@ export default function SettingsModal({
                 >
                   {t("settings.operationModeProxy")}
                 </button>
+                <button
+                  type="button"
+                  onClick={() => setSettings((prev) => ({ ...prev, operationMode: "env" }))}
+                  className={`px-4 py-1.5 text-sm font-medium rounded-md transition-all min-w-[80px] ${
+                    settings.operationMode === "env"
+                      ? "bg-white dark:bg-gray-700 text-gray-900 dark:text-gray-100 shadow-sm"
+                      : "text-gray-600 dark:text-gray-400 hover:text-gray-900 dark:hover:text-gray-200"
+                  }`}
+                >
+                  {t("settings.operationModeEnv")}
+                </button>
               </div>
               {settings.operationMode === "proxy" && (
                 <div className="flex items-center gap-2">
@@ -740,6 +873,150 @@ export default function SettingsModal({
                 {t("settings.proxyRetryCountDescription")}
               </p>
             )}
+            {settings.operationMode === "proxy" && (
+              <div className="mt-2">
+                <div className="flex items-center gap-2">
+                  <label className="text-sm text-gray-500 dark:text-gray-400">
+                    {t("settings.proxyDeadlineSecs")}
+                  </label>
+                  <input
+                    type="number"
+                    min="0"
+                    step="1"
+                    value={settings.proxyDeadlineSecs ?? 0}
+                    onChange={(e) => {
+                      const value = parseInt(e.target.value);
+                      if (!isNaN(value) && value >= 0) {
+                        setSettings((prev) => ({
+                          ...prev,
+                          proxyDeadlineSecs: value,
+                        }));
+                      }
+                    }}
+                    className="w-20 px-2 py-1 text-sm bg-white dark:bg-gray-800 border border-gray-200 dark:border-gray-700 rounded-md focus:outline-none focus:ring-2 focus:ring-blue-500/40"
+                  />
+                </div>
+                <p className="text-xs text-gray-500 dark:text-gray-400 mt-2">
+                  {t("settings.proxyDeadlineSecsDescription")}
+                </p>
+                <label className="flex items-center justify-between mt-2 text-sm text-gray-500 dark:text-gray-400">
+                  {t("settings.proxyAggressiveRetry")}
+                  <input
+                    type="checkbox"
+                    checked={!!settings.proxyAggressiveRetry}
+                    onChange={(e) =>
+                      setSettings((prev) => ({
+                        ...prev,
+                        proxyAggressiveRetry: e.target.checked,
+                      }))
+                    }
+                    className="w-4 h-4 text-blue-500 rounded focus:ring-blue-500/20"
+                  />
+                </label>
+                <p className="text-xs text-gray-500 dark:text-gray-400 mt-2">
+                  {t("settings.proxyAggressiveRetryDescription")}
+                </p>
+                <label className="flex items-center justify-between mt-2 text-sm text-gray-500 dark:text-gray-400">
+                  {t("settings.proxyDiagnosticHeaders")}
+                  <input
+                    type="checkbox"
+                    checked={!!settings.proxyDiagnosticHeaders}
+                    onChange={(e) =>
+                      setSettings((prev) => ({
+                        ...prev,
+                        proxyDiagnosticHeaders: e.target.checked,
+                      }))
+                    }
+                    className="w-4 h-4 text-blue-500 rounded focus:ring-blue-500/20"
+                  />
+                </label>
+                <p className="text-xs text-gray-500 dark:text-gray-400 mt-2">
+                  {t("settings.proxyDiagnosticHeadersDescription")}
+                </p>
+                <label className="flex items-center justify-between mt-2 text-sm text-gray-500 dark:text-gray-400">
+                  {t("settings.proxyProviderBanner")}
+                  <input
+                    type="checkbox"
+                    checked={!!settings.proxyProviderBanner}
+                    onChange={(e) =>
+                      setSettings((prev) => ({
+                        ...prev,
+                        proxyProviderBanner: e.target.checked,
+                      }))
+                    }
+                    className="w-4 h-4 text-blue-500 rounded focus:ring-blue-500/20"
+                  />
+                </label>
+                <p className="text-xs text-gray-500 dark:text-gray-400 mt-2">
+                  {t("settings.proxyProviderBannerDescription")}
+                </p>
+                <label className="flex items-center justify-between mt-2 text-sm text-gray-500 dark:text-gray-400">
+                  {t("settings.proxyLocalCountTokens")}
+                  <input
+                    type="checkbox"
+                    checked={!!settings.proxyLocalCountTokens}
+                    onChange={(e) =>
+                      setSettings((prev) => ({
+                        ...prev,
+                        proxyLocalCountTokens: e.target.checked,
+                      }))
+                    }
+                    className="w-4 h-4 text-blue-500 rounded focus:ring-blue-500/20"
+                  />
+                </label>
+                <p className="text-xs text-gray-500 dark:text-gray-400 mt-2">
+                  {t("settings.proxyLocalCountTokensDescription")}
+                </p>
+                <label className="flex items-center justify-between mt-2 text-sm text-gray-500 dark:text-gray-400">
+                  {t("settings.proxyTls")}
+                  <input
+                    type="checkbox"
+                    checked={!!settings.proxyTls}
+                    onChange={(e) =>
+                      setSettings((prev) => ({
+                        ...prev,
+                        proxyTls: e.target.checked,
+                      }))
+                    }
+                    className="w-4 h-4 text-blue-500 rounded focus:ring-blue-500/20"
+                  />
+                </label>
+                <p className="text-xs text-gray-500 dark:text-gray-400 mt-2">
+                  {t("settings.proxyTlsDescription")}
+                </p>
+              </div>
+            )}
+            {settings.operationMode === "env" && (
+              <div className="mt-2 space-y-2">
+                <p className="text-xs text-gray-500 dark:text-gray-400">
+                  {t("settings.operationModeEnvDescription")}
+                </p>
+                {initialOperationMode === "env" && envSnippet ? (
+                  <div className="flex items-center gap-2">
+                    <code className="flex-1 px-3 py-2 text-xs font-mono bg-gray-100 dark:bg-gray-800 rounded-lg break-all">
+                      {envSnippet.sourceLine}
+                    </code>
+                    <button
+                      type="button"
+                      onClick={() =>
+                        navigator.clipboard
+                          .writeText(envSnippet.sourceLine)
+                          .then(() => onNotify?.(t("settings.envSnippetCopied"), "success"))
+                          .catch(() => undefined)
+                      }
+                      className="px-2 py-2 text-xs text-gray-500 dark:text-gray-400 hover:text-blue-500 dark:hover:text-blue-400 hover:bg-gray-100 dark:hover:bg-gray-800 rounded-lg transition-colors"
+                      title={t("settings.copyEnvSnippet")}
+                    >
+                      <Copy size={16} />
+                    </button>
+                  </div>
+                ) : (
+                  <p className="text-xs text-gray-400 dark:text-gray-500">
+                    {t("settings.envSnippetAfterSave")}
+                  </p>
+                )}
+              </div>
+            )}
           </div>
 
           {/* 窗口行为设置 */}
@@ -769,6 +1046,49 @@ export default function SettingsModal({
                   className="w-4 h-4 text-blue-500 rounded focus:ring-blue-500/20"
                 />
               </label>
+              <label className="flex items-center justify-between">
+                <div>
+                  <span className="text-sm text-gray-900 dark:text-gray-100">
+                    {t("settings.launchAtLogin")}
+                  </span>
+                  <p className="text-xs text-gray-500 dark:text-gray-400 mt-1">
+                    {t("settings.launchAtLoginDescription")}
+                  </p>
+                </div>
+                <input
+                  type="checkbox"
+                  checked={settings.launchAtLogin ?? false}
+                  onChange={(e) =>
+                    setSettings((prev) => ({
+                      ...prev,
+                      launchAtLogin: e.target.checked,
+                    }))
+                  }
+                  className="w-4 h-4 text-blue-500 rounded focus:ring-blue-500/20"
+                />
+              </label>
+              <label className="flex items-center justify-between">
+                <div>
+                  <span className="text-sm text-gray-900 dark:text-gray-100">
+                    {t("settings.startMinimized")}
+                  </span>
+                  <p className="text-xs text-gray-500 dark:text-gray-400 mt-1">
+                    {t("settings.startMinimizedDescription")}
+                  </p>
+                </div>
+                <input
+                  type="checkbox"
+                  checked={settings.startMinimized ?? false}
+                  disabled={!settings.launchAtLogin}
+                  onChange={(e) =>
+                    setSettings((prev) => ({
+                      ...prev,
+                      startMinimized: e.target.checked,
+                    }))
+                  }
+                  className="w-4 h-4 text-blue-500 rounded focus:ring-blue-500/20 disabled:opacity-50"
+                />
+              </label>
               {/* Claude 插件联动开关 */}
               <label className="flex items-center justify-between">
                 <div>
@@ -791,6 +1111,115 @@ export default function SettingsModal({
                   className="w-4 h-4 text-blue-500 rounded focus:ring-blue-500/20"
                 />
               </label>
+              {/* Codex 单文件模式 */}
+              <label className="flex items-center justify-between">
+                <div>
+                  <span className="text-sm text-gray-900 dark:text-gray-100">
+                    {t("settings.codexSingleConfig")}
+                  </span>
+                  <p className="text-xs text-gray-500 dark:text-gray-400 mt-1 max-w-[34rem]">
+                    {t("settings.codexSingleConfigDescription")}
+                  </p>
+                </div>
+                <input
+                  type="checkbox"
+                  checked={!!settings.codexSingleConfig}
+                  onChange={(e) =>
+                    setSettings((prev) => ({
+                      ...prev,
+                      codexSingleConfig: e.target.checked,
+                    }))
+                  }
+                  className="w-4 h-4 text-blue-500 rounded focus:ring-blue-500/20"
+                />
+              </label>
+              {/* Claude Code 状态栏 */}
+              <label className="flex items-center justify-between">
+                <div>
+                  <span className="text-sm text-gray-900 dark:text-gray-100">
+                    {t("settings.claudeStatusline")}
+                  </span>
+                  <p className="text-xs text-gray-500 dark:text-gray-400 mt-1 max-w-[34rem]">
+                    {t("settings.claudeStatuslineDescription")}
+                  </p>
+                </div>
+                <input
+                  type="checkbox"
+                  checked={!!settings.claudeStatusline}
+                  onChange={(e) =>
+                    setSettings((prev) => ({
+                      ...prev,
+                      claudeStatusline: e.target.checked,
+                    }))
+                  }
+                  className="w-4 h-4 text-blue-500 rounded focus:ring-blue-500/20"
+                />
+              </label>
+              {/* 运行中会话的重启提醒 */}
+              <label className="flex items-center justify-between">
+                <div>
+                  <span className="text-sm text-gray-900 dark:text-gray-100">
+                    {t("settings.runningSessionNotice")}
+                  </span>
+                  <p className="text-xs text-gray-500 dark:text-gray-400 mt-1 max-w-[34rem]">
+                    {t("settings.runningSessionNoticeDescription")}
+                  </p>
+                </div>
+                <input
+                  type="checkbox"
+                  checked={!!settings.runningSessionNotice}
+                  onChange={(e) =>
+                    setSettings((prev) => ({
+                      ...prev,
+                      runningSessionNotice: e.target.checked,
+                    }))
+                  }
+                  className="w-4 h-4 text-blue-500 rounded focus:ring-blue-500/20"
+                />
+              </label>
+              {/* 按时间段规则自动切换 */}
+              <label className="flex items-center justify-between">
+                <div>
+                  <span className="text-sm text-gray-900 dark:text-gray-100">
+                    {t("settings.routingAutoSwitch")}
+                  </span>
+                  <p className="text-xs text-gray-500 dark:text-gray-400 mt-1 max-w-[34rem]">
+                    {t("settings.routingAutoSwitchDescription")}
+                  </p>
+                </div>
+                <input
+                  type="checkbox"
+                  checked={!!settings.routingAutoSwitch}
+                  onChange={(e) =>
+                    setSettings((prev) => ({
+                      ...prev,
+                      routingAutoSwitch: e.target.checked,
+                    }))
+                  }
+                  className="w-4 h-4 text-blue-500 rounded focus:ring-blue-500/20"
+                />
+              </label>
+              {/* VS Code 扩展配置路径 */}
+              <div>
+                <span className="text-sm text-gray-900 dark:text-gray-100">
+                  {t("settings.vscodeSettingsPath")}
+                </span>
+                <p className="text-xs text-gray-500 dark:text-gray-400 mt-1 mb-2 max-w-[34rem]">
+                  {t("settings.vscodeSettingsPathDescription")}
+                </p>
+                <input
+                  type="text"
+                  value={settings.vscodeSettingsPath ?? ""}
+                  onChange={(e) =>
+                    setSettings((prev) => ({
+                      ...prev,
+                      vscodeSettingsPath: e.target.value || undefined,
+                    }))
+                  }
+                  placeholder={t("settings.vscodeSettingsPathPlaceholder")}
+                  className="w-full px-3 py-2 text-xs font-mono bg-white dark:bg-gray-800 border border-gray-200 dark:border-gray-700 rounded-lg focus:outline-none focus:ring-2 focus:ring-blue-500/40"
+                />
+              </div>
             </div>
           </div>
 
@@ -832,19 +1261,25 @@ export default function SettingsModal({
                   {t("settings.appConfigDir")}
                 </label>
                 <p className="text-xs text-gray-400 dark:text-gray-500 mb-1">
-                  {t("settings.appConfigDirDescription")}
+                  {portableDataDir
+                    ? t("settings.portableDataDirDescription")
+                    : t("settings.appConfigDirDescription")}
                 </p>
                 <div className="flex gap-2">
                   <input
                     type="text"
-                    value={appConfigDir ?? resolvedAppConfigDir ?? ""}
+                    value={
+                      portableDataDir ?? appConfigDir ?? resolvedAppConfigDir ?? ""
+                    }
                     onChange={(e) => setAppConfigDir(e.target.value)}
                     placeholder={t("settings.browsePlaceholderApp")}
-                    className="flex-1 px-3 py-2 text-xs font-mono bg-white dark:bg-gray-800 border border-gray-200 dark:border-gray-700 rounded-lg focus:outline-none focus:ring-2 focus:ring-blue-500/40"
+                    disabled={!!portableDataDir}
+                    className="flex-1 px-3 py-2 text-xs font-mono bg-white dark:bg-gray-800 border border-gray-200 dark:border-gray-700 rounded-lg focus:outline-none focus:ring-2 focus:ring-blue-500/40 disabled:opacity-60"
                   />
                   <button
                     type="button"
                     onClick={handleBrowseAppConfigDir}
+                    disabled={!!portableDataDir}
                     className="px-2 py-2 text-xs text-gray-500 dark:text-gray-400 hover:text-blue-500 dark:hover:text-blue-400 hover:bg-gray-100 dark:hover:bg-gray-800 rounded-lg transition-colors"
                     title={t("settings.browseDirectory")}
                   >
@@ -853,6 +1288,7 @@ export default function SettingsModal({
                   <button
                     type="button"
                     onClick={handleResetAppConfigDir}
+                    disabled={!!portableDataDir}
                     className="px-2 py-2 text-xs text-gray-500 dark:text-gray-400 hover:text-blue-500 dark:hover:text-blue-400 hover:bg-gray-100 dark:hover:bg-gray-800 rounded-lg transition-colors"
                     title={t("settings.resetDefault")}
                   >
@@ -935,6 +1371,117 @@ export default function SettingsModal({
             </div>
           </div>
 
+          {/* 应用锁 */}
+          <AppLockSettings onNotify={onNotify} />
+
+          {/* 团队托管配置 */}
+          <ManagedSourceSettings
+            source={settings.managedSource}
+            onChange={(managedSource) =>
+              setSettings((prev) => ({ ...prev, managedSource }))
+            }
+            onSynced={onImportSuccess}
+            onNotify={onNotify}
+          />
+
+          {/* 通用配置片段 */}
+          <CommonFragmentsSettings onNotify={onNotify} />
+
+          {/* 合并策略 */}
+          <MergeStrategiesSettings
+            strategies={settings.mergeStrategies}
+            onChange={(mergeStrategies) =>
+              setSettings((prev) => ({ ...prev, mergeStrategies }))
+            }
+          />
+
+          {/* 自动排序 */}
+          <AutoRankSettings
+            autoRank={settings.autoRank}
+            onChange={(autoRank) =>
+              setSettings((prev) => ({ ...prev, autoRank }))
+            }
+            onRanked={onImportSuccess}
+            onNotify={onNotify}
+          />
+
+          {/* 请求路由 */}
+          <RequestRoutesSettings
+            routes={settings.requestRoutes}
+            onChange={(requestRoutes) =>
+              setSettings((prev) => ({ ...prev, requestRoutes }))
+            }
+          />
+
+          {/* 局域网共享 */}
+          <LanShareSettings
+            lanShare={settings.lanShare}
+            onChange={(lanShare) =>
+              setSettings((prev) => ({ ...prev, lanShare }))
+            }
+            onConnected={onImportSuccess}
+            onNotify={onNotify}
+          />
+
+          {/* 上游域名解析 */}
+          <ProxyDnsSettings
+            dns={settings.proxyDns}
+            onChange={(proxyDns) =>
+              setSettings((prev) => ({ ...prev, proxyDns }))
+            }
+          />
+
+          {/* 上游连接 */}
+          <ProxyConnectionSettings
+            connection={settings.proxyConnection}
+            onChange={(proxyConnection) =>
+              setSettings((prev) => ({ ...prev, proxyConnection }))
+            }
+            onNotify={onNotify}
+          />
+
+          {/* 请求录制与重放 */}
+          <ProxyCaptureSettings
+            capture={settings.proxyCapture}
+            onChange={(proxyCapture) =>
+              setSettings((prev) => ({ ...prev, proxyCapture }))
+            }
+            onNotify={onNotify}
+          />
+
+          {/* Key 用量 */}
+          <KeyUsageSection onNotify={onNotify} />
+
+          {/* 首字节延迟 */}
+          <TtfbSection onNotify={onNotify} />
+
+          {/* 用量日历 */}
+          <UsageCalendarSection onNotify={onNotify} />
+
+          {/* Webhook 通知 */}
+          <WebhookSettingsSection
+            webhook={settings.webhook}
+            onChange={(webhook) =>
+              setSettings((prev) => ({ ...prev, webhook }))
+            }
+            onNotify={onNotify}
+          />
+
+          {/* 切换钩子 */}
+          <HooksSettings
+            hooks={settings.hooks ?? []}
+            onChange={(hooks) => setSettings((prev) => ({ ...prev, hooks }))}
+            onNotify={onNotify}
+          />
+
+          {/* 审计日志 */}
+          <AuditLogSection
+            retentionDays={settings.auditRetentionDays ?? 90}
+            onRetentionChange={(days) =>
+              setSettings((prev) => ({ ...prev, auditRetentionDays: days }))
+            }
+          />
+
           {/* 导入导出 */}
           <div>
             <h3 className="text-sm font-medium text-gray-900 dark:text-gray-100 mb-3">
@@ -951,6 +1498,15 @@ export default function SettingsModal({
                   {t("settings.exportConfig")}
                 </button>
 
+                {/* 导出诊断包 */}
+                <button
+                  onClick={handleExportDiagnostics}
+                  className="w-full flex items-center justify-center gap-2 px-3 py-2 text-xs font-medium rounded-lg transition-colors bg-gray-500 hover:bg-gray-600 dark:bg-gray-600 dark:hover:bg-gray-700 text-white"
+                >
+                  <Save size={12} />
+                  {t("settings.exportDiagnostics")}
+                </button>
+
                 {/* 导入区域 */}
                 <div className="space-y-2">
                   <div className="flex items-center gap-2">
@@ -0,0 +1,79 @@
+import React, { useState } from "react";
+import { useTranslation } from "react-i18next";
+import { RefreshCw } from "lucide-react";
+import { ProviderTtfb } from "../types";
+import { extractErrorMessage } from "../utils/errorUtils";
+
+interface TtfbSectionProps {
+  onNotify?: (
+    message: string,
+    type: "success" | "error",
+    duration?: number,
+  ) => void;
+}
+
+// 设置中的首字节延迟区域：代理模式下各供应商流式响应的 TTFB p50 / p95
+const TtfbSection: React.FC<TtfbSectionProps> = ({ onNotify }) => {
+  const { t } = useTranslation();
+  const [stats, setStats] = useState<ProviderTtfb[] | null>(null);
+
+  const handleRefresh = async () => {
+    try {
+      setStats(await window.api.getTtfbStats());
+    } catch (error) {
+      onNotify?.(extractErrorMessage(error), "error", 5000);
+    }
+  };
+
+  return (
+    <div>
+      <h3 className="text-sm font-medium text-gray-900 dark:text-gray-100 mb-2">
+        {t("ttfb.title")}
+      </h3>
+      <p className="text-xs text-gray-500 dark:text-gray-400 mb-3 leading-relaxed">
+        {t("ttfb.description")}
+      </p>
+      <div className="p-4 bg-gray-100 dark:bg-gray-800 rounded-lg space-y-2">
+        <button
+          type="button"
+          onClick={handleRefresh}
+          className="w-full flex items-center justify-center gap-2 px-3 py-2 text-xs font-medium rounded-lg transition-colors bg-gray-500 hover:bg-gray-600 dark:bg-gray-600 dark:hover:bg-gray-700 text-white"
+        >
+          <RefreshCw size={12} />
+          {t("ttfb.refresh")}
+        </button>
+        {stats && stats.length === 0 && (
+          <p className="text-xs text-gray-400 dark:text-gray-500">
+            {t("ttfb.empty")}
+          </p>
+        )}
+        {stats && stats.length > 0 && (
+          <table className="w-full text-xs text-gray-700 dark:text-gray-300">
+            <thead className="text-gray-500 dark:text-gray-400">
+              <tr>
+                <th className="text-left font-normal">{t("ttfb.provider")}</th>
+                <th className="text-right font-normal">{t("ttfb.samples")}</th>
+                <th className="text-right font-normal">p50</th>
+                <th className="text-right font-normal">p95</th>
+              </tr>
+            </thead>
+            <tbody>
+              {stats.map((item) => (
+                <tr key={`${item.appType}:${item.providerId}`}>
+                  <td className="truncate">
+                    {t(`apps.${item.appType}`)} · {item.providerName}
+                  </td>
+                  <td className="text-right">{item.samples}</td>
+                  <td className="text-right">{item.p50Ms}ms</td>
+                  <td className="text-right">{item.p95Ms}ms</td>
+                </tr>
+              ))}
+            </tbody>
+          </table>
+        )}
+      </div>
+    </div>
+  );
+};
+
+export default TtfbSection;
@@ -0,0 +1,117 @@
+import React, { useState } from "react";
+import { useTranslation } from "react-i18next";
+import { Lock, X } from "lucide-react";
+import { isLinux } from "../lib/platform";
+import { extractErrorMessage } from "../utils/errorUtils";
+
+interface UnlockModalProps {
+  isOpen: boolean;
+  onUnlocked: () => void;
+  onCancel: () => void;
+}
+
+export const UnlockModal: React.FC<UnlockModalProps> = ({
+  isOpen,
+  onUnlocked,
+  onCancel,
+}) => {
+  const { t } = useTranslation();
+  const [passcode, setPasscode] = useState("");
+  const [error, setError] = useState("");
+  const [submitting, setSubmitting] = useState(false);
+
+  if (!isOpen) return null;
+
+  const close = (unlocked: boolean) => {
+    setPasscode("");
+    setError("");
+    if (unlocked) {
+      onUnlocked();
+    } else {
+      onCancel();
+    }
+  };
+
+  const handleSubmit = async (e: React.FormEvent) => {
+    e.preventDefault();
+    if (!passcode || submitting) return;
+    setSubmitting(true);
+    try {
+      const status = await window.api.unlockApp(passcode);
+      if (status.unlocked) {
+        close(true);
+      }
+    } catch (err) {
+      setError(extractErrorMessage(err) || t("appLock.unlockFailed"));
+      setPasscode("");
+    } finally {
+      setSubmitting(false);
+    }
+  };
+
+  return (
+    <div className="fixed inset-0 z-[60] flex items-center justify-center">
+      {/* Backdrop */}
+      <div
+        className={`absolute inset-0 bg-black/50${isLinux() ? "" : " backdrop-blur-sm"}`}
+        onClick={() => close(false)}
+      />
+
+      {/* Dialog */}
+      <form
+        onSubmit={handleSubmit}
+        className="relative bg-white dark:bg-gray-900 rounded-xl shadow-lg max-w-sm w-full mx-4 overflow-hidden"
+      >
+        <div className="flex items-center justify-between p-6 border-b border-gray-200 dark:border-gray-800">
+          <div className="flex items-center gap-3">
+            <div className="w-10 h-10 bg-blue-100 dark:bg-blue-500/10 rounded-full flex items-center justify-center">
+              <Lock size={20} className="text-blue-500" />
+            </div>
+            <h3 className="text-lg font-semibold text-gray-900 dark:text-gray-100">
+              {t("appLock.unlockTitle")}
+            </h3>
+          </div>
+          <button
+            type="button"
+            onClick={() => close(false)}
+            className="p-1 text-gray-500 hover:text-gray-900 hover:bg-gray-100 dark:text-gray-400 dark:hover:text-gray-100 dark:hover:bg-gray-800 rounded-md transition-colors"
+          >
+            <X size={18} />
+          </button>
+        </div>
+
+        <div className="p-6 space-y-3">
+          <p className="text-sm text-gray-500 dark:text-gray-400">
+            {t("appLock.unlockHint")}
+          </p>
+          <input
+            type="password"
+            value={passcode}
+            onChange={(e) => setPasscode(e.target.value)}
+            placeholder={t("appLock.passcodePlaceholder")}
+            autoFocus
+            className="w-full px-3 py-2 text-sm border border-gray-200 dark:border-gray-700 rounded-lg bg-white dark:bg-gray-800 text-gray-900 dark:text-gray-100 focus:outline-none focus:ring-2 focus:ring-blue-500/20 focus:border-blue-500"
+          />
+          {error && <p className="text-xs text-red-500">{error}</p>}
+        </div>
+
+        <div className="flex items-center justify-end gap-3 p-6 border-t border-gray-200 dark:border-gray-800 bg-gray-100 dark:bg-gray-900">
+          <button
+            type="button"
+            onClick={() => close(false)}
+            className="px-4 py-2 text-sm font-medium text-gray-500 hover:text-gray-900 hover:bg-white dark:text-gray-400 dark:hover:text-gray-100 dark:hover:bg-gray-800 rounded-md transition-colors"
+          >
+            {t("common.cancel")}
+          </button>
+          <button
+            type="submit"
+            disabled={!passcode || submitting}
+            className="px-4 py-2 text-sm font-medium bg-blue-500 text-white hover:bg-blue-500/90 rounded-md transition-colors disabled:opacity-50"
+          >
+            {t("appLock.unlock")}
+          </button>
+        </div>
+      </form>
+    </div>
+  );
+};
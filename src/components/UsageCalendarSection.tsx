@@ -0,0 +1,235 @@
+import React, { useEffect, useMemo, useState } from "react";
+import { useTranslation } from "react-i18next";
+import { ChevronLeft, ChevronRight, Download } from "lucide-react";
+import { DailyUsage, ReportFormat } from "../types";
+import { cn } from "../lib/styles";
+import { extractErrorMessage } from "../utils/errorUtils";
+
+interface UsageCalendarSectionProps {
+  onNotify?: (
+    message: string,
+    type: "success" | "error",
+    duration?: number,
+  ) => void;
+}
+
+const pad = (value: number) => String(value).padStart(2, "0");
+
+// 本地日期 YYYY-MM-DD（与后端按本地时区汇总的日期一致）
+const formatDate = (date: Date) =>
+  `${date.getFullYear()}-${pad(date.getMonth() + 1)}-${pad(date.getDate())}`;
+
+// 按当月最大请求数分档着色
+const intensityClass = (requests: number, max: number) => {
+  if (requests === 0 || max === 0) return "bg-gray-200 dark:bg-gray-700";
+  const ratio = requests / max;
+  if (ratio > 0.75) return "bg-blue-600 text-white";
+  if (ratio > 0.5) return "bg-blue-500 text-white";
+  if (ratio > 0.25) return "bg-blue-300 dark:bg-blue-700";
+  return "bg-blue-100 dark:bg-blue-900";
+};
+
+// 设置中的用量日历：按天展示代理模式下的请求数，点击某天查看各供应商的请求、失败与 token 用量
+const UsageCalendarSection: React.FC<UsageCalendarSectionProps> = ({
+  onNotify,
+}) => {
+  const { t } = useTranslation();
+  const [month, setMonth] = useState(() => {
+    const now = new Date();
+    return new Date(now.getFullYear(), now.getMonth(), 1);
+  });
+  const [summary, setSummary] = useState<DailyUsage[]>([]);
+  const [selected, setSelected] = useState<string | null>(null);
+
+  useEffect(() => {
+    const end = new Date(month.getFullYear(), month.getMonth() + 1, 0);
+    window.api
+      .getDailySummary(formatDate(month), formatDate(end))
+      .then(setSummary)
+      .catch((error) => onNotify?.(extractErrorMessage(error), "error", 5000));
+  }, [month]);
+
+  // 日期 -> 当天所有供应商的请求数合计
+  const totals = useMemo(() => {
+    const map = new Map<string, number>();
+    for (const item of summary) {
+      map.set(item.date, (map.get(item.date) ?? 0) + item.requests);
+    }
+    return map;
+  }, [summary]);
+  const max = Math.max(0, ...totals.values());
+
+  const daysInMonth = new Date(
+    month.getFullYear(),
+    month.getMonth() + 1,
+    0,
+  ).getDate();
+  // 周一为一周的第一天
+  const leading = (month.getDay() + 6) % 7;
+  const weekdays = t("usageCalendar.weekdays").split(",");
+  const selectedItems = summary.filter((item) => item.date === selected);
+
+  // 导出当前月份的用量报表
+  const handleExport = async (format: ReportFormat) => {
+    const start = formatDate(month);
+    const end = formatDate(
+      new Date(month.getFullYear(), month.getMonth() + 1, 0),
+    );
+    try {
+      const filePath = await window.api.saveFileDialog(
+        `cc-switch-usage-${start.slice(0, 7)}.${format}`,
+      );
+      if (!filePath) return;
+      const result = await window.api.exportUsageReport(
+        start,
+        end,
+        format,
+        filePath,
+      );
+      onNotify?.(
+        t("usageCalendar.exported", {
+          rows: result.rows,
+          cost: result.totalCost.toFixed(2),
+        }),
+        "success",
+        3000,
+      );
+    } catch (error) {
+      onNotify?.(extractErrorMessage(error), "error", 5000);
+    }
+  };
+
+  const shiftMonth = (delta: number) => {
+    setSelected(null);
+    setMonth(new Date(month.getFullYear(), month.getMonth() + delta, 1));
+  };
+
+  return (
+    <div>
+      <h3 className="text-sm font-medium text-gray-900 dark:text-gray-100 mb-2">
+        {t("usageCalendar.title")}
+      </h3>
+      <p className="text-xs text-gray-500 dark:text-gray-400 mb-3 leading-relaxed">
+        {t("usageCalendar.description")}
+      </p>
+      <div className="p-4 bg-gray-100 dark:bg-gray-800 rounded-lg space-y-3">
+        <div className="flex items-center justify-between text-xs text-gray-700 dark:text-gray-300">
+          <button
+            type="button"
+            onClick={() => shiftMonth(-1)}
+            className="p-1 rounded hover:bg-gray-200 dark:hover:bg-gray-700"
+            title={t("usageCalendar.previousMonth")}
+          >
+            <ChevronLeft size={14} />
+          </button>
+          <span className="font-medium">
+            {t("usageCalendar.month", {
+              year: month.getFullYear(),
+              month: month.getMonth() + 1,
+            })}
+          </span>
+          <button
+            type="button"
+            onClick={() => shiftMonth(1)}
+            className="p-1 rounded hover:bg-gray-200 dark:hover:bg-gray-700"
+            title={t("usageCalendar.nextMonth")}
+          >
+            <ChevronRight size={14} />
+          </button>
+        </div>
+        <div className="grid grid-cols-7 gap-1 text-[10px] text-center">
+          {weekdays.map((day) => (
+            <div key={day} className="text-gray-500 dark:text-gray-400">
+              {day}
+            </div>
+          ))}
+          {Array.from({ length: leading }, (_, index) => (
+            <div key={`blank-${index}`} />
+          ))}
+          {Array.from({ length: daysInMonth }, (_, index) => {
+            const date = formatDate(
+              new Date(month.getFullYear(), month.getMonth(), index + 1),
+            );
+            const requests = totals.get(date) ?? 0;
+            return (
+              <button
+                key={date}
+                type="button"
+                onClick={() => setSelected(date === selected ? null : date)}
+                className={cn(
+                  "aspect-square rounded text-gray-700 dark:text-gray-300",
+                  intensityClass(requests, max),
+                  date === selected && "ring-2 ring-blue-500",
+                )}
+                title={t("usageCalendar.dayRequests", { date, count: requests })}
+              >
+                {index + 1}
+              </button>
+            );
+          })}
+        </div>
+        <div className="flex gap-2">
+          {(["csv", "json"] as ReportFormat[]).map((format) => (
+            <button
+              key={format}
+              type="button"
+              onClick={() => handleExport(format)}
+              className="flex-1 flex items-center justify-center gap-2 px-3 py-2 text-xs font-medium rounded-lg transition-colors bg-gray-500 hover:bg-gray-600 dark:bg-gray-600 dark:hover:bg-gray-700 text-white"
+            >
+              <Download size={12} />
+              {t("usageCalendar.export", { format: format.toUpperCase() })}
+            </button>
+          ))}
+        </div>
+        {selected && selectedItems.length === 0 && (
+          <p className="text-xs text-gray-400 dark:text-gray-500">
+            {t("usageCalendar.empty")}
+          </p>
+        )}
+        {selectedItems.length > 0 && (
+          <table className="w-full text-xs text-gray-700 dark:text-gray-300">
+            <thead className="text-gray-500 dark:text-gray-400">
+              <tr>
+                <th className="text-left font-normal">
+                  {t("usageCalendar.provider")}
+                </th>
+                <th className="text-right font-normal">
+                  {t("usageCalendar.requests")}
+                </th>
+                <th className="text-right font-normal">
+                  {t("usageCalendar.failures")}
+                </th>
+                <th className="text-right font-normal">
+                  {t("usageCalendar.tokens")}
+                </th>
+              </tr>
+            </thead>
+            <tbody>
+              {selectedItems.map((item) => (
+                <tr key={`${item.appType}:${item.providerId}`}>
+                  <td className="truncate">
+                    {t(`apps.${item.appType}`)} · {item.providerName}
+                  </td>
+                  <td className="text-right">{item.requests}</td>
+                  <td
+                    className={cn(
+                      "text-right",
+                      item.failures > 0 && "text-red-500 dark:text-red-400",
+                    )}
+                  >
+                    {item.failures}
+                  </td>
+                  <td className="text-right">
+                    {item.tokens.toLocaleString()}
+                  </td>
+                </tr>
+              ))}
+            </tbody>
+          </table>
+        )}
+      </div>
+    </div>
+  );
+};
+
+export default UsageCalendarSection;
@@ -0,0 +1,146 @@
+import React, { useState } from "react";
+import { useTranslation } from "react-i18next";
+import { Send } from "lucide-react";
+import { WebhookEvent, WebhookFormat, WebhookSettings } from "../types";
+import { extractErrorMessage } from "../utils/errorUtils";
+
+interface WebhookSettingsProps {
+  webhook?: WebhookSettings;
+  onChange: (webhook: WebhookSettings) => void;
+  onNotify?: (
+    message: string,
+    type: "success" | "error",
+    duration?: number,
+  ) => void;
+}
+
+const FORMATS: WebhookFormat[] = ["json", "slack", "feishu", "dingtalk"];
+
+const EVENTS: WebhookEvent[] = [
+  "all_providers_failed",
+  "failover",
+  "balance_low",
+  "provider_switched",
+];
+
+const inputClass =
+  "w-full px-3 py-2 text-xs bg-white dark:bg-gray-800 border border-gray-200 dark:border-gray-700 rounded-lg focus:outline-none focus:ring-2 focus:ring-blue-500/40";
+
+// 设置中的 Webhook 通知区域：地址、签名密钥、消息格式与订阅事件
+const WebhookSettingsSection: React.FC<WebhookSettingsProps> = ({
+  webhook,
+  onChange,
+  onNotify,
+}) => {
+  const { t } = useTranslation();
+  const [testing, setTesting] = useState(false);
+  const current: WebhookSettings = webhook ?? { url: "", format: "json" };
+  const events = current.events ?? [];
+
+  const update = (patch: Partial<WebhookSettings>) =>
+    onChange({ ...current, ...patch });
+
+  const toggleEvent = (event: WebhookEvent) =>
+    update({
+      events: events.includes(event)
+        ? events.filter((e) => e !== event)
+        : [...events, event],
+    });
+
+  // 测试使用当前填写的设置，无需先保存
+  const handleTest = async () => {
+    setTesting(true);
+    try {
+      await window.api.testWebhook({
+        ...current,
+        url: current.url.trim(),
+        secret: current.secret?.trim() || undefined,
+      });
+      onNotify?.(t("webhook.testSuccess"), "success", 2000);
+    } catch (error) {
+      onNotify?.(
+        `${t("webhook.testFailed")}: ${extractErrorMessage(error)}`,
+        "error",
+        5000,
+      );
+    } finally {
+      setTesting(false);
+    }
+  };
+
+  return (
+    <div>
+      <h3 className="text-sm font-medium text-gray-900 dark:text-gray-100 mb-2">
+        {t("webhook.title")}
+      </h3>
+      <p className="text-xs text-gray-500 dark:text-gray-400 mb-3 leading-relaxed">
+        {t("webhook.description")}
+      </p>
+      <div className="p-4 bg-gray-100 dark:bg-gray-800 rounded-lg space-y-2">
+        <input
+          type="text"
+          value={current.url}
+          onChange={(e) => update({ url: e.target.value })}
+          placeholder={t("webhook.urlPlaceholder")}
+          className={`${inputClass} font-mono`}
+        />
+        <input
+          type="password"
+          value={current.secret ?? ""}
+          onChange={(e) => update({ secret: e.target.value })}
+          placeholder={t("webhook.secretPlaceholder")}
+          className={inputClass}
+        />
+        <div className="flex items-center gap-2">
+          <label className="text-sm text-gray-500 dark:text-gray-400">
+            {t("webhook.format")}
+          </label>
+          <select
+            value={current.format}
+            onChange={(e) =>
+              update({ format: e.target.value as WebhookFormat })
+            }
+            className="flex-1 px-2 py-1 text-sm bg-white dark:bg-gray-800 border border-gray-200 dark:border-gray-700 rounded-md focus:outline-none focus:ring-2 focus:ring-blue-500/40"
+          >
+            {FORMATS.map((format) => (
+              <option key={format} value={format}>
+                {t(`webhook.formats.${format}`)}
+              </option>
+            ))}
+          </select>
+        </div>
+        <div>
+          <p className="text-sm text-gray-500 dark:text-gray-400 mb-1">
+            {t("webhook.events")}
+          </p>
+          <div className="grid grid-cols-2 gap-1">
+            {EVENTS.map((event) => (
+              <label
+                key={event}
+                className="flex items-center gap-2 text-xs text-gray-700 dark:text-gray-300"
+              >
+                <input
+                  type="checkbox"
+                  checked={events.includes(event)}
+                  onChange={() => toggleEvent(event)}
+                />
+                {t(`webhook.eventNames.${event}`)}
+              </label>
+            ))}
+          </div>
+        </div>
+        <button
+          type="button"
+          onClick={handleTest}
+          disabled={testing || !current.url.trim()}
+          className="w-full flex items-center justify-center gap-2 px-3 py-2 text-xs font-medium rounded-lg transition-colors bg-gray-500 hover:bg-gray-600 dark:bg-gray-600 dark:hover:bg-gray-700 text-white disabled:opacity-50"
+        >
+          <Send size={12} />
+          {t("webhook.test")}
+        </button>
+      </div>
+    </div>
+  );
+};
+
+export default WebhookSettingsSection;
@@ -81,4 +81,27 @@ export const codexProviderPresets: CodexProviderPreset[] = [
       "https://codex-api-hk-cdn.packycode.com/v1",
     ],
   },
+  {
+    name: "Ollama",
+    websiteUrl: "https://ollama.com",
+    category: "local",
+    // 本地服务不校验 Key，填写任意非空值即可
+    auth: generateThirdPartyAuth("ollama"),
+    config: generateThirdPartyConfig(
+      "ollama",
+      "http://localhost:11434/v1",
+      "gpt-oss:20b",
+    ),
+  },
+  {
+    name: "LM Studio",
+    websiteUrl: "https://lmstudio.ai",
+    category: "local",
+    auth: generateThirdPartyAuth("lmstudio"),
+    config: generateThirdPartyConfig(
+      "lmstudio",
+      "http://localhost:1234/v1",
+      "openai/gpt-oss-20b",
+    ),
+  },
 ];
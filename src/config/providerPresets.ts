@@ -165,4 +165,27 @@ export const providerPresets: ProviderPreset[] = [
     ],
     category: "third_party",
   },
+  {
+    name: "Ollama",
+    websiteUrl: "https://ollama.com",
+    settingsConfig: {
+      env: {
+        ANTHROPIC_BASE_URL: "http://localhost:11434",
+        // 本地服务不校验 Key，填写任意非空值即可
+        ANTHROPIC_AUTH_TOKEN: "ollama",
+      },
+    },
+    category: "local",
+  },
+  {
+    name: "LM Studio",
+    websiteUrl: "https://lmstudio.ai",
+    settingsConfig: {
+      env: {
+        ANTHROPIC_BASE_URL: "http://localhost:1234",
+        ANTHROPIC_AUTH_TOKEN: "lmstudio",
+      },
+    },
+    category: "local",
+  },
 ];
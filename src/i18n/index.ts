@@ -4,13 +4,15 @@ import { initReactI18next } from "react-i18next";
 import en from "./locales/en.json";
 import zh from "./locales/zh.json";
 
-const DEFAULT_LANGUAGE: "zh" | "en" = "zh";
+type Language = "zh" | "en" | "ja";
 
-const getInitialLanguage = (): "zh" | "en" => {
+const DEFAULT_LANGUAGE: Language = "zh";
+
+const getInitialLanguage = (): Language => {
   if (typeof window !== "undefined") {
     try {
       const stored = window.localStorage.getItem("language");
-      if (stored === "zh" || stored === "en") {
+      if (stored === "zh" || stored === "en" || stored === "ja") {
         return stored;
       }
     } catch (error) {
@@ -32,6 +34,10 @@ const getInitialLanguage = (): "zh" | "en" => {
     return "en";
   }
 
+  if (navigatorLang?.startsWith("ja")) {
+    return "ja";
+  }
+
   return DEFAULT_LANGUAGE;
 };
 
@@ -47,7 +53,7 @@ const resources = {
 i18n.use(initReactI18next).init({
   resources,
   lng: getInitialLanguage(), // 根据本地存储或系统语言选择默认语言
-  fallbackLng: "en", // 如果缺少中文翻译则退回英文
+  fallbackLng: "en", // 缺少对应翻译（含日文界面）时退回英文
 
   interpolation: {
     escapeValue: false, // React 已经默认转义
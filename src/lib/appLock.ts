@@ -0,0 +1,23 @@
+// 应用锁：敏感操作前确认已解锁，未解锁时弹出密码输入框
+// 真正的校验在后端命令中进行，这里只负责在调用前引导用户解锁
+
+type UnlockHandler = () => Promise<boolean>;
+
+let unlockHandler: UnlockHandler | null = null;
+
+// 由 App 注册解锁弹窗，返回用户是否成功解锁
+export const registerUnlockHandler = (handler: UnlockHandler | null) => {
+  unlockHandler = handler;
+};
+
+// 确保处于解锁状态；用户取消时返回 false
+export const ensureAppUnlocked = async (): Promise<boolean> => {
+  try {
+    const status = await window.api.getAppLockStatus();
+    if (!status.enabled || status.unlocked) return true;
+  } catch (error) {
+    console.error("获取应用锁状态失败:", error);
+    return true; // 交由后端校验
+  }
+  return unlockHandler ? await unlockHandler() : false;
+};
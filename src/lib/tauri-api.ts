@@ -8,7 +8,52 @@ import {
   McpServer,
   McpServerSpec,
   McpConfigResponse,
+  MigrationReport,
+  ProjectBinding,
+  EnvSnippet,
+  DeepLinkRequest,
+  ProviderShare,
+  DecodedShare,
+  ProviderSnippet,
+  SnippetMerge,
+  DuplicateProvider,
+  ImportedDuplicate,
+  BulkResult,
+  ProvidersUpdatedEvent,
+  ProxyAuthFailedEvent,
+  RunningSession,
+  SessionsRestartNeededEvent,
+  ProxyStatusEvent,
+  RepairAction,
+  SelfCheckReport,
+  ProviderFilter,
+  ProviderSummary,
+  AppLockStatus,
+  AuditEntry,
+  ManagedSyncReport,
+  WebhookSettings,
+  SwitchHook,
+  HookRun,
+  CommonFragment,
+  CommonMergePreview,
+  ProviderRank,
+  SharedProxy,
+  CapturedRequest,
+  ReplayResult,
+  KeyUsage,
+  ProviderTtfb,
+  DailyUsage,
+  ReportFormat,
+  UsageReportResult,
+  CaCertificates,
+  ProtocolDetection,
+  WizardUrlCheck,
+  WizardKeyCheck,
+  WizardTemplate,
+  WizardInput,
+  WizardPreview,
 } from "../types";
+import { extractErrorMessage } from "../utils/errorUtils";
 
 // 应用类型
 export type AppType = "claude" | "codex";
@@ -123,7 +168,7 @@ export const tauriAPI = {
       console.error("导入默认配置失败:", error);
       return {
         success: false,
-        message: String(error),
+        message: extractErrorMessage(error),
       };
     }
   },
@@ -179,6 +224,18 @@ export const tauriAPI = {
     }
   },
 
+  // 打开供应商网站控制台（按配置附带登录 Token 等查询参数）
+  openProviderDashboard: async (
+    id: string,
+    app?: AppType,
+  ): Promise<boolean> => {
+    return await invoke<boolean>("open_provider_dashboard", {
+      id,
+      app_type: app,
+      app,
+    });
+  },
+
   // 更新托盘菜单
   updateTrayMenu: async (): Promise<boolean> => {
     try {
@@ -238,6 +295,169 @@ export const tauriAPI = {
     }
   },
 
+  // 获取便携模式数据目录（非便携模式返回 null）
+  getPortableDataDir: async (): Promise<string | null> => {
+    try {
+      return await invoke<string | null>("get_portable_data_dir");
+    } catch (error) {
+      console.error("获取便携模式数据目录失败:", error);
+      return null;
+    }
+  },
+
+  // 获取尚未确认的旧版布局迁移报告
+  getMigrationReport: async (): Promise<MigrationReport | null> => {
+    try {
+      return await invoke<MigrationReport | null>("get_migration_report");
+    } catch (error) {
+      console.error("获取迁移报告失败:", error);
+      return null;
+    }
+  },
+
+  // 确认迁移报告（之后不再提示）
+  acknowledgeMigrationReport: async (): Promise<boolean> => {
+    try {
+      return await invoke<boolean>("acknowledge_migration_report");
+    } catch (error) {
+      console.error("确认迁移报告失败:", error);
+      return false;
+    }
+  },
+
+  // 导出诊断包（配置已打码），返回 zip 路径
+  exportDiagnostics: async (filePath?: string): Promise<string> => {
+    try {
+      return await invoke<string>("export_diagnostics", { filePath });
+    } catch (error) {
+      console.error("导出诊断包失败:", error);
+      throw new Error(extractErrorMessage(error));
+    }
+  },
+
+  // 获取最近的日志行（应用内日志查看器）
+  getRecentLogs: async (lines?: number): Promise<string[]> => {
+    try {
+      return await invoke<string[]>("get_recent_logs", { lines });
+    } catch (error) {
+      console.error("获取日志失败:", error);
+      throw new Error(extractErrorMessage(error));
+    }
+  },
+
+  // 项目级切换：获取已记住的项目列表
+  getProjects: async (): Promise<ProjectBinding[]> => {
+    return await invoke<ProjectBinding[]>("get_projects");
+  },
+
+  // 记住一个项目目录
+  addProject: async (path: string): Promise<ProjectBinding[]> => {
+    return await invoke<ProjectBinding[]>("add_project", { path });
+  },
+
+  // 从列表移除项目（不修改项目文件）
+  removeProject: async (path: string): Promise<ProjectBinding[]> => {
+    return await invoke<ProjectBinding[]>("remove_project", { path });
+  },
+
+  // 将 Claude 供应商写入项目的 .claude/settings.local.json
+  switchProjectProvider: async (
+    path: string,
+    providerId: string,
+  ): Promise<ProjectBinding> => {
+    return await invoke<ProjectBinding>("switch_project_provider", {
+      path,
+      providerId,
+    });
+  },
+
+  // 清理项目中由 cc-switch 写入的配置
+  clearProjectProvider: async (path: string): Promise<ProjectBinding> => {
+    return await invoke<ProjectBinding>("clear_project_provider", { path });
+  },
+
+  // 环境变量模式：获取 shell 片段（未指定时按平台选择 sh / ps1）
+  getEnvSnippet: async (shell?: "sh" | "ps1"): Promise<EnvSnippet> => {
+    return await invoke<EnvSnippet>("get_env_snippet", { shell });
+  },
+
+  // 取出待确认的深链接请求（冷启动时链接先于前端到达）
+  takePendingDeepLink: async (): Promise<DeepLinkRequest | null> => {
+    try {
+      return await invoke<DeepLinkRequest | null>("take_pending_deep_link");
+    } catch (error) {
+      console.error("获取深链接请求失败:", error);
+      return null;
+    }
+  },
+
+  // 监听深链接到达事件（收到后调用 takePendingDeepLink 读取）
+  onDeepLinkRequest: async (callback: () => void): Promise<UnlistenFn> => {
+    return await listen("deep-link-request", () => callback());
+  },
+
+  // 生成供应商分享字符串与二维码（默认去除 API Key）
+  encodeProviderShare: async (
+    providerId: string,
+    app: AppType,
+    includeKey = false,
+  ): Promise<ProviderShare> => {
+    return await invoke<ProviderShare>("encode_provider_share", {
+      id: providerId,
+      includeKey,
+      app_type: app,
+      app,
+    });
+  },
+
+  // 解析分享字符串或 ccswitch://add 链接
+  decodeProviderShare: async (payload: string): Promise<DecodedShare> => {
+    return await invoke<DecodedShare>("decode_provider_share", { payload });
+  },
+
+  // 解析粘贴的环境变量 / JSON / TOML 配置片段为供应商草稿（不写入配置）
+  parseProviderSnippet: async (
+    text: string,
+    app?: AppType,
+  ): Promise<ProviderSnippet> => {
+    return await invoke<ProviderSnippet>("parse_provider_snippet", {
+      text,
+      app_type: app,
+      app,
+    });
+  },
+
+  // 添加或导入前检查是否已有相同 API 地址与密钥的供应商
+  findDuplicateProvider: async (
+    provider: Provider,
+    app?: AppType,
+  ): Promise<DuplicateProvider | null> => {
+    return await invoke<DuplicateProvider | null>("find_duplicate_provider", {
+      provider,
+      app_type: app,
+      app,
+    });
+  },
+
+  // 用粘贴的新片段匹配已有供应商，预览 API 地址 / 凭证的变化（不写入配置）
+  mergeProviderSnippet: async (
+    text: string,
+    app?: AppType,
+    providerId?: string,
+  ): Promise<SnippetMerge> => {
+    return await invoke<SnippetMerge>("merge_provider_snippet", {
+      text,
+      providerId,
+      app_type: app,
+      app,
+    });
+  },
+
+  // 将 Claude Code 当前登录的订阅账号导入为供应商
+  importClaudeOAuthAccount: async (): Promise<Provider> => {
+    return await invoke<Provider>("import_claude_oauth_account");
+  },
+
   // 获取应用配置文件路径
   getAppConfigPath: async (): Promise<string> => {
     try {
@@ -263,7 +483,7 @@ export const tauriAPI = {
       return await invoke<ConfigStatus>("get_claude_plugin_status");
     } catch (error) {
       console.error("获取 Claude 插件状态失败:", error);
-      return { exists: false, path: "", error: String(error) };
+      return { exists: false, path: "", error: extractErrorMessage(error) };
     }
   },
 
@@ -272,7 +492,7 @@ export const tauriAPI = {
     try {
       return await invoke<string | null>("read_claude_plugin_config");
     } catch (error) {
-      throw new Error(`读取 Claude 插件配置失败: ${String(error)}`);
+      throw new Error(`读取 Claude 插件配置失败: ${extractErrorMessage(error)}`);
     }
   },
 
@@ -284,7 +504,7 @@ export const tauriAPI = {
     try {
       return await invoke<boolean>("apply_claude_plugin_config", { official });
     } catch (error) {
-      throw new Error(`写入 Claude 插件配置失败: ${String(error)}`);
+      throw new Error(`写入 Claude 插件配置失败: ${extractErrorMessage(error)}`);
     }
   },
 
@@ -293,7 +513,7 @@ export const tauriAPI = {
     try {
       return await invoke<boolean>("is_claude_plugin_applied");
     } catch (error) {
-      throw new Error(`检测 Claude 插件配置失败: ${String(error)}`);
+      throw new Error(`检测 Claude 插件配置失败: ${extractErrorMessage(error)}`);
     }
   },
 
@@ -311,7 +531,7 @@ export const tauriAPI = {
         appType: app,
       });
     } catch (error) {
-      throw new Error(`查询用量失败: ${String(error)}`);
+      throw new Error(`查询用量失败: ${extractErrorMessage(error)}`);
     }
   },
 
@@ -496,6 +716,86 @@ export const tauriAPI = {
     }
   },
 
+  // 检测地址支持的接口格式并给出配置建议
+  probeEndpoint: async (
+    url: string,
+    key?: string,
+  ): Promise<ProtocolDetection> => {
+    return await invoke<ProtocolDetection>("probe_endpoint", { url, key });
+  },
+
+  // 发现供应商的可用模型（结果保存到 meta.discovered_models）
+  discoverProviderModels: async (
+    providerId: string,
+    app?: AppType,
+  ): Promise<string[]> => {
+    return await invoke<string[]>("discover_provider_models", {
+      providerId,
+      app_type: app,
+      app,
+    });
+  },
+
+  // 按地址与 API Key 发现可用模型（新建供应商时使用）
+  discoverModels: async (baseUrl: string, apiKey?: string): Promise<string[]> => {
+    return await invoke<string[]>("discover_models", { baseUrl, apiKey });
+  },
+
+  // 新建供应商向导：检查地址格式与可达性
+  wizardCheckUrl: async (url: string): Promise<WizardUrlCheck> => {
+    return await invoke<WizardUrlCheck>("wizard_check_url", { url });
+  },
+
+  // 新建供应商向导：低成本校验 API Key
+  wizardCheckKey: async (
+    url: string,
+    key: string,
+    app?: AppType,
+  ): Promise<WizardKeyCheck> => {
+    return await invoke<WizardKeyCheck>("wizard_check_key", {
+      url,
+      key,
+      app_type: app,
+      app,
+    });
+  },
+
+  // 新建供应商向导：列出配置模板（传入地址时标注支持情况与推荐模板）
+  wizardTemplates: async (
+    app?: AppType,
+    url?: string,
+    key?: string,
+  ): Promise<WizardTemplate[]> => {
+    return await invoke<WizardTemplate[]>("wizard_templates", {
+      url,
+      key,
+      app_type: app,
+      app,
+    });
+  },
+
+  // 新建供应商向导：预览生成的供应商配置（不保存）
+  wizardPreview: async (input: WizardInput): Promise<WizardPreview> => {
+    return await invoke<WizardPreview>("wizard_preview", { input });
+  },
+
+  // 测试供应商的主地址与全部自定义端点（结果用于按延迟排序的端点故障转移）
+  testProviderEndpoints: async (
+    providerId: string,
+    app?: AppType,
+  ): Promise<EndpointLatencyResult[]> => {
+    try {
+      return await invoke<EndpointLatencyResult[]>("test_provider_endpoints", {
+        providerId,
+        app_type: app,
+        app,
+      });
+    } catch (error) {
+      console.error("测试供应商端点失败:", error);
+      throw error;
+    }
+  },
+
   // 获取自定义端点列表
   getCustomEndpoints: async (
     appType: AppType,
@@ -537,7 +837,7 @@ export const tauriAPI = {
       if (error instanceof Error) {
         throw error;
       } else {
-        throw new Error(String(error));
+        throw new Error(extractErrorMessage(error));
       }
     }
   },
@@ -600,7 +900,7 @@ export const tauriAPI = {
         filePath: filePath,
       });
     } catch (error) {
-      throw new Error(`导出配置失败: ${String(error)}`);
+      throw new Error(`导出配置失败: ${extractErrorMessage(error)}`);
     }
   },
 
@@ -611,6 +911,7 @@ export const tauriAPI = {
     success: boolean;
     message: string;
     backupId?: string;
+    duplicates?: ImportedDuplicate[];
   }> => {
     try {
       // 兼容参数命名差异：同时传递 file_path 与 filePath
@@ -619,7 +920,7 @@ export const tauriAPI = {
         filePath: filePath,
       });
     } catch (error) {
-      throw new Error(`导入配置失败: ${String(error)}`);
+      throw new Error(`导入配置失败: ${extractErrorMessage(error)}`);
     }
   },
 
@@ -684,23 +985,6 @@ export const tauriAPI = {
     }
   },
 
-  // Update providers sort order
-  updateProvidersSortOrder: async (
-    updates: Array<{ id: string; sortIndex: number }>,
-    app?: AppType,
-  ): Promise<boolean> => {
-    try {
-      return await invoke<boolean>("update_providers_sort_order", {
-        updates,
-        app_type: app,
-        app,
-      });
-    } catch (error) {
-      console.error("更新供应商排序失败:", error);
-      throw error;
-    }
-  },
-
   // 切换供应商的代理启用状态
   toggleProxyProvider: async (
     providerId: string,
@@ -721,40 +1005,448 @@ export const tauriAPI = {
     }
   },
 
-  // 处理运行模式变更
-  handleOperationModeChange: async (
-    operationMode: string,
-    claudeCommonConfig?: string,
-    codexCommonConfig?: string,
+  // 启用/停用供应商
+  setProviderEnabled: async (
+    providerId: string,
+    enabled: boolean,
+    app?: AppType,
+  ): Promise<boolean> => {
+    return await invoke<boolean>("set_provider_enabled", {
+      providerId,
+      enabled,
+      app_type: app,
+      app,
+    });
+  },
+
+  // 锁定/解锁供应商
+  setProviderLocked: async (
+    providerId: string,
+    locked: boolean,
+    app?: AppType,
+  ): Promise<boolean> => {
+    return await invoke<boolean>("set_provider_locked", {
+      providerId,
+      locked,
+      app_type: app,
+      app,
+    });
+  },
+
+  // 置顶/取消置顶供应商（代理故障转移中始终优先）
+  setProviderPinned: async (
+    providerId: string,
+    pinned: boolean,
+    app?: AppType,
+  ): Promise<boolean> => {
+    return await invoke<boolean>("set_provider_pinned", {
+      providerId,
+      pinned,
+      app_type: app,
+      app,
+    });
+  },
+
+  // 让供应商进入维护模式 minutes 分钟（0 表示结束维护），返回结束时间
+  setProviderMaintenance: async (
+    providerId: string,
+    minutes: number,
+    app?: AppType,
+  ): Promise<number | null> => {
+    return await invoke<number | null>("set_provider_maintenance", {
+      providerId,
+      minutes,
+      app_type: app,
+      app,
+    });
+  },
+
+  // 获取供应商未脱敏的配置（后端会弹出系统确认框）
+  revealProviderSecret: async (
+    providerId: string,
+    app?: AppType,
+  ): Promise<Record<string, any>> => {
+    return await invoke<Record<string, any>>("reveal_provider_secret", {
+      id: providerId,
+      app_type: app,
+      app,
+    });
+  },
+
+  // 应用锁状态
+  getAppLockStatus: async (): Promise<AppLockStatus> => {
+    return await invoke<AppLockStatus>("get_app_lock_status");
+  },
+
+  // 验证访问密码并解锁
+  unlockApp: async (passcode: string): Promise<AppLockStatus> => {
+    return await invoke<AppLockStatus>("unlock_app", { passcode });
+  },
+
+  // 立即重新锁定
+  lockApp: async (): Promise<AppLockStatus> => {
+    return await invoke<AppLockStatus>("lock_app");
+  },
+
+  // 设置/修改/移除访问密码（passcode 为空表示移除）
+  setAppLockPasscode: async (
+    current: string | null,
+    passcode: string | null,
+  ): Promise<AppLockStatus> => {
+    return await invoke<AppLockStatus>("set_app_lock_passcode", {
+      current,
+      passcode,
+    });
+  },
+
+  // 获取审计日志（最新在前）
+  getAuditLog: async (limit?: number, offset?: number): Promise<AuditEntry[]> => {
+    return await invoke<AuditEntry[]>("get_audit_log", { limit, offset });
+  },
+
+  // 立即同步团队托管配置
+  refreshManagedProviders: async (): Promise<ManagedSyncReport> => {
+    return await invoke<ManagedSyncReport>("refresh_managed_providers");
+  },
+
+  // 使用给定设置发送测试消息
+  testWebhook: async (webhook: WebhookSettings): Promise<boolean> => {
+    return await invoke<boolean>("test_webhook", { webhook });
+  },
+
+  // 最近的钩子执行记录（新的在前）
+  getHookRuns: async (): Promise<HookRun[]> => {
+    return await invoke<HookRun[]>("get_hook_runs");
+  },
+
+  // 用示例事件信息试运行钩子（无需先保存）
+  testHook: async (hook: SwitchHook): Promise<HookRun> => {
+    return await invoke<HookRun>("test_hook", { hook });
+  },
+
+  // 按条件检索供应商（仅返回摘要，不含配置与密钥）
+  queryProviders: async (
+    app: AppType,
+    filter: ProviderFilter = {},
+  ): Promise<ProviderSummary[]> => {
+    return await invoke<ProviderSummary[]>("query_providers", {
+      filter,
+      app_type: app,
+      app,
+    });
+  },
+
+  // 批量启用/关闭代理
+  bulkSetProxyEnabled: async (
+    ids: string[],
+    enabled: boolean,
+    app?: AppType,
+  ): Promise<BulkResult> => {
+    return await invoke<BulkResult>("bulk_set_proxy_enabled", {
+      ids,
+      enabled,
+      app_type: app,
+      app,
+    });
+  },
+
+  // 批量删除供应商（当前供应商会被跳过）
+  bulkDeleteProviders: async (
+    ids: string[],
+    app?: AppType,
+  ): Promise<BulkResult> => {
+    return await invoke<BulkResult>("bulk_delete_providers", {
+      ids,
+      app_type: app,
+      app,
+    });
+  },
+
+  // 批量设置分类（传空值清除分类）
+  bulkSetCategory: async (
+    ids: string[],
+    category: string | null,
+    app?: AppType,
+  ): Promise<BulkResult> => {
+    return await invoke<BulkResult>("bulk_set_category", {
+      ids,
+      category,
+      app_type: app,
+      app,
+    });
+  },
+
+  // 按 orderedIds 顺序重排并重写全部 sortIndex（返回重排后的完整顺序）
+  reorderProviders: async (
+    orderedIds: string[],
+    app?: AppType,
+  ): Promise<string[]> => {
+    return await invoke<string[]>("reorder_providers", {
+      orderedIds,
+      app_type: app,
+      app,
+    });
+  },
+
+  // 监听批量操作完成事件
+  onProvidersUpdated: async (
+    callback: (data: ProvidersUpdatedEvent) => void,
+  ): Promise<UnlistenFn> => {
+    return await listen<ProvidersUpdatedEvent>("providers-updated", (event) =>
+      callback(event.payload),
+    );
+  },
+
+  // 监听代理鉴权失败事件
+  onProxyAuthFailed: async (
+    callback: (data: ProxyAuthFailedEvent) => void,
+  ): Promise<UnlistenFn> => {
+    return await listen<ProxyAuthFailedEvent>("proxy-auth-failed", (event) =>
+      callback(event.payload),
+    );
+  },
+
+  // 列出运行中的 Claude Code / Codex 会话
+  getRunningSessions: async (app?: AppType): Promise<RunningSession[]> => {
+    return await invoke<RunningSession[]>("get_running_sessions", {
+      app_type: app,
+      app,
+    });
+  },
+
+  // 监听切换后仍有会话使用旧配置的提醒
+  onSessionsRestartNeeded: async (
+    callback: (data: SessionsRestartNeededEvent) => void,
+  ): Promise<UnlistenFn> => {
+    return await listen<SessionsRestartNeededEvent>(
+      "sessions-restart-needed",
+      (event) => callback(event.payload),
+    );
+  },
+
+  // 监听本地代理中断/恢复事件
+  onProxyStatusChanged: async (
+    callback: (data: ProxyStatusEvent) => void,
+  ): Promise<UnlistenFn> => {
+    return await listen<ProxyStatusEvent>("proxy-status-changed", (event) =>
+      callback(event.payload),
+    );
+  },
+
+  // 监听配置被其他进程修改并重新加载的事件
+  onConfigReloaded: async (
+    callback: (data: { revision: number }) => void,
+  ): Promise<UnlistenFn> => {
+    return await listen<{ revision: number }>("config-reloaded", (event) =>
+      callback(event.payload),
+    );
+  },
+
+  // 监听配置被其他进程修改且与本地未保存修改冲突的事件
+  onConfigConflict: async (
+    callback: (data: { revision: number }) => void,
+  ): Promise<UnlistenFn> => {
+    return await listen<{ revision: number }>("config-conflict", (event) =>
+      callback(event.payload),
+    );
+  },
+
+  // 解决配置冲突：保留本地修改（覆盖外部配置）或使用外部配置
+  resolveConfigConflict: async (keepLocal: boolean): Promise<boolean> => {
+    return await invoke<boolean>("resolve_config_conflict", {
+      keep_local: keepLocal,
+      keepLocal,
+    });
+  },
+
+  // 监听后台延迟写盘失败的事件
+  onConfigSaveFailed: async (
+    callback: (data: { message: string }) => void,
+  ): Promise<UnlistenFn> => {
+    return await listen<{ message: string }>("config-save-failed", (event) =>
+      callback(event.payload),
+    );
+  },
+
+  // 按当前运行模式重新写入 live 配置
+  reapplyCurrent: async (app?: AppType): Promise<boolean> => {
+    return await invoke<boolean>("reapply_current", { app_type: app, app });
+  },
+
+  // 获取配置自检结果（refresh 为 true 时重新检查）
+  getConfigSelfCheck: async (refresh?: boolean): Promise<SelfCheckReport> => {
+    return await invoke<SelfCheckReport>("get_config_self_check", { refresh });
+  },
+
+  // 按指定方式修复配置问题，返回修复后的自检结果
+  repairConfigIssue: async (
+    action: RepairAction,
+    app?: AppType,
+  ): Promise<SelfCheckReport> => {
+    return await invoke<SelfCheckReport>("repair_config_issue", {
+      action,
+      app_type: app,
+      app,
+    });
+  },
+
+  // 监听启动自检事件
+  onConfigSelfCheck: async (
+    callback: (report: SelfCheckReport) => void,
+  ): Promise<UnlistenFn> => {
+    return await listen<SelfCheckReport>("config-self-check", (event) =>
+      callback(event.payload),
+    );
+  },
+
+  // 获取通用配置片段
+  getCommonFragments: async (app?: AppType): Promise<CommonFragment[]> => {
+    return await invoke<CommonFragment[]>("get_common_fragments", {
+      app_type: app,
+      app,
+    });
+  },
+
+  // 新增或更新通用配置片段（originalName 为修改前的名称）
+  saveCommonFragment: async (
+    fragment: CommonFragment,
+    originalName?: string,
+    app?: AppType,
+  ): Promise<CommonFragment[]> => {
+    return await invoke<CommonFragment[]>("save_common_fragment", {
+      fragment,
+      original_name: originalName,
+      originalName,
+      app_type: app,
+      app,
+    });
+  },
+
+  // 删除通用配置片段
+  deleteCommonFragment: async (
+    name: string,
+    app?: AppType,
+  ): Promise<CommonFragment[]> => {
+    return await invoke<CommonFragment[]>("delete_common_fragment", {
+      name,
+      app_type: app,
+      app,
+    });
+  },
+
+  // 预览片段合并结果（fragments 缺省为已保存的片段）
+  previewCommonMerge: async (
+    app?: AppType,
+    providerId?: string,
+    fragments?: CommonFragment[],
+  ): Promise<CommonMergePreview> => {
+    return await invoke<CommonMergePreview>("preview_common_merge", {
+      provider_id: providerId,
+      providerId,
+      fragments,
+      app_type: app,
+      app,
+    });
+  },
+
+  // 探测已启用的供应商并按错误率、延迟重排
+  autoRankProviders: async (app?: AppType): Promise<ProviderRank[]> => {
+    return await invoke<ProviderRank[]>("auto_rank_providers", {
+      app_type: app,
+      app,
+    });
+  },
+
+  // 搜索局域网中的共享代理
+  discoverSharedProxies: async (): Promise<SharedProxy[]> => {
+    return await invoke<SharedProxy[]>("discover_shared_proxies");
+  },
+
+  // 连接共享代理：添加指向它的供应商并切换
+  connectSharedProxy: async (
+    name: string,
+    url: string,
+    token: string,
   ): Promise<boolean> => {
+    return await invoke<boolean>("connect_shared_proxy", { name, url, token });
+  },
+
+  // 获取录制的代理请求（新的在前）
+  getCapturedRequests: async (): Promise<CapturedRequest[]> => {
+    return await invoke<CapturedRequest[]>("get_captured_requests");
+  },
+
+  // 清空录制的代理请求
+  clearCapturedRequests: async (): Promise<boolean> => {
+    return await invoke<boolean>("clear_captured_requests");
+  },
+
+  // 用指定供应商重放录制的请求
+  replayRequest: async (
+    id: string,
+    providerId: string,
+  ): Promise<ReplayResult> => {
+    return await invoke<ReplayResult>("replay_request", { id, providerId });
+  },
+
+  // 获取代理模式下各 Key 的用量统计
+  getKeyUsageStats: async (): Promise<KeyUsage[]> => {
+    return await invoke<KeyUsage[]>("get_key_usage_stats");
+  },
+
+  // 获取代理模式下各供应商流式响应的首字节延迟统计
+  getTtfbStats: async (): Promise<ProviderTtfb[]> => {
+    return await invoke<ProviderTtfb[]>("get_ttfb_stats");
+  },
+
+  // 获取日期范围内（含首尾，YYYY-MM-DD）的每日用量汇总
+  getDailySummary: async (
+    start: string,
+    end: string,
+  ): Promise<DailyUsage[]> => {
+    return await invoke<DailyUsage[]>("get_daily_summary", { start, end });
+  },
+
+  // 导出日期范围内的用量报表（CSV / JSON，含按供应商价格估算的费用）
+  exportUsageReport: async (
+    start: string,
+    end: string,
+    format: ReportFormat,
+    filePath: string,
+  ): Promise<UsageReportResult> => {
+    return await invoke<UsageReportResult>("export_usage_report", {
+      start,
+      end,
+      format,
+      filePath,
+    });
+  },
+
+  // 额外根证书：查看、重新加载与打开目录
+  getCaCertificates: async (): Promise<CaCertificates> => {
+    return await invoke<CaCertificates>("get_ca_certificates");
+  },
+
+  reloadCaCertificates: async (): Promise<CaCertificates> => {
+    return await invoke<CaCertificates>("reload_ca_certificates");
+  },
+
+  openCaCertificatesFolder: async (): Promise<void> => {
+    await invoke("open_ca_certificates_folder");
+  },
+
+  // 处理运行模式变更
+  handleOperationModeChange: async (operationMode: string): Promise<boolean> => {
     try {
       return await invoke<boolean>("handle_operation_mode_change", {
         operation_mode: operationMode,
         operationMode: operationMode,
-        claudeCommonConfig,
-        codexCommonConfig,
       });
     } catch (error) {
       console.error("处理运行模式变更失败:", error);
       throw error;
     }
   },
-
-  // 同步代理模式通用配置
-  syncProxyCommonConfig: async (
-    claudeCommonConfig?: string,
-    codexCommonConfig?: string,
-  ): Promise<boolean> => {
-    try {
-      return await invoke<boolean>("sync_proxy_common_config", {
-        claudeCommonConfig,
-        codexCommonConfig,
-      });
-    } catch (error) {
-      console.error("同步代理模式通用配置失败:", error);
-      throw error;
-    }
-  },
 };
 
 // 创建全局 API 对象，兼容现有代码
@@ -3,6 +3,7 @@ export type ProviderCategory =
   | "cn_official" // 国产官方
   | "aggregator" // 聚合网站
   | "third_party" // 第三方供应商
+  | "local" // 本地模型服务
   | "custom"; // 自定义
 
 export interface Provider {
@@ -14,10 +15,18 @@ export interface Provider {
   category?: ProviderCategory;
   createdAt?: number; // 添加时间戳（毫秒）
   sortIndex?: number; // 排序索引（用于自定义拖拽排序）
-  // 可选：供应商元数据（仅存于 ~/.cc-switch/config.json，不写入 live 配置）
+  // 可选：供应商元数据（仅存于 ~/.cc-switch/cc-switch.db，不写入 live 配置）
   meta?: ProviderMeta;
   // 代理模式下是否启用此供应商（仅在代理模式下有效）
   proxyEnabled?: boolean;
+  // 是否启用（未设置视为启用）；停用后保留在列表中，但不可切换、不参与代理与用量刷新
+  enabled?: boolean;
+  // 是否锁定；锁定后后端拒绝编辑与删除，需显式解锁
+  locked?: boolean;
+  // 是否置顶；置顶后在代理故障转移中始终排在最前
+  pinned?: boolean;
+  // 维护模式结束时间（毫秒时间戳）；到期前不参与代理与自动切换
+  maintenanceUntil?: number;
 }
 
 export interface AppConfig {
@@ -38,6 +47,8 @@ export interface UsageScript {
   language: "javascript"; // 脚本语言
   code: string; // 脚本代码（JSON 格式配置）
   timeout?: number; // 超时时间（秒，默认 10）
+  allowedHosts?: string[]; // 额外允许访问的主机（默认仅允许与 baseUrl 同源）
+  persistCookies?: boolean; // 是否持久化 Cookie 会话（加密存储）
 }
 
 // 单个套餐用量数据
@@ -65,10 +76,173 @@ export interface ProviderMeta {
   custom_endpoints?: Record<string, CustomEndpoint>;
   // 用量查询脚本配置
   usage_script?: UsageScript;
+  // 低余额告警配置
+  balance_alert?: BalanceAlert;
+  // 切换时写入的 live 配置目录（为空时使用全局目录）
+  live_config_dir?: string;
+  // 凭证类型：订阅账号 / ChatGPT 登录（OAuth）供应商切换时保留并回填登录令牌
+  credential_kind?: CredentialKind;
+  // 订阅账号凭证（由后端维护，前端只读）
+  oauth?: OAuthCredential;
+  // 是否来自团队托管配置源（只读）
+  managed?: boolean;
+  // 切换到该供应商时一并写入的 VS Code 扩展配置
+  apply_targets?: ApplyTarget[];
+  // 代理模式下的限流配置
+  rate_limit?: RateLimit;
+  // 按时间段调整优先级或可用性的路由规则
+  routing_rules?: RoutingRule[];
+  // 上游接口格式：与客户端不一致时由代理转换（未设置时原样转发）
+  api_format?: ApiFormat;
+  // 打开网站控制台时附加的查询参数模板，支持 {{apiKey}} / {{baseUrl}}
+  dashboard_query?: string;
+  // 每百万 token 的价格（币种自定），用于用量报表估算费用
+  token_price?: number;
+  // 可选接口的支持情况（count_tokens / models / batches），false 时代理跳过该供应商
+  capabilities?: Record<string, boolean>;
+  // 代理模式下主地址失败后依次尝试自定义端点（未设置时只用主地址）
+  endpoint_failover?: EndpointFailover;
+  // 切换与代理时自动使用后台测速中延迟最低的可用端点
+  auto_select_endpoint?: boolean;
+  // 代理模式下的主机映射（主机名 -> IP），优先于全局映射与 DoH
+  host_mapping?: Record<string, string>;
+  // 代理模式下 TCP 连接的目标主机（可带端口），请求的 Host 仍为 URL 主机
+  connect_host?: string;
+  // 代理模式下 TLS 握手使用的 SNI（证书也按该域名校验）
+  sni_host?: string;
+  // 代理模式下不校验上游证书（仅用于排查问题）
+  accept_invalid_certs?: boolean;
+  // mTLS 客户端证书：PEM 文件路径或 keychain:<服务名>（macOS）
+  client_cert?: {
+    cert: string;
+    key: string;
+  };
+  // 代理模式下改为连接的 Unix 域套接字（本地模型服务）
+  unix_socket?: string;
+  // 从模型列表接口发现的可用模型
+  discovered_models?: string[];
+}
+
+export type ApiFormat = "anthropic" | "openai_chat";
+
+// 接口格式检测（probe_endpoint）
+export type WireProtocol = "anthropic" | "openai_chat" | "openai_responses";
+
+export interface ProtocolCheck {
+  protocol: WireProtocol;
+  url: string;
+  supported: boolean;
+  status?: number | null;
+  error?: string | null;
+}
+
+export interface ProtocolSuggestion {
+  appType: "claude" | "codex";
+  wireApi?: string | null; // Codex 的 wire_api
+  apiFormat?: ApiFormat | null; // 需要代理转换协议时的上游格式
+  baseUrl: string;
+  settingsConfig: Record<string, any>;
+}
+
+export interface ProtocolDetection {
+  checks: ProtocolCheck[];
+  suggestions: ProtocolSuggestion[];
+}
+
+// 新建供应商向导（wizard_* 命令）
+export interface WizardUrlCheck {
+  url: string;
+  valid: boolean;
+  reachable: boolean;
+  local: boolean;
+  latencyMs?: number | null;
+  status?: number | null;
+  error?: string | null;
+}
+
+export interface WizardKeyCheck {
+  status: "valid" | "invalid" | "unknown";
+  httpStatus?: number | null;
+  message?: string | null;
+  models: string[]; // 校验时顺带获取到的模型列表
+}
+
+export interface WizardTemplate {
+  id: string;
+  appType: "claude" | "codex";
+  wireApi?: string | null;
+  apiFormat?: ApiFormat | null;
+  requiresProxy: boolean;
+  supported?: boolean | null; // 未检测时为空
+  recommended: boolean;
+}
+
+export interface WizardInput {
+  appType: "claude" | "codex";
+  template: string;
+  name?: string;
+  baseUrl: string;
+  apiKey: string;
+  model?: string;
+  websiteUrl?: string;
+}
+
+export interface WizardPreview {
+  provider: Provider;
+  issues: string[];
+  requiresProxy: boolean;
+}
+
+// 供应商内端点故障转移的尝试顺序
+export type EndpointFailover = "ordered" | "latency";
+
+// 时间段路由规则（本地时间；结束早于开始表示跨越午夜）
+export interface RoutingRule {
+  days: number[]; // 1 为周一，7 为周日；为空表示每天
+  start: string; // HH:MM
+  end: string; // HH:MM（不含）
+  effect: RoutingEffect;
+}
+
+// prefer 排到前面，avoid 排到后面，only 仅在时段内可用，exclude 时段内不可用
+export type RoutingEffect = "prefer" | "avoid" | "only" | "exclude";
+
+// 代理限流：最近一分钟内达到上限后跳过该供应商
+export interface RateLimit {
+  requestsPerMinute?: number;
+  tokensPerMinute?: number; // 按上游响应中的 usage 统计
+}
+
+export type ApplyTarget = "cline" | "roo_code";
+
+export type CredentialKind = "api_key" | "claude_oauth" | "codex_oauth";
+
+// 订阅账号凭证
+export interface OAuthCredential {
+  credentials: Record<string, unknown>;
+  account?: { emailAddress?: string; [key: string]: unknown };
+  updatedAt?: number;
+}
+
+// 低余额告警阈值
+export interface BalanceAlert {
+  enabled: boolean;
+  remainingBelow?: number; // 剩余额度低于该值时告警
+  percentBelow?: number; // 剩余百分比低于该值时告警
+  notify?: boolean; // 是否发送系统通知（默认开启）
+  cooldownMinutes?: number; // 冷却时间（分钟，默认 60）
 }
 
 // 运行模式类型
-export type OperationMode = "write" | "proxy";
+export type OperationMode = "write" | "proxy" | "env";
+
+// 环境变量模式生成的 shell 片段
+export interface EnvSnippet {
+  shell: "sh" | "ps1";
+  path: string;
+  content: string;
+  sourceLine: string;
+}
 
 // 应用设置类型（用于 SettingsModal 与 Tauri API）
 export interface Settings {
@@ -76,6 +250,10 @@ export interface Settings {
   showInTray: boolean;
   // 点击关闭按钮时是否最小化到托盘而不是关闭应用
   minimizeToTrayOnClose: boolean;
+  // 登录系统时自动启动
+  launchAtLogin?: boolean;
+  // 开机自启时隐藏主窗口，仅保留托盘图标
+  startMinimized?: boolean;
   // 启用 Claude 插件联动（写入 ~/.claude/config.json 的 primaryApiKey）
   enableClaudePluginIntegration?: boolean;
   // 覆盖 Claude Code 配置目录（可选）
@@ -83,7 +261,7 @@ export interface Settings {
   // 覆盖 Codex 配置目录（可选）
   codexConfigDir?: string;
   // 首选语言（可选，默认中文）
-  language?: "en" | "zh";
+  language?: "en" | "zh" | "ja"; // ja 暂无界面翻译，界面回退英文，后端消息使用日文
   // Claude 自定义端点列表
   customEndpointsClaude?: Record<string, CustomEndpoint>;
   // Codex 自定义端点列表
@@ -92,6 +270,296 @@ export interface Settings {
   operationMode?: OperationMode;
   // 代理模式下的重试次数，默认1，允许0
   proxyRetryCount?: number;
+  // 代理模式下单个请求的总时限（秒），0 表示不限制
+  proxyDeadlineSecs?: number;
+  // 请求发出后连接中断时仍重试（可能重复计费）
+  proxyAggressiveRetry?: boolean;
+  // 代理响应中附加 x-ccswitch-* 诊断响应头
+  proxyDiagnosticHeaders?: boolean;
+  // 代理模式下在转发的请求中注入标明供应商的系统提示
+  proxyProviderBanner?: boolean;
+  // 代理模式下本地估算并应答 count_tokens 请求
+  proxyLocalCountTokens?: boolean;
+  // 代理以 HTTPS 监听（本地自签名证书）
+  proxyTls?: boolean;
+  // 代理的局域网共享
+  lanShare?: LanShareSettings;
+  // 代理请求录制
+  proxyCapture?: ProxyCaptureSettings;
+  // 代理上游请求的域名解析
+  proxyDns?: ProxyDnsSettings;
+  // 代理上游连接的协议与连接复用参数
+  proxyConnection?: ProxyConnectionSettings;
+  // Codex 写入模式下在单个 config.toml 中维护全部供应商条目，切换时只改写 model_provider
+  codexSingleConfig?: boolean;
+  // 在 Claude Code 状态栏中显示当前供应商与剩余额度
+  claudeStatusline?: boolean;
+  // 切换后检测到运行中的 Claude Code / Codex 会话时发送系统通知
+  runningSessionNotice?: boolean;
+  // VS Code 用户 settings.json 路径（写入 Cline / Roo Code 配置时使用，留空为默认位置）
+  vscodeSettingsPath?: string;
+  // 全局快捷键（如 "CmdOrCtrl+Alt+]"，留空表示不注册）
+  hotkeys?: HotkeySettings;
+  // 审计日志保留天数，0 表示永久保留（默认 90）
+  auditRetentionDays?: number;
+  // 团队托管配置源（未设置时不拉取）
+  managedSource?: ManagedSource;
+  // Webhook 通知
+  webhook?: WebhookSettings;
+  // 切换供应商与运行模式前后执行的钩子
+  hooks?: SwitchHook[];
+  // 写入 live 配置时的按键合并策略
+  mergeStrategies?: MergeStrategies;
+  // 按探测结果定时重排供应商
+  autoRank?: AutoRankSettings;
+  // 写入模式下按时间段路由规则自动切换供应商
+  routingAutoSwitch?: boolean;
+  // 代理按请求模型与路径选择供应商的规则
+  requestRoutes?: RequestRoutes;
+}
+
+// 请求路由规则：模型与路径同时满足时命中，留空表示不限制
+export interface RequestRoute {
+  name: string;
+  model?: string; // 支持 * 通配，如 *haiku*
+  path?: string; // 路径前缀，如 /v1/messages
+  providers: string[]; // 命中后优先使用的供应商 ID（按顺序）
+  exclusive: boolean; // 只使用上述供应商
+}
+
+// 各应用的请求路由规则（按顺序匹配）
+export interface RequestRoutes {
+  claude: RequestRoute[];
+  codex: RequestRoute[];
+}
+
+// 局域网共享：在额外端口上对局域网开放代理
+export interface LanShareSettings {
+  enabled: boolean;
+  bindAddress: string; // 0.0.0.0 表示所有网卡
+  port: number;
+  token: string; // 必填，客户端以 Bearer 或 x-api-key 携带
+  allowedIps: string[]; // IP 或 CIDR 网段，为空表示不限制
+}
+
+// 代理请求录制：保存最近的请求/响应供排查与重放
+export interface ProxyCaptureSettings {
+  enabled: boolean;
+  maxEntries: number; // 保留的最近请求条数
+  maxBodyKb: number; // 请求体与响应体各自保存的上限（KB）
+}
+
+// 代理上游请求的域名解析：DoH 与全局主机映射
+export interface ProxyDnsSettings {
+  dohUrl?: string; // DNS-over-HTTPS 地址（JSON 接口），为空时使用系统解析
+  hosts: Record<string, string>; // 主机名 -> IP
+}
+
+// 上游连接的 HTTP 版本：auto 按 ALPN 协商，http2 为 prior knowledge
+export type UpstreamHttpVersion = "auto" | "http1" | "http2";
+
+// 代理上游连接的协议与连接复用参数
+export interface ProxyConnectionSettings {
+  httpVersion: UpstreamHttpVersion;
+  poolIdleTimeoutSecs: number; // 空闲连接保留时长，0 表示不复用
+  http2KeepAliveSecs: number; // HTTP/2 keep-alive ping 间隔，0 表示不发送
+}
+
+// 额外根证书目录（应用配置目录下的 ca-certs）与其中的证书文件
+export interface CaCertificates {
+  dir: string;
+  files: Array<{
+    file: string;
+    certificates: number; // 成功加入信任列表的证书数
+    error?: string | null;
+  }>;
+}
+
+// 一条录制的请求/响应（凭证请求头与 Key 已脱敏）
+export interface CapturedRequest {
+  id: string;
+  timestamp: number; // 毫秒
+  appType: "claude" | "codex";
+  method: string;
+  path: string;
+  requestHeaders: [string, string][];
+  requestBody: string;
+  requestTruncated: boolean;
+  status: number;
+  responseBody: string;
+  responseTruncated: boolean;
+  providerId?: string; // 全部供应商失败时为空
+  providerName?: string;
+  durationMs: number;
+}
+
+// 用指定供应商重放请求的结果
+export interface ReplayResult {
+  providerName: string;
+  status: number;
+  body: string;
+  truncated: boolean;
+  durationMs: number;
+}
+
+// 代理模式下单个 Key 的用量统计（进程内，重启后清零）
+export interface KeyUsage {
+  appType: "claude" | "codex";
+  providerId: string;
+  providerName: string;
+  key: string; // 掩码后的 Key
+  requests: number;
+  failures: number;
+  lastStatus?: number; // 网络错误或超时时为空
+  lastUsed: number; // 毫秒
+  lastFailure?: number;
+  blocked: boolean; // 鉴权失败冷却中
+  limited: boolean; // 已达到限流上限
+}
+
+// 代理模式下单个供应商流式响应的首字节延迟（最近若干次请求的滚动统计）
+export interface ProviderTtfb {
+  appType: "claude" | "codex";
+  providerId: string;
+  providerName: string;
+  samples: number;
+  p50Ms: number;
+  p95Ms: number;
+}
+
+// 某天某个供应商的用量汇总（代理模式，按本地日期持久化）
+export interface DailyUsage {
+  date: string; // YYYY-MM-DD
+  appType: "claude" | "codex";
+  providerId: string;
+  providerName: string;
+  requests: number;
+  failures: number;
+  tokens: number;
+}
+
+export type ReportFormat = "csv" | "json";
+
+// 用量报表导出结果
+export interface UsageReportResult {
+  filePath: string;
+  rows: number;
+  totalTokens: number;
+  totalCost: number; // 仅包含设置了价格的供应商
+}
+
+// 局域网中通过 mDNS 发现的共享代理
+export interface SharedProxy {
+  name: string;
+  addresses: string[];
+  port: number;
+  tls: boolean;
+  url: string;
+}
+
+// 定时自动排序
+export interface AutoRankSettings {
+  enabled: boolean;
+  intervalMinutes: number;
+}
+
+// 单个供应商的探测结果
+export interface ProviderRank {
+  id: string;
+  name: string;
+  latency?: number | null; // 成功请求的平均延迟（毫秒）
+  errorRate: number; // 0–1
+  sortIndex: number;
+}
+
+// 合并策略：override 覆盖，preserve 保留已有值，append 数组追加去重
+export type MergeStrategy = "override" | "preserve" | "append";
+
+// 各应用的合并策略，键为点分隔路径（如 permissions.allow）
+export interface MergeStrategies {
+  claude: Record<string, MergeStrategy>;
+  codex: Record<string, MergeStrategy>;
+}
+
+// 团队托管配置源
+export interface ManagedSource {
+  url: string;
+  token?: string; // 以 Bearer 方式发送
+  refreshMinutes: number;
+}
+
+export interface ManagedSyncResult {
+  added: string[];
+  updated: string[];
+  removed: string[];
+  skipped: string[];
+}
+
+export interface ManagedSyncReport {
+  claude: ManagedSyncResult;
+  codex: ManagedSyncResult;
+}
+
+export type WebhookEvent =
+  | "all_providers_failed"
+  | "failover"
+  | "balance_low"
+  | "provider_switched";
+
+export type WebhookFormat = "json" | "slack" | "feishu" | "dingtalk";
+
+// Webhook 通知设置
+export interface WebhookSettings {
+  url: string;
+  secret?: string; // json 格式用于 HMAC 签名，飞书/钉钉用于加签
+  format: WebhookFormat;
+  events?: WebhookEvent[]; // 为空表示全部事件
+}
+
+export type HookEvent =
+  | "before_switch"
+  | "after_switch"
+  | "before_mode_change"
+  | "after_mode_change";
+
+export type HookKind = "shell" | "js";
+
+// 切换钩子
+export interface SwitchHook {
+  id: string;
+  name: string;
+  enabled: boolean;
+  event: HookEvent;
+  kind: HookKind;
+  script: string;
+  apps?: string[]; // 为空表示全部应用
+  timeoutSecs?: number; // 默认 10 秒
+  abortOnFailure?: boolean; // 前置钩子失败时取消操作
+}
+
+// 一次钩子执行的结果
+export interface HookRun {
+  hookId: string;
+  name: string;
+  event: HookEvent;
+  timestamp: number;
+  success: boolean;
+  exitCode?: number | null;
+  timedOut: boolean;
+  stdout: string;
+  stderr: string;
+  error?: string | null;
+  durationMs: number;
+}
+
+// 全局快捷键配置
+export interface HotkeySettings {
+  enabled: boolean;
+  nextClaude?: string;
+  prevClaude?: string;
+  nextCodex?: string;
+  prevCodex?: string;
+  toggleProxy?: string;
 }
 
 // MCP 服务器连接参数（宽松：允许扩展字段）
@@ -136,3 +604,284 @@ export interface McpConfigResponse {
   configPath: string;
   servers: Record<string, McpServer>;
 }
+
+// 配置差异（live 同步 / 切换预览）
+export interface ConfigKeyChange {
+  path: string; // 点分隔键路径，如 env.ANTHROPIC_BASE_URL
+  kind: "added" | "removed" | "modified";
+  oldValue?: unknown;
+  newValue?: unknown;
+}
+
+export interface ConfigFileDiff {
+  path: string;
+  oldContent: string;
+  newContent: string;
+  changed: boolean;
+}
+
+export interface ConfigDiff {
+  changed: boolean;
+  changes: ConfigKeyChange[];
+  files: ConfigFileDiff[];
+}
+
+// 切换供应商预览
+export interface SwitchPreview extends ConfigDiff {
+  providerId: string;
+  providerName: string;
+  currentProviderId?: string;
+}
+
+// 通用配置片段：切换时合并到供应商配置之下（供应商自身的值优先）
+export interface CommonFragment {
+  name: string;
+  content: string; // Claude 为 JSON 对象，Codex 为 TOML
+  enabled: boolean;
+}
+
+// 片段合并预览
+export interface CommonMergePreview extends ConfigDiff {
+  providerId: string;
+  providerName: string;
+  merged: Record<string, any>;
+}
+
+// 供应商配置字段级校验错误
+export interface FieldError {
+  field: string; // 点分隔字段路径，如 env.ANTHROPIC_AUTH_TOKEN
+  message: string;
+}
+
+// 旧版布局迁移报告（首次启动向导）
+export interface MigratedProvider {
+  app: "claude" | "codex";
+  id: string;
+  name: string;
+  source: string; // 来源文件
+  merged: boolean; // 是否合并到已存在的供应商
+}
+
+// 项目级切换：写入 <项目>/.claude/settings.local.json 的绑定
+export interface ProjectBinding {
+  path: string;
+  providerId?: string;
+  managedKeys?: string[];
+  updatedAt?: number;
+}
+
+// ccswitch:// 深链接解析结果（需用户确认后执行）
+export type DeepLinkRequest =
+  | {
+      action: "switch";
+      appType: string;
+      providerId: string;
+      providerName: string;
+    }
+  | {
+      action: "add";
+      appType: string;
+      provider: Provider;
+      keyStripped: boolean;
+      // 未导入的配置键（会执行命令或不在白名单内）
+      removedKeys: string[];
+    }
+  | { action: "invalid"; url: string; message: string };
+
+// 供应商分享字符串与二维码
+export interface ProviderShare {
+  payload: string;
+  link: string; // ccswitch://add?config=<payload>
+  qrPng?: string; // PNG data URL，内容过长时为空
+  keyStripped: boolean;
+}
+
+// 分享字符串解析结果（已分配新 ID，尚未写入配置）
+export interface DecodedShare {
+  appType: string;
+  provider: Provider;
+  keyStripped: boolean;
+  removedKeys: string[];
+}
+
+// 与待添加供应商重复的已有供应商：配置完全一致，或 API 地址与密钥相同
+export interface DuplicateProvider {
+  id: string;
+  name: string;
+  kind: "identical" | "same_credentials";
+}
+
+// 导入的配置文件中互相重复的一对供应商
+export interface ImportedDuplicate {
+  appType: string;
+  id: string;
+  name: string;
+  duplicate: DuplicateProvider;
+}
+
+// 从粘贴的配置片段（环境变量 / JSON / Codex TOML）解析出的供应商草稿
+export interface ProviderSnippet {
+  appType: string;
+  provider: Provider;
+  missing: string[]; // 需要用户补填的字段，如 env.ANTHROPIC_AUTH_TOKEN
+}
+
+// 用新片段更新已有供应商时的字段变化（凭证已脱敏）
+export interface FieldChange {
+  field: string;
+  old?: string;
+  new: string;
+}
+
+// 更新合并预览：仅替换 API 地址与凭证，确认后通过 updateProvider 保存
+export interface SnippetMerge {
+  appType: string;
+  provider: Provider;
+  changes: FieldChange[];
+}
+
+// 供应商检索条件（均可选）
+export interface ProviderFilter {
+  text?: string; // 匹配名称、API 地址、官网与分类
+  categories?: string[];
+  enabled?: boolean;
+  sort?: "custom" | "name" | "createdAt";
+}
+
+// 检索返回的供应商摘要（不含 settingsConfig 与密钥）
+export interface ProviderSummary {
+  id: string;
+  name: string;
+  baseUrl?: string;
+  websiteUrl?: string;
+  category?: string;
+  sortIndex?: number;
+  createdAt?: number;
+  current: boolean;
+  enabled: boolean;
+  locked: boolean;
+  proxyEnabled: boolean;
+  credentialKind: CredentialKind;
+}
+
+// 批量操作结果：skipped 为被跳过的供应商及原因（如当前供应商不可删除）
+export interface BulkResult {
+  updated: string[];
+  skipped: { id: string; reason: string }[];
+}
+
+// 批量操作完成事件（每次批量调用只发送一次）
+export interface ProvidersUpdatedEvent {
+  appType: string;
+  action: "proxy" | "delete" | "category" | "reorder";
+  ids: string[];
+}
+
+// 代理遇到 401/403 后将供应商标记为鉴权失败
+// 运行中的 Claude Code / Codex CLI 会话
+export interface RunningSession {
+  pid: number;
+  appType: "claude" | "codex";
+  startedAt: number; // 秒级时间戳
+  cwd?: string | null;
+}
+
+// 切换后仍有会话使用旧配置（message 为已本地化的提示）
+export interface SessionsRestartNeededEvent {
+  reason: "switch" | "mode_change";
+  appType?: "claude" | "codex" | null;
+  sessions: RunningSession[];
+  message: string;
+}
+
+export interface ProxyAuthFailedEvent {
+  appType: string;
+  providerId: string;
+  providerName: string;
+  status: number;
+  cooldownMinutes: number;
+}
+
+// 本地代理意外中断/自动恢复事件（message 为已本地化的提示）
+export interface ProxyStatusEvent {
+  status: "down" | "recovered";
+  message: string;
+}
+
+export type RepairAction = "reset_current" | "reapply" | "import_live";
+
+// 启动自检发现的配置问题
+export interface SelfCheckIssue {
+  appType: string;
+  kind:
+    | "current_missing"
+    | "live_missing"
+    | "live_invalid"
+    | "live_mismatch"
+    | "proxy_mismatch";
+  message: string;
+  path?: string;
+  repairs: RepairAction[]; // 第一个为推荐的修复方式
+}
+
+export interface SelfCheckReport {
+  issues: SelfCheckIssue[];
+  checkedAt: number;
+}
+
+export interface MigrationReport {
+  migratedAt: number;
+  providers: MigratedProvider[];
+  archivedFiles: string[];
+  deduplicated: number;
+  appConfigDir?: string;
+  legacyConfigBackup?: string;
+  acknowledged: boolean;
+}
+
+// 后端命令返回的结构化错误
+export type BackendErrorCode =
+  | "CONFIG_ERROR"
+  | "IO_ERROR"
+  | "PROXY_ERROR"
+  | "SCRIPT_ERROR"
+  | "VALIDATION_ERROR";
+
+export interface BackendError {
+  code: BackendErrorCode;
+  message: string;
+  debug?: string | null; // 底层错误详情，仅用于日志
+  fields?: FieldError[]; // 仅 VALIDATION_ERROR 时存在
+}
+
+// 应用锁状态（enabled=false 时 unlocked 恒为 true）
+export interface AppLockStatus {
+  enabled: boolean;
+  unlocked: boolean;
+}
+
+// 审计日志
+export type AuditAction =
+  | "provider_added"
+  | "provider_updated"
+  | "key_changed"
+  | "provider_deleted"
+  | "provider_switched"
+  | "provider_enabled"
+  | "provider_disabled"
+  | "provider_locked"
+  | "provider_unlocked"
+  | "bulk_update"
+  | "mode_changed"
+  | "config_imported"
+  | "managed_sync"
+  | "app_lock_changed";
+
+export interface AuditEntry {
+  timestamp: number; // 毫秒
+  user: string;
+  action: AuditAction;
+  appType?: string;
+  target?: string; // 通常为供应商名称
+  detail?: string;
+}
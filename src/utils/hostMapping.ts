@@ -0,0 +1,22 @@
+/**
+ * 主机映射与文本互转：每行一条 `主机名 IP`（如 `api.example.com 203.0.113.10`）
+ */
+export const formatHostMapping = (hosts?: Record<string, string>): string =>
+  Object.entries(hosts ?? {})
+    .map(([host, ip]) => `${host} ${ip}`)
+    .join("\n");
+
+/**
+ * 解析主机映射文本，忽略空行与不完整的行
+ * @param text 每行一条 `主机名 IP`
+ */
+export const parseHostMapping = (text: string): Record<string, string> => {
+  const hosts: Record<string, string> = {};
+  for (const line of text.split("\n")) {
+    const [host, ip] = line.trim().split(/\s+/);
+    if (host && ip) {
+      hosts[host] = ip;
+    }
+  }
+  return hosts;
+};
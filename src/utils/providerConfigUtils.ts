@@ -2,6 +2,13 @@
 
 import type { TemplateValueConfig } from "../config/providerPresets";
 
+// 后端脱敏后的密钥前缀（与后端 redact::MASK_PREFIX 保持一致）
+export const MASKED_SECRET_PREFIX = "••••••••";
+
+// 是否为后端返回的脱敏值（需通过 revealProviderSecret 获取明文）
+export const isMaskedSecret = (value: string): boolean =>
+  value.startsWith(MASKED_SECRET_PREFIX);
+
 const isPlainObject = (value: unknown): value is Record<string, any> => {
   return Object.prototype.toString.call(value) === "[object Object]";
 };
@@ -366,6 +373,13 @@ export const getCodexBaseUrl = (
   }
 };
 
+// 在 Codex 的 TOML 配置文本中写入或更新 wire_api 字段（仅替换已有的字段）
+export const setCodexWireApi = (configText: string, wireApi: string): string =>
+  configText.replace(
+    /wire_api\s*=\s*(["'])([^"']+)\1/,
+    `wire_api = "${wireApi}"`,
+  );
+
 // 在 Codex 的 TOML 配置文本中写入或更新 base_url 字段
 export const setCodexBaseUrl = (
   configText: string,
@@ -8,6 +8,50 @@ import {
   McpConfigResponse,
   McpServer,
   McpServerSpec,
+  MigrationReport,
+  ProjectBinding,
+  EnvSnippet,
+  DeepLinkRequest,
+  ProviderShare,
+  DecodedShare,
+  ProviderSnippet,
+  SnippetMerge,
+  DuplicateProvider,
+  ImportedDuplicate,
+  BulkResult,
+  ProvidersUpdatedEvent,
+  ProxyAuthFailedEvent,
+  RunningSession,
+  SessionsRestartNeededEvent,
+  ProxyStatusEvent,
+  RepairAction,
+  SelfCheckReport,
+  ProviderFilter,
+  ProviderSummary,
+  AppLockStatus,
+  AuditEntry,
+  ManagedSyncReport,
+  WebhookSettings,
+  SwitchHook,
+  HookRun,
+  CommonFragment,
+  CommonMergePreview,
+  ProviderRank,
+  SharedProxy,
+  CapturedRequest,
+  ReplayResult,
+  KeyUsage,
+  ProviderTtfb,
+  DailyUsage,
+  ReportFormat,
+  UsageReportResult,
+  CaCertificates,
+  ProtocolDetection,
+  WizardUrlCheck,
+  WizardKeyCheck,
+  WizardTemplate,
+  WizardInput,
+  WizardPreview,
 } from "./types";
 import { AppType } from "./lib/tauri-api";
 import type { UnlistenFn } from "@tauri-apps/api/event";
@@ -48,10 +92,12 @@ declare global {
         success: boolean;
         message: string;
         backupId?: string;
+        duplicates?: ImportedDuplicate[];
       }>;
       selectConfigDirectory: (defaultPath?: string) => Promise<string | null>;
       openConfigFolder: (app?: AppType) => Promise<void>;
       openExternal: (url: string) => Promise<void>;
+      openProviderDashboard: (id: string, app?: AppType) => Promise<boolean>;
       updateTrayMenu: () => Promise<boolean>;
       onProviderSwitched: (
         callback: (data: { appType: string; providerId: string }) => void,
@@ -61,6 +107,42 @@ declare global {
       restartApp: () => Promise<boolean>;
       checkForUpdates: () => Promise<void>;
       isPortable: () => Promise<boolean>;
+      getPortableDataDir: () => Promise<string | null>;
+      getMigrationReport: () => Promise<MigrationReport | null>;
+      acknowledgeMigrationReport: () => Promise<boolean>;
+      exportDiagnostics: (filePath?: string) => Promise<string>;
+      getRecentLogs: (lines?: number) => Promise<string[]>;
+      getProjects: () => Promise<ProjectBinding[]>;
+      addProject: (path: string) => Promise<ProjectBinding[]>;
+      removeProject: (path: string) => Promise<ProjectBinding[]>;
+      switchProjectProvider: (
+        path: string,
+        providerId: string,
+      ) => Promise<ProjectBinding>;
+      clearProjectProvider: (path: string) => Promise<ProjectBinding>;
+      getEnvSnippet: (shell?: "sh" | "ps1") => Promise<EnvSnippet>;
+      takePendingDeepLink: () => Promise<DeepLinkRequest | null>;
+      onDeepLinkRequest: (callback: () => void) => Promise<UnlistenFn>;
+      encodeProviderShare: (
+        providerId: string,
+        app: AppType,
+        includeKey?: boolean,
+      ) => Promise<ProviderShare>;
+      decodeProviderShare: (payload: string) => Promise<DecodedShare>;
+      parseProviderSnippet: (
+        text: string,
+        app?: AppType,
+      ) => Promise<ProviderSnippet>;
+      findDuplicateProvider: (
+        provider: Provider,
+        app?: AppType,
+      ) => Promise<DuplicateProvider | null>;
+      mergeProviderSnippet: (
+        text: string,
+        app?: AppType,
+        providerId?: string,
+      ) => Promise<SnippetMerge>;
+      importClaudeOAuthAccount: () => Promise<Provider>;
       getAppConfigPath: () => Promise<string>;
       openAppConfigFolder: () => Promise<void>;
       // Claude 插件配置能力
@@ -120,6 +202,35 @@ declare global {
           error?: string;
         }>
       >;
+      probeEndpoint: (url: string, key?: string) => Promise<ProtocolDetection>;
+      discoverProviderModels: (
+        providerId: string,
+        app?: AppType,
+      ) => Promise<string[]>;
+      discoverModels: (baseUrl: string, apiKey?: string) => Promise<string[]>;
+      wizardCheckUrl: (url: string) => Promise<WizardUrlCheck>;
+      wizardCheckKey: (
+        url: string,
+        key: string,
+        app?: AppType,
+      ) => Promise<WizardKeyCheck>;
+      wizardTemplates: (
+        app?: AppType,
+        url?: string,
+        key?: string,
+      ) => Promise<WizardTemplate[]>;
+      wizardPreview: (input: WizardInput) => Promise<WizardPreview>;
+      testProviderEndpoints: (
+        providerId: string,
+        app?: AppType,
+      ) => Promise<
+        Array<{
+          url: string;
+          latency: number | null;
+          status?: number;
+          error?: string;
+        }>
+      >;
       // 自定义端点管理
       getCustomEndpoints: (
         appType: AppType,
@@ -140,28 +251,139 @@ declare global {
         providerId: string,
         url: string,
       ) => Promise<void>;
-      // Provider sort order management
-      updateProvidersSortOrder: (
-        updates: Array<{ id: string; sortIndex: number }>,
-        app?: AppType,
-      ) => Promise<boolean>;
       // 切换供应商的代理启用状态
       toggleProxyProvider: (
         providerId: string,
         enabled: boolean,
         app?: AppType,
       ) => Promise<boolean>;
-      // 处理运行模式变更
-      handleOperationModeChange: (
-        operationMode: string,
-        claudeCommonConfig?: string,
-        codexCommonConfig?: string,
+      setProviderEnabled: (
+        providerId: string,
+        enabled: boolean,
+        app?: AppType,
+      ) => Promise<boolean>;
+      setProviderLocked: (
+        providerId: string,
+        locked: boolean,
+        app?: AppType,
       ) => Promise<boolean>;
-      // 同步代理模式通用配置
-      syncProxyCommonConfig: (
-        claudeCommonConfig?: string,
-        codexCommonConfig?: string,
+      setProviderPinned: (
+        providerId: string,
+        pinned: boolean,
+        app?: AppType,
       ) => Promise<boolean>;
+      setProviderMaintenance: (
+        providerId: string,
+        minutes: number,
+        app?: AppType,
+      ) => Promise<number | null>;
+      revealProviderSecret: (
+        providerId: string,
+        app?: AppType,
+      ) => Promise<Record<string, any>>;
+      getAppLockStatus: () => Promise<AppLockStatus>;
+      unlockApp: (passcode: string) => Promise<AppLockStatus>;
+      lockApp: () => Promise<AppLockStatus>;
+      setAppLockPasscode: (
+        current: string | null,
+        passcode: string | null,
+      ) => Promise<AppLockStatus>;
+      getAuditLog: (limit?: number, offset?: number) => Promise<AuditEntry[]>;
+      refreshManagedProviders: () => Promise<ManagedSyncReport>;
+      testWebhook: (webhook: WebhookSettings) => Promise<boolean>;
+      getHookRuns: () => Promise<HookRun[]>;
+      testHook: (hook: SwitchHook) => Promise<HookRun>;
+      queryProviders: (
+        app: AppType,
+        filter?: ProviderFilter,
+      ) => Promise<ProviderSummary[]>;
+      bulkSetProxyEnabled: (
+        ids: string[],
+        enabled: boolean,
+        app?: AppType,
+      ) => Promise<BulkResult>;
+      bulkDeleteProviders: (ids: string[], app?: AppType) => Promise<BulkResult>;
+      bulkSetCategory: (
+        ids: string[],
+        category: string | null,
+        app?: AppType,
+      ) => Promise<BulkResult>;
+      reorderProviders: (
+        orderedIds: string[],
+        app?: AppType,
+      ) => Promise<string[]>;
+      onProvidersUpdated: (
+        callback: (data: ProvidersUpdatedEvent) => void,
+      ) => Promise<UnlistenFn>;
+      onProxyAuthFailed: (
+        callback: (data: ProxyAuthFailedEvent) => void,
+      ) => Promise<UnlistenFn>;
+      getRunningSessions: (app?: AppType) => Promise<RunningSession[]>;
+      onSessionsRestartNeeded: (
+        callback: (data: SessionsRestartNeededEvent) => void,
+      ) => Promise<UnlistenFn>;
+      onProxyStatusChanged: (
+        callback: (data: ProxyStatusEvent) => void,
+      ) => Promise<UnlistenFn>;
+      onConfigReloaded: (
+        callback: (data: { revision: number }) => void,
+      ) => Promise<UnlistenFn>;
+      onConfigConflict: (
+        callback: (data: { revision: number }) => void,
+      ) => Promise<UnlistenFn>;
+      resolveConfigConflict: (keepLocal: boolean) => Promise<boolean>;
+      onConfigSaveFailed: (
+        callback: (data: { message: string }) => void,
+      ) => Promise<UnlistenFn>;
+      reapplyCurrent: (app?: AppType) => Promise<boolean>;
+      getConfigSelfCheck: (refresh?: boolean) => Promise<SelfCheckReport>;
+      repairConfigIssue: (
+        action: RepairAction,
+        app?: AppType,
+      ) => Promise<SelfCheckReport>;
+      onConfigSelfCheck: (
+        callback: (report: SelfCheckReport) => void,
+      ) => Promise<UnlistenFn>;
+      getCommonFragments: (app?: AppType) => Promise<CommonFragment[]>;
+      saveCommonFragment: (
+        fragment: CommonFragment,
+        originalName?: string,
+        app?: AppType,
+      ) => Promise<CommonFragment[]>;
+      deleteCommonFragment: (
+        name: string,
+        app?: AppType,
+      ) => Promise<CommonFragment[]>;
+      previewCommonMerge: (
+        app?: AppType,
+        providerId?: string,
+        fragments?: CommonFragment[],
+      ) => Promise<CommonMergePreview>;
+      autoRankProviders: (app?: AppType) => Promise<ProviderRank[]>;
+      discoverSharedProxies: () => Promise<SharedProxy[]>;
+      connectSharedProxy: (
+        name: string,
+        url: string,
+        token: string,
+      ) => Promise<boolean>;
+      getCapturedRequests: () => Promise<CapturedRequest[]>;
+      clearCapturedRequests: () => Promise<boolean>;
+      replayRequest: (id: string, providerId: string) => Promise<ReplayResult>;
+      getKeyUsageStats: () => Promise<KeyUsage[]>;
+      getTtfbStats: () => Promise<ProviderTtfb[]>;
+      getDailySummary: (start: string, end: string) => Promise<DailyUsage[]>;
+      exportUsageReport: (
+        start: string,
+        end: string,
+        format: ReportFormat,
+        filePath: string,
+      ) => Promise<UsageReportResult>;
+      // 额外根证书
+      getCaCertificates: () => Promise<CaCertificates>;
+      reloadCaCertificates: () => Promise<CaCertificates>;
+      openCaCertificatesFolder: () => Promise<void>;
+      // 处理运行模式变更
+      handleOperationModeChange: (operationMode: string) => Promise<boolean>;
       // app_config_dir override via Store
       getAppConfigDirOverride: () => Promise<string | null>;
       setAppConfigDirOverride: (path: string | null) => Promise<boolean>;